@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use odoo_ls_server::core::evaluation::Evaluation;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+use ruff_text_size::{TextRange, TextSize};
+
+/*
+Benchmarks of the symbol functions called on each hover or completion. The symbols are built by hand, so neither Odoo
+nor python is needed to run them:
+cargo bench --bench symbols
+*/
+
+const CHAIN_LENGTH: u32 = 50;
+
+fn range(offset: u32) -> TextRange {
+    TextRange::new(TextSize::new(offset), TextSize::new(offset + 1))
+}
+
+/* A file with a class and a chain of variables: var_0 = Class, var_1 = var_0, ... Return the last variable */
+fn build_variable_chain(session: &mut SessionInfo) -> Rc<RefCell<Symbol>> {
+    let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+    let file = root.borrow_mut().add_new_file(session, &S!("bench_variables"), &S!("/bench/bench_variables.py"));
+    let class = file.borrow_mut().add_new_class(session, &S!("Class"), &range(0), &TextSize::new(1));
+    let mut previous = class;
+    for i in 0..CHAIN_LENGTH {
+        let variable = file.borrow_mut().add_new_variable(session, &format!("var_{}", i), &range(10 + i));
+        variable.borrow_mut().as_variable_mut().evaluations = vec![Evaluation::eval_from_symbol(&Rc::downgrade(&previous))];
+        previous = variable;
+    }
+    previous
+}
+
+/* A file with a hierarchy of classes, where only the deepest base declares the member. Return the last subclass */
+fn build_class_hierarchy(session: &mut SessionInfo) -> Rc<RefCell<Symbol>> {
+    let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+    let file = root.borrow_mut().add_new_file(session, &S!("bench_classes"), &S!("/bench/bench_classes.py"));
+    let base = file.borrow_mut().add_new_class(session, &S!("Class_0"), &range(0), &TextSize::new(1));
+    base.borrow_mut().add_new_variable(session, &S!("member"), &range(1));
+    let mut previous = base;
+    for i in 1..CHAIN_LENGTH {
+        let class = file.borrow_mut().add_new_class(session, &format!("Class_{}", i), &range(10 * i), &TextSize::new(10 * i + 1));
+        class.borrow_mut().as_class_sym_mut().bases.insert(previous.clone());
+        previous = class;
+    }
+    previous
+}
+
+fn bench_symbols(c: &mut Criterion) {
+    let mut sync_odoo = SyncOdoo::new();
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(sender, receiver, &mut sync_odoo, None);
+    let last_variable = build_variable_chain(&mut session);
+    let last_class = build_class_hierarchy(&mut session);
+    let member = S!("member");
+
+    c.bench_function("follow_ref", |b| b.iter(|| {
+        Symbol::follow_ref(black_box(&last_variable), &mut session, &mut None, false, false, None, &mut vec![])
+    }));
+    c.bench_function("get_member_symbol", |b| b.iter(|| {
+        last_class.borrow().get_member_symbol(&mut session, black_box(&member), None, false, false)
+    }));
+    c.bench_function("get_member_symbol_all", |b| b.iter(|| {
+        last_class.borrow().get_member_symbol(&mut session, black_box(&member), None, false, true)
+    }));
+}
+
+criterion_group!(benches, bench_symbols);
+criterion_main!(benches);
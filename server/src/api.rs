@@ -0,0 +1,124 @@
+//! Entry point to embed the Odoo analysis in other Rust tools (linters, formatters, migration scripts...) without
+//! running the language server.
+//!
+//! ```no_run
+//! use odoo_ls_server::api::Analysis;
+//! use odoo_ls_server::core::config::Config;
+//!
+//! let mut config = Config::new();
+//! config.odoo_path = String::from("/path/to/odoo");
+//! config.addons = vec![String::from("/path/to/addons")];
+//! let mut analysis = Analysis::new(config, vec![String::from("/path/to/addons/my_module")]);
+//! for (path, diagnostics) in analysis.take_diagnostics() {
+//!     println!("{}: {} diagnostics", path, diagnostics.len());
+//! }
+//! for model in analysis.model_names() {
+//!     println!("{}", model);
+//! }
+//! ```
+//!
+//! Everything that is not covered by [`Analysis`] can be reached through [`Analysis::session`], that gives the same
+//! [`SessionInfo`] the language server is using, with access to the [`SyncOdoo`] and the symbol tree.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crossbeam_channel::{Receiver, Sender};
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{Diagnostic, PublishDiagnosticsParams};
+
+use crate::core::config::{Config, RefreshMode};
+use crate::core::file_mgr::FileMgr;
+use crate::core::odoo::SyncOdoo;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer;
+
+/// An analyzed Odoo environment.
+///
+/// The whole build pipeline (arch, arch eval, odoo and validation steps) is run by [`Analysis::new`]. Messages that the
+/// language server would send to the client (logs, diagnostics) are kept in an internal channel, and diagnostics can be
+/// retrieved with [`Analysis::take_diagnostics`].
+pub struct Analysis {
+    sync_odoo: SyncOdoo,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+    diagnostics: HashMap<String, Vec<Diagnostic>>,
+}
+
+impl Analysis {
+
+    /// Load Odoo, the stdlib and the addons given in the configuration, and analyze them.
+    /// Only files in `workspace_folders` are validated and produce diagnostics.
+    /// The configuration is used as is, except the refresh mode that is forced to [`RefreshMode::Off`].
+    pub fn new(mut config: Config, workspace_folders: Vec<String>) -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let mut analysis = Self {
+            sync_odoo: SyncOdoo::new(),
+            sender,
+            receiver,
+            diagnostics: HashMap::new(),
+        };
+        for folder in workspace_folders {
+            analysis.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(PathBuf::from(folder).sanitize());
+        }
+        config.refresh_mode = RefreshMode::Off;
+        let mut session = analysis.session();
+        SyncOdoo::init(&mut session, config);
+        analysis
+    }
+
+    /// Create a session on the analyzed environment, to use the lower level API ([`SyncOdoo`], symbols, features...).
+    pub fn session(&mut self) -> SessionInfo<'_> {
+        SessionInfo::new_from_custom_channel(self.sender.clone(), self.receiver.clone(), &mut self.sync_odoo, None)
+    }
+
+    pub fn sync_odoo(&self) -> &SyncOdoo {
+        &self.sync_odoo
+    }
+
+    /// Return the diagnostics published since the last call, by file path.
+    /// A file whose diagnostics have been cleared is present with an empty list.
+    pub fn take_diagnostics(&mut self) -> HashMap<String, Vec<Diagnostic>> {
+        while let Ok(msg) = self.receiver.try_recv() {
+            let Message::Notification(n) = msg else {
+                continue;
+            };
+            if n.method.as_str() != PublishDiagnostics::METHOD {
+                continue;
+            }
+            let Ok(params) = serde_json::from_value::<PublishDiagnosticsParams>(n.params) else {
+                continue;
+            };
+            self.diagnostics.insert(FileMgr::uri2pathname(params.uri.as_str()), params.diagnostics);
+        }
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Names of all the models found in the addons path, sorted.
+    pub fn model_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.sync_odoo.models.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Classes declaring or extending a model, as seen from the given module (only the classes in the module and its
+    /// dependencies are returned). Without module, all the classes of the model are returned.
+    pub fn model_classes(&mut self, model_name: &str, from_module: Option<&str>) -> Vec<Rc<RefCell<Symbol>>> {
+        let Some(model) = self.sync_odoo.models.get(model_name).cloned() else {
+            return vec![];
+        };
+        let module = from_module.and_then(|name| self.sync_odoo.modules.get(name)).and_then(|module| module.upgrade());
+        let model = model.borrow();
+        match module {
+            Some(module) => {
+                let mut session = self.session();
+                model.get_symbols(&mut session, module).collect()
+            },
+            None => model.get_all_symbols().collect(),
+        }
+    }
+}
@@ -8,6 +8,38 @@ pub struct Cli {
     #[arg(short, long)]
     pub parse: bool,
 
+    //Do not run the server, but check the environment (python, odoo path, addons, typeshed...) with the given options, then stop.
+    #[arg(long)]
+    pub doctor: bool,
+
+    //Do not run the server, but scan the modules of the addon paths for the APIs deprecated or removed between two versions of Odoo, then stop.
+    #[arg(long)]
+    pub migrate_scan: bool,
+
+    //Do not run the server, but build the database of the given community path and addons, then time standard workloads on it (hovers, edits) and print the report, then stop.
+    #[arg(long)]
+    pub bench: bool,
+
+    //number of hovers of the benchmark (bench mode required)
+    #[arg(long, default_value_t = 1000)]
+    pub bench_hovers: usize,
+
+    //number of incremental edits of the benchmark (bench mode required)
+    #[arg(long, default_value_t = 100)]
+    pub bench_edits: usize,
+
+    //major version of Odoo the modules are migrated from (migrate-scan mode required)
+    #[arg(long = "from", required_if_eq("migrate_scan", "true"))]
+    pub migrate_from: Option<u32>,
+
+    //major version of Odoo the modules are migrated to (migrate-scan mode required)
+    #[arg(long = "to", required_if_eq("migrate_scan", "true"))]
+    pub migrate_to: Option<u32>,
+
+    //format of the migration report or of the benchmark report (migrate-scan or bench mode required)
+    #[arg(value_enum, long, default_value="json")]
+    pub report_format: ReportFormat,
+
     //addon paths you want to parse (parse mode required)
     #[arg(short, long)]
     pub addons: Option<Vec<String>>,
@@ -48,6 +80,10 @@ pub struct Cli {
     #[arg(long)]
     pub use_tcp: bool,
 
+    //Process the changes at once, without the debounce delays of the configuration. Useful for tests and batch processing
+    #[arg(long)]
+    pub no_delay: bool,
+
     #[arg(value_enum, long, default_value="trace")]
     pub log_level: LogLevel,
 
@@ -63,4 +99,10 @@ pub enum LogLevel {
     INFO,
     WARN,
     ERROR,
-}
\ No newline at end of file
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ReportFormat {
+    JSON,
+    MARKDOWN,
+}
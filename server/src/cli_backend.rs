@@ -5,12 +5,12 @@ use tracing::{error, info};
 
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer;
-use crate::args::Cli;
+use crate::args::{Cli, ReportFormat};
 use std::io::Write;
 use std::path::PathBuf;
 use std::fs::File;
 use serde_json::json;
-use crate::core::{config::{Config, DiagMissingImportsMode}, odoo::SyncOdoo};
+use crate::core::{bench::Bench, config::{Config, DiagMissingImportsMode}, doctor::Doctor, migration::MigrationScan, odoo::SyncOdoo};
 use crate::S;
 
 
@@ -27,6 +27,91 @@ impl CliBackend {
         }
     }
 
+    fn build_config(&self, community_path: String) -> Config {
+        let mut config = Config::new();
+        config.addons = self.cli.addons.clone().unwrap_or(vec![]);
+        config.odoo_path = community_path;
+        config.python_path = self.cli.python.clone().unwrap_or(S!("python3"));
+        config.refresh_mode = crate::core::config::RefreshMode::Off;
+        config.no_delay = true;
+        config.diag_missing_imports = DiagMissingImportsMode::All;
+        config.no_typeshed = self.cli.no_typeshed;
+        config.additional_stubs = self.cli.stubs.clone().unwrap_or(vec![]);
+        config.stdlib = self.cli.stdlib.clone().unwrap_or(S!(""));
+        config
+    }
+
+    /* Write the report of a command to the output file, or stdout */
+    fn write_output(&self, content: &str) {
+        match self.cli.output.as_ref() {
+            Some(output_path) => {
+                if let Err(e) = File::create(output_path).and_then(|mut file| file.write_all(content.as_bytes())) {
+                    error!("Unable to write to {}: {}", output_path, e)
+                }
+            },
+            None => println!("{}", content)
+        }
+    }
+
+    /* Check the environment described by the arguments and write the report to the output file, or stdout */
+    pub fn doctor(&self) {
+        let config = self.build_config(self.cli.community_path.clone().unwrap_or(S!("")));
+        let server = SyncOdoo::new();
+        let mut stubs_dirs = if config.no_typeshed { vec![] } else { server.stubs_dirs.clone() };
+        stubs_dirs.extend(config.additional_stubs.iter().map(|stub| PathBuf::from(stub).sanitize()));
+        let stdlib_dir = if config.stdlib.is_empty() { server.stdlib_dir.clone() } else { PathBuf::from(&config.stdlib).sanitize() };
+        let report = Doctor::run(&config, &stdlib_dir, &stubs_dirs);
+        let json_string = serde_json::to_string_pretty(&report).unwrap();
+        self.write_output(&json_string);
+        if report.has_errors() {
+            std::process::exit(1);
+        }
+    }
+
+    /* Scan the modules of the addons paths for the APIs that changed between the two versions and write the report to the output file, or stdout */
+    pub fn migrate_scan(&self) {
+        let (Some(from), Some(to)) = (self.cli.migrate_from, self.cli.migrate_to) else {
+            error!("Please provide the versions to migrate from and to with --from and --to");
+            std::process::exit(2);
+        };
+        if from >= to {
+            error!("The version to migrate to ({}) must be greater than the version to migrate from ({})", to, from);
+            std::process::exit(1);
+        }
+        let addons_paths = self.cli.addons.clone().unwrap_or(vec![]);
+        info!("Scanning addons path {:?} for a migration from {} to {}", addons_paths, from, to);
+        let report = MigrationScan::new(from, to).run(&addons_paths);
+        let content = match self.cli.report_format {
+            ReportFormat::JSON => serde_json::to_string_pretty(&report).unwrap(),
+            ReportFormat::MARKDOWN => report.to_markdown(),
+        };
+        self.write_output(&content);
+    }
+
+    /* Build the environment described by the arguments, time the workloads of the benchmark on it, and write the report to the
+    output file, or stdout. The addons paths are the workspace, as the validation is a part of the build */
+    pub fn bench(&self) {
+        let community_path = self.cli.community_path.clone().expect("Please provide a Community path");
+        let mut server = SyncOdoo::new();
+        let (s, r) = crossbeam_channel::unbounded();
+        //the logs and diagnostics are dropped as they are sent: they are not written to stdout, as writing them would be timed
+        //too, and keeping them would count in the memory of the report
+        let drained = r.clone();
+        std::thread::spawn(move || for _ in drained.iter() {});
+        let mut session = SessionInfo::new_from_custom_channel(s, r, &mut server, None);
+        let config = self.build_config(community_path);
+        for addons_path in config.addons.iter() {
+            session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(PathBuf::from(addons_path).sanitize());
+        }
+        let bench = Bench { hovers: self.cli.bench_hovers, edits: self.cli.bench_edits };
+        let report = bench.run(&mut session, config);
+        let content = match self.cli.report_format {
+            ReportFormat::JSON => serde_json::to_string_pretty(&report).unwrap(),
+            ReportFormat::MARKDOWN => report.to_markdown(),
+        };
+        self.write_output(&content);
+    }
+
     pub fn run(&self) {
         let community_path = self.cli.community_path.clone().expect("Please provide a Community path");
         let mut server = SyncOdoo::new();
@@ -44,15 +129,7 @@ impl CliBackend {
             session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(PathBuf::from(tracked_folder).sanitize());
         }
 
-        let mut config = Config::new();
-        config.addons = addons_paths;
-        config.odoo_path = community_path;
-        config.python_path = S!("python3");
-        config.refresh_mode = crate::core::config::RefreshMode::Off;
-        config.diag_missing_imports = DiagMissingImportsMode::All;
-        config.no_typeshed = self.cli.no_typeshed;
-        config.additional_stubs = self.cli.stubs.clone().unwrap_or(vec![]);
-        config.stdlib = self.cli.stdlib.clone().unwrap_or(S!(""));
+        let config = self.build_config(community_path);
         SyncOdoo::init(&mut session, config);
 
         let output_path = self.cli.output.clone().unwrap_or(S!("output.json"));
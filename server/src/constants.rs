@@ -4,6 +4,14 @@ use core::fmt;
 pub const EXTENSION_NAME: &str = "Odoo";
 pub const EXTENSION_VERSION: &str = "0.2.7";
 
+pub const SUPPRESSION_COMMENT: &str = "# odoo-ls:";
+
+//lines starting a jupyter-style cell in a python file
+pub const CELL_MARKERS: [&str; 2] = ["# %%", "#%%"];
+
+//name of the namespace holding the files analyzed outside of any module, like scratch scripts. It can't be imported from python.
+pub const STANDALONE_NAMESPACE: &str = "$standalone";
+
 pub const DEBUG_ODOO_BUILDER: bool = false;
 pub const DEBUG_MEMORY: bool = false;
 
@@ -0,0 +1,55 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::core::csv_parser::read_csv;
+use crate::core::symbols::module_symbol::ModuleSymbol;
+use crate::core::symbols::symbol::Symbol;
+use crate::core::xml_parser::{attribute, strip_comments, RECORD_RE};
+use crate::threads::SessionInfo;
+
+pub const ACCESS_MODEL: &str = "ir.model.access";
+
+static MODEL_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<field\b([^>]*\bname=["']model_id["'][^>]*)/?>"#).unwrap());
+
+/* The models given access to by the ir.model.access records of a csv file, from their model_id:id column. The models are
+named like the xml ids of their ir.model record, without module: model_sale_order for sale.order */
+pub fn parse_access_csv(content: &str) -> Vec<String> {
+    read_csv(content).iter()
+        .filter_map(|row| row.get("model_id:id").or_else(|| row.get("model_id/id")).map(model_name))
+        .filter(|model| !model.is_empty())
+        .collect()
+}
+
+/* The models given access to by the <record model="ir.model.access"> of a xml file */
+pub fn parse_access_xml(content: &str) -> Vec<String> {
+    let content = strip_comments(content);
+    RECORD_RE.captures_iter(&content)
+        .filter(|record| attribute(&record[1], "model").is_some_and(|model| model.value == ACCESS_MODEL))
+        .filter_map(|record| record.get(2).and_then(|body| MODEL_ID_RE.captures(body.as_str())))
+        .filter_map(|field| attribute(&field[1], "ref"))
+        .map(|model| model_name(&model.value))
+        .collect()
+}
+
+/* Name of the xml id of the ir.model record of a model, without module: model_sale_order for sale.order */
+pub fn model_xml_name(model: &str) -> String {
+    format!("model_{}", model.replace('.', "_"))
+}
+
+fn model_name(xml_id: &str) -> String {
+    xml_id.split_once('.').map(|(_, name)| name).unwrap_or(xml_id).to_string()
+}
+
+/* True if the module, or one of the modules it depends on, declares an access rule for the model */
+pub fn has_access_rule(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>, model: &str) -> bool {
+    let xml_name = model_xml_name(model);
+    let declaring: Vec<String> = session.sync_odoo.modules.values()
+        .filter_map(|module| module.upgrade())
+        .filter(|module| module.borrow().as_module_package().access_models.contains(&xml_name))
+        .map(|module| module.borrow().as_module_package().dir_name.clone())
+        .collect();
+    declaring.iter().any(|dir_name| ModuleSymbol::is_in_deps(session, module, dir_name, &mut None))
+}
@@ -0,0 +1,231 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use lsp_types::TextDocumentContentChangeEvent;
+use ruff_python_ast::visitor::{walk_expr, Visitor};
+use ruff_python_ast::Expr;
+use ruff_text_size::Ranged;
+use serde::Serialize;
+
+use crate::constants::EXTENSION_VERSION;
+use crate::core::config::Config;
+use crate::core::odoo::SyncOdoo;
+use crate::features::hover::HoverFeature;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+
+/* Timings of a workload of the benchmark, in microseconds as most hovers take less than a millisecond */
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkloadTimings {
+    pub name: String,
+    pub runs: usize,
+    pub total_us: u64,
+    pub mean_us: u64,
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub max_us: u64,
+    pub rss_kb: Option<u64>, //memory of the process at the end of the workload. None if it can't be read
+}
+
+impl WorkloadTimings {
+
+    pub fn from_durations(name: &str, durations: &[Duration], rss_kb: Option<u64>) -> WorkloadTimings {
+        let mut micros: Vec<u64> = durations.iter().map(|duration| duration.as_micros() as u64).collect();
+        micros.sort();
+        let total_us: u64 = micros.iter().sum();
+        let percentile = |percent: usize| if micros.is_empty() { 0 } else { micros[(micros.len() - 1) * percent / 100] };
+        WorkloadTimings {
+            name: name.to_string(),
+            runs: micros.len(),
+            total_us,
+            mean_us: if micros.is_empty() { 0 } else { total_us / micros.len() as u64 },
+            p50_us: percentile(50),
+            p95_us: percentile(95),
+            max_us: micros.last().cloned().unwrap_or(0),
+            rss_kb,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    pub server_version: String,
+    pub odoo_path: String,
+    pub modules: usize,
+    pub models: usize,
+    pub files: usize,
+    pub workloads: Vec<WorkloadTimings>,
+    pub peak_rss_kb: Option<u64>,
+}
+
+impl BenchReport {
+
+    pub fn to_markdown(&self) -> String {
+        let mut res = format!("# Benchmark of odoo_ls_server {}\n\n", self.server_version);
+        res += &format!("Odoo: {} ({} modules, {} models, {} files)\n\n", self.odoo_path, self.modules, self.models, self.files);
+        res += "| Workload | Runs | Total (ms) | Mean (ms) | p50 (ms) | p95 (ms) | Max (ms) | Memory (MB) |\n";
+        res += "|---|---|---|---|---|---|---|---|\n";
+        let ms = |us: u64| format!("{:.2}", us as f64 / 1000.0);
+        for workload in self.workloads.iter() {
+            let memory = workload.rss_kb.map(|kb| (kb / 1024).to_string()).unwrap_or(String::from("-"));
+            res += &format!("| {} | {} | {} | {} | {} | {} | {} | {} |\n", workload.name, workload.runs, ms(workload.total_us),
+                ms(workload.mean_us), ms(workload.p50_us), ms(workload.p95_us), ms(workload.max_us), memory);
+        }
+        if let Some(peak) = self.peak_rss_kb {
+            res += &format!("\nPeak memory: {} MB\n", peak / 1024);
+        }
+        res
+    }
+}
+
+/* Memory of the server process, as given by /proc/self/status */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ProcessMemory {
+    pub rss_kb: u64,
+    pub peak_rss_kb: u64,
+}
+
+impl ProcessMemory {
+
+    /* None on the systems without /proc: the report has no memory then */
+    pub fn read() -> Option<ProcessMemory> {
+        fs::read_to_string("/proc/self/status").ok().and_then(|content| ProcessMemory::parse(&content))
+    }
+
+    pub fn parse(status: &str) -> Option<ProcessMemory> {
+        let value = |key: &str| status.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|rest| rest.trim_start_matches(':').split_whitespace().next())
+            .and_then(|kb| kb.parse::<u64>().ok());
+        Some(ProcessMemory { rss_kb: value("VmRSS")?, peak_rss_kb: value("VmHWM")? })
+    }
+}
+
+/* Standardized workloads run on an odoo checkout and its addons, so the performance of two releases, or of two machines,
+can be compared: the full build of the database, hovers on the names of the python files of the addons, and edits of one of
+them, each followed by its rebuild like a save would be. The files are never written: the edits are only applied in memory */
+pub struct Bench {
+    pub hovers: usize,
+    pub edits: usize,
+}
+
+impl Bench {
+
+    pub fn run(&self, session: &mut SessionInfo, config: Config) -> BenchReport {
+        let odoo_path = config.odoo_path.clone();
+        let addons = config.addons.clone();
+        let start = Instant::now();
+        SyncOdoo::init(session, config);
+        let build = WorkloadTimings::from_durations("full build", &[start.elapsed()], ProcessMemory::read().map(|memory| memory.rss_kb));
+        let paths = Bench::addons_files(session, &addons);
+        let hovers = self.run_hovers(session, &paths);
+        let edits = self.run_edits(session, &paths);
+        BenchReport {
+            server_version: EXTENSION_VERSION.to_string(),
+            odoo_path,
+            modules: session.sync_odoo.modules.len(),
+            models: session.sync_odoo.models.len(),
+            files: session.sync_odoo.get_file_mgr().borrow().files.len(),
+            workloads: vec![build, hovers, edits],
+            peak_rss_kb: ProcessMemory::read().map(|memory| memory.peak_rss_kb),
+        }
+    }
+
+    /* The python files of the modules of the addons paths, in a stable order. The files of odoo itself if there are none */
+    fn addons_files(session: &mut SessionInfo, addons: &[String]) -> Vec<String> {
+        let mut paths: Vec<String> = session.sync_odoo.get_file_mgr().borrow().files.iter()
+            .filter(|(path, file_info)| path.ends_with(".py") && !path.ends_with("__manifest__.py") && file_info.borrow().ast.is_some())
+            .map(|(path, _)| path.clone())
+            .collect();
+        let in_addons: Vec<String> = paths.iter().filter(|path| addons.iter().any(|addons_path| Path::new(path).starts_with(addons_path))).cloned().collect();
+        if !in_addons.is_empty() {
+            paths = in_addons;
+        }
+        paths.sort();
+        paths
+    }
+
+    /* Hover on the names used in the files, going through the files until the number of hovers is reached */
+    fn run_hovers(&self, session: &mut SessionInfo, paths: &[String]) -> WorkloadTimings {
+        let mut targets = vec![];
+        for path in paths.iter() {
+            let Some(file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(path) else {
+                continue;
+            };
+            let mut collector = NameCollector { offsets: vec![] };
+            if let Some(ast) = file_info.borrow().ast.as_ref() {
+                collector.visit_body(ast);
+            }
+            targets.extend(collector.offsets.into_iter().map(|offset| (path.clone(), offset)));
+            if targets.len() >= self.hovers {
+                break;
+            }
+        }
+        targets.truncate(self.hovers);
+        let mut durations = vec![];
+        for (path, offset) in targets.iter() {
+            let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path)) else {
+                continue;
+            };
+            let Some(file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(path) else {
+                continue;
+            };
+            let position = file_info.borrow().offset_to_position(*offset as usize);
+            let start = Instant::now();
+            HoverFeature::get_hover(session, &file_symbol, &file_info, position.line, position.character);
+            durations.push(start.elapsed());
+        }
+        WorkloadTimings::from_durations("hover", &durations, ProcessMemory::read().map(|memory| memory.rss_kb))
+    }
+
+    /* Edit a file of the models of the addons, and rebuild it after each edit. The original content is restored at the end */
+    fn run_edits(&self, session: &mut SessionInfo, paths: &[String]) -> WorkloadTimings {
+        let mut durations = vec![];
+        let Some(path) = paths.iter().find(|path| path.contains("/models/")).or(paths.first()).cloned() else {
+            return WorkloadTimings::from_durations("incremental edit", &durations, None);
+        };
+        let Some(file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path) else {
+            return WorkloadTimings::from_durations("incremental edit", &durations, None);
+        };
+        let original = file_info.borrow().get_content().unwrap_or_default();
+        let mut version = file_info.borrow().version;
+        for index in 0..=self.edits {
+            //the last edit restores the original content, and is not counted
+            let text = if index < self.edits { format!("{}\n# odools bench edit {}\n", original, index) } else { original.clone() };
+            version += 1;
+            let start = Instant::now();
+            session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, &path, Some(&vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text,
+            }]), Some(version), false);
+            SyncOdoo::reload_path(session, &PathBuf::from(&path));
+            SyncOdoo::process_rebuilds(session);
+            if index < self.edits {
+                durations.push(start.elapsed());
+            }
+        }
+        file_info.borrow_mut().opened = false;
+        WorkloadTimings::from_durations("incremental edit", &durations, ProcessMemory::read().map(|memory| memory.rss_kb))
+    }
+}
+
+/* Start of the names and attributes of a file, where the hovers are requested */
+struct NameCollector {
+    offsets: Vec<u32>,
+}
+
+impl<'a> Visitor<'a> for NameCollector {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(name) => self.offsets.push(name.range().start().to_u32()),
+            Expr::Attribute(attr) => self.offsets.push(attr.attr.range().start().to_u32()),
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lsp_types::Diagnostic;
+use serde::{Deserialize, Serialize};
+
+use crate::core::cache::StableHasher;
+use crate::core::module_graph::ModuleGraph;
+
+/* Number of modules built together by the initial build. A batch is recorded in the journal once all its files are validated */
+pub const BATCH_SIZE: usize = 20;
+
+/* Validation diagnostics of a file of a completed batch, with the hash of the content they have been computed on */
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalFile {
+    pub hash: String,
+    pub diagnostics: Vec<Diagnostic>, //ranges hold offsets, like the diagnostics stored in the FileInfo
+}
+
+impl JournalFile {
+
+    pub fn new(content: &str, diagnostics: Vec<Diagnostic>) -> JournalFile {
+        JournalFile { hash: StableHasher::key(content), diagnostics }
+    }
+
+    pub fn matches(&self, content: &str) -> bool {
+        self.hash == StableHasher::key(content)
+    }
+}
+
+/* Progress of the initial build, written in the cache directory after each batch of modules. If the server is stopped before
+the end of the build (editor restart, laptop sleep), the next start finds the batches that were already completed. Without
+evaluations stored on disk their modules are loaded again, but the validation, the longest step, is skipped for their
+unchanged files: the diagnostics recorded in the journal are published instead, and the file is only validated when it is
+opened. The files validated by the batch in progress are recorded too when the server is shut down. The journal is removed
+once the build is complete */
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildJournal {
+    pub key: String,
+    pub completed: Vec<Vec<String>>, //modules of the completed batches
+    pub files: HashMap<String, JournalFile>, //files of the completed batches, by path
+}
+
+impl BuildJournal {
+
+    /* The journal is only valid for the same database: same cache fingerprint and same addons paths */
+    pub fn key(fingerprint: &str, odoo_path: &str, addons: &[String]) -> String {
+        StableHasher::key(&(fingerprint, odoo_path, addons))
+    }
+
+    pub fn new(key: String) -> BuildJournal {
+        BuildJournal { key, completed: vec![], files: HashMap::new() }
+    }
+
+    pub fn path(dir: &Path, key: &str) -> PathBuf {
+        dir.join(format!("build_{}.json", key))
+    }
+
+    /* The journal of an interrupted build of the same database, if any. An unreadable journal is ignored */
+    pub fn load(dir: &Path, key: &str) -> Option<BuildJournal> {
+        let content = fs::read_to_string(BuildJournal::path(dir, key)).ok()?;
+        serde_json::from_str::<BuildJournal>(&content).ok().filter(|journal| journal.key == key)
+    }
+
+    /* Written in a temporary file first, so a server killed while writing doesn't leave a truncated journal */
+    pub fn save(&self, dir: &Path) -> std::io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let path = BuildJournal::path(dir, &self.key);
+        let tmp_path = path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&tmp_path, serde_json::to_string(self)?)?;
+        fs::rename(&tmp_path, &path)
+    }
+
+    pub fn remove(&self, dir: &Path) {
+        let _ = fs::remove_file(BuildJournal::path(dir, &self.key));
+    }
+
+    pub fn complete_batch(&mut self, batch: &[String], files: HashMap<String, JournalFile>) {
+        self.completed.push(batch.to_vec());
+        self.files.extend(files);
+    }
+
+    /* Files validated by a batch stopped by the shutdown of the server. Its modules are built again on the next start, but
+    these files are not validated again */
+    pub fn add_files(&mut self, files: HashMap<String, JournalFile>) {
+        self.files.extend(files);
+    }
+
+    pub fn completed_modules(&self) -> HashSet<String> {
+        self.completed.iter().flatten().cloned().collect()
+    }
+
+    /* Split the modules in batches following the order of their dependencies, so the dependencies of a module are built in
+    its batch or in a previous one. The order is the same on each run, for the same modules */
+    pub fn batches(depends: &HashMap<String, Vec<String>>) -> Vec<Vec<String>> {
+        let ranks = ModuleGraph::ranks(depends);
+        let mut modules: Vec<&String> = depends.keys().collect();
+        modules.sort_by_key(|module| (ranks.get(*module).cloned().unwrap_or(u32::MAX), module.to_string()));
+        modules.chunks(BATCH_SIZE).map(|batch| batch.iter().map(|module| module.to_string()).collect()).collect()
+    }
+}
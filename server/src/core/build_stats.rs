@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use lsp_types::request::Request;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestLatency {
+    pub method: String,
+    pub count: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    pub slow: u64, //number of requests above the slow request threshold
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStatsResult {
+    pub modules: usize,
+    pub models: usize,
+    pub files: usize,
+    pub init_ms: u64, //duration of the last build of the database
+    pub requests: Vec<RequestLatency>, //slowest methods first
+}
+
+#[derive(Debug)]
+pub enum BuildStatsRequest {}
+
+impl Request for BuildStatsRequest {
+    type Params = ();
+    type Result = BuildStatsResult;
+    const METHOD: &'static str = "Odoo/buildStats";
+}
+
+/* Time spent by the server on each method of the LSP requests, since the start of the server. Kept when the database is reset */
+#[derive(Debug, Default)]
+pub struct RequestStats {
+    latencies: HashMap<String, RequestLatency>,
+    pub init_ms: u64,
+}
+
+impl RequestStats {
+
+    /* Record the duration of a request, and return true if it is above the threshold (in ms, 0 to disable) */
+    pub fn record(&mut self, method: &str, duration: Duration, slow_threshold: u64) -> bool {
+        let ms = duration.as_millis() as u64;
+        let is_slow = slow_threshold > 0 && ms >= slow_threshold;
+        let latency = self.latencies.entry(method.to_string()).or_insert_with(|| RequestLatency {
+            method: method.to_string(),
+            ..Default::default()
+        });
+        latency.count += 1;
+        latency.total_ms += ms;
+        latency.max_ms = latency.max_ms.max(ms);
+        if is_slow {
+            latency.slow += 1;
+        }
+        is_slow
+    }
+
+    pub fn latencies(&self) -> Vec<RequestLatency> {
+        let mut latencies: Vec<RequestLatency> = self.latencies.values().cloned().collect();
+        latencies.sort_by(|a, b| b.total_ms.cmp(&a.total_ms).then_with(|| a.method.cmp(&b.method)));
+        latencies
+    }
+
+    /* Short description of the target of a request, to make the report of a slow request actionable: the document and the position */
+    pub fn describe_target(params: &Value) -> Option<String> {
+        let uri = params.pointer("/textDocument/uri").and_then(|uri| uri.as_str())?;
+        let line = params.pointer("/position/line").or_else(|| params.pointer("/range/start/line")).and_then(|line| line.as_u64());
+        Some(match line {
+            Some(line) => format!("{}:{}", uri, line + 1),
+            None => uri.to_string(),
+        })
+    }
+}
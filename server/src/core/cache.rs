@@ -0,0 +1,102 @@
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use lsp_types::request::Request;
+use serde::{Deserialize, Serialize};
+
+/* Directory of the files kept between two runs of the server: $XDG_CACHE_HOME/odoo_ls or ~/.cache/odoo_ls, and
+%LOCALAPPDATA%\odoo_ls on Windows. None if the environment gives none */
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CACHE_HOME").map(PathBuf::from).filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+    base.map(|base| base.join("odoo_ls"))
+}
+
+/* FNV-1a hasher, whose result doesn't depend on the Rust release nor on the platform, unlike the DefaultHasher, so the keys it
+gives can name files kept between two runs of the server, or two builds of it. Integers are hashed in little endian, and usize
+as u64 */
+pub struct StableHasher {
+    state: u64,
+}
+
+impl StableHasher {
+
+    pub fn new() -> StableHasher {
+        StableHasher { state: 0xcbf29ce484222325 }
+    }
+
+    /* Hex key of a value, to name a file or compare with a stored key */
+    pub fn key<T: Hash + ?Sized>(value: &T) -> String {
+        let mut hasher = StableHasher::new();
+        value.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+impl Hasher for StableHasher {
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/* Everything that can change the result of an evaluation without any change in the analyzed files.
+Cached data must be keyed by this fingerprint, so switching the interpreter, upgrading Odoo or the hooks of the server never reuse stale evaluations */
+#[derive(Debug, PartialEq, Clone, Hash)]
+pub struct CacheFingerprint {
+    pub python_version: String,
+    pub odoo_version: String,
+    pub server_version: String,
+    pub hook_sets: Vec<(String, u32)>,
+}
+
+impl CacheFingerprint {
+
+    pub fn key(&self) -> String {
+        StableHasher::key(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearCacheResult {
+    pub fingerprint: String, //fingerprint of the rebuilt database
+}
+
+/* Drop every cached evaluation and rebuild the database from scratch */
+#[derive(Debug)]
+pub enum ClearCacheRequest {}
+
+impl Request for ClearCacheRequest {
+    type Params = ();
+    type Result = ClearCacheResult;
+    const METHOD: &'static str = "Odoo/clearCache";
+}
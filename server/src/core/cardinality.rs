@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+
+use ruff_python_ast::visitor::{walk_expr, Visitor};
+use ruff_python_ast::{CmpOp, ExceptHandler, Expr, ExprAttribute, Operator, Stmt, StmtFunctionDef, UnaryOp};
+use ruff_text_size::Ranged;
+
+/* Number of records of a recordset, as far as it is known from the code of a method */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cardinality {
+    Singleton, //at most one record: accessing its fields is safe
+    Multi, //any number of records: accessing a field raises "Expected singleton" if there are several
+    Unknown,
+}
+
+/* Methods giving a recordset with the same records as the one they are called on */
+const SAME_RECORDS_METHODS: [&str; 8] = ["sudo", "with_context", "with_user", "with_company", "with_env", "with_prefetch", "exists", "sorted"];
+
+/* Methods giving a subset of the records they are called on */
+const SUBSET_METHODS: [&str; 2] = ["filtered", "filtered_domain"];
+
+/* Cardinality of self in a method, given by its decorators and its name: computes and constraints are called on all the records
+to process, onchanges on the single record of the form. Unknown for the other methods, as most of them are only called on one record */
+pub fn method_self_cardinality(func_def: &StmtFunctionDef) -> Cardinality {
+    for decorator in func_def.decorator_list.iter() {
+        let expr = match &decorator.expression {
+            Expr::Call(call) => call.func.as_ref(),
+            expr => expr
+        };
+        let Some(attr) = expr.as_attribute_expr() else {
+            continue;
+        };
+        if !attr.value.as_name_expr().is_some_and(|name| name.id.as_str() == "api") {
+            continue;
+        }
+        match attr.attr.as_str() {
+            "depends" | "depends_context" | "constrains" => return Cardinality::Multi,
+            "onchange" => return Cardinality::Singleton,
+            "model" | "model_create_multi" => return Cardinality::Unknown,
+            _ => {}
+        }
+    }
+    if func_def.name.as_str().starts_with("_compute_") {
+        return Cardinality::Multi;
+    }
+    Cardinality::Unknown
+}
+
+/* Follow the statements of a method to know the cardinality of its local recordsets, and collect the attributes of
+recordsets with several records used in arithmetic or in ordering comparisons, like `self.amount * 2` in a compute.
+The attributes are not checked to be fields: it is up to the caller, that can evaluate them */
+pub struct CardinalityAnalyzer<'a> {
+    scope: HashMap<String, Cardinality>,
+    operands: Vec<&'a ExprAttribute>,
+    stop: Option<u32>, //offset at which the scope is recorded in snapshot, and the analysis stops
+    snapshot: Option<HashMap<String, Cardinality>>,
+}
+
+impl<'a> CardinalityAnalyzer<'a> {
+
+    pub fn analyze(func_def: &'a StmtFunctionDef) -> Vec<&'a ExprAttribute> {
+        let mut analyzer = CardinalityAnalyzer::new(func_def, None);
+        analyzer.visit_body(&func_def.body);
+        analyzer.operands
+    }
+
+    /* Cardinality of a local recordset at an offset of the method, after the ensure_one() and the len() guards met before it */
+    pub fn cardinality_at(func_def: &'a StmtFunctionDef, offset: u32, name: &str) -> Cardinality {
+        let mut analyzer = CardinalityAnalyzer::new(func_def, Some(offset));
+        analyzer.visit_body(&func_def.body);
+        let scope = analyzer.snapshot.unwrap_or(analyzer.scope);
+        scope.get(name).copied().unwrap_or(Cardinality::Unknown)
+    }
+
+    fn new(func_def: &'a StmtFunctionDef, stop: Option<u32>) -> Self {
+        let mut analyzer = CardinalityAnalyzer {
+            scope: HashMap::new(),
+            operands: vec![],
+            stop,
+            snapshot: None,
+        };
+        if let Some(self_parameter) = func_def.parameters.posonlyargs.iter().chain(&func_def.parameters.args).next() {
+            analyzer.scope.insert(self_parameter.parameter.name.to_string(), method_self_cardinality(func_def));
+        }
+        analyzer
+    }
+
+    /* Cardinality of the recordset given by an expression, with the cardinalities known at this point of the method */
+    pub fn cardinality(&self, expr: &Expr) -> Cardinality {
+        match expr {
+            Expr::Name(name) => self.scope.get(name.id.as_str()).copied().unwrap_or(Cardinality::Unknown),
+            Expr::Subscript(subscript) if subscript.slice.is_number_literal_expr() => Cardinality::Singleton,
+            Expr::Attribute(attr) if ["user", "company"].contains(&attr.attr.as_str()) && CardinalityAnalyzer::is_env(&attr.value) => Cardinality::Singleton,
+            Expr::Call(call) => {
+                let Some(method) = call.func.as_attribute_expr() else {
+                    return Cardinality::Unknown;
+                };
+                match method.attr.as_str() {
+                    "search" => {
+                        let limit = call.arguments.find_keyword("limit").map(|keyword| &keyword.value);
+                        let is_one = limit.and_then(|limit| limit.as_number_literal_expr()).and_then(|limit| limit.value.as_int()).is_some_and(|limit| limit.as_u32() == Some(1));
+                        if is_one { Cardinality::Singleton } else { Cardinality::Multi }
+                    },
+                    "browse" => match call.arguments.args.first() {
+                        Some(Expr::List(_) | Expr::Tuple(_) | Expr::Set(_) | Expr::ListComp(_)) => Cardinality::Multi,
+                        Some(Expr::NumberLiteral(_)) => Cardinality::Singleton,
+                        Some(ids) => match CardinalityAnalyzer::last_identifier(ids) {
+                            Some(name) if name.ends_with("ids") => Cardinality::Multi,
+                            Some(name) if name.ends_with("id") => Cardinality::Singleton,
+                            _ => Cardinality::Unknown,
+                        },
+                        None => Cardinality::Unknown,
+                    },
+                    "ref" if CardinalityAnalyzer::is_env(&method.value) => Cardinality::Singleton,
+                    "ensure_one" => Cardinality::Singleton,
+                    name if SAME_RECORDS_METHODS.contains(&name) || SUBSET_METHODS.contains(&name) => self.cardinality(&method.value),
+                    _ => Cardinality::Unknown,
+                }
+            },
+            _ => Cardinality::Unknown,
+        }
+    }
+
+    fn visit_body(&mut self, body: &'a [Stmt]) {
+        for stmt in body.iter() {
+            if self.snapshot.is_some() {
+                return;
+            }
+            //the scope is recorded before the statement containing the offset, or inside it for the blocks
+            if let Some(stop) = self.stop {
+                let is_block = matches!(stmt, Stmt::For(_) | Stmt::While(_) | Stmt::If(_) | Stmt::With(_) | Stmt::Try(_));
+                if stmt.range().start().to_u32() >= stop || (stmt.range().end().to_u32() >= stop && !is_block) {
+                    self.snapshot = Some(self.scope.clone());
+                    return;
+                }
+            }
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Assign(assign) => {
+                self.check_expr(&assign.value);
+                let cardinality = self.cardinality(&assign.value);
+                for target in assign.targets.iter() {
+                    self.bind(target, cardinality);
+                }
+            },
+            Stmt::AnnAssign(ann_assign) => {
+                if let Some(value) = ann_assign.value.as_ref() {
+                    self.check_expr(value);
+                    let cardinality = self.cardinality(value);
+                    self.bind(&ann_assign.target, cardinality);
+                }
+            },
+            Stmt::AugAssign(aug_assign) => {
+                self.check_expr(&aug_assign.value);
+                if CardinalityAnalyzer::is_arithmetic(&aug_assign.op) {
+                    self.check_operand(&aug_assign.value);
+                }
+            },
+            Stmt::Expr(expr) => {
+                self.check_expr(&expr.value);
+                //records.ensure_one() raises if there are several records, so they are a singleton after it
+                if let Some(call) = expr.value.as_call_expr() {
+                    if let Some(method) = call.func.as_attribute_expr().filter(|method| method.attr.as_str() == "ensure_one") {
+                        self.bind(&method.value, Cardinality::Singleton);
+                    }
+                }
+            },
+            Stmt::Return(ret) => {
+                if let Some(value) = ret.value.as_ref() {
+                    self.check_expr(value);
+                }
+            },
+            Stmt::Assert(assert_stmt) => {
+                self.check_expr(&assert_stmt.test);
+                //assert len(records) == 1
+                if let Some((records, true)) = CardinalityAnalyzer::len_guard(&assert_stmt.test) {
+                    self.bind(records, Cardinality::Singleton);
+                }
+            },
+            Stmt::For(for_stmt) => {
+                self.check_expr(&for_stmt.iter);
+                //iterating on a recordset gives its records one by one
+                self.bind(&for_stmt.target, Cardinality::Singleton);
+                self.visit_branches(vec![(&for_stmt.body, vec![]), (&for_stmt.orelse, vec![])], false, vec![], true);
+            },
+            Stmt::While(while_stmt) => {
+                self.check_expr(&while_stmt.test);
+                self.visit_branches(vec![(&while_stmt.body, vec![]), (&while_stmt.orelse, vec![])], false, vec![], true);
+            },
+            Stmt::If(if_stmt) => {
+                self.check_expr(&if_stmt.test);
+                for clause in if_stmt.elif_else_clauses.iter() {
+                    if let Some(test) = clause.test.as_ref() {
+                        self.check_expr(test);
+                    }
+                }
+                let has_else = if_stmt.elif_else_clauses.last().is_some_and(|clause| clause.test.is_none());
+                let clauses = std::iter::once((Some(if_stmt.test.as_ref()), &if_stmt.body))
+                    .chain(if_stmt.elif_else_clauses.iter().map(|clause| (clause.test.as_ref(), &clause.body)));
+                //`if len(records) == 1:` narrows its block, `if len(records) > 1:` the next ones, and the code after it if
+                //the block raises or returns
+                let mut excluded = vec![];
+                let mut branches = vec![];
+                for (test, body) in clauses {
+                    let mut singletons = excluded.clone();
+                    match test.and_then(CardinalityAnalyzer::len_guard) {
+                        Some((records, true)) => singletons.push(records),
+                        Some((records, false)) => excluded.push(records),
+                        None => {}
+                    }
+                    branches.push((body, singletons));
+                }
+                self.visit_branches(branches, has_else, excluded, false);
+            },
+            Stmt::With(with_stmt) => {
+                for item in with_stmt.items.iter() {
+                    self.check_expr(&item.context_expr);
+                }
+                self.visit_body(&with_stmt.body);
+            },
+            Stmt::Try(try_stmt) => {
+                self.visit_body(&try_stmt.body);
+                let handlers = try_stmt.handlers.iter().map(|handler| match handler {
+                    ExceptHandler::ExceptHandler(handler) => (&handler.body, vec![]),
+                }).collect();
+                self.visit_branches(handlers, false, vec![], false);
+                self.visit_body(&try_stmt.orelse);
+                self.visit_body(&try_stmt.finalbody);
+            },
+            //nested functions and classes have their own scope
+            _ => {}
+        }
+    }
+
+    /* Visit blocks of which only one is executed, with the recordsets known to be singletons in each block, and the ones known
+    to be singletons if they are all skipped. A recordset keeps its cardinality after them only if all the blocks agree, the
+    state before them counting as a block if they may all be skipped. The blocks ending by a raise or a return don't count, nor
+    the ones ending by a break or a continue, unless they are the bodies of a loop: the code after the loop runs after them */
+    fn visit_branches(&mut self, branches: Vec<(&'a Vec<Stmt>, Vec<&'a Expr>)>, exhaustive: bool, skipped_singletons: Vec<&'a Expr>, is_loop: bool) {
+        let before = self.scope.clone();
+        let mut results = vec![];
+        for (branch, singletons) in branches {
+            self.scope = before.clone();
+            //the offset is in the test of the block: the guard doesn't apply yet
+            if let (Some(stop), Some(first)) = (self.stop, branch.first()) {
+                if self.snapshot.is_none() && first.range().start().to_u32() >= stop {
+                    self.snapshot = Some(self.scope.clone());
+                }
+            }
+            for records in singletons {
+                self.bind(records, Cardinality::Singleton);
+            }
+            self.visit_body(branch);
+            let scope = std::mem::take(&mut self.scope);
+            if !CardinalityAnalyzer::leaves_block(branch, is_loop) {
+                results.push(scope);
+            }
+        }
+        if !exhaustive {
+            self.scope = before.clone();
+            for records in skipped_singletons {
+                self.bind(records, Cardinality::Singleton);
+            }
+            results.push(std::mem::take(&mut self.scope));
+        }
+        if results.is_empty() {
+            results.push(before);
+        }
+        let mut merged = results[0].clone();
+        for result in results[1..].iter() {
+            for (name, cardinality) in merged.iter_mut() {
+                if result.get(name) != Some(cardinality) {
+                    *cardinality = Cardinality::Unknown;
+                }
+            }
+        }
+        for result in results[1..].iter() {
+            for name in result.keys() {
+                merged.entry(name.clone()).or_insert(Cardinality::Unknown);
+            }
+        }
+        self.scope = merged;
+    }
+
+    fn bind(&mut self, target: &Expr, cardinality: Cardinality) {
+        match target {
+            Expr::Name(name) => {
+                self.scope.insert(name.id.to_string(), cardinality);
+            },
+            //unpacked values are not recordsets we can follow
+            Expr::Tuple(tuple) => tuple.elts.iter().for_each(|elt| self.bind(elt, Cardinality::Unknown)),
+            Expr::List(list) => list.elts.iter().for_each(|elt| self.bind(elt, Cardinality::Unknown)),
+            _ => {}
+        }
+    }
+
+    fn check_expr(&mut self, expr: &'a Expr) {
+        let mut visitor = OperandVisitor {
+            analyzer: self,
+        };
+        visitor.visit_expr(expr);
+    }
+
+    fn check_operand(&mut self, operand: &'a Expr) {
+        let Some(attr) = operand.as_attribute_expr() else {
+            return;
+        };
+        if attr.value.is_name_expr() && self.cardinality(&attr.value) == Cardinality::Multi {
+            self.operands.push(attr);
+        }
+    }
+
+    /* The recordset whose length is compared to 1 by the test, and true if the test passing means that it has at most one
+    record: len(records) == 1, or false if it means that it has several: len(records) > 1 */
+    fn len_guard(test: &Expr) -> Option<(&Expr, bool)> {
+        let compare = test.as_compare_expr().filter(|compare| compare.ops.len() == 1)?;
+        let call = compare.left.as_call_expr().filter(|call| call.func.as_name_expr().is_some_and(|func| func.id.as_str() == "len"))?;
+        let [records] = &call.arguments.args[..] else {
+            return None;
+        };
+        let count = compare.comparators[0].as_number_literal_expr()?.value.as_int()?.as_u32()?;
+        match (compare.ops[0], count) {
+            (CmpOp::Eq, 1) | (CmpOp::LtE, 1) | (CmpOp::Lt, 2) => Some((records, true)),
+            (CmpOp::NotEq, 1) | (CmpOp::Gt, 1) | (CmpOp::GtE, 2) => Some((records, false)),
+            _ => None,
+        }
+    }
+
+    /* True if the block never continues to the statement after it. A break or a continue ending the body of a loop goes to the
+    statement after the loop, or to the next iteration that may not happen */
+    fn leaves_block(body: &[Stmt], is_loop: bool) -> bool {
+        match body.last() {
+            Some(Stmt::Raise(_) | Stmt::Return(_)) => true,
+            Some(Stmt::Continue(_) | Stmt::Break(_)) => !is_loop,
+            _ => false,
+        }
+    }
+
+    fn is_arithmetic(operator: &Operator) -> bool {
+        matches!(operator, Operator::Add | Operator::Sub | Operator::Mult | Operator::Div | Operator::FloorDiv | Operator::Mod | Operator::Pow)
+    }
+
+    fn is_env(expr: &Expr) -> bool {
+        expr.as_attribute_expr().is_some_and(|attr| attr.attr.as_str() == "env")
+    }
+
+    fn last_identifier(expr: &Expr) -> Option<&str> {
+        match expr {
+            Expr::Name(name) => Some(name.id.as_str()),
+            Expr::Attribute(attr) => Some(attr.attr.as_str()),
+            _ => None
+        }
+    }
+}
+
+struct OperandVisitor<'a, 'b> {
+    analyzer: &'b mut CardinalityAnalyzer<'a>,
+}
+
+impl<'a, 'b> Visitor<'a> for OperandVisitor<'a, 'b> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::BinOp(bin_op) if CardinalityAnalyzer::is_arithmetic(&bin_op.op) => {
+                self.analyzer.check_operand(&bin_op.left);
+                self.analyzer.check_operand(&bin_op.right);
+            },
+            Expr::UnaryOp(unary_op) if matches!(unary_op.op, UnaryOp::USub | UnaryOp::UAdd) => {
+                self.analyzer.check_operand(&unary_op.operand);
+            },
+            Expr::Compare(compare) if compare.ops.iter().any(|op| matches!(op, CmpOp::Lt | CmpOp::LtE | CmpOp::Gt | CmpOp::GtE)) => {
+                self.analyzer.check_operand(&compare.left);
+                for comparator in compare.comparators.iter() {
+                    self.analyzer.check_operand(comparator);
+                }
+            },
+            //the variables of lambdas and comprehensions are not the ones of the method
+            Expr::Lambda(_) | Expr::ListComp(_) | Expr::SetComp(_) | Expr::DictComp(_) | Expr::Generator(_) => return,
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
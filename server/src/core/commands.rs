@@ -0,0 +1,105 @@
+use lsp_server::{Request as ServerRequest, ResponseError};
+use lsp_types::request::Request;
+use lsp_types::ExecuteCommandParams;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::build_stats::BuildStatsRequest;
+use crate::core::cache::ClearCacheRequest;
+use crate::core::config::ConfigurationSchemaRequest;
+use crate::core::diagnostic_codes::ListDiagnosticCodesRequest;
+use crate::core::doctor::DoctorRequest;
+use crate::core::focus::ToggleFocusRequest;
+use crate::core::integrity_checker::CheckIntegrityRequest;
+use crate::core::module_graph::ExportModuleGraphRequest;
+use crate::features::model_source::GetModelSourceRequest;
+use crate::features::scaffold::ScaffoldRequest;
+use crate::features::translation::ExportPotPreviewRequest;
+use crate::features::xml_ids::FindUnusedXmlIdsRequest;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadModuleParams {
+    pub module: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadModuleResult {
+    pub path: String, //directory of the reloaded module
+}
+
+/* Rebuild all the files of a module, after changes made outside of the editor, like a git checkout */
+#[derive(Debug)]
+pub enum ReloadModuleRequest {}
+
+impl Request for ReloadModuleRequest {
+    type Params = ReloadModuleParams;
+    type Result = ReloadModuleResult;
+    const METHOD: &'static str = "Odoo/reloadModule";
+}
+
+/* A command of workspace/executeCommand, running a custom request of the server. Its first argument is the params of
+the request, so the clients that don't know the custom requests can still reach them */
+pub struct ServerCommand {
+    pub name: &'static str,
+    pub method: &'static str,
+    accepts: fn(&Value) -> bool, //true if the value can be deserialized as the params of the request
+}
+
+pub static SERVER_COMMANDS: &[ServerCommand] = &[
+    ServerCommand { name: "odools.reloadModule", method: ReloadModuleRequest::METHOD, accepts: ServerCommand::accepts::<ReloadModuleRequest> },
+    ServerCommand { name: "odools.toggleFocus", method: ToggleFocusRequest::METHOD, accepts: ServerCommand::accepts::<ToggleFocusRequest> },
+    ServerCommand { name: "odools.clearCache", method: ClearCacheRequest::METHOD, accepts: ServerCommand::accepts::<ClearCacheRequest> },
+    ServerCommand { name: "odools.buildStats", method: BuildStatsRequest::METHOD, accepts: ServerCommand::accepts::<BuildStatsRequest> },
+    ServerCommand { name: "odools.doctor", method: DoctorRequest::METHOD, accepts: ServerCommand::accepts::<DoctorRequest> },
+    ServerCommand { name: "odools.configurationSchema", method: ConfigurationSchemaRequest::METHOD, accepts: ServerCommand::accepts::<ConfigurationSchemaRequest> },
+    ServerCommand { name: "odools.listDiagnosticCodes", method: ListDiagnosticCodesRequest::METHOD, accepts: ServerCommand::accepts::<ListDiagnosticCodesRequest> },
+    ServerCommand { name: "odools.checkIntegrity", method: CheckIntegrityRequest::METHOD, accepts: ServerCommand::accepts::<CheckIntegrityRequest> },
+    ServerCommand { name: "odools.exportModuleGraph", method: ExportModuleGraphRequest::METHOD, accepts: ServerCommand::accepts::<ExportModuleGraphRequest> },
+    ServerCommand { name: "odools.exportPotPreview", method: ExportPotPreviewRequest::METHOD, accepts: ServerCommand::accepts::<ExportPotPreviewRequest> },
+    ServerCommand { name: "odools.findUnusedXmlIds", method: FindUnusedXmlIdsRequest::METHOD, accepts: ServerCommand::accepts::<FindUnusedXmlIdsRequest> },
+    ServerCommand { name: "odools.getModelSource", method: GetModelSourceRequest::METHOD, accepts: ServerCommand::accepts::<GetModelSourceRequest> },
+    ServerCommand { name: "odools.scaffold", method: ScaffoldRequest::METHOD, accepts: ServerCommand::accepts::<ScaffoldRequest> },
+];
+
+impl ServerCommand {
+
+    pub fn names() -> Vec<String> {
+        SERVER_COMMANDS.iter().map(|command| command.name.to_string()).collect()
+    }
+
+    /* Turn a workspace/executeCommand request into the custom request of its command, with the same id so the client
+    gets the result of the request as the result of the command */
+    pub fn to_request(request: ServerRequest) -> Result<ServerRequest, ResponseError> {
+        let params: ExecuteCommandParams = serde_json::from_value(request.params).map_err(|e| ServerCommand::error(format!("Invalid command params: {}", e)))?;
+        let Some(command) = SERVER_COMMANDS.iter().find(|command| command.name == params.command) else {
+            return Err(ServerCommand::error(format!("Unknown command {}", params.command)));
+        };
+        //without argument, the requests without params get null, and the others an empty object
+        let candidates = match params.arguments.into_iter().next() {
+            Some(argument) => vec![argument],
+            None => vec![Value::Null, Value::Object(Map::new())],
+        };
+        let Some(params) = candidates.into_iter().find(|candidate| (command.accepts)(candidate)) else {
+            return Err(ServerCommand::error(format!("Invalid argument for command {}", command.name)));
+        };
+        Ok(ServerRequest {
+            id: request.id,
+            method: command.method.to_string(),
+            params,
+        })
+    }
+
+    fn accepts<R: Request>(value: &Value) -> bool {
+        serde_json::from_value::<R::Params>(value.clone()).is_ok()
+    }
+
+    fn error(message: String) -> ResponseError {
+        ResponseError {
+            code: -32602, //InvalidParams
+            message,
+            data: None,
+        }
+    }
+}
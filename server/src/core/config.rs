@@ -1,6 +1,10 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::str::FromStr;
 use lsp_types::request::Request;
+use lsp_types::DiagnosticSeverity;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum RefreshMode {
@@ -46,6 +50,49 @@ impl FromStr for DiagMissingImportsMode {
     }
 }
 
+/* Target of go to definition for the symbols declared in a stub, like the ones of typeshed */
+#[derive(Debug, PartialEq, Clone)]
+pub enum DefinitionTarget {
+    Stub,
+    Implementation, //the python file found in sys.path for the stub, or the stub if there is none
+}
+
+impl FromStr for DefinitionTarget {
+
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<DefinitionTarget, Self::Err> {
+        match input {
+            "stub"  => Ok(DefinitionTarget::Stub),
+            "implementation"  => Ok(DefinitionTarget::Implementation),
+            _      => Err(()),
+        }
+    }
+}
+
+/* How the modules of an addons path are processed. Third-party addons (like the OCA ones) can be indexed for the navigation and
+the completion without reporting their diagnostics */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AddonsPolicy {
+    Full,
+    SuggestOnly, //built up to the arch eval and the odoo step, but never validated
+    Ignore, //not loaded at all
+}
+
+impl FromStr for AddonsPolicy {
+
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<AddonsPolicy, Self::Err> {
+        match input {
+            "full"  => Ok(AddonsPolicy::Full),
+            "suggestOnly"  => Ok(AddonsPolicy::SuggestOnly),
+            "ignore"  => Ok(AddonsPolicy::Ignore),
+            _      => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PythonPathRequestResult {
@@ -61,19 +108,48 @@ impl Request for PythonPathRequest {
     const METHOD: &'static str = "Odoo/getPythonPath";
 }
 
+/* JSON schema of the settings accepted in the initializationOptions of initialize and in the "Odoo" section of workspace/configuration */
+#[derive(Debug)]
+pub enum ConfigurationSchemaRequest {}
+
+impl Request for ConfigurationSchemaRequest {
+    type Params = ();
+    type Result = Value;
+    const METHOD: &'static str = "Odoo/configurationSchema";
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub refresh_mode: RefreshMode,
     pub auto_save_delay: u64,
+    pub max_refresh_delay: u64, // upper bound of auto_save_delay
+    pub reload_delay: u64, // minimum delay before resetting the database after a change of configuration or of a manifest
+    pub no_delay: bool, // process the changes at once. Set by --no-delay, for the tests and the batch processing
     pub diag_missing_imports: DiagMissingImportsMode,
     pub diag_only_opened_files: bool,
     pub addons: Vec<String>,
+    pub addons_policies: HashMap<String, AddonsPolicy>, // policy of the addons paths, by sanitized canonical path. Full if not given
+    pub external_paths: HashMap<String, bool>, // paths explicitly marked external (true) or internal (false), by sanitized canonical path
     pub odoo_path: String,
     pub python_path: String,
     pub no_typeshed: bool,
     pub additional_stubs: Vec<String>,
     pub stdlib: String,
     pub ac_filter_model_names: bool, // AC: Only show model names from module dependencies 
+    pub hook_sets: Vec<String>, // additional sets of hooks to load (see HookRegistry)
+    pub on_type_formatting: bool, // off by default to not fight with the formatter of the user
+    pub definition_target: DefinitionTarget,
+    pub range_formatting: bool, // align field declarations and manifest entries on range formatting. Off by default, like on_type_formatting
+    pub spell_check: bool, // report the typos of the labels and helps of fields. Off by default
+    pub access_hints: bool, // report the writes on models without access rule nor sudo(). Off by default, as it is a heuristic
+    pub populate_checks: bool, // validate the populate factories and sizes of models. Off by default, as only the developers of Odoo use them
+    pub strict_mode: bool, // report the expressions of the workspace that can't be evaluated. Off by default
+    pub dictionary: HashSet<String>, // words of the project accepted by the spell check, in lowercase
+    pub large_file_threshold: u64, // size in KB above which files are only partially analyzed. 0 to disable
+    pub slow_request_threshold: u64, // duration in ms above which a request is reported to the client. 0 to disable
+    pub exclude: Vec<String>, // glob patterns of files whose diagnostics are not reported, relative to the folder of odools.toml
+    pub diag_severities: HashMap<String, Option<DiagnosticSeverity>>, // severity overrides by code. None to disable the code
+    pub project_config: Option<String>, // path of the odools.toml merged in this config
 }
 
 impl Config {
@@ -81,15 +157,249 @@ impl Config {
         Self {
             refresh_mode: RefreshMode::Adaptive,
             auto_save_delay: 1000,
+            max_refresh_delay: 15000,
+            reload_delay: 4000,
+            no_delay: false,
             diag_missing_imports: DiagMissingImportsMode::All,
             diag_only_opened_files: false,
             addons: Vec::new(),
+            addons_policies: HashMap::new(),
+            external_paths: HashMap::new(),
             odoo_path: "".to_string(),
             python_path: "python3".to_string(),
             no_typeshed: false,
             additional_stubs: vec![],
             stdlib: "".to_string(),
             ac_filter_model_names: false,
+            hook_sets: vec![],
+            on_type_formatting: false,
+            range_formatting: false,
+            definition_target: DefinitionTarget::Stub,
+            spell_check: false,
+            access_hints: false,
+            populate_checks: false,
+            strict_mode: false,
+            dictionary: HashSet::new(),
+            large_file_threshold: 2048,
+            slow_request_threshold: 2000,
+            exclude: vec![],
+            diag_severities: HashMap::new(),
+            project_config: None,
+        }
+    }
+
+    /* Delays of the event queue between a change and its processing */
+    pub fn event_delays(&self) -> EventDelays {
+        if self.no_delay {
+            return EventDelays { refresh: 0, reload: 0 };
+        }
+        let refresh = std::cmp::min(self.auto_save_delay, self.max_refresh_delay);
+        EventDelays {
+            refresh,
+            reload: std::cmp::max(refresh, self.reload_delay),
         }
     }
+
+    /* Policy of the addons path containing the path. The deepest addons path wins if they are nested */
+    pub fn addons_policy(&self, path: &str) -> AddonsPolicy {
+        self.addons_policies.iter()
+            .filter(|(addons_path, _)| Path::new(path).starts_with(addons_path.as_str()))
+            .max_by_key(|(addons_path, _)| addons_path.len())
+            .map(|(_, policy)| *policy)
+            .unwrap_or(AddonsPolicy::Full)
+    }
+
+    /* External flag given for the path, whatever the workspace folders and sys.path say. The deepest path wins if they are
+    nested. None if the path is not covered: the flag is then inherited from the parent symbol, as usual */
+    pub fn external_override(&self, path: &str) -> Option<bool> {
+        self.external_paths.iter()
+            .filter(|(external_path, _)| Path::new(path).starts_with(external_path.as_str()))
+            .max_by_key(|(external_path, _)| external_path.len())
+            .map(|(_, external)| *external)
+    }
+
+    /* Whether the diagnostics of the file are hidden by the exclude patterns of odools.toml. The patterns are relative to the
+    folder of odools.toml, like "addons/*/migrations/**" */
+    pub fn is_excluded(&self, path: &str) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        let root = self.project_config.as_ref().and_then(|project_config| Path::new(project_config).parent());
+        let relative = root.and_then(|root| Path::new(path).strip_prefix(root).ok()).unwrap_or(Path::new(path));
+        self.exclude.iter().any(|pattern| glob::Pattern::new(pattern).is_ok_and(|pattern| pattern.matches_path(relative)))
+    }
+
+    /* Settings given by the client: the initializationOptions of initialize, overridden by the "Odoo" section of
+    workspace/configuration for the clients supporting it. None if the client gave neither */
+    pub fn merge_client_settings(initialization_options: Option<&Value>, workspace_settings: Option<&Value>) -> Option<Value> {
+        let mut settings = None;
+        for source in [initialization_options, workspace_settings].into_iter().flatten() {
+            if let Some(values) = source.as_object() {
+                settings.get_or_insert_with(Map::new).extend(values.clone());
+            }
+        }
+        settings.map(Value::Object)
+    }
+
+    /* Schema of the client settings, for the editors configuring the server with initializationOptions */
+    pub fn schema() -> Value {
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Odoo Language Server settings",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "odooPath": {
+                    "type": "string",
+                    "description": "Path of the Odoo community sources. Used when no configuration is selected"
+                },
+                "addons": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Paths of the addons directories, in addition to the ones of odooPath"
+                },
+                "pythonPath": {
+                    "type": "string",
+                    "description": "Python interpreter of the Odoo environment. Asked to the client with Odoo/getPythonPath if not given"
+                },
+                "addonsPolicies": {
+                    "type": "object",
+                    "description": "Policy of addons paths: full, suggestOnly to index the modules without validating them (like third-party addons), or ignore to not load them",
+                    "additionalProperties": {
+                        "type": "string",
+                        "enum": ["full", "suggestOnly", "ignore"]
+                    }
+                },
+                "externalPaths": {
+                    "type": "object",
+                    "description": "Paths marked as external (true) or internal (false), whatever the workspace folders. External code is evaluated loosely, never validated, and its diagnostics are not reported, like for a vendored copy of odoo",
+                    "additionalProperties": { "type": "boolean" }
+                },
+                "configurations": {
+                    "type": "object",
+                    "description": "Named configurations, each with an odooPath and addons",
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "odooPath": { "type": "string" },
+                            "addons": { "type": "array", "items": { "type": "string" } }
+                        },
+                        "required": ["odooPath", "addons"]
+                    }
+                },
+                "selectedConfiguration": {
+                    "type": "string",
+                    "description": "Key of the configuration to use in configurations"
+                },
+                "autoRefresh": {
+                    "type": "string",
+                    "enum": ["onSave", "afterDelay", "adaptive", "off"],
+                    "default": "adaptive",
+                    "description": "When the server rebuilds the changed files"
+                },
+                "autoRefreshDelay": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 1000,
+                    "description": "Delay in ms before rebuilding the changed files"
+                },
+                "autoRefreshMaxDelay": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 15000,
+                    "description": "Upper bound of autoRefreshDelay"
+                },
+                "reloadDelay": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 4000,
+                    "description": "Minimum delay in ms before reloading the database after a change of configuration or of a manifest"
+                },
+                "autocompletion": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "filterModelNames": {
+                            "type": "boolean",
+                            "default": true,
+                            "description": "Only show model names from module dependencies"
+                        }
+                    }
+                },
+                "diagMissingImportLevel": {
+                    "type": "string",
+                    "enum": ["all", "only_odoo", "none"],
+                    "default": "all",
+                    "description": "Missing imports to report"
+                },
+                "hookSets": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "default": [],
+                    "description": "Additional sets of hooks to load"
+                },
+                "onTypeFormatting": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Fix the indentation while typing"
+                },
+                "rangeFormatting": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Align field declarations and manifest entries when formatting a selection"
+                },
+                "definitionTarget": {
+                    "type": "string",
+                    "enum": ["stub", "implementation"],
+                    "default": "stub",
+                    "description": "Target of go to definition for the symbols declared in a stub"
+                },
+                "accessHints": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Report the writes on models for which the module and its dependencies declare no access rule, without sudo()"
+                },
+                "populateChecks": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Report the unknown fields of the populate factories and the unknown populate sizes"
+                },
+                "spellCheck": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Report the typos of the labels and helps of fields"
+                },
+                "strictMode": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Report the expressions of the workspace that can't be evaluated"
+                },
+                "largeFileThreshold": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 2048,
+                    "description": "Size in KB above which files are only partially analyzed. 0 to disable"
+                },
+                "slowRequestThreshold": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 2000,
+                    "description": "Duration in ms above which a request is reported as slow. 0 to disable"
+                },
+                "serverLogLevel": {
+                    "type": "string",
+                    "enum": ["trace", "debug", "info", "warn", "error"],
+                    "default": "info",
+                    "description": "Ignored here: the log level is given on the command line"
+                }
+            }
+        })
+    }
+}
+
+/* Delays in ms used by the delayed process thread */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EventDelays {
+    pub refresh: u64, // before rebuilding the changed files
+    pub reload: u64, // before resetting the database
 }
\ No newline at end of file
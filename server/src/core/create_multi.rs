@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{CmpOp, Expr, ExprStringLiteral, Stmt, StmtFunctionDef};
+use ruff_text_size::{Ranged, TextRange};
+
+/* Methods of dict taking a key as first argument */
+const KEY_METHODS: [&str; 2] = ["get", "setdefault"];
+
+/* Methods of dict that lists don't have */
+const DICT_METHODS: [&str; 6] = ["get", "setdefault", "keys", "items", "values", "update"];
+
+/* True if the function is decorated with @api.model_create_multi, so create receives a list of values */
+pub fn is_create_multi(func_def: &StmtFunctionDef) -> bool {
+    func_def.decorator_list.iter().any(|decorator| match &decorator.expression {
+        Expr::Attribute(attr) => attr.attr.as_str() == "model_create_multi" && attr.value.as_name_expr().is_some_and(|name| name.id.as_str() == "api"),
+        Expr::Name(name) => name.id.as_str() == "model_create_multi",
+        _ => false
+    })
+}
+
+/* Name of the parameter holding the list of values: the first one after self */
+pub fn vals_list_parameter(func_def: &StmtFunctionDef) -> Option<&str> {
+    func_def.parameters.posonlyargs.iter().chain(&func_def.parameters.args).nth(1).map(|arg| arg.parameter.name.id.as_str())
+}
+
+/* How the list of values is used in the body of a create method decorated with @api.model_create_multi */
+#[derive(Debug, Default)]
+pub struct ValsUsage<'a> {
+    pub keys: Vec<&'a ExprStringLiteral>, //keys read, written or tested on the dictionaries of the list
+    pub dict_uses: Vec<TextRange>, //the list itself used as a dictionary, like vals_list['name']
+}
+
+/* Collect the usages of the list of values. The dictionaries are the variables looping on the list (for vals in vals_list, in
+loops and comprehensions) and its items (vals_list[0]) */
+pub fn analyze(func_def: &StmtFunctionDef) -> ValsUsage {
+    let Some(vals_list) = vals_list_parameter(func_def) else {
+        return ValsUsage::default();
+    };
+    let mut visitor = ValsVisitor {
+        vals_list,
+        dicts: HashSet::new(),
+        usage: ValsUsage::default(),
+    };
+    for stmt in func_def.body.iter() {
+        visitor.visit_stmt(stmt);
+    }
+    visitor.usage
+}
+
+struct ValsVisitor<'a> {
+    vals_list: &'a str,
+    dicts: HashSet<String>, //names of the variables holding one of the dictionaries
+    usage: ValsUsage<'a>,
+}
+
+impl<'a> ValsVisitor<'a> {
+
+    fn is_vals_list(&self, expr: &Expr) -> bool {
+        expr.as_name_expr().is_some_and(|name| name.id.as_str() == self.vals_list)
+    }
+
+    fn is_vals(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Name(name) => self.dicts.contains(name.id.as_str()),
+            Expr::Subscript(subscript) => self.is_vals_list(&subscript.value) && !subscript.slice.is_string_literal_expr(),
+            _ => false
+        }
+    }
+
+    fn add_loop_target(&mut self, target: &Expr, iter: &Expr) {
+        if let (Expr::Name(target), true) = (target, self.is_vals_list(iter)) {
+            self.dicts.insert(target.id.to_string());
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for ValsVisitor<'a> {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let Stmt::For(for_stmt) = stmt {
+            self.add_loop_target(&for_stmt.target, &for_stmt.iter);
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        //the comprehension element is visited before the generators, which declare the loop variables
+        let generators = match expr {
+            Expr::ListComp(comp) => comp.generators.as_slice(),
+            Expr::SetComp(comp) => comp.generators.as_slice(),
+            Expr::DictComp(comp) => comp.generators.as_slice(),
+            Expr::Generator(comp) => comp.generators.as_slice(),
+            _ => &[],
+        };
+        for generator in generators.iter() {
+            self.add_loop_target(&generator.target, &generator.iter);
+        }
+        match expr {
+            Expr::Subscript(subscript) => {
+                if let Expr::StringLiteral(key) = subscript.slice.as_ref() {
+                    if self.is_vals(&subscript.value) {
+                        self.usage.keys.push(key);
+                    } else if self.is_vals_list(&subscript.value) {
+                        self.usage.dict_uses.push(expr.range());
+                    }
+                }
+            },
+            Expr::Call(call) => {
+                if let Expr::Attribute(method) = call.func.as_ref() {
+                    if self.is_vals(&method.value) && KEY_METHODS.contains(&method.attr.as_str()) {
+                        if let Some(Expr::StringLiteral(key)) = call.arguments.args.first() {
+                            self.usage.keys.push(key);
+                        }
+                    } else if self.is_vals_list(&method.value) && DICT_METHODS.contains(&method.attr.as_str()) {
+                        self.usage.dict_uses.push(expr.range());
+                    }
+                }
+            },
+            Expr::Compare(compare) => {
+                //'name' in vals
+                if let (Expr::StringLiteral(key), [CmpOp::In | CmpOp::NotIn], [container]) = (compare.left.as_ref(), &compare.ops[..], &compare.comparators[..]) {
+                    if self.is_vals(container) {
+                        self.usage.keys.push(key);
+                    }
+                }
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
@@ -0,0 +1,34 @@
+/* The reader shared by the features reading the csv data files. Odoo loads them with the csv module of python: the values can
+be quoted, and contain commas, quotes and line breaks */
+
+/* A row of a csv data file, with the values of its columns */
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRow {
+    pub line: u32, //0-based line where the row starts, like lsp positions
+    pub values: Vec<(String, String)>, //column of the header, value
+}
+
+impl CsvRow {
+
+    pub fn get(&self, column: &str) -> Option<&str> {
+        self.values.iter().find(|(name, _)| name == column).map(|(_, value)| value.as_str())
+    }
+}
+
+/* The rows of a csv file, by the columns of its header. Rows that can't be read are skipped */
+pub fn read_csv(content: &str) -> Vec<CsvRow> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(content.as_bytes());
+    let Ok(header) = reader.headers().cloned() else {
+        return vec![];
+    };
+    let header: Vec<String> = header.iter().map(|column| column.trim().to_string()).collect();
+    reader.records().flatten().map(|record| CsvRow {
+        line: record.position().map(|position| position.line().saturating_sub(1) as u32).unwrap_or_default(),
+        values: header.iter().cloned().zip(record.iter().map(|value| value.trim().to_string())).collect(),
+    }).collect()
+}
+
+/* Columns of the records referenced by xml id, like "model_id:id" or "groups_id/id" */
+pub fn is_reference_column(column: &str) -> bool {
+    column.ends_with(":id") || column.ends_with("/id")
+}
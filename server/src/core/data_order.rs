@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::core::csv_parser::{is_reference_column, read_csv};
+use crate::core::xml_parser::{attribute, strip_comments, xml_id_references};
+
+static DECLARATION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<(?:record|template|menuitem|report|act_window)\b([^>]*?)/?>"#).unwrap());
+
+/* The records of the module created by a data file, and the ones it needs, by their name without the module. The records of
+other modules are not kept: they are created by the dependencies, before the module is installed */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DataFileIds {
+    pub declared: Vec<String>,
+    pub referenced: Vec<String>,
+}
+
+/* A data file referencing a record of the module that is only created by a data file loaded after it */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardReference {
+    pub file: usize, //index in the data files of the manifest
+    pub xml_id: String,
+    pub declared_in: usize,
+}
+
+impl DataFileIds {
+
+    pub fn parse_xml(module_name: &str, content: &str) -> DataFileIds {
+        let content = strip_comments(content);
+        let mut ids = DataFileIds::default();
+        for declaration in DECLARATION_RE.captures_iter(&content) {
+            if let Some(id) = attribute(&declaration[1], "id") {
+                ids.add(module_name, &id.value, true);
+            }
+        }
+        for reference in xml_id_references(&content) {
+            ids.add(module_name, reference, false);
+        }
+        ids
+    }
+
+    /* The xml ids are in the "id" column, and the records referenced in the columns named like "model_id:id" or "groups_id/id" */
+    pub fn parse_csv(module_name: &str, content: &str) -> DataFileIds {
+        let mut ids = DataFileIds::default();
+        for row in read_csv(content).iter() {
+            for (column, value) in row.values.iter() {
+                if column == "id" {
+                    ids.add(module_name, value, true);
+                } else if is_reference_column(column) {
+                    ids.add(module_name, value, false);
+                }
+            }
+        }
+        ids
+    }
+
+    fn add(&mut self, module_name: &str, xml_id: &str, declared: bool) {
+        let name = match xml_id.split_once('.') {
+            Some((module, name)) if module == module_name => name,
+            Some(_) => return,
+            None => xml_id,
+        };
+        if name.is_empty() {
+            return;
+        }
+        let ids = if declared { &mut self.declared } else { &mut self.referenced };
+        if !ids.iter().any(|id| id == name) {
+            ids.push(name.to_string());
+        }
+    }
+}
+
+/* Odoo loads the data files in the order of the manifest: a record must be created by the file using it, or by a file listed
+before. The records that are not created by any data file of the manifest (demo data, records created by python code) are
+not reported */
+pub fn find_forward_references(files: &[DataFileIds]) -> Vec<ForwardReference> {
+    let mut declared_in: HashMap<&str, usize> = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        for id in file.declared.iter() {
+            declared_in.entry(id.as_str()).or_insert(index);
+        }
+    }
+    let mut res = vec![];
+    for (index, file) in files.iter().enumerate() {
+        for id in file.referenced.iter() {
+            if let Some(declaration) = declared_in.get(id.as_str()).filter(|declaration| **declaration > index) {
+                res.push(ForwardReference { file: index, xml_id: id.clone(), declared_in: *declaration });
+            }
+        }
+    }
+    res
+}
+
+/* Parse a data file of the manifest, given its path. Other files than xml and csv ones declare nothing */
+pub fn parse_data_file(module_name: &str, path: &Path, content: &str) -> DataFileIds {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("xml") => DataFileIds::parse_xml(module_name, content),
+        Some("csv") => DataFileIds::parse_csv(module_name, content),
+        _ => DataFileIds::default(),
+    }
+}
@@ -0,0 +1,115 @@
+use crate::constants::{flatten_tree, Tree};
+use crate::S;
+
+/* A constraint on the usage of a decorator, checked on each decorated function */
+#[derive(Debug)]
+pub enum DecoratorRule {
+    Called, //the decorator is a factory and must be called, like @api.depends('field')
+    RequiredKeyword(&'static str), //a keyword-only argument without default value
+    ModelMethod, //the decorated function must be a method of a model
+    PrivateMethod, //the name of the decorated method must start with an underscore
+    MethodName(&'static str), //the decorated method is expected to have this name
+    FieldNames { paths: bool }, //the string arguments are fields of the model, or paths starting by a field if paths is set
+}
+
+/* Odoo semantics of a decorator, shown on hover and used to validate the decorated functions */
+#[derive(Debug)]
+pub struct DecoratorInfo {
+    pub path: &'static [&'static str],
+    pub since: u32, //first major version of Odoo providing the decorator
+    pub description: &'static str,
+    pub rules: &'static [DecoratorRule],
+}
+
+impl DecoratorInfo {
+
+    pub fn name(&self) -> String {
+        self.path.join(".")
+    }
+
+    /* Human readable description of the rules, appended to the hover */
+    pub fn usage(&self) -> Vec<String> {
+        self.rules.iter().map(|rule| match rule {
+            DecoratorRule::Called => S!("must be called with its arguments"),
+            DecoratorRule::RequiredKeyword(keyword) => format!("requires the `{}` keyword argument", keyword),
+            DecoratorRule::ModelMethod => S!("only applies to methods of models"),
+            DecoratorRule::PrivateMethod => S!("the name of the method must start with an underscore"),
+            DecoratorRule::MethodName(name) => format!("is meant to decorate the `{}` method", name),
+            DecoratorRule::FieldNames { paths: true } => S!("its arguments are fields of the model, or paths starting by a field"),
+            DecoratorRule::FieldNames { paths: false } => S!("its arguments are fields of the model, paths are not supported"),
+        }).collect()
+    }
+}
+
+pub static DECORATORS: &[DecoratorInfo] = &[
+    DecoratorInfo {
+        path: &["odoo", "api", "depends"],
+        since: 8,
+        description: "Declares the field dependencies of a compute method. The field is recomputed when one of the dependencies changes on the record, \
+            dotted paths like `partner_id.name` follow relational fields. A callable returning the dependencies can be given instead of field names.",
+        rules: &[DecoratorRule::Called, DecoratorRule::ModelMethod, DecoratorRule::FieldNames { paths: true }],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "depends_context"],
+        since: 13,
+        description: "Declares the context keys a non-stored compute method depends on. The computed values are cached per value of these keys.",
+        rules: &[DecoratorRule::Called, DecoratorRule::ModelMethod],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "constrains"],
+        since: 8,
+        description: "Declares a constraint method, called with the records whose given fields are created or modified. It raises a ValidationError \
+            when the constraint is not satisfied. Only plain field names are supported, dotted paths are ignored.",
+        rules: &[DecoratorRule::Called, DecoratorRule::ModelMethod, DecoratorRule::FieldNames { paths: false }],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "onchange"],
+        since: 8,
+        description: "Declares an onchange method, called by the form views when one of the given fields is modified. It works on a pseudo-record \
+            of the form and can return a warning or a domain. Only plain field names are supported.",
+        rules: &[DecoratorRule::Called, DecoratorRule::ModelMethod, DecoratorRule::FieldNames { paths: false }],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "model"],
+        since: 8,
+        description: "Declares a method whose self is a recordset whose content is not relevant, only its model is.",
+        rules: &[DecoratorRule::ModelMethod],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "model_create_multi"],
+        since: 12,
+        description: "Declares a method that takes a list of dictionaries and creates multiple records. The method can be called with either \
+            a single dictionary or a list of them.",
+        rules: &[DecoratorRule::ModelMethod, DecoratorRule::MethodName("create")],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "ondelete"],
+        since: 14,
+        description: "Declares a method called when records are deleted, to prevent the deletion by raising an error. Unlike an override of unlink, \
+            it is not called on the uninstallation of the module unless `at_uninstall=True`, which avoids blocking the uninstallation.",
+        rules: &[DecoratorRule::Called, DecoratorRule::RequiredKeyword("at_uninstall"), DecoratorRule::ModelMethod],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "autovacuum"],
+        since: 14,
+        description: "Declares a method called by the daily vacuum cron (ir.autovacuum), to garbage collect data like transient records or logs.",
+        rules: &[DecoratorRule::ModelMethod, DecoratorRule::PrivateMethod],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "returns"],
+        since: 8,
+        description: "Declares the model of the records returned by the method, to convert the result between the old and new API.",
+        rules: &[DecoratorRule::Called],
+    },
+    DecoratorInfo {
+        path: &["odoo", "api", "private"],
+        since: 18,
+        description: "Declares a public method as not callable through RPC.",
+        rules: &[DecoratorRule::ModelMethod],
+    },
+];
+
+pub fn find_decorator_info(tree: &Tree, version_major: u32) -> Option<&'static DecoratorInfo> {
+    let path = flatten_tree(tree);
+    DECORATORS.iter().find(|info| info.since <= version_major && info.path.iter().eq(path.iter()))
+}
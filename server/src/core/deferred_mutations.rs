@@ -0,0 +1,37 @@
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use crate::constants::BuildSteps;
+use crate::core::symbols::symbol::Symbol;
+
+/* A change of a file requested by a hook. The hooks run in the middle of evaluations, where the file being evaluated can
+be borrowed: they queue the changes of the files instead of borrowing them */
+#[derive(Debug, Clone)]
+pub enum DeferredMutation {
+    AddDependency { file: Weak<RefCell<Symbol>>, dependency: Weak<RefCell<Symbol>>, step: BuildSteps, dep_level: BuildSteps },
+    AddModelDependency { file: Weak<RefCell<Symbol>>, model: String },
+    AddNotFoundPath { file: Weak<RefCell<Symbol>>, step: BuildSteps, path: Vec<String> },
+}
+
+/* Changes queued by the hooks, applied in order by SyncOdoo::apply_deferred_mutations once no symbol is borrowed: after
+the eval hooks of a file, and between the items of the rebuild. The changes queued while answering a request, like the
+models used by a hover, are applied by the next rebuild */
+#[derive(Debug, Default)]
+pub struct MutationQueue {
+    mutations: Vec<DeferredMutation>,
+}
+
+impl MutationQueue {
+
+    pub fn push(&mut self, mutation: DeferredMutation) {
+        self.mutations.push(mutation);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mutations.is_empty()
+    }
+
+    pub fn take(&mut self) -> Vec<DeferredMutation> {
+        std::mem::take(&mut self.mutations)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::constants::{flatten_tree, Tree};
+
+/* An API of Odoo that is deprecated or removed */
+#[derive(Debug)]
+pub enum DeprecatedApi {
+    ModelMethod(&'static str), //a method of BaseModel, flagged when overridden in a model or called
+    Symbol(&'static [&'static str]), //full path to a symbol, flagged when imported or used as decorator
+}
+
+#[derive(Debug)]
+pub struct Deprecation {
+    pub api: DeprecatedApi,
+    pub deprecated_in: u32,
+    pub removed_in: Option<u32>,
+    pub hint: &'static str,
+}
+
+impl Deprecation {
+
+    pub fn name(&self) -> String {
+        match &self.api {
+            DeprecatedApi::ModelMethod(name) => name.to_string(),
+            DeprecatedApi::Symbol(path) => path.join("."),
+        }
+    }
+
+    pub fn is_removed(&self, version_major: u32) -> bool {
+        self.removed_in.is_some_and(|removed_in| version_major >= removed_in)
+    }
+
+    pub fn message(&self, version_major: u32) -> String {
+        if self.is_removed(version_major) {
+            format!("Removed in {}.0: {} does not exist anymore, {}", self.removed_in.unwrap(), self.name(), self.hint)
+        } else {
+            format!("Deprecation Warning: Since {}.0: {} is deprecated, {}", self.deprecated_in, self.name(), self.hint)
+        }
+    }
+}
+
+/* APIs that changed between versions of Odoo. Only entries deprecated in the version of the analyzed Odoo, or before, are used. */
+pub static DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        api: DeprecatedApi::Symbol(&["odoo", "api", "multi"]),
+        deprecated_in: 13,
+        removed_in: Some(13),
+        hint: "methods are always called on recordsets",
+    },
+    Deprecation {
+        api: DeprecatedApi::Symbol(&["odoo", "api", "one"]),
+        deprecated_in: 13,
+        removed_in: Some(13),
+        hint: "loop on self instead",
+    },
+    Deprecation {
+        api: DeprecatedApi::Symbol(&["odoo", "api", "model_cr"]),
+        deprecated_in: 13,
+        removed_in: Some(13),
+        hint: "override _register_hook or init instead",
+    },
+    Deprecation {
+        api: DeprecatedApi::Symbol(&["odoo", "tests", "common", "SavepointCase"]),
+        deprecated_in: 15,
+        removed_in: None,
+        hint: "use TransactionCase, that now uses a savepoint for each test",
+    },
+    Deprecation {
+        api: DeprecatedApi::Symbol(&["odoo", "tools", "misc", "ustr"]),
+        deprecated_in: 17,
+        removed_in: Some(18),
+        hint: "use str instead",
+    },
+    Deprecation {
+        api: DeprecatedApi::ModelMethod("name_get"),
+        deprecated_in: 17,
+        removed_in: Some(18),
+        hint: "override _compute_display_name and use display_name instead",
+    },
+    Deprecation {
+        api: DeprecatedApi::ModelMethod("user_has_groups"),
+        deprecated_in: 17,
+        removed_in: Some(18),
+        hint: "use self.env.user.has_group instead",
+    },
+    Deprecation {
+        api: DeprecatedApi::ModelMethod("_check_recursion"),
+        deprecated_in: 18,
+        removed_in: None,
+        hint: "use _has_cycle instead",
+    },
+];
+
+pub fn find_model_method_deprecation(name: &str, version_major: u32) -> Option<&'static Deprecation> {
+    DEPRECATIONS.iter().find(|deprecation| deprecation.deprecated_in <= version_major
+        && matches!(deprecation.api, DeprecatedApi::ModelMethod(method) if method == name))
+}
+
+pub fn find_symbol_deprecation(tree: &Tree, version_major: u32) -> Option<&'static Deprecation> {
+    find_symbol_path_deprecation(&flatten_tree(tree), version_major)
+}
+
+/* Find the deprecation of a symbol by its full path. Removed symbols can't be resolved anymore in the analyzed version,
+so they are only known by the path they are imported or used with */
+pub fn find_symbol_path_deprecation(path: &Vec<String>, version_major: u32) -> Option<&'static Deprecation> {
+    DEPRECATIONS.iter().find(|deprecation| deprecation.deprecated_in <= version_major
+        && matches!(deprecation.api, DeprecatedApi::Symbol(symbol_path) if symbol_path.iter().eq(path.iter())))
+}
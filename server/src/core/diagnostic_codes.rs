@@ -0,0 +1,91 @@
+use std::str::FromStr;
+
+use lsp_types::request::Request;
+use lsp_types::{CodeDescription, Diagnostic, NumberOrString, Uri};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/* error_code.md documents every code: it is the source of the list returned to the clients, and the target of the links of
+the diagnostics */
+const ERROR_CODES_DOC: &str = include_str!("../../error_code.md");
+pub const ERROR_CODES_URL: &str = "https://github.com/odoo/odoo-ls/blob/master/server/error_code.md";
+
+pub static DIAGNOSTIC_CODES: Lazy<Vec<DiagnosticCodeInfo>> = Lazy::new(|| parse_error_codes(ERROR_CODES_DOC));
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCodeInfo {
+    pub code: String,
+    pub severity: String, //default severity, named like in the severities of odools.toml: info, warning or error
+    pub message: String, //with placeholders like XXXX for the names
+    pub description: String,
+    pub href: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDiagnosticCodesResult {
+    pub codes: Vec<DiagnosticCodeInfo>,
+}
+
+#[derive(Debug)]
+pub enum ListDiagnosticCodesRequest {}
+
+impl Request for ListDiagnosticCodesRequest {
+    type Params = ();
+    type Result = ListDiagnosticCodesResult;
+    const METHOD: &'static str = "Odoo/listDiagnosticCodes";
+}
+
+/* The codes documented by error_code.md. A code is a "### OLSXZZZZ" title, followed by the message between quotes and the
+description. The X of the code gives its severity */
+pub fn parse_error_codes(doc: &str) -> Vec<DiagnosticCodeInfo> {
+    let mut codes = vec![];
+    for section in doc.split("\n### ").skip(1) {
+        let mut lines = section.lines();
+        let Some(code) = lines.next().map(|code| code.trim()).filter(|code| code.starts_with("OLS")) else {
+            continue;
+        };
+        let severity = match code.chars().nth(3) {
+            Some('1') => "info",
+            Some('2') => "warning",
+            _ => "error",
+        };
+        let lines: Vec<&str> = lines.map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#')).collect();
+        let (message, description) = match lines.first() {
+            Some(first) if first.starts_with('"') => {
+                //the quotes are kept on alternative messages: "Asset X not found" or "No file matches the asset X"
+                let message = first.trim_end_matches('.');
+                let message = if message.matches('"').count() == 2 { message.trim_matches('"') } else { message };
+                (message.to_string(), lines[1..].join(" "))
+            },
+            _ => (String::new(), lines.join(" ")),
+        };
+        codes.push(DiagnosticCodeInfo {
+            code: code.to_string(),
+            severity: severity.to_string(),
+            message,
+            description,
+            href: documentation_url(code),
+        });
+    }
+    codes
+}
+
+/* Link to the documentation of a code, on its title in error_code.md */
+pub fn documentation_url(code: &str) -> String {
+    format!("{}#{}", ERROR_CODES_URL, code.to_lowercase())
+}
+
+/* Set the link to the documentation of the code of a diagnostic, if the code is documented */
+pub fn add_code_description(diagnostic: &mut Diagnostic) {
+    let Some(NumberOrString::String(code)) = diagnostic.code.as_ref() else {
+        return;
+    };
+    if !DIAGNOSTIC_CODES.iter().any(|info| info.code == *code) {
+        return;
+    }
+    if let Ok(href) = Uri::from_str(&documentation_url(code)) {
+        diagnostic.code_description = Some(CodeDescription { href });
+    }
+}
@@ -0,0 +1,240 @@
+/* Structured content of a docstring, written with the sphinx/rst fields (:param x: ...) or the google style sections (Args:, Returns:...) */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Docstring {
+    pub summary: String,
+    pub params: Vec<DocParam>,
+    pub returns: Option<DocReturn>,
+    pub raises: Vec<DocRaise>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocParam {
+    pub name: String,
+    pub typ: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocReturn {
+    pub typ: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocRaise {
+    pub exception: String,
+    pub description: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Section {
+    Text,
+    Args,
+    Returns,
+    Raises,
+}
+
+/* Last parsed entry, that receives the continuation lines */
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Entry {
+    None,
+    Param(usize),
+    Returns,
+    Raise(usize),
+}
+
+impl Docstring {
+
+    pub fn parse(raw: &str) -> Docstring {
+        let mut doc = Docstring::default();
+        let mut summary: Vec<String> = vec![];
+        let mut section = Section::Text;
+        let mut entry_indent: Option<usize> = None;
+        let mut last = Entry::None;
+        for line in Docstring::clean_lines(raw).iter() {
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            if trimmed.is_empty() {
+                if section == Section::Text {
+                    summary.push(String::new());
+                    last = Entry::None;
+                }
+                continue;
+            }
+            if trimmed.starts_with(':') {
+                if let Some(entry) = doc.parse_sphinx_field(trimmed) {
+                    section = Section::Text;
+                    last = entry;
+                    continue;
+                }
+            }
+            if indent == 0 {
+                if let Some(new_section) = Docstring::parse_section_header(trimmed) {
+                    section = new_section;
+                    entry_indent = None;
+                    last = Entry::None;
+                    continue;
+                }
+            }
+            match section {
+                Section::Text => {
+                    if indent > 0 && last != Entry::None {
+                        doc.append_description(last, trimmed);
+                    } else {
+                        last = Entry::None;
+                        summary.push(line.clone());
+                    }
+                },
+                _ if indent == 0 => {
+                    //end of the section
+                    section = Section::Text;
+                    last = Entry::None;
+                    summary.push(line.clone());
+                },
+                _ => {
+                    let entry_indent = *entry_indent.get_or_insert(indent);
+                    if indent > entry_indent && last != Entry::None {
+                        doc.append_description(last, trimmed);
+                    } else {
+                        last = doc.parse_google_entry(section, trimmed);
+                    }
+                }
+            }
+        }
+        doc.summary = summary.join("\n").trim().to_string();
+        doc
+    }
+
+    /* true if the docstring contains more than a free text */
+    pub fn has_sections(&self) -> bool {
+        !self.params.is_empty() || self.returns.is_some() || !self.raises.is_empty()
+    }
+
+    pub fn get_param(&self, name: &str) -> Option<&DocParam> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /* Lines of the docstring, without the indentation of the source code, like inspect.cleandoc */
+    fn clean_lines(raw: &str) -> Vec<String> {
+        let lines: Vec<&str> = raw.lines().collect();
+        let margin = lines.iter().skip(1).filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len()).min().unwrap_or(0);
+        lines.iter().enumerate().map(|(index, line)| {
+            if index == 0 {
+                line.trim_start().to_string()
+            } else {
+                line.chars().skip(margin).collect::<String>().trim_end().to_string()
+            }
+        }).collect()
+    }
+
+    fn parse_section_header(line: &str) -> Option<Section> {
+        match line {
+            "Args:" | "Arguments:" | "Parameters:" | "Params:" | "Keyword Args:" | "Keyword Arguments:" => Some(Section::Args),
+            "Returns:" | "Return:" | "Yields:" => Some(Section::Returns),
+            "Raises:" => Some(Section::Raises),
+            _ => None
+        }
+    }
+
+    /* Parse a field like ":param int x: description". Return None if the line is not a known field */
+    fn parse_sphinx_field(&mut self, line: &str) -> Option<Entry> {
+        let (field, description) = line[1..].split_once(':')?;
+        let description = description.trim().to_string();
+        let parts: Vec<&str> = field.split_whitespace().collect();
+        match parts.as_slice() {
+            ["param" | "parameter" | "arg" | "argument" | "key" | "keyword", typ @ .., name] => {
+                let typ = if typ.is_empty() { None } else { Some(typ.join(" ")) };
+                let index = self.get_or_add_param(name);
+                self.params[index].description = description;
+                if typ.is_some() {
+                    self.params[index].typ = typ;
+                }
+                Some(Entry::Param(index))
+            },
+            ["type", name] => {
+                let index = self.get_or_add_param(name);
+                self.params[index].typ = Some(description);
+                Some(Entry::None)
+            },
+            ["return" | "returns"] => {
+                self.returns.get_or_insert_with(DocReturn::default).description = description;
+                Some(Entry::Returns)
+            },
+            ["rtype"] => {
+                self.returns.get_or_insert_with(DocReturn::default).typ = Some(description);
+                Some(Entry::None)
+            },
+            ["raise" | "raises" | "except" | "exception", exception] => {
+                self.raises.push(DocRaise { exception: exception.to_string(), description });
+                Some(Entry::Raise(self.raises.len() - 1))
+            },
+            _ => None
+        }
+    }
+
+    /* Parse an entry of a google style section, like "x (int): description" */
+    fn parse_google_entry(&mut self, section: Section, line: &str) -> Entry {
+        let (head, description) = match line.split_once(':') {
+            Some((head, description)) => (head.trim(), description.trim().to_string()),
+            None => ("", line.to_string()),
+        };
+        match section {
+            Section::Args => {
+                let (name, typ) = match head.split_once('(') {
+                    Some((name, typ)) => (name.trim(), Some(typ.trim_end_matches(')').trim().to_string())),
+                    None => (head, None),
+                };
+                if name.is_empty() {
+                    return Entry::None;
+                }
+                let index = self.get_or_add_param(name.trim_start_matches('*'));
+                self.params[index].description = description;
+                if typ.is_some() {
+                    self.params[index].typ = typ;
+                }
+                Entry::Param(index)
+            },
+            Section::Returns => {
+                let returns = self.returns.get_or_insert_with(DocReturn::default);
+                if head.is_empty() || head.contains(' ') {
+                    returns.description = line.to_string();
+                } else {
+                    returns.typ = Some(head.to_string());
+                    returns.description = description;
+                }
+                Entry::Returns
+            },
+            Section::Raises => {
+                if head.is_empty() {
+                    self.raises.push(DocRaise { exception: line.to_string(), description: String::new() });
+                } else {
+                    self.raises.push(DocRaise { exception: head.to_string(), description });
+                }
+                Entry::Raise(self.raises.len() - 1)
+            },
+            Section::Text => Entry::None
+        }
+    }
+
+    fn get_or_add_param(&mut self, name: &str) -> usize {
+        if let Some(index) = self.params.iter().position(|param| param.name == name) {
+            return index;
+        }
+        self.params.push(DocParam { name: name.to_string(), ..Default::default() });
+        self.params.len() - 1
+    }
+
+    fn append_description(&mut self, entry: Entry, line: &str) {
+        let description = match entry {
+            Entry::Param(index) => &mut self.params[index].description,
+            Entry::Returns => &mut self.returns.get_or_insert_with(DocReturn::default).description,
+            Entry::Raise(index) => &mut self.raises[index].description,
+            Entry::None => return,
+        };
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(line);
+    }
+}
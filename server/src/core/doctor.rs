@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use lsp_types::request::Request;
+use serde::{Deserialize, Serialize};
+
+use super::cache::cache_dir;
+use super::config::Config;
+
+/* Below this number of inotify watches, the client may be unable to watch all the files of Odoo and its addons */
+const MIN_INOTIFY_WATCHES: u64 = 65536;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DoctorStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: DoctorStatus,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+
+    pub fn has_errors(&self) -> bool {
+        self.checks.iter().any(|check| check.status == DoctorStatus::Error)
+    }
+
+    fn push(&mut self, name: &str, status: DoctorStatus, message: String) {
+        self.checks.push(DoctorCheck {
+            name: name.to_string(),
+            status,
+            message,
+        });
+    }
+}
+
+#[derive(Debug)]
+pub enum DoctorRequest {}
+
+impl Request for DoctorRequest {
+    type Params = ();
+    type Result = DoctorReport;
+    const METHOD: &'static str = "Odoo/doctor";
+}
+
+/* Check the environment the server runs in, to help users to fix their setup before looking at wrong diagnostics */
+pub struct Doctor {}
+
+impl Doctor {
+
+    pub fn run(config: &Config, stdlib_dir: &String, stubs_dirs: &Vec<String>) -> DoctorReport {
+        let mut report = DoctorReport { checks: vec![] };
+        Doctor::check_interpreter(&mut report, config);
+        Doctor::check_odoo_path(&mut report, config);
+        Doctor::check_addons(&mut report, config);
+        Doctor::check_typeshed(&mut report, stdlib_dir, stubs_dirs);
+        Doctor::check_file_watcher(&mut report);
+        Doctor::check_cache_writable(&mut report);
+        report
+    }
+
+    fn check_interpreter(report: &mut DoctorReport, config: &Config) {
+        let output = Command::new(config.python_path.clone()).args(&["-c", "import sys; print('%d.%d.%d' % sys.version_info[:3])"]).output();
+        match output {
+            Ok(output) if output.status.success() => {
+                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                report.push("interpreter", DoctorStatus::Ok, format!("{} (python {})", config.python_path, version));
+            },
+            Ok(output) => {
+                report.push("interpreter", DoctorStatus::Error, format!("{} failed: {}", config.python_path, String::from_utf8_lossy(&output.stderr).trim()));
+            },
+            Err(e) => {
+                report.push("interpreter", DoctorStatus::Error, format!("Unable to run {}: {}", config.python_path, e));
+            }
+        }
+    }
+
+    fn check_odoo_path(report: &mut DoctorReport, config: &Config) {
+        if config.odoo_path.is_empty() {
+            report.push("odoo path", DoctorStatus::Error, "No Odoo path is configured".to_string());
+        } else if !PathBuf::from(&config.odoo_path).join("odoo").join("release.py").exists() {
+            report.push("odoo path", DoctorStatus::Error, format!("{} is not an Odoo directory: odoo/release.py not found", config.odoo_path));
+        } else {
+            report.push("odoo path", DoctorStatus::Ok, config.odoo_path.clone());
+        }
+    }
+
+    fn check_addons(report: &mut DoctorReport, config: &Config) {
+        for addon_path in config.addons.iter() {
+            let Ok(entries) = fs::read_dir(addon_path) else {
+                report.push("addons", DoctorStatus::Error, format!("{} is not a readable directory", addon_path));
+                continue;
+            };
+            let modules = entries.filter_map(|entry| entry.ok()).filter(|entry| entry.path().join("__manifest__.py").exists()).count();
+            if modules == 0 {
+                report.push("addons", DoctorStatus::Warning, format!("{} doesn't contain any module", addon_path));
+            } else {
+                report.push("addons", DoctorStatus::Ok, format!("{}: {} modules", addon_path, modules));
+            }
+        }
+    }
+
+    fn check_typeshed(report: &mut DoctorReport, stdlib_dir: &String, stubs_dirs: &Vec<String>) {
+        if PathBuf::from(stdlib_dir).join("builtins.pyi").exists() {
+            report.push("typeshed", DoctorStatus::Ok, stdlib_dir.clone());
+        } else {
+            report.push("typeshed", DoctorStatus::Error, format!("builtins.pyi not found in {}", stdlib_dir));
+        }
+        for stub_dir in stubs_dirs.iter() {
+            if !PathBuf::from(stub_dir).is_dir() {
+                report.push("typeshed", DoctorStatus::Warning, format!("Stubs directory {} not found", stub_dir));
+            }
+        }
+    }
+
+    fn check_file_watcher(report: &mut DoctorReport) {
+        if !cfg!(target_os = "linux") {
+            report.push("file watcher", DoctorStatus::Ok, "No limit to check on this platform".to_string());
+            return;
+        }
+        let max_watches = fs::read_to_string("/proc/sys/fs/inotify/max_user_watches").ok().and_then(|content| content.trim().parse::<u64>().ok());
+        match max_watches {
+            Some(max_watches) if max_watches < MIN_INOTIFY_WATCHES => {
+                report.push("file watcher", DoctorStatus::Warning, format!("fs.inotify.max_user_watches is {}. Increase it to at least {} to watch all files", max_watches, MIN_INOTIFY_WATCHES));
+            },
+            Some(max_watches) => {
+                report.push("file watcher", DoctorStatus::Ok, format!("fs.inotify.max_user_watches is {}", max_watches));
+            },
+            None => {
+                report.push("file watcher", DoctorStatus::Warning, "Unable to read fs.inotify.max_user_watches".to_string());
+            }
+        }
+    }
+
+    /* The cache directory keeps the journal of the initial build. It doesn't have to exist yet: it is created with the first
+    journal, if its nearest existing parent is writable */
+    fn check_cache_writable(report: &mut DoctorReport) {
+        let Some(dir) = cache_dir() else {
+            report.push("cache", DoctorStatus::Warning, "No cache directory: set HOME, XDG_CACHE_HOME or LOCALAPPDATA to resume interrupted builds".to_string());
+            return;
+        };
+        let Some(existing) = dir.ancestors().find(|ancestor| ancestor.is_dir()) else {
+            report.push("cache", DoctorStatus::Warning, format!("{} can't be created: none of its parents exists", dir.display()));
+            return;
+        };
+        let probe = existing.join(format!(".odoo_ls_doctor_{}", std::process::id()));
+        let status = match fs::write(&probe, b"") {
+            Ok(_) => {
+                let _ = fs::remove_file(&probe);
+                if existing == dir {
+                    (DoctorStatus::Ok, format!("{} is writable", dir.display()))
+                } else {
+                    (DoctorStatus::Ok, format!("{} doesn't exist yet, and will be created in {}", dir.display(), existing.display()))
+                }
+            },
+            Err(e) => (DoctorStatus::Warning, format!("{} is not writable: {}", existing.display(), e)),
+        };
+        report.push("cache", status.0, status.1);
+    }
+}
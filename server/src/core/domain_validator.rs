@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use ruff_python_ast::Expr;
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::constants::*;
+use crate::core::python_validator::PythonValidator;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::S;
+
+pub static DOMAIN_OPERATORS: &[&str] = &[
+    "=", "!=", "<=", "<", ">", ">=", "=?", "=like", "=ilike", "like", "not like", "ilike", "not ilike",
+    "in", "not in", "child_of", "parent_of", "any", "not any",
+];
+
+/* Fields that every model has, without being declared in the python code */
+pub static MAGIC_FIELDS: &[&str] = &["id", "display_name", "create_date", "create_uid", "write_date", "write_uid"];
+
+/* Validate domains written as python literals, like [('state', '=', 'draft'), '|', ('user_id', '=', False), ('active', '=', True)].
+Values that are not literals are ignored, as they can't be evaluated before runtime. */
+pub struct DomainValidator {}
+
+impl DomainValidator {
+
+    pub fn validate(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, domain: &Expr, from_module: Option<Rc<RefCell<Symbol>>>) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let items = match domain {
+            Expr::List(list) => &list.elts,
+            Expr::Tuple(tuple) => &tuple.elts,
+            _ => return diagnostics
+        };
+        //Check the arity of the operators, reading the domain from the end as it uses the polish notation.
+        //Remaining terms are joined with an implicit '&'
+        let mut terms: u32 = 0;
+        for item in items.iter().rev() {
+            match item {
+                Expr::StringLiteral(operator) => {
+                    let (operands, operator_name) = match operator.value.to_str() {
+                        "!" => (1, "!"),
+                        "&" => (2, "&"),
+                        "|" => (2, "|"),
+                        other => {
+                            diagnostics.push(DomainValidator::diagnostic(&operator.range, "OLS30601",
+                                format!("Invalid domain item \"{}\": it should be a condition or one of '&', '|', '!'", other)));
+                            return diagnostics;
+                        }
+                    };
+                    if terms < operands {
+                        diagnostics.push(DomainValidator::diagnostic(&operator.range, "OLS30601",
+                            format!("Missing conditions for the operator '{}' of the domain", operator_name)));
+                        return diagnostics;
+                    }
+                    terms = terms - operands + 1;
+                },
+                Expr::List(_) | Expr::Tuple(_) => {
+                    terms += 1;
+                    DomainValidator::validate_condition(session, class, item, from_module.clone(), &mut diagnostics);
+                },
+                _ => {
+                    terms += 1; //not a literal, can't be checked
+                }
+            }
+        }
+        diagnostics
+    }
+
+    fn validate_condition(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, condition: &Expr, from_module: Option<Rc<RefCell<Symbol>>>, diagnostics: &mut Vec<Diagnostic>) {
+        let elts = match condition {
+            Expr::List(list) => &list.elts,
+            Expr::Tuple(tuple) => &tuple.elts,
+            _ => return
+        };
+        if elts.len() != 3 {
+            diagnostics.push(DomainValidator::diagnostic(&condition.range(), "OLS30601",
+                S!("A domain condition should have 3 elements: a field, an operator and a value")));
+            return;
+        }
+        if let Expr::StringLiteral(operator) = &elts[1] {
+            if !DOMAIN_OPERATORS.contains(&operator.value.to_str().to_lowercase().as_str()) {
+                diagnostics.push(DomainValidator::diagnostic(&operator.range, "OLS30603",
+                    format!("Invalid operator \"{}\" in domain", operator.value.to_str())));
+            }
+        }
+        let Expr::StringLiteral(path) = &elts[0] else {
+            return; //can be a TRUE_LEAF or FALSE_LEAF
+        };
+        //only the first part of a path is checked
+        let field_name = S!(path.value.to_str().split('.').next().unwrap());
+        if !DomainValidator::is_field(session, class, &field_name, from_module) {
+            let model_name = class.borrow().as_class_sym()._model.as_ref().map(|model| model.name.clone()).unwrap_or_default();
+            diagnostics.push(DomainValidator::diagnostic(&path.range, "OLS30602",
+                format!("{} is not a field of model {}", field_name, model_name)));
+        }
+    }
+
+    pub fn is_field(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, field_name: &String, from_module: Option<Rc<RefCell<Symbol>>>) -> bool {
+        if MAGIC_FIELDS.contains(&field_name.as_str()) {
+            return true;
+        }
+        let (symbols, _) = class.borrow().get_member_symbol(session, field_name, from_module, false, false);
+        if symbols.first().is_some_and(|symbol| PythonValidator::get_field_info(session, symbol).is_some()) {
+            return true;
+        }
+        let Some(model_name) = class.borrow().as_class_sym()._model.as_ref().map(|model| model.name.clone()) else {
+            return false;
+        };
+        DomainValidator::is_delegated_field(session, &model_name, field_name, &mut vec![model_name.clone()])
+    }
+
+    /* True if the field is delegated by _inherits: the fields of the parent models are fields of the model, read and written
+    through the link field, which is created by Odoo if it is not declared */
+    fn is_delegated_field(session: &mut SessionInfo, model_name: &String, field_name: &String, visited: &mut Vec<String>) -> bool {
+        let Some(model) = session.sync_odoo.models.get(model_name).cloned() else {
+            return false;
+        };
+        let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+        let inherits: Vec<(String, String)> = classes.iter()
+            .flat_map(|class| class.borrow().as_class_sym()._model.as_ref().map(|model| model.inherits.clone()).unwrap_or_default())
+            .collect();
+        for (parent, link_field) in inherits.iter() {
+            if link_field == field_name {
+                return true;
+            }
+            if visited.contains(parent) {
+                continue;
+            }
+            visited.push(parent.clone());
+            let Some(parent_model) = session.sync_odoo.models.get(parent).cloned() else {
+                continue;
+            };
+            let parent_classes: Vec<Rc<RefCell<Symbol>>> = parent_model.borrow().get_all_symbols().collect();
+            for parent_class in parent_classes.iter() {
+                let (symbols, _) = parent_class.borrow().get_member_symbol(session, field_name, None, false, false);
+                if symbols.first().is_some_and(|symbol| PythonValidator::get_field_info(session, symbol).is_some()) {
+                    return true;
+                }
+            }
+            if DomainValidator::is_delegated_field(session, parent, field_name, visited) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn diagnostic(range: &TextRange, code: &str, message: String) -> Diagnostic {
+        Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(DiagnosticSeverity::ERROR),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None,
+        )
+    }
+}
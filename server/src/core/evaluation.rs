@@ -11,6 +11,7 @@ use crate::threads::SessionInfo;
 use crate::S;
 
 use super::file_mgr::FileMgr;
+use super::python_utils;
 use super::python_validator::PythonValidator;
 use super::symbols::symbol::Symbol;
 use super::symbols::symbol_mgr::SectionIndex;
@@ -158,27 +159,67 @@ pub struct EvaluationSymbol {
     pub get_symbol_hook: Option<GetSymbolHook>,
 }
 
+/* Why the evaluator gave up on an expression instead of returning an evaluation. They are reported by the validator in
+strict mode (see Config::strict_mode) */
+#[derive(Debug, Clone, PartialEq)]
+pub enum GiveUpReason {
+    UnknownName(String),
+    UnknownAttribute(String, TextRange), //the name of the attribute and the range of the object
+    UnknownValue, //the expression is evaluated, but not to a symbol
+    AmbiguousValue(usize), //the expression has multiple possible values, the evaluator only follows single ones
+    UnknownCallee,
+    UninferableCall(String), //the function has no return type, and its return value can't be inferred from its body
+    CallOnInstance,
+}
+
+impl GiveUpReason {
+    pub fn message(&self) -> String {
+        match self {
+            GiveUpReason::UnknownName(name) => format!("Unable to infer the value of {}: name not found", name),
+            GiveUpReason::UnknownAttribute(attr, _) => format!("Unable to infer the value of {}: attribute not found", attr),
+            GiveUpReason::UnknownValue => S!("Unable to infer the value of this expression"),
+            GiveUpReason::AmbiguousValue(count) => format!("Unable to infer the value of this expression: it has {} possible values", count),
+            GiveUpReason::UnknownCallee => S!("Unable to infer the result of this call: the called object is unknown"),
+            GiveUpReason::UninferableCall(name) => format!("Unable to infer the result of {}: add a return type annotation", name),
+            GiveUpReason::CallOnInstance => S!("Unable to infer the result of a call on an instance"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GiveUp {
+    pub range: TextRange,
+    pub reason: GiveUpReason,
+}
+
 #[derive(Default)]
 pub struct AnalyzeAstResult {
     pub evaluations: Vec<Evaluation>,
     pub effective_sym: Option<Weak<RefCell<Symbol>>>,
     pub factory: Option<Weak<RefCell<Symbol>>>,
-    pub diagnostics: Vec<Diagnostic>
+    pub diagnostics: Vec<Diagnostic>,
+    pub give_ups: Vec<GiveUp>, //failures of the evaluation of the expression and of its sub-expressions
 }
 
 impl AnalyzeAstResult {
     pub fn from_only_diagnostics(diags: Vec<Diagnostic>) -> Self {
-        AnalyzeAstResult { evaluations: vec![], effective_sym: None, factory: None, diagnostics: diags }
+        AnalyzeAstResult { evaluations: vec![], effective_sym: None, factory: None, diagnostics: diags, give_ups: vec![] }
+    }
+
+    pub fn from_give_ups(diags: Vec<Diagnostic>, give_ups: Vec<GiveUp>) -> Self {
+        AnalyzeAstResult { evaluations: vec![], effective_sym: None, factory: None, diagnostics: diags, give_ups }
     }
 }
 
 impl Evaluation {
 
+    /* The builtins classes are missing when the stdlib can't be found (see SyncOdoo::degraded). The evaluations are then
+    built without symbol */
     pub fn new_list(odoo: &mut SyncOdoo, values: Vec<Expr>, range: TextRange) -> Evaluation {
         Evaluation {
             symbol: EvaluationSymbol {
                 sym: EvaluationSymbolPtr::WEAK(EvaluationSymbolWeak{
-                    weak: Rc::downgrade(&odoo.get_symbol(&(vec![S!("builtins")], vec![S!("list")]), u32::MAX).last().expect("builtins list not found")),
+                    weak: odoo.get_symbol(&(vec![S!("builtins")], vec![S!("list")]), u32::MAX).last().map(Rc::downgrade).unwrap_or_default(),
                     instance: true,
                 }),
                 context: HashMap::new(),
@@ -190,11 +231,21 @@ impl Evaluation {
         }
     }
 
+    /* A list whose elements are instances of a class, like the list of values given to a create decorated with
+    @api.model_create_multi. The class of the elements is kept in the context under "element", see Evaluation::element_evaluation */
+    pub fn new_list_of(odoo: &mut SyncOdoo, element: &Tree, range: TextRange) -> Evaluation {
+        let mut evaluation = Evaluation::new_list(odoo, vec![], range);
+        if let Some(element) = odoo.get_symbol(element, u32::MAX).last() {
+            evaluation.symbol.context.insert(S!("element"), ContextValue::SYMBOL(Rc::downgrade(element)));
+        }
+        evaluation
+    }
+
     pub fn new_tuple(odoo: &mut SyncOdoo, values: Vec<Expr>, range: TextRange) -> Evaluation {
         Evaluation {
             symbol: EvaluationSymbol {
                 sym: EvaluationSymbolPtr::WEAK(EvaluationSymbolWeak{
-                    weak: Rc::downgrade(&odoo.get_symbol(&(vec![S!("builtins")], vec![S!("tuple")]), u32::MAX).last().expect("builtins list not found")),
+                    weak: odoo.get_symbol(&(vec![S!("builtins")], vec![S!("tuple")]), u32::MAX).last().map(Rc::downgrade).unwrap_or_default(),
                     instance: true,
                 }),
                 context: HashMap::new(),
@@ -210,7 +261,7 @@ impl Evaluation {
         Evaluation {
             symbol: EvaluationSymbol {
                 sym: EvaluationSymbolPtr::WEAK(EvaluationSymbolWeak{
-                    weak: Rc::downgrade(&odoo.get_symbol(&(vec![S!("builtins")], vec![S!("dict")]), u32::MAX).last().expect("builtins list not found")),
+                    weak: odoo.get_symbol(&(vec![S!("builtins")], vec![S!("dict")]), u32::MAX).last().map(Rc::downgrade).unwrap_or_default(),
                     instance: true,
                 }),
                 context: HashMap::new(),
@@ -222,6 +273,22 @@ impl Evaluation {
         }
     }
 
+    pub fn new_set(odoo: &mut SyncOdoo, range: TextRange) -> Evaluation {
+        Evaluation {
+            symbol: EvaluationSymbol {
+                sym: EvaluationSymbolPtr::WEAK(EvaluationSymbolWeak{
+                    weak: odoo.get_symbol(&(vec![S!("builtins")], vec![S!("set")]), u32::MAX).last().map(Rc::downgrade).unwrap_or_default(),
+                    instance: true,
+                }),
+                context: HashMap::new(),
+                factory: None,
+                get_symbol_hook: None
+            },
+            value: None,
+            range: Some(range)
+        }
+    }
+
     pub fn new_constant(odoo: &mut SyncOdoo, values: Expr, range: TextRange) -> Evaluation {
         let tree_value = match &values {
             Expr::StringLiteral(_s) => {
@@ -240,7 +307,7 @@ impl Evaluation {
         };
         let symbol;
         if !values.is_none_literal_expr() {
-            symbol = Rc::downgrade(&odoo.get_symbol(&tree_value, u32::MAX).last().expect("builtins class not found"));
+            symbol = odoo.get_symbol(&tree_value, u32::MAX).last().map(Rc::downgrade).unwrap_or_default();
         } else {
             symbol = Weak::new();
         }
@@ -259,6 +326,24 @@ impl Evaluation {
         }
     }
 
+    /* Evaluation of a str built at runtime, like f-strings or "%s_id" % name. The value is unknown */
+    pub fn new_str_instance(odoo: &mut SyncOdoo, range: TextRange) -> Evaluation {
+        let symbol = odoo.get_symbol(&(vec![S!("builtins")], vec![S!("str")]), u32::MAX).last().map(Rc::downgrade).unwrap_or_default();
+        Evaluation {
+            symbol: EvaluationSymbol {
+                sym: EvaluationSymbolPtr::WEAK(EvaluationSymbolWeak{
+                    weak: symbol,
+                    instance: true,
+                }),
+                context: HashMap::new(),
+                factory: None,
+                get_symbol_hook: None
+            },
+            value: None,
+            range: Some(range)
+        }
+    }
+
     pub fn new_none() -> Self {
         Self {
             symbol: EvaluationSymbol {
@@ -304,6 +389,34 @@ impl Evaluation {
         res
     }
 
+    /* Evaluation of the elements of a list built by Evaluation::new_list_of, following the variables holding it */
+    pub fn element_evaluation(&self, session: &mut SessionInfo) -> Option<Evaluation> {
+        let element = match self.symbol.context.get("element") {
+            Some(ContextValue::SYMBOL(element)) => element.clone(),
+            _ => {
+                let symbol = self.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()?;
+                let evals = Symbol::follow_ref(&symbol, session, &mut None, false, true, None, &mut vec![]);
+                let [(eval_sym, _)] = evals.as_slice() else {
+                    return None;
+                };
+                let eval_sym = eval_sym.upgrade()?;
+                let eval_sym = eval_sym.borrow();
+                let [eval] = eval_sym.evaluations()?.as_slice() else {
+                    return None;
+                };
+                match eval.symbol.context.get("element") {
+                    Some(ContextValue::SYMBOL(element)) => element.clone(),
+                    _ => return None,
+                }
+            }
+        };
+        Some(Evaluation {
+            symbol: EvaluationSymbol::new_with_symbol(element, true, HashMap::new(), None, None),
+            value: None,
+            range: None,
+        })
+    }
+
     pub fn follow_ref_and_get_value(&self, session: &mut SessionInfo, context: &mut Option<Context>, diagnostics: &mut Vec<Diagnostic>) -> Option<EvaluationValue> {
         if self.value.is_some() {
             Some(self.value.as_ref().unwrap().clone())
@@ -381,6 +494,12 @@ impl Evaluation {
     // eval_from_ast should be called on '"5"' to build the evaluation of 'a'
     //The result is a list, because some ast can give various possible results. For example: a = func()
     pub fn eval_from_ast(session: &mut SessionInfo, ast: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> (Vec<Evaluation>, Vec<Diagnostic>) {
+        let analyze_result = Evaluation::analyze_expr(session, ast, parent, max_infer);
+        return (analyze_result.evaluations, analyze_result.diagnostics)
+    }
+
+    /* Like eval_from_ast, but keep the whole result, with the reasons of the failures of the evaluation */
+    pub fn analyze_expr(session: &mut SessionInfo, ast: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> AnalyzeAstResult {
         let from_module;
         if let Some(module) = parent.borrow().find_module() {
             from_module = ContextValue::MODULE(Rc::downgrade(&module));
@@ -391,8 +510,71 @@ impl Evaluation {
             (S!("module"), from_module),
             (S!("range"), ContextValue::RANGE(ast.range()))
         ]));
-        let analyze_result = Evaluation::analyze_ast(session, &ExprOrIdent::Expr(ast), parent, max_infer, &mut context);
-        return (analyze_result.evaluations, analyze_result.diagnostics)
+        Evaluation::analyze_ast(session, &ExprOrIdent::Expr(ast), parent, max_infer, &mut context)
+    }
+
+    /* Build the evaluations of a type annotation: the annotated variable is an instance of the given class(es).
+    Optional[X], Union[X, Y], X | Y, Annotated[X, ...] and string forward references are unwrapped, and generic
+    aliases like list[X] are evaluated to their base class */
+    pub fn eval_from_annotation(session: &mut SessionInfo, annotation: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> (Vec<Evaluation>, Vec<Diagnostic>) {
+        let mut evals = vec![];
+        let mut diagnostics = vec![];
+        match annotation {
+            Expr::NoneLiteral(_) => {},
+            Expr::StringLiteral(s) => {
+                if let Ok(parsed) = ruff_python_parser::parse_expression(s.value.to_str()) {
+                    //ranges of the parsed expression are relative to the string, so drop the diagnostics
+                    let (sub_evals, _) = Evaluation::eval_from_annotation(session, parsed.expr(), parent, max_infer);
+                    evals.extend(sub_evals);
+                }
+            },
+            Expr::BinOp(op) if op.op == Operator::BitOr => {
+                for side in [&op.left, &op.right] {
+                    let (sub_evals, diags) = Evaluation::eval_from_annotation(session, side, parent.clone(), max_infer);
+                    evals.extend(sub_evals);
+                    diagnostics.extend(diags);
+                }
+            },
+            Expr::Subscript(sub) => {
+                let base_name = match sub.value.as_ref() {
+                    Expr::Name(name) => name.id.as_str(),
+                    Expr::Attribute(attr) => attr.attr.as_str(),
+                    _ => ""
+                };
+                let inner: Vec<&Expr> = match (base_name, sub.slice.as_ref()) {
+                    ("Optional", slice) => vec![slice],
+                    ("Union", Expr::Tuple(tuple)) => tuple.elts.iter().collect(),
+                    ("Union", slice) => vec![slice],
+                    ("Annotated", Expr::Tuple(tuple)) => tuple.elts.first().into_iter().collect(),
+                    _ => vec![&sub.value]
+                };
+                for expr in inner {
+                    let (sub_evals, diags) = Evaluation::eval_from_annotation(session, expr, parent.clone(), max_infer);
+                    evals.extend(sub_evals);
+                    diagnostics.extend(diags);
+                }
+            },
+            _ => {
+                let (base_evals, diags) = Evaluation::eval_from_ast(session, annotation, parent, max_infer);
+                diagnostics.extend(diags);
+                for eval in base_evals.iter() {
+                    let Some(base_sym) = eval.symbol.get_symbol(session, &mut None, &mut diagnostics, None).0.upgrade() else {
+                        continue;
+                    };
+                    for (sym, instance) in Symbol::follow_ref(&base_sym, session, &mut None, false, false, None, &mut diagnostics) {
+                        let is_class = sym.upgrade().map_or(false, |sym| sym.borrow().typ() == SymType::CLASS);
+                        if is_class && !instance {
+                            evals.push(Evaluation {
+                                symbol: EvaluationSymbol::new_with_symbol(sym, true, HashMap::new(), None, None),
+                                value: None,
+                                range: Some(annotation.range())
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        (evals, diagnostics)
     }
 
     /* Given an Expr, try to return the represented String. None if it can't be achieved */
@@ -469,6 +651,8 @@ impl Evaluation {
         let effective_sym = None;
         let factory = None;
         let mut diagnostics = vec![];
+        let mut give_ups = vec![];
+        let strict = odoo.config.strict_mode; //the give-ups are only reported in strict mode
         let module: Option<Rc<RefCell<Symbol>>> = parent.borrow().find_module();
 
         match ast {
@@ -490,6 +674,9 @@ impl Evaluation {
             ExprOrIdent::Expr(Expr::EllipsisLiteral(expr)) => {
                 evals.push(Evaluation::new_constant(odoo, ast.expr().clone(), expr.range));
             }
+            ExprOrIdent::Expr(Expr::FString(expr)) => {
+                evals.push(Evaluation::new_str_instance(odoo, expr.range));
+            },
             ExprOrIdent::Expr(Expr::List(expr)) => {
                 let mut values: Vec<ruff_python_ast::Expr> = Vec::new();
                 for e in expr.elts.iter() {
@@ -532,9 +719,25 @@ impl Evaluation {
                 }
                 evals.push(Evaluation::new_dict(odoo, values, expr.range));
             },
+            //the content of comprehensions is unknown, but not their type
+            ExprOrIdent::Expr(Expr::ListComp(expr)) => {
+                evals.push(Evaluation::new_list(odoo, vec![], expr.range));
+            },
+            ExprOrIdent::Expr(Expr::SetComp(expr)) => {
+                evals.push(Evaluation::new_set(odoo, expr.range));
+            },
+            ExprOrIdent::Expr(Expr::DictComp(expr)) => {
+                evals.push(Evaluation::new_dict(odoo, vec![], expr.range));
+            },
             ExprOrIdent::Expr(Expr::Call(expr)) => {
-                let (base_eval, diags) = Evaluation::eval_from_ast(session, &expr.func, parent, max_infer);
-                diagnostics.extend(diags);
+                //getattr(rec, 'partner_id') is evaluated like rec.partner_id
+                if let Some(attribute) = python_utils::getattr_as_attribute(expr) {
+                    return Evaluation::analyze_ast(session, &ExprOrIdent::Expr(&Expr::Attribute(attribute)), parent, max_infer, context);
+                }
+                let base_result = Evaluation::analyze_expr(session, &expr.func, parent.clone(), max_infer);
+                diagnostics.extend(base_result.diagnostics);
+                give_ups.extend(base_result.give_ups);
+                let base_eval = base_result.evaluations;
                 //TODO actually we only evaluate if there is only one function behind the evaluation.
                 // we could evaluate the result of each function and filter results by signature matching.
                 /* example:
@@ -558,7 +761,11 @@ impl Evaluation {
                 print(c) <= string/int with value 5. if we had a parameter to 'other_test', only string with value 5
                 */
                 if base_eval.len() != 1 {
-                    return AnalyzeAstResult::from_only_diagnostics(diagnostics);
+                    //without evaluation, the reason is given by the callee
+                    if strict && base_eval.len() > 1 {
+                        give_ups.push(GiveUp { range: expr.func.range(), reason: GiveUpReason::AmbiguousValue(base_eval.len()) });
+                    }
+                    return AnalyzeAstResult::from_give_ups(diagnostics, give_ups);
                 }
                 let mut context = Some(base_eval[0].symbol.context.clone());
                 //TODO context should give params
@@ -568,6 +775,9 @@ impl Evaluation {
                     if base_sym.borrow().typ() == SymType::CLASS {
                         if instance {
                             //TODO handle call on class instance
+                            if strict {
+                                give_ups.push(GiveUp { range: expr.range, reason: GiveUpReason::CallOnInstance });
+                            }
                         } else {
                             //TODO diagnostic __new__ call parameters
                             evals.push(Evaluation{
@@ -599,21 +809,50 @@ impl Evaluation {
                             }
                         }
                         if base_sym.borrow().evaluations().is_some() {
+                            //hooks giving the result of a call from its arguments, like date_utils.start_of(value, 'month'), get the first one
+                            let has_hook = base_sym.borrow().evaluations().unwrap().iter().any(|eval| eval.symbol.get_symbol_hook.is_some());
+                            let first_arg = match expr.arguments.args.first() {
+                                Some(arg) if has_hook => {
+                                    let (arg_evals, _) = Evaluation::eval_from_ast(session, arg, parent.clone(), max_infer);
+                                    arg_evals.first().map(|arg_eval| arg_eval.symbol.get_symbol(session, &mut None, &mut vec![], None))
+                                },
+                                _ => None
+                            };
                             for eval in base_sym.borrow().evaluations().unwrap().iter() {
                                 let mut e = eval.clone();
                                 e.symbol.context.extend(context.as_mut().unwrap().clone());
+                                if let Some((arg_symbol, arg_instance)) = first_arg.as_ref() {
+                                    e.symbol.context.insert(S!("first_arg"), ContextValue::SYMBOL(arg_symbol.clone()));
+                                    e.symbol.context.insert(S!("first_arg_instance"), ContextValue::BOOLEAN(*arg_instance));
+                                }
                                 e.range = Some(expr.range.clone());
                                 evals.push(e);
                             }
                         }
+                        if strict && evals.is_empty() {
+                            give_ups.push(GiveUp { range: expr.range, reason: GiveUpReason::UninferableCall(base_sym.borrow().name().clone()) });
+                        }
+                    } else if strict {
+                        give_ups.push(GiveUp { range: expr.func.range(), reason: GiveUpReason::UnknownCallee });
                     }
+                } else if strict {
+                    give_ups.push(GiveUp { range: expr.func.range(), reason: GiveUpReason::UnknownCallee });
                 }
             },
             ExprOrIdent::Expr(Expr::Attribute(expr)) => {
-                let (base_evals, diags) = Evaluation::eval_from_ast(session, &expr.value, parent.clone(), max_infer);
-                diagnostics.extend(diags);
+                let base_result = Evaluation::analyze_expr(session, &expr.value, parent.clone(), max_infer);
+                diagnostics.extend(base_result.diagnostics);
+                give_ups.extend(base_result.give_ups);
+                let base_evals = base_result.evaluations;
                 if base_evals.len() != 1 || base_evals[0].symbol.get_symbol(session, &mut None, &mut diagnostics, None).0.is_expired() {
-                    return AnalyzeAstResult::from_only_diagnostics(diagnostics);
+                    if strict {
+                        match base_evals.len() {
+                            0 => {},
+                            1 => give_ups.push(GiveUp { range: expr.value.range(), reason: GiveUpReason::UnknownValue }),
+                            count => give_ups.push(GiveUp { range: expr.value.range(), reason: GiveUpReason::AmbiguousValue(count) }),
+                        }
+                    }
+                    return AnalyzeAstResult::from_give_ups(diagnostics, give_ups);
                 }
                 let base_ref = base_evals[0].symbol.get_symbol(session, &mut None, &mut diagnostics, Some(parent.borrow().get_file().unwrap().upgrade().unwrap().clone())).0;
                 let bases = Symbol::follow_ref(&base_ref.upgrade().unwrap(), session, &mut None, false, false, None, &mut diagnostics);
@@ -633,6 +872,9 @@ impl Evaluation {
                         }
                     }
                 }
+                if strict && evals.is_empty() {
+                    give_ups.push(GiveUp { range: expr.attr.range(), reason: GiveUpReason::UnknownAttribute(expr.attr.to_string(), expr.value.range()) });
+                }
             },
             ExprOrIdent::Expr(Expr::Name(_)) | ExprOrIdent::Ident(_) | ExprOrIdent::Parameter(_) => {
                 let infered_syms = match ast {
@@ -651,22 +893,44 @@ impl Evaluation {
                 };
 
                 if infered_syms.is_empty() {
-                    return AnalyzeAstResult::from_only_diagnostics(diagnostics);
+                    if strict {
+                        let (name, range) = match ast {
+                            ExprOrIdent::Expr(Expr::Name(expr)) => (expr.id.to_string(), expr.range),
+                            ExprOrIdent::Ident(expr) => (expr.id.to_string(), expr.range),
+                            ExprOrIdent::Parameter(expr) => (expr.name.id.to_string(), expr.range),
+                            _ => unreachable!(),
+                        };
+                        give_ups.push(GiveUp { range, reason: GiveUpReason::UnknownName(name) });
+                    }
+                    return AnalyzeAstResult::from_give_ups(diagnostics, give_ups);
                 }
                 for infered_sym in infered_syms.iter() {
                     evals.push(Evaluation::eval_from_symbol(&Rc::downgrade(infered_sym)));
                 }
             },
             ExprOrIdent::Expr(Expr::Subscript(sub)) => {
-                let (eval_left, diags) = Evaluation::eval_from_ast(session, &sub.value, parent.clone(), max_infer);
-                diagnostics.extend(diags);
+                let left_result = Evaluation::analyze_expr(session, &sub.value, parent.clone(), max_infer);
+                diagnostics.extend(left_result.diagnostics);
+                give_ups.extend(left_result.give_ups);
+                let eval_left = left_result.evaluations;
                 if eval_left.len() != 1 || eval_left[0].symbol.get_symbol(session, &mut None, &mut diagnostics, None).0.is_expired() { //TODO set context?
-                    return AnalyzeAstResult::from_only_diagnostics(diagnostics);
+                    if strict {
+                        match eval_left.len() {
+                            0 => {},
+                            1 => give_ups.push(GiveUp { range: sub.value.range(), reason: GiveUpReason::UnknownValue }),
+                            count => give_ups.push(GiveUp { range: sub.value.range(), reason: GiveUpReason::AmbiguousValue(count) }),
+                        }
+                    }
+                    return AnalyzeAstResult::from_give_ups(diagnostics, give_ups);
                 }
                 let base = &eval_left[0].symbol.get_symbol(session, &mut None, &mut diagnostics, None).0; //TODO set context?
                 let bases = Symbol::follow_ref(&base.upgrade().unwrap(), session, &mut None, false, false, None, &mut diagnostics);
                 if bases.len() != 1 {
-                    return AnalyzeAstResult::from_only_diagnostics(diagnostics);
+                    if strict {
+                        let reason = if bases.is_empty() { GiveUpReason::UnknownValue } else { GiveUpReason::AmbiguousValue(bases.len()) };
+                        give_ups.push(GiveUp { range: sub.value.range(), reason });
+                    }
+                    return AnalyzeAstResult::from_give_ups(diagnostics, give_ups);
                 }
                 let base = &bases[0];
                 let base = base.0.upgrade().unwrap();
@@ -698,13 +962,19 @@ impl Evaluation {
                 match operator.op {
                     Operator::Add => {
 
+                    },
+                    Operator::Mod => {
+                        //"%s_id" % name
+                        if matches!(operator.left.as_ref(), Expr::StringLiteral(_) | Expr::FString(_)) {
+                            evals.push(Evaluation::new_str_instance(odoo, operator.range));
+                        }
                     },
                     _ => {}
                 }
             }
-            _ => {}
+            ExprOrIdent::Expr(_) => {}
         }
-        AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics }
+        AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics, give_ups }
     }
 }
 
@@ -755,6 +1025,10 @@ impl EvaluationSymbol {
         }
         if self.get_symbol_hook.is_some() {
             let hook = self.get_symbol_hook.unwrap();
+            //hooks read the file to add their dependencies and diagnostics
+            if let Some(file) = file_symbol.as_ref() {
+                debug_assert!(file.try_borrow().is_ok(), "file is mutably borrowed while running a get_symbol hook");
+            }
             return hook(session, self, &mut Some(full_context), diagnostics, file_symbol);
         }
         match &self.sym {
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use ruff_python_ast::{Expr, Keyword};
+use ruff_text_size::TextRange;
+
+/* Keyword arguments given to a field declaration, with their value if it is a boolean literal */
+#[derive(Debug)]
+pub struct FieldKwargs {
+    kwargs: HashMap<String, (Option<bool>, TextRange)>,
+}
+
+impl FieldKwargs {
+
+    pub fn from_keywords(keywords: &[Keyword]) -> Self {
+        let mut kwargs = HashMap::new();
+        for keyword in keywords.iter() {
+            let Some(arg) = keyword.arg.as_ref() else {
+                continue;
+            };
+            let value = match &keyword.value {
+                Expr::BooleanLiteral(b) => Some(b.value),
+                _ => None
+            };
+            kwargs.insert(arg.to_string(), (value, keyword.range));
+        }
+        Self { kwargs }
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.kwargs.contains_key(name)
+    }
+
+    pub fn is_true(&self, name: &str) -> bool {
+        self.kwargs.get(name).is_some_and(|(value, _)| *value == Some(true))
+    }
+
+    pub fn is_false(&self, name: &str) -> bool {
+        self.kwargs.get(name).is_some_and(|(value, _)| *value == Some(false))
+    }
+
+    pub fn is_computed(&self) -> bool {
+        self.has("compute") || self.has("related")
+    }
+
+    pub fn range(&self, name: &str) -> Option<TextRange> {
+        self.kwargs.get(name).map(|(_, range)| *range)
+    }
+}
+
+/* A rule on the combination of keyword arguments of a field. When the rule matches, a warning is emitted on the keyword */
+pub struct FieldKwargRule {
+    pub code: &'static str,
+    pub keyword: &'static str,
+    pub message: &'static str,
+    pub matches: fn(&FieldKwargs) -> bool,
+}
+
+/* Each rule has its own code, so it can be disabled with a suppression comment */
+pub static FIELD_KWARG_RULES: &[FieldKwargRule] = &[
+    FieldKwargRule {
+        code: "OLS20404",
+        keyword: "store",
+        message: "store=True has no effect on a field that is not computed or related",
+        matches: |kwargs| kwargs.is_true("store") && !kwargs.is_computed(),
+    },
+    FieldKwargRule {
+        code: "OLS20405",
+        keyword: "search",
+        message: "A search method is never used on a stored field",
+        matches: |kwargs| kwargs.has("search") && (kwargs.is_true("store") || !kwargs.is_computed()),
+    },
+    FieldKwargRule {
+        code: "OLS20406",
+        keyword: "inverse",
+        message: "An inverse method is only used on computed fields",
+        matches: |kwargs| kwargs.has("inverse") && !kwargs.has("compute"),
+    },
+    FieldKwargRule {
+        code: "OLS20407",
+        keyword: "readonly",
+        message: "readonly=False on a related field that is not stored will write on the related record",
+        matches: |kwargs| kwargs.is_false("readonly") && kwargs.has("related") && !kwargs.is_true("store"),
+    },
+    FieldKwargRule {
+        code: "OLS20408",
+        keyword: "compute_sudo",
+        message: "compute_sudo is only used on computed fields",
+        matches: |kwargs| kwargs.has("compute_sudo") && !kwargs.is_computed(),
+    },
+    FieldKwargRule {
+        code: "OLS20409",
+        keyword: "copy",
+        message: "copy=True has no effect on a computed field that is not stored",
+        matches: |kwargs| kwargs.is_true("copy") && kwargs.is_computed() && !kwargs.is_true("store"),
+    },
+];
@@ -2,32 +2,70 @@ use lsp_types::notification::{Notification, PublishDiagnostics};
 use ropey::Rope;
 use ruff_python_ast::Mod;
 use ruff_python_parser::Mode;
-use lsp_types::{Diagnostic, DiagnosticSeverity, MessageType, NumberOrString, Position, PublishDiagnosticsParams, Range, TextDocumentContentChangeEvent};
-use tracing::{error, warn};
+use lsp_types::{Diagnostic, DiagnosticSeverity, MessageType, NumberOrString, Position, PublishDiagnosticsParams, Range, TextDocumentContentChangeEvent, Uri};
+use tracing::{error, trace, warn};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::{collections::HashMap, fs};
+use std::{collections::{HashMap, HashSet}, fs};
 use crate::threads::SessionInfo;
+use crate::core::build_journal::JournalFile;
+use crate::core::config::Config;
+use crate::core::diagnostic_codes::add_code_description;
+use crate::core::evaluation::GiveUp;
+use crate::core::uri;
 use crate::utils::PathSanitizer;
 use std::rc::Rc;
 use std::cell::RefCell;
 use crate::S;
 use crate::constants::*;
-use ruff_text_size::TextRange;
+use ruff_text_size::{TextRange, TextSize};
+
+/* Diagnostic codes disabled with '# odoo-ls: disable=CODE1,CODE2' comments (for the line of the comment)
+or '# odoo-ls: disable-file=CODE1,CODE2' comments (for the whole file) */
+#[derive(Debug, Default)]
+pub struct DiagnosticSuppressions {
+    pub file: HashSet<String>,
+    pub lines: HashMap<u32, HashSet<String>>,
+}
+
+impl DiagnosticSuppressions {
+
+    pub fn is_suppressed(&self, code: &str, line: u32) -> bool {
+        self.file.contains(code) || self.lines.get(&line).is_some_and(|codes| codes.contains(code))
+    }
+
+    /* Parse the directive following the SUPPRESSION_COMMENT. Return true if it applies to the whole file, and the codes */
+    pub fn parse_directive(directive: &str) -> Option<(bool, Vec<String>)> {
+        let directive = directive.trim();
+        let (file_scope, codes) = if let Some(codes) = directive.strip_prefix("disable-file=") {
+            (true, codes)
+        } else if let Some(codes) = directive.strip_prefix("disable=") {
+            (false, codes)
+        } else {
+            return None;
+        };
+        let codes: Vec<String> = codes.split(',').map(|c| c.trim()).filter(|c| !c.is_empty()).map(|c| c.to_string()).collect();
+        Some((file_scope, codes))
+    }
+}
 
 #[derive(Debug)]
 pub struct FileInfo {
     pub ast: Option<Vec<ruff_python_ast::Stmt>>,
     pub version: i32,
     pub uri: String,
+    pub client_uri: Option<Uri>, //uri of the document opened by the client, that can be another path to the file, like through a symlink
     pub valid: bool, // indicates if the file contains syntax error or not
     pub opened: bool,
+    pub large: bool, // bigger than the largeFileThreshold. Only its top-level symbols are indexed
     need_push: bool,
     text_rope: Option<ropey::Rope>,
     text_hash: u64,
-    diagnostics: HashMap<BuildSteps, Vec<Diagnostic>>,
+    diagnostics: HashMap<BuildSteps, (i32, Vec<Diagnostic>)>, //diagnostics of each step, stamped with the version of the document they have been computed on
+    give_ups: HashMap<Option<TextSize>, Vec<GiveUp>>, //in strict mode, give-ups of the evaluation of the file (None) and of the body of its functions (by start offset)
+    suppressions: DiagnosticSuppressions,
 }
 
 impl FileInfo {
@@ -36,12 +74,16 @@ impl FileInfo {
             ast: None,
             version: 0,
             uri,
+            client_uri: None,
             valid: true,
             opened: false,
+            large: false,
             need_push: false,
             text_rope: None,
             text_hash: 0,
             diagnostics: HashMap::new(),
+            give_ups: HashMap::new(),
+            suppressions: DiagnosticSuppressions::default(),
         }
     }
     pub fn update(&mut self, session: &mut SessionInfo, uri: &str, content: Option<&Vec<TextDocumentContentChangeEvent>>, version: Option<i32>, force: bool) -> bool {
@@ -52,6 +94,7 @@ impl FileInfo {
         // -100 can be given as version number to indicates that the file has not been opened yet, and that we have to load it ourself
         // See https://github.com/Microsoft/language-server-protocol/issues/177
         // Return true if the update has been done and not discarded
+        let previous_version = self.version;
         if let Some(version) = version {
             if version == -100 {
                 self.version = 1;
@@ -65,7 +108,11 @@ impl FileInfo {
         } else if self.version != 0 && !force {
             return false;
         }
-        self.diagnostics.clear();
+        if self.version == previous_version {
+            //the content changes without a new version, like a file read again from the disk: the diagnostics can't be told apart
+            self.diagnostics.clear();
+        }
+        self.give_ups.clear();
         if let Some(content) = content {
             for change in content.iter() {
                 self.apply_change(change);
@@ -88,14 +135,27 @@ impl FileInfo {
         if old_hash == self.text_hash {
             return false;
         }
+        self.update_large(session.sync_odoo.config.large_file_threshold);
         self._build_ast();
         true
     }
 
+    /* Compare the size of the text with the largeFileThreshold setting (in KB). Return true if the flag changed */
+    pub fn update_large(&mut self, threshold: u64) -> bool {
+        let large = threshold > 0 && self.text_rope.as_ref().is_some_and(|rope| rope.len_bytes() as u64 > threshold * 1024);
+        let changed = large != self.large;
+        self.large = large;
+        changed
+    }
+
     pub fn _build_ast(&mut self) {
+        if self.uri.ends_with(".xml") {
+            return; //xml files are only cached for the python code they embed
+        }
         let mut diagnostics = vec![];
         let content = &self.text_rope.as_ref().unwrap().slice(..);
         let source = content.to_string(); //cast to string to get a version with all changes
+        let source = FileInfo::mask_cell_magics(&source).unwrap_or(source);
         let ast = ruff_python_parser::parse_unchecked(source.as_str(), Mode::Module);
         self.valid = true;
         for error in ast.errors().iter() {
@@ -110,6 +170,7 @@ impl FileInfo {
                 None,
                 None));
         }
+        self.suppressions = self.get_suppressions();
         match ast.into_syntax() {
             Mod::Expression(_expr) => {
                 warn!("No support for expression-file only");
@@ -122,40 +183,113 @@ impl FileInfo {
         self.replace_diagnostics(BuildSteps::SYNTAX, diagnostics);
     }
 
+    /* Files of jupyter-style cells can use the IPython syntax: line magics (%time), shell commands (!pip) and cell magics (%%sql)
+    whose whole cell is not python. These lines are replaced by '...' and blanks, so the offsets of the rest of the file don't change.
+    Return None if the file has no cell */
+    pub fn mask_cell_magics(source: &str) -> Option<String> {
+        if !source.lines().any(|line| CELL_MARKERS.iter().any(|marker| line.starts_with(marker))) {
+            return None;
+        }
+        let mut res = String::with_capacity(source.len());
+        let mut in_cell_magic = false;
+        for line in source.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let eol = &line[content.len()..];
+            let code = content.trim_start();
+            if CELL_MARKERS.iter().any(|marker| content.starts_with(marker)) {
+                in_cell_magic = false;
+            } else if in_cell_magic {
+                res.push_str(&" ".repeat(content.len()));
+                res.push_str(eol);
+                continue;
+            } else if code.starts_with('%') || code.starts_with('!') {
+                in_cell_magic = code.starts_with("%%");
+                res.push_str(&content[..content.len() - code.len()]);
+                if code.len() >= 3 {
+                    res.push_str("...");
+                    res.push_str(&" ".repeat(code.len() - 3));
+                } else {
+                    res.push_str(&" ".repeat(code.len()));
+                }
+                res.push_str(eol);
+                continue;
+            }
+            res.push_str(line);
+        }
+        Some(res)
+    }
+
     pub fn replace_diagnostics(&mut self, step: BuildSteps, diagnostics: Vec<Diagnostic>) {
+        self.replace_diagnostics_for_version(step, diagnostics, self.version);
+    }
+
+    /* Store the diagnostics of a step computed on the given version of the document.
+    If the document has been updated since, the diagnostics are stale and are discarded. Return false in this case. */
+    pub fn replace_diagnostics_for_version(&mut self, step: BuildSteps, diagnostics: Vec<Diagnostic>, version: i32) -> bool {
+        if version != self.version {
+            trace!("Discarding {:?} diagnostics of {} computed on version {} (current version: {})", step, self.uri, version, self.version);
+            return false;
+        }
         self.need_push = true;
-        self.diagnostics.insert(step, diagnostics);
+        let diagnostics = self.remove_suppressed(diagnostics);
+        self.diagnostics.insert(step, (version, diagnostics));
+        true
     }
 
     pub fn update_validation_diagnostics(&mut self, diagnostics: HashMap<BuildSteps, Vec<Diagnostic>>) {
         self.need_push = true;
-        for (key, value) in diagnostics.iter() {
-            self.diagnostics.entry(*key).or_insert_with(|| vec![]).extend(value.clone());
+        let version = self.version;
+        for (key, value) in diagnostics.into_iter() {
+            let value = self.remove_suppressed(value);
+            let entry = self.diagnostics.entry(key).or_insert_with(|| (version, vec![]));
+            if entry.0 != version {
+                *entry = (version, vec![]);
+            }
+            entry.1.extend(value);
+        }
+    }
+
+    /* Drop the diagnostics disabled by a suppression comment. Diagnostics are not converted yet, so the line of the range
+    holds the offset of the diagnostic in the file */
+    fn remove_suppressed(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        if self.suppressions.file.is_empty() && self.suppressions.lines.is_empty() {
+            return diagnostics;
         }
+        let Some(rope) = self.text_rope.as_ref() else {
+            return diagnostics;
+        };
+        diagnostics.into_iter().filter(|diagnostic| {
+            let Some(NumberOrString::String(code)) = &diagnostic.code else {
+                return true;
+            };
+            let line = FileInfo::offset_to_position_with_rope(rope, diagnostic.range.start.line as usize).line;
+            !self.suppressions.is_suppressed(code, line)
+        }).collect()
     }
 
     fn update_range(&self, mut diagnostic: Diagnostic) -> Diagnostic {
         diagnostic.range.start = self.offset_to_position(diagnostic.range.start.line as usize);
         diagnostic.range.end = self.offset_to_position(diagnostic.range.end.line as usize);
+        //related information are stored with offsets too, and point to the same file
+        for related in diagnostic.related_information.iter_mut().flatten() {
+            related.location.range.start = self.offset_to_position(related.location.range.start.line as usize);
+            related.location.range.end = self.offset_to_position(related.location.range.end.line as usize);
+        }
         diagnostic
     }
 
     pub fn publish_diagnostics(&mut self, session: &mut SessionInfo) {
         if self.need_push {
+            //diagnostics computed on an older version of the document are dropped, the pending rebuild will replace them
+            let version = self.version;
+            self.diagnostics.retain(|_, (diagnostics_version, _)| *diagnostics_version == version);
             let mut all_diagnostics = Vec::new();
-
-            for diagnostics in self.diagnostics.values() {
-                for d in diagnostics.iter() {
-                    all_diagnostics.push(self.update_range(d.clone()));
-                }
-            }
-            let mut slash = "";
-            if cfg!(windows) {
-                slash = "/";
+            for mut d in self.configured_diagnostics(&session.sync_odoo.config) {
+                add_code_description(&mut d);
+                all_diagnostics.push(self.update_range(d));
             }
-            let uri = format!("file://{}{}", slash, self.uri);
             session.send_notification::<PublishDiagnosticsParams>(PublishDiagnostics::METHOD, PublishDiagnosticsParams{
-                uri: lsp_types::Uri::from_str(&uri).expect("Unable to parse uri"),
+                uri: self.client_uri.clone().unwrap_or_else(|| FileMgr::pathname2uri(&self.uri)),
                 diagnostics: all_diagnostics,
                 version: Some(self.version),
             });
@@ -163,6 +297,131 @@ impl FileInfo {
         }
     }
 
+    /* Replace the give-ups of the evaluation of the file (function: None) or of the body of the function starting at the given offset */
+    pub fn replace_give_ups(&mut self, function: Option<TextSize>, give_ups: Vec<GiveUp>) {
+        if give_ups.is_empty() {
+            self.give_ups.remove(&function);
+        } else {
+            self.give_ups.insert(function, give_ups);
+        }
+    }
+
+    /* Give-ups of the evaluation of the expressions in the range. A value assigned to several targets is evaluated for each
+    of them, but its give-ups are only given once */
+    pub fn give_ups_in(&self, range: &TextRange) -> Vec<GiveUp> {
+        let mut res: Vec<GiveUp> = vec![];
+        for give_up in self.give_ups.values().flatten().filter(|give_up| range.contains_range(give_up.range)) {
+            if !res.iter().any(|known| known.range == give_up.range && known.reason == give_up.reason) {
+                res.push(give_up.clone());
+            }
+        }
+        res
+    }
+
+    /* Diagnostics of a step computed on the current version of the document */
+    pub fn step_diagnostics(&self, step: BuildSteps) -> Option<&Vec<Diagnostic>> {
+        self.diagnostics.get(&step).filter(|(version, _)| *version == self.version).map(|(_, diagnostics)| diagnostics)
+    }
+
+    /* Diagnostics computed on the current version of the document, as reported to the client: none if the file is excluded
+    by the settings of the project, and with the severities they override */
+    fn configured_diagnostics(&self, config: &Config) -> Vec<Diagnostic> {
+        let mut res = vec![];
+        if config.is_excluded(&self.uri) {
+            return res;
+        }
+        for (_, diagnostics) in self.diagnostics.values().filter(|(version, _)| *version == self.version) {
+            for d in diagnostics.iter() {
+                let mut d = d.clone();
+                if let Some(NumberOrString::String(code)) = &d.code {
+                    match config.diag_severities.get(code) {
+                        Some(None) => continue,
+                        Some(Some(severity)) => d.severity = Some(*severity),
+                        None => {}
+                    }
+                }
+                res.push(d);
+            }
+        }
+        res
+    }
+
+    /* Return the number of errors and warnings computed on the current version of the document, as reported to the client */
+    pub fn count_diagnostics(&self, config: &Config) -> (u32, u32) {
+        let mut errors = 0;
+        let mut warnings = 0;
+        for d in self.configured_diagnostics(config) {
+            match d.severity {
+                Some(DiagnosticSeverity::ERROR) => errors += 1,
+                Some(DiagnosticSeverity::WARNING) => warnings += 1,
+                _ => {}
+            }
+        }
+        (errors, warnings)
+    }
+
+    /* Return the '# type: X' comments of the file, indexed by the offset of the end of the code they follow on their line */
+    pub fn get_type_comments(&self) -> HashMap<u32, String> {
+        let mut res = HashMap::new();
+        let Some(rope) = self.text_rope.as_ref() else {
+            return res;
+        };
+        let mut offset = 0;
+        for line in rope.lines() {
+            let line = line.to_string();
+            if let Some(index) = line.find("# type:") {
+                let annotation = line[index + 7..].trim();
+                let code_end = line[..index].trim_end().len();
+                if code_end > 0 && !annotation.is_empty() && !annotation.starts_with("ignore") {
+                    res.insert((offset + code_end) as u32, annotation.to_string());
+                }
+            }
+            offset += line.len();
+        }
+        res
+    }
+
+    /* Return the content of the line, without its line ending */
+    pub fn get_line(&self, line: u32) -> Option<String> {
+        let line = self.text_rope.as_ref()?.get_line(line as usize)?;
+        Some(line.to_string().trim_end_matches(['\n', '\r']).to_string())
+    }
+
+    /* Return the whole text of the file */
+    pub fn get_content(&self) -> Option<String> {
+        self.text_rope.as_ref().map(|rope| rope.to_string())
+    }
+
+    /* Return the text of the given range of the file */
+    pub fn get_text(&self, range: &TextRange) -> Option<String> {
+        let rope = self.text_rope.as_ref()?;
+        let start = rope.try_byte_to_char(range.start().to_usize()).ok()?;
+        let end = rope.try_byte_to_char(range.end().to_usize()).ok()?;
+        Some(rope.get_slice(start..end)?.to_string())
+    }
+
+    fn get_suppressions(&self) -> DiagnosticSuppressions {
+        let mut res = DiagnosticSuppressions::default();
+        let Some(rope) = self.text_rope.as_ref() else {
+            return res;
+        };
+        for (index, line) in rope.lines().enumerate() {
+            let line = line.to_string();
+            let Some(comment_index) = line.find(SUPPRESSION_COMMENT) else {
+                continue;
+            };
+            let Some((file_scope, codes)) = DiagnosticSuppressions::parse_directive(&line[comment_index + SUPPRESSION_COMMENT.len()..]) else {
+                continue;
+            };
+            if file_scope {
+                res.file.extend(codes);
+            } else {
+                res.lines.entry(index as u32).or_default().extend(codes);
+            }
+        }
+        res
+    }
+
     pub fn offset_to_position_with_rope(rope: &Rope, offset: usize) -> Position {
         let char = rope.try_byte_to_char(offset).expect("unable to get char from bytes");
         let line = rope.try_char_to_line(char).ok().expect("unable to get line from char");
@@ -229,6 +488,21 @@ impl FileMgr {
         }
     }
 
+    /* Validation diagnostics of the python files of the given directories, with the content they have been computed on */
+    pub fn validation_diagnostics(&self, dirs: &[PathBuf]) -> HashMap<String, JournalFile> {
+        let mut res = HashMap::new();
+        for (path, file_info) in self.files.iter() {
+            if !path.ends_with(".py") || !dirs.iter().any(|dir| PathBuf::from(path).starts_with(dir)) {
+                continue;
+            }
+            let file_info = file_info.borrow();
+            if let (Some(content), Some(diagnostics)) = (file_info.get_content(), file_info.step_diagnostics(BuildSteps::VALIDATION)) {
+                res.insert(path.clone(), JournalFile::new(&content, diagnostics.clone()));
+            }
+        }
+        res
+    }
+
     pub fn text_range_to_range(&mut self, session: &mut SessionInfo, path: &String, range: &TextRange) -> Range {
         let file = self.files.get(path);
         if let Some(file) = file {
@@ -295,15 +569,46 @@ impl FileMgr {
     }
 
     pub fn add_workspace_folder(&mut self, path: String) {
-        let sanitized = PathBuf::from(path).sanitize();
+        let sanitized = PathBuf::from(path).sanitize_canonical();
         if !self.workspace_folder.contains(&sanitized) {
             self.workspace_folder.push(sanitized);
         }
     }
 
     pub fn remove_workspace_folder(&mut self, path: String) {
-        let index = self.workspace_folder.iter().position(|x| *x == path).unwrap();
-        self.workspace_folder.swap_remove(index);
+        let sanitized = PathBuf::from(path).sanitize_canonical();
+        if let Some(index) = self.workspace_folder.iter().position(|x| *x == sanitized) {
+            self.workspace_folder.swap_remove(index);
+        }
+    }
+
+    pub fn get_workspace_folders(&self) -> &Vec<String> {
+        &self.workspace_folder
+    }
+
+    /* Apply a new largeFileThreshold to the cached files, and return the paths of the ones that must be rebuilt, as they
+    crossed the threshold */
+    pub fn update_large_files(&self, threshold: u64) -> Vec<String> {
+        let mut res = vec![];
+        for file in self.files.values() {
+            let mut file = file.borrow_mut();
+            if file.update_large(threshold) {
+                res.push(file.uri.clone());
+            }
+        }
+        res
+    }
+
+    /* Publish again the diagnostics of the files of the workspace, to apply a change of the settings filtering them */
+    pub fn republish_diagnostics(&self, session: &mut SessionInfo) {
+        for file in self.files.values() {
+            let mut file = file.borrow_mut();
+            if !file.opened && !self.is_in_workspace(&file.uri) {
+                continue;
+            }
+            file.need_push = true;
+            file.publish_diagnostics(session);
+        }
     }
 
     pub fn is_in_workspace(&self, path: &str) -> bool {
@@ -316,11 +621,7 @@ impl FileMgr {
     }
 
     pub fn pathname2uri(s: &String) -> lsp_types::Uri {
-        let mut slash = "";
-        if cfg!(windows) {
-            slash = "/";
-        }
-        let url = lsp_types::Uri::from_str(&format!("file://{}{}", slash, s));
+        let url = lsp_types::Uri::from_str(&uri::path_to_uri(s));
         if let Ok(url) = url {
             return url;
         } else {
@@ -329,10 +630,8 @@ impl FileMgr {
     }
 
     pub fn uri2pathname(s: &str) -> String {
-        if let Ok(url) = url::Url::parse(s) {
-            if let Ok(url) = url.to_file_path() {
-                return url.sanitize();
-            }
+        if let Some(path) = uri::uri_to_path(s) {
+            return PathBuf::from(path).sanitize_canonical();
         }
         error!("Unable to extract path from uri: {s}");
         S!(s)
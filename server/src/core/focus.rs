@@ -0,0 +1,77 @@
+use std::collections::{HashMap, HashSet};
+
+use lsp_types::request::Request;
+use serde::{Deserialize, Serialize};
+
+use super::module_graph::ModuleGraph;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleFocusParams {
+    #[serde(default)]
+    pub modules: Vec<String>, //modules to add to the focus, or to remove if they are already in it. Empty to clear the focus
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleFocusResult {
+    pub modules: Vec<String>, //focused modules after the change. Empty if all the modules are validated
+}
+
+/* Restrict the validation to a few modules of the workspace and their dependencies. The files of the other modules are
+built up to the arch eval, so they can still be imported, hovered and completed, but are not validated */
+#[derive(Debug)]
+pub enum ToggleFocusRequest {}
+
+impl Request for ToggleFocusRequest {
+    type Params = ToggleFocusParams;
+    type Result = ToggleFocusResult;
+    const METHOD: &'static str = "Odoo/toggleFocus";
+}
+
+/* Modules focused by the user. Only them and their dependencies are validated, or everything if there is no focus */
+#[derive(Debug, Default)]
+pub struct Focus {
+    modules: Vec<String>,
+    closure: Option<HashSet<String>>, //None if it has to be computed again
+}
+
+impl Focus {
+
+    pub fn modules(&self) -> &Vec<String> {
+        &self.modules
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.modules.is_empty()
+    }
+
+    /* Add the modules that are not focused, remove the other ones. Clear the focus if no module is given */
+    pub fn toggle(&mut self, modules: &[String]) {
+        if modules.is_empty() {
+            self.modules.clear();
+        }
+        for module in modules.iter() {
+            match self.modules.iter().position(|focused| focused == module) {
+                Some(index) => { self.modules.remove(index); },
+                None => self.modules.push(module.clone()),
+            }
+        }
+        self.modules.sort();
+        self.closure = None;
+    }
+
+    /* To call when the dependencies of the modules change */
+    pub fn invalidate(&mut self) {
+        self.closure = None;
+    }
+
+    /* True if the module has to be validated. The depends are only used if the closure has to be computed again */
+    pub fn contains(&mut self, module: &str, depends: impl FnOnce() -> HashMap<String, Vec<String>>) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        let modules = &self.modules;
+        self.closure.get_or_insert_with(|| ModuleGraph::subgraph(&depends(), modules).into_keys().collect()).contains(module)
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::constants::Tree;
+
+use super::python_arch_builder_hooks::{PythonArchBuilderClassHook, PythonArchBuilderFileHook, PythonArchBuilderHooks};
+use super::python_arch_eval_hooks::{PythonArchEvalDecoratorHook, PythonArchEvalFileHook, PythonArchEvalFunctionHook, PythonArchEvalHooks};
+
+type HookSetRegistration = fn (registry: &mut HookRegistry);
+
+/* Sets of hooks that can be loaded. "odoo" is always loaded, others have to be listed in the hookSets configuration.
+A set can also enable validation rules, that check if it is loaded with is_loaded.
+The version of a set must be increased when its hooks change, as it is part of the cache fingerprint */
+static HOOK_SETS: &[(&str, u32, HookSetRegistration)] = &[
+    ("odoo", 2, HookRegistry::register_odoo_hooks),
+    ("oca_queue_job", 1, PythonArchEvalHooks::register_oca_queue_job_hooks),
+];
+
+/* Hooks run during the arch and arch eval steps to declare and evaluate the symbols that can't be inferred from the code (dynamic
+attributes, descriptors, decorators...). Hooks are indexed by the last name of their tree, to quickly filter them while building. */
+#[derive(Default)]
+pub struct HookRegistry {
+    class_hooks: HashMap<String, Vec<PythonArchBuilderClassHook>>,
+    arch_file_hooks: HashMap<String, Vec<PythonArchBuilderFileHook>>,
+    file_hooks: HashMap<String, Vec<PythonArchEvalFileHook>>,
+    function_hooks: HashMap<String, Vec<PythonArchEvalFunctionHook>>,
+    decorator_hooks: HashMap<String, Vec<PythonArchEvalDecoratorHook>>,
+    loaded_sets: Vec<String>,
+}
+
+impl fmt::Debug for HookRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HookRegistry")
+            .field("class_hooks", &self.class_hooks.values().map(|hooks| hooks.len()).sum::<usize>())
+            .field("arch_file_hooks", &self.arch_file_hooks.values().map(|hooks| hooks.len()).sum::<usize>())
+            .field("file_hooks", &self.file_hooks.values().map(|hooks| hooks.len()).sum::<usize>())
+            .field("function_hooks", &self.function_hooks.values().map(|hooks| hooks.len()).sum::<usize>())
+            .field("decorator_hooks", &self.decorator_hooks.values().map(|hooks| hooks.len()).sum::<usize>())
+            .field("loaded_sets", &self.loaded_sets)
+            .finish()
+    }
+}
+
+impl HookRegistry {
+
+    pub fn new() -> Self {
+        let mut registry = HookRegistry::default();
+        registry.load_set("odoo");
+        registry
+    }
+
+    /* Load a set of hooks by its name. Return false if the set doesn't exist */
+    pub fn load_set(&mut self, name: &str) -> bool {
+        if self.is_loaded(name) {
+            return true;
+        }
+        let Some((_, _, register)) = HOOK_SETS.iter().find(|(set_name, _, _)| *set_name == name) else {
+            return false;
+        };
+        register(self);
+        self.loaded_sets.push(name.to_string());
+        true
+    }
+
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.loaded_sets.iter().any(|set| set == name)
+    }
+
+    pub fn available_sets() -> Vec<&'static str> {
+        HOOK_SETS.iter().map(|(name, _, _)| *name).collect()
+    }
+
+    /* Name and version of the loaded sets */
+    pub fn loaded_sets_versions(&self) -> Vec<(String, u32)> {
+        self.loaded_sets.iter().filter_map(|name| HOOK_SETS.iter().find(|(set_name, _, _)| *set_name == name.as_str()))
+            .map(|(name, version, _)| (name.to_string(), *version)).collect()
+    }
+
+    /* The "odoo" set contains the hooks of both the arch and the arch eval steps */
+    fn register_odoo_hooks(registry: &mut HookRegistry) {
+        PythonArchBuilderHooks::register_odoo_hooks(registry);
+        PythonArchEvalHooks::register_odoo_hooks(registry);
+    }
+
+    pub fn register_class_hook(&mut self, hook: PythonArchBuilderClassHook) {
+        let key = hook.tree.1.last().cloned().unwrap_or_default();
+        self.class_hooks.entry(key).or_default().push(hook);
+    }
+
+    pub fn register_arch_file_hook(&mut self, hook: PythonArchBuilderFileHook) {
+        let key = hook.file_tree.last().cloned().unwrap_or_default();
+        self.arch_file_hooks.entry(key).or_default().push(hook);
+    }
+
+    pub fn register_file_hook(&mut self, hook: PythonArchEvalFileHook) {
+        let key = hook.file_tree.last().cloned().unwrap_or_default();
+        self.file_hooks.entry(key).or_default().push(hook);
+    }
+
+    pub fn register_function_hook(&mut self, hook: PythonArchEvalFunctionHook) {
+        let key = hook.tree.1.last().cloned().unwrap_or_default();
+        self.function_hooks.entry(key).or_default().push(hook);
+    }
+
+    pub fn register_decorator_hook(&mut self, hook: PythonArchEvalDecoratorHook) {
+        let key = HookRegistry::last_name(&hook.decorator_tree);
+        self.decorator_hooks.entry(key).or_default().push(hook);
+    }
+
+    pub fn get_class_hooks(&self, tree: &Tree) -> Vec<PythonArchBuilderClassHook> {
+        let Some(hooks) = tree.1.last().and_then(|name| self.class_hooks.get(name)) else {
+            return vec![];
+        };
+        hooks.iter().filter(|hook| hook.tree == *tree).cloned().collect()
+    }
+
+    pub fn get_arch_file_hooks(&self, file_tree: &Vec<String>) -> Vec<PythonArchBuilderFileHook> {
+        let Some(hooks) = file_tree.last().and_then(|name| self.arch_file_hooks.get(name)) else {
+            return vec![];
+        };
+        hooks.iter().filter(|hook| hook.file_tree == *file_tree).cloned().collect()
+    }
+
+    pub fn get_file_hooks(&self, file_tree: &Vec<String>) -> Vec<PythonArchEvalFileHook> {
+        let Some(hooks) = file_tree.last().and_then(|name| self.file_hooks.get(name)) else {
+            return vec![];
+        };
+        hooks.iter().filter(|hook| hook.file_tree == *file_tree).cloned().collect()
+    }
+
+    pub fn get_function_hooks(&self, tree: &Tree) -> Vec<PythonArchEvalFunctionHook> {
+        let Some(hooks) = tree.1.last().and_then(|name| self.function_hooks.get(name)) else {
+            return vec![];
+        };
+        hooks.iter().filter(|hook| hook.tree == *tree).cloned().collect()
+    }
+
+    pub fn get_decorator_hooks(&self, decorator_tree: &Tree) -> Vec<PythonArchEvalDecoratorHook> {
+        let Some(hooks) = self.decorator_hooks.get(&HookRegistry::last_name(decorator_tree)) else {
+            return vec![];
+        };
+        hooks.iter().filter(|hook| hook.decorator_tree == *decorator_tree).cloned().collect()
+    }
+
+    pub fn has_decorator_hooks(&self) -> bool {
+        !self.decorator_hooks.is_empty()
+    }
+
+    fn last_name(tree: &Tree) -> String {
+        tree.1.last().or(tree.0.last()).cloned().unwrap_or_default()
+    }
+}
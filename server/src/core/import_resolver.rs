@@ -4,7 +4,7 @@ use tracing::error;
 use std::collections::HashSet;
 use std::rc::Rc;
 use std::cell::RefCell;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use ruff_text_size::TextRange;
 use ruff_python_ast::{Alias, Identifier};
@@ -101,7 +101,7 @@ pub fn resolve_import_stmt(session: &mut SessionInfo, source_file_symbol: &Rc<Re
             if name_symbol.is_none() {
                 if !name.contains(".") {
                     //TODO WTF?
-                    let name_symbol_vec = from_symbol.as_ref().unwrap().borrow_mut().get_symbol(&(vec![], vec![name.clone()]), u32::MAX);
+                    let name_symbol_vec = from_symbol.as_ref().unwrap().borrow().get_symbol(&(vec![], vec![name.clone()]), u32::MAX);
                     //TODO what if multiple values?
                     name_symbol = name_symbol_vec.get(0).cloned();
                 }
@@ -137,7 +137,7 @@ pub fn resolve_import_stmt(session: &mut SessionInfo, source_file_symbol: &Rc<Re
             None);
         if name_symbol.is_none() { //If not a file/package, try to look up in symbols in current file (second parameter of get_symbol)
             //TODO what if multiple values?
-            name_symbol = next_symbol.as_ref().unwrap().borrow_mut().get_symbol(&(vec![], name_last_name), u32::MAX).get(0).cloned();
+            name_symbol = next_symbol.as_ref().unwrap().borrow().get_symbol(&(vec![], name_last_name), u32::MAX).get(0).cloned();
             if name_symbol.is_none() {
                 result[name_index as usize].symbol = fallback_sym.clone();
                 continue;
@@ -153,26 +153,29 @@ pub fn resolve_import_stmt(session: &mut SessionInfo, source_file_symbol: &Rc<Re
     return result;
 }
 
+/* Find a module in the addons paths. Paths are checked in order, and a directory containing a manifest is preferred to a
+plain python package of the same name that would be in a previous path, so the resolution does not depend on the layout
+of the addons paths */
 pub fn find_module(session: &mut SessionInfo, odoo_addons: Rc<RefCell<Symbol>>, name: &String) -> Option<Rc<RefCell<Symbol>>> {
     let paths = (*odoo_addons).borrow().paths().clone();
-    for path in paths.iter() {
-        let full_path = Path::new(path.as_str()).join(name);
-        if is_dir_cs(full_path.sanitize()) {
-            let _arc_symbol = Symbol::create_from_path(session, &full_path, odoo_addons.clone(), false);
-            if _arc_symbol.is_some() {
-                let typ = _arc_symbol.as_ref().unwrap().borrow().typ();
-                match typ {
-                    SymType::NAMESPACE => {
-                        return Some(_arc_symbol.as_ref().unwrap().clone());
-                    },
-                    SymType::PACKAGE => {
-                        let _arc_symbol = _arc_symbol.as_ref().unwrap().clone();
-                        session.sync_odoo.modules.insert(name.clone(), Rc::downgrade(&_arc_symbol));
-                        session.sync_odoo.add_to_rebuild_arch(_arc_symbol.clone());
-                        return Some(_arc_symbol);
-                    },
-                    _ => {return None}
-                }
+    let mut candidates: Vec<PathBuf> = paths.iter().map(|path| Path::new(path.as_str()).join(name)).filter(|path| is_dir_cs(path.sanitize())).collect();
+    candidates.sort_by_key(|path| !path.join("__manifest__.py").exists()); //stable: keep the order of the addons paths
+    for full_path in candidates.iter() {
+        let _arc_symbol = Symbol::create_from_path(session, full_path, odoo_addons.clone(), false);
+        if _arc_symbol.is_some() {
+            let typ = _arc_symbol.as_ref().unwrap().borrow().typ();
+            match typ {
+                SymType::NAMESPACE => {
+                    return Some(_arc_symbol.as_ref().unwrap().clone());
+                },
+                SymType::PACKAGE => {
+                    let _arc_symbol = _arc_symbol.as_ref().unwrap().clone();
+                    session.sync_odoo.modules.insert(name.clone(), Rc::downgrade(&_arc_symbol));
+                    session.sync_odoo.invalidate_module_ranks();
+                    session.sync_odoo.add_to_rebuild_arch(_arc_symbol.clone());
+                    return Some(_arc_symbol);
+                },
+                _ => {return None}
             }
         }
     }
@@ -217,7 +220,7 @@ fn _get_or_create_symbol(session: &mut SessionInfo, symbol: Rc<RefCell<Symbol>>,
     let mut sym: Option<Rc<RefCell<Symbol>>> = Some(symbol.clone());
     let mut last_symbol = symbol.clone();
     for branch in names.iter() {
-        let mut next_symbol = sym.as_ref().unwrap().borrow_mut().get_symbol(&(vec![branch.clone()], vec![]), u32::MAX);
+        let mut next_symbol = sym.as_ref().unwrap().borrow().get_symbol(&(vec![branch.clone()], vec![]), u32::MAX);
         if next_symbol.is_empty() {
             next_symbol = match _resolve_new_symbol(session, sym.as_ref().unwrap().clone(), &branch, asname.clone()) {
                 Ok(v) => vec![v],
@@ -242,7 +245,11 @@ fn _resolve_new_symbol(session: &mut SessionInfo, parent: Rc<RefCell<Symbol>>, n
     if (*parent).borrow().typ() == SymType::COMPILED {
         return Ok((*parent).borrow_mut().add_new_compiled(session, &sym_name, &S!("")));
     }
-    let paths = (*parent).borrow().paths().clone();
+    let mut paths = (*parent).borrow().paths().clone();
+    if (*parent).borrow().get_tree() == tree(vec!["odoo", "addons"], vec![]) {
+        //a module hides python packages of the same name in other addons paths, whatever the order of the paths
+        paths.sort_by_key(|path| !Path::new(path.as_str()).join(name).join("__manifest__.py").exists());
+    }
     for path in paths.iter() {
         let mut full_path = Path::new(path.as_str()).join(name);
         for stub in session.sync_odoo.stubs_dirs.iter() {
@@ -327,7 +334,7 @@ pub fn get_all_valid_names(session: &mut SessionInfo, source_file_symbol: &Rc<Re
     }
     for (index, branch) in names.iter().enumerate() {
         if index != names.len() -1 {
-            let mut next_symbol = sym.as_ref().unwrap().borrow_mut().get_symbol(&(vec![branch.clone()], vec![]), u32::MAX);
+            let mut next_symbol = sym.as_ref().unwrap().borrow().get_symbol(&(vec![branch.clone()], vec![]), u32::MAX);
             if next_symbol.is_empty() {
                 next_symbol = match _resolve_new_symbol(session, sym.as_ref().unwrap().clone(), &branch, None) {
                     Ok(v) => vec![v],
@@ -344,7 +351,7 @@ pub fn get_all_valid_names(session: &mut SessionInfo, source_file_symbol: &Rc<Re
     if let Some(sym) = sym {
         let filter = names.last().unwrap();
         for symbol in sym.borrow().all_symbols() {
-            if symbol.borrow().name().starts_with(filter) {
+            if symbol.borrow().name().starts_with(filter) && !symbol.borrow().is_comprehension() {
                 result.insert(symbol.borrow().name().clone());
             }
         }
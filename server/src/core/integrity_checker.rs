@@ -0,0 +1,266 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use lsp_types::request::Request;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::SymType;
+use crate::core::symbols::package_symbol::PackageSymbol;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckIntegrityResult {
+    pub checked_symbols: u32,
+    pub violations: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum CheckIntegrityRequest {}
+
+impl Request for CheckIntegrityRequest {
+    type Params = ();
+    type Result = CheckIntegrityResult;
+    const METHOD: &'static str = "Odoo/checkIntegrity";
+}
+
+/* Walk the whole symbol graph from the root and report every broken invariant:
+- each symbol is reachable from a single container (no duplicated strong references)
+- weak_self points to the symbol itself
+- parent points to the container holding the symbol
+- dependencies and dependents are registered on both sides
+- dependencies point to symbols that are still in the tree
+- classes and variables are owned by the module they are declared in
+- the symbols referenced by the tree or by the indexes of SyncOdoo are still in the tree. A symbol out of the tree that is
+still alive is leaked, usually because it belongs to a cycle of strong references (containers and extension symbols) */
+pub struct IntegrityChecker {
+    visited: HashSet<*const RefCell<Symbol>>,
+    dependency_targets: Vec<(String, Rc<RefCell<Symbol>>)>,
+    referenced_targets: Vec<(String, Rc<RefCell<Symbol>>)>, //alive symbols referenced by the tree or by SyncOdoo, with the referencing path
+    ext_references: Vec<(Rc<RefCell<Symbol>>, Rc<RefCell<Symbol>>)>, //symbols holding an extension symbol, with the extension symbol
+    checked_symbols: u32,
+    violations: Vec<String>,
+}
+
+impl IntegrityChecker {
+
+    pub fn check(session: &mut SessionInfo) -> CheckIntegrityResult {
+        let mut checker = IntegrityChecker {
+            visited: HashSet::new(),
+            dependency_targets: vec![],
+            referenced_targets: vec![],
+            ext_references: vec![],
+            checked_symbols: 0,
+            violations: vec![],
+        };
+        if let Some(root) = session.sync_odoo.symbols.clone() {
+            checker.check_symbol(&root, None, String::from("Root"));
+        }
+        for (from, target) in checker.dependency_targets.drain(..) {
+            if !checker.visited.contains(&Rc::as_ptr(&target)) {
+                checker.violations.push(format!("{}: depends on {} which is not in the symbol tree anymore", from, target.borrow().name()));
+            }
+        }
+        for (name, module) in session.sync_odoo.modules.iter() {
+            if let Some(module) = module.upgrade() {
+                checker.referenced_targets.push((format!("module {}", name), module));
+            }
+        }
+        for (name, model) in session.sync_odoo.models.iter() {
+            for symbol in model.borrow().get_all_symbols() {
+                checker.referenced_targets.push((format!("model {}", name), symbol));
+            }
+        }
+        for symbol in session.sync_odoo.not_found_symbols.iter() {
+            checker.referenced_targets.push((String::from("not found symbols"), symbol));
+        }
+        checker.check_leaks();
+        CheckIntegrityResult {
+            checked_symbols: checker.checked_symbols,
+            violations: checker.violations,
+        }
+    }
+
+    fn check_symbol(&mut self, symbol: &Rc<RefCell<Symbol>>, container: Option<&Rc<RefCell<Symbol>>>, path: String) {
+        if !self.visited.insert(Rc::as_ptr(symbol)) {
+            self.violations.push(format!("{}: symbol is reachable from more than one container", path));
+            return;
+        }
+        self.checked_symbols += 1;
+        let sym = symbol.borrow();
+        match sym.weak_self() {
+            None => self.violations.push(format!("{}: weak_self is not set", path)),
+            Some(weak_self) => match weak_self.upgrade() {
+                None => self.violations.push(format!("{}: weak_self is expired", path)),
+                Some(rc) => if !Rc::ptr_eq(&rc, symbol) {
+                    self.violations.push(format!("{}: weak_self does not point to the symbol itself", path));
+                }
+            }
+        }
+        if let Some(container) = container {
+            match sym.parent() {
+                None => self.violations.push(format!("{}: parent is not set", path)),
+                Some(parent) => match parent.upgrade() {
+                    None => self.violations.push(format!("{}: parent is expired", path)),
+                    Some(parent) => if !Rc::ptr_eq(&parent, container) {
+                        self.violations.push(format!("{}: parent is {} but the symbol is stored in {}", path, parent.borrow().name(), container.borrow().name()));
+                    }
+                }
+            }
+        }
+        if [SymType::NAMESPACE, SymType::PACKAGE, SymType::FILE].contains(&sym.typ()) {
+            self.check_dependencies(symbol, &sym, &path);
+        }
+        if [SymType::CLASS, SymType::VARIABLE].contains(&sym.typ()) {
+            self.check_owning_module(&sym, &path);
+        }
+        if let Symbol::Class(class) = &*sym {
+            for base in class.bases.iter() {
+                self.referenced_targets.push((format!("{} (base class)", path), base));
+            }
+        }
+        for ext_symbol in IntegrityChecker::ext_symbols(&sym) {
+            self.referenced_targets.push((format!("{} (extension symbol)", path), ext_symbol.clone()));
+            self.ext_references.push((symbol.clone(), ext_symbol));
+        }
+        let children = IntegrityChecker::children(&sym);
+        drop(sym);
+        for child in children.iter() {
+            let child_path = format!("{}.{}", path, child.borrow().name());
+            self.check_symbol(child, Some(symbol), child_path);
+        }
+    }
+
+    /* Symbols stored in the containers of the symbol, that hold them by a strong reference */
+    fn children(sym: &Symbol) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut children: Vec<Rc<RefCell<Symbol>>> = vec![];
+        match sym {
+            Symbol::Root(_) | Symbol::Namespace(_) => {
+                children.extend(sym.all_module_symbol().cloned());
+            },
+            Symbol::Package(_) => {
+                children.extend(sym.all_module_symbol().cloned());
+                children.extend(sym.iter_symbols().flat_map(|(_, sections)| sections.values().flatten().cloned()));
+            },
+            Symbol::File(_) | Symbol::Class(_) | Symbol::Function(_) => {
+                children.extend(sym.iter_symbols().flat_map(|(_, sections)| sections.values().flatten().cloned()));
+            },
+            Symbol::Compiled(c) => {
+                children.extend(c.module_symbols.values().cloned());
+            },
+            Symbol::Variable(_) => {}
+        }
+        children
+    }
+
+    /* Symbols of other containers added to the symbol, also held by a strong reference */
+    fn ext_symbols(sym: &Symbol) -> Vec<Rc<RefCell<Symbol>>> {
+        let ext_symbols = match sym {
+            Symbol::File(f) => &f.ext_symbols,
+            Symbol::Package(PackageSymbol::Module(m)) => &m.ext_symbols,
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => &p.ext_symbols,
+            Symbol::Class(c) => &c.ext_symbols,
+            Symbol::Function(f) => &f.ext_symbols,
+            Symbol::Root(_) | Symbol::Namespace(_) | Symbol::Compiled(_) | Symbol::Variable(_) => return vec![],
+        };
+        ext_symbols.values().flatten().cloned().collect()
+    }
+
+    /* Report the referenced symbols that are alive out of the tree, and the cycles of strong references. A cycle is closed
+    by an extension symbol holding one of its containers: once the cycle is out of the tree, nothing can free it */
+    fn check_leaks(&mut self) {
+        let mut reported: HashSet<*const RefCell<Symbol>> = HashSet::new();
+        for (from, target) in std::mem::take(&mut self.referenced_targets) {
+            if self.visited.contains(&Rc::as_ptr(&target)) || !reported.insert(Rc::as_ptr(&target)) {
+                continue;
+            }
+            self.violations.push(format!("{}: references {} which is not in the symbol tree anymore but is still alive", from, target.borrow().name()));
+            if let Some(cycle) = IntegrityChecker::find_strong_cycle(&target, &target, &mut HashSet::new()) {
+                self.violations.push(format!("{}: leaked in a cycle of strong references: {}", target.borrow().name(), IntegrityChecker::cycle_names(&cycle)));
+            }
+        }
+        for (holder, ext_symbol) in std::mem::take(&mut self.ext_references) {
+            if let Some(path) = IntegrityChecker::find_strong_cycle(&ext_symbol, &holder, &mut HashSet::new()) {
+                let cycle: Vec<Rc<RefCell<Symbol>>> = std::iter::once(holder.clone()).chain(path).collect();
+                self.violations.push(format!("{}: cycle of strong references: {}", holder.borrow().name(), IntegrityChecker::cycle_names(&cycle)));
+            }
+        }
+    }
+
+    /* The path of strong references from symbol to target, target included, if any */
+    fn find_strong_cycle(symbol: &Rc<RefCell<Symbol>>, target: &Rc<RefCell<Symbol>>, seen: &mut HashSet<*const RefCell<Symbol>>) -> Option<Vec<Rc<RefCell<Symbol>>>> {
+        if !seen.insert(Rc::as_ptr(symbol)) {
+            return None;
+        }
+        let (children, ext_symbols) = {
+            let sym = symbol.borrow();
+            (IntegrityChecker::children(&sym), IntegrityChecker::ext_symbols(&sym))
+        };
+        for next in children.iter().chain(ext_symbols.iter()) {
+            if Rc::ptr_eq(next, target) {
+                return Some(vec![symbol.clone(), next.clone()]);
+            }
+            if let Some(mut path) = IntegrityChecker::find_strong_cycle(next, target, seen) {
+                path.insert(0, symbol.clone());
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    fn cycle_names(cycle: &[Rc<RefCell<Symbol>>]) -> String {
+        cycle.iter().map(|symbol| symbol.borrow().name().clone()).collect::<Vec<String>>().join(" -> ")
+    }
+
+    /* The owning module of a class or a variable must be the module found in its parents (see Symbol::owning_module) */
+    fn check_owning_module(&mut self, sym: &Symbol, path: &String) {
+        let owning_module = sym.owning_module();
+        let declaring_module = sym.find_module();
+        let same = match (&owning_module, &declaring_module) {
+            (Some(owning_module), Some(declaring_module)) => Rc::ptr_eq(owning_module, declaring_module),
+            (None, None) => true,
+            _ => false
+        };
+        if !same {
+            let module_name = |module: &Option<Rc<RefCell<Symbol>>>| module.as_ref().map(|module| module.borrow().name().clone()).unwrap_or(String::from("no module"));
+            self.violations.push(format!("{}: owned by {} but declared in {}", path, module_name(&owning_module), module_name(&declaring_module)));
+        }
+    }
+
+    /* dependencies[step][level] and dependents[level][step] must mirror each other (see Symbol::add_dependency) */
+    fn check_dependencies(&mut self, symbol: &Rc<RefCell<Symbol>>, sym: &Symbol, path: &String) {
+        for (step, levels) in sym.dependencies().iter().enumerate() {
+            for (level, deps) in levels.iter().enumerate() {
+                for dep in deps.iter() {
+                    let registered = match dep.borrow().typ() {
+                        SymType::NAMESPACE | SymType::PACKAGE | SymType::FILE => {
+                            dep.borrow().dependents().get(level).and_then(|steps| steps.get(step)).map_or(false, |set| set.contains(symbol))
+                        },
+                        _ => false
+                    };
+                    if !registered {
+                        self.violations.push(format!("{}: depends on {} (step {}, level {}) but is not in its dependents", path, dep.borrow().name(), step, level));
+                    }
+                    self.dependency_targets.push((path.clone(), dep));
+                }
+            }
+        }
+        for (level, steps) in sym.dependents().iter().enumerate() {
+            for (step, dependents) in steps.iter().enumerate() {
+                for dependent in dependents.iter() {
+                    let registered = match dependent.borrow().typ() {
+                        SymType::NAMESPACE | SymType::PACKAGE | SymType::FILE => {
+                            dependent.borrow().dependencies().get(step).and_then(|levels| levels.get(level)).map_or(false, |set| set.contains(symbol))
+                        },
+                        _ => false
+                    };
+                    if !registered {
+                        self.violations.push(format!("{}: {} is registered as dependent (level {}, step {}) but does not depend on it", path, dependent.borrow().name(), level, step));
+                    }
+                }
+            }
+        }
+    }
+}
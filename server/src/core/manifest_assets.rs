@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use glob::{glob, Pattern};
+use ruff_python_ast::Expr;
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::utils::PathSanitizer as _;
+use crate::S;
+
+/* Extensions of the files that can be part of an asset bundle */
+pub static ASSET_EXTENSIONS: &[&str] = &["js", "scss", "css", "xml"];
+
+/* A path of an asset bundle, as written in the 'assets' key of a manifest */
+#[derive(Debug, Clone)]
+pub struct AssetPath {
+    pub path: String,
+    pub range: TextRange,
+    pub removed: bool, //from a ('remove', path) directive
+}
+
+impl AssetPath {
+
+    /* Paths of an entry of a bundle: a path, or a directive like ('include', bundle), ('after', target, path), ('replace', old, new)...
+    Bundles included with 'include' are not paths and are ignored */
+    pub fn from_entry(entry: &Expr) -> Vec<AssetPath> {
+        match entry {
+            Expr::StringLiteral(s) => vec![AssetPath { path: S!(s.value.to_str()), range: s.range, removed: false }],
+            Expr::Tuple(tuple) => {
+                let Some(Expr::StringLiteral(directive)) = tuple.elts.first() else {
+                    return vec![];
+                };
+                let paths: Vec<(String, TextRange)> = tuple.elts.iter().skip(1).filter_map(|e| match e {
+                    Expr::StringLiteral(s) => Some((S!(s.value.to_str()), s.range())),
+                    _ => None
+                }).collect();
+                match (directive.value.to_str(), paths.as_slice()) {
+                    ("remove", [(path, range)]) => vec![AssetPath { path: path.clone(), range: *range, removed: true }],
+                    ("prepend" | "append", [(path, range)]) |
+                    ("after" | "before", [_, (path, range)]) => vec![AssetPath { path: path.clone(), range: *range, removed: false }],
+                    ("replace", [(old, old_range), (new, new_range)]) => vec![
+                        AssetPath { path: old.clone(), range: *old_range, removed: true },
+                        AssetPath { path: new.clone(), range: *new_range, removed: false },
+                    ],
+                    _ => vec![]
+                }
+            },
+            _ => vec![]
+        }
+    }
+
+    pub fn is_glob(&self) -> bool {
+        self.path.contains(['*', '?', '['])
+    }
+
+    /* Urls are loaded by the browser and can't be checked */
+    pub fn is_url(&self) -> bool {
+        self.path.starts_with("http://") || self.path.starts_with("https://") || self.path.starts_with("//")
+    }
+
+    /* Name of the module the path starts with */
+    pub fn module_name(&self) -> Option<&str> {
+        self.path.trim_start_matches('/').split('/').next().filter(|name| !name.is_empty())
+    }
+
+    /* Resolve the path to the files it targets, given the directory of the module it starts with */
+    pub fn resolve(&self, module_dir: &str) -> Vec<PathBuf> {
+        let relative = self.path.trim_start_matches('/').splitn(2, '/').nth(1).unwrap_or("");
+        let full_path = PathBuf::from(module_dir).join(relative);
+        if !self.is_glob() {
+            return if full_path.is_file() { vec![full_path] } else { vec![] };
+        }
+        let Some(pattern) = full_path.to_str() else {
+            return vec![];
+        };
+        match glob(pattern) {
+            Ok(paths) => paths.filter_map(|p| p.ok()).filter(|p| p.is_file()).collect(),
+            Err(_) => vec![]
+        }
+    }
+
+    /* true if the file, given relatively to the addons directory (like 'web/static/src/app.js'), is targeted by this path */
+    pub fn matches(&self, relative_file: &str) -> bool {
+        let path = self.path.trim_start_matches('/');
+        if !self.is_glob() {
+            return path == relative_file;
+        }
+        Pattern::new(path).is_ok_and(|pattern| pattern.matches(relative_file))
+    }
+}
+
+/* Files of static/src of a module that could be part of a bundle, relatively to the addons directory */
+pub fn list_static_files(module_dir: &str, module_name: &str) -> Vec<String> {
+    let mut res = vec![];
+    let static_dir = PathBuf::from(module_dir).join("static").join("src");
+    collect_files(&static_dir, &mut res, &mut HashSet::new());
+    res.iter().filter_map(|file| {
+        let relative = file.strip_prefix(module_dir).ok()?;
+        Some(format!("{}/{}", module_name, relative.to_str()?.replace('\\', "/")))
+    }).collect()
+}
+
+/* Collect the asset files of a directory and its subdirectories. Symlinks are followed, and each physical directory
+is visited once, so a symlink loop can't recurse forever */
+fn collect_files(dir: &Path, res: &mut Vec<PathBuf>, visited: &mut HashSet<String>) {
+    if !visited.insert(dir.sanitize_canonical()) {
+        return;
+    }
+    let Ok(entries) = dir.read_dir() else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, res, visited);
+        } else if path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ASSET_EXTENSIONS.contains(&ext)) {
+            res.push(path);
+        }
+    }
+}
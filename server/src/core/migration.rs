@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ruff_python_ast::{Expr, Stmt, StmtClassDef};
+use ruff_text_size::{Ranged, TextRange};
+use serde::Serialize;
+
+use crate::features::ast_utils::AstUtils;
+use crate::utils::PathSanitizer as _;
+
+use super::deprecations::{DeprecatedApi, Deprecation, DEPRECATIONS};
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationOccurrence {
+    pub path: String,
+    pub line: u32,
+}
+
+/* All the occurrences of a deprecated API in a module */
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationRuleIssues {
+    pub rule: String,
+    pub message: String,
+    pub removed: bool, //the API does not exist anymore in the target version, the module will crash instead of logging a warning
+    pub occurrences: Vec<MigrationOccurrence>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationModuleReport {
+    pub module: String,
+    pub path: String,
+    pub issues: Vec<MigrationRuleIssues>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    pub modules: Vec<MigrationModuleReport>, //only the modules with at least one issue
+}
+
+impl MigrationReport {
+
+    pub fn to_markdown(&self) -> String {
+        let mut res = format!("# Migration report from {}.0 to {}.0\n\n", self.from, self.to);
+        if self.modules.is_empty() {
+            res += "No deprecated API found.\n";
+            return res;
+        }
+        for module in self.modules.iter() {
+            res += &format!("## {}\n\n", module.module);
+            for issue in module.issues.iter() {
+                let status = if issue.removed { "removed" } else { "deprecated" };
+                res += &format!("### {} ({}, {} occurrences)\n\n{}\n\n", issue.rule, status, issue.occurrences.len(), issue.message);
+                for occurrence in issue.occurrences.iter() {
+                    res += &format!("- {}:{}\n", occurrence.path, occurrence.line);
+                }
+                res += "\n";
+            }
+        }
+        res
+    }
+}
+
+/* Scan the python files of the modules for the APIs deprecated or removed between two major versions of Odoo.
+The scan only reads the files, so it does not need the sources of Odoo, and can report issues for a version that is not installed */
+pub struct MigrationScan {
+    from: u32,
+    to: u32,
+    rules: Vec<&'static Deprecation>,
+}
+
+impl MigrationScan {
+
+    pub fn new(from: u32, to: u32) -> Self {
+        //the APIs deprecated in the target version, that were still available in the version the modules are migrated from
+        let rules = DEPRECATIONS.iter().filter(|deprecation| {
+            deprecation.deprecated_in <= to && !deprecation.removed_in.is_some_and(|removed_in| removed_in <= from)
+        }).collect();
+        Self { from, to, rules }
+    }
+
+    /* Scan all the modules of the given addons paths. An addons path can also be the directory of a single module */
+    pub fn run(&self, addons_paths: &Vec<String>) -> MigrationReport {
+        let mut module_dirs = vec![];
+        for addons_path in addons_paths.iter() {
+            let addons_path = PathBuf::from(addons_path);
+            if addons_path.join("__manifest__.py").exists() {
+                module_dirs.push(addons_path);
+                continue;
+            }
+            let Ok(entries) = fs::read_dir(&addons_path) else {
+                continue;
+            };
+            module_dirs.extend(entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.join("__manifest__.py").exists()));
+        }
+        module_dirs.sort();
+        let modules = module_dirs.iter().map(|module_dir| self.scan_module(module_dir)).filter(|module| !module.issues.is_empty()).collect();
+        MigrationReport { from: self.from, to: self.to, modules }
+    }
+
+    fn scan_module(&self, module_dir: &Path) -> MigrationModuleReport {
+        let mut files = vec![];
+        collect_python_files(module_dir, &mut files, &mut HashSet::new());
+        files.sort();
+        let mut issues: BTreeMap<String, MigrationRuleIssues> = BTreeMap::new();
+        for file in files.iter() {
+            let Ok(content) = fs::read_to_string(file) else {
+                continue;
+            };
+            let Ok(parsed) = ruff_python_parser::parse_module(&content) else {
+                continue; //syntax errors are reported by the server, not by the migration scan
+            };
+            let rope = ropey::Rope::from_str(&content);
+            let path = file.sanitize();
+            for (deprecation, range) in self.scan_stmts(&parsed.into_syntax().body) {
+                let line = rope.try_byte_to_line(range.start().to_usize()).unwrap_or(0) as u32 + 1;
+                issues.entry(deprecation.name()).or_insert_with(|| MigrationRuleIssues {
+                    rule: deprecation.name(),
+                    message: deprecation.message(self.to),
+                    removed: deprecation.is_removed(self.to),
+                    occurrences: vec![],
+                }).occurrences.push(MigrationOccurrence { path: path.clone(), line });
+            }
+        }
+        MigrationModuleReport {
+            module: module_dir.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string(),
+            path: module_dir.sanitize(),
+            issues: issues.into_values().collect(),
+        }
+    }
+
+    /* Return the deprecated APIs used by the statements of a file, with the range of their usage */
+    fn scan_stmts(&self, stmts: &Vec<Stmt>) -> Vec<(&'static Deprecation, TextRange)> {
+        let mut res = vec![];
+        let mut aliases: HashMap<String, Vec<String>> = HashMap::new(); //local name => full path of the imported symbol
+        let mut model_ranges = vec![]; //in the classes of models, self is a recordset
+        for stmt in stmts.iter() {
+            match stmt {
+                Stmt::Import(import) => {
+                    for alias in import.names.iter() {
+                        let path: Vec<String> = alias.name.as_str().split('.').map(|part| part.to_string()).collect();
+                        self.check_symbol(&path, alias.range, &mut res);
+                        match alias.asname.as_ref() {
+                            Some(asname) => { aliases.insert(asname.to_string(), path); },
+                            None => { aliases.insert(path[0].clone(), vec![path[0].clone()]); }
+                        }
+                    }
+                },
+                Stmt::ImportFrom(import_from) if import_from.level == 0 => {
+                    let Some(module) = import_from.module.as_ref() else {
+                        continue;
+                    };
+                    for alias in import_from.names.iter() {
+                        let mut path: Vec<String> = module.as_str().split('.').map(|part| part.to_string()).collect();
+                        path.push(alias.name.to_string());
+                        self.check_symbol(&path, alias.range, &mut res);
+                        aliases.insert(alias.asname.as_ref().unwrap_or(&alias.name).to_string(), path);
+                    }
+                },
+                Stmt::ClassDef(class_def) => {
+                    if self.scan_class(class_def, &aliases, &mut res) {
+                        model_ranges.push(class_def.range);
+                    }
+                },
+                _ => {}
+            }
+        }
+        for call in AstUtils::collect_calls(stmts) {
+            if let Some(path) = resolve_dotted_expr(&call.func, &aliases) {
+                self.check_symbol(&path, call.func.range(), &mut res);
+            }
+            if let Expr::Attribute(attr) = call.func.as_ref() {
+                let self_is_records = model_ranges.iter().any(|range: &TextRange| range.contains_range(call.range));
+                if is_records_expr(&attr.value, self_is_records) {
+                    self.check_model_method(attr.attr.as_str(), attr.attr.range, &mut res);
+                }
+            }
+        }
+        res.sort_by_key(|(_, range)| range.start());
+        res
+    }
+
+    /* Check the bases, the decorators and the overridden methods of a class. Return true if the class is a model */
+    fn scan_class(&self, class_def: &StmtClassDef, aliases: &HashMap<String, Vec<String>>, res: &mut Vec<(&'static Deprecation, TextRange)>) -> bool {
+        let bases: Vec<Vec<String>> = class_def.bases().iter().filter_map(|base| resolve_dotted_expr(base, aliases)).collect();
+        for (base, path) in class_def.bases().iter().zip(bases.iter()) {
+            self.check_symbol(path, base.range(), res);
+        }
+        let is_model = bases.iter().any(|path| path.len() == 3 && path[0] == "odoo" && path[1] == "models")
+            || class_def.body.iter().any(|stmt| match stmt {
+                Stmt::Assign(assign) => assign.targets.iter().any(|target| target.as_name_expr().is_some_and(|name| name.id.as_str() == "_name" || name.id.as_str() == "_inherit")),
+                _ => false
+            });
+        for stmt in class_def.body.iter() {
+            let Stmt::FunctionDef(func) = stmt else {
+                continue;
+            };
+            if is_model {
+                self.check_model_method(func.name.as_str(), func.name.range, res);
+            }
+            for decorator in func.decorator_list.iter() {
+                let expr = match &decorator.expression {
+                    Expr::Call(call) => call.func.as_ref(),
+                    expr => expr,
+                };
+                if let Some(path) = resolve_dotted_expr(expr, aliases) {
+                    self.check_symbol(&path, decorator.range, res);
+                }
+            }
+        }
+        is_model
+    }
+
+    fn check_symbol(&self, path: &Vec<String>, range: TextRange, res: &mut Vec<(&'static Deprecation, TextRange)>) {
+        for rule in self.rules.iter() {
+            if matches!(rule.api, DeprecatedApi::Symbol(symbol_path) if symbol_path.iter().eq(path.iter())) {
+                res.push((rule, range));
+            }
+        }
+    }
+
+    fn check_model_method(&self, name: &str, range: TextRange, res: &mut Vec<(&'static Deprecation, TextRange)>) {
+        for rule in self.rules.iter() {
+            if matches!(rule.api, DeprecatedApi::ModelMethod(method) if method == name) {
+                res.push((rule, range));
+            }
+        }
+    }
+}
+
+/* Return the full path of a dotted expression like api.multi, by replacing its first name with the imported symbol */
+fn resolve_dotted_expr(expr: &Expr, aliases: &HashMap<String, Vec<String>>) -> Option<Vec<String>> {
+    match expr {
+        Expr::Name(name) => aliases.get(name.id.as_str()).cloned(),
+        Expr::Attribute(attr) => {
+            let mut path = resolve_dotted_expr(&attr.value, aliases)?;
+            path.push(attr.attr.to_string());
+            Some(path)
+        },
+        _ => None
+    }
+}
+
+/* Recordset methods returning a recordset of the same model */
+static RECORDSET_METHODS: &[&str] = &[
+    "browse", "search", "sudo", "with_context", "with_user", "with_company", "with_env", "filtered", "filtered_domain", "sorted",
+    "exists", "create", "new", "copy",
+];
+
+/* True if the expression is a recordset, like self in a model, self.env['res.partner'] or self.partner_id.sudo(). Without the
+symbols of Odoo, other receivers are unknown and their methods are not reported */
+fn is_records_expr(expr: &Expr, self_is_records: bool) -> bool {
+    match expr {
+        Expr::Name(name) => self_is_records && name.id.as_str() == "self",
+        Expr::Subscript(subscript) => subscript.value.as_attribute_expr().is_some_and(|attr| attr.attr.as_str() == "env"),
+        Expr::Attribute(attr) => attr.attr.as_str() != "env" && is_records_expr(&attr.value, self_is_records),
+        Expr::Call(call) => match call.func.as_ref() {
+            Expr::Name(name) => self_is_records && name.id.as_str() == "super",
+            Expr::Attribute(attr) => RECORDSET_METHODS.contains(&attr.attr.as_str()) && is_records_expr(&attr.value, self_is_records),
+            _ => false
+        },
+        _ => false
+    }
+}
+
+/* Collect the python files of a module. Each physical directory is visited once, so a symlink loop can't recurse forever */
+fn collect_python_files(dir: &Path, res: &mut Vec<PathBuf>, visited: &mut HashSet<String>) {
+    if !visited.insert(dir.sanitize_canonical()) {
+        return;
+    }
+    let Ok(entries) = dir.read_dir() else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_python_files(&path, res, visited);
+        } else if path.extension().is_some_and(|ext| ext == "py") {
+            res.push(path);
+        }
+    }
+}
@@ -1,14 +1,54 @@
+pub mod access_rights;
+pub mod bench;
+pub mod build_journal;
+pub mod build_stats;
+pub mod cache;
+pub mod cardinality;
+pub mod commands;
 pub mod config;
+pub mod create_multi;
+pub mod csv_parser;
+pub mod data_order;
+pub mod decorators;
+pub mod deferred_mutations;
+pub mod deprecations;
+pub mod diagnostic_codes;
+pub mod docstring;
+pub mod doctor;
+pub mod domain_validator;
 pub mod evaluation;
+pub mod field_rules;
 pub mod file_mgr;
+pub mod focus;
+pub mod hook_registry;
 pub mod import_resolver;
+pub mod integrity_checker;
+pub mod manifest_assets;
+pub mod migration;
 pub mod model;
+pub mod module_graph;
+pub mod module_status;
 pub mod odoo;
+pub mod populate;
+pub mod project_config;
 pub mod python_arch_builder;
 pub mod python_arch_builder_hooks;
 pub mod python_arch_eval;
 pub mod python_arch_eval_hooks;
 pub mod python_odoo_builder;
 pub mod python_validator;
+pub mod quarantine;
 pub mod python_utils;
-pub mod symbols;
\ No newline at end of file
+pub mod read_group;
+pub mod reference_fields;
+pub mod reports;
+pub mod request_progress;
+pub mod routes;
+pub mod settings;
+pub mod site_packages;
+pub mod spell_check;
+pub mod sql;
+pub mod symbols;
+pub mod uri;
+pub mod views;
+pub mod xml_code;
\ No newline at end of file
@@ -2,14 +2,21 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::rc::Weak;
 use weak_table::PtrWeakHashSet;
-use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use ruff_python_ast::{Stmt, StmtClassDef};
+use ruff_text_size::{Ranged, TextRange};
 
+use crate::constants::{flatten_tree, BuildStatus, SymType, Tree};
 use crate::threads::SessionInfo;
 
+use super::python_arch_builder::PythonArchBuilder;
+use super::python_arch_eval::PythonArchEval;
 use super::symbols::module_symbol::ModuleSymbol;
 use super::symbols::symbol::Symbol;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ModelData {
     pub name: String,
     pub inherit: Vec<String>,
@@ -24,6 +31,7 @@ pub struct ModelData {
     pub is_abstract: bool,
     pub transient: bool,
     pub rec_name: Option<String>,
+    pub rec_names_search: Vec<String>, //fields searched by name_search. Empty if only rec_name is searched
     pub order: String,
     pub check_company_auto: bool,
     pub parent_name: String,
@@ -48,6 +56,7 @@ impl ModelData {
             is_abstract: false,
             transient: false,
             rec_name: None,
+            rec_names_search: Vec::new(),
             order: String::from("id"),
             check_company_auto: false,
             parent_name: String::from("parent_id"),
@@ -59,38 +68,188 @@ impl ModelData {
     }
 }
 
+/* What a class brings to its model: its model attributes, the declarations of its members, that are its fields and the signatures
+of its methods, and the return types inferred for its methods. The bodies of the methods are only part of it through their return
+types, so editing them usually doesn't change the model */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelContribution {
+    pub data: ModelData,
+    pub declarations: u64, //fingerprint of the declarations
+    pub return_types: HashMap<String, Vec<String>>, //by method, recorded when the methods are evaluated. See Model::record_return_types
+}
+
+impl ModelContribution {
+
+    /* get_text gives the source code of a range of the file */
+    pub fn from_class(data: &ModelData, class_def: &StmtClassDef, get_text: impl Fn(&TextRange) -> Option<String>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        for stmt in class_def.body.iter() {
+            let range = match stmt {
+                Stmt::FunctionDef(func) => {
+                    let start = func.decorator_list.first().map(|decorator| decorator.range().start()).unwrap_or(func.range().start());
+                    let end = func.body.first().map(|body| body.range().start()).unwrap_or(func.range().end());
+                    TextRange::new(start, end)
+                },
+                stmt => stmt.range(),
+            };
+            get_text(&range).map(|text| text.trim().to_string()).unwrap_or_default().hash(&mut hasher);
+        }
+        ModelContribution {
+            data: data.clone(),
+            declarations: hasher.finish(),
+            return_types: HashMap::new(),
+        }
+    }
+
+    fn same_declarations(&self, other: &ModelContribution) -> bool {
+        self.data == other.data && self.declarations == other.declarations
+    }
+}
+
 #[derive(Debug)]
 pub struct Model {
     name: String,
     symbols: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
+    contributions: HashMap<Tree, ModelContribution>, //by tree of the contributing class
+    removed: HashMap<Tree, ModelContribution>, //contributions of unloaded classes, until they are loaded again or the validation starts
+    reloaded_return_types: HashMap<Tree, HashMap<String, Vec<String>>>, //return types of the classes loaded again with the same declarations, to compare when the validation starts
     pub dependents: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
 }
 
 impl Model {
-    pub fn new(name: String, symbol: Rc<RefCell<Symbol>>) -> Self {
-        let mut res = Self {
+    pub fn new(name: String) -> Self {
+        Self {
             name,
             symbols: PtrWeakHashSet::new(),
+            contributions: HashMap::new(),
+            removed: HashMap::new(),
+            reloaded_return_types: HashMap::new(),
             dependents: PtrWeakHashSet::new(),
-        };
-        res.symbols.insert(symbol);
-        res
+        }
     }
 
-    pub fn add_symbol(&mut self, session: &mut SessionInfo, symbol: Rc<RefCell<Symbol>>) {
+    /* Add a class to the model. The dependents are validated again only if the class brings something else than before it
+    was unloaded, so editing the body of a method of a popular model doesn't revalidate all the files using it.
+    The return types of the methods can only be compared once they are evaluated again, when the validation starts. See flush_pending.
+    Without contribution, the dependents are always validated again */
+    pub fn add_symbol(&mut self, session: &mut SessionInfo, symbol: Rc<RefCell<Symbol>>, contribution: Option<ModelContribution>) {
+        let tree = symbol.borrow().get_tree();
         self.symbols.insert(symbol);
-        self.add_dependents_to_validation(session);
+        let previous = self.removed.remove(&tree).or_else(|| self.contributions.remove(&tree));
+        match (contribution.as_ref(), previous) {
+            (Some(contribution), Some(previous)) if contribution.same_declarations(&previous) => {
+                if !previous.return_types.is_empty() {
+                    //if the class is loaded several times before the validation, compare with what the dependents were validated with
+                    self.reloaded_return_types.entry(tree.clone()).or_insert(previous.return_types);
+                    session.sync_odoo.pending_model_removals.insert(self.name.clone());
+                }
+            },
+            _ => self.add_dependents_to_validation(session),
+        }
+        if let Some(contribution) = contribution {
+            self.contributions.insert(tree, contribution);
+        }
     }
 
-    pub fn remove_symbol(&mut self, session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) {
+    /* Remove a class from the model. Its contribution is kept until the validation starts, as the class is usually loaded again
+    right after, when its file is rebuilt. See flush_removed */
+    pub fn remove_symbol(&mut self, session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>, tree: &Tree) {
         self.symbols.remove(symbol);
-        self.add_dependents_to_validation(session);
+        match self.contributions.remove(tree) {
+            Some(contribution) => {
+                self.removed.insert(tree.clone(), contribution);
+                session.sync_odoo.pending_model_removals.insert(self.name.clone());
+            },
+            None => self.add_dependents_to_validation(session),
+        }
+    }
+
+    /* Validate the dependents again if some classes have been removed and not loaded again, or if the methods of the classes
+    loaded again don't return the same types as before. The methods are evaluated here, once all the models are built */
+    pub fn flush_pending(model: &Rc<RefCell<Model>>, session: &mut SessionInfo) {
+        let (mut changed, reloaded_return_types) = {
+            let mut model = model.borrow_mut();
+            let removed = !model.removed.is_empty();
+            model.removed.clear();
+            (removed, std::mem::take(&mut model.reloaded_return_types))
+        };
+        for (tree, previous_return_types) in reloaded_return_types.iter() {
+            if changed {
+                break;
+            }
+            let Some(class) = session.sync_odoo.get_symbol(tree, u32::MAX).first().cloned() else {
+                changed = true;
+                break;
+            };
+            for (method, previous_types) in previous_return_types.iter() {
+                let function = class.borrow().get_content_symbol(method, u32::MAX).into_iter().find(|function| function.borrow().typ() == SymType::FUNCTION);
+                let Some(function) = function else {
+                    changed = true;
+                    break;
+                };
+                if function.borrow().as_func().arch_status == BuildStatus::PENDING {
+                    let _ = PythonArchBuilder::new(function.clone()).load_arch(session);
+                }
+                if function.borrow().as_func().arch_eval_status == BuildStatus::PENDING {
+                    PythonArchEval::new(function.clone()).eval_arch(session);
+                }
+                if Model::return_types(session, &function) != *previous_types {
+                    changed = true;
+                    break;
+                }
+            }
+        }
+        if changed {
+            model.borrow().add_dependents_to_validation(session);
+        }
+    }
+
+    /* Record the return types inferred for a method of a model in the contribution of its class */
+    pub fn record_return_types(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) {
+        let Some(class) = function.borrow().parent().and_then(|parent| parent.upgrade()) else {
+            return;
+        };
+        if class.borrow().typ() != SymType::CLASS {
+            return;
+        }
+        let Some(model) = class.borrow().as_class_sym()._model.as_ref().and_then(|model| session.sync_odoo.models.get(&model.name).cloned()) else {
+            return;
+        };
+        let return_types = Model::return_types(session, function);
+        let tree = class.borrow().get_tree();
+        let name = function.borrow().name().clone();
+        //a function can be evaluated while the model is iterated. Its return types are then unknown, and not compared
+        let Ok(mut model) = model.try_borrow_mut() else {
+            return;
+        };
+        if let Some(contribution) = model.contributions.get_mut(&tree) {
+            contribution.return_types.insert(name, return_types);
+        }
+    }
+
+    /* The types of the evaluations of a function, as the paths of the evaluated symbols */
+    fn return_types(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) -> Vec<String> {
+        let evaluations = function.borrow().evaluations().cloned().unwrap_or_default();
+        evaluations.iter().map(|evaluation| {
+            let (symbol, instance) = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            match symbol.upgrade() {
+                Some(symbol) => format!("{}{}", flatten_tree(&symbol.borrow().get_tree()).join("."), if instance { "()" } else { "" }),
+                None => String::from("?"),
+            }
+        }).collect()
+    }
+
+    pub fn get_contribution(&self, tree: &Tree) -> Option<&ModelContribution> {
+        self.contributions.get(tree)
     }
 
     pub fn get_symbols(&self, session: &mut SessionInfo, from_module: Rc<RefCell<Symbol>>) -> impl Iterator<Item= Rc<RefCell<Symbol>>> {
         let mut symbol = Vec::new();
         for s in self.symbols.iter() {
-            let module = s.borrow().find_module().expect("Model should be declared in a module");
+            //a class whose module has been unloaded is not visible anymore
+            let Some(module) = s.borrow().owning_module() else {
+                continue;
+            };
             if ModuleSymbol::is_in_deps(session, &from_module, &module.borrow().as_module_package().dir_name, &mut None) {
                 symbol.push(s);
             }
@@ -98,6 +257,10 @@ impl Model {
         symbol.into_iter()
     }
 
+    pub fn get_all_symbols(&self) -> impl Iterator<Item= Rc<RefCell<Symbol>>> + '_ {
+        self.symbols.iter()
+    }
+
     pub fn get_main_symbols(&self, session: &mut SessionInfo, from_module: Option<Rc<RefCell<Symbol>>>, acc: &mut Option<HashSet<String>>) -> Vec<Rc<RefCell<Symbol>>> {
         if acc.is_none() {
             *acc = Some(HashSet::new());
@@ -105,10 +268,11 @@ impl Model {
         let mut res: Vec<Rc<RefCell<Symbol>>> = vec![];
         for sym in self.symbols.iter() {
             if !sym.borrow().as_class_sym()._model.as_ref().unwrap().inherit.contains(&sym.borrow().as_class_sym()._model.as_ref().unwrap().name) {
-                if from_module.is_none() || sym.as_ref().borrow().find_module().is_none() {
+                let module = sym.borrow().owning_module();
+                if from_module.is_none() || module.is_none() {
                     res.push(sym);
                 } else {
-                    let dir_name = sym.borrow().find_module().unwrap().borrow().as_module_package().dir_name.clone();
+                    let dir_name = module.unwrap().borrow().as_module_package().dir_name.clone();
                     if (acc.is_some() && acc.as_ref().unwrap().contains(&dir_name)) ||
                     ModuleSymbol::is_in_deps(session, from_module.as_ref().unwrap(), &dir_name, acc) {
                         res.push(sym);
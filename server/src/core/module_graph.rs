@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use lsp_types::request::Request;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::threads::SessionInfo;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportModuleGraphParams {
+    #[serde(default)]
+    pub modules: Vec<String>, //modules exported with their dependencies. Empty for the modules of the workspace
+    #[serde(default)]
+    pub svg: bool, //render the graph with the dot command of Graphviz too
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportModuleGraphResult {
+    pub dot: String,
+    pub svg: Option<String>, //None if not requested or if Graphviz is not installed
+    pub cycles: Vec<Vec<String>>,
+    pub unknown_modules: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum ExportModuleGraphRequest {}
+
+impl Request for ExportModuleGraphRequest {
+    type Params = ExportModuleGraphParams;
+    type Result = ExportModuleGraphResult;
+    const METHOD: &'static str = "Odoo/exportModuleGraph";
+}
+
+/* Topological sort of the modules on their dependencies, used to validate the files of a module after the files of the
+modules it depends on: a change of a base model then doesn't publish errors in dependent modules that would be retracted
+once the base module is validated */
+pub struct ModuleGraph {}
+
+impl ModuleGraph {
+
+    /* Rank of each module: 0 for modules without known dependencies, and otherwise one more than the highest rank of its
+    dependencies. Dependencies that are not in the graph are ignored. Modules in a dependency cycle, or depending on one,
+    are ranked after all the others */
+    pub fn ranks(depends: &HashMap<String, Vec<String>>) -> HashMap<String, u32> {
+        let mut ranks: HashMap<String, u32> = HashMap::new();
+        let mut remaining: Vec<&String> = depends.keys().collect();
+        remaining.sort(); //to get the same order on each run
+        let mut rank = 0;
+        while !remaining.is_empty() {
+            let (ready, others): (Vec<&String>, Vec<&String>) = remaining.into_iter().partition(|module| {
+                depends[*module].iter().all(|dep| !depends.contains_key(dep) || ranks.get(dep).is_some_and(|dep_rank| *dep_rank < rank))
+            });
+            if ready.is_empty() {
+                for module in others.into_iter() {
+                    ranks.insert(module.clone(), rank);
+                }
+                break;
+            }
+            for module in ready.into_iter() {
+                ranks.insert(module.clone(), rank);
+            }
+            remaining = others;
+            rank += 1;
+        }
+        ranks
+    }
+
+    /* Export the manifest dependencies of the given modules for architecture reviews */
+    pub fn export(session: &mut SessionInfo, params: &ExportModuleGraphParams) -> ExportModuleGraphResult {
+        let depends: HashMap<String, Vec<String>> = session.sync_odoo.modules.iter()
+            .filter_map(|(name, module)| module.upgrade().map(|module| (name.clone(), module.borrow().as_module_package().depends().clone())))
+            .collect();
+        let mut roots = params.modules.clone();
+        if roots.is_empty() {
+            roots = session.sync_odoo.modules.iter()
+                .filter(|(_, module)| module.upgrade().is_some_and(|module| module.borrow().in_workspace()))
+                .map(|(name, _)| name.clone())
+                .collect();
+        }
+        let graph = ModuleGraph::subgraph(&depends, &roots);
+        let cycles = ModuleGraph::cycles(&graph);
+        let unknown_modules: Vec<String> = graph.iter().filter(|(module, _)| !depends.contains_key(*module)).map(|(module, _)| module.clone()).collect();
+        let dot = ModuleGraph::to_dot(&graph, &roots, &cycles, &unknown_modules);
+        let svg = if params.svg { ModuleGraph::render_svg(&dot) } else { None };
+        ExportModuleGraphResult { dot, svg, cycles, unknown_modules }
+    }
+
+    /* The roots and all their dependencies, recursively. Unknown modules are kept, without dependencies */
+    pub fn subgraph(depends: &HashMap<String, Vec<String>>, roots: &[String]) -> BTreeMap<String, Vec<String>> {
+        let mut graph = BTreeMap::new();
+        let mut to_visit: Vec<String> = roots.to_vec();
+        while let Some(module) = to_visit.pop() {
+            if graph.contains_key(&module) {
+                continue;
+            }
+            let deps = depends.get(&module).cloned().unwrap_or_default();
+            to_visit.extend(deps.iter().cloned());
+            graph.insert(module, deps);
+        }
+        graph
+    }
+
+    /* Strongly connected components of more than one module, or of a module depending on itself (Tarjan's algorithm) */
+    pub fn cycles(graph: &BTreeMap<String, Vec<String>>) -> Vec<Vec<String>> {
+        struct Tarjan<'a> {
+            graph: &'a BTreeMap<String, Vec<String>>,
+            index: HashMap<&'a String, usize>,
+            low_link: HashMap<&'a String, usize>,
+            stack: Vec<&'a String>,
+            cycles: Vec<Vec<String>>,
+        }
+        impl<'a> Tarjan<'a> {
+            fn visit(&mut self, module: &'a String) {
+                let index = self.index.len();
+                self.index.insert(module, index);
+                self.low_link.insert(module, index);
+                self.stack.push(module);
+                let graph = self.graph;
+                for dep in graph.get(module).into_iter().flatten() {
+                    if !graph.contains_key(dep) {
+                        continue;
+                    }
+                    if !self.index.contains_key(dep) {
+                        self.visit(dep);
+                        let low_link = self.low_link[module].min(self.low_link[dep]);
+                        self.low_link.insert(module, low_link);
+                    } else if self.stack.contains(&dep) {
+                        let low_link = self.low_link[module].min(self.index[dep]);
+                        self.low_link.insert(module, low_link);
+                    }
+                }
+                if self.low_link[module] == self.index[module] {
+                    let mut component = vec![];
+                    while let Some(member) = self.stack.pop() {
+                        component.push(member.clone());
+                        if member == module {
+                            break;
+                        }
+                    }
+                    let self_dependent = self.graph.get(module).is_some_and(|deps| deps.contains(module));
+                    if component.len() > 1 || self_dependent {
+                        component.sort();
+                        self.cycles.push(component);
+                    }
+                }
+            }
+        }
+        let mut tarjan = Tarjan { graph, index: HashMap::new(), low_link: HashMap::new(), stack: vec![], cycles: vec![] };
+        for module in graph.keys() {
+            if !tarjan.index.contains_key(module) {
+                tarjan.visit(module);
+            }
+        }
+        tarjan.cycles.sort();
+        tarjan.cycles
+    }
+
+    /* Edges go from a module to its dependencies. The selected modules are bold, the unknown ones dashed, and the modules
+    and edges of the cycles red */
+    pub fn to_dot(graph: &BTreeMap<String, Vec<String>>, roots: &[String], cycles: &[Vec<String>], unknown_modules: &[String]) -> String {
+        let cycle_of = |module: &String| cycles.iter().position(|cycle| cycle.contains(module));
+        let mut res = String::from("digraph modules {\n    rankdir=BT;\n    node [shape=box];\n");
+        for module in graph.keys() {
+            let mut attributes = vec![];
+            if roots.contains(module) {
+                attributes.push("style=bold");
+            }
+            if unknown_modules.contains(module) {
+                attributes.push("style=dashed");
+                attributes.push("fontcolor=gray");
+            }
+            if cycle_of(module).is_some() {
+                attributes.push("color=red");
+            }
+            if attributes.is_empty() {
+                res += &format!("    \"{}\";\n", module);
+            } else {
+                res += &format!("    \"{}\" [{}];\n", module, attributes.join(", "));
+            }
+        }
+        let mut edges = BTreeSet::new();
+        for (module, deps) in graph.iter() {
+            for dep in deps.iter() {
+                edges.insert((module, dep));
+            }
+        }
+        for (module, dep) in edges.into_iter() {
+            if cycle_of(module).is_some() && cycle_of(module) == cycle_of(dep) {
+                res += &format!("    \"{}\" -> \"{}\" [color=red];\n", module, dep);
+            } else {
+                res += &format!("    \"{}\" -> \"{}\";\n", module, dep);
+            }
+        }
+        res += "}\n";
+        res
+    }
+
+    fn render_svg(dot: &String) -> Option<String> {
+        let child = Command::new("dot").arg("-Tsvg").stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                warn!("Unable to render the module graph, is Graphviz installed? {}", e);
+                return None;
+            }
+        };
+        child.stdin.take()?.write_all(dot.as_bytes()).ok()?;
+        let output = child.wait_with_output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use lsp_types::notification::Notification;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleStatusParams {
+    pub module: String,
+    pub files: u32,
+    pub errors: u32,
+    pub warnings: u32,
+    pub duration_ms: u64, //from the validation of the first file of the module to the last one
+}
+
+#[derive(Debug)]
+pub enum ModuleStatusNotification {}
+
+impl Notification for ModuleStatusNotification {
+    type Params = ModuleStatusParams;
+    const METHOD: &'static str = "Odoo/moduleStatus";
+}
+
+/* Accumulate the results of the validation of the files of each module, until all the files of the module are validated */
+#[derive(Debug, Default)]
+pub struct ModuleStatusTracker {
+    modules: HashMap<String, (Instant, ModuleStatusParams)>,
+    pending: Option<HashMap<String, u32>>, //number of files of each module waiting for validation. None until computed
+}
+
+impl ModuleStatusTracker {
+
+    pub fn start_file(&mut self, module: &String) {
+        self.modules.entry(module.clone()).or_insert_with(|| (Instant::now(), ModuleStatusParams {
+            module: module.clone(),
+            files: 0,
+            errors: 0,
+            warnings: 0,
+            duration_ms: 0,
+        }));
+    }
+
+    pub fn end_file(&mut self, module: &String, errors: u32, warnings: u32) {
+        if let Some((_, status)) = self.modules.get_mut(module) {
+            status.files += 1;
+            status.errors += errors;
+            status.warnings += warnings;
+        }
+    }
+
+    /* Return the status of the module, and forget it so a next validation starts from scratch */
+    pub fn finish(&mut self, module: &String) -> Option<ModuleStatusParams> {
+        let (start, mut status) = self.modules.remove(module)?;
+        status.duration_ms = start.elapsed().as_millis() as u64;
+        Some(status)
+    }
+
+    /* Return whether files of the module are still waiting for validation. The counts of the modules are computed once
+    from the queue of validation, then updated as its files are popped */
+    pub fn is_pending(&mut self, module: &String, count_pending: impl FnOnce() -> HashMap<String, u32>) -> bool {
+        self.pending.get_or_insert_with(count_pending).get(module).is_some_and(|count| *count > 0)
+    }
+
+    /* A file of the module left the queue of validation */
+    pub fn pop_pending(&mut self, module: &String) {
+        if let Some(count) = self.pending.as_mut().and_then(|pending| pending.get_mut(module)) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /* Files were added to or removed from the queue of validation: the counts have to be computed again */
+    pub fn invalidate_pending(&mut self) {
+        self.pending = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.modules.clear();
+        self.pending = None;
+    }
+}
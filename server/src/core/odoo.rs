@@ -1,34 +1,71 @@
 use crate::core::config::{Config, PythonPathRequest, PythonPathRequestResult};
+use crate::core::diagnostic_codes::add_code_description;
 use crate::threads::SessionInfo;
+use crate::features::code_action::CodeActionFeature;
 use crate::features::completion::CompletionFeature;
+use crate::features::formatting::FormattingFeature;
 use crate::features::definition::DefinitionFeature;
+use crate::features::document_color::DocumentColorFeature;
+use crate::features::document_link::DocumentLinkFeature;
+use crate::features::document_symbol::DocumentSymbolFeature;
 use crate::features::hover::HoverFeature;
+use crate::features::signature_help::SignatureHelpFeature;
+use crate::features::implementation::ImplementationFeature;
+use crate::features::inline_value::InlineValueFeature;
+use crate::features::references::ReferenceFeature;
+use crate::features::rename::RenameFeature;
+use crate::features::workspace_symbol::WorkspaceSymbolFeature;
 use std::collections::HashMap;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use lsp_server::ResponseError;
+use lsp_server::{RequestId, ResponseError};
 use lsp_types::*;
-use request::{RegisterCapability, Request, WorkspaceConfiguration};
-use tracing::{debug, error, info, trace};
+use request::{ApplyWorkspaceEdit, GotoImplementationParams, GotoImplementationResponse, RegisterCapability, Request, WorkspaceConfiguration};
+use notification::Notification;
+use tracing::{debug, error, info, trace, warn};
 
 use std::collections::HashSet;
 use weak_table::PtrWeakHashSet;
 use std::process::Command;
 use std::str::FromStr;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::env;
 use std::cmp;
 use regex::Regex;
 use crate::constants::*;
-use super::config::{DiagMissingImportsMode, RefreshMode};
-use super::file_mgr::FileMgr;
+use super::deferred_mutations::{DeferredMutation, MutationQueue};
+use super::config::{AddonsPolicy, DefinitionTarget, DiagMissingImportsMode, RefreshMode};
+use super::file_mgr::{FileInfo, FileMgr};
+use super::project_config::{ProjectConfig, PROJECT_CONFIG_FILE};
+use super::reports::ReportValidator;
+use super::views::{ViewValidator, XmlFieldIndex};
+use super::xml_code::ServerActionCode;
 use super::symbols::symbol::Symbol;
+use crate::crash_report::CrashReport;
+use crate::core::integrity_checker::{CheckIntegrityResult, IntegrityChecker};
+use crate::core::module_status::{ModuleStatusNotification, ModuleStatusTracker};
+use crate::core::quarantine::{FileQuarantinedNotification, FileQuarantinedParams, Quarantine, STEP_TIME_BUDGET};
+use crate::core::routes::RouteIndex;
+use crate::core::site_packages::SitePackagesWatcher;
+use crate::core::build_journal::{BuildJournal, JournalFile};
+use crate::core::cache::{cache_dir, CacheFingerprint, ClearCacheResult};
+use crate::core::build_stats::{BuildStatsResult, RequestStats};
+use crate::core::focus::{Focus, ToggleFocusParams, ToggleFocusResult};
+use crate::core::doctor::{Doctor, DoctorReport};
+use crate::features::model_source::{GetModelSourceParams, GetModelSourceResult, ModelSourceFeature};
+use crate::features::scaffold::{ScaffoldFeature, ScaffoldParams, ScaffoldResult};
+use crate::features::translation::{ExportPotPreviewParams, ExportPotPreviewResult, TranslationFeature};
+use crate::features::xml_ids::{FindUnusedXmlIdsParams, FindUnusedXmlIdsResult, XmlIdFeature};
+use crate::core::hook_registry::HookRegistry;
 use crate::core::model::Model;
+use crate::core::commands::{ReloadModuleParams, ReloadModuleResult};
+use crate::core::module_graph::{ExportModuleGraphParams, ExportModuleGraphResult, ModuleGraph};
 use crate::core::python_arch_builder::PythonArchBuilder;
+use crate::core::request_progress::RequestProgress;
 use crate::core::python_arch_eval::PythonArchEval;
 use crate::core::python_odoo_builder::PythonOdooBuilder;
 use crate::core::python_validator::PythonValidator;
@@ -58,14 +95,37 @@ pub struct SyncOdoo {
     pub modules: HashMap<String, Weak<RefCell<Symbol>>>,
     pub models: HashMap<String, Rc<RefCell<Model>>>,
     pub interrupt_rebuild: Arc<AtomicBool>,
+    pub shutdown: Arc<AtomicBool>, //set when the client stops the server. Builds stop before the next file
+    pub cancelled_requests: Arc<Mutex<HashSet<RequestId>>>, //requests cancelled by the client while they wait or run
     rebuild_arch: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
     rebuild_arch_eval: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
     rebuild_odoo: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
     rebuild_validation: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
+    rebuild_priority: PtrWeakHashSet<Weak<RefCell<Symbol>>>, //symbols to build before the others, like opened files
+    module_ranks: Option<HashMap<String, u32>>, //rank of the modules in the order of their dependencies. None if it has to be computed again
     pub state_init: InitState,
     pub not_found_symbols: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
     pub load_odoo_addons: bool, //indicate if we want to load odoo addons or not
-    pub need_rebuild: bool //if true, the next process_rebuilds will drop everything and rebuild everything
+    pub need_rebuild: bool, //if true, the next process_rebuilds will drop everything and rebuild everything
+    pub capabilities: ClientCapabilities, //capabilities of the client, given at initialization
+    pub initialization_options: Option<serde_json::Value>, //settings given in initialize, for the clients without workspace/configuration
+    pub hooks: HookRegistry,
+    pub module_status: ModuleStatusTracker, //results of the validation of modules, sent to the client when a module is fully validated
+    pub routes: RouteIndex, //urls declared by http controllers
+    pub xml_fields: XmlFieldIndex, //fields used by the views of the data files
+    pub python_version: String,
+    pub cache_fingerprint: Option<CacheFingerprint>, //computed once the versions of python and odoo are known
+    pub site_packages: SitePackagesWatcher, //installed python packages, polled to detect the changes made with pip
+    pub degraded: Option<String>, //reason why the interpreter or the stdlib can't be used. Only the odoo features are available then
+    degraded_uris: Vec<Uri>, //workspace folders where the degraded mode is reported, kept by reset to clear the diagnostic
+    pub request_stats: RequestStats, //latencies of the LSP requests
+    pub focus: Focus, //modules to validate, kept when the database is reset
+    unfocused: PtrWeakHashSet<Weak<RefCell<Symbol>>>, //files waiting for validation, outside of the focus
+    resumed_files: HashMap<String, JournalFile>, //validation diagnostics of the files validated by an interrupted initial build
+    restored: PtrWeakHashSet<Weak<RefCell<Symbol>>>, //files whose diagnostics come from the journal, validated when they are opened
+    pub pending_model_removals: HashSet<String>, //models with unloaded classes that may be loaded again before the validation, or with reloaded classes whose return types must be compared
+    pub quarantine: Quarantine, //files whose build keeps failing, only built up to the arch step
+    pub deferred_mutations: MutationQueue, //changes of the files requested by the hooks, see MutationQueue
 }
 
 unsafe impl Send for SyncOdoo {}
@@ -89,14 +149,37 @@ impl SyncOdoo {
             modules: HashMap::new(),
             models: HashMap::new(),
             interrupt_rebuild: Arc::new(AtomicBool::new(false)),
+            shutdown: Arc::new(AtomicBool::new(false)),
+            cancelled_requests: Arc::new(Mutex::new(HashSet::new())),
             rebuild_arch: PtrWeakHashSet::new(),
             rebuild_arch_eval: PtrWeakHashSet::new(),
             rebuild_odoo: PtrWeakHashSet::new(),
             rebuild_validation: PtrWeakHashSet::new(),
+            rebuild_priority: PtrWeakHashSet::new(),
+            module_ranks: None,
             state_init: InitState::NOT_READY,
             not_found_symbols: PtrWeakHashSet::new(),
             load_odoo_addons: true,
             need_rebuild: false,
+            capabilities: ClientCapabilities::default(),
+            initialization_options: None,
+            hooks: HookRegistry::new(),
+            module_status: ModuleStatusTracker::default(),
+            routes: RouteIndex::default(),
+            xml_fields: XmlFieldIndex::default(),
+            python_version: S!(""),
+            cache_fingerprint: None,
+            site_packages: SitePackagesWatcher::default(),
+            degraded: None,
+            degraded_uris: vec![],
+            request_stats: RequestStats::default(),
+            focus: Focus::default(),
+            unfocused: PtrWeakHashSet::new(),
+            resumed_files: HashMap::new(),
+            restored: PtrWeakHashSet::new(),
+            pending_model_removals: HashSet::new(),
+            quarantine: Quarantine::default(),
+            deferred_mutations: MutationQueue::default(),
         };
         sync_odoo
     }
@@ -121,6 +204,22 @@ impl SyncOdoo {
         session.sync_odoo.rebuild_arch_eval = PtrWeakHashSet::new();
         session.sync_odoo.rebuild_odoo = PtrWeakHashSet::new();
         session.sync_odoo.rebuild_validation = PtrWeakHashSet::new();
+        session.sync_odoo.rebuild_priority = PtrWeakHashSet::new();
+        session.sync_odoo.module_ranks = None;
+        session.sync_odoo.focus.invalidate();
+        session.sync_odoo.unfocused = PtrWeakHashSet::new();
+        session.sync_odoo.resumed_files.clear();
+        session.sync_odoo.restored = PtrWeakHashSet::new();
+        session.sync_odoo.pending_model_removals.clear();
+        session.sync_odoo.quarantine.clear();
+        session.sync_odoo.deferred_mutations.take();
+        session.sync_odoo.module_status.clear();
+        session.sync_odoo.routes.clear();
+        session.sync_odoo.xml_fields.clear();
+        session.sync_odoo.python_version = S!("");
+        session.sync_odoo.cache_fingerprint = None;
+        session.sync_odoo.site_packages.clear();
+        session.sync_odoo.degraded = None;
         session.sync_odoo.state_init = InitState::NOT_READY;
         session.sync_odoo.not_found_symbols = PtrWeakHashSet::new();
         session.sync_odoo.load_odoo_addons = true;
@@ -134,6 +233,13 @@ impl SyncOdoo {
         session.sync_odoo.state_init = InitState::NOT_READY;
         session.send_notification("$Odoo/loadingStatusUpdate", "start");
         session.sync_odoo.config = config;
+        CrashReport::set_config(&session.sync_odoo.config);
+        session.sync_odoo.hooks = HookRegistry::new();
+        for hook_set in session.sync_odoo.config.hook_sets.clone().iter() {
+            if !session.sync_odoo.hooks.load_set(hook_set) {
+                session.log_message(MessageType::ERROR, format!("Unknown hook set: {}. Available sets: {}", hook_set, HookRegistry::available_sets().join(", ")));
+            }
+        }
         if session.sync_odoo.config.no_typeshed {
             session.sync_odoo.stubs_dirs.clear();
         }
@@ -158,37 +264,43 @@ impl SyncOdoo {
             for stub_dir in session.sync_odoo.stubs_dirs.iter() {
                 root_symbol.add_path(stub_dir.clone());
             }
-            let output = Command::new(session.sync_odoo.config.python_path.clone()).args(&["-c", "import sys; import json; print(json.dumps(sys.path))"]).output();
-            if let Err(_output) = &output {
-                error!("Wrong python command: {}", session.sync_odoo.config.python_path.clone());
-                session.send_notification("$Odoo/invalid_python_path", ());
-                session.send_notification("$Odoo/loadingStatusUpdate", "stop");
-                return;
+            let output = Command::new(session.sync_odoo.config.python_path.clone()).args(&["-c", "import sys; import json; print(json.dumps({'path': sys.path, 'version': '%d.%d.%d' % sys.version_info[:3]}))"]).output();
+            match &output {
+                Err(_) => {
+                    error!("Wrong python command: {}", session.sync_odoo.config.python_path.clone());
+                    session.send_notification("$Odoo/invalid_python_path", ());
+                    session.sync_odoo.degraded = Some(format!("The python interpreter '{}' can't be run", session.sync_odoo.config.python_path));
+                },
+                Ok(output) if !output.status.success() => {
+                    error!("{}", String::from_utf8_lossy(&output.stderr));
+                    session.sync_odoo.degraded = Some(format!("The python interpreter '{}' failed to give its sys.path", session.sync_odoo.config.python_path));
+                },
+                Ok(_) => {}
             }
-            let output = output.unwrap();
-            if output.status.success() {
+            if let Some(output) = output.as_ref().ok().filter(|output| output.status.success()) {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 session.log_message(MessageType::INFO, format!("Detected sys.path: {}", stdout));
-                let paths: Vec<String> = serde_json::from_str(&stdout).expect("Unable to get paths with json of sys.path output");
+                let python_info: serde_json::Value = serde_json::from_str(&stdout).expect("Unable to get paths with json of sys.path output");
+                let paths: Vec<String> = serde_json::from_value(python_info["path"].clone()).expect("Unable to get paths with json of sys.path output");
+                session.sync_odoo.python_version = python_info["version"].as_str().unwrap_or_default().to_string();
                 for path in paths.iter() {
                     let path = path.replace("\\\\", "\\");
                     let pathbuf = PathBuf::from(path);
                     if pathbuf.is_dir() {
-                        let final_path = pathbuf.sanitize();
+                        let final_path = pathbuf.sanitize_canonical();
                         session.log_message(MessageType::INFO, format!("Adding sys.path: {}", final_path));
                         root_symbol.add_path(final_path.clone());
                         root_symbol.as_root_mut().sys_path.push(final_path.clone());
                     }
                 }
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                error!("{}", stderr);
             }
         }
         SyncOdoo::load_builtins(session);
+        SyncOdoo::publish_degraded_mode(session);
         session.sync_odoo.state_init = InitState::PYTHON_READY;
         SyncOdoo::build_database(session);
         session.send_notification("$Odoo/loadingStatusUpdate", "stop");
+        session.sync_odoo.request_stats.init_ms = start_time.elapsed().as_millis() as u64;
         info!("Time taken: {} ms", start_time.elapsed().as_millis());
     }
 
@@ -198,6 +310,11 @@ impl SyncOdoo {
         if !builtins_path.exists() {
             session.log_message(MessageType::ERROR, String::from("Unable to find builtins.pyi"));
             error!("Unable to find builtins at: {}", builtins_path.sanitize());
+            let reason = format!("The stdlib stubs can't be found at {}", path.sanitize());
+            session.sync_odoo.degraded = Some(match session.sync_odoo.degraded.take() {
+                Some(previous) => format!("{}. {}", previous, reason),
+                None => reason,
+            });
             return;
         };
         let _builtins_rc_symbol = Symbol::create_from_path(session, &builtins_path, session.sync_odoo.symbols.as_ref().unwrap().clone(), false);
@@ -205,6 +322,64 @@ impl SyncOdoo {
         SyncOdoo::process_rebuilds(session);
     }
 
+    /* Without interpreter or stdlib, every import of a python library would be reported. Instead, the server works in a
+    degraded mode: the imports outside of odoo are not reported, and a single diagnostic on the workspace folders explains
+    why. The odoo features are still available */
+    pub fn publish_degraded_mode(session: &mut SessionInfo) {
+        let Some(reason) = session.sync_odoo.degraded.clone() else {
+            for uri in std::mem::take(&mut session.sync_odoo.degraded_uris) {
+                session.send_notification::<PublishDiagnosticsParams>(notification::PublishDiagnostics::METHOD, PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: vec![],
+                    version: None,
+                });
+            }
+            return;
+        };
+        let message = format!("OdooLS is running in degraded mode: {}. The python libraries are not resolved, only the Odoo features are available. Check the python path and the stdlib of your configuration.", reason);
+        warn!("{}", message);
+        session.send_notification(notification::ShowMessage::METHOD, ShowMessageParams {
+            typ: MessageType::ERROR,
+            message: message.clone(),
+        });
+        let workspace_folders = session.sync_odoo.get_file_mgr().borrow().get_workspace_folders().clone();
+        let uris: Vec<Uri> = workspace_folders.iter().map(FileMgr::pathname2uri).collect();
+        //the folders removed from the workspace since the last publication are cleared
+        for uri in std::mem::take(&mut session.sync_odoo.degraded_uris) {
+            if !uris.contains(&uri) {
+                session.send_notification::<PublishDiagnosticsParams>(notification::PublishDiagnostics::METHOD, PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: vec![],
+                    version: None,
+                });
+            }
+        }
+        let mut diagnostic = Diagnostic::new(
+            Range::new(Position::new(0, 0), Position::new(0, 0)),
+            Some(DiagnosticSeverity::ERROR),
+            Some(NumberOrString::String(S!("OLS30004"))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None,
+        );
+        add_code_description(&mut diagnostic);
+        for uri in uris.iter() {
+            session.send_notification::<PublishDiagnosticsParams>(notification::PublishDiagnostics::METHOD, PublishDiagnosticsParams {
+                uri: uri.clone(),
+                diagnostics: vec![diagnostic.clone()],
+                version: None,
+            });
+        }
+        session.sync_odoo.degraded_uris = uris;
+    }
+
+    /* In degraded mode, the python libraries can't be resolved. The symbols outside of odoo are then not reported, the
+    reason being given once for the workspace by publish_degraded_mode */
+    pub fn is_unresolvable(&self, tree: &[String]) -> bool {
+        self.degraded.is_some() && tree.first().is_some_and(|name| name != "odoo")
+    }
+
     pub fn build_database(session: &mut SessionInfo) {
         session.log_message(MessageType::INFO, String::from("Building Database"));
         let result = SyncOdoo::build_base(session);
@@ -272,8 +447,11 @@ impl SyncOdoo {
         session.sync_odoo.version_minor = _version_minor;
         session.sync_odoo.version_micro = _version_micro;
         session.sync_odoo.full_version = _full_version;
+        let fingerprint = session.sync_odoo.compute_cache_fingerprint();
+        info!("Cache fingerprint: {}", fingerprint.key());
+        session.sync_odoo.cache_fingerprint = Some(fingerprint);
         //build base
-        session.sync_odoo.symbols.as_ref().unwrap().borrow_mut().add_path(session.sync_odoo.config.odoo_path.clone());
+        session.sync_odoo.symbols.as_ref().unwrap().borrow_mut().add_path(PathBuf::from(&session.sync_odoo.config.odoo_path).sanitize_canonical());
         if session.sync_odoo.symbols.is_none() {
             panic!("Odoo root symbol not found")
         }
@@ -294,30 +472,47 @@ impl SyncOdoo {
         let addon_symbol = addon_symbol[0].clone();
         if odoo_addon_path.exists() {
             if session.sync_odoo.load_odoo_addons {
-                addon_symbol.borrow_mut().add_path(
-                    odoo_addon_path.sanitize()
-                );
+                SyncOdoo::add_addons_path(session, &addon_symbol, &odoo_addon_path);
             }
         } else {
             let odoo_addon_path = PathBuf::from(odoo_path.clone()).join("addons");
             session.log_message(MessageType::ERROR, format!("Unable to find odoo addons path at {}", odoo_addon_path.sanitize()));
             return false;
         }
-        for addon in session.sync_odoo.config.addons.iter() {
+        for addon in session.sync_odoo.config.addons.clone().iter() {
             let addon_path = PathBuf::from(addon);
+            if session.sync_odoo.config.addons_policy(&addon_path.sanitize_canonical()) == AddonsPolicy::Ignore {
+                info!("Addons path {} is ignored by its policy", addon);
+                continue;
+            }
             if addon_path.exists() {
-                addon_symbol.borrow_mut().add_path(
-                    addon_path.sanitize()
-                );
+                SyncOdoo::add_addons_path(session, &addon_symbol, &addon_path);
+            } else {
+                session.log_message(MessageType::WARNING, format!("Addons path {} does not exist", addon));
             }
         }
         return true;
     }
 
+    /* Merge a directory in the odoo.addons namespace. The order of the paths gives the priority: if a module is present
+    in many addons paths, the first one wins, like in Odoo. A path already merged is ignored, so its priority is not changed. */
+    fn add_addons_path(session: &mut SessionInfo, addon_symbol: &Rc<RefCell<Symbol>>, path: &PathBuf) {
+        let path = path.sanitize_canonical();
+        let already_added = addon_symbol.borrow().paths().iter().any(|p| PathBuf::from(p) == PathBuf::from(&path));
+        if already_added {
+            info!("Addons path {} is already in odoo.addons", path);
+            return;
+        }
+        addon_symbol.borrow_mut().add_path(path);
+    }
+
     fn build_modules(session: &mut SessionInfo) {
+        let mut module_symbols: Vec<Rc<RefCell<Symbol>>> = vec![];
         {
             let addons_symbol = session.sync_odoo.get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX)[0].clone();
-            let addons_path = addons_symbol.borrow_mut().paths().clone();
+            let addons_path = addons_symbol.borrow().paths().clone();
+            //physical directories of the loaded modules, to not load twice a module symlinked under another name
+            let mut module_dirs: HashSet<String> = HashSet::new();
             for addon_path in addons_path.iter() {
                 info!("searching modules in {}", addon_path);
                 if PathBuf::from(addon_path).exists() {
@@ -325,10 +520,25 @@ impl SyncOdoo {
                     for item in PathBuf::from(addon_path).read_dir().expect("Unable to find odoo addons path") {
                         match item {
                             Ok(item) => {
-                                if item.file_type().unwrap().is_dir() && !session.sync_odoo.modules.contains_key(&item.file_name().to_str().unwrap().to_string()) {
+                                //follow symlinks: modules are often symlinked in an addons directory
+                                if !item.path().is_dir() {
+                                    continue;
+                                }
+                                let dir_name = item.file_name().to_str().unwrap().to_string();
+                                let module_dir = item.path().sanitize_canonical();
+                                if module_dirs.contains(&module_dir) {
+                                    info!("{} is ignored, as it is a link to an already loaded module", item.path().sanitize());
+                                    continue;
+                                }
+                                if let Some(existing) = session.sync_odoo.modules.get(&dir_name).and_then(|m| m.upgrade()) {
+                                    if item.path().join("__manifest__.py").exists() {
+                                        info!("Module {} in {} is ignored, as it is already provided by {}", dir_name, addon_path, existing.borrow().as_module_package().path);
+                                    }
+                                } else {
                                     let module_symbol = Symbol::create_from_path(session, &item.path(), addons_symbol.clone(), true);
-                                    if module_symbol.is_some() {
-                                        session.sync_odoo.add_to_rebuild_arch(module_symbol.unwrap());
+                                    if let Some(module_symbol) = module_symbol {
+                                        module_dirs.insert(module_dir);
+                                        module_symbols.push(module_symbol);
                                     }
                                 }
                             },
@@ -338,7 +548,7 @@ impl SyncOdoo {
                 }
             }
         }
-        SyncOdoo::process_rebuilds(session);
+        SyncOdoo::build_module_batches(session, module_symbols);
         //println!("{}", self.symbols.as_ref().unwrap().borrow_mut().debug_print_graph());
         //fs::write("out_architecture.json", self.get_symbol(&tree(vec!["odoo", "addons", "module_1"], vec![])).as_ref().unwrap().borrow().debug_to_json().to_string()).expect("Unable to write file");
         let modules_count = session.sync_odoo.modules.len();
@@ -347,13 +557,137 @@ impl SyncOdoo {
         session.sync_odoo.state_init = InitState::ODOO_READY;
     }
 
+    /* Build the modules by batches, in the order of their dependencies, and record each completed batch in the build journal,
+    with the validation diagnostics of its files. The unchanged files of the batches completed by an interrupted build are not
+    validated again: their recorded diagnostics are published. The journal is kept if the build is stopped before its end, with the
+    files already validated by the batch stopped by a shutdown */
+    fn build_module_batches(session: &mut SessionInfo, module_symbols: Vec<Rc<RefCell<Symbol>>>) {
+        let journal_dir = cache_dir();
+        let key = BuildJournal::key(
+            &session.sync_odoo.cache_fingerprint.as_ref().map(|fingerprint| fingerprint.key()).unwrap_or_default(),
+            &session.sync_odoo.config.odoo_path,
+            &session.sync_odoo.config.addons);
+        let mut journal = match journal_dir.as_ref().and_then(|dir| BuildJournal::load(dir, &key)) {
+            Some(journal) => {
+                session.sync_odoo.resumed_files = journal.files.clone();
+                session.log_message(MessageType::INFO, format!("Resuming an interrupted build: {} modules were already validated", journal.completed_modules().len()));
+                journal
+            },
+            None => BuildJournal::new(key),
+        };
+        let symbols_by_name: HashMap<String, Rc<RefCell<Symbol>>> = module_symbols.into_iter()
+            .map(|symbol| (symbol.borrow().as_module_package().dir_name.clone(), symbol)).collect();
+        let depends: HashMap<String, Vec<String>> = symbols_by_name.iter()
+            .map(|(name, symbol)| (name.clone(), symbol.borrow().as_module_package().depends().clone())).collect();
+        let completed = journal.completed_modules();
+        for batch in BuildJournal::batches(&depends).iter() {
+            for module in batch.iter() {
+                session.sync_odoo.add_to_rebuild_arch(symbols_by_name[module].clone());
+            }
+            SyncOdoo::process_rebuilds(session);
+            if session.sync_odoo.need_rebuild {
+                return;
+            }
+            let stopped = session.sync_odoo.shutdown.load(Ordering::SeqCst);
+            if let Some(journal_dir) = journal_dir.as_ref().filter(|_| !batch.iter().all(|module| completed.contains(module))) {
+                let module_dirs: Vec<PathBuf> = batch.iter()
+                    .filter_map(|module| symbols_by_name[module].borrow().paths().first().map(PathBuf::from))
+                    .collect();
+                let files = session.sync_odoo.get_file_mgr().borrow().validation_diagnostics(&module_dirs);
+                if stopped {
+                    journal.add_files(files);
+                } else {
+                    journal.complete_batch(batch, files);
+                }
+                if let Err(e) = journal.save(journal_dir) {
+                    warn!("Unable to save the build journal: {}", e);
+                }
+            }
+            if stopped {
+                info!("Server is shutting down, the progress of the build is kept in the journal");
+                return;
+            }
+        }
+        session.sync_odoo.resumed_files.clear();
+        if let Some(journal_dir) = journal_dir.as_ref() {
+            journal.remove(journal_dir);
+        }
+    }
+
+    /* Publish the diagnostics recorded by an interrupted initial build for the file, instead of validating it again. Return
+    false if the file was not validated by that build, or has changed since */
+    fn restore_validation(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> bool {
+        if session.sync_odoo.resumed_files.is_empty() {
+            return false;
+        }
+        let path = SyncOdoo::file_info_path(symbol);
+        let Some(journal_file) = session.sync_odoo.resumed_files.remove(&path) else {
+            return false;
+        };
+        let Some(file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path) else {
+            return false;
+        };
+        if !file_info.borrow().get_content().is_some_and(|content| journal_file.matches(&content)) {
+            return false;
+        }
+        file_info.borrow_mut().replace_diagnostics(BuildSteps::VALIDATION, journal_file.diagnostics);
+        session.sync_odoo.restored.insert(symbol.clone());
+        true
+    }
+
+    /* Path of the FileInfo of a file or package symbol */
+    fn file_info_path(symbol: &Rc<RefCell<Symbol>>) -> String {
+        let symbol = symbol.borrow();
+        let mut path = symbol.paths()[0].clone();
+        if symbol.typ() == SymType::PACKAGE {
+            path = PathBuf::from(path).join("__init__.py").sanitize() + symbol.as_package().i_ext().as_str();
+        }
+        path
+    }
+
+    /* Apply the changes of the files queued by the hooks (see MutationQueue). No symbol may be borrowed. The changes of the
+    files removed since are dropped */
+    pub fn apply_deferred_mutations(&mut self) {
+        for mutation in self.deferred_mutations.take() {
+            match mutation {
+                DeferredMutation::AddDependency { file, dependency, step, dep_level } => {
+                    let (Some(file), Some(dependency)) = (file.upgrade(), dependency.upgrade()) else {
+                        continue;
+                    };
+                    if Rc::ptr_eq(&file, &dependency) {
+                        continue;
+                    }
+                    debug_assert!(file.try_borrow_mut().is_ok() && dependency.try_borrow_mut().is_ok(), "a symbol is borrowed while applying the deferred mutations");
+                    file.borrow_mut().add_dependency(&mut dependency.borrow_mut(), step, dep_level);
+                },
+                DeferredMutation::AddModelDependency { file, model } => {
+                    let (Some(file), Some(model)) = (file.upgrade(), self.models.get(&model).cloned()) else {
+                        continue;
+                    };
+                    debug_assert!(file.try_borrow_mut().is_ok(), "a symbol is borrowed while applying the deferred mutations");
+                    file.borrow_mut().add_model_dependencies(&model);
+                },
+                DeferredMutation::AddNotFoundPath { file, step, path } => {
+                    let Some(file) = file.upgrade() else {
+                        continue;
+                    };
+                    debug_assert!(file.try_borrow_mut().is_ok(), "a symbol is borrowed while applying the deferred mutations");
+                    file.borrow_mut().not_found_paths_mut().push((step, path));
+                },
+            }
+        }
+    }
+
     pub fn get_symbol(&self, tree: &Tree, position: u32) -> Vec<Rc<RefCell<Symbol>>> {
-        self.symbols.as_ref().unwrap().borrow_mut().get_symbol(&tree, position)
+        self.symbols.as_ref().unwrap().borrow().get_symbol(&tree, position)
     }
 
     fn pop_item(&mut self, step: BuildSteps) -> Option<Rc<RefCell<Symbol>>> {
         let mut arc_sym: Option<Rc<RefCell<Symbol>>> = None;
-        //Part 1: Find the symbol with a unmutable set
+        if step == BuildSteps::VALIDATION {
+            self.update_module_ranks();
+        }
+        //Part 1: Find the symbol with a unmutable set, in prioritized symbols first
         {
             let set =  match step {
                 BuildSteps::ARCH_EVAL => &self.rebuild_arch_eval,
@@ -361,31 +695,8 @@ impl SyncOdoo {
                 BuildSteps::VALIDATION => &self.rebuild_validation,
                 _ => &self.rebuild_arch
             };
-            let mut selected_sym: Option<Rc<RefCell<Symbol>>> = None;
-            let mut selected_count: u32 = 999999999;
-            let mut current_count: u32;
-            for sym in &*set {
-                current_count = 0;
-                let file = sym.borrow().get_file().unwrap().upgrade().unwrap();
-                let file = file.borrow();
-                for (index, dep_set) in file.get_all_dependencies(step).iter().enumerate() {
-                    let index_set =  match index {
-                        x if x == BuildSteps::ARCH as usize => &self.rebuild_arch,
-                        x if x == BuildSteps::ARCH_EVAL as usize => &self.rebuild_arch_eval,
-                        x if x == BuildSteps::VALIDATION as usize => &self.rebuild_validation,
-                        _ => continue,
-                    };
-                    current_count +=
-                        dep_set.iter().filter(|dep| index_set.contains(dep)).count() as u32;
-                }
-                if current_count < selected_count {
-                    selected_sym = Some(sym.clone());
-                    selected_count = current_count;
-                    if current_count == 0 {
-                        break;
-                    }
-                }
-            }
+            let selected_sym = self._select_item(self.rebuild_priority.iter().filter(|sym| set.contains(sym)), step)
+                .or_else(|| self._select_item(set.iter(), step));
             if selected_sym.is_some() {
                 arc_sym = selected_sym.map(|x| x.clone());
             }
@@ -399,16 +710,168 @@ impl SyncOdoo {
             };
             if arc_sym.is_none() {
                 set.clear(); //remove any potential dead weak ref
+                if step == BuildSteps::VALIDATION {
+                    self.module_status.invalidate_pending();
+                }
                 return None;
             }
             let arc_sym_unwrapped = arc_sym.unwrap();
             if !set.remove(&arc_sym_unwrapped) {
                 panic!("Unable to remove selected symbol from rebuild set")
             }
+            if step == BuildSteps::VALIDATION {
+                self.rebuild_priority.remove(&arc_sym_unwrapped);
+                if let Some(module) = arc_sym_unwrapped.borrow().find_module() {
+                    self.module_status.pop_pending(&module.borrow().as_module_package().dir_name);
+                }
+            }
             return Some(arc_sym_unwrapped);
         }
     }
 
+    /* Select the symbol with the less dependencies still waiting to be built. For the validation, the symbols of the modules
+with the lowest rank in the order of dependencies are selected first */
+    fn _select_item(&self, candidates: impl Iterator<Item = Rc<RefCell<Symbol>>>, step: BuildSteps) -> Option<Rc<RefCell<Symbol>>> {
+        let mut selected_sym: Option<Rc<RefCell<Symbol>>> = None;
+        let mut selected_count: u32 = 999999999;
+        let mut selected_rank: u32 = u32::MAX;
+        let mut current_count: u32;
+        for sym in candidates {
+            current_count = 0;
+            let current_rank = if step == BuildSteps::VALIDATION { self.module_rank(&sym) } else { 0 };
+            if current_rank > selected_rank {
+                continue;
+            }
+            let file = sym.borrow().get_file().unwrap().upgrade().unwrap();
+            let file = file.borrow();
+            for (index, dep_set) in file.get_all_dependencies(step).iter().enumerate() {
+                let index_set =  match index {
+                    x if x == BuildSteps::ARCH as usize => &self.rebuild_arch,
+                    x if x == BuildSteps::ARCH_EVAL as usize => &self.rebuild_arch_eval,
+                    x if x == BuildSteps::VALIDATION as usize => &self.rebuild_validation,
+                    _ => continue,
+                };
+                current_count +=
+                    dep_set.iter().filter(|dep| index_set.contains(dep)).count() as u32;
+            }
+            if current_rank < selected_rank || current_count < selected_count {
+                selected_sym = Some(sym.clone());
+                selected_count = current_count;
+                selected_rank = current_rank;
+                if current_count == 0 && current_rank == 0 {
+                    break;
+                }
+            }
+        }
+        selected_sym
+    }
+
+    pub fn invalidate_module_ranks(&mut self) {
+        self.module_ranks = None;
+        self.focus.invalidate();
+    }
+
+    fn update_module_ranks(&mut self) {
+        if self.module_ranks.is_some() {
+            return;
+        }
+        let depends: HashMap<String, Vec<String>> = self.modules.iter()
+            .filter_map(|(name, module)| module.upgrade().map(|module| (name.clone(), module.borrow().as_module_package().depends().clone())))
+            .collect();
+        self.module_ranks = Some(ModuleGraph::ranks(&depends));
+    }
+
+    /* 0 for the files that are not in a module (odoo itself), then the rank of the module in the order of dependencies */
+    fn module_rank(&self, symbol: &Rc<RefCell<Symbol>>) -> u32 {
+        let Some(module) = symbol.borrow().find_module() else {
+            return 0;
+        };
+        let dir_name = module.borrow().as_module_package().dir_name.clone();
+        self.module_ranks.as_ref().and_then(|ranks| ranks.get(&dir_name)).map(|rank| rank + 1).unwrap_or(0)
+    }
+
+    /* True if the file is in an addons path whose modules are indexed but never validated, like third-party addons */
+    fn is_suggest_only(&self, symbol: &Rc<RefCell<Symbol>>) -> bool {
+        if self.config.addons_policies.is_empty() {
+            return false;
+        }
+        symbol.borrow().paths().first().is_some_and(|path| self.config.addons_policy(path) == AddonsPolicy::SuggestOnly)
+    }
+
+    /* True if the file has to be validated: there is no focus, the file is opened, or it is in a focused module or one of
+    their dependencies. Files outside of modules, like the ones of odoo itself, are out of any focus */
+    fn is_focused(&mut self, symbol: &Rc<RefCell<Symbol>>) -> bool {
+        if !self.focus.is_active() {
+            return true;
+        }
+        let path = {
+            let symbol = symbol.borrow();
+            let mut path = symbol.paths()[0].clone();
+            if symbol.typ() == SymType::PACKAGE {
+                path = PathBuf::from(path).join("__init__.py").sanitize() + symbol.as_package().i_ext().as_str();
+            }
+            path
+        };
+        if self.file_mgr.borrow().get_file_info(&path).is_some_and(|file_info| file_info.borrow().opened) {
+            return true;
+        }
+        let Some(module) = symbol.borrow().find_module() else {
+            return false;
+        };
+        let dir_name = module.borrow().as_module_package().dir_name.clone();
+        let modules = &self.modules;
+        self.focus.contains(&dir_name, || modules.iter()
+            .filter_map(|(name, module)| module.upgrade().map(|module| (name.clone(), module.borrow().as_module_package().depends().clone())))
+            .collect())
+    }
+
+    /* Change the focused modules, and validate the files of the modules that are now in the focus. The diagnostics of the
+    modules leaving the focus are kept until their files change */
+    pub fn toggle_focus(session: &mut SessionInfo, modules: &[String]) {
+        session.sync_odoo.focus.toggle(modules);
+        let unfocused: Vec<Rc<RefCell<Symbol>>> = session.sync_odoo.unfocused.iter().collect();
+        session.sync_odoo.unfocused = PtrWeakHashSet::new();
+        for symbol in unfocused {
+            session.sync_odoo.rebuild_validation.insert(symbol);
+        }
+        session.sync_odoo.module_status.invalidate_pending();
+        SyncOdoo::process_rebuilds(session);
+    }
+
+    /* Sort the symbols in the order their modules are loaded by Odoo: symbols outside of modules first, then by dependencies */
+    pub fn sort_by_module_rank(&mut self, symbols: &mut Vec<Rc<RefCell<Symbol>>>) {
+        self.update_module_ranks();
+        symbols.sort_by_cached_key(|symbol| self.module_rank(symbol));
+    }
+
+    /* Move an opened file and its direct dependencies to the front of the rebuild queues, if the file is still waiting
+    for its arch eval or validation. Hover and completion will then be available without waiting for the whole build.
+    Return true if the file has to be validated, as its diagnostics were restored from the build journal */
+    pub fn prioritize_file(&mut self, path: &PathBuf) -> bool {
+        let Some(file_symbol) = self.get_file_symbol(path) else {
+            return false;
+        };
+        let file = file_symbol.borrow();
+        if file.build_status(BuildSteps::ARCH_EVAL) == BuildStatus::DONE && file.build_status(BuildSteps::VALIDATION) == BuildStatus::DONE {
+            return false;
+        }
+        for step in [BuildSteps::ARCH, BuildSteps::ARCH_EVAL, BuildSteps::VALIDATION] {
+            for dep_set in file.get_all_dependencies(step).iter() {
+                for dep in dep_set.iter() {
+                    self.rebuild_priority.insert(dep);
+                }
+            }
+        }
+        drop(file);
+        //the diagnostics of the file come from the journal of an interrupted build: its local symbols are needed now
+        let restored = self.restored.remove(&file_symbol);
+        if restored {
+            self.add_to_validations(file_symbol.clone());
+        }
+        self.rebuild_priority.insert(file_symbol);
+        restored
+    }
+
     pub fn process_rebuilds(session: &mut SessionInfo) {
         session.sync_odoo.interrupt_rebuild.store(false, Ordering::SeqCst);
         let mut already_arch_rebuilt: HashSet<Tree> = HashSet::new();
@@ -416,6 +879,11 @@ impl SyncOdoo {
         let mut already_odoo_rebuilt: HashSet<Tree> = HashSet::new();
         let mut already_validation_rebuilt: HashSet<Tree> = HashSet::new();
         while !session.sync_odoo.need_rebuild && (!session.sync_odoo.rebuild_arch.is_empty() || !session.sync_odoo.rebuild_arch_eval.is_empty() || !session.sync_odoo.rebuild_odoo.is_empty() || !session.sync_odoo.rebuild_validation.is_empty()) {
+            if session.sync_odoo.shutdown.load(Ordering::SeqCst) {
+                info!("Server is shutting down, rebuild stopped");
+                return;
+            }
+            session.sync_odoo.apply_deferred_mutations();
             trace!("remains: {:?} - {:?} - {:?} - {:?}", session.sync_odoo.rebuild_arch.len(), session.sync_odoo.rebuild_arch_eval.len(), session.sync_odoo.rebuild_odoo.len(), session.sync_odoo.rebuild_validation.len());
             let sym = session.sync_odoo.pop_item(BuildSteps::ARCH);
             if let Some(sym_rc) = sym {
@@ -426,8 +894,10 @@ impl SyncOdoo {
                 }
                 already_arch_rebuilt.insert(tree);
                 //TODO should delete previous first
-                let mut builder = PythonArchBuilder::new(sym_rc);
-                builder.load_arch(session);
+                SyncOdoo::run_guarded_step(session, BuildSteps::ARCH, &sym_rc, |session| {
+                    let mut builder = PythonArchBuilder::new(sym_rc.clone());
+                    builder.load_arch(session);
+                });
                 continue;
             }
             let sym = session.sync_odoo.pop_item(BuildSteps::ARCH_EVAL);
@@ -438,9 +908,14 @@ impl SyncOdoo {
                     continue;
                 }
                 already_arch_eval_rebuilt.insert(tree);
+                if SyncOdoo::skip_quarantined(session, BuildSteps::ARCH_EVAL, &sym_rc) {
+                    continue;
+                }
                 //TODO should delete previous first
-                let mut builder = PythonArchEval::new(sym_rc);
-                builder.eval_arch(session);
+                SyncOdoo::run_guarded_step(session, BuildSteps::ARCH_EVAL, &sym_rc, |session| {
+                    let mut builder = PythonArchEval::new(sym_rc.clone());
+                    builder.eval_arch(session);
+                });
                 continue;
             }
             let sym = session.sync_odoo.pop_item(BuildSteps::ODOO);
@@ -451,22 +926,50 @@ impl SyncOdoo {
                     continue;
                 }
                 already_odoo_rebuilt.insert(tree);
+                if SyncOdoo::skip_quarantined(session, BuildSteps::ODOO, &sym_rc) {
+                    continue;
+                }
                 //TODO should delete previous first
-                let mut builder = PythonOdooBuilder::new(sym_rc);
-                builder.load_odoo_content(session);
+                SyncOdoo::run_guarded_step(session, BuildSteps::ODOO, &sym_rc, |session| {
+                    let mut builder = PythonOdooBuilder::new(sym_rc.clone());
+                    builder.load_odoo_content(session);
+                });
                 continue;
             }
+            //the arch, eval and odoo steps are done: the removed classes won't be loaded again
+            SyncOdoo::flush_model_removals(session);
             let sym = session.sync_odoo.pop_item(BuildSteps::VALIDATION);
             if let Some(sym_rc) = sym {
-                let tree = sym_rc.borrow_mut().get_tree();
+                if session.sync_odoo.is_suggest_only(&sym_rc) {
+                    continue;
+                }
+                if !session.sync_odoo.is_focused(&sym_rc) {
+                    session.sync_odoo.unfocused.insert(sym_rc);
+                    continue;
+                }
+                let tree = sym_rc.borrow().get_tree();
                 if already_validation_rebuilt.contains(&tree) {
                     info!("Already validation rebuilt, skipping");
                     continue;
                 }
                 already_validation_rebuilt.insert(tree);
+                if SyncOdoo::skip_quarantined(session, BuildSteps::VALIDATION, &sym_rc) {
+                    continue;
+                }
                 //TODO should delete previous first
-                let mut validator = PythonValidator::new(sym_rc);
-                validator.validate(session);
+                let module = sym_rc.borrow().find_module();
+                if let Some(module) = module.as_ref() {
+                    session.sync_odoo.module_status.start_file(&module.borrow().as_module_package().dir_name);
+                }
+                if !SyncOdoo::restore_validation(session, &sym_rc) {
+                    SyncOdoo::run_guarded_step(session, BuildSteps::VALIDATION, &sym_rc, |session| {
+                        let mut validator = PythonValidator::new(sym_rc.clone());
+                        validator.validate(session);
+                    });
+                }
+                if let Some(module) = module.as_ref() {
+                    SyncOdoo::update_module_status(session, &sym_rc, module);
+                }
                 if session.sync_odoo.state_init == InitState::ODOO_READY && session.sync_odoo.interrupt_rebuild.load(Ordering::SeqCst) {
                     session.sync_odoo.interrupt_rebuild.store(false, Ordering::SeqCst);
                     session.log_message(MessageType::INFO, S!("Rebuild interrupted"));
@@ -476,12 +979,89 @@ impl SyncOdoo {
                 continue;
             }
         }
+        session.sync_odoo.apply_deferred_mutations();
         if session.sync_odoo.need_rebuild {
             session.log_message(MessageType::INFO, S!("Rebuild required. Resetting database on breaktime..."));
             SessionInfo::request_reload(session);
         }
     }
 
+    /* Run a build step of a file, catching its panics, so a bug on a file doesn't stop the build of the workspace. A file whose
+    steps keep panicking or exceeding the time budget is quarantined: the client is notified, and its next steps are skipped */
+    fn run_guarded_step(session: &mut SessionInfo, step: BuildSteps, symbol: &Rc<RefCell<Symbol>>, run: impl FnOnce(&mut SessionInfo)) {
+        let path = symbol.borrow().paths().first().cloned().unwrap_or_default();
+        let step_name = format!("{:?}", step);
+        CrashReport::set_current_file(&step_name, Some(&path));
+        let start = Instant::now();
+        let reason = match CrashReport::catch_recoverable(|| run(session)) {
+            Err(message) => Some(format!("the {} step panicked: {}", step_name, message)),
+            Ok(()) if start.elapsed() > STEP_TIME_BUDGET => Some(format!("the {} step took {}s", step_name, start.elapsed().as_secs())),
+            Ok(()) => None,
+        };
+        let Some(reason) = reason else {
+            session.sync_odoo.quarantine.record_success(&path);
+            return;
+        };
+        error!("Build of {} failed: {}", path, reason);
+        //a panic leaves the step in progress: it is marked as done, to not be run again when the other files use the symbols
+        if [SymType::FILE, SymType::PACKAGE].contains(&symbol.borrow().typ()) {
+            symbol.borrow_mut().set_build_status(step, BuildStatus::DONE);
+        }
+        if session.sync_odoo.quarantine.record_failure(&path) {
+            session.log_message(MessageType::WARNING, format!("{} is quarantined, as {}. Its symbols are kept, but it won't be evaluated nor validated until it is edited", path, reason));
+            session.send_notification(FileQuarantinedNotification::METHOD, FileQuarantinedParams {
+                path,
+                step: step_name,
+                reason,
+            });
+        }
+    }
+
+    /* Mark the step as done without running it if the file is quarantined */
+    fn skip_quarantined(session: &mut SessionInfo, step: BuildSteps, symbol: &Rc<RefCell<Symbol>>) -> bool {
+        let quarantined = symbol.borrow().paths().first().is_some_and(|path| session.sync_odoo.quarantine.is_quarantined(path));
+        if quarantined && [SymType::FILE, SymType::PACKAGE].contains(&symbol.borrow().typ()) {
+            symbol.borrow_mut().set_build_status(step, BuildStatus::DONE);
+        }
+        quarantined
+    }
+
+    fn flush_model_removals(session: &mut SessionInfo) {
+        let model_names: Vec<String> = session.sync_odoo.pending_model_removals.drain().collect();
+        for model_name in model_names {
+            let model = session.sync_odoo.models.get(&model_name).cloned();
+            if let Some(model) = model {
+                Model::flush_pending(&model, session);
+            }
+        }
+    }
+
+    /* Add the diagnostics of a validated file to the status of its module, and notify the client if it was the last file
+    of the module waiting for validation */
+    fn update_module_status(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, module: &Rc<RefCell<Symbol>>) {
+        let module_name = module.borrow().as_module_package().dir_name.clone();
+        let path = SyncOdoo::file_info_path(file_symbol);
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path);
+        let (errors, warnings) = file_info.map(|file_info| file_info.borrow().count_diagnostics(&session.sync_odoo.config)).unwrap_or((0, 0));
+        session.sync_odoo.module_status.end_file(&module_name, errors, warnings);
+        let rebuild_validation = &session.sync_odoo.rebuild_validation;
+        let pending = session.sync_odoo.module_status.is_pending(&module_name, || {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for sym in rebuild_validation.iter() {
+                if let Some(sym_module) = sym.borrow().find_module() {
+                    *counts.entry(sym_module.borrow().as_module_package().dir_name.clone()).or_default() += 1;
+                }
+            }
+            counts
+        });
+        if pending {
+            return;
+        }
+        if let Some(status) = session.sync_odoo.module_status.finish(&module_name) {
+            session.send_notification(ModuleStatusNotification::METHOD, status);
+        }
+    }
+
     pub fn rebuild_arch_now(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) {
         session.sync_odoo.rebuild_arch.remove(symbol);
         let mut builder = PythonArchBuilder::new(symbol.clone());
@@ -529,6 +1109,7 @@ impl SyncOdoo {
         if symbol.borrow().build_status(BuildSteps::VALIDATION) != BuildStatus::IN_PROGRESS {
             symbol.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
             self.rebuild_validation.insert(symbol);
+            self.module_status.invalidate_pending();
         }
     }
 
@@ -546,6 +1127,7 @@ impl SyncOdoo {
 
     pub fn remove_from_rebuild_validation(&mut self, symbol: &Rc<RefCell<Symbol>>) {
         self.rebuild_validation.remove(symbol);
+        self.module_status.invalidate_pending();
     }
 
     pub fn is_in_rebuild(&self, symbol: &Rc<RefCell<Symbol>>, step: BuildSteps) -> bool {
@@ -564,6 +1146,15 @@ impl SyncOdoo {
         false
     }
 
+    pub fn compute_cache_fingerprint(&self) -> CacheFingerprint {
+        CacheFingerprint {
+            python_version: self.python_version.clone(),
+            odoo_version: self.full_version.clone(),
+            server_version: EXTENSION_VERSION.to_string(),
+            hook_sets: self.hooks.loaded_sets_versions(),
+        }
+    }
+
     pub fn get_file_mgr(&mut self) -> Rc<RefCell<FileMgr>> {
         self.file_mgr.clone()
     }
@@ -571,6 +1162,13 @@ impl SyncOdoo {
     /* Path must be absolute. Return a valid tree according the root paths and odoo/addons path. The given
     tree may not be in the graph however */
     pub fn tree_from_path(&self, path: &PathBuf) -> Result<Tree, &str> {
+        //paths of the database are canonical: a file reached through a symlink must give the same tree
+        let path_str = path.sanitize_canonical();
+        let standalone_tree = tree(vec![STANDALONE_NAMESPACE, path_str.as_str()], vec![]);
+        if !self.symbols.as_ref().unwrap().borrow().get_symbol(&standalone_tree, u32::MAX).is_empty() {
+            return Ok(standalone_tree);
+        }
+        let path = &PathBuf::from(path_str);
         //First check in odoo, before anywhere else
         {
             let odoo_sym = self.symbols.as_ref().unwrap().borrow().get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX);
@@ -578,9 +1176,12 @@ impl SyncOdoo {
             for addon_path in odoo_sym.borrow().paths().iter() {
                 if path.starts_with(addon_path) {
                     let path = path.strip_prefix(addon_path).unwrap().to_path_buf();
+                    if self.is_shadowed_module(addon_path, &path) {
+                        return Err("Path is in a module hidden by the same module in another addons path");
+                    }
                     let mut tree: Tree = (vec![S!("odoo"), S!("addons")], vec![]);
                     path.components().for_each(|c| {
-                        tree.0.push(c.as_os_str().to_str().unwrap().replace(".py", "").replace(".pyi", "").to_string());
+                        tree.0.push(SyncOdoo::tree_name(&c));
                     });
                     if vec!["__init__", "__manifest__"].contains(&tree.0.last().unwrap().as_str()) {
                         tree.0.pop();
@@ -589,12 +1190,29 @@ impl SyncOdoo {
                 }
             }
         }
+        //modules symlinked in an addons path have their files outside of it
+        for (module_name, module) in self.modules.iter() {
+            let Some(module) = module.upgrade() else {
+                continue;
+            };
+            let module_path = PathBuf::from(module.borrow().as_module_package().path.clone());
+            if let Ok(relative) = path.strip_prefix(&module_path) {
+                let mut tree: Tree = (vec![S!("odoo"), S!("addons"), module_name.clone()], vec![]);
+                relative.components().for_each(|c| {
+                    tree.0.push(SyncOdoo::tree_name(&c));
+                });
+                if vec!["__init__", "__manifest__"].contains(&tree.0.last().unwrap().as_str()) {
+                    tree.0.pop();
+                }
+                return Ok(tree);
+            }
+        }
         for root_path in self.symbols.as_ref().unwrap().borrow().paths().iter() {
             if path.starts_with(root_path) {
                 let path = path.strip_prefix(root_path).unwrap().to_path_buf();
                 let mut tree: Tree = (vec![], vec![]);
                 path.components().for_each(|c| {
-                    tree.0.push(c.as_os_str().to_str().unwrap().replace(".py", "").to_string());
+                    tree.0.push(SyncOdoo::tree_name(&c));
                 });
                 if tree.0.len() > 0 && vec!["__init__", "__manifest__"].contains(&tree.0.last().unwrap().as_str()) {
                     tree.0.pop();
@@ -605,6 +1223,81 @@ impl SyncOdoo {
         Err("Path not found in any module")
     }
 
+    /* Name of a component of a path in a tree: python files are named without their extension */
+    fn tree_name(component: &Component) -> String {
+        let name = component.as_os_str().to_str().unwrap();
+        name.strip_suffix(".pyi").or_else(|| name.strip_suffix(".py")).unwrap_or(name).to_string()
+    }
+
+    /* Return true if the relative path is in a module of the addons path that is not loaded, because a module with the same
+    name has been found first in another addons path */
+    fn is_shadowed_module(&self, addon_path: &String, relative_path: &PathBuf) -> bool {
+        let Some(module_name) = relative_path.components().next().map(|c| c.as_os_str().to_string_lossy().to_string()) else {
+            return false;
+        };
+        let Some(module) = self.modules.get(&module_name).and_then(|m| m.upgrade()) else {
+            return false;
+        };
+        let module_path = PathBuf::from(module.borrow().as_module_package().path.clone());
+        module_path != PathBuf::from(addon_path).join(&module_name)
+    }
+
+    /* Python files opened outside of the addons paths and of sys.path, like scratch scripts or files of jupyter-style '# %%' cells
+    at the root of the workspace, are analyzed as standalone files. Odoo is importable from them, but they are not part of any module:
+    they don't register models or routes, and the rules about modules don't apply. Cells are analyzed as one script, in their order,
+    without their IPython magics (see FileInfo::mask_cell_magics). */
+    pub fn is_standalone_candidate(&self, path: &PathBuf) -> bool {
+        if self.tree_from_path(path).is_ok() {
+            return false;
+        }
+        let path = path.sanitize();
+        let odoo_dirs = [PathBuf::from(&self.config.odoo_path).join("odoo").sanitize(), PathBuf::from(&self.config.odoo_path).join("addons").sanitize()];
+        let in_addons = self.config.addons.iter().chain(odoo_dirs.iter()).any(|addons| !addons.is_empty() && path.starts_with(addons.as_str()));
+        !in_addons && self.file_mgr.borrow().is_in_workspace(&path)
+    }
+
+    pub fn is_standalone_tree(tree: &Tree) -> bool {
+        tree.0.first().is_some_and(|name| name == STANDALONE_NAMESPACE)
+    }
+
+    /* Add the file to the standalone namespace and queue it for a build */
+    pub fn load_standalone_file(session: &mut SessionInfo, path: &PathBuf) {
+        let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+        let namespace = root.borrow().get_symbol(&tree(vec![STANDALONE_NAMESPACE], vec![]), u32::MAX).first().cloned();
+        let namespace = match namespace {
+            Some(namespace) => namespace,
+            None => root.borrow_mut().add_new_namespace(session, &S!(STANDALONE_NAMESPACE), &S!("")),
+        };
+        //files are named by their path, as two scratch files can have the same name in different folders
+        let path_str = path.sanitize_canonical();
+        let file = namespace.borrow_mut().add_new_file(session, &path_str, &path_str);
+        session.sync_odoo.add_to_rebuild_arch(file);
+    }
+
+    /* Drop the symbols of the file and queue the symbols depending on it for a rebuild. Standalone files are loaded again,
+    as no other symbol imports them */
+    pub fn reload_path(session: &mut SessionInfo, path: &PathBuf) {
+        //an edited file gets a new chance, even if its build was failing. The quarantine of a package is on its directory
+        let quarantined_path = if path.file_stem().is_some_and(|stem| stem == "__init__") { path.parent().map(|dir| dir.sanitize()) } else { Some(path.sanitize()) };
+        if let Some(quarantined_path) = quarantined_path {
+            session.sync_odoo.quarantine.release(&quarantined_path);
+        }
+        match session.sync_odoo.tree_from_path(path) {
+            Ok(tree) => {
+                let _ = SyncOdoo::_unload_path(session, path, false);
+                SyncOdoo::search_symbols_to_rebuild(session, &tree);
+                if SyncOdoo::is_standalone_tree(&tree) {
+                    SyncOdoo::load_standalone_file(session, path);
+                }
+            },
+            Err(_) => {
+                if session.sync_odoo.is_standalone_candidate(path) {
+                    SyncOdoo::load_standalone_file(session, path);
+                }
+            }
+        }
+    }
+
     pub fn _unload_path(session: &mut SessionInfo, path: &PathBuf, clean_cache: bool) -> Result<Rc<RefCell<Symbol>>, String> {
         let ub_symbol = session.sync_odoo.symbols.as_ref().unwrap().clone();
         let symbol = ub_symbol.borrow();
@@ -702,6 +1395,31 @@ impl SyncOdoo {
         need_rebuild
     }
 
+    /* Poll the directories of sys.path, and reload the python packages that were installed, upgraded or removed since the
+    last poll. The files importing them are built again, so the "not found" diagnostics of a new package disappear */
+    pub fn refresh_site_packages(session: &mut SessionInfo) {
+        let dirs: Vec<String> = {
+            let root = session.sync_odoo.symbols.as_ref().unwrap().borrow();
+            root.as_root().sys_path.iter().filter(|dir| !dir.is_empty() && **dir != session.sync_odoo.config.odoo_path
+                && !session.sync_odoo.config.addons.contains(dir)).cloned().collect()
+        };
+        let changes = session.sync_odoo.site_packages.poll(&dirs);
+        if changes.is_empty() {
+            return;
+        }
+        for (dir, name) in changes.iter() {
+            session.log_message(MessageType::INFO, format!("Python package changed: {} in {}", name, dir));
+            let symbol = session.sync_odoo.symbols.as_ref().unwrap().borrow().get_module_symbol(name);
+            if let Some(symbol) = symbol {
+                if symbol.borrow().paths().iter().any(|path| path.starts_with(dir.as_str())) {
+                    Symbol::unload(session, symbol);
+                }
+            }
+            SyncOdoo::search_symbols_to_rebuild(session, &(vec![name.clone()], vec![]));
+        }
+        SyncOdoo::process_rebuilds(session);
+    }
+
     pub fn get_file_symbol(&self, path: &PathBuf) -> Option<Rc<RefCell<Symbol>>> {
         let symbol = self.symbols.as_ref().unwrap().borrow();
         let tree = &self.tree_from_path(&path);
@@ -746,43 +1464,110 @@ impl Odoo {
         let config_params = ConfigurationParams {
             items: vec![configuration_item],
         };
-        let config = session.send_request::<ConfigurationParams, Vec<serde_json::Value>>(WorkspaceConfiguration::METHOD, config_params).unwrap().unwrap();
-        let python_path = session.send_request::<(), PythonPathRequestResult>(PythonPathRequest::METHOD, ());
-        if let Err(_e) = python_path {
-            session.log_message(MessageType::ERROR, S!("Unable to get PythonPath. Be sure that your editor support the route Odoo/getPythonPath"));
-            return Err(format!("{:?}", _e));
-        }
-        let python_path = python_path.unwrap();
-        let python_path = match python_path {
-            Some(p) => {p.python_path},
-            None => {
-                session.log_message(MessageType::WARNING, S!("No PythonPath provided. Be sure that your editor support the route Odoo/getPythonPath and that route always return a result. Using 'python3' instead"));
-                S!("python3")
-            }
+        let initialization_options = session.sync_odoo.initialization_options.clone();
+        let supports_configuration = session.sync_odoo.capabilities.workspace.as_ref().and_then(|workspace| workspace.configuration).unwrap_or(false);
+        let workspace_settings = if supports_configuration || initialization_options.is_none() {
+            session.send_request::<ConfigurationParams, Vec<serde_json::Value>>(WorkspaceConfiguration::METHOD, config_params).ok().flatten()
+                .and_then(|settings| settings.into_iter().next())
+        } else {
+            None
         };
-        let config = config.get(0);
-        if !config.is_some() {
+        let config = Config::merge_client_settings(initialization_options.as_ref(), workspace_settings.as_ref());
+        let Some(config) = config else {
             session.log_message(MessageType::ERROR, String::from("No config found for Odoo. Exiting..."));
             return Err(S!("no config found for Odoo"));
-        }
-        let config = config.unwrap();
+        };
+        //the pythonPath setting avoids the Odoo/getPythonPath request, that only the VS Code extension implements
+        let python_path = match config.get("pythonPath").and_then(|python_path| python_path.as_str()) {
+            Some(python_path) => Some(python_path.to_string()),
+            None => match session.send_request::<(), PythonPathRequestResult>(PythonPathRequest::METHOD, ()) {
+                Ok(python_path) => python_path.map(|p| p.python_path),
+                Err(_e) if initialization_options.is_some() => None,
+                Err(_e) => {
+                    session.log_message(MessageType::ERROR, S!("Unable to get PythonPath. Be sure that your editor support the route Odoo/getPythonPath"));
+                    return Err(format!("{:?}", _e));
+                }
+            }
+        };
+        Odoo::parse_configuration(session, &config, python_path)
+    }
+
+    /* Build the configuration from the settings of the client, merged with the odools.toml of the workspace */
+    pub fn parse_configuration(session: &mut SessionInfo, config: &serde_json::Value, python_path: Option<String>) -> Result<Config, String> {
         //values for sync block
-        let mut _refresh_mode : RefreshMode = RefreshMode::OnSave;
-        let mut _auto_save_delay : u64 = 2000;
+        let mut _refresh_mode : RefreshMode = RefreshMode::Adaptive;
+        let mut _auto_save_delay : u64 = 1000;
+        let mut _max_refresh_delay : u64 = 15000;
+        let mut _reload_delay : u64 = 4000;
         let mut _ac_filter_model_names : bool = true;
         let mut _diag_missing_imports : DiagMissingImportsMode = DiagMissingImportsMode::All;
+        let mut _hook_sets: Vec<String> = vec![];
+        let mut _on_type_formatting: bool = false;
+        let mut _range_formatting: bool = false;
+        let mut _definition_target: DefinitionTarget = DefinitionTarget::Stub;
+        let mut _large_file_threshold: u64 = 2048;
+        let mut _slow_request_threshold: u64 = 2000;
+        let mut _spell_check: bool = false;
+        let mut _access_hints: bool = false;
+        let mut _populate_checks: bool = false;
+        let mut _strict_mode: Option<bool> = None;
+        let mut _odoo_path: Option<String> = None;
+        let mut _addons: Option<Vec<String>> = None;
+        let mut _addons_policies: HashMap<String, AddonsPolicy> = HashMap::new();
+        let mut _external_paths: HashMap<String, bool> = HashMap::new();
         let mut selected_configuration: String = S!("");
         let mut configurations = serde_json::Map::new();
         if let Some(map) = config.as_object() {
             for (key, value) in map {
                 match key.as_str() {
+                    "odooPath" => {
+                        if let Some(odoo_path) = value.as_str() {
+                            _odoo_path = Some(odoo_path.to_string());
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse odooPath. It should be a string"));
+                        }
+                    },
+                    "addons" => {
+                        if let Some(values) = value.as_array() {
+                            _addons = Some(values.iter().filter_map(|v| v.as_str()).map(|v| v.to_string()).collect());
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse addons. It should be a list of strings"));
+                        }
+                    },
+                    "pythonPath" => {
+                        //already read
+                    },
+                    "addonsPolicies" => {
+                        if let Some(policies) = value.as_object() {
+                            for (addons_path, policy) in policies.iter() {
+                                match policy.as_str().map(AddonsPolicy::from_str) {
+                                    Some(Ok(policy)) => { _addons_policies.insert(PathBuf::from(addons_path).sanitize_canonical(), policy); },
+                                    _ => session.log_message(MessageType::ERROR, format!("Unable to parse the policy of {}. It should be full, suggestOnly or ignore", addons_path)),
+                                }
+                            }
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse addonsPolicies. It should be an object of addons paths and policies"));
+                        }
+                    },
+                    "externalPaths" => {
+                        if let Some(paths) = value.as_object() {
+                            for (path, external) in paths.iter() {
+                                match external.as_bool() {
+                                    Some(external) => { _external_paths.insert(PathBuf::from(path).sanitize_canonical(), external); },
+                                    None => session.log_message(MessageType::ERROR, format!("Unable to parse the external flag of {}. It should be a boolean", path)),
+                                }
+                            }
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse externalPaths. It should be an object of paths and booleans"));
+                        }
+                    },
                     "autoRefresh" => {
                         if let Some(refresh_mode) = value.as_str() {
                             _refresh_mode = match RefreshMode::from_str(refresh_mode) {
                                 Ok(mode) => mode,
                                 Err(_) => {
-                                    session.log_message(MessageType::ERROR, String::from("Unable to parse RefreshMode. Setting it to onSave"));
-                                    RefreshMode::OnSave
+                                    session.log_message(MessageType::ERROR, String::from("Unable to parse RefreshMode. Setting it to adaptive"));
+                                    RefreshMode::Adaptive
                                 }
                             };
                         }
@@ -791,8 +1576,22 @@ impl Odoo {
                         if let Some(refresh_delay) = value.as_u64() {
                             _auto_save_delay = refresh_delay;
                         } else {
-                            session.log_message(MessageType::ERROR, String::from("Unable to parse auto_save_delay. Setting it to 2000"));
-                            _auto_save_delay = 2000
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse auto_save_delay. Setting it to 1000"));
+                            _auto_save_delay = 1000
+                        }
+                    },
+                    "autoRefreshMaxDelay" => {
+                        if let Some(max_delay) = value.as_u64() {
+                            _max_refresh_delay = max_delay;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse autoRefreshMaxDelay. Setting it to 15000"));
+                        }
+                    },
+                    "reloadDelay" => {
+                        if let Some(reload_delay) = value.as_u64() {
+                            _reload_delay = reload_delay;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse reloadDelay. Setting it to 4000"));
                         }
                     },
                     "autocompletion" => {
@@ -836,6 +1635,80 @@ impl Odoo {
                             selected_configuration = value_str.to_string();
                         }
                     },
+                    "hookSets" => {
+                        if let Some(values) = value.as_array() {
+                            _hook_sets = values.iter().filter_map(|v| v.as_str()).map(|v| v.to_string()).collect();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse hookSets. It should be a list of strings"));
+                        }
+                    },
+                    "onTypeFormatting" => {
+                        if let Some(on_type_formatting) = value.as_bool() {
+                            _on_type_formatting = on_type_formatting;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse onTypeFormatting. Setting it to false"));
+                        }
+                    },
+                    "rangeFormatting" => {
+                        if let Some(range_formatting) = value.as_bool() {
+                            _range_formatting = range_formatting;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse rangeFormatting. Setting it to false"));
+                        }
+                    },
+                    "definitionTarget" => {
+                        if let Some(definition_target) = value.as_str() {
+                            _definition_target = match DefinitionTarget::from_str(definition_target) {
+                                Ok(target) => target,
+                                Err(_) => {
+                                    session.log_message(MessageType::ERROR, String::from("Unable to parse definitionTarget. Setting it to stub"));
+                                    DefinitionTarget::Stub
+                                }
+                            };
+                        }
+                    },
+                    "accessHints" => {
+                        if let Some(access_hints) = value.as_bool() {
+                            _access_hints = access_hints;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse accessHints. Setting it to false"));
+                        }
+                    },
+                    "populateChecks" => {
+                        if let Some(populate_checks) = value.as_bool() {
+                            _populate_checks = populate_checks;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse populateChecks. Setting it to false"));
+                        }
+                    },
+                    "spellCheck" => {
+                        if let Some(spell_check) = value.as_bool() {
+                            _spell_check = spell_check;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse spellCheck. Setting it to false"));
+                        }
+                    },
+                    "strictMode" => {
+                        if let Some(strict_mode) = value.as_bool() {
+                            _strict_mode = Some(strict_mode);
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse strictMode. Setting it to false"));
+                        }
+                    },
+                    "largeFileThreshold" => {
+                        if let Some(large_file_threshold) = value.as_u64() {
+                            _large_file_threshold = large_file_threshold;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse largeFileThreshold. Setting it to 2048"));
+                        }
+                    },
+                    "slowRequestThreshold" => {
+                        if let Some(slow_request_threshold) = value.as_u64() {
+                            _slow_request_threshold = slow_request_threshold;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse slowRequestThreshold. Setting it to 2000"));
+                        }
+                    },
                     "serverLogLevel" => {
                         //Too late, set it with command line
                     },
@@ -847,7 +1720,20 @@ impl Odoo {
         }
         debug!("configurations: {:?}", configurations);
         debug!("selected_configuration: {:?}", selected_configuration);
+        //merge order: defaults < odools.toml of the workspace < settings of the client
         let mut config = Config::new();
+        let project_config = Odoo::load_project_config(session);
+        if let Some(project_config) = project_config.as_ref() {
+            config.project_config = Some(project_config.path.clone());
+            config.addons = project_config.addons.clone().unwrap_or_default();
+            config.odoo_path = project_config.odoo_path.clone().unwrap_or_default();
+            config.exclude = project_config.exclude.clone();
+            config.diag_severities = project_config.diag_severities.clone();
+            config.dictionary = project_config.dictionary.iter().map(|word| word.to_lowercase()).collect();
+            config.strict_mode = project_config.strict.unwrap_or(false);
+            config.addons_policies = project_config.addons_policies.clone();
+            config.external_paths = project_config.external_paths.clone();
+        }
         if configurations.contains_key(&selected_configuration) {
             let odoo_conf = configurations.get(&selected_configuration).unwrap();
             let odoo_conf = odoo_conf.as_object().unwrap();
@@ -855,27 +1741,80 @@ impl Odoo {
                 .as_array().expect("the addons value must be an array")
                 .into_iter().map(|v| v.as_str().unwrap().to_string()).collect();
             config.odoo_path = odoo_conf.get("odooPath").expect("odooPath must exist").as_str().expect("odooPath must be a String").to_string();
+        } else if let Some(odoo_path) = _odoo_path {
+            //paths given directly in the settings, by the clients without named configurations
+            config.odoo_path = odoo_path;
+            if let Some(addons) = _addons {
+                config.addons = addons;
+            }
+        } else if project_config.as_ref().is_some_and(|project_config| project_config.odoo_path.is_some()) {
+            session.log_message(MessageType::INFO, format!("Using the odoo path of {}", config.project_config.as_ref().unwrap()));
         } else {
             config.addons = vec![];
             config.odoo_path = S!("");
             session.log_message(MessageType::ERROR, S!("Unable to find selected configuration. No odoo path has been found."));
         }
-        config.python_path = python_path.clone();
+        config.python_path = match python_path.or(project_config.and_then(|project_config| project_config.python_path)) {
+            Some(python_path) => python_path,
+            None => {
+                session.log_message(MessageType::WARNING, S!("No PythonPath provided. Be sure that your editor support the route Odoo/getPythonPath and that route always return a result. Using 'python3' instead"));
+                S!("python3")
+            }
+        };
         config.refresh_mode = _refresh_mode;
         config.auto_save_delay = _auto_save_delay;
+        config.max_refresh_delay = _max_refresh_delay;
+        config.reload_delay = _reload_delay;
+        config.no_delay = session.sync_odoo.config.no_delay; //given by the command line
         config.ac_filter_model_names = _ac_filter_model_names;
         config.diag_missing_imports = _diag_missing_imports;
+        config.hook_sets = _hook_sets;
+        config.on_type_formatting = _on_type_formatting;
+        config.range_formatting = _range_formatting;
+        config.definition_target = _definition_target;
+        config.large_file_threshold = _large_file_threshold;
+        config.slow_request_threshold = _slow_request_threshold;
+        config.spell_check = _spell_check;
+        config.access_hints = _access_hints;
+        config.populate_checks = _populate_checks;
+        config.addons_policies.extend(_addons_policies);
+        config.external_paths.extend(_external_paths);
+        if let Some(strict_mode) = _strict_mode {
+            config.strict_mode = strict_mode;
+        }
 
         debug!("Final config: {:?}", config);
         Ok(config)
     }
 
+    /* Load the odools.toml of the workspace, and publish the diagnostics of its validation */
+    fn load_project_config(session: &mut SessionInfo) -> Option<ProjectConfig> {
+        let workspace_folders = session.sync_odoo.get_file_mgr().borrow().get_workspace_folders().clone();
+        let path = ProjectConfig::find(&workspace_folders)?;
+        let (project_config, diagnostics) = ProjectConfig::load(&path);
+        if !diagnostics.is_empty() {
+            session.log_message(MessageType::WARNING, format!("{} contains invalid settings. They are ignored", project_config.path));
+        }
+        Odoo::publish_project_config_diagnostics(session, &project_config.path, diagnostics);
+        Some(project_config)
+    }
+
+    fn publish_project_config_diagnostics(session: &mut SessionInfo, path: &String, mut diagnostics: Vec<Diagnostic>) {
+        diagnostics.iter_mut().for_each(add_code_description);
+        session.send_notification::<PublishDiagnosticsParams>(notification::PublishDiagnostics::METHOD, PublishDiagnosticsParams {
+            uri: FileMgr::pathname2uri(path),
+            diagnostics,
+            version: None,
+        });
+    }
+
     pub fn init(session: &mut SessionInfo) {
         let start = std::time::Instant::now();
         session.log_message(MessageType::LOG, String::from("Building new Odoo knowledge database"));
         let config = Odoo::update_configuration(session);
         match config {
             Ok(config) => {
+                session.update_event_delays(config.event_delays());
                 SyncOdoo::init(session, config);
                 session.log_message(MessageType::LOG, format!("End building database in {} seconds. {} detected modules.",
                     (std::time::Instant::now() - start).as_secs(),
@@ -947,6 +1886,15 @@ impl Odoo {
             params.text_document_position_params.position.line,
             params.text_document_position_params.position.character));
         let path = FileMgr::uri2pathname(params.text_document_position_params.text_document.uri.as_str());
+        if params.text_document_position_params.text_document.uri.to_string().ends_with("__manifest__.py") {
+            let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
+            if let Some(file_info) = file_info {
+                if file_info.borrow().ast.is_some() {
+                    return Ok(HoverFeature::get_manifest_hover(session, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+                }
+            }
+            return Ok(None);
+        }
         if params.text_document_position_params.text_document.uri.to_string().ends_with(".py") {
             if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
                 let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
@@ -960,6 +1908,26 @@ impl Odoo {
         Ok(None)
     }
 
+    pub fn handle_signature_help(session: &mut SessionInfo, params: SignatureHelpParams) -> Result<Option<SignatureHelp>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let path = FileMgr::uri2pathname(params.text_document_position_params.text_document.uri.as_str());
+        if !path.ends_with(".py") {
+            return Ok(None);
+        }
+        let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) else {
+            return Ok(None);
+        };
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path);
+        if let Some(file_info) = file_info {
+            if file_info.borrow().ast.is_some() {
+                return Ok(SignatureHelpFeature::get_signature_help(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+            }
+        }
+        Ok(None)
+    }
+
     pub fn handle_goto_definition(session: &mut SessionInfo, params: GotoDefinitionParams) -> Result<Option<GotoDefinitionResponse>, ResponseError> {
         if session.sync_odoo.state_init == InitState::NOT_READY {
             return Ok(None);
@@ -969,6 +1937,22 @@ impl Odoo {
             params.text_document_position_params.position.line,
             params.text_document_position_params.position.character));
         let path = FileMgr::uri2pathname(params.text_document_position_params.text_document.uri.as_str());
+        if params.text_document_position_params.text_document.uri.to_string().ends_with("__manifest__.py") {
+            let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
+            if let Some(file_info) = file_info {
+                if file_info.borrow().ast.is_some() {
+                    return Ok(DefinitionFeature::get_manifest_location(session, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+                }
+            }
+            return Ok(None);
+        }
+        if params.text_document_position_params.text_document.uri.to_string().ends_with(".xml") {
+            let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
+            if let Some(file_info) = file_info {
+                return Ok(DefinitionFeature::get_server_action_location(session, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+            }
+            return Ok(None);
+        }
         if params.text_document_position_params.text_document.uri.to_string().ends_with(".py") {
             if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
                 let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
@@ -982,6 +1966,112 @@ impl Odoo {
         Ok(None)
     }
 
+    pub fn handle_goto_implementation(session: &mut SessionInfo, params: GotoImplementationParams) -> Result<Option<GotoImplementationResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("GoToImplementation requested on {} at {} - {}",
+            params.text_document_position_params.text_document.uri.to_string(),
+            params.text_document_position_params.position.line,
+            params.text_document_position_params.position.character));
+        let path = FileMgr::uri2pathname(params.text_document_position_params.text_document.uri.as_str());
+        if params.text_document_position_params.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
+                if let Some(file_info) = file_info {
+                    if file_info.borrow().ast.is_some() {
+                        return Ok(ImplementationFeature::get_implementations(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn handle_references(session: &mut SessionInfo, params: ReferenceParams) -> Result<Option<Vec<Location>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("References requested on {} at {} - {}",
+            params.text_document_position.text_document.uri.to_string(),
+            params.text_document_position.position.line,
+            params.text_document_position.position.character));
+        let path = FileMgr::uri2pathname(params.text_document_position.text_document.uri.as_str());
+        let mut progress = RequestProgress::new(params.work_done_progress_params.work_done_token.clone(), params.partial_result_params.partial_result_token.clone());
+        if params.text_document_position.text_document.uri.to_string().ends_with(".xml") {
+            progress.begin(session, "Searching references");
+            let references = ReferenceFeature::get_xml_references(session, &path, &params.text_document_position.position, &mut progress);
+            progress.end(session, None);
+            return Ok(references);
+        }
+        if params.text_document_position.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
+                if let Some(file_info) = file_info {
+                    if file_info.borrow().ast.is_some() {
+                        progress.begin(session, "Searching references");
+                        let references = ReferenceFeature::get_references(session, &file_symbol, &file_info, params.text_document_position.position.line, params.text_document_position.position.character, &mut progress);
+                        progress.end(session, None);
+                        return Ok(references);
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn handle_prepare_rename(session: &mut SessionInfo, params: TextDocumentPositionParams) -> Result<Option<PrepareRenameResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let path = FileMgr::uri2pathname(params.text_document.uri.as_str());
+        let Some((file_symbol, file_info)) = Odoo::get_python_file(session, &path) else {
+            return Ok(None);
+        };
+        RenameFeature::prepare_rename(session, &file_symbol, &file_info, params.position.line, params.position.character)
+            .map(Some)
+            .map_err(Odoo::rename_error)
+    }
+
+    pub fn handle_rename(session: &mut SessionInfo, params: RenameParams) -> Result<Option<WorkspaceEdit>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Rename requested on {} at {} - {}",
+            params.text_document_position.text_document.uri.to_string(),
+            params.text_document_position.position.line,
+            params.text_document_position.position.character));
+        let path = FileMgr::uri2pathname(params.text_document_position.text_document.uri.as_str());
+        let Some((file_symbol, file_info)) = Odoo::get_python_file(session, &path) else {
+            return Ok(None);
+        };
+        let mut progress = RequestProgress::new(params.work_done_progress_params.work_done_token.clone(), None);
+        RenameFeature::rename(session, &file_symbol, &file_info, params.text_document_position.position.line, params.text_document_position.position.character, &params.new_name, &mut progress)
+            .map(Some)
+            .map_err(Odoo::rename_error)
+    }
+
+    /* The message of a refused rename is shown to the user by the client */
+    fn rename_error(message: String) -> ResponseError {
+        ResponseError {
+            code: -32803, //RequestFailed
+            message,
+            data: None,
+        }
+    }
+
+    fn get_python_file(session: &mut SessionInfo, path: &String) -> Option<(Rc<RefCell<Symbol>>, Rc<RefCell<FileInfo>>)> {
+        if !path.ends_with(".py") {
+            return None;
+        }
+        let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone()))?;
+        let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(path)?;
+        if file_info.borrow().ast.is_none() {
+            return None;
+        }
+        Some((file_symbol, file_info))
+    }
+
     pub fn handle_autocomplete(session: &mut SessionInfo ,params: CompletionParams) -> Result<Option<CompletionResponse>, ResponseError> {
         if session.sync_odoo.state_init == InitState::NOT_READY {
             return Ok(None);
@@ -992,6 +2082,13 @@ impl Odoo {
             params.text_document_position.position.character
             ));
         let path = FileMgr::uri2pathname(params.text_document_position.text_document.uri.as_str());
+        if params.text_document_position.text_document.uri.to_string().ends_with(".xml") {
+            let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
+            if let Some(file_info) = file_info {
+                return Ok(CompletionFeature::autocomplete_server_action(session, &file_info, params.text_document_position.position.line, params.text_document_position.position.character));
+            }
+            return Ok(None);
+        }
         if params.text_document_position.text_document.uri.to_string().ends_with(".py") {
             if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
                 let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
@@ -1005,22 +2102,255 @@ impl Odoo {
         Ok(None)
     }
 
+    /* Complete the documentation of a completion item, for the items that are sent without it, like model fields */
+    pub fn handle_completion_resolve(session: &mut SessionInfo, params: CompletionItem) -> Result<Option<CompletionItem>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(Some(params));
+        }
+        Ok(Some(CompletionFeature::resolve(session, params)))
+    }
+
+    pub fn handle_on_type_formatting(session: &mut SessionInfo, params: DocumentOnTypeFormattingParams) -> Result<Option<Vec<TextEdit>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY || !session.sync_odoo.config.on_type_formatting {
+            return Ok(None);
+        }
+        let path = FileMgr::uri2pathname(params.text_document_position.text_document.uri.as_str());
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path);
+        if let Some(file_info) = file_info {
+            return Ok(FormattingFeature::on_type_formatting(&file_info, &params));
+        }
+        Ok(None)
+    }
+
+    pub fn handle_range_formatting(session: &mut SessionInfo, params: DocumentRangeFormattingParams) -> Result<Option<Vec<TextEdit>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY || !session.sync_odoo.config.range_formatting {
+            return Ok(None);
+        }
+        let path = FileMgr::uri2pathname(params.text_document.uri.as_str());
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path);
+        if let Some(file_info) = file_info {
+            return Ok(FormattingFeature::range_formatting(&file_info, &params));
+        }
+        Ok(None)
+    }
+
+    pub fn handle_check_integrity(session: &mut SessionInfo) -> Result<Option<CheckIntegrityResult>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let result = IntegrityChecker::check(session);
+        session.log_message(MessageType::INFO, format!("Integrity check done on {} symbols: {} violation(s) found", result.checked_symbols, result.violations.len()));
+        for violation in result.violations.iter() {
+            warn!("Integrity violation: {}", violation);
+        }
+        Ok(Some(result))
+    }
+
+    pub fn handle_export_pot_preview(session: &mut SessionInfo, params: ExportPotPreviewParams) -> Result<Option<ExportPotPreviewResult>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(Some(TranslationFeature::export_pot_preview(session, &params)))
+    }
+
+    pub fn handle_find_unused_xml_ids(session: &mut SessionInfo, params: FindUnusedXmlIdsParams) -> Result<Option<FindUnusedXmlIdsResult>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(Some(XmlIdFeature::find_unused_xml_ids(session, &params)))
+    }
+
+    pub fn handle_export_module_graph(session: &mut SessionInfo, params: ExportModuleGraphParams) -> Result<Option<ExportModuleGraphResult>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(Some(ModuleGraph::export(session, &params)))
+    }
+
+    pub fn handle_get_model_source(session: &mut SessionInfo, params: GetModelSourceParams) -> Result<Option<GetModelSourceResult>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(ModelSourceFeature::get_model_source(session, &params))
+    }
+
+    pub fn handle_build_stats(session: &mut SessionInfo) -> Result<Option<BuildStatsResult>, ResponseError> {
+        Ok(Some(BuildStatsResult {
+            modules: session.sync_odoo.modules.len(),
+            models: session.sync_odoo.models.len(),
+            files: session.sync_odoo.get_file_mgr().borrow().files.len(),
+            init_ms: session.sync_odoo.request_stats.init_ms,
+            requests: session.sync_odoo.request_stats.latencies(),
+        }))
+    }
+
+    /* Can be run before the end of the initialization, as it is used to find why the initialization fails */
+    pub fn handle_doctor(session: &mut SessionInfo) -> Result<Option<DoctorReport>, ResponseError> {
+        let report = Doctor::run(&session.sync_odoo.config, &session.sync_odoo.stdlib_dir, &session.sync_odoo.stubs_dirs);
+        Ok(Some(report))
+    }
+
+    pub fn handle_clear_cache(session: &mut SessionInfo) -> Result<Option<ClearCacheResult>, ResponseError> {
+        session.log_message(MessageType::INFO, S!("Clearing the cache on request of the client"));
+        let config = session.sync_odoo.config.clone();
+        SyncOdoo::reset(session, config);
+        Ok(session.sync_odoo.cache_fingerprint.as_ref().map(|fingerprint| ClearCacheResult {
+            fingerprint: fingerprint.key(),
+        }))
+    }
+
+    pub fn handle_reload_module(session: &mut SessionInfo, params: ReloadModuleParams) -> Result<Option<ReloadModuleResult>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let Some(module) = session.sync_odoo.modules.get(&params.module).and_then(|module| module.upgrade()) else {
+            return Err(ResponseError {
+                code: -32803, //RequestFailed
+                message: format!("Module {} is not loaded", params.module),
+                data: None,
+            });
+        };
+        let path = module.borrow().as_module_package().path.clone();
+        session.log_message(MessageType::INFO, format!("Reloading module {} on request of the client", params.module));
+        SyncOdoo::reload_path(session, &PathBuf::from(&path));
+        SyncOdoo::process_rebuilds(session);
+        Ok(Some(ReloadModuleResult { path }))
+    }
+
+    pub fn handle_scaffold(session: &mut SessionInfo, params: ScaffoldParams) -> Result<Option<ScaffoldResult>, ResponseError> {
+        let failure = |message: String| ResponseError {
+            code: -32803, //RequestFailed
+            message,
+            data: None,
+        };
+        if !ScaffoldFeature::is_valid_name(&params.name) {
+            return Err(failure(format!("{} is not a valid module name: use lowercase letters, digits and underscores", params.name)));
+        }
+        let Some(addons_path) = params.addons_path.clone().or_else(|| session.sync_odoo.config.addons.first().cloned()) else {
+            return Err(failure(S!("No addons path to create the module in")));
+        };
+        let module_dir = PathBuf::from(&addons_path).join(&params.name);
+        if module_dir.exists() {
+            return Err(failure(format!("{} already exists", module_dir.sanitize())));
+        }
+        if !ScaffoldFeature::supports_create(session) {
+            return Err(failure(S!("The client can't create files with a workspace edit")));
+        }
+        let edit = ScaffoldFeature::build_edit(&module_dir, &params.name, session.sync_odoo.version_major);
+        let result = session.send_request::<ApplyWorkspaceEditParams, ApplyWorkspaceEditResponse>(ApplyWorkspaceEdit::METHOD, ApplyWorkspaceEditParams {
+            label: Some(format!("Scaffold module {}", params.name)),
+            edit,
+        });
+        let applied = matches!(result, Ok(Some(ApplyWorkspaceEditResponse { applied: true, .. })));
+        Ok(Some(ScaffoldResult { path: module_dir.sanitize(), applied }))
+    }
+
+    pub fn handle_toggle_focus(session: &mut SessionInfo, params: ToggleFocusParams) -> Result<Option<ToggleFocusResult>, ResponseError> {
+        SyncOdoo::toggle_focus(session, &params.modules);
+        let modules = session.sync_odoo.focus.modules().clone();
+        if modules.is_empty() {
+            session.log_message(MessageType::INFO, S!("Focus cleared. All the modules are validated"));
+        } else {
+            session.log_message(MessageType::INFO, format!("Focus on {}. The other modules are not validated", modules.join(", ")));
+        }
+        Ok(Some(ToggleFocusResult { modules }))
+    }
+
+    pub fn handle_workspace_symbol(session: &mut SessionInfo, params: WorkspaceSymbolParams) -> Result<Option<WorkspaceSymbolResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(WorkspaceSymbolFeature::get_workspace_symbols(session, &params.query))
+    }
+
+    pub fn handle_document_symbol(session: &mut SessionInfo, params: DocumentSymbolParams) -> Result<Option<DocumentSymbolResponse>, ResponseError> {
+        let path = FileMgr::uri2pathname(params.text_document.uri.as_str());
+        Ok(DocumentSymbolFeature::get_document_symbols(session, &path))
+    }
+
+    pub fn handle_document_link(session: &mut SessionInfo, params: DocumentLinkParams) -> Result<Option<Vec<DocumentLink>>, ResponseError> {
+        let path = FileMgr::uri2pathname(params.text_document.uri.as_str());
+        Ok(DocumentLinkFeature::get_document_links(session, &path))
+    }
+
+    pub fn handle_document_color(session: &mut SessionInfo, params: DocumentColorParams) -> Result<Option<Vec<ColorInformation>>, ResponseError> {
+        let path = FileMgr::uri2pathname(params.text_document.uri.as_str());
+        Ok(DocumentColorFeature::get_document_colors(session, &path))
+    }
+
+    pub fn handle_inline_value(session: &mut SessionInfo, params: InlineValueParams) -> Result<Option<Vec<InlineValue>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let path = FileMgr::uri2pathname(params.text_document.uri.as_str());
+        if !path.ends_with(".py") {
+            return Ok(None);
+        }
+        let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) else {
+            return Ok(None);
+        };
+        Ok(InlineValueFeature::get_inline_values(session, &file_symbol, &path, &params))
+    }
+
+    pub fn handle_color_presentation(params: ColorPresentationParams) -> Result<Option<Vec<ColorPresentation>>, ResponseError> {
+        Ok(Some(DocumentColorFeature::get_color_presentations(&params)))
+    }
+
+    pub fn handle_code_action(session: &mut SessionInfo, params: CodeActionParams) -> Result<Option<CodeActionResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let path = FileMgr::uri2pathname(params.text_document.uri.as_str());
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path);
+        if let Some(file_info) = file_info {
+            return Ok(CodeActionFeature::get_code_actions(session, &file_info, &params));
+        }
+        Ok(None)
+    }
+
     pub fn handle_did_change_configuration(session: &mut SessionInfo, _params: DidChangeConfigurationParams) {
         let old_config = session.sync_odoo.config.clone();
         match Odoo::update_configuration(session) {
             Ok (config) => {
                 session.sync_odoo.config = config.clone();
+                CrashReport::set_config(&config);
+                if let Some(old_project_config) = old_config.project_config.as_ref() {
+                    if config.project_config.as_ref() != Some(old_project_config) {
+                        Odoo::publish_project_config_diagnostics(session, old_project_config, vec![]);
+                    }
+                }
+                if config.exclude != old_config.exclude || config.diag_severities != old_config.diag_severities {
+                    let file_mgr = session.sync_odoo.get_file_mgr();
+                    file_mgr.borrow().republish_diagnostics(session);
+                }
                 if config.odoo_path != old_config.odoo_path ||
+                    config.python_path != old_config.python_path ||
                     config.addons != old_config.addons ||
+                    config.addons_policies != old_config.addons_policies ||
+                    config.external_paths != old_config.external_paths ||
+                    config.hook_sets != old_config.hook_sets ||
                     config.additional_stubs != old_config.additional_stubs ||
                     config.stdlib != old_config.stdlib {
                         SyncOdoo::reset(session, config);
                 } else {
-                    if old_config.diag_missing_imports != session.sync_odoo.config.diag_missing_imports {
+                    if old_config.diag_missing_imports != session.sync_odoo.config.diag_missing_imports ||
+                        old_config.spell_check != session.sync_odoo.config.spell_check ||
+                        old_config.access_hints != session.sync_odoo.config.access_hints ||
+                        old_config.populate_checks != session.sync_odoo.config.populate_checks ||
+                        old_config.strict_mode != session.sync_odoo.config.strict_mode ||
+                        old_config.dictionary != session.sync_odoo.config.dictionary {
                         SyncOdoo::refresh_evaluations(session);
                     }
-                    if old_config.auto_save_delay != session.sync_odoo.config.auto_save_delay {
-                        session.update_auto_refresh_delay(session.sync_odoo.config.auto_save_delay);
+                    if old_config.event_delays() != session.sync_odoo.config.event_delays() {
+                        session.update_event_delays(session.sync_odoo.config.event_delays());
+                    }
+                    if old_config.large_file_threshold != session.sync_odoo.config.large_file_threshold {
+                        //the cached files keep their text: only the ones crossing the new threshold are built again
+                        let changed = session.sync_odoo.get_file_mgr().borrow().update_large_files(session.sync_odoo.config.large_file_threshold);
+                        for path in changed.iter().filter(|path| path.ends_with(".py")) {
+                            SyncOdoo::reload_path(session, &PathBuf::from(path));
+                        }
+                        SyncOdoo::process_rebuilds(session);
                     }
                 }
             },
@@ -1035,10 +2365,10 @@ impl Odoo {
         let file_mgr = session.sync_odoo.get_file_mgr();
         let mut file_mgr = file_mgr.borrow_mut();
         for added in params.event.added {
-            file_mgr.add_workspace_folder(added.uri.to_string());
+            file_mgr.add_workspace_folder(FileMgr::uri2pathname(added.uri.as_str()));
         }
         for removed in params.event.removed {
-            file_mgr.remove_workspace_folder(removed.uri.to_string());
+            file_mgr.remove_workspace_folder(FileMgr::uri2pathname(removed.uri.as_str()));
         }
     }
 
@@ -1046,10 +2376,15 @@ impl Odoo {
         let mut to_create = vec![];
         let mut to_delete = vec![];
         let mut to_change = vec![];
+        let mut project_config_changed = false;
         for event in params.changes {
             if event.uri.to_string().contains(".git") {
                 continue;
             }
+            if event.uri.as_str().ends_with(PROJECT_CONFIG_FILE) {
+                project_config_changed = true;
+                continue;
+            }
             match event.typ {
                 FileChangeType::CREATED  => { to_create.push(FileCreate{uri: event.uri.to_string()}); }
                 FileChangeType::DELETED => { to_delete.push(FileDelete{uri: event.uri.to_string()}); }
@@ -1072,6 +2407,9 @@ impl Odoo {
         if !to_change.is_empty() {
             Odoo::handle_file_update(session, &to_change);
         }
+        if project_config_changed {
+            Odoo::handle_did_change_configuration(session, DidChangeConfigurationParams { settings: serde_json::Value::Null });
+        }
     }
 
     fn handle_file_update(session: &mut SessionInfo, file_uris: &Vec<Uri>) {
@@ -1081,7 +2419,7 @@ impl Odoo {
         for uri in file_uris.iter() {
             let path = uri.to_file_path().unwrap();
             session.log_message(MessageType::INFO, format!("File update: {}", path.sanitize()));
-            if Odoo::update_file_cache(session, path.clone(), None, -100) {
+            if Odoo::update_file_cache(session, path.clone(), None, None, -100) {
                 Odoo::update_file_index(session, path, true, false);
             }
         }
@@ -1092,7 +2430,10 @@ impl Odoo {
         // that we use the same base version of the file for future incrementation.
         let path = params.text_document.uri.to_file_path().unwrap();
         session.log_message(MessageType::INFO, format!("File opened: {}", path.sanitize()));
-        if Odoo::update_file_cache(session, path.clone(), Some(&vec![TextDocumentContentChangeEvent{
+        if session.sync_odoo.state_init != InitState::NOT_READY && session.sync_odoo.prioritize_file(&path) {
+            session.request_delayed_rebuild();
+        }
+        if Odoo::update_file_cache(session, path.clone(), Some(&params.text_document.uri), Some(&vec![TextDocumentContentChangeEvent{
             range: None,
             range_length: None,
                 text: params.text_document.text}]), params.text_document.version) {
@@ -1110,6 +2451,10 @@ impl Odoo {
         if let Some(file_info) = file_info {
             file_info.borrow_mut().opened = false;
         }
+        //standalone files are only analyzed while they are opened
+        if session.sync_odoo.tree_from_path(&path).is_ok_and(|tree| SyncOdoo::is_standalone_tree(&tree)) {
+            let _ = SyncOdoo::_unload_path(session, &path, true);
+        }
     }
 
     pub fn handle_did_rename(session: &mut SessionInfo, params: RenameFilesParams) {
@@ -1158,6 +2503,7 @@ impl Odoo {
             //1 - delete old uri
             let _ = SyncOdoo::_unload_path(session, &PathBuf::from(&path), false);
             session.sync_odoo.get_file_mgr().borrow_mut().delete_path(session, &path);
+            session.sync_odoo.xml_fields.remove_file(&path);
         }
         SyncOdoo::process_rebuilds(session);
     }
@@ -1166,7 +2512,7 @@ impl Odoo {
         let path = params.text_document.uri.to_file_path().unwrap();
         session.log_message(MessageType::INFO, format!("File changed: {}", path.sanitize()));
         let version = params.text_document.version;
-        if Odoo::update_file_cache(session, path.clone(), Some(&params.content_changes), version) {
+        if Odoo::update_file_cache(session, path.clone(), Some(&params.text_document.uri), Some(&params.content_changes), version) {
             if (session.sync_odoo.config.refresh_mode != RefreshMode::AfterDelay && session.sync_odoo.config.refresh_mode != RefreshMode::Adaptive) || session.sync_odoo.state_init == InitState::NOT_READY {
                 return
             }
@@ -1184,29 +2530,45 @@ impl Odoo {
     }
 
     // return true if the file has been updated, is valid for an index reload, and contents have been changed
-    fn update_file_cache(session: &mut SessionInfo, path: PathBuf, content: Option<&Vec<TextDocumentContentChangeEvent>>, version: i32) -> bool {
+    // path is the canonical path of the file, and client_uri the uri of the document opened by the client, that the diagnostics are published on
+    fn update_file_cache(session: &mut SessionInfo, path: PathBuf, client_uri: Option<&Uri>, content: Option<&Vec<TextDocumentContentChangeEvent>>, version: i32) -> bool {
         if path.extension().is_some() && path.extension().unwrap() == "py" {
             let tree = session.sync_odoo.tree_from_path(&path);
-            if let Err(_e) = tree { //is not part of odoo (or not in addons path)
+            //is not part of odoo (or not in addons path), and is not a standalone file being opened or edited
+            if tree.is_err() && (version < 0 || !session.sync_odoo.is_standalone_candidate(&path)) {
                 return false;
             }
             session.log_message(MessageType::INFO, format!("File Change Event: {}, version {}", path.to_str().unwrap(), version));
             let (file_updated, file_info) = session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, &path.sanitize(), content, Some(version), false);
+            if let Some(client_uri) = client_uri {
+                file_info.borrow_mut().client_uri = Some(client_uri.clone());
+            }
             file_info.borrow_mut().publish_diagnostics(session); //To push potential syntax errors or refresh previous one
             return file_info.borrow().valid && (!file_info.borrow().opened || version >= 0) && file_updated;
         }
+        if path.extension().is_some_and(|ext| ext == "xml") && session.sync_odoo.get_file_mgr().borrow().is_in_workspace(&path.sanitize()) {
+            //xml files are not indexed, only the python code of their server actions and crons, their reports and views are validated
+            let (file_updated, file_info) = session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, &path.sanitize(), content, Some(version), false);
+            if let Some(client_uri) = client_uri {
+                file_info.borrow_mut().client_uri = Some(client_uri.clone());
+            }
+            if file_updated {
+                let text = file_info.borrow().get_content().unwrap_or_default();
+                let mut diagnostics = ServerActionCode::validate(session, &text);
+                diagnostics.extend(ReportValidator::validate(session, &path.sanitize(), &text));
+                diagnostics.extend(ViewValidator::validate(session, &text));
+                session.sync_odoo.xml_fields.index_file(&path.sanitize(), &text);
+                file_info.borrow_mut().replace_diagnostics(BuildSteps::VALIDATION, diagnostics);
+                file_info.borrow_mut().publish_diagnostics(session);
+            }
+        }
         false
     }
 
     pub fn update_file_index(session: &mut SessionInfo, path: PathBuf, is_save: bool, is_open: bool) {
         if path.extension().is_some() && path.extension().unwrap() == "py" {
             if is_open || (is_save && session.sync_odoo.config.refresh_mode == RefreshMode::OnSave) {
-                let tree = session.sync_odoo.tree_from_path(&path);
-                if !tree.is_err() { //is part of odoo (and in addons path)
-                    let tree = tree.unwrap().clone();
-                    let _ = SyncOdoo::_unload_path(session, &path, false);
-                    SyncOdoo::search_symbols_to_rebuild(session, &tree);
-                }
+                SyncOdoo::reload_path(session, &path);
                 SyncOdoo::process_rebuilds(session);
             } else {
                 if session.sync_odoo.config.refresh_mode == RefreshMode::AfterDelay || session.sync_odoo.config.refresh_mode == RefreshMode::Adaptive {
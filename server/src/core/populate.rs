@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use ruff_python_ast::{Expr, ExprStringLiteral, Operator, Stmt, StmtFunctionDef};
+use ruff_text_size::TextRange;
+
+/* Support of the populate framework (odoo/tools/populate.py). It is only used by the developers of Odoo to fill a database
+with generated records, so its rules are opt-in, with the populateChecks setting */
+pub const POPULATE_FACTORIES: &str = "_populate_factories";
+pub const POPULATE_SIZES: &str = "_populate_sizes";
+pub const POPULATE_SIZE_NAMES: [&str; 3] = ["small", "medium", "large"];
+
+/* Return the field names of the factories returned by a _populate_factories method, like "name" in
+
+    def _populate_factories(self):
+        factories = [("name", populate.constant("partner_{counter}"))]
+        return factories + [("active", populate.cartesian([True, False]))]
+
+The returned value can be a list, a variable assigned in the method, or a sum of them. Factories built dynamically are ignored */
+pub fn factory_field_names(func: &StmtFunctionDef) -> Vec<&ExprStringLiteral> {
+    let mut res = vec![];
+    let mut variables: HashMap<&str, Vec<&Expr>> = HashMap::new();
+    for stmt in func.body.iter() {
+        match stmt {
+            Stmt::Assign(assign) => {
+                if let [Expr::Name(name)] = assign.targets.as_slice() {
+                    variables.entry(name.id.as_str()).or_default().push(&assign.value);
+                }
+            },
+            Stmt::AugAssign(aug_assign) if aug_assign.op == Operator::Add => {
+                if let Expr::Name(name) = aug_assign.target.as_ref() {
+                    variables.entry(name.id.as_str()).or_default().push(&aug_assign.value);
+                }
+            },
+            Stmt::Return(ret) => {
+                if let Some(value) = ret.value.as_ref() {
+                    collect_factories(value, &variables, &mut res);
+                }
+            },
+            _ => {}
+        }
+    }
+    res
+}
+
+fn collect_factories<'a>(expr: &'a Expr, variables: &HashMap<&str, Vec<&'a Expr>>, res: &mut Vec<&'a ExprStringLiteral>) {
+    let elts = match expr {
+        Expr::List(list) => &list.elts,
+        Expr::Tuple(tuple) => &tuple.elts,
+        Expr::BinOp(bin_op) if bin_op.op == Operator::Add => {
+            collect_factories(&bin_op.left, variables, res);
+            collect_factories(&bin_op.right, variables, res);
+            return;
+        },
+        Expr::Name(name) => {
+            for value in variables.get(name.id.as_str()).into_iter().flatten() {
+                collect_factories(value, variables, res);
+            }
+            return;
+        },
+        _ => return
+    };
+    for elt in elts.iter() {
+        if let Some(Expr::StringLiteral(field_name)) = elt.as_tuple_expr().and_then(|factory| factory.elts.first()) {
+            if !res.iter().any(|known| known.range == field_name.range) {
+                res.push(field_name);
+            }
+        }
+    }
+}
+
+/* Return the field name of a factory at the given offset, if the offset is in a _populate_factories method of a class */
+pub fn find_factory_field_name_at(ast: &Vec<Stmt>, offset: u32) -> Option<(String, TextRange)> {
+    for stmt in ast.iter() {
+        let Stmt::ClassDef(class_def) = stmt else {
+            continue;
+        };
+        if offset < class_def.range.start().to_u32() || offset > class_def.range.end().to_u32() {
+            continue;
+        }
+        for class_stmt in class_def.body.iter() {
+            let Stmt::FunctionDef(func) = class_stmt else {
+                continue;
+            };
+            if func.name.as_str() != POPULATE_FACTORIES {
+                continue;
+            }
+            let field_name = factory_field_names(func).into_iter()
+                .find(|field_name| field_name.range.start().to_u32() <= offset && offset <= field_name.range.end().to_u32());
+            if let Some(field_name) = field_name {
+                return Some((field_name.value.to_string(), field_name.range));
+            }
+        }
+    }
+    None
+}
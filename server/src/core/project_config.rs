@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+
+use crate::constants::EXTENSION_NAME;
+use crate::core::config::AddonsPolicy;
+use crate::utils::PathSanitizer as _;
+use crate::S;
+
+pub const PROJECT_CONFIG_FILE: &str = "odools.toml";
+
+/* Settings of a project, checked in at the root of a workspace folder so a team shares the same behavior of the server.
+They are merged over the defaults, and the settings of the client are merged over them. Example:
+
+    odoo_path = "../odoo"                 # relative paths are resolved from the folder of the file
+    addons = ["./addons", "../enterprise"]
+    python_path = ".venv/bin/python"
+    exclude = ["addons/*/migrations/**"]  # glob patterns, from the folder of the file, of files whose diagnostics are not reported
+    dictionary = ["webshop", "payslip"]   # words accepted by the spell check of the labels (spellCheck setting)
+    strict = true                         # report the expressions that can't be evaluated (strictMode setting)
+
+    [diagnostics]
+    OLS20201 = "error"                    # error, warning, info, hint or none to disable the code
+
+    [addons_policy]
+    "../oca" = "suggestOnly"              # full, suggestOnly (indexed but never validated) or ignore (not loaded)
+
+    [external]
+    "./odoo" = true                       # vendored code treated as external, even in the workspace. false for the opposite
+*/
+#[derive(Debug, Default, Clone)]
+pub struct ProjectConfig {
+    pub path: String,
+    pub odoo_path: Option<String>,
+    pub addons: Option<Vec<String>>,
+    pub python_path: Option<String>,
+    pub exclude: Vec<String>,
+    pub dictionary: Vec<String>,
+    pub strict: Option<bool>,
+    pub diag_severities: HashMap<String, Option<DiagnosticSeverity>>,
+    pub addons_policies: HashMap<String, AddonsPolicy>,
+    pub external_paths: HashMap<String, bool>,
+}
+
+impl ProjectConfig {
+
+    /* Return the path of the first project settings file found at the root of the workspace folders */
+    pub fn find(workspace_folders: &Vec<String>) -> Option<PathBuf> {
+        workspace_folders.iter().map(|folder| PathBuf::from(folder).join(PROJECT_CONFIG_FILE)).find(|path| path.is_file())
+    }
+
+    /* Parse the settings file. Invalid entries are ignored and reported by the returned diagnostics, positioned in the file */
+    pub fn load(path: &PathBuf) -> (ProjectConfig, Vec<Diagnostic>) {
+        let mut config = ProjectConfig { path: path.sanitize(), ..Default::default() };
+        let mut diagnostics = vec![];
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                diagnostics.push(ProjectConfig::diagnostic(Range::default(), format!("Unable to read {}: {}", PROJECT_CONFIG_FILE, e)));
+                return (config, diagnostics);
+            }
+        };
+        let table = match content.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(e) => {
+                let range = e.span().map(|span| Range::new(ProjectConfig::position(&content, span.start), ProjectConfig::position(&content, span.end)))
+                    .unwrap_or_default();
+                diagnostics.push(ProjectConfig::diagnostic(range, format!("Invalid {}: {}", PROJECT_CONFIG_FILE, e.message())));
+                return (config, diagnostics);
+            }
+        };
+        let root = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        for (key, value) in table.iter() {
+            let range = ProjectConfig::key_range(&content, key);
+            match key.as_str() {
+                "odoo_path" => match value.as_str() {
+                    Some(odoo_path) => config.odoo_path = Some(root.join(odoo_path).sanitize()),
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("odoo_path must be a string"))),
+                },
+                "addons" => match ProjectConfig::string_array(value) {
+                    Some(addons) => config.addons = Some(addons.iter().map(|addon| root.join(addon).sanitize()).collect()),
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("addons must be a list of strings"))),
+                },
+                "python_path" => match value.as_str() {
+                    //a command like "python3" is kept as is, a path is resolved from the folder of the file
+                    Some(python_path) if python_path.contains('/') || python_path.contains('\\') => config.python_path = Some(root.join(python_path).sanitize()),
+                    Some(python_path) => config.python_path = Some(python_path.to_string()),
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("python_path must be a string"))),
+                },
+                "exclude" => match ProjectConfig::string_array(value) {
+                    Some(patterns) => {
+                        for pattern in patterns.into_iter() {
+                            if let Err(e) = glob::Pattern::new(&pattern) {
+                                diagnostics.push(ProjectConfig::diagnostic(range, format!("Invalid pattern '{}': {}", pattern, e)));
+                            } else {
+                                config.exclude.push(pattern);
+                            }
+                        }
+                    },
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("exclude must be a list of glob patterns"))),
+                },
+                "dictionary" => match ProjectConfig::string_array(value) {
+                    Some(words) => config.dictionary = words,
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("dictionary must be a list of words"))),
+                },
+                "strict" => match value.as_bool() {
+                    Some(strict) => config.strict = Some(strict),
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("strict must be a boolean"))),
+                },
+                "diagnostics" => match value.as_table() {
+                    Some(severities) => {
+                        for (code, severity) in severities.iter() {
+                            let code_range = ProjectConfig::key_range(&content, code);
+                            match severity.as_str().map(ProjectConfig::parse_severity) {
+                                Some(Ok(severity)) => { config.diag_severities.insert(code.clone(), severity); },
+                                _ => diagnostics.push(ProjectConfig::diagnostic(code_range, format!("Invalid severity for {}. Use error, warning, info, hint or none", code))),
+                            }
+                        }
+                    },
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("diagnostics must be a table of codes and severities"))),
+                },
+                "addons_policy" => match value.as_table() {
+                    Some(policies) => {
+                        for (addons_path, policy) in policies.iter() {
+                            match policy.as_str().map(AddonsPolicy::from_str) {
+                                Some(Ok(policy)) => { config.addons_policies.insert(root.join(addons_path).sanitize_canonical(), policy); },
+                                _ => diagnostics.push(ProjectConfig::diagnostic(range, format!("Invalid policy for {}. Use full, suggestOnly or ignore", addons_path))),
+                            }
+                        }
+                    },
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("addons_policy must be a table of addons paths and policies"))),
+                },
+                "external" => match value.as_table() {
+                    Some(paths) => {
+                        for (path, external) in paths.iter() {
+                            match external.as_bool() {
+                                Some(external) => { config.external_paths.insert(root.join(path).sanitize_canonical(), external); },
+                                None => diagnostics.push(ProjectConfig::diagnostic(range, format!("Invalid external flag for {}. Use true or false", path))),
+                            }
+                        }
+                    },
+                    None => diagnostics.push(ProjectConfig::diagnostic(range, S!("external must be a table of paths and booleans"))),
+                },
+                _ => diagnostics.push(ProjectConfig::diagnostic(range, format!("Unknown key: {}", key))),
+            }
+        }
+        (config, diagnostics)
+    }
+
+    fn parse_severity(severity: &str) -> Result<Option<DiagnosticSeverity>, ()> {
+        match severity {
+            "error" => Ok(Some(DiagnosticSeverity::ERROR)),
+            "warning" => Ok(Some(DiagnosticSeverity::WARNING)),
+            "info" => Ok(Some(DiagnosticSeverity::INFORMATION)),
+            "hint" => Ok(Some(DiagnosticSeverity::HINT)),
+            "none" => Ok(None),
+            _ => Err(()),
+        }
+    }
+
+    fn string_array(value: &toml::Value) -> Option<Vec<String>> {
+        value.as_array()?.iter().map(|v| v.as_str().map(|s| s.to_string())).collect()
+    }
+
+    /* Range of the first line defining the key, or the start of the file */
+    fn key_range(content: &str, key: &str) -> Range {
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with('=')) {
+                let start = (line.len() - trimmed.len()) as u32;
+                return Range::new(Position::new(index as u32, start), Position::new(index as u32, start + key.len() as u32));
+            }
+        }
+        Range::default()
+    }
+
+    fn position(content: &str, offset: usize) -> Position {
+        let before = &content[..offset.min(content.len())];
+        let line = before.matches('\n').count() as u32;
+        let character = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) as u32;
+        Position::new(line, character)
+    }
+
+    fn diagnostic(range: Range, message: String) -> Diagnostic {
+        Diagnostic::new(range,
+            Some(DiagnosticSeverity::ERROR),
+            Some(NumberOrString::String(S!("OLS31601"))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None)
+    }
+}
@@ -3,13 +3,15 @@ use std::cell::RefCell;
 use std::vec;
 use anyhow::Error;
 use ruff_text_size::{Ranged, TextRange};
-use ruff_python_ast::{Alias, Expr, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtFor, StmtFunctionDef, StmtIf, StmtTry, StmtWith};
-use lsp_types::Diagnostic;
+use ruff_python_ast::{Alias, Expr, Identifier, Operator, Stmt, StmtAnnAssign, StmtAssign, StmtAugAssign, StmtClassDef, StmtFor, StmtFunctionDef, StmtIf, StmtTry, StmtWith};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
 use tracing::{trace, warn};
 use weak_table::traits::WeakElement;
 use std::path::PathBuf;
 
-use crate::constants::{BuildStatus, BuildSteps, SymType};
+use crate::constants::{BuildStatus, BuildSteps, SymType, EXTENSION_NAME};
+use crate::core::docstring::Docstring;
+use crate::core::create_multi;
 use crate::core::python_utils;
 use crate::core::import_resolver::resolve_import_stmt;
 use crate::core::symbols::symbol::Symbol;
@@ -31,7 +33,8 @@ pub struct PythonArchBuilder {
     current_step: BuildSteps,
     sym_stack: Vec<Rc<RefCell<Symbol>>>,
     __all_symbols_to_add: Vec<(String, TextRange)>,
-    diagnostics: Vec<Diagnostic>
+    diagnostics: Vec<Diagnostic>,
+    degraded: bool, //only index top-level symbols, for large files
 }
 
 impl PythonArchBuilder {
@@ -42,7 +45,8 @@ impl PythonArchBuilder {
             current_step: BuildSteps::ARCH, //dummy, evaluated in load_arch
             sym_stack: vec![symbol],
             __all_symbols_to_add: Vec::new(),
-            diagnostics: vec![]
+            diagnostics: vec![],
+            degraded: false,
         }
     }
 
@@ -75,6 +79,8 @@ impl PythonArchBuilder {
                 self.file.borrow().parent().as_ref().unwrap().upgrade().is_some() &&
                 self.file.borrow().parent().as_ref().unwrap().upgrade().unwrap().borrow().in_workspace()) ||
                 session.sync_odoo.get_file_mgr().borrow().is_in_workspace(path.as_str());
+            //code marked external in the configuration, like a vendored odoo, is never validated even in a workspace folder
+            let in_workspace = in_workspace && session.sync_odoo.config.external_override(path.as_str()) != Some(true);
             self.file.borrow_mut().set_in_workspace(in_workspace);
         }
         let file_info_rc = match self.file_mode {
@@ -84,6 +90,21 @@ impl PythonArchBuilder {
                 },
             false => {session.sync_odoo.get_file_mgr().borrow().get_file_info(&path).unwrap()}
         };
+        if self.file_mode {
+            self.file.borrow_mut().clear_dead_branches();
+        }
+        if self.file_mode && file_info_rc.borrow().large {
+            self.degraded = true;
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(0, 0), Position::new(0, 0)),
+                Some(DiagnosticSeverity::INFORMATION),
+                Some(NumberOrString::String(S!("OLS10001"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("This file is bigger than {} KB: only its top-level symbols are indexed, and it is neither evaluated nor validated. See the largeFileThreshold setting", session.sync_odoo.config.large_file_threshold),
+                None,
+                None,
+            ));
+        }
         if self.file_mode {
             //diagnostics for functions are stored directly on funcs
             let mut file_info = file_info_rc.borrow_mut();
@@ -99,6 +120,9 @@ impl PythonArchBuilder {
             };
             self.visit_node(session, &ast)?;
             self._resolve_all_symbols(session);
+            //the module is looked up once for all the symbols of the file
+            let module = self.file.borrow().find_module().as_ref().map(Rc::downgrade);
+            self.sym_stack[0].borrow().set_owning_module_of_members(&module);
             if self.file_mode {
                 session.sync_odoo.add_to_rebuild_arch_eval(self.sym_stack[0].clone());
             }
@@ -169,7 +193,8 @@ impl PythonArchBuilder {
                 let mut dep_to_add = vec![];
                 let symbol = import_result.symbol.borrow();
                 for (name, loc_syms) in symbol.iter_symbols() {
-                    if all_name_allowed || name_filter.contains(&name) {
+                    //without __all__, the names starting with an underscore are not imported
+                    if (all_name_allowed && !name.starts_with('_')) || name_filter.contains(&name) {
                         let mut variable = self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &name, &import_result.range);
                         let mut loc = variable.borrow_mut();
                         loc.as_variable_mut().is_import_variable = true;
@@ -177,6 +202,18 @@ impl PythonArchBuilder {
                         dep_to_add.push(variable.clone());
                     }
                 }
+                //__all__ of a package can list its submodules, that are not symbols of its __init__.py
+                if symbol.typ() == SymType::PACKAGE {
+                    for name in name_filter.iter().filter(|name| !symbol.iter_symbols().any(|(symbol_name, _)| symbol_name == *name)) {
+                        let Some(submodule) = symbol.get_symbol(&(vec![name.clone()], vec![]), u32::MAX).first().cloned() else {
+                            continue;
+                        };
+                        let variable = self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, name, &import_result.range);
+                        variable.borrow_mut().as_variable_mut().is_import_variable = true;
+                        variable.borrow_mut().as_variable_mut().evaluations = vec![Evaluation::eval_from_symbol(&Rc::downgrade(&submodule))];
+                        dep_to_add.push(variable);
+                    }
+                }
                 drop(symbol);
                 for sym in dep_to_add {
                     let mut sym_bw = sym.borrow_mut();
@@ -185,7 +222,7 @@ impl PythonArchBuilder {
                     let evaluated_type = evaluated_type.get_symbol(session, &mut None, &mut self.diagnostics, None).0;
                     if !evaluated_type.is_expired() {
                         let evaluated_type = evaluated_type.upgrade().unwrap();
-                        let evaluated_type_file = evaluated_type.borrow_mut().get_file().unwrap().clone().upgrade().unwrap();
+                        let evaluated_type_file = evaluated_type.borrow().get_file().unwrap().clone().upgrade().unwrap();
                         if !Rc::ptr_eq(&self.file, &evaluated_type_file) {
                             self.file.borrow_mut().add_dependency(&mut evaluated_type_file.borrow_mut(), self.current_step, BuildSteps::ARCH);
                         }
@@ -215,11 +252,20 @@ impl PythonArchBuilder {
                     self.create_local_symbols_from_import_stmt(session, import_from_stmt.module.as_ref(), &import_from_stmt.names, Some(import_from_stmt.level), &import_from_stmt.range)?
                 },
                 Stmt::AnnAssign(ann_assign_stmt) => {
+                    self.visit_comprehensions(session, stmt);
                     self._visit_ann_assign(session, ann_assign_stmt);
                 },
                 Stmt::Assign(assign_stmt) => {
+                    self.visit_comprehensions(session, stmt);
                     self._visit_assign(session, assign_stmt);
                 },
+                Stmt::AugAssign(aug_assign_stmt) => {
+                    self.visit_comprehensions(session, stmt);
+                    self._visit_aug_assign(aug_assign_stmt);
+                },
+                Stmt::Expr(_) | Stmt::Return(_) => {
+                    self.visit_comprehensions(session, stmt);
+                },
                 Stmt::FunctionDef(function_def_stmt) => {
                     self.visit_func_def(session, function_def_stmt)?;
                 },
@@ -344,11 +390,49 @@ impl PythonArchBuilder {
         }
     }
 
+    /* Extend the value of __all__ with the names of `__all__ += [...]`, so star imports of the file import them too */
+    fn _visit_aug_assign(&mut self, aug_assign_stmt: &StmtAugAssign) {
+        if !self.file_mode || self.sym_stack.len() != 1 || aug_assign_stmt.op != Operator::Add {
+            return;
+        }
+        if !aug_assign_stmt.target.as_name_expr().is_some_and(|target| target.id.as_str() == "__all__") {
+            return;
+        }
+        let added: Vec<Expr> = match aug_assign_stmt.value.as_ref() {
+            Expr::List(list) => list.elts.clone(),
+            Expr::Tuple(tuple) => tuple.elts.clone(),
+            _ => return
+        };
+        let all = self.sym_stack[0].borrow().get_content_symbol("__all__", aug_assign_stmt.range.start().to_u32()).last().cloned();
+        let Some(all) = all else {
+            return;
+        };
+        let is_external = self.sym_stack[0].borrow().is_external();
+        let mut all = all.borrow_mut();
+        let Some(evaluation) = all.as_variable_mut().evaluations.get_mut(0) else {
+            return;
+        };
+        match evaluation.value.as_mut() {
+            Some(EvaluationValue::LIST(values)) | Some(EvaluationValue::TUPLE(values)) => values.extend(added.iter().cloned()),
+            _ => return
+        }
+        if is_external {
+            for item in added.iter() {
+                if let Expr::StringLiteral(s) = item {
+                    self.__all_symbols_to_add.push((s.value.to_string(), s.range));
+                }
+            }
+        }
+    }
+
     fn visit_func_def(&mut self, session: &mut SessionInfo, func_def: &StmtFunctionDef) -> Result<(), Error> {
+        let in_class = self.sym_stack.last().unwrap().borrow().typ() == SymType::CLASS;
         let mut sym = self.sym_stack.last().unwrap().borrow_mut().add_new_function(
             session, &func_def.name.id.to_string(), &func_def.range, &func_def.body.get(0).unwrap().range().start());
         let mut sym_bw = sym.borrow_mut();
         let mut func_sym = sym_bw.as_func_mut();
+        func_sym.is_forwarding = in_class && python_utils::is_forwarding_override(func_def);
+        func_sym.is_create_multi = in_class && create_multi::is_create_multi(func_def);
         for decorator in func_def.decorator_list.iter() {
             if decorator.expression.is_name_expr() && decorator.expression.as_name_expr().unwrap().id.to_string() == "staticmethod" {
                 func_sym.is_static = true;
@@ -360,19 +444,26 @@ impl PythonArchBuilder {
         if func_def.body.len() > 0 && func_def.body[0].is_expr_stmt() {
             let expr: &ruff_python_ast::StmtExpr = func_def.body[0].as_expr_stmt().unwrap();
             if let Some(s) = expr.value.as_string_literal_expr() {
+                let doc = Docstring::parse(s.value.to_str());
+                func_sym.doc = if doc.has_sections() { Some(doc) } else { None };
                 func_sym.doc_string = Some(s.value.to_string())
             }
         }
         drop(sym_bw);
         //add params
-        for arg in func_def.parameters.posonlyargs.iter().chain(&func_def.parameters.args) {
-            let param = sym.borrow_mut().add_new_variable(session, &arg.parameter.name.id.to_string(), &arg.range);
+        let parameters = &func_def.parameters;
+        let positional = parameters.posonlyargs.iter().chain(&parameters.args).map(|arg| (&arg.parameter.name, arg.range, false, false));
+        let vararg = parameters.vararg.iter().map(|arg| (&arg.name, arg.range, true, false));
+        let keyword_only = parameters.kwonlyargs.iter().map(|arg| (&arg.parameter.name, arg.range, false, false));
+        let kwarg = parameters.kwarg.iter().map(|arg| (&arg.name, arg.range, false, true));
+        for (name, range, is_args, is_kwargs) in positional.chain(vararg).chain(keyword_only).chain(kwarg) {
+            let param = sym.borrow_mut().add_new_variable(session, &name.id.to_string(), &range);
             param.borrow_mut().as_variable_mut().is_parameter = true;
             sym.borrow_mut().as_func_mut().args.push(Argument {
                 symbol: Rc::downgrade(&param),
                 default_value: None,
-                is_args: false,
-                is_kwargs: false,
+                is_args,
+                is_kwargs,
             });
         }
         //visit body
@@ -402,12 +493,29 @@ impl PythonArchBuilder {
         }
         drop(sym_bw);
         self.sym_stack.push(sym.clone());
-        self.visit_node(session, &class_def.body)?;
+        if !self.degraded {
+            self.visit_node(session, &class_def.body)?;
+            self.add_class_setup_attributes(session, &sym, class_def);
+        }
         self.sym_stack.pop();
         PythonArchBuilderHooks::on_class_def(session, sym);
         Ok(())
     }
 
+    /* Declare the attributes cached on cls by setUpClass as members of the class, so the tests find them on self. An
+    attribute already declared in the class body keeps its declaration, and only the first assignment is kept */
+    fn add_class_setup_attributes(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, class_def: &StmtClassDef) {
+        let Some(setup) = python_utils::find_class_setup(&class_def.body) else {
+            return;
+        };
+        for (attr, _) in python_utils::cls_attribute_assigns(setup) {
+            if !class.borrow().get_content_symbol(attr.attr.as_str(), u32::MAX).is_empty() {
+                continue;
+            }
+            class.borrow_mut().add_new_variable(session, &attr.attr.to_string(), &attr.attr.range);
+        }
+    }
+
     fn _resolve_all_symbols(&mut self, session: &mut SessionInfo) {
         for (symbol_name, range) in self.__all_symbols_to_add.drain(..) {
             if self.sym_stack.last().unwrap().borrow().get_content_symbol(&symbol_name, u32::MAX).is_empty() {
@@ -418,20 +526,52 @@ impl PythonArchBuilder {
 
     fn visit_if(&mut self, session: &mut SessionInfo, if_stmt: &StmtIf) -> Result<(), Error> {
         //TODO check platform condition (sys.version > 3.12, etc...)
-        self.visit_node(session, &if_stmt.body)?;
-        for else_clause in if_stmt.elif_else_clauses.iter() {
-            self.visit_node(session, &else_clause.body)?;
+        //Symbols of branches that are dead for the current version of Odoo are still built, but marked as dead on the file
+        let version = (session.sync_odoo.version_major, session.sync_odoo.version_minor, session.sync_odoo.version_micro);
+        let dead_branches = python_utils::dead_if_branches(if_stmt, version);
+        let bodies = std::iter::once(&if_stmt.body).chain(if_stmt.elif_else_clauses.iter().map(|clause| &clause.body));
+        for (body, dead) in bodies.zip(dead_branches.iter()) {
+            if *dead {
+                if let Some(range) = python_utils::body_range(body) {
+                    self.file.borrow_mut().add_dead_branch(range);
+                }
+            }
+            self.visit_node(session, body)?;
         }
         Ok(())
     }
 
+    /* Like in python, comprehensions have their own scope: their variables are declared in a function symbol, that is not visible
+    after the comprehension. Variables are visible from the start of their comprehension, so the names used in the element of
+    the comprehension can be resolved */
+    fn visit_comprehensions(&mut self, session: &mut SessionInfo, stmt: &Stmt) {
+        let mut scopes: Vec<Rc<RefCell<Symbol>>> = vec![];
+        for (name, comprehension_range, generators) in AstUtils::collect_comprehensions(stmt) {
+            while scopes.last().is_some_and(|scope| !scope.borrow().range().contains_range(comprehension_range)) {
+                scopes.pop();
+            }
+            let parent = scopes.last().cloned().unwrap_or_else(|| self.sym_stack.last().unwrap().clone());
+            let scope = parent.borrow_mut().add_new_function(session, &S!(name), &comprehension_range, &comprehension_range.start());
+            scope.borrow_mut().as_func_mut().is_comprehension = true;
+            for step in [BuildSteps::ARCH, BuildSteps::ARCH_EVAL, BuildSteps::ODOO, BuildSteps::VALIDATION] {
+                scope.borrow_mut().set_build_status(step, BuildStatus::DONE); //the variables are evaluated with the statement
+            }
+            for generator in generators.iter() {
+                for target in python_utils::target_names(&generator.target) {
+                    let range = TextRange::new(comprehension_range.start(), target.range.end());
+                    scope.borrow_mut().add_new_variable(session, &target.id.to_string(), &range);
+                }
+            }
+            scopes.push(scope);
+        }
+    }
+
     fn visit_for(&mut self, session: &mut SessionInfo, for_stmt: &StmtFor) -> Result<(), Error> {
-        let unpacked = python_utils::unpack_assign(&vec![*for_stmt.target.clone()], None, None);
-        for assign in unpacked {
-            self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
+        for target in python_utils::target_names(&for_stmt.target) {
+            self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &target.id.to_string(), &target.range);
         }
         self.visit_node(session, &for_stmt.body)?;
-        //TODO should split evaluations as in if
+        //the else clause is run when the loop ends without break, so the targets are still bound
         self.visit_node(session, &for_stmt.orelse)?;
         Ok(())
     }
@@ -451,14 +591,8 @@ impl PythonArchBuilder {
     fn visit_with(&mut self, session: &mut SessionInfo, with_stmt: &StmtWith) -> Result<(), Error> {
         for item in with_stmt.items.iter() {
             if let Some(var) = item.optional_vars.as_ref() {
-                match &**var {
-                    Expr::Name(expr_name) => {
-                        self.sym_stack.last().unwrap().borrow_mut().add_new_variable(
-                            session, &expr_name.id.to_string(), &var.range());
-                    },
-                    Expr::Tuple(_) => {continue;},
-                    Expr::List(_) => {continue;},
-                    _ => {continue;}
+                for target in python_utils::target_names(var) {
+                    self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &target.id.to_string(), &target.range);
                 }
             }
         }
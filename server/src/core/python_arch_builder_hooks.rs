@@ -1,84 +1,122 @@
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::cell::RefCell;
+use crate::constants::Tree;
 use crate::core::symbols::symbol::Symbol;
 use crate::threads::SessionInfo;
 use crate::S;
 
+use super::hook_registry::HookRegistry;
 use super::odoo::SyncOdoo;
 
+type PythonArchBuilderClassHookFunc = fn (session: &mut SessionInfo, class: &mut Symbol);
+type PythonArchBuilderFileHookFunc = fn (session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>);
+
+/* Hook called on the class of tree once its body is built, to declare the members that are created dynamically */
+#[derive(Clone)]
+pub struct PythonArchBuilderClassHook {
+    pub tree: Tree,
+    pub func: PythonArchBuilderClassHookFunc
+}
+
+/* Hook called on the file of file_tree once its arch is built */
+#[derive(Clone)]
+pub struct PythonArchBuilderFileHook {
+    pub file_tree: Vec<String>,
+    pub func: PythonArchBuilderFileHookFunc
+}
+
+/* Classes of odoo.fields, whose __get__ is declared to evaluate the fields as their value on records */
+static FIELD_CLASSES: &[&str] = &[
+    "Boolean", "Integer", "Float", "Monetary", "Char", "Text", "Html", "Date", "Datetime", "Binary", "Image", "Selection",
+    "Reference", "Many2one", "Many2oneReference", "Json", "Properties", "PropertiesDefinition", "One2many", "Many2many", "Id",
+];
+
 pub struct PythonArchBuilderHooks {}
 
 impl PythonArchBuilderHooks {
 
+    pub fn register_odoo_hooks(registry: &mut HookRegistry) {
+        registry.register_class_hook(PythonArchBuilderClassHook {
+            tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel")]),
+            func: PythonArchBuilderHooks::_add_env
+        });
+        registry.register_class_hook(PythonArchBuilderClassHook {
+            tree: (vec![S!("odoo"), S!("api")], vec![S!("Environment")]),
+            func: PythonArchBuilderHooks::_add_environment_attributes
+        });
+        for field_class in FIELD_CLASSES.iter() {
+            registry.register_class_hook(PythonArchBuilderClassHook {
+                tree: (vec![S!("odoo"), S!("fields")], vec![S!(*field_class)]),
+                func: PythonArchBuilderHooks::_add_get
+            });
+        }
+        registry.register_arch_file_hook(PythonArchBuilderFileHook {
+            file_tree: vec![S!("odoo"), S!("release")],
+            func: PythonArchBuilderHooks::_check_release
+        });
+    }
+
     pub fn on_class_def(session: &mut SessionInfo, symbol: Rc<RefCell<Symbol>>) {
-        let mut sym = symbol.borrow_mut();
-        let name = &sym.name();
-        match name.as_str() {
-            "BaseModel" => {
-                if sym.get_tree() == (vec![S!("odoo"), S!("models")], vec![S!("BaseModel")]) {
-                    // ----------- env ------------
-                    let env = sym.get_symbol(&(vec![], vec![S!("env")]), u32::MAX);
-                    if env.is_empty() {
-                        let mut range = sym.range().clone();
-                        let slots = sym.get_symbol(&(vec![], vec![S!("__slots__")]), u32::MAX);
-                        if slots.len() == 1 {
-                            if slots.len() == 1 {
-                                range = slots[0].borrow().range().clone();
-                            }
-                        }
-                        sym.add_new_variable(session, &S!("env"), &range);
-                    }
-                }
-            },
-            "Environment" => {
-                if sym.get_tree() == (vec![S!("odoo"), S!("api")], vec![S!("Environment")]) {
-                    let new_sym = sym.get_symbol(&(vec![], vec![S!("__new__")]), u32::MAX);
-                    let mut range = sym.range().clone();
-                    if new_sym.len() == 1 {
-                        range = new_sym[0].borrow().range().clone();
-                    }
-                    // ----------- env.cr ------------
-                    sym.add_new_variable(session, &S!("cr"), &range);
-                    // ----------- env.uid ------------
-                    let uid_sym = sym.add_new_variable(session, &S!("uid"), &range);
-                    uid_sym.borrow_mut().as_variable_mut().doc_string = Some(S!("The current user id (for access rights checks)"));
-                    // ----------- env.context ------------
-                    let context_sym = sym.add_new_variable(session, &S!("context"), &range);
-                    context_sym.borrow_mut().as_variable_mut().doc_string = Some(S!("The current context"));
-                    // ----------- env.su ------------
-                    let su_sym = sym.add_new_variable(session, &S!("su"), &range);
-                    su_sym.borrow_mut().as_variable_mut().doc_string = Some(S!("whether in superuser mode"));
-                }
-            },
-            "Boolean" | "Integer" | "Float" | "Monetary" | "Char" | "Text" | "Html" | "Date" | "Datetime" |
-            "Binary" | "Image" | "Selection" | "Reference" | "Many2one" | "Many2oneReference" | "Json" |
-            "Properties" | "PropertiesDefinition" | "One2many" | "Many2many" | "Id" => {
-                if sym.get_tree().0 == vec![S!("odoo"), S!("fields")] {
-                    if vec![S!("Many2one"), S!("Many2many"), S!("One2many")].contains(&sym.name()) {
-                        //TODO how to do this?
-                    }
-                    // ----------- __get__ ------------
-                    let get_sym = sym.get_symbol(&(vec![], vec![S!("__get__")]), u32::MAX);
-                    if get_sym.is_empty() {
-                        let range = sym.range().clone();
-                        sym.add_new_variable(session, &S!("__get__"), &range);
-                    }
-                }
-            }
-            _ => {}
+        let tree = symbol.borrow().get_tree();
+        for hook in session.sync_odoo.hooks.get_class_hooks(&tree).iter() {
+            (hook.func)(session, &mut symbol.borrow_mut());
         }
     }
 
     pub fn on_done(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) {
-        let name = symbol.borrow().name().clone();
-        if name == "release" {
-            if symbol.borrow().get_tree() == (vec![S!("odoo"), S!("release")], vec![]) {
-                let (maj, min, mic) = SyncOdoo::read_version(session, PathBuf::from(symbol.borrow().paths()[0].clone()));
-                if maj != session.sync_odoo.version_major || min != session.sync_odoo.version_minor || mic != session.sync_odoo.version_micro {
-                    session.sync_odoo.need_rebuild = true;
-                }
+        let file_tree = symbol.borrow().get_tree().0;
+        for hook in session.sync_odoo.hooks.get_arch_file_hooks(&file_tree).iter() {
+            (hook.func)(session, symbol);
+        }
+    }
+
+    fn _add_env(session: &mut SessionInfo, sym: &mut Symbol) {
+        // ----------- env ------------
+        let env = sym.get_symbol(&(vec![], vec![S!("env")]), u32::MAX);
+        if env.is_empty() {
+            let mut range = sym.range().clone();
+            let slots = sym.get_symbol(&(vec![], vec![S!("__slots__")]), u32::MAX);
+            if slots.len() == 1 {
+                range = slots[0].borrow().range().clone();
             }
+            sym.add_new_variable(session, &S!("env"), &range);
+        }
+    }
+
+    fn _add_environment_attributes(session: &mut SessionInfo, sym: &mut Symbol) {
+        let new_sym = sym.get_symbol(&(vec![], vec![S!("__new__")]), u32::MAX);
+        let mut range = sym.range().clone();
+        if new_sym.len() == 1 {
+            range = new_sym[0].borrow().range().clone();
+        }
+        // ----------- env.cr ------------
+        sym.add_new_variable(session, &S!("cr"), &range);
+        // ----------- env.uid ------------
+        let uid_sym = sym.add_new_variable(session, &S!("uid"), &range);
+        uid_sym.borrow_mut().as_variable_mut().doc_string = Some(S!("The current user id (for access rights checks)"));
+        // ----------- env.context ------------
+        let context_sym = sym.add_new_variable(session, &S!("context"), &range);
+        context_sym.borrow_mut().as_variable_mut().doc_string = Some(S!("The current context"));
+        // ----------- env.su ------------
+        let su_sym = sym.add_new_variable(session, &S!("su"), &range);
+        su_sym.borrow_mut().as_variable_mut().doc_string = Some(S!("whether in superuser mode"));
+    }
+
+    fn _add_get(session: &mut SessionInfo, sym: &mut Symbol) {
+        // ----------- __get__ ------------
+        let get_sym = sym.get_symbol(&(vec![], vec![S!("__get__")]), u32::MAX);
+        if get_sym.is_empty() {
+            let range = sym.range().clone();
+            sym.add_new_variable(session, &S!("__get__"), &range);
+        }
+    }
+
+    /* The version of odoo is read before the build. If the release file has changed, everything has to be built again */
+    fn _check_release(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) {
+        let (maj, min, mic) = SyncOdoo::read_version(session, PathBuf::from(symbol.borrow().paths()[0].clone()));
+        if maj != session.sync_odoo.version_major || min != session.sync_odoo.version_minor || mic != session.sync_odoo.version_micro {
+            session.sync_odoo.need_rebuild = true;
         }
     }
-}
\ No newline at end of file
+}
@@ -3,18 +3,19 @@ use std::rc::Rc;
 use std::cell::RefCell;
 use std::{u32, vec};
 
-use ruff_text_size::{Ranged, TextRange};
+use ruff_text_size::{Ranged, TextRange, TextSize};
 use ruff_python_ast::{Alias, Expr, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtFor, StmtFunctionDef, StmtIf, StmtReturn, StmtTry, StmtWith};
 use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
 use tracing::{debug, trace};
 use std::path::PathBuf;
 
 use crate::constants::*;
+use crate::core::create_multi;
 use crate::core::import_resolver::resolve_import_stmt;
 use crate::core::odoo::SyncOdoo;
 use crate::core::symbols::symbol::Symbol;
-use crate::core::evaluation::Evaluation;
-use crate::core::python_utils;
+use crate::core::evaluation::{Evaluation, EvaluationValue, GiveUp};
+use crate::core::python_utils::{self, Assign};
 use crate::features::ast_utils::AstUtils;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
@@ -24,6 +25,7 @@ use super::config::DiagMissingImportsMode;
 use super::evaluation::ContextValue;
 use super::file_mgr::FileMgr;
 use super::import_resolver::ImportResult;
+use super::model::Model;
 use super::python_arch_eval_hooks::PythonArchEvalHooks;
 use super::symbols::function_symbol::FunctionSymbol;
 
@@ -37,6 +39,9 @@ pub struct PythonArchEval {
     diagnostics: Vec<Diagnostic>,
     safe_import: Vec<bool>,
     ast_indexes: Vec<u16>,
+    type_comments: HashMap<u32, String>, //'# type:' comments of the file, indexed by the end offset of the statement they annotate
+    strict: bool, //record the give-ups of the evaluation of the values, reported by the validator. See Config::strict_mode
+    give_ups: Vec<GiveUp>,
 }
 
 impl PythonArchEval {
@@ -49,6 +54,9 @@ impl PythonArchEval {
             diagnostics: Vec::new(),
             safe_import: vec![false],
             ast_indexes: vec![],
+            type_comments: HashMap::new(),
+            strict: false, //dummy, evaluated in eval_arch
+            give_ups: vec![],
         }
     }
 
@@ -68,6 +76,7 @@ impl PythonArchEval {
             self.file_mode = Rc::ptr_eq(&file, &symbol);
             self.current_step = if self.file_mode {BuildSteps::ARCH_EVAL} else {BuildSteps::VALIDATION};
             self.ast_indexes = symbol.borrow().ast_indexes().unwrap_or(&vec![]).clone(); //copy current ast_indexes if we are not evaluating a file
+            self.strict = session.sync_odoo.config.strict_mode && file.borrow().in_workspace();
         }
         trace!("evaluating {} - {}", self.file.borrow().paths().first().unwrap_or(&S!("No path found")), symbol.borrow().name());
         symbol.borrow_mut().set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::IN_PROGRESS);
@@ -85,7 +94,9 @@ impl PythonArchEval {
         };
         let file_info_rc = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path).expect("File not found in cache").clone();
         let file_info = (*file_info_rc).borrow();
-        if file_info.ast.is_some() {
+        let version = file_info.version;
+        self.type_comments = file_info.get_type_comments();
+        if file_info.ast.is_some() && !file_info.large {
             let ast = match self.file_mode {
                 true => {file_info.ast.as_ref().unwrap()},
                 false => {
@@ -97,15 +108,29 @@ impl PythonArchEval {
                 self.visit_stmt(session, stmt);
                 self.ast_indexes.pop();
             }
-            if !self.file_mode {
+            if !self.file_mode && !self.sym_stack[0].borrow().as_func().is_forwarding {
                 if self.sym_stack[0].borrow().as_func().evaluations.is_empty() {
                     self.sym_stack[0].borrow_mut().as_func_mut().evaluations = vec![Evaluation::new_none()];
                 }
             }
         }
         drop(file_info);
+        if !self.file_mode && self.sym_stack[0].borrow().as_func().is_forwarding {
+            //super() is not evaluated: the forwarding override returns what the method it forwards to returns
+            let function = self.sym_stack[0].clone();
+            let mut evaluations = FunctionSymbol::get_forwarded_evaluations(session, &function);
+            if evaluations.is_empty() {
+                evaluations = vec![Evaluation::new_none()];
+            }
+            function.borrow_mut().as_func_mut().evaluations = evaluations;
+        }
+        if !self.file_mode {
+            Model::record_return_types(session, &self.sym_stack[0]);
+        }
+        let function = if self.file_mode { None } else { Some(symbol.borrow().range().start()) };
+        file_info_rc.borrow_mut().replace_give_ups(function, std::mem::take(&mut self.give_ups));
         if self.file_mode {
-            file_info_rc.borrow_mut().replace_diagnostics(BuildSteps::ARCH_EVAL, self.diagnostics.clone());
+            file_info_rc.borrow_mut().replace_diagnostics_for_version(BuildSteps::ARCH_EVAL, self.diagnostics.clone(), version);
             PythonArchEvalHooks::on_file_eval(session.sync_odoo, self.sym_stack.first().unwrap().clone());
         } else {
             //then Symbol must be a function
@@ -146,11 +171,16 @@ impl PythonArchEval {
                 self.visit_func_def(session, func_stmt);
             },
             Stmt::AnnAssign(ann_assign_stmt) => {
+                self._visit_comprehensions(session, stmt);
                 self._visit_ann_assign(session, ann_assign_stmt);
             },
             Stmt::Assign(assign_stmt) => {
+                self._visit_comprehensions(session, stmt);
                 self._visit_assign(session, assign_stmt);
             },
+            Stmt::Expr(_) | Stmt::AugAssign(_) => {
+                self._visit_comprehensions(session, stmt);
+            },
             Stmt::If(if_stmt) => {
                 self._visit_if(session, if_stmt);
             },
@@ -164,6 +194,7 @@ impl PythonArchEval {
                 self._visit_with(session, with_stmt);
             }
             Stmt::Return(return_stmt) => {
+                self._visit_comprehensions(session, stmt);
                 self._visit_return(session, return_stmt);
             }
             _ => {}
@@ -236,7 +267,12 @@ impl PythonArchEval {
                 let import_sym_ref = _import_result.symbol.clone();
                 let has_loop = self.check_for_loop_evaluation(session, import_sym_ref, &variable);
                 if !has_loop { //anti-loop. We want to be sure we are not evaluating to the same sym
-                    variable.borrow_mut().set_evaluations(vec![Evaluation::eval_from_symbol(&Rc::downgrade(&_import_result.symbol))]);
+                    let mut evaluations = vec![Evaluation::eval_from_symbol(&Rc::downgrade(&_import_result.symbol))];
+                    if *self.safe_import.last().unwrap() {
+                        //optional dependency: found here, but None where the package is not installed
+                        evaluations.push(Evaluation::new_none());
+                    }
+                    variable.borrow_mut().set_evaluations(evaluations);
                     let file_of_import_symbol = _import_result.symbol.borrow().get_file();
                     if let Some(import_file) = file_of_import_symbol {
                         let import_file = import_file.upgrade().unwrap();
@@ -249,7 +285,7 @@ impl PythonArchEval {
                     file_tree.extend(_import_result.name.split(".").map(str::to_string));
                     self.file.borrow_mut().not_found_paths_mut().push((self.current_step, file_tree.clone()));
                     session.sync_odoo.not_found_symbols.insert(self.file.clone());
-                    if self._match_diag_config(session.sync_odoo, &_import_result.symbol) {
+                    if !session.sync_odoo.is_unresolvable(&file_tree) && self._match_diag_config(session.sync_odoo, &_import_result.symbol) {
                         self.diagnostics.push(Diagnostic::new(
                             Range::new(Position::new(_import_result.range.start().to_u32(), 0), Position::new(_import_result.range.end().to_u32(), 0)),
                             Some(DiagnosticSeverity::WARNING),
@@ -268,10 +304,13 @@ impl PythonArchEval {
                 if BUILT_IN_LIBS.contains(&file_tree[0].as_str()) {
                     continue;
                 }
-                if !self.safe_import.last().unwrap() {
+                if *self.safe_import.last().unwrap() {
+                    //optional dependency: the name is None when the package is not installed, like in the handler of the ImportError
+                    variable.borrow_mut().set_evaluations(vec![Evaluation::new_none()]);
+                } else {
                     self.file.borrow_mut().not_found_paths_mut().push((self.current_step, file_tree.clone()));
                     session.sync_odoo.not_found_symbols.insert(self.file.clone());
-                    if self._match_diag_config(session.sync_odoo, &_import_result.symbol) {
+                    if !session.sync_odoo.is_unresolvable(&file_tree) && self._match_diag_config(session.sync_odoo, &_import_result.symbol) {
                         self.diagnostics.push(Diagnostic::new(
                             Range::new(Position::new(_import_result.range.start().to_u32(), 0), Position::new(_import_result.range.end().to_u32(), 0)),
                             Some(DiagnosticSeverity::WARNING),
@@ -296,17 +335,21 @@ impl PythonArchEval {
             let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&assign.target.id.to_string(), &assign.target.range);
             if let Some(variable_rc) = variable {
                 let parent = variable_rc.borrow().parent().unwrap().upgrade().unwrap().clone();
+                let mut evaluations = vec![];
                 if assign.annotation.is_some() {
-                    let (eval, diags) = Evaluation::eval_from_ast(session, &assign.annotation.as_ref().unwrap(), parent, &ann_assign_stmt.range.start());
-                    variable_rc.borrow_mut().set_evaluations(eval);
+                    let (eval, diags) = Evaluation::eval_from_annotation(session, &assign.annotation.as_ref().unwrap(), parent.clone(), &ann_assign_stmt.range.start());
+                    evaluations = eval;
                     self.diagnostics.extend(diags);
-                } else if assign.value.is_some() {
-                    let (eval, diags) = Evaluation::eval_from_ast(session, &assign.value.as_ref().unwrap(), parent, &ann_assign_stmt.range.start());
-                    variable_rc.borrow_mut().set_evaluations(eval);
+                }
+                //fallback on the value if the annotation can't be resolved
+                if evaluations.is_empty() && assign.value.is_some() {
+                    let (eval, diags) = self._eval_assign_value(session, assign, parent, &ann_assign_stmt.range.start());
+                    evaluations = eval;
                     self.diagnostics.extend(diags);
-                } else {
+                } else if assign.annotation.is_none() && assign.value.is_none() {
                     panic!("either value or annotation should exists");
                 }
+                variable_rc.borrow_mut().set_evaluations(evaluations);
                 let mut dep_to_add = vec![];
                 let v_mut = variable_rc.borrow_mut();
                 for evaluation in v_mut.evaluations().unwrap().iter() {
@@ -338,47 +381,95 @@ impl PythonArchEval {
 
     fn _visit_assign(&mut self, session: &mut SessionInfo, assign_stmt: &StmtAssign) {
         let assigns = python_utils::unpack_assign(&assign_stmt.targets, None, Some(&assign_stmt.value));
+        let type_comment = match assigns.len() {
+            1 => self.type_comments.get(&assign_stmt.range.end().to_u32()).and_then(|comment| ruff_python_parser::parse_expression(comment).ok()),
+            _ => None
+        };
         for assign in assigns.iter() {
             let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&assign.target.id.to_string(), &assign.target.range);
             if let Some(variable_rc) = variable {
                 let parent = variable_rc.borrow().parent().as_ref().unwrap().upgrade().unwrap().clone();
-                let (eval, diags) = Evaluation::eval_from_ast(session, &assign.value.as_ref().unwrap(), parent, &assign_stmt.range.start());
-                variable_rc.borrow_mut().set_evaluations(eval);
-                self.diagnostics.extend(diags);
-                let mut dep_to_add = vec![];
-                let v_mut = variable_rc.borrow_mut();
-                for evaluation in v_mut.evaluations().unwrap().iter() {
-                    if let Some(sym) = evaluation.symbol.get_symbol(session, &mut None, &mut self.diagnostics, None).0.upgrade() {
-                        if let Some(file) = sym.borrow().get_file().clone() {
-                            let sym_file = file.upgrade().unwrap().clone();
-                            if !Rc::ptr_eq(&self.file, &sym_file) {
-                                match Rc::ptr_eq(&variable_rc, &sym_file) {
-                                    true => {
-                                        dep_to_add.push(variable_rc.clone());
-                                    },
-                                    false => {
-                                        dep_to_add.push(sym_file);
-                                    }
-                                };
-                            }
-                        }
-                    }
+                let mut evaluations = vec![];
+                if let Some(type_comment) = type_comment.as_ref() {
+                    //diagnostics are dropped, as the ranges are relative to the comment
+                    (evaluations, _) = Evaluation::eval_from_annotation(session, type_comment.expr(), parent.clone(), &assign_stmt.range.start());
                 }
-                drop(v_mut);
-                for dep in dep_to_add {
-                    self.file.borrow_mut().add_dependency(&mut dep.borrow_mut(), self.current_step, BuildSteps::ARCH);
+                if evaluations.is_empty() {
+                    let (eval, diags) = self._eval_assign_value(session, assign, parent, &assign_stmt.range.start());
+                    evaluations = eval;
+                    self.diagnostics.extend(diags);
                 }
-
+                variable_rc.borrow_mut().set_evaluations(evaluations);
+                self._add_evaluation_dependencies(session, &variable_rc);
             } else {
                 debug!("Symbol not found");
             }
         }
     }
 
+    /* Make the file depend on the files of the symbols a variable is evaluated to */
+    fn _add_evaluation_dependencies(&mut self, session: &mut SessionInfo, variable_rc: &Rc<RefCell<Symbol>>) {
+        let mut dep_to_add = vec![];
+        let v_mut = variable_rc.borrow_mut();
+        for evaluation in v_mut.evaluations().unwrap().iter() {
+            if let Some(sym) = evaluation.symbol.get_symbol(session, &mut None, &mut self.diagnostics, None).0.upgrade() {
+                if let Some(file) = sym.borrow().get_file().clone() {
+                    let sym_file = file.upgrade().unwrap().clone();
+                    if !Rc::ptr_eq(&self.file, &sym_file) {
+                        match Rc::ptr_eq(variable_rc, &sym_file) {
+                            true => {
+                                dep_to_add.push(variable_rc.clone());
+                            },
+                            false => {
+                                dep_to_add.push(sym_file);
+                            }
+                        };
+                    }
+                }
+            }
+        }
+        drop(v_mut);
+        for dep in dep_to_add {
+            self.file.borrow_mut().add_dependency(&mut dep.borrow_mut(), self.current_step, BuildSteps::ARCH);
+        }
+    }
+
+    /* Evaluate the value of an unpacked assignment. If the value can't be unpacked statically (a, b = func()), the target
+    gets the element at its index of the evaluated tuple or list, or no evaluation at all if it is unknown */
+    fn _eval_assign_value(&mut self, session: &mut SessionInfo, assign: &Assign, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> (Vec<Evaluation>, Vec<Diagnostic>) {
+        let Some(value) = assign.value.as_ref() else {
+            return (vec![], vec![]);
+        };
+        let result = Evaluation::analyze_expr(session, value, parent.clone(), max_infer);
+        if self.strict {
+            self.give_ups.extend(result.give_ups);
+        }
+        let (evals, mut diags) = (result.evaluations, result.diagnostics);
+        let Some(index) = assign.index else {
+            return (evals, diags);
+        };
+        let mut res = vec![];
+        for eval in evals.iter() {
+            let element = match eval.value.as_ref() {
+                Some(EvaluationValue::TUPLE(values)) | Some(EvaluationValue::LIST(values)) => values.get(index),
+                _ => None
+            };
+            if let Some(element) = element {
+                let (element_evals, element_diags) = Evaluation::eval_from_ast(session, element, parent.clone(), max_infer);
+                res.extend(element_evals);
+                diags.extend(element_diags);
+            }
+        }
+        (res, diags)
+    }
+
     fn create_diagnostic_base_not_found(&mut self, session: &mut SessionInfo, file: &mut Symbol, tree_not_found: &Tree, range: &TextRange) {
         let tree = flatten_tree(tree_not_found);
         file.not_found_paths_mut().push((BuildSteps::ARCH_EVAL, tree.clone()));
         session.sync_odoo.not_found_symbols.insert(file.get_rc().unwrap());
+        if session.sync_odoo.is_unresolvable(&tree) {
+            return;
+        }
         self.diagnostics.push(Diagnostic::new(
             Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
             Some(DiagnosticSeverity::WARNING),
@@ -391,6 +482,7 @@ impl PythonArchEval {
     }
 
     fn load_base_classes(&mut self, session: &mut SessionInfo, loc_sym: &Rc<RefCell<Symbol>>, class_stmt: &StmtClassDef) {
+        loc_sym.borrow_mut().as_class_sym_mut().ordered_bases.clear();
         for base in class_stmt.bases() {
             let eval_base = Evaluation::eval_from_ast(session, base, self.sym_stack.last().unwrap().clone(), &class_stmt.range().start());
             self.diagnostics.extend(eval_base.1);
@@ -448,6 +540,7 @@ impl PythonArchEval {
                     if !Rc::ptr_eq(&self.file, &file_symbol) {
                         self.file.borrow_mut().add_dependency(&mut file_symbol.borrow_mut(), self.current_step, BuildSteps::ARCH);
                     }
+                    loc_sym.borrow_mut().as_class_sym_mut().ordered_bases.push(Rc::downgrade(&symbol));
                     loc_sym.borrow_mut().as_class_sym_mut().bases.insert(symbol);
                 }
             }
@@ -462,13 +555,60 @@ impl PythonArchEval {
         variable.as_ref().unwrap().borrow_mut().ast_indexes_mut().clear();
         variable.as_ref().unwrap().borrow_mut().ast_indexes_mut().extend(self.ast_indexes.iter());
         self.load_base_classes(session, variable.as_ref().unwrap(), class_stmt);
-        self.sym_stack.push(variable.unwrap().clone());
+        self.sym_stack.push(variable.as_ref().unwrap().clone());
         for (index, stmt) in class_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
             self.visit_stmt(session, stmt);
             self.ast_indexes.pop();
         }
         self.sym_stack.pop();
+        self._eval_class_setup_attributes(session, variable.as_ref().unwrap(), class_stmt);
+    }
+
+    /* Evaluate the attributes cached on cls by setUpClass in the scope of setUpClass, where cls is the class. The body
+    of the method is not built at this step: values depending on its local variables are not evaluated */
+    fn _eval_class_setup_attributes(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, class_stmt: &StmtClassDef) {
+        let Some(setup) = python_utils::find_class_setup(&class_stmt.body) else {
+            return;
+        };
+        let Some(setup_sym) = class.borrow().get_positioned_symbol(&setup.name.to_string(), &setup.range) else {
+            return;
+        };
+        for (attr, value) in python_utils::cls_attribute_assigns(setup) {
+            let variable = class.borrow().get_positioned_symbol(&attr.attr.to_string(), &attr.attr.range);
+            let Some(variable_rc) = variable else {
+                continue; //declared in the class body
+            };
+            //diagnostics are dropped, the validation of setUpClass reports them
+            let (evaluations, _) = Evaluation::eval_from_ast(session, value, setup_sym.clone(), &attr.range.start());
+            variable_rc.borrow_mut().set_evaluations(evaluations);
+            self._add_evaluation_dependencies(session, &variable_rc);
+        }
+    }
+
+    fn _apply_decorator_hooks(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef, function: &Rc<RefCell<Symbol>>) {
+        if func_stmt.decorator_list.is_empty() || !session.sync_odoo.hooks.has_decorator_hooks() {
+            return;
+        }
+        for decorator in func_stmt.decorator_list.iter() {
+            let expr = match &decorator.expression {
+                Expr::Call(call) => call.func.as_ref(),
+                expr => expr
+            };
+            let (evaluations, _) = Evaluation::eval_from_ast(session, expr, self.sym_stack.last().unwrap().clone(), &func_stmt.range.start());
+            for evaluation in evaluations.iter() {
+                let Some(decorator_sym) = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade() else {
+                    continue;
+                };
+                let decorator_types = Symbol::follow_ref(&decorator_sym, session, &mut None, true, false, None, &mut vec![]);
+                for (decorator_type, _) in decorator_types.iter() {
+                    if let Some(decorator_type) = decorator_type.upgrade() {
+                        let decorator_tree = decorator_type.borrow().get_tree();
+                        PythonArchEvalHooks::on_decorator(session.sync_odoo, &decorator_tree, function.clone());
+                    }
+                }
+            }
+        }
     }
 
     fn visit_func_def(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef) {
@@ -492,21 +632,34 @@ impl PythonArchEval {
                         continue;
                     }
                     is_first = false;
+                    let parameter = variable.borrow().as_func().symbols.get(&arg.parameter.name.id.to_string()).and_then(|sections| sections.get(&0)).and_then(|syms| syms.first()).cloned();
+                    let Some(parameter) = parameter else {
+                        continue;
+                    };
+                    let mut evaluations = vec![];
                     if arg.parameter.annotation.is_some() {
-                        let (eval, diags) = Evaluation::eval_from_ast(session,
+                        let (eval, diags) = Evaluation::eval_from_annotation(session,
                                                     &arg.parameter.annotation.as_ref().unwrap(),
                                                     self.sym_stack.last().unwrap().clone(),
                                                     &func_stmt.range.start());
-                        variable.borrow_mut().set_evaluations(eval);
+                        evaluations = eval;
                         self.diagnostics.extend(diags);
-                    } else if arg.default.is_some() {
+                    }
+                    if evaluations.is_empty() && arg.default.is_some() {
                         let (eval, diags) = Evaluation::eval_from_ast(session,
                                                     arg.default.as_ref().unwrap(),
                                                     self.sym_stack.last().unwrap().clone(),
                                                     &func_stmt.range.start());
-                        variable.borrow_mut().set_evaluations(eval);
+                        evaluations = eval;
                         self.diagnostics.extend(diags);
                     }
+                    //with @api.model_create_multi, create receives a list of values
+                    if evaluations.is_empty() && variable.borrow().as_func().is_create_multi && create_multi::vals_list_parameter(func_stmt) == Some(arg.parameter.name.id.as_str()) {
+                        evaluations = vec![Evaluation::new_list_of(session.sync_odoo, &(vec![S!("builtins")], vec![S!("dict")]), arg.range)];
+                    }
+                    if !evaluations.is_empty() {
+                        parameter.borrow_mut().set_evaluations(evaluations);
+                    }
                 }
             } else if !variable.borrow_mut().as_func_mut().is_static{
                 self.diagnostics.push(Diagnostic::new(
@@ -520,6 +673,7 @@ impl PythonArchEval {
                 ))
             }
         }
+        self._apply_decorator_hooks(session, func_stmt, &variable);
         if !self.file_mode || variable.borrow().get_in_parents(&vec![SymType::CLASS], true).is_none() {
             variable.borrow_mut().as_func_mut().arch_eval_status = BuildStatus::IN_PROGRESS;
             self.sym_stack.push(variable.clone());
@@ -535,6 +689,10 @@ impl PythonArchEval {
 
     fn _visit_if(&mut self, session: &mut SessionInfo, if_stmt: &StmtIf) {
         //TODO eval test (walrus op)
+        //Branches that are dead for the current version of Odoo are evaluated, but their diagnostics are dropped
+        let version = (session.sync_odoo.version_major, session.sync_odoo.version_minor, session.sync_odoo.version_micro);
+        let dead_branches = python_utils::dead_if_branches(if_stmt, version);
+        let diagnostics_count = self.diagnostics.len();
         self.ast_indexes.push(0 as u16);//0 for body
         for (index, stmt) in if_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
@@ -542,8 +700,12 @@ impl PythonArchEval {
             self.ast_indexes.pop();
         }
         self.ast_indexes.pop();
+        if dead_branches[0] {
+            self.diagnostics.truncate(diagnostics_count);
+        }
         for (index, elif_clause) in if_stmt.elif_else_clauses.iter().enumerate() {
             //TODO eval test of else clauses
+            let diagnostics_count = self.diagnostics.len();
             self.ast_indexes.push((index+1) as u16);//0 for body, so index + 1
             for (index_stmt, stmt) in elif_clause.body.iter().enumerate() {
                 self.ast_indexes.push(index_stmt as u16);
@@ -551,43 +713,86 @@ impl PythonArchEval {
                 self.ast_indexes.pop();
             }
             self.ast_indexes.pop();
+            if dead_branches[index + 1] {
+                self.diagnostics.truncate(diagnostics_count);
+            }
         }
     }
 
-    fn _visit_for(&mut self, session: &mut SessionInfo, for_stmt: &StmtFor) {
+    /* Evaluation of the elements of an iterable, given by the type of the elements of the list if it is known, or by the
+    __iter__ method of its class */
+    fn _eval_iter_element(&mut self, session: &mut SessionInfo, scope: Rc<RefCell<Symbol>>, iter: &Expr, max_infer: &TextSize) -> Vec<Evaluation> {
         let (eval_iter_node, diags) = Evaluation::eval_from_ast(session,
-            &for_stmt.iter,
-            self.sym_stack.last().unwrap().clone(),
-            &for_stmt.target.range().start());
+            iter,
+            scope,
+            max_infer);
         self.diagnostics.extend(diags);
-        if eval_iter_node.len() == 1 { //Only handle values that we are sure about
-            let eval = &eval_iter_node[0];
-            let (weak_symbol, _instance) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
-            if let Some(symbol) = weak_symbol.upgrade() {
-                let symbol_eval = Symbol::follow_ref(&symbol, session, &mut None, false, false, None, &mut vec![]);
-                if symbol_eval.len() == 1 && symbol_eval[0].0.upgrade().is_some() {
-                    let symbol_type_rc = symbol_eval[0].0.upgrade().unwrap();
-                    let symbol_type = symbol_type_rc.borrow();
-                    if symbol_type.typ() == SymType::CLASS {
-                        let (iter, _) = symbol_type.get_member_symbol(session, &S!("__iter__"), None, true, false);
-                        if iter.len() == 1 {
-                            if iter[0].borrow().evaluations().is_some() && iter[0].borrow().evaluations().unwrap().len() == 1 {
-                                let iter = iter[0].borrow();
-                                let eval_iter = &iter.evaluations().unwrap()[0];
-                                if for_stmt.target.is_name_expr() { //only handle simple variable for now
-                                    let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&for_stmt.target.as_name_expr().unwrap().id.to_string(), &for_stmt.target.range());
-                                    variable.as_ref().unwrap().borrow_mut().evaluations_mut().unwrap().clear();
-                                    variable.as_ref().unwrap().borrow_mut().evaluations_mut().unwrap().push(
-                                        Evaluation::eval_from_symbol(
-                                            &eval_iter.symbol.get_symbol(session, &mut Some(HashMap::from([(S!("parent"), ContextValue::SYMBOL(Rc::downgrade(&symbol_type_rc)))])), &mut vec![], None).0
-                                        )
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+        if eval_iter_node.len() != 1 { //Only handle values that we are sure about
+            return vec![];
+        }
+        let eval = &eval_iter_node[0];
+        if let Some(element) = eval.element_evaluation(session) {
+            return vec![element];
+        }
+        let (weak_symbol, _instance) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
+        let Some(symbol) = weak_symbol.upgrade() else {
+            return vec![];
+        };
+        let symbol_eval = Symbol::follow_ref(&symbol, session, &mut None, false, false, None, &mut vec![]);
+        if symbol_eval.len() != 1 || symbol_eval[0].0.upgrade().is_none() {
+            return vec![];
+        }
+        let symbol_type_rc = symbol_eval[0].0.upgrade().unwrap();
+        let symbol_type = symbol_type_rc.borrow();
+        if symbol_type.typ() != SymType::CLASS {
+            return vec![];
+        }
+        let (iter, _) = symbol_type.get_member_symbol(session, &S!("__iter__"), None, true, false);
+        if iter.len() != 1 || iter[0].borrow().evaluations().is_none() || iter[0].borrow().evaluations().unwrap().len() != 1 {
+            return vec![];
+        }
+        let iter = iter[0].borrow();
+        let eval_iter = &iter.evaluations().unwrap()[0];
+        vec![Evaluation::eval_from_symbol(
+            &eval_iter.symbol.get_symbol(session, &mut Some(HashMap::from([(S!("parent"), ContextValue::SYMBOL(Rc::downgrade(&symbol_type_rc)))])), &mut vec![], None).0
+        )]
+    }
+
+    /* Evaluate the variables of the comprehensions of a statement as the elements of their iterable. The first iterable is
+    evaluated in the enclosing scope, the next ones in the scope of the comprehension, where the previous variables are bound */
+    fn _visit_comprehensions(&mut self, session: &mut SessionInfo, stmt: &Stmt) {
+        let mut scopes: Vec<Rc<RefCell<Symbol>>> = vec![];
+        for (name, comprehension_range, generators) in AstUtils::collect_comprehensions(stmt) {
+            while scopes.last().is_some_and(|scope| !scope.borrow().range().contains_range(comprehension_range)) {
+                scopes.pop();
+            }
+            let parent = scopes.last().cloned().unwrap_or_else(|| self.sym_stack.last().unwrap().clone());
+            let Some(scope) = parent.borrow().get_positioned_symbol(&S!(name), &comprehension_range) else {
+                continue;
+            };
+            for (index, generator) in generators.iter().enumerate() {
+                let Expr::Name(target) = &generator.target else {
+                    continue;
+                };
+                let range = TextRange::new(comprehension_range.start(), target.range.end());
+                let Some(variable) = scope.borrow().get_positioned_symbol(&target.id.to_string(), &range) else {
+                    continue;
+                };
+                let evaluations = match index {
+                    0 => self._eval_iter_element(session, parent.clone(), &generator.iter, &comprehension_range.start()),
+                    _ => self._eval_iter_element(session, scope.clone(), &generator.iter, &generator.iter.range().start()),
+                };
+                variable.borrow_mut().set_evaluations(evaluations);
             }
+            scopes.push(scope);
+        }
+    }
+
+    fn _visit_for(&mut self, session: &mut SessionInfo, for_stmt: &StmtFor) {
+        let evaluations = self._eval_iter_element(session, self.sym_stack.last().unwrap().clone(), &for_stmt.iter, &for_stmt.target.range().start());
+        if !evaluations.is_empty() && for_stmt.target.is_name_expr() { //only handle simple variable for now
+            let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&for_stmt.target.as_name_expr().unwrap().id.to_string(), &for_stmt.target.range());
+            variable.as_ref().unwrap().borrow_mut().set_evaluations(evaluations);
         }
         self.ast_indexes.push(0 as u16);
         for (index_stmt, stmt) in for_stmt.body.iter().enumerate() {
@@ -607,16 +812,7 @@ impl PythonArchEval {
     }
 
     fn _visit_try(&mut self, session: &mut SessionInfo, try_stmt: &StmtTry) {
-        let mut safe_import = false;
-        for handler in try_stmt.handlers.iter() {
-            let handler = handler.as_except_handler().unwrap();
-            if let Some(type_) = &handler.type_ {
-                if type_.is_name_expr() && type_.as_name_expr().unwrap().id.to_string() == "ImportError" {
-                    safe_import = true;
-                }
-            }
-        }
-        self.safe_import.push(safe_import);
+        self.safe_import.push(python_utils::catches_import_error(&try_stmt.handlers));
         self.ast_indexes.push(0 as u16);
         for (index, stmt) in try_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
@@ -660,9 +856,12 @@ impl PythonArchEval {
         let func = self.sym_stack[0].clone();
         if func.borrow().typ() == SymType::FUNCTION {
             if let Some(value) = return_stmt.value.as_ref() {
-                let (eval, diags) = Evaluation::eval_from_ast(session, value, func.clone(), &return_stmt.range.start());
-                self.diagnostics.extend(diags);
-                FunctionSymbol::add_return_evaluations(func, session, eval);
+                let result = Evaluation::analyze_expr(session, value, func.clone(), &return_stmt.range.start());
+                self.diagnostics.extend(result.diagnostics);
+                if self.strict {
+                    self.give_ups.extend(result.give_ups);
+                }
+                FunctionSymbol::add_return_evaluations(func, session, result.evaluations);
             } else {
                 FunctionSymbol::add_return_evaluations(func, session, vec![Evaluation::new_none()]);
             }
@@ -671,38 +870,22 @@ impl PythonArchEval {
 
     fn _visit_with(&mut self, session: &mut SessionInfo, with_stmt: &StmtWith) {
         for item in with_stmt.items.iter() {
-            if let Some(var) = item.optional_vars.as_ref() {
-                match &**var {
-                    Expr::Name(expr_name) => {
-                        let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&expr_name.id.to_string(), &expr_name.range());
-                        if let Some(variable_rc) = variable {
-                            let parent = variable_rc.borrow().parent().unwrap().upgrade().unwrap().clone();
-                            let (eval, diags) = Evaluation::eval_from_ast(session, &item.context_expr, parent, &with_stmt.range.start());
-                            let mut evals = vec![];
-                            for eval in eval.iter() {
-                                let symbol = eval.symbol.get_symbol(session, &mut None, &mut self.diagnostics, Some(self.file.clone()));
-                                if let Some(symbol) = symbol.0.upgrade() {
-                                    let _enter_ = symbol.borrow().get_symbol(&(vec![], vec![S!("__enter__")]), u32::MAX);
-                                    if let Some(_enter_) = _enter_.last() {
-                                        match *_enter_.borrow() {
-                                            Symbol::Function(ref func) => {
-                                                evals.extend(func.evaluations.clone());
-                                            },
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                            variable_rc.borrow_mut().set_evaluations(eval);
-                            self.diagnostics.extend(diags);
-                        }
-                    },
-                    Expr::Tuple(_) => {continue;},
-                    Expr::List(_) => {continue;},
-                    _ => {continue;}
-                }
-            }
-
+            //context managers without target (with self.env.cr.savepoint():) are evaluated too, for their diagnostics
+            let parent = self.sym_stack.last().unwrap().clone();
+            let (evals, diags) = Evaluation::eval_from_ast(session, &item.context_expr, parent.clone(), &with_stmt.range.start());
+            self.diagnostics.extend(diags);
+            let Some(var) = item.optional_vars.as_ref() else {
+                continue;
+            };
+            let Expr::Name(expr_name) = &**var else {
+                continue; //names of unpacked targets are declared without evaluation
+            };
+            let Some(variable) = parent.borrow_mut().get_positioned_symbol(&expr_name.id.to_string(), &expr_name.range()) else {
+                continue;
+            };
+            let mut evaluations = self._eval_enter(session, &evals);
+            evaluations.extend(self._eval_form_record(session, &item.context_expr, parent.clone(), &with_stmt.range.start()));
+            variable.borrow_mut().set_evaluations(evaluations);
         }
         for (index, stmt) in with_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
@@ -711,4 +894,66 @@ impl PythonArchEval {
         }
     }
 
+    /* Evaluation of the value bound by "as": the return of __enter__, or the context manager itself if __enter__ returns
+    self or is unknown, so the context of the evaluation (the model of a recordset for example) is kept */
+    fn _eval_enter(&mut self, session: &mut SessionInfo, evals: &Vec<Evaluation>) -> Vec<Evaluation> {
+        let mut res = vec![];
+        for eval in evals.iter() {
+            let mut entered = vec![];
+            let (weak_symbol, _instance) = eval.symbol.get_symbol(session, &mut None, &mut vec![], Some(self.file.clone()));
+            if let Some(symbol) = weak_symbol.upgrade() {
+                for (class, _) in Symbol::follow_ref(&symbol, session, &mut None, false, false, None, &mut vec![]).iter() {
+                    let Some(class) = class.upgrade() else {
+                        continue;
+                    };
+                    if class.borrow().typ() != SymType::CLASS {
+                        continue;
+                    }
+                    let (enter, _) = class.borrow().get_member_symbol(session, &S!("__enter__"), None, true, false);
+                    for enter in enter.iter() {
+                        let enter_evals = enter.borrow().evaluations().cloned().unwrap_or_default();
+                        for enter_eval in enter_evals.iter() {
+                            let mut context = Some(HashMap::from([(S!("parent"), ContextValue::SYMBOL(Rc::downgrade(&class)))]));
+                            let (returned, _) = enter_eval.symbol.get_symbol(session, &mut context, &mut vec![], None);
+                            if let Some(returned) = returned.upgrade() {
+                                if !Rc::ptr_eq(&returned, &class) {
+                                    entered.push(Evaluation::eval_from_symbol(&Rc::downgrade(&returned)));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if entered.is_empty() {
+                res.push(eval.clone());
+            } else {
+                res.extend(entered);
+            }
+        }
+        res
+    }
+
+    /* Form(record) of odoo.tests edits the record it wraps and gives access to its fields: the variable bound by
+    "with Form(record) as f" gets the evaluations of the record too, so its fields are known in the block */
+    fn _eval_form_record(&mut self, session: &mut SessionInfo, context_expr: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Vec<Evaluation> {
+        let Expr::Call(call) = context_expr else {
+            return vec![];
+        };
+        let Some(record) = call.arguments.find_argument("record", 0) else {
+            return vec![];
+        };
+        let (func_evals, _) = Evaluation::eval_from_ast(session, &call.func, parent.clone(), max_infer);
+        let is_form = func_evals.iter().any(|eval| {
+            eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().is_some_and(|class| {
+                let tree = class.borrow().get_tree();
+                tree.1 == vec![S!("Form")] && tree.0.starts_with(&[S!("odoo"), S!("tests")])
+            })
+        });
+        if !is_form {
+            return vec![];
+        }
+        //the diagnostics of the record are already given by the evaluation of the whole context expression
+        Evaluation::eval_from_ast(session, record, parent, max_infer).0
+    }
+
 }
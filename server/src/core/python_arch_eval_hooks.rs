@@ -13,7 +13,9 @@ use crate::constants::*;
 use crate::threads::SessionInfo;
 use crate::S;
 
+use super::deferred_mutations::DeferredMutation;
 use super::evaluation::Evaluation;
+use super::hook_registry::HookRegistry;
 use super::evaluation::ContextValue;
 use super::evaluation::EvaluationSymbol;
 use super::file_mgr::FileMgr;
@@ -21,6 +23,7 @@ use super::symbols::module_symbol::ModuleSymbol;
 
 type PythonArchEvalHookFile = fn (odoo: &mut SyncOdoo, file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>);
 
+#[derive(Clone)]
 pub struct PythonArchEvalFileHook {
     pub file_tree: Vec<String>,
     pub content_tree: Vec<String>, //if set, will provide symbol in file content instead of the file symbol to func
@@ -36,10 +39,10 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
         let env_file = odoo.get_symbol(&(vec![S!("odoo"), S!("api")], vec![]), u32::MAX);
         let env_class = odoo.get_symbol(&(vec![S!("odoo"), S!("api")], vec![S!("Environment")]), u32::MAX);
         if !env_class.is_empty() {
-            let mut env = symbol.borrow_mut();
             let env_class = env_class.last().unwrap();
             let mut context = HashMap::new();
             context.insert(S!("test_mode"), super::evaluation::ContextValue::BOOLEAN(true));
+            let mut env = symbol.borrow_mut();
             env.set_evaluations(vec![Evaluation {
                 symbol: EvaluationSymbol::new_with_symbol(
                     Rc::downgrade(env_class),
@@ -51,8 +54,8 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
                 value: None,
                 range: None,
             }]);
-            file_symbol.borrow_mut().add_dependency(&mut env_file.last().unwrap().borrow_mut(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
             env.set_doc_string(Some(S!("")));
+            PythonArchEvalHooks::defer_dependency(odoo, &file_symbol, env_file.last().unwrap());
         }
     }},
     PythonArchEvalFileHook { file_tree: vec![S!("odoo"), S!("models")],
@@ -60,9 +63,9 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
                         if_exist_only: true,
                         func: |odoo: &mut SyncOdoo, _file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
         let values: Vec<ruff_python_ast::Expr> = Vec::new();
-        let mut id = symbol.borrow_mut();
-        let range = id.range().clone();
-        id.set_evaluations(vec![Evaluation::new_list(odoo, values, range.clone())]);
+        let range = symbol.borrow().range().clone();
+        let evaluation = Evaluation::new_list(odoo, values, range.clone());
+        symbol.borrow_mut().set_evaluations(vec![evaluation]);
     }},
     PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("api")],
                             content_tree: vec![S!("Environment"), S!("cr")],
@@ -82,7 +85,7 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
                 value: None,
                 range: None,
             }]);
-            file_symbol.borrow_mut().add_dependency(&mut cursor_file.last().unwrap().borrow_mut(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
+            PythonArchEvalHooks::defer_dependency(odoo, &file_symbol, cursor_file.last().unwrap());
         }
     }},
     PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("tests"), S!("common")],
@@ -106,7 +109,7 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
                 value: None,
                 range: None,
             }]);
-            file_symbol.borrow_mut().add_dependency(&mut env_file.last().unwrap().borrow_mut(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
+            PythonArchEvalHooks::defer_dependency(odoo, &file_symbol, env_file.last().unwrap());
         }
     }},
     PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("fields")],
@@ -233,13 +236,78 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
 
 type PythonArchEvalHookFunc = fn (odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>);
 
+#[derive(Clone)]
 pub struct PythonArchEvalFunctionHook {
     pub tree: Tree,
     pub if_exist_only: bool,
     pub func: PythonArchEvalHookFunc
 }
 
-static arch_eval_function_hooks: Lazy<Vec<PythonArchEvalFunctionHook>> = Lazy::new(|| {vec![
+/* Hook called on each function decorated by the symbol of decorator_tree, with the function symbol */
+#[derive(Clone)]
+pub struct PythonArchEvalDecoratorHook {
+    pub decorator_tree: Tree,
+    pub func: PythonArchEvalHookFunc
+}
+
+/* Methods of BaseModel that return a recordset of the same model than self.
+Add a method here to keep the model type through chains like rec.sudo().with_context(lang='fr').partner_id */
+static returns_self_model_methods: &[&str] = &[
+    "__iter__",
+    "sudo",
+    "with_context",
+    "with_company",
+    "with_user",
+    "with_env",
+    "with_prefetch",
+    "create",
+    "copy",
+    "browse",
+    "search",
+    "exists",
+    "ensure_one",
+    "filtered",
+    "filtered_domain",
+    "sorted",
+];
+
+/* Return types of the helpers of odoo.tools that can't be inferred from their code: (file, function, return type) */
+static tools_return_types: &[(&[&str], &str, (&[&str], &[&str]))] = &[
+    (&["odoo", "tools", "misc"], "topological_sort", (&["builtins"], &["list"])),
+    (&["odoo", "tools", "misc"], "format_amount", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "misc"], "format_date", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "misc"], "format_datetime", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "misc"], "format_time", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "misc"], "formatLang", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "misc"], "file_path", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "misc"], "html_escape", (&["markupsafe"], &["Markup"])),
+    (&["odoo", "tools", "float_utils"], "float_round", (&["builtins"], &["float"])),
+    (&["odoo", "tools", "float_utils"], "float_compare", (&["builtins"], &["int"])),
+    (&["odoo", "tools", "float_utils"], "float_is_zero", (&["builtins"], &["bool"])),
+    (&["odoo", "tools", "float_utils"], "float_repr", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "mail"], "html2plaintext", (&["builtins"], &["str"])),
+    (&["odoo", "tools", "mail"], "plaintext2html", (&["markupsafe"], &["Markup"])),
+    (&["odoo", "tools", "mail"], "html_sanitize", (&["markupsafe"], &["Markup"])),
+    (&["odoo", "tools", "mail"], "email_normalize", (&["builtins"], &["str"])),
+];
+
+/* Helpers of odoo.tools returning a list, with the type of its elements: (file, function, element type).
+groupby(records, key) returns a list of (key, group) tuples */
+static tools_list_return_types: &[(&[&str], &str, (&[&str], &[&str]))] = &[
+    (&["odoo", "tools", "misc"], "groupby", (&["builtins"], &["tuple"])),
+    (&["odoo", "tools", "mail"], "email_split", (&["builtins"], &["str"])),
+];
+
+/* Helpers of odoo.tools returning a value of the same type than their first argument, like date_utils.start_of(value, 'month') */
+static tools_returns_first_arg: &[(&[&str], &str)] = &[
+    (&["odoo", "tools", "date_utils"], "start_of"),
+    (&["odoo", "tools", "date_utils"], "end_of"),
+    (&["odoo", "tools", "date_utils"], "add"),
+    (&["odoo", "tools", "date_utils"], "subtract"),
+];
+
+static arch_eval_function_hooks: Lazy<Vec<PythonArchEvalFunctionHook>> = Lazy::new(|| {
+    let mut hooks = vec![
     PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("api")], vec![S!("Environment"), S!("__getitem__")]),
                         if_exist_only: true,
                         func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
@@ -254,96 +322,111 @@ static arch_eval_function_hooks: Lazy<Vec<PythonArchEvalFunctionHook>> = Lazy::n
             range: None
         }]);
     }},
-    PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!("__iter__")]),
+    ];
+    for method in returns_self_model_methods.iter() {
+        hooks.push(PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!(*method)]),
                         if_exist_only: true,
-                        func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
-        symbol.borrow_mut().evaluations_mut().unwrap().clear();
-        symbol.borrow_mut().evaluations_mut().unwrap().push(Evaluation {
-            symbol: EvaluationSymbol::new_self(HashMap::new(), None, None),
-            range: None,
-            value: None
+                        func: PythonArchEvalHooks::_set_returns_self
         });
-    }},
-    PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!("sudo")]),
+    }
+    for method in ["read_group", "_read_group"] {
+        hooks.push(PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!(method)]),
                         if_exist_only: true,
-                        func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
-        let mut sudo = symbol.borrow_mut();
-        sudo.evaluations_mut().unwrap().clear();
-        sudo.evaluations_mut().unwrap().push(Evaluation {
-            symbol: EvaluationSymbol::new_self(
-                HashMap::new(),
-                None,
-                None,
-            ),
-            range: None,
-            value: None
+                        func: PythonArchEvalHooks::_set_read_group_return_type
         });
-    }},
-    PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!("create")]),
+    }
+    for (file, function, _) in tools_return_types.iter() {
+        hooks.push(PythonArchEvalFunctionHook { tree: tree(file.to_vec(), vec![function]),
                         if_exist_only: true,
-                        func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
-        let mut create = symbol.borrow_mut();
-        create.evaluations_mut().unwrap().clear();
-        create.evaluations_mut().unwrap().push(Evaluation {
-            symbol: EvaluationSymbol::new_self(
-                HashMap::new(),
-                None,
-                None,
-            ),
-            range: None,
-            value: None
+                        func: PythonArchEvalHooks::_set_tools_return_type
         });
-    }},
-    PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!("search")]),
+    }
+    for (file, function, _) in tools_list_return_types.iter() {
+        hooks.push(PythonArchEvalFunctionHook { tree: tree(file.to_vec(), vec![function]),
                         if_exist_only: true,
-                        func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
-        let mut search: std::cell::RefMut<Symbol> = symbol.borrow_mut();
-        search.evaluations_mut().unwrap().clear();
-        search.evaluations_mut().unwrap().push(Evaluation {
-            symbol: EvaluationSymbol::new_self(
-                HashMap::new(),
-                None,
-                None,
-            ),
-            range: None,
-            value: None
+                        func: PythonArchEvalHooks::_set_tools_list_return_type
         });
-    }},
-]});
+    }
+    for (file, function) in tools_returns_first_arg.iter() {
+        hooks.push(PythonArchEvalFunctionHook { tree: tree(file.to_vec(), vec![function]),
+                        if_exist_only: true,
+                        func: PythonArchEvalHooks::_set_returns_first_arg
+        });
+    }
+    hooks
+});
 
 pub struct PythonArchEvalHooks {
 }
 
 impl PythonArchEvalHooks {
 
+    pub fn register_odoo_hooks(registry: &mut HookRegistry) {
+        for hook in arch_eval_file_hooks.iter() {
+            registry.register_file_hook(hook.clone());
+        }
+        for hook in arch_eval_function_hooks.iter() {
+            registry.register_function_hook(hook.clone());
+        }
+        for decorator_tree in [(vec![S!("functools")], vec![S!("cached_property")]), (vec![S!("odoo"), S!("tools"), S!("func")], vec![S!("lazy_property")])] {
+            registry.register_decorator_hook(PythonArchEvalDecoratorHook {
+                decorator_tree,
+                func: PythonArchEvalHooks::_set_property
+            });
+        }
+    }
+
+    /* queue_job (OCA/queue): with_delay() and delayable() return a proxy calling the methods of the recordset later */
+    pub fn register_oca_queue_job_hooks(registry: &mut HookRegistry) {
+        for method in ["with_delay", "delayable"] {
+            registry.register_function_hook(PythonArchEvalFunctionHook {
+                tree: (vec![S!("odoo"), S!("addons"), S!("queue_job"), S!("models"), S!("base")], vec![S!("Base"), S!(method)]),
+                if_exist_only: true,
+                func: PythonArchEvalHooks::_set_returns_self
+            });
+        }
+    }
+
+    /* The hooks borrow mutably the symbols they change: no borrow of them may be held while they run. The changes of the
+    files are queued (see MutationQueue), and applied once the hooks are run */
     pub fn on_file_eval(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
         let tree = symbol.borrow().get_tree();
-        let name = symbol.borrow().name().clone();
-        for hook in arch_eval_file_hooks.iter() {
-            if name.eq(hook.file_tree.last().unwrap()) {
-                if tree.0 == hook.file_tree {
-                    if hook.content_tree.is_empty() {
-                        (hook.func)(odoo, symbol.clone(), symbol.clone());
-                    } else {
-                        let sub_symbol = symbol.borrow().get_symbol(&(vec![], hook.content_tree.clone()), u32::MAX);
-                        if !sub_symbol.is_empty() {
-                            (hook.func)(odoo, symbol.clone(), sub_symbol.last().unwrap().clone());
-                        }
-                    }
+        for hook in odoo.hooks.get_file_hooks(&tree.0).iter() {
+            debug_assert!(symbol.try_borrow_mut().is_ok(), "file {:?} is borrowed while running its eval hooks", tree.0);
+            if hook.content_tree.is_empty() {
+                (hook.func)(odoo, symbol.clone(), symbol.clone());
+            } else {
+                let sub_symbol = symbol.borrow().get_symbol(&(vec![], hook.content_tree.clone()), u32::MAX);
+                if !sub_symbol.is_empty() {
+                    debug_assert!(sub_symbol.last().unwrap().try_borrow_mut().is_ok(), "symbol {:?} is borrowed while running its eval hook", hook.content_tree);
+                    (hook.func)(odoo, symbol.clone(), sub_symbol.last().unwrap().clone());
                 }
             }
         }
+        odoo.apply_deferred_mutations();
+    }
+
+    /* The file evaluated by a hook depends on the arch of the file of a symbol it uses, like odoo/api.py for Environment */
+    fn defer_dependency(odoo: &mut SyncOdoo, file_symbol: &Rc<RefCell<Symbol>>, dependency: &Rc<RefCell<Symbol>>) {
+        odoo.deferred_mutations.push(DeferredMutation::AddDependency {
+            file: Rc::downgrade(file_symbol),
+            dependency: Rc::downgrade(dependency),
+            step: BuildSteps::ARCH_EVAL,
+            dep_level: BuildSteps::ARCH,
+        });
     }
 
     pub fn on_function_eval(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
         let tree = symbol.borrow().get_tree();
-        let name = symbol.borrow().name().clone();
-        for hook in arch_eval_function_hooks.iter() {
-            if name.eq(hook.tree.1.last().unwrap()) {
-                if tree == hook.tree {
-                    (hook.func)(odoo, symbol.clone());
-                }
-            }
+        for hook in odoo.hooks.get_function_hooks(&tree).iter() {
+            debug_assert!(symbol.try_borrow_mut().is_ok(), "function {:?} is borrowed while running its eval hooks", tree);
+            (hook.func)(odoo, symbol.clone());
+        }
+    }
+
+    pub fn on_decorator(odoo: &mut SyncOdoo, decorator_tree: &Tree, function: Rc<RefCell<Symbol>>) {
+        for hook in odoo.hooks.get_decorator_hooks(decorator_tree).iter() {
+            (hook.func)(odoo, function.clone());
         }
     }
 
@@ -367,14 +450,15 @@ impl PythonArchEvalHooks {
                             } else {
                                 from_module = None;
                             }
+                            //the file can be borrowed by the evaluation running the hook
                             if let Some(file_symbol) = file_symbol {
-                                let mut f = file_symbol.borrow_mut();
-                                f.add_model_dependencies(model);
+                                session.sync_odoo.deferred_mutations.push(DeferredMutation::AddModelDependency { file: Rc::downgrade(&file_symbol), model: s.clone() });
                             }
                             let symbols = model.clone().borrow().get_main_symbols(session, from_module.clone(), &mut None);
                             if symbols.len() > 0 {
                                 for s in symbols.iter() {
-                                    if from_module.is_none() || ModuleSymbol::is_in_deps(session, &from_module.as_ref().unwrap(),&s.borrow().find_module().unwrap().borrow().as_module_package().dir_name, &mut None) {
+                                    let module = s.borrow().owning_module();
+                                    if from_module.is_none() || module.is_some_and(|module| ModuleSymbol::is_in_deps(session, &from_module.as_ref().unwrap(), &module.borrow().as_module_package().dir_name, &mut None)) {
                                         return (Rc::downgrade(s), true);
                                     }
                                 }
@@ -454,6 +538,102 @@ impl PythonArchEvalHooks {
         (evaluation_sym.get_weak().weak.clone() , evaluation_sym.get_weak().instance)
     }
 
+    fn _set_property(_odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
+        if symbol.borrow().typ() == SymType::FUNCTION {
+            symbol.borrow_mut().as_func_mut().is_property = true;
+        }
+    }
+
+    fn _set_returns_self(_odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
+        let mut function = symbol.borrow_mut();
+        function.evaluations_mut().unwrap().clear();
+        function.evaluations_mut().unwrap().push(Evaluation {
+            symbol: EvaluationSymbol::new_self(
+                HashMap::new(),
+                None,
+                None,
+            ),
+            range: None,
+            value: None
+        });
+    }
+
+    fn _set_tools_return_type(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
+        let symbol_tree = symbol.borrow().get_tree();
+        let Some((_, _, (return_file, return_content))) = tools_return_types.iter()
+            .find(|(file, function, _)| symbol_tree == tree(file.to_vec(), vec![function])) else {
+            return;
+        };
+        let return_tree = tree(return_file.to_vec(), return_content.to_vec());
+        let return_sym = odoo.get_symbol(&return_tree, u32::MAX);
+        let Some(return_sym) = return_sym.last() else {
+            let file = symbol.borrow().get_file().clone();
+            odoo.deferred_mutations.push(DeferredMutation::AddNotFoundPath { file: file.unwrap(), step: BuildSteps::ARCH_EVAL, path: flatten_tree(&return_tree) });
+            odoo.not_found_symbols.insert(symbol);
+            return;
+        };
+        symbol.borrow_mut().set_evaluations(vec![Evaluation {
+            symbol: EvaluationSymbol::new_with_symbol(
+                Rc::downgrade(return_sym),
+                true,
+                HashMap::new(),
+                None,
+                None
+            ),
+            value: None,
+            range: None
+        }]);
+    }
+
+    fn _set_tools_list_return_type(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
+        let symbol_tree = symbol.borrow().get_tree();
+        let Some((_, _, (element_file, element_content))) = tools_list_return_types.iter()
+            .find(|(file, function, _)| symbol_tree == tree(file.to_vec(), vec![function])) else {
+            return;
+        };
+        let range = symbol.borrow().range().clone();
+        let mut evaluation = Evaluation::new_list_of(odoo, &tree(element_file.to_vec(), element_content.to_vec()), range);
+        evaluation.range = None;
+        symbol.borrow_mut().set_evaluations(vec![evaluation]);
+    }
+
+    /* read_group returns a list of dictionaries, whose keys are given by the specs of each call (see ReadGroupCall::result_keys).
+    _read_group returns a list of tuples since Odoo 17, and a list of dictionaries before */
+    fn _set_read_group_return_type(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
+        let element = if symbol.borrow().name() == "_read_group" && odoo.version_major >= 17 { "tuple" } else { "dict" };
+        let range = symbol.borrow().range().clone();
+        let mut evaluation = Evaluation::new_list_of(odoo, &(vec![S!("builtins")], vec![S!(element)]), range);
+        evaluation.range = None;
+        symbol.borrow_mut().set_evaluations(vec![evaluation]);
+    }
+
+    fn _set_returns_first_arg(_odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
+        symbol.borrow_mut().set_evaluations(vec![Evaluation {
+            symbol: EvaluationSymbol::new_with_symbol(
+                Weak::new(),
+                true,
+                HashMap::new(),
+                None,
+                Some(PythonArchEvalHooks::eval_first_arg)
+            ),
+            value: None,
+            range: None
+        }]);
+    }
+
+    /* The type of the first argument of the call, given in the context by the evaluation of calls */
+    fn eval_first_arg(_session: &mut SessionInfo, _evaluation_sym: &EvaluationSymbol, context: &mut Option<Context>, _diagnostics: &mut Vec<Diagnostic>, _file_symbol: Option<Rc<RefCell<Symbol>>>) -> (Weak<RefCell<Symbol>>, bool)
+    {
+        let Some(context) = context else {
+            return (Weak::new(), false);
+        };
+        let instance = context.get(&S!("first_arg_instance")).map(|instance| instance.as_bool()).unwrap_or(true);
+        match context.get(&S!("first_arg")) {
+            Some(ContextValue::SYMBOL(arg)) => (arg.clone(), instance),
+            _ => (Weak::new(), false)
+        }
+    }
+
     fn _update_get_eval(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>, tree: Tree) {
         let get_sym = symbol.borrow().get_symbol(&(vec![], vec![S!("__get__")]), u32::MAX);
         if get_sym.is_empty() {
@@ -461,8 +641,8 @@ impl PythonArchEvalHooks {
         }
         let return_sym = odoo.get_symbol(&tree, u32::MAX);
         if return_sym.is_empty() {
-            let file = symbol.borrow_mut().get_file().clone();
-            file.as_ref().unwrap().upgrade().unwrap().borrow_mut().not_found_paths_mut().push((BuildSteps::ARCH_EVAL, flatten_tree(&tree)));
+            let file = symbol.borrow().get_file().clone();
+            odoo.deferred_mutations.push(DeferredMutation::AddNotFoundPath { file: file.unwrap(), step: BuildSteps::ARCH_EVAL, path: flatten_tree(&tree) });
             odoo.not_found_symbols.insert(symbol);
             return;
         }
@@ -4,11 +4,17 @@ use std::cell::RefCell;
 use lsp_types::notification::ShowMessage;
 use lsp_types::MessageType;
 use ruff_python_ast::Expr;
-use lsp_types::{Diagnostic, ShowMessageParams, notification::Notification};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, ShowMessageParams, notification::Notification};
+use ruff_python_ast::Stmt;
+use ruff_text_size::TextRange;
 use tracing::{error, info};
 
-use crate::constants::{BuildStatus, BuildSteps, SymType, DEBUG_ODOO_BUILDER};
-use crate::core::model::{Model, ModelData};
+use crate::constants::{BuildStatus, BuildSteps, SymType, DEBUG_ODOO_BUILDER, EXTENSION_NAME};
+use crate::core::file_mgr::FileInfo;
+use crate::core::model::{Model, ModelContribution, ModelData};
+use crate::core::odoo::SyncOdoo;
+use crate::core::routes::{Route, RouteDecl, ROUTE_AUTHS, ROUTE_TYPES};
+use crate::features::ast_utils::AstUtils;
 use crate::core::symbols::symbol::Symbol;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
@@ -51,15 +57,21 @@ impl PythonOdooBuilder {
             symbol.set_build_status(BuildSteps::ODOO, BuildStatus::DONE);
             return;
         }
+        let version = file_info.borrow().version;
+        let is_standalone = SyncOdoo::is_standalone_tree(&symbol.get_tree());
         drop(symbol);
-        self._load(session);
-        file_info.borrow_mut().replace_diagnostics(BuildSteps::ODOO, self.diagnostics.clone());
+        //standalone files are not part of a module, their classes are not registered in Odoo
+        if !is_standalone {
+            self._load(session, &file_info.borrow());
+            self._load_routes(session, &path, &file_info.borrow());
+        }
+        file_info.borrow_mut().replace_diagnostics_for_version(BuildSteps::ODOO, self.diagnostics.clone(), version);
         session.sync_odoo.add_to_validations(self.symbol.clone());
         let mut symbol = self.symbol.borrow_mut();
         symbol.set_build_status(BuildSteps::ODOO, BuildStatus::DONE);
     }
 
-    fn _load(&mut self, session: &mut SessionInfo) {
+    fn _load(&mut self, session: &mut SessionInfo, file_info: &FileInfo) {
         let symbol = self.symbol.borrow_mut();
         let iterator = symbol.get_sorted_symbols();
         drop(symbol);
@@ -71,6 +83,7 @@ impl PythonOdooBuilder {
             if !self.test_symbol_is_model(session, &sym, &mut s_to_build) {
                 continue;
             }
+            s_to_build.update_owning_module();
             self._load_class_inherit(session, &mut s_to_build);
             self._load_class_name(session, &mut s_to_build);
             if s_to_build.as_class_sym()._model.is_none() {
@@ -78,18 +91,94 @@ impl PythonOdooBuilder {
             }
             self._load_class_inherits(session, &mut s_to_build);
             self._load_class_attributes(session, &mut s_to_build);
-            let model = session.sync_odoo.models.get_mut(&s_to_build.as_class_sym()._model.as_ref().unwrap().name).cloned();
-            if model.is_none() {
-                let model = Model::new(s_to_build.as_class_sym()._model.as_ref().unwrap().name.clone(), sym.clone());
-                session.sync_odoo.models.insert(s_to_build.as_class_sym()._model.as_ref().unwrap().name.clone(), Rc::new(RefCell::new(model)));
-            } else {
-                let model = model.unwrap();
-                drop(s_to_build);
-                model.borrow_mut().add_symbol(session, sym.clone());
+            let model_data = s_to_build.as_class_sym()._model.as_ref().unwrap();
+            let class_indexes = s_to_build.ast_indexes().cloned().unwrap_or_default();
+            let contribution = file_info.ast.as_ref().filter(|_| !class_indexes.is_empty())
+                .and_then(|ast| AstUtils::find_stmt_from_ast(ast, &class_indexes).as_class_def_stmt())
+                .map(|class_def| ModelContribution::from_class(model_data, class_def, |range| file_info.get_text(range)));
+            let model_name = model_data.name.clone();
+            drop(s_to_build);
+            let model = session.sync_odoo.models.entry(model_name.clone()).or_insert_with(|| Rc::new(RefCell::new(Model::new(model_name)))).clone();
+            model.borrow_mut().add_symbol(session, sym.clone(), contribution);
+        }
+    }
+
+    /* Index the routes declared by the controllers of the file, and check them against the routes of other controllers */
+    fn _load_routes(&mut self, session: &mut SessionInfo, path: &String, file_info: &FileInfo) {
+        session.sync_odoo.routes.remove_file(path);
+        let controller = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("http")], vec![S!("Controller")]), u32::MAX);
+        let Some(controller) = controller.first().cloned() else {
+            return;
+        };
+        let Some(ast) = file_info.ast.as_ref() else {
+            return;
+        };
+        let iterator = self.symbol.borrow().get_sorted_symbols();
+        for class in iterator {
+            if class.borrow().typ() != SymType::CLASS || Rc::ptr_eq(&class, &controller) {
+                continue;
             }
+            if !class.borrow().as_class_sym().inherits(&controller, &mut None) {
+                continue;
+            }
+            let class_indexes = class.borrow().ast_indexes().cloned().unwrap_or_default();
+            if class_indexes.is_empty() {
+                continue;
+            }
+            let Some(class_stmt) = AstUtils::find_stmt_from_ast(ast, &class_indexes).as_class_def_stmt() else {
+                continue;
+            };
+            for stmt in class_stmt.body.iter() {
+                let Stmt::FunctionDef(func) = stmt else {
+                    continue;
+                };
+                let function = class.borrow().get_symbol(&(vec![], vec![S!(func.name.as_str())]), u32::MAX);
+                let Some(function) = function.last() else {
+                    continue;
+                };
+                for decl in RouteDecl::from_function(func) {
+                    self._check_route(session, &decl, func.name.as_str());
+                    session.sync_odoo.routes.add(Route {
+                        decl,
+                        function: Rc::downgrade(function),
+                        file_path: path.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn _check_route(&mut self, session: &mut SessionInfo, decl: &RouteDecl, function_name: &str) {
+        if let Some((auth, range)) = decl.auth.as_ref() {
+            if !ROUTE_AUTHS.contains(&auth.as_str()) {
+                self._add_route_diagnostic(range, "OLS20702", format!("Unknown auth \"{}\" for route {}. Expected one of: {}", auth, decl.path, ROUTE_AUTHS.join(", ")));
+            }
+        }
+        if let Some((typ, range)) = decl.typ.as_ref() {
+            if !ROUTE_TYPES.contains(&typ.as_str()) {
+                self._add_route_diagnostic(range, "OLS20702", format!("Unknown type \"{}\" for route {}. Expected one of: {}", typ, decl.path, ROUTE_TYPES.join(", ")));
+            }
+        }
+        //a subclass redefining the same method with the same url is an override, not a duplicate
+        let duplicate = session.sync_odoo.routes.get(&decl.path).into_iter().find(|route| route.function_name() != function_name)
+            .map(|route| route.handler_name());
+        if let Some(duplicate) = duplicate {
+            self._add_route_diagnostic(&decl.range, "OLS20701", format!("Route {} is already declared by {}", decl.path, duplicate));
         }
     }
 
+    fn _add_route_diagnostic(&mut self, range: &TextRange, code: &str, message: String) {
+        self.diagnostics.push(Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(DiagnosticSeverity::WARNING),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None,
+        ));
+    }
+
     fn _load_class_inherit(&mut self, session: &mut SessionInfo, symbol: &mut Symbol) {
         let _inherit = symbol.get_symbol(&(vec![], vec![S!("_inherit")]), u32::MAX);
         if let Some(_inherit) = _inherit.last() {
@@ -241,6 +330,13 @@ impl PythonOdooBuilder {
         } else {
             symbol.as_class_sym_mut()._model.as_mut().unwrap().rec_name = Some(S!("name")); //TODO if name is not on model, take 'id'
         }
+        let rec_names_search = self._get_attribute(session, symbol, &"_rec_names_search".to_string());
+        if let Some(EvaluationValue::LIST(l) | EvaluationValue::TUPLE(l)) = rec_names_search {
+            symbol.as_class_sym_mut()._model.as_mut().unwrap().rec_names_search = l.iter()
+                .filter_map(|e| e.as_string_literal_expr().map(|s| S!(s.value.to_str()))).collect();
+        } else {
+            symbol.as_class_sym_mut()._model.as_mut().unwrap().rec_names_search = vec![];
+        }
         let _check_company_auto = self._get_attribute(session, symbol, &"_check_company_auto".to_string());
         if let Some(EvaluationValue::CONSTANT(Expr::BooleanLiteral(b))) = _check_company_auto {
             symbol.as_class_sym_mut()._model.as_mut().unwrap().check_company_auto = b.value;
@@ -1,4 +1,5 @@
-use ruff_python_ast::{Expr, ExprName};
+use ruff_python_ast::{BoolOp, CmpOp, ExceptHandler, Expr, ExprAttribute, ExprCall, ExprContext, ExprList, ExprName, ExprStringLiteral, FStringElement, FStringPart, Identifier, Operator, Stmt, StmtFunctionDef, StmtIf, UnaryOp};
+use ruff_text_size::{Ranged, TextRange};
 use tracing::error;
 
 #[derive(Debug, Clone)]
@@ -11,11 +12,19 @@ pub struct Assign {
 
 fn _link_tuples(targets: Vec<Expr>, values: Vec<Expr>) -> Vec<Assign> {
     let mut res: Vec<Assign> = Vec::new();
-    if targets.len() != values.len() {
+    let starred = targets.iter().position(|target| target.is_starred_expr());
+    let nb_after = starred.map(|star| targets.len() - star - 1).unwrap_or(0);
+    if (starred.is_none() && targets.len() != values.len()) || (starred.is_some() && values.len() < targets.len() - 1) {
         error!("Invalid stmt: can't unpack a tuple with a different number of elements");
         return res;
     }
     for (index, target) in targets.iter().enumerate() {
+        //targets after a starred one are aligned on the end of the values
+        let value = match starred {
+            Some(star) if index == star => None, //the starred target can receive no value at all
+            Some(star) if index > star => values.get(values.len() - (targets.len() - index)),
+            _ => values.get(index),
+        };
         match target {
             Expr::Attribute(_) => {},
             Expr::Subscript(_) => {},
@@ -23,57 +32,26 @@ fn _link_tuples(targets: Vec<Expr>, values: Vec<Expr>) -> Vec<Assign> {
                 res.push(Assign {
                     target: expr.clone(),
                     annotation: None,
-                    value: Some(values.get(index).unwrap().clone()),
+                    value: value.cloned(),
                     index: None,
                 });
             }
-            Expr::Tuple(expr) => {
-                let value = values.get(index).unwrap();
-                if value.is_tuple_expr() {
-                    let mut inner_unpack = _link_tuples(expr.elts.clone(), value.clone().tuple_expr().unwrap().elts.clone());
-                    res.append(&mut inner_unpack);
-                } else if value.is_list_expr() {
-                    let mut inner_unpack = _link_tuples(expr.elts.clone(), value.clone().list_expr().unwrap().elts.clone());
-                    res.append(&mut inner_unpack);
-                } else {
-                    for (index, target) in expr.elts.iter().enumerate() {
-                        match target {
-                            Expr::Name(tar) => {
-                                res.push(Assign {
-                                    target: tar.clone(),
-                                    annotation: None,
-                                    value: Some(value.clone()),
-                                    index: Some(index),
-                                });
-                            }
-                            _ => {continue;}
-                        }
-                    }
+            Expr::Starred(expr) => {
+                //the starred target receives a list of all the values that are not unpacked to the other targets
+                if let Expr::Name(name) = expr.value.as_ref() {
+                    res.push(Assign {
+                        target: name.clone(),
+                        annotation: None,
+                        value: Some(_list_of(values[index..values.len() - nb_after].to_vec(), expr.range)),
+                        index: None,
+                    });
                 }
+            }
+            Expr::Tuple(expr) => {
+                res.append(&mut _unpack_elts(&expr.elts, value.unwrap()));
             },
             Expr::List(expr) => {
-                let value = values.get(index).unwrap();
-                if value.is_tuple_expr() {
-                    let mut inner_unpack = _link_tuples(expr.elts.clone(), value.clone().tuple_expr().unwrap().elts.clone());
-                    res.append(&mut inner_unpack);
-                } else if value.is_list_expr() {
-                    let mut inner_unpack = _link_tuples(expr.elts.clone(), value.clone().list_expr().unwrap().elts.clone());
-                    res.append(&mut inner_unpack);
-                } else {
-                    for (index, target) in expr.elts.iter().enumerate() {
-                        match target {
-                            Expr::Name(tar) => {
-                                res.push(Assign {
-                                    target: tar.clone(),
-                                    annotation: None,
-                                    value: Some(value.clone()),
-                                    index: Some(index),
-                                });
-                            }
-                            _ => {continue;}
-                        }
-                    }
-                }
+                res.append(&mut _unpack_elts(&expr.elts, value.unwrap()));
             }
             _ => {}
         }
@@ -81,6 +59,53 @@ fn _link_tuples(targets: Vec<Expr>, values: Vec<Expr>) -> Vec<Assign> {
     res
 }
 
+/* Unpack a value to a tuple or list of targets. If the value is not a literal tuple or list, each target is
+associated to the whole value with its index */
+fn _unpack_elts(elts: &Vec<Expr>, value: &Expr) -> Vec<Assign> {
+    if let Expr::Tuple(tuple) = value {
+        return _link_tuples(elts.clone(), tuple.elts.clone());
+    }
+    if let Expr::List(list) = value {
+        return _link_tuples(elts.clone(), list.elts.clone());
+    }
+    let mut res = vec![];
+    let starred = elts.iter().position(|target| target.is_starred_expr());
+    for (index, target) in elts.iter().enumerate() {
+        match target {
+            Expr::Name(tar) => {
+                //the index of the targets after a starred one depends on the length of the value, that is unknown
+                let after_star = starred.is_some_and(|star| index > star);
+                res.push(Assign {
+                    target: tar.clone(),
+                    annotation: None,
+                    value: if after_star { None } else { Some(value.clone()) },
+                    index: if after_star { None } else { Some(index) },
+                });
+            }
+            Expr::Starred(expr) => {
+                if let Expr::Name(name) = expr.value.as_ref() {
+                    res.push(Assign {
+                        target: name.clone(),
+                        annotation: None,
+                        value: Some(_list_of(vec![], expr.range)),
+                        index: None,
+                    });
+                }
+            }
+            _ => {continue;}
+        }
+    }
+    res
+}
+
+fn _list_of(elts: Vec<Expr>, range: TextRange) -> Expr {
+    Expr::List(ExprList {
+        range,
+        elts,
+        ctx: ExprContext::Load,
+    })
+}
+
 pub fn unpack_assign(targets: &Vec<Expr>, annotation: Option<&Box<Expr>>, value: Option<&Box<Expr>>) -> Vec<Assign> {
     //Given the target, the annotation and the values, return a list of tuples (variable: ExprName, annotation, value)
     //for each variable, associating annotation and value for the right variable
@@ -90,7 +115,7 @@ pub fn unpack_assign(targets: &Vec<Expr>, annotation: Option<&Box<Expr>>, value:
     // Ex: for "a: int", return [("a", "int", None)]
     // Ex: for "(a, (b, c)) = (1, (2, 3))", return [("a", None, 1), ("b", None, 2), ("c", None, 3)]
     // Ex: for "a, b = b, a = 1, 2" return [("a", None, 1), ("b", None, 2), ("a", None, 2), ("b", None, 1)]
-    // Ex: for "a, *b, c, d = 1, 2, 3, 4, 5" return [("a", None, 1), ("b", None, (2, 3)), ("c", None, 4), ("d", None, 5)] //TODO
+    // Ex: for "a, *b, c, d = 1, 2, 3, 4, 5" return [("a", None, 1), ("b", None, [2, 3]), ("c", None, 4), ("d", None, 5)]
     let mut res: Vec<Assign> = Vec::new();
 
     for target in targets.iter() {
@@ -126,61 +151,439 @@ pub fn unpack_assign(targets: &Vec<Expr>, annotation: Option<&Box<Expr>>, value:
             Expr::Tuple(expr) => {
                 // if we have a tuple, we want to untuple the value if possible. If not or because we don't know
                 // the type of the value, we return the value with an index
-                if value.is_none() {
+                let Some(value) = value else {
                     error!("Invalid stmt: can't annotate a tuple");
                     continue;
-                }
-                let value = value.unwrap();
-                if value.is_tuple_expr() {
-                    res.append(&mut _link_tuples(expr.elts.clone(), value.clone().tuple_expr().unwrap().elts.clone()));
-                } else if value.is_list_expr() {
-                    res.append(&mut _link_tuples(expr.elts.clone(), value.clone().list_expr().unwrap().elts.clone()));
-                } else {
-                    for (index, target) in expr.elts.iter().enumerate() {
-                        match target {
-                            Expr::Name(tar) => {
-                                res.push(Assign {
-                                    target: tar.clone(),
-                                    annotation: None,
-                                    value: Some(*value.clone()),
-                                    index: Some(index),
-                                });
-                            }
-                            _ => {continue;}
-                        }
-                    }
-                }
+                };
+                res.append(&mut _unpack_elts(&expr.elts, value));
             }
             Expr::List(expr) => {
                 // Same code than for Tuple
-                if value.is_none() {
+                let Some(value) = value else {
                     error!("Invalid stmt: can't annotate a List");
                     continue;
+                };
+                res.append(&mut _unpack_elts(&expr.elts, value));
+            }
+            _ => {}
+        }
+    }
+
+    res
+}
+/* Names bound by the target of a for or a with statement: "x", "a, (b, *c)"... Attributes and subscripts bind no name */
+pub fn target_names(target: &Expr) -> Vec<&ExprName> {
+    match target {
+        Expr::Name(name) => vec![name],
+        Expr::Starred(starred) => target_names(&starred.value),
+        Expr::Tuple(tuple) => tuple.elts.iter().flat_map(target_names).collect(),
+        Expr::List(list) => list.elts.iter().flat_map(target_names).collect(),
+        _ => vec![],
+    }
+}
+
+/* Evaluate a test comparing the version of Odoo with a literal, like "odoo.release.version_info >= (17, 0)" or
+"release.version_info[0] < 16". Return None if the test can't be statically evaluated. */
+pub fn eval_version_condition(test: &Expr, version: (u32, u32, u32)) -> Option<bool> {
+    match test {
+        Expr::UnaryOp(op) if op.op == UnaryOp::Not => eval_version_condition(&op.operand, version).map(|value| !value),
+        Expr::BoolOp(bool_op) => {
+            let values: Vec<Option<bool>> = bool_op.values.iter().map(|value| eval_version_condition(value, version)).collect();
+            match bool_op.op {
+                BoolOp::And if values.contains(&Some(false)) => Some(false),
+                BoolOp::Or if values.contains(&Some(true)) => Some(true),
+                _ if values.contains(&None) => None,
+                BoolOp::And => Some(true),
+                BoolOp::Or => Some(false),
+            }
+        },
+        Expr::Compare(compare) if compare.ops.len() == 1 => {
+            let version = [version.0, version.1, version.2];
+            let (left, right) = (compare.left.as_ref(), &compare.comparators[0]);
+            let (lhs, rhs) = match (_version_info_slice(left, &version), _version_info_slice(right, &version)) {
+                (Some(lhs), None) => (lhs, _int_tuple(right)?),
+                (None, Some(rhs)) => (_int_tuple(left)?, rhs),
+                _ => return None,
+            };
+            //python compares tuples of different lengths element by element, then by length
+            let ordering = lhs.cmp(&rhs);
+            match compare.ops[0] {
+                CmpOp::Lt => Some(ordering.is_lt()),
+                CmpOp::LtE => Some(ordering.is_le()),
+                CmpOp::Gt => Some(ordering.is_gt()),
+                CmpOp::GtE => Some(ordering.is_ge()),
+                CmpOp::Eq => Some(ordering.is_eq()),
+                CmpOp::NotEq => Some(ordering.is_ne()),
+                _ => None,
+            }
+        },
+        _ => None
+    }
+}
+
+/* Return, for the body of the if statement then for each elif/else clause, if it is never executed with the given version
+of Odoo. Branches whose test can't be evaluated are considered as alive. */
+pub fn dead_if_branches(if_stmt: &StmtIf, version: (u32, u32, u32)) -> Vec<bool> {
+    let mut res = vec![];
+    let mut previous_taken = false;
+    let mut previous_unknown = false;
+    let tests = std::iter::once(Some(if_stmt.test.as_ref())).chain(if_stmt.elif_else_clauses.iter().map(|clause| clause.test.as_ref()));
+    for test in tests {
+        let value = match test {
+            Some(test) => eval_version_condition(test, version),
+            None => Some(true),
+        };
+        res.push(previous_taken || value == Some(false));
+        if value == Some(true) && !previous_unknown {
+            previous_taken = true;
+        }
+        if value.is_none() {
+            previous_unknown = true;
+        }
+    }
+    res
+}
+
+/* Range covering all the statements of a body */
+pub fn body_range(body: &[Stmt]) -> Option<TextRange> {
+    Some(TextRange::new(body.first()?.range().start(), body.last()?.range().end()))
+}
+
+/* If expr is the version_info of odoo.release (or an index of it), return the corresponding part of the version */
+fn _version_info_slice(expr: &Expr, version: &[u32; 3]) -> Option<Vec<u32>> {
+    match expr {
+        Expr::Attribute(attr) if attr.attr.as_str() == "version_info" => {
+            match attr.value.as_ref() {
+                Expr::Name(name) if name.id.as_str() == "release" => Some(version.to_vec()),
+                Expr::Attribute(parent) if parent.attr.as_str() == "release" => Some(version.to_vec()),
+                _ => None
+            }
+        },
+        Expr::Subscript(subscript) => {
+            let full = _version_info_slice(&subscript.value, version)?;
+            let Expr::NumberLiteral(index) = subscript.slice.as_ref() else {
+                return None;
+            };
+            let index = index.value.as_int()?.as_usize()?;
+            full.get(index).map(|value| vec![*value])
+        },
+        _ => None
+    }
+}
+
+fn _int_tuple(expr: &Expr) -> Option<Vec<u32>> {
+    match expr {
+        Expr::Tuple(tuple) => tuple.elts.iter().map(|elt| _int_literal(elt)).collect(),
+        other => _int_literal(other).map(|value| vec![value]),
+    }
+}
+
+fn _int_literal(expr: &Expr) -> Option<u32> {
+    let Expr::NumberLiteral(number) = expr else {
+        return None;
+    };
+    number.value.as_int()?.as_u32()
+}
+
+/* Exceptions whose handler catches a failed import */
+const IMPORT_ERRORS: [&str; 2] = ["ImportError", "ModuleNotFoundError"];
+
+/* Return true if one of the handlers of a try statement catches a failed import, like `except ImportError:` or
+`except (ImportError, ModuleNotFoundError):`. Imports of the try body are optional dependencies. A bare `except:` or an
+`except Exception:` catches any error of the body, and doesn't make its imports optional */
+pub fn catches_import_error(handlers: &[ExceptHandler]) -> bool {
+    let is_import_error = |expr: &Expr| expr.as_name_expr().is_some_and(|name| IMPORT_ERRORS.contains(&name.id.as_str()));
+    handlers.iter().any(|handler| {
+        let ExceptHandler::ExceptHandler(handler) = handler;
+        match handler.type_.as_deref() {
+            None => false,
+            Some(Expr::Tuple(tuple)) => tuple.elts.iter().any(is_import_error),
+            Some(expr) => is_import_error(expr),
+        }
+    })
+}
+
+/* Method of the test classes run once before their tests. The records it caches on the class (cls.partner = ...) are
+used by the tests as attributes of self */
+pub const CLASS_SETUP_METHOD: &str = "setUpClass";
+
+/* Return the setUpClass classmethod of a class body, if any */
+pub fn find_class_setup(class_body: &[Stmt]) -> Option<&StmtFunctionDef> {
+    class_body.iter().filter_map(|stmt| stmt.as_function_def_stmt()).find(|func_def| {
+        func_def.name.as_str() == CLASS_SETUP_METHOD
+            && func_def.decorator_list.iter().any(|decorator| decorator.expression.as_name_expr().is_some_and(|name| name.id.as_str() == "classmethod"))
+    })
+}
+
+/* Attributes assigned on the first parameter (cls) of a classmethod, with their value: `cls.partner = cls.env[...].create(...)`.
+Assignments nested in with, if, for and try blocks are included */
+pub fn cls_attribute_assigns(func_def: &StmtFunctionDef) -> Vec<(&ExprAttribute, &Expr)> {
+    let mut res = vec![];
+    let Some(cls) = func_def.parameters.posonlyargs.iter().chain(&func_def.parameters.args).next() else {
+        return res;
+    };
+    _cls_attribute_assigns(&func_def.body, cls.parameter.name.as_str(), &mut res);
+    res
+}
+
+fn _cls_attribute_assigns<'a>(body: &'a [Stmt], cls: &str, res: &mut Vec<(&'a ExprAttribute, &'a Expr)>) {
+    let is_cls_attribute = |target: &Expr| target.as_attribute_expr().is_some_and(|attr| attr.value.as_name_expr().is_some_and(|name| name.id.as_str() == cls));
+    for stmt in body.iter() {
+        match stmt {
+            Stmt::Assign(assign) => {
+                for target in assign.targets.iter().filter(|target| is_cls_attribute(target)) {
+                    res.push((target.as_attribute_expr().unwrap(), assign.value.as_ref()));
                 }
-                let value = value.unwrap();
-                if value.is_tuple_expr() {
-                    res.append(&mut _link_tuples(expr.elts.clone(), value.clone().tuple_expr().unwrap().elts.clone()));
-                } else if value.is_list_expr() {
-                    res.append(&mut _link_tuples(expr.elts.clone(), value.clone().list_expr().unwrap().elts.clone()));
-                } else {
-                    for (index, target) in expr.elts.iter().enumerate() {
-                        match target {
-                            Expr::Name(tar) => {
-                                res.push(Assign {
-                                    target: tar.clone(),
-                                    annotation: None,
-                                    value: Some(*value.clone()),
-                                    index: Some(index),
-                                });
-                            }
-                            _ => {continue;}
-                        }
-                    }
+            },
+            Stmt::AnnAssign(ann_assign) => {
+                if let Some(value) = ann_assign.value.as_ref().filter(|_| is_cls_attribute(&ann_assign.target)) {
+                    res.push((ann_assign.target.as_attribute_expr().unwrap(), value.as_ref()));
                 }
-            }
+            },
+            Stmt::With(with_stmt) => _cls_attribute_assigns(&with_stmt.body, cls, res),
+            Stmt::If(if_stmt) => {
+                _cls_attribute_assigns(&if_stmt.body, cls, res);
+                for clause in if_stmt.elif_else_clauses.iter() {
+                    _cls_attribute_assigns(&clause.body, cls, res);
+                }
+            },
+            Stmt::For(for_stmt) => _cls_attribute_assigns(&for_stmt.body, cls, res),
+            Stmt::Try(try_stmt) => {
+                _cls_attribute_assigns(&try_stmt.body, cls, res);
+                _cls_attribute_assigns(&try_stmt.orelse, cls, res);
+                _cls_attribute_assigns(&try_stmt.finalbody, cls, res);
+            },
             _ => {}
         }
     }
+}
+
+/* True if the method only returns the call of the same method of super() with its own parameters, like
+`def write(self, vals): return super().write(vals)`. Such overrides keep the signature and the return type of the parent */
+pub fn is_forwarding_override(func_def: &StmtFunctionDef) -> bool {
+    let mut body = func_def.body.iter();
+    let mut stmt = body.next();
+    //skip the docstring
+    if stmt.is_some_and(|stmt| stmt.as_expr_stmt().is_some_and(|expr| expr.value.is_string_literal_expr())) {
+        stmt = body.next();
+    }
+    if body.next().is_some() {
+        return false;
+    }
+    let Some(call) = stmt.and_then(|stmt| stmt.as_return_stmt()).and_then(|ret| ret.value.as_ref()).and_then(|value| value.as_call_expr()) else {
+        return false;
+    };
+    let Some(method) = call.func.as_attribute_expr() else {
+        return false;
+    };
+    let is_super_call = method.value.as_call_expr().is_some_and(|super_call| super_call.func.as_name_expr().is_some_and(|name| name.id.as_str() == "super"));
+    if !is_super_call || method.attr.as_str() != func_def.name.as_str() {
+        return false;
+    }
+    let parameters = &func_def.parameters;
+    //self is given by super()
+    let positional: Vec<&str> = parameters.posonlyargs.iter().chain(&parameters.args).skip(1).map(|arg| arg.parameter.name.as_str()).collect();
+    let keyword_only: Vec<&str> = parameters.kwonlyargs.iter().map(|arg| arg.parameter.name.as_str()).collect();
+    let mut forwarded: Vec<&str> = vec![];
+    for (index, arg) in call.arguments.args.iter().enumerate() {
+        let name = match arg {
+            Expr::Starred(starred) => starred.value.as_name_expr().map(|name| name.id.as_str())
+                .filter(|name| parameters.vararg.as_ref().is_some_and(|vararg| vararg.name.as_str() == *name)),
+            Expr::Name(name) => Some(name.id.as_str()).filter(|name| positional.get(index) == Some(name)),
+            _ => None,
+        };
+        let Some(name) = name else {
+            return false;
+        };
+        forwarded.push(name);
+    }
+    for keyword in call.arguments.keywords.iter() {
+        let Some(value) = keyword.value.as_name_expr().map(|name| name.id.as_str()) else {
+            return false;
+        };
+        let is_forwarded = match keyword.arg.as_ref() {
+            Some(arg) => arg.as_str() == value && (positional.contains(&value) || keyword_only.contains(&value)),
+            None => parameters.kwarg.as_ref().is_some_and(|kwarg| kwarg.name.as_str() == value),
+        };
+        if !is_forwarded || forwarded.contains(&value) {
+            return false;
+        }
+        forwarded.push(value);
+    }
+    let parameter_count = positional.len() + keyword_only.len() + parameters.vararg.is_some() as usize + parameters.kwarg.is_some() as usize;
+    forwarded.len() == parameter_count
+}
+
+/* The object and the literal name of a call to getattr or hasattr, like getattr(rec, 'partner_id'). None if the name is not a
+literal identifier, as it can't be resolved */
+pub fn literal_attribute_call<'a>(call: &'a ExprCall, function: &str) -> Option<(&'a Expr, &'a ExprStringLiteral)> {
+    if !call.func.as_name_expr().is_some_and(|name| name.id.as_str() == function) || !call.arguments.keywords.is_empty() {
+        return None;
+    }
+    let max_args = if function == "getattr" { 3 } else { 2 };
+    if call.arguments.args.len() < 2 || call.arguments.args.len() > max_args {
+        return None;
+    }
+    let Expr::StringLiteral(name) = &call.arguments.args[1] else {
+        return None;
+    };
+    let text = name.value.to_str();
+    let is_identifier = text.starts_with(|c: char| c.is_alphabetic() || c == '_') && text.chars().all(|c| c.is_alphanumeric() || c == '_');
+    is_identifier.then_some((&call.arguments.args[0], name))
+}
+
+/* getattr(rec, 'partner_id'), written as the attribute access rec.partner_id, so it can be evaluated like one */
+pub fn getattr_as_attribute(call: &ExprCall) -> Option<ExprAttribute> {
+    let (object, name) = literal_attribute_call(call, "getattr")?;
+    Some(ExprAttribute {
+        range: call.range,
+        value: Box::new(object.clone()),
+        attr: Identifier::new(name.value.to_str(), name.range),
+        ctx: ExprContext::Load,
+    })
+}
+
+/* Attributes guaranteed by the test of an if, like `hasattr(rec, 'partner_id') and hasattr(rec, 'user_id')`, as the
+object and the attribute name */
+pub fn hasattr_guards(test: &Expr) -> Vec<(&Expr, &str)> {
+    match test {
+        Expr::Call(call) => literal_attribute_call(call, "hasattr").map(|(object, name)| vec![(object, name.value.to_str())]).unwrap_or_default(),
+        Expr::BoolOp(bool_op) if bool_op.op == BoolOp::And => bool_op.values.iter().flat_map(hasattr_guards).collect(),
+        _ => vec![]
+    }
+}
 
+/* The bodies of the ifs guarded by hasattr in the statements, nested ones included, with the object and the name of each
+guard. See hasattr_guards */
+pub fn hasattr_guarded_bodies(stmts: &[Stmt]) -> Vec<(TextRange, &Expr, &str)> {
+    let mut res = vec![];
+    for stmt in stmts.iter() {
+        match stmt {
+            Stmt::If(if_stmt) => {
+                if let (Some(first), Some(last)) = (if_stmt.body.first(), if_stmt.body.last()) {
+                    let body_range = TextRange::new(first.start(), last.end());
+                    res.extend(hasattr_guards(&if_stmt.test).into_iter().map(|(object, name)| (body_range, object, name)));
+                }
+                res.extend(hasattr_guarded_bodies(&if_stmt.body));
+                for clause in if_stmt.elif_else_clauses.iter() {
+                    res.extend(hasattr_guarded_bodies(&clause.body));
+                }
+            },
+            Stmt::ClassDef(class_def) => res.extend(hasattr_guarded_bodies(&class_def.body)),
+            Stmt::FunctionDef(func_def) => res.extend(hasattr_guarded_bodies(&func_def.body)),
+            Stmt::For(for_stmt) => {
+                res.extend(hasattr_guarded_bodies(&for_stmt.body));
+                res.extend(hasattr_guarded_bodies(&for_stmt.orelse));
+            },
+            Stmt::While(while_stmt) => {
+                res.extend(hasattr_guarded_bodies(&while_stmt.body));
+                res.extend(hasattr_guarded_bodies(&while_stmt.orelse));
+            },
+            Stmt::With(with_stmt) => res.extend(hasattr_guarded_bodies(&with_stmt.body)),
+            Stmt::Try(try_stmt) => {
+                res.extend(hasattr_guarded_bodies(&try_stmt.body));
+                for handler in try_stmt.handlers.iter() {
+                    let ExceptHandler::ExceptHandler(handler) = handler;
+                    res.extend(hasattr_guarded_bodies(&handler.body));
+                }
+                res.extend(hasattr_guarded_bodies(&try_stmt.orelse));
+                res.extend(hasattr_guarded_bodies(&try_stmt.finalbody));
+            },
+            _ => {}
+        }
+    }
     res
-}
\ No newline at end of file
+}
+
+/* True if the recordset is switched to the superuser on its way, like self.env['res.partner'].sudo() or
+self.sudo().partner_id */
+pub fn is_sudo_chain(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(call) => match call.func.as_ref() {
+            Expr::Attribute(attr) => attr.attr.as_str() == "sudo" || is_sudo_chain(&attr.value),
+            func => is_sudo_chain(func),
+        },
+        Expr::Attribute(attr) => is_sudo_chain(&attr.value),
+        Expr::Subscript(subscript) => is_sudo_chain(&subscript.value),
+        _ => false
+    }
+}
+
+/* The innermost function containing the offset, in the classes and functions of the body */
+pub fn find_enclosing_function(body: &[Stmt], offset: u32) -> Option<&StmtFunctionDef> {
+    for stmt in body.iter() {
+        if stmt.range().start().to_u32() > offset || stmt.range().end().to_u32() < offset {
+            continue;
+        }
+        match stmt {
+            Stmt::ClassDef(class_def) => return find_enclosing_function(&class_def.body, offset),
+            Stmt::FunctionDef(func_def) => return find_enclosing_function(&func_def.body, offset).or(Some(func_def)),
+            _ => {}
+        }
+    }
+    None
+}
+
+/* Return the text of a string before the offset, for plain strings and strings formatted at runtime, like f"partner_{suffix}",
+"partner_%s" % suffix or "partner_{}".format(suffix). The text is taken from the source, as written. The text after the first
+formatted part can't be known, so None is returned if the offset is after it, or if the offset is not in the string */
+pub fn literal_prefix(expr: &Expr, source: &str, offset: usize) -> Option<String> {
+    match expr {
+        Expr::StringLiteral(string) => string_parts_prefix(string.value.iter().map(|part| part.range), source, offset),
+        Expr::FString(fstring) => {
+            let formatted = fstring.value.iter().any(|part| match part {
+                FStringPart::FString(fstring) => fstring.elements.iter()
+                    .any(|element| matches!(element, FStringElement::Expression(_)) && element.range().start().to_usize() < offset),
+                FStringPart::Literal(_) => false,
+            });
+            if formatted {
+                return None;
+            }
+            string_parts_prefix(fstring.value.iter().map(|part| part.range()), source, offset)
+        },
+        Expr::BinOp(bin_op) if bin_op.op == Operator::Mod => {
+            let Expr::StringLiteral(format) = bin_op.left.as_ref() else {
+                return None;
+            };
+            string_parts_prefix(format.value.iter().map(|part| part.range), source, offset).filter(|prefix| !prefix.contains('%'))
+        },
+        Expr::Call(call) => {
+            let Expr::Attribute(attr) = call.func.as_ref() else {
+                return None;
+            };
+            let Expr::StringLiteral(format) = attr.value.as_ref() else {
+                return None;
+            };
+            if attr.attr.as_str() != "format" {
+                return None;
+            }
+            string_parts_prefix(format.value.iter().map(|part| part.range), source, offset).filter(|prefix| !prefix.contains('{'))
+        },
+        _ => None
+    }
+}
+
+/* Source of the parts of a string ("partner_" "id") before the offset, without their prefixes and quotes. None if the offset
+is not in the content of one of the parts */
+fn string_parts_prefix(parts: impl Iterator<Item = TextRange>, source: &str, offset: usize) -> Option<String> {
+    let mut prefix = String::new();
+    for range in parts {
+        let text = source.get(range.start().to_usize()..range.end().to_usize())?;
+        let opener = text.find(['\'', '"'])?;
+        let quote = &text[opener..opener + 1];
+        let quote_len = if text[opener..].starts_with(&quote.repeat(3)) { 3 } else { 1 };
+        let content_start = range.start().to_usize() + opener + quote_len;
+        //the string being typed is not closed yet
+        let closed = text.len() >= opener + 2 * quote_len && text.ends_with(&quote.repeat(quote_len));
+        let content_end = if closed { range.end().to_usize() - quote_len } else { range.end().to_usize() };
+        if offset < content_start {
+            return None;
+        }
+        if offset <= content_end {
+            prefix += source.get(content_start..offset)?;
+            return Some(prefix);
+        }
+        prefix += source.get(content_start..content_end)?;
+    }
+    None
+}
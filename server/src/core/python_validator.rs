@@ -1,24 +1,43 @@
-use ruff_python_ast::{Alias, Expr, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtTry};
-use ruff_text_size::{Ranged, TextRange};
+use ruff_python_ast::{Alias, Expr, ExprCall, ExprStringLiteral, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtFunctionDef, StmtTry};
+use ruff_text_size::{Ranged, TextRange, TextSize};
 use tracing::{trace, warn};
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Position, Range};
 use crate::constants::*;
 use crate::core::symbols::symbol::Symbol;
 use crate::core::odoo::SyncOdoo;
 use crate::core::import_resolver::resolve_import_stmt;
+use crate::core::python_utils;
 use crate::core::symbols::module_symbol::ModuleSymbol;
 use crate::features::ast_utils::AstUtils;
+use crate::features::translation::TranslationCallVisitor;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
 use crate::S;
 
-use super::evaluation::{Evaluation, EvaluationValue};
-use super::file_mgr::FileInfo;
+use super::access_rights;
+use super::cardinality::CardinalityAnalyzer;
+use super::create_multi;
+use super::domain_validator::DomainValidator;
+use super::decorators::{find_decorator_info, DecoratorInfo, DecoratorRule};
+use super::deprecations::{find_model_method_deprecation, find_symbol_deprecation, find_symbol_path_deprecation, Deprecation};
+use super::evaluation::{Evaluation, EvaluationValue, GiveUp, GiveUpReason};
+use super::field_rules::{FieldKwargs, FIELD_KWARG_RULES};
+use super::file_mgr::{FileInfo, FileMgr};
+use super::settings::{SettingsStorage, SETTINGS_MODEL};
+use super::populate::{self, POPULATE_FACTORIES, POPULATE_SIZES, POPULATE_SIZE_NAMES};
 use super::python_arch_builder::PythonArchBuilder;
 use super::python_arch_eval::PythonArchEval;
+use super::read_group::ReadGroupCall;
+use super::reference_fields;
+use super::spell_check::SpellChecker;
+use super::sql;
+
+/* Fields holding a single value, that can't be read on several records at once */
+const SCALAR_FIELD_TYPES: [&str; 10] = ["Integer", "Float", "Monetary", "Char", "Text", "Html", "Date", "Datetime", "Boolean", "Selection"];
 
 #[derive(Debug)]
 pub struct PythonValidator {
@@ -26,7 +45,9 @@ pub struct PythonValidator {
     sym_stack: Vec<Rc<RefCell<Symbol>>>,
     pub diagnostics: Vec<Diagnostic>, //collect diagnostic from arch and arch_eval too from inner functions, but put everything at Validation level
     safe_imports: Vec<bool>,
-    current_module: Option<Rc<RefCell<Symbol>>>
+    current_module: Option<Rc<RefCell<Symbol>>>,
+    strict: bool, //report the expressions that can't be evaluated (strict mode, on the files of the workspace)
+    hasattr_guards: Vec<(String, String)>, //objects (as written) and attributes checked by the hasattr of the enclosing ifs, that can't be reported as missing
 }
 
 /* PythonValidator operate on a single Symbol. Unlike other steps, it can be done on symbol containing code (file and functions only. Not class, variable, namespace).
@@ -40,6 +61,8 @@ impl PythonValidator {
             diagnostics: vec![],
             safe_imports: vec![false],
             current_module: None,
+            strict: false,
+            hasattr_guards: vec![],
         }
     }
 
@@ -63,6 +86,10 @@ impl PythonValidator {
         }
         let sym_type = symbol.typ().clone();
         drop(symbol);
+        //in degraded mode, every name coming from a python library would be a give-up
+        self.strict = session.sync_odoo.config.strict_mode && session.sync_odoo.degraded.is_none() && self.sym_stack[0].borrow().get_file()
+            .and_then(|file| file.upgrade())
+            .is_some_and(|file| file.borrow().in_workspace());
         match sym_type {
             SymType::FILE | SymType::PACKAGE => {
                 trace!("Validating {}", self.sym_stack[0].borrow().paths().first().unwrap_or(&S!("No path found")));
@@ -70,12 +97,23 @@ impl PythonValidator {
                 let file_info_rc = self.get_file_info(session.sync_odoo).clone();
                 file_info_rc.borrow_mut().replace_diagnostics(BuildSteps::VALIDATION, vec![]);
                 let file_info = file_info_rc.borrow();
-                if file_info.ast.is_some() && file_info.valid {
+                let version = file_info.version;
+                if file_info.ast.is_some() && file_info.valid && !file_info.large {
                     self.validate_body(session, file_info.ast.as_ref().unwrap());
+                    self._check_translations(file_info.ast.as_ref().unwrap());
+                    self._check_order_calls(session, file_info.ast.as_ref().unwrap());
+                    self._check_deprecated_calls(session, file_info.ast.as_ref().unwrap());
+                    self._check_orm_calls(session, file_info.ast.as_ref().unwrap());
+                    self._check_create_multi(session, file_info.ast.as_ref().unwrap());
+                    self._check_getattr_calls(session, file_info.ast.as_ref().unwrap());
+                    self._check_read_group_calls(session, file_info.ast.as_ref().unwrap());
+                    self._check_recordset_cardinality(session, file_info.ast.as_ref().unwrap());
+                    self._check_sql_queries(file_info.ast.as_ref().unwrap());
+                    self._check_access_rights(session, file_info.ast.as_ref().unwrap());
                 }
                 drop(file_info);
                 let mut file_info = file_info_rc.borrow_mut();
-                file_info.replace_diagnostics(BuildSteps::VALIDATION, self.diagnostics.clone());
+                file_info.replace_diagnostics_for_version(BuildSteps::VALIDATION, self.diagnostics.clone(), version);
             },
             SymType::FUNCTION => {
                 trace!("Validating function {}", self.sym_stack[0].borrow().name());
@@ -135,6 +173,10 @@ impl PythonValidator {
         for stmt in vec_ast.iter() {
             match stmt {
                 Stmt::FunctionDef(f) => {
+                    self._check_deprecated_function_def(session, f);
+                    if f.name.as_str() == POPULATE_FACTORIES {
+                        self._check_populate_factories(session, f);
+                    }
                     let sym = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&f.name.to_string(), &f.range);
                     if let Some(sym) = sym {
                         let val_status = sym.borrow().build_status(BuildSteps::VALIDATION).clone();
@@ -168,11 +210,34 @@ impl PythonValidator {
                     self.visit_ann_assign(session, a);
                 },
                 Stmt::Expr(e) => {
-                    let (eval, diags) = Evaluation::eval_from_ast(session, &e.value, self.sym_stack.last().unwrap().clone(), &e.range.start());
-                    self.diagnostics.extend(diags);
+                    let result = Evaluation::analyze_expr(session, &e.value, self.sym_stack.last().unwrap().clone(), &e.range.start());
+                    self.diagnostics.extend(result.diagnostics);
+                    self._report_give_ups(session, result.give_ups);
                 },
                 Stmt::If(i) => {
-                    self.validate_body(session, &i.body);
+                    //only validate the branches that can be executed with the current version of Odoo
+                    let version = (session.sync_odoo.version_major, session.sync_odoo.version_minor, session.sync_odoo.version_micro);
+                    let dead_branches = python_utils::dead_if_branches(i, version);
+                    let bodies = std::iter::once(&i.body).chain(i.elif_else_clauses.iter().map(|clause| &clause.body));
+                    //in the body of `if hasattr(rec, 'partner_id'):`, rec.partner_id exists
+                    let guards: Vec<(String, String)> = {
+                        let file_info = self.get_file_info(session.sync_odoo);
+                        let file_info = file_info.borrow();
+                        python_utils::hasattr_guards(&i.test).into_iter()
+                            .map(|(object, name)| (file_info.get_text(&object.range()).unwrap_or_default(), name.to_string()))
+                            .collect()
+                    };
+                    for (index, (body, dead)) in bodies.zip(dead_branches.iter()).enumerate() {
+                        if *dead {
+                            continue;
+                        }
+                        let guard_count = self.hasattr_guards.len();
+                        if index == 0 {
+                            self.hasattr_guards.extend(guards.iter().cloned());
+                        }
+                        self.validate_body(session, body);
+                        self.hasattr_guards.truncate(guard_count);
+                    }
                 },
                 Stmt::Break(_) => {},
                 Stmt::Continue(_) => {},
@@ -183,7 +248,12 @@ impl PythonValidator {
                     //TODO check condition ? if some checks has to be done on single Expr
                     self.validate_body(session, &f.body);
                 },
-                Stmt::Return(r) => {},
+                Stmt::Return(r) => {
+                    if let Some(value) = r.value.as_ref() {
+                        let evaluated = self.sym_stack[0].borrow().typ() == SymType::FUNCTION;
+                        self._check_strict_evaluation(session, value, evaluated);
+                    }
+                },
                 _ => {
                     trace!("Stmt not handled");
                 }
@@ -195,6 +265,7 @@ impl PythonValidator {
         let sym = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&c.name.to_string(), &c.range);
         if let Some(sym) = sym {
             self._check_model(session, &sym);
+            self._check_duplicate_members(session, &sym, c);
             self.sym_stack.push(sym);
             self.validate_body(session, &c.body);
             self.sym_stack.pop();
@@ -204,16 +275,7 @@ impl PythonValidator {
     }
 
     fn visit_try(&mut self, session: &mut SessionInfo, node: &StmtTry) {
-        let mut safe_import = false;
-        for handler in node.handlers.iter() {
-            let handler = handler.as_except_handler().unwrap();
-            if let Some(type_) = &handler.type_ {
-                if type_.is_name_expr() && type_.as_name_expr().unwrap().id.to_string() == "ImportError" {
-                    safe_import = true;
-                }
-            }
-        }
-        self.safe_imports.push(safe_import);
+        self.safe_imports.push(python_utils::catches_import_error(&node.handlers));
         self.validate_body(session, &node.body);
         self.safe_imports.pop();
     }
@@ -228,7 +290,17 @@ impl PythonValidator {
             name_aliases,
             level,
             &mut None);
-        for import_result in import_results.iter() {
+        for (import_result, alias) in import_results.iter().zip(name_aliases.iter()) {
+            let deprecation = if import_result.found {
+                find_symbol_deprecation(&import_result.symbol.borrow().get_tree(), session.sync_odoo.version_major)
+            } else {
+                let mut path = import_result.file_tree.0.clone();
+                path.extend(alias.name.split('.').map(|part| part.to_string()));
+                find_symbol_path_deprecation(&path, session.sync_odoo.version_major)
+            };
+            if let Some(deprecation) = deprecation {
+                self._add_deprecation_diagnostic(session, deprecation, &import_result.range);
+            }
             if import_result.found && self.current_module.is_some() {
                 let module = import_result.symbol.borrow().find_module();
                 if let Some(module) = module {
@@ -249,11 +321,1117 @@ impl PythonValidator {
     }
 
     fn visit_ann_assign(&mut self, session: &mut SessionInfo, assign: &StmtAnnAssign) {
-
+        if let Some(value) = assign.value.as_ref() {
+            if self.strict {
+                let evaluated = !python_utils::unpack_assign(&vec![*assign.target.clone()], Some(&assign.annotation), Some(value)).is_empty();
+                self._check_strict_evaluation(session, value, evaluated);
+            }
+            self._check_field_default(session, value);
+            self._check_field_kwargs(session, value);
+            self._check_field_spelling(session, value);
+            self._check_model_field(session, value);
+            if let Expr::Name(target) = assign.target.as_ref() {
+                self._check_order_attribute(session, target.id.as_str(), value);
+                self._check_rec_names_attribute(session, target.id.as_str(), value);
+                self._check_settings_field(session, target.id.as_str(), value);
+            }
+        }
     }
 
     fn visit_assign(&mut self, session: &mut SessionInfo, assign: &StmtAssign) {
+        if self.strict {
+            //the values assigned to attributes or items (self.name = ...) are not evaluated with the assignment
+            let evaluated = !python_utils::unpack_assign(&assign.targets, None, Some(&assign.value)).is_empty();
+            self._check_strict_evaluation(session, &assign.value, evaluated);
+        }
+        self._check_field_default(session, &assign.value);
+        self._check_field_kwargs(session, &assign.value);
+        self._check_field_spelling(session, &assign.value);
+        self._check_model_field(session, &assign.value);
+        for target in assign.targets.iter() {
+            if let Expr::Name(target) = target {
+                self._check_order_attribute(session, target.id.as_str(), &assign.value);
+                self._check_rec_names_attribute(session, target.id.as_str(), &assign.value);
+                self._check_settings_field(session, target.id.as_str(), &assign.value);
+                if target.id.as_str() == POPULATE_SIZES {
+                    self._check_populate_sizes(session, &assign.value);
+                }
+            }
+        }
+    }
+
+    /* In strict mode, report why the evaluator gave up on the value. The values already evaluated by PythonArchEval, that are
+    the values assigned to names and the returned values, are not evaluated again: their give-ups are recorded by the evaluation */
+    fn _check_strict_evaluation(&mut self, session: &mut SessionInfo, value: &Expr, evaluated: bool) {
+        if !self.strict {
+            return;
+        }
+        let give_ups = if evaluated {
+            self.get_file_info(session.sync_odoo).borrow().give_ups_in(&value.range())
+        } else {
+            Evaluation::analyze_expr(session, value, self.sym_stack.last().unwrap().clone(), &value.range().start()).give_ups
+        };
+        self._report_give_ups(session, give_ups);
+    }
+
+    fn _report_give_ups(&mut self, session: &mut SessionInfo, give_ups: Vec<GiveUp>) {
+        if !self.strict {
+            return;
+        }
+        let file_info = self.get_file_info(session.sync_odoo);
+        for give_up in give_ups.iter() {
+            if let GiveUpReason::UnknownAttribute(attr, object_range) = &give_up.reason {
+                let object = file_info.borrow().get_text(object_range).unwrap_or_default();
+                if self.hasattr_guards.iter().any(|(guard_object, guard_attr)| guard_attr == attr && *guard_object == object) {
+                    continue;
+                }
+            }
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(give_up.range.start().to_u32(), 0), Position::new(give_up.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20008"))),
+                Some(EXTENSION_NAME.to_string()),
+                give_up.reason.message(),
+                None,
+                None));
+        }
+    }
+
+    /* If value is a field declaration with a default argument, check that the default callable exists and that
+    its return type (or the literal value) is compatible with the field type */
+    fn _check_field_default(&mut self, session: &mut SessionInfo, value: &Expr) {
+        let class = self.sym_stack.last().unwrap().clone();
+        if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let Expr::Call(call) = value else {
+            return;
+        };
+        let Some(default) = call.arguments.find_keyword("default") else {
+            return;
+        };
+        let Some(field_type) = self._get_field_type(session, &class, &call.func) else {
+            return;
+        };
+        let default_kinds = match &default.value {
+            Expr::Lambda(lambda) => {
+                PythonValidator::_literal_kind(&lambda.body).into_iter().collect()
+            },
+            Expr::Name(name) => {
+                let (evals, _) = Evaluation::eval_from_ast(session, &default.value, class.clone(), &call.range.start());
+                let callable = evals.first().and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade());
+                let Some(callable) = callable else {
+                    self.diagnostics.push(Diagnostic::new(
+                        Range::new(Position::new(name.range.start().to_u32(), 0), Position::new(name.range.end().to_u32(), 0)),
+                        Some(DiagnosticSeverity::ERROR),
+                        Some(NumberOrString::String(S!("OLS30401"))),
+                        Some(EXTENSION_NAME.to_string()),
+                        format!("Default callable {} not found", name.id),
+                        None,
+                        None,
+                    ));
+                    return;
+                };
+                self._get_return_kinds(session, &callable)
+            },
+            other => {
+                PythonValidator::_literal_kind(other).into_iter().collect()
+            }
+        };
+        for kind in default_kinds.iter() {
+            if !PythonValidator::_is_valid_default_kind(&field_type, kind) {
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(default.value.range().start().to_u32(), 0), Position::new(default.value.range().end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::WARNING),
+                    Some(NumberOrString::String(S!("OLS20401"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("Default value of type {} is not compatible with a {} field", kind, field_type),
+                    None,
+                    None,
+                ));
+                return;
+            }
+        }
+    }
 
+    /* If value is a field declaration, check the combination of its keyword arguments against FIELD_KWARG_RULES */
+    fn _check_field_kwargs(&mut self, session: &mut SessionInfo, value: &Expr) {
+        let class = self.sym_stack.last().unwrap().clone();
+        if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let Expr::Call(call) = value else {
+            return;
+        };
+        if call.arguments.keywords.is_empty() || self._get_field_type(session, &class, &call.func).is_none() {
+            return;
+        }
+        let kwargs = FieldKwargs::from_keywords(&call.arguments.keywords);
+        for rule in FIELD_KWARG_RULES.iter() {
+            if !(rule.matches)(&kwargs) {
+                continue;
+            }
+            let range = kwargs.range(rule.keyword).unwrap_or(call.range);
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!(rule.code))),
+                Some(EXTENSION_NAME.to_string()),
+                S!(rule.message),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* If the spellCheck setting is enabled, report the typos of the string, the help and the selection labels of a field declaration */
+    fn _check_field_spelling(&mut self, session: &mut SessionInfo, value: &Expr) {
+        if !session.sync_odoo.config.spell_check {
+            return;
+        }
+        let class = self.sym_stack.last().unwrap().clone();
+        if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let Expr::Call(call) = value else {
+            return;
+        };
+        let Some(field_type) = self._get_field_type(session, &class, &call.func) else {
+            return;
+        };
+        //position of the string and of the selection in the positional arguments of the field
+        let (string_index, selection_index) = match field_type.as_str() {
+            "Many2one" => (1, None),
+            "One2many" => (2, None),
+            "Many2many" => (4, None),
+            "Selection" | "Reference" => (1, Some(0)),
+            _ => (0, None),
+        };
+        let mut texts: Vec<&ExprStringLiteral> = vec![];
+        if let Some(Expr::StringLiteral(string)) = call.arguments.args.get(string_index) {
+            texts.push(string);
+        }
+        for keyword in ["string", "help"] {
+            if let Some(Expr::StringLiteral(string)) = call.arguments.find_keyword(keyword).map(|kw| &kw.value) {
+                texts.push(string);
+            }
+        }
+        let mut selections = vec![];
+        if let Some(selection) = selection_index.and_then(|index| call.arguments.args.get(index)) {
+            selections.push(selection);
+        }
+        for keyword in ["selection", "selection_add"] {
+            if let Some(kw) = call.arguments.find_keyword(keyword) {
+                selections.push(&kw.value);
+            }
+        }
+        for selection in selections {
+            let items = match selection {
+                Expr::List(list) => &list.elts,
+                Expr::Tuple(tuple) => &tuple.elts,
+                _ => continue,
+            };
+            for item in items.iter() {
+                if let Some(Expr::StringLiteral(label)) = item.as_tuple_expr().and_then(|t| t.elts.get(1)) {
+                    texts.push(label);
+                }
+            }
+        }
+        let file_info = self.get_file_info(session.sync_odoo);
+        let file_info = file_info.borrow();
+        let spell_checker = SpellChecker::new(&session.sync_odoo.config.dictionary);
+        for part in texts.iter().flat_map(|text| text.value.iter()) {
+            let source = file_info.get_text(&part.range).unwrap_or_default();
+            for (offset, word, suggestion) in spell_checker.check(&part.value) {
+                //the word is searched in the source, as escape sequences shift the offsets of the value
+                let occurrence = part.value[..offset].matches(word.as_str()).count();
+                let range = match source.match_indices(word.as_str()).nth(occurrence) {
+                    Some((start, _)) => {
+                        let start = part.range.start() + TextSize::new(start as u32);
+                        TextRange::at(start, TextSize::new(word.len() as u32))
+                    },
+                    None => part.range,
+                };
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::INFORMATION),
+                    Some(NumberOrString::String(S!("OLS10501"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("Possible typo: {}. Did you mean {}?", word, suggestion),
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+
+    /* The model_field of a Many2oneReference must be a Char field of the model, holding the name of the model of the
+    referenced record */
+    fn _check_model_field(&mut self, session: &mut SessionInfo, value: &Expr) {
+        let class = self.sym_stack.last().unwrap().clone();
+        if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let Expr::Call(call) = value else {
+            return;
+        };
+        let Some((model_field, range)) = reference_fields::model_field(call) else {
+            return;
+        };
+        if self._get_field_type(session, &class, &call.func).as_deref() != Some(reference_fields::MANY2ONE_REFERENCE_FIELD) {
+            return;
+        }
+        let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+        let (symbols, _) = class.borrow().get_member_symbol(session, &model_field, self.current_module.clone(), false, false);
+        let field_info = symbols.first().and_then(|symbol| PythonValidator::get_field_info(session, symbol));
+        let (severity, code, message) = match field_info {
+            None => (DiagnosticSeverity::ERROR, "OLS30408", format!("{} is not a field of model {}", model_field, model_name)),
+            Some((field_type, _)) if field_type != reference_fields::MODEL_FIELD_TYPE => (DiagnosticSeverity::WARNING, "OLS20413",
+                format!("The model_field {} is a {} field, it should be a Char field holding the name of the model", model_field, field_type)),
+            Some(_) => return,
+        };
+        self.diagnostics.push(Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(severity),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None,
+        ));
+    }
+
+    /* Return the name of the field class if func is evaluated to a class of odoo.fields */
+    fn _get_field_type(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, func: &Expr) -> Option<String> {
+        let (evals, _) = Evaluation::eval_from_ast(session, func, class.clone(), &func.range().start());
+        let field_class = evals.first()?.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()?;
+        let field_class = field_class.borrow();
+        if field_class.typ() != SymType::CLASS || field_class.get_tree().0 != vec![S!("odoo"), S!("fields")] {
+            return None;
+        }
+        Some(field_class.name().clone())
+    }
+
+    /* Return the builtin types the given callable can return, as far as we know them */
+    fn _get_return_kinds(&mut self, session: &mut SessionInfo, callable: &Rc<RefCell<Symbol>>) -> Vec<String> {
+        let mut res = vec![];
+        if callable.borrow().typ() != SymType::FUNCTION {
+            return res;
+        }
+        if callable.borrow().build_status(BuildSteps::VALIDATION) == BuildStatus::PENDING {
+            //evaluate the function now to get its return type
+            let mut v = PythonValidator::new(callable.clone());
+            v.validate(session);
+        }
+        let evaluations = callable.borrow().evaluations().cloned().unwrap_or_default();
+        for eval in evaluations.iter() {
+            if let Some(EvaluationValue::CONSTANT(expr)) = eval.value.as_ref() {
+                res.extend(PythonValidator::_literal_kind(expr));
+                continue;
+            }
+            let Some(sym) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade() else {
+                continue;
+            };
+            let sym = sym.borrow();
+            if sym.typ() == SymType::CLASS && sym.get_tree().0 == vec![S!("builtins")] {
+                res.push(sym.name().clone());
+            }
+        }
+        res
+    }
+
+    fn _literal_kind(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::StringLiteral(_) => Some(S!("str")),
+            Expr::NumberLiteral(n) => match n.value {
+                ruff_python_ast::Number::Int(_) => Some(S!("int")),
+                ruff_python_ast::Number::Float(_) => Some(S!("float")),
+                ruff_python_ast::Number::Complex { .. } => Some(S!("complex")),
+            },
+            Expr::List(_) => Some(S!("list")),
+            Expr::Dict(_) => Some(S!("dict")),
+            _ => None
+        }
+    }
+
+    /* Only catch obvious mismatches. Booleans are always accepted, as False is the empty value of any field */
+    fn _is_valid_default_kind(field_type: &String, kind: &String) -> bool {
+        let invalid_for: &[&str] = match kind.as_str() {
+            "str" => &["Boolean", "Integer", "Float", "Monetary", "Many2one", "One2many", "Many2many"],
+            "int" => &["Char", "Text", "Html", "One2many", "Many2many"],
+            "float" | "complex" => &["Boolean", "Integer", "Char", "Text", "Html", "Many2one", "One2many", "Many2many"],
+            "list" => &["Boolean", "Integer", "Float", "Monetary", "Char", "Text", "Html", "Many2one"],
+            "dict" => &["Boolean", "Integer", "Float", "Monetary", "Char", "Text", "Html", "Many2one", "One2many", "Many2many"],
+            _ => &[]
+        };
+        !invalid_for.contains(&field_type.as_str())
+    }
+
+    /* The first argument of a translation function must be a string literal, or it can't be extracted to the .pot file */
+    fn _check_translations(&mut self, ast: &Vec<Stmt>) {
+        for call in TranslationCallVisitor::collect(ast) {
+            if call.msgid.is_some() {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(call.range.start().to_u32(), 0), Position::new(call.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20501"))),
+                Some(EXTENSION_NAME.to_string()),
+                S!("The first argument of a translation function should be a string literal"),
+                None,
+                None,
+            ));
+        }
+    }
+
+    fn _check_order_attribute(&mut self, session: &mut SessionInfo, name: &str, value: &Expr) {
+        if name != "_order" {
+            return;
+        }
+        let class = self.sym_stack.last().unwrap().clone();
+        if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        if let Expr::StringLiteral(order) = value {
+            self._check_order(session, &class, order);
+        }
+    }
+
+    /* The field displayed as the name of the records (_rec_name) and the fields searched by name_search (_rec_names_search)
+    must be fields of the model. Paths like "partner_id.name" are accepted in _rec_names_search, only their first part is checked */
+    fn _check_rec_names_attribute(&mut self, session: &mut SessionInfo, name: &str, value: &Expr) {
+        let names: Vec<&ExprStringLiteral> = match (name, value) {
+            ("_rec_name", Expr::StringLiteral(rec_name)) => vec![rec_name],
+            ("_rec_names_search", Expr::List(list)) => list.elts.iter().filter_map(|e| e.as_string_literal_expr()).collect(),
+            ("_rec_names_search", Expr::Tuple(tuple)) => tuple.elts.iter().filter_map(|e| e.as_string_literal_expr()).collect(),
+            _ => return
+        };
+        let class = self.sym_stack.last().unwrap().clone();
+        if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+        for rec_name in names {
+            let field_name = S!(rec_name.value.to_str().split('.').next().unwrap());
+            if DomainValidator::is_field(session, &class, &field_name, self.current_module.clone()) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(rec_name.range.start().to_u32(), 0), Position::new(rec_name.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::ERROR),
+                Some(NumberOrString::String(S!("OLS30406"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("{} is not a field of model {}", field_name, model_name),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* A field of res.config.settings with a default_model saves the default value of the field of this model with the
+    same name, without the "default_" prefix. The model and the field must exist */
+    fn _check_settings_field(&mut self, session: &mut SessionInfo, name: &str, value: &Expr) {
+        let class = self.sym_stack.last().unwrap().clone();
+        if class.borrow().typ() != SymType::CLASS || !class.borrow().as_class_sym()._model.as_ref().is_some_and(|model| model.name == SETTINGS_MODEL) {
+            return;
+        }
+        let Expr::Call(call) = value else {
+            return;
+        };
+        for storage in SettingsStorage::from_keywords(&call.arguments.keywords) {
+            let SettingsStorage::DefaultValue(model, range) = storage else {
+                continue;
+            };
+            let message = if !session.sync_odoo.models.contains_key(&model) {
+                format!("Unknown model {}", model)
+            } else if SettingsStorage::find_default_field(session, &model, name, self.current_module.clone()).is_none() {
+                format!("{} is not a field of model {}", SettingsStorage::default_field_name(name), model)
+            } else {
+                continue;
+            };
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::ERROR),
+                Some(NumberOrString::String(S!("OLS30407"))),
+                Some(EXTENSION_NAME.to_string()),
+                message,
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* Check the order given to search methods and to sorted, if the records are of a known model */
+    fn _check_order_calls(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        let file_symbol = self.sym_stack[0].clone();
+        for call in AstUtils::collect_calls(ast) {
+            let Expr::Attribute(attr) = call.func.as_ref() else {
+                continue;
+            };
+            let order = match attr.attr.as_str() {
+                "search" | "search_read" | "search_fetch" => call.arguments.find_keyword("order").map(|k| &k.value),
+                "sorted" => call.arguments.find_argument("key", 0),
+                _ => continue
+            };
+            let Some(Expr::StringLiteral(order)) = order else {
+                continue;
+            };
+            let Some(class) = PythonValidator::_get_records_model(session, &file_symbol, call, &attr.value) else {
+                continue;
+            };
+            self._check_order(session, &class, order);
+        }
+    }
+
+    /* Return the model class of the records the method is called on, if known */
+    fn _get_records_model(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, call: &ExprCall, records: &Expr) -> Option<Rc<RefCell<Symbol>>> {
+        let scope = Symbol::get_scope_symbol(file_symbol.clone(), call.range.start().to_u32(), false);
+        let (evals, _) = Evaluation::eval_from_ast(session, records, scope, &call.range.start());
+        let class = evals.first().and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade())?;
+        if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return None;
+        }
+        Some(class)
+    }
+
+    /* Check the values given to create and write, and the domains given to the search methods */
+    fn _check_orm_calls(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        let file_symbol = self.sym_stack[0].clone();
+        for call in AstUtils::collect_calls(ast) {
+            let Expr::Attribute(attr) = call.func.as_ref() else {
+                continue;
+            };
+            let method = attr.attr.as_str();
+            let argument = match method {
+                "create" => call.arguments.find_argument("vals_list", 0),
+                "write" => call.arguments.find_argument("vals", 0),
+                "search" | "search_count" | "search_read" | "search_fetch" | "filtered_domain" => call.arguments.find_argument("domain", 0),
+                _ => continue
+            };
+            let Some(argument) = argument else {
+                continue;
+            };
+            if !matches!(argument, Expr::List(_) | Expr::Tuple(_) | Expr::Dict(_)) {
+                continue;
+            }
+            let Some(class) = PythonValidator::_get_records_model(session, &file_symbol, call, &attr.value) else {
+                continue;
+            };
+            match method {
+                "create" | "write" => {
+                    if method == "create" && argument.is_dict_expr() && PythonValidator::_is_create_multi(session, &class, self.current_module.clone()) {
+                        self.diagnostics.push(Diagnostic::new(
+                            Range::new(Position::new(argument.range().start().to_u32(), 0), Position::new(argument.range().end().to_u32(), 0)),
+                            Some(DiagnosticSeverity::WARNING),
+                            Some(NumberOrString::String(S!("OLS20411"))),
+                            Some(EXTENSION_NAME.to_string()),
+                            S!("create is decorated with @api.model_create_multi: give a list of values instead of a single dictionary"),
+                            None,
+                            None,
+                        ));
+                    }
+                    let vals_list: Vec<&Expr> = match argument {
+                        Expr::List(list) => list.elts.iter().collect(),
+                        other => vec![other],
+                    };
+                    for vals in vals_list {
+                        self._check_vals(session, &class, vals);
+                    }
+                },
+                _ => {
+                    let diagnostics = DomainValidator::validate(session, &class, argument, self.current_module.clone());
+                    self.diagnostics.extend(diagnostics);
+                }
+            }
+        }
+    }
+
+    /* getattr(records, 'name') without default raises an AttributeError if the model has no such member. hasattr is not reported,
+    as generic helpers use it to test members that only some models have, and neither is a getattr guarded by such a hasattr */
+    fn _check_getattr_calls(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        let file_symbol = self.sym_stack[0].clone();
+        let file_info = self.get_file_info(session.sync_odoo);
+        let guarded_bodies = python_utils::hasattr_guarded_bodies(ast);
+        for call in AstUtils::collect_calls(ast) {
+            let Some((records, name)) = python_utils::literal_attribute_call(call, "getattr") else {
+                continue;
+            };
+            if call.arguments.args.len() != 2 {
+                continue;
+            }
+            let guarded = guarded_bodies.iter().any(|(body_range, object, guard_name)| {
+                *guard_name == name.value.to_str() && body_range.contains_range(call.range())
+                    && file_info.borrow().get_text(&object.range()) == file_info.borrow().get_text(&records.range())
+            });
+            if guarded {
+                continue;
+            }
+            let Some(class) = PythonValidator::_get_records_model(session, &file_symbol, call, records) else {
+                continue;
+            };
+            let member_name = name.value.to_string();
+            let (members, _) = class.borrow().get_member_symbol(session, &member_name, self.current_module.clone(), false, false);
+            if !members.is_empty() || DomainValidator::is_field(session, &class, &member_name, self.current_module.clone()) {
+                continue;
+            }
+            let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(name.range.start().to_u32(), 0), Position::new(name.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20009"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("{} is not a member of model {}: getattr raises an AttributeError. Give a default value if the member is optional", member_name, model_name),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* True if a class of the model in the workspace overrides create with @api.model_create_multi. The create of BaseModel
+    accepts both a dictionary and a list, so only the overrides of the workspace can reject a single dictionary */
+    fn _is_create_multi(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, from_module: Option<Rc<RefCell<Symbol>>>) -> bool {
+        let Some(model_name) = class.borrow().as_class_sym()._model.as_ref().map(|model| model.name.clone()) else {
+            return false;
+        };
+        let Some(model) = session.sync_odoo.models.get(&model_name).cloned() else {
+            return false;
+        };
+        let classes: Vec<Rc<RefCell<Symbol>>> = match from_module {
+            Some(from_module) => model.borrow().get_symbols(session, from_module).collect(),
+            None => model.borrow().get_all_symbols().collect(),
+        };
+        classes.iter().any(|model_class| {
+            let model_class = model_class.borrow();
+            model_class.get_file().and_then(|file| file.upgrade()).is_some_and(|file| file.borrow().in_workspace())
+                && model_class.get_content_symbol("create", u32::MAX).iter()
+                    .any(|create| create.borrow().typ() == SymType::FUNCTION && create.borrow().as_func().is_create_multi)
+        })
+    }
+
+    /* In a create decorated with @api.model_create_multi, the keys used on the dictionaries of the list of values must be fields
+    of the model, and the list must not be used as a single dictionary */
+    fn _check_create_multi(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        let file_symbol = self.sym_stack[0].clone();
+        for class_def in ast.iter().filter_map(|stmt| stmt.as_class_def_stmt()) {
+            let Some(class) = file_symbol.borrow().get_positioned_symbol(&class_def.name.to_string(), &class_def.range) else {
+                continue;
+            };
+            let Some(model_name) = class.borrow().as_class_sym()._model.as_ref().map(|model| model.name.clone()) else {
+                continue;
+            };
+            for func_def in class_def.body.iter().filter_map(|stmt| stmt.as_function_def_stmt()) {
+                if !create_multi::is_create_multi(func_def) {
+                    continue;
+                }
+                let vals_list = create_multi::vals_list_parameter(func_def).unwrap_or_default();
+                let usage = create_multi::analyze(func_def);
+                for key in usage.keys {
+                    let field_name = key.value.to_string();
+                    if DomainValidator::is_field(session, &class, &field_name, self.current_module.clone()) {
+                        continue;
+                    }
+                    self.diagnostics.push(Diagnostic::new(
+                        Range::new(Position::new(key.range.start().to_u32(), 0), Position::new(key.range.end().to_u32(), 0)),
+                        Some(DiagnosticSeverity::ERROR),
+                        Some(NumberOrString::String(S!("OLS30404"))),
+                        Some(EXTENSION_NAME.to_string()),
+                        format!("{} is not a field of model {}", field_name, model_name),
+                        None,
+                        None,
+                    ));
+                }
+                for range in usage.dict_uses {
+                    self.diagnostics.push(Diagnostic::new(
+                        Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                        Some(DiagnosticSeverity::WARNING),
+                        Some(NumberOrString::String(S!("OLS20412"))),
+                        Some(EXTENSION_NAME.to_string()),
+                        format!("{} is a list of values with @api.model_create_multi, not a dictionary. Loop on it to read the values of each record", vals_list),
+                        None,
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+
+    /* Check the literal groupby and aggregate specs of read_group and _read_group: their fields must be fields of the model,
+    and their granularities and aggregate functions must be known by Odoo */
+    fn _check_read_group_calls(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        let file_symbol = self.sym_stack[0].clone();
+        for call in AstUtils::collect_calls(ast) {
+            let Some(read_group) = ReadGroupCall::parse(call, session.sync_odoo.version_major) else {
+                continue;
+            };
+            let Expr::Attribute(method) = call.func.as_ref() else {
+                continue;
+            };
+            let Some(class) = PythonValidator::_get_records_model(session, &file_symbol, call, &method.value) else {
+                continue;
+            };
+            let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+            let fields = read_group.groupby.iter().map(|groupby| (&groupby.field, groupby.range))
+                .chain(read_group.aggregates.iter().filter_map(|aggregate| aggregate.field.as_ref().map(|field| (field, aggregate.range))));
+            for (field, range) in fields {
+                if DomainValidator::is_field(session, &class, field, self.current_module.clone()) {
+                    continue;
+                }
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::ERROR),
+                    Some(NumberOrString::String(S!("OLS31401"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("{} is not a field of model {}", field, model_name),
+                    None,
+                    None,
+                ));
+            }
+            for (range, message) in read_group.spec_errors() {
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::ERROR),
+                    Some(NumberOrString::String(S!("OLS31402"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    message,
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+
+    /* Writes on models for which the module and its dependencies declare no access rule, without sudo(): the users that are not
+    superuser get an AccessError. Opt-in with the accessHints setting, as the records may come from a variable set with sudo() */
+    fn _check_access_rights(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        if !session.sync_odoo.config.access_hints {
+            return;
+        }
+        let file_symbol = self.sym_stack[0].clone();
+        let Some(module) = self.current_module.clone() else {
+            return;
+        };
+        if !file_symbol.borrow().get_file().and_then(|file| file.upgrade()).is_some_and(|file| file.borrow().in_workspace()) {
+            return;
+        }
+        for call in AstUtils::collect_calls(ast) {
+            let Expr::Attribute(attr) = call.func.as_ref() else {
+                continue;
+            };
+            if !matches!(attr.attr.as_str(), "create" | "write" | "unlink") || python_utils::is_sudo_chain(&attr.value) {
+                continue;
+            }
+            let Some(class) = PythonValidator::_get_records_model(session, &file_symbol, call, &attr.value) else {
+                continue;
+            };
+            let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+            //abstract models have no table, and no access rule
+            let is_abstract = session.sync_odoo.models.get(&model_name).is_some_and(|model| model.borrow().get_all_symbols()
+                .any(|class| class.borrow().as_class_sym()._model.as_ref().is_some_and(|model| model.is_abstract)));
+            if is_abstract || access_rights::has_access_rule(session, &module, &model_name) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(attr.attr.range.start().to_u32(), 0), Position::new(attr.attr.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS21301"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("No access rule of this module or its dependencies grants access to model {}: {} raises an AccessError for the users that are not superuser. Add a rule to ir.model.access.csv or use sudo()", model_name, attr.attr),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* Flag the fields of recordsets that may hold several records, used in arithmetic or comparisons in the methods of models, like
+    `self.amount * 2` in a compute. Odoo raises "Expected singleton" when there is more than one record */
+    fn _check_recordset_cardinality(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        let file_symbol = self.sym_stack[0].clone();
+        for class_def in ast.iter().filter_map(|stmt| stmt.as_class_def_stmt()) {
+            let class = file_symbol.borrow().get_positioned_symbol(&class_def.name.to_string(), &class_def.range);
+            if !class.is_some_and(|class| class.borrow().as_class_sym()._model.is_some()) {
+                continue;
+            }
+            for func_def in class_def.body.iter().filter_map(|stmt| stmt.as_function_def_stmt()) {
+                for operand in CardinalityAnalyzer::analyze(func_def) {
+                    let scope = Symbol::get_scope_symbol(file_symbol.clone(), operand.range.start().to_u32(), false);
+                    let (evals, _) = Evaluation::eval_from_ast(session, &operand.value, scope, &operand.range.start());
+                    let Some(records) = evals.first().and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()) else {
+                        continue;
+                    };
+                    if records.borrow().typ() != SymType::CLASS || records.borrow().as_class_sym()._model.is_none() {
+                        continue;
+                    }
+                    let field_name = operand.attr.to_string();
+                    let (fields, _) = records.borrow().get_member_symbol(session, &field_name, self.current_module.clone(), false, false);
+                    let Some((field_type, _)) = fields.first().and_then(|field| PythonValidator::get_field_info(session, field)) else {
+                        continue;
+                    };
+                    if !SCALAR_FIELD_TYPES.contains(&field_type.as_str()) {
+                        continue;
+                    }
+                    let records_name = operand.value.as_name_expr().map(|name| name.id.to_string()).unwrap_or_default();
+                    self.diagnostics.push(Diagnostic::new(
+                        Range::new(Position::new(operand.range.start().to_u32(), 0), Position::new(operand.range.end().to_u32(), 0)),
+                        Some(DiagnosticSeverity::WARNING),
+                        Some(NumberOrString::String(S!("OLS21701"))),
+                        Some(EXTENSION_NAME.to_string()),
+                        format!("{} may contain several records: reading {} raises \"Expected singleton\". Loop on the records or call {}.ensure_one() first", records_name, field_name, records_name),
+                        None,
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+
+    /* Queries given to cr.execute must not be built by interpolating values in their text: they have to be given as parameters
+    of the query, to be escaped by psycopg2 */
+    fn _check_sql_queries(&mut self, ast: &Vec<Stmt>) {
+        for call in AstUtils::collect_calls(ast) {
+            if !sql::is_cursor_execute(call) {
+                continue;
+            }
+            let Some(query) = call.arguments.find_argument("query", 0) else {
+                continue;
+            };
+            let Some(interpolation) = sql::unsafe_interpolation(query) else {
+                continue;
+            };
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(query.range().start().to_u32(), 0), Position::new(query.range().end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS21101"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("SQL query built with {}: the interpolated values are not escaped. Give them as parameters of execute to prevent SQL injections", interpolation),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* The keys of the values given to create or write must be fields of the model */
+    fn _check_vals(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, vals: &Expr) {
+        let Expr::Dict(dict) = vals else {
+            return;
+        };
+        let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+        for item in dict.items.iter() {
+            let Some(Expr::StringLiteral(key)) = item.key.as_ref() else {
+                continue;
+            };
+            let field_name = key.value.to_string();
+            if DomainValidator::is_field(session, class, &field_name, self.current_module.clone()) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(key.range.start().to_u32(), 0), Position::new(key.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::ERROR),
+                Some(NumberOrString::String(S!("OLS30404"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("{} is not a field of model {}", field_name, model_name),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* Validate an order specification like "date desc, id": each part must be a stored and sortable field of the model,
+    optionally followed by a direction and a nulls placement */
+    fn _check_order(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, order: &ExprStringLiteral) {
+        let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+        let range = Range::new(Position::new(order.range.start().to_u32(), 0), Position::new(order.range.end().to_u32(), 0));
+        for part in order.value.to_str().split(',') {
+            let tokens: Vec<&str> = part.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+            let valid_syntax = match tokens[1..].iter().map(|t| t.to_lowercase()).collect::<Vec<String>>().as_slice() {
+                [] => true,
+                [direction] => direction == "asc" || direction == "desc",
+                [nulls, placement] => nulls == "nulls" && (placement == "first" || placement == "last"),
+                [direction, nulls, placement] => (direction == "asc" || direction == "desc") && nulls == "nulls" && (placement == "first" || placement == "last"),
+                _ => false
+            };
+            if !valid_syntax {
+                self.diagnostics.push(Diagnostic::new(
+                    range,
+                    Some(DiagnosticSeverity::ERROR),
+                    Some(NumberOrString::String(S!("OLS30403"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("Invalid order specification: \"{}\"", part.trim()),
+                    None,
+                    None,
+                ));
+                continue;
+            }
+            //only the first part of a path is checked
+            let field_name = S!(tokens[0].split('.').next().unwrap());
+            if ["id", "create_date", "create_uid", "write_date", "write_uid"].contains(&field_name.as_str()) {
+                continue;
+            }
+            let (symbols, _) = class.borrow().get_member_symbol(session, &field_name, self.current_module.clone(), false, false);
+            let field = symbols.first().and_then(|sym| PythonValidator::get_field_info(session, sym));
+            let (message, severity, code) = match field {
+                None => (format!("{} is not a field of model {}", field_name, model_name), DiagnosticSeverity::ERROR, "OLS30402"),
+                Some((_, false)) => (format!("Field {} is not stored and can not be used to sort records", field_name), DiagnosticSeverity::WARNING, "OLS20402"),
+                Some((field_type, true)) if ["One2many", "Many2many", "Binary", "Image", "Json", "Properties", "PropertiesDefinition"].contains(&field_type.as_str()) => {
+                    (format!("Field {} of type {} can not be used to sort records", field_name, field_type), DiagnosticSeverity::WARNING, "OLS20403")
+                },
+                _ => continue
+            };
+            self.diagnostics.push(Diagnostic::new(
+                range,
+                Some(severity),
+                Some(NumberOrString::String(S!(code))),
+                Some(EXTENSION_NAME.to_string()),
+                message,
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* With the populate hook set, the factories returned by _populate_factories must generate values of fields of the model */
+    fn _check_populate_factories(&mut self, session: &mut SessionInfo, func: &StmtFunctionDef) {
+        let class = self.sym_stack.last().unwrap().clone();
+        if !session.sync_odoo.config.populate_checks || class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+        for field_name in populate::factory_field_names(func) {
+            let name = field_name.value.to_string();
+            if DomainValidator::is_field(session, &class, &name, self.current_module.clone()) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(field_name.range.start().to_u32(), 0), Position::new(field_name.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::ERROR),
+                Some(NumberOrString::String(S!("OLS30405"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("{} is not a field of model {}", name, model_name),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* With the populate hook set, the keys of _populate_sizes must be the sizes known by the populate command */
+    fn _check_populate_sizes(&mut self, session: &mut SessionInfo, value: &Expr) {
+        let class = self.sym_stack.last().unwrap().clone();
+        if !session.sync_odoo.config.populate_checks || class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let Expr::Dict(dict) = value else {
+            return;
+        };
+        for item in dict.items.iter() {
+            let Some(Expr::StringLiteral(size)) = item.key.as_ref() else {
+                continue;
+            };
+            if POPULATE_SIZE_NAMES.contains(&size.value.to_str()) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(size.range.start().to_u32(), 0), Position::new(size.range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20410"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("Unknown populate size {}. Use {}", size.value, POPULATE_SIZE_NAMES.join(", ")),
+                None,
+                None,
+            ));
+        }
+    }
+
+    /* Return the type of the field and if it is stored, or None if the symbol is not a field.
+    The field is considered as stored if its declaration can't be found. */
+    pub fn get_field_info(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<(String, bool)> {
+        if symbol.borrow().typ() != SymType::VARIABLE {
+            return None;
+        }
+        let evaluations = symbol.borrow().evaluations().cloned().unwrap_or_default();
+        let field_class = evaluations.first()?.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()?;
+        if field_class.borrow().typ() != SymType::CLASS || field_class.borrow().get_tree().0 != vec![S!("odoo"), S!("fields")] {
+            return None;
+        }
+        let field_type = field_class.borrow().name().clone();
+        let stored = match PythonValidator::_find_field_call_keywords(session, symbol) {
+            Some(keywords) => {
+                let store = keywords.iter().find(|(name, _)| name == "store").and_then(|(_, value)| value.clone());
+                let computed = keywords.iter().any(|(name, _)| name == "compute" || name == "related");
+                store.unwrap_or(!computed)
+            },
+            None => true
+        };
+        Some((field_type, stored))
+    }
+
+    /* Return the keywords of the field declaration, with their value if it is a boolean literal */
+    fn _find_field_call_keywords(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<Vec<(String, Option<bool>)>> {
+        let keywords = AstUtils::get_field_call_keywords(session, symbol)?;
+        Some(keywords.iter().filter_map(|keyword| {
+            let name = keyword.arg.as_ref()?.to_string();
+            let value = match &keyword.value {
+                Expr::BooleanLiteral(b) => Some(b.value),
+                _ => None
+            };
+            Some((name, value))
+        }).collect())
+    }
+
+    /* Flag the overrides of deprecated methods in models, the deprecated decorators and the invalid uses of Odoo decorators */
+    fn _check_deprecated_function_def(&mut self, session: &mut SessionInfo, func: &StmtFunctionDef) {
+        let parent = self.sym_stack.last().unwrap().clone();
+        let version_major = session.sync_odoo.version_major;
+        let in_model = parent.borrow().typ() == SymType::CLASS && parent.borrow().as_class_sym()._model.is_some();
+        if in_model {
+            if let Some(deprecation) = find_model_method_deprecation(func.name.as_str(), version_major) {
+                self._add_deprecation_diagnostic(session, deprecation, &func.name.range);
+            }
+        }
+        for decorator in func.decorator_list.iter() {
+            //@api.depends('field') is a call of the decorator factory, evaluate the factory itself
+            let call = match &decorator.expression {
+                Expr::Call(call) => Some(call),
+                _ => None,
+            };
+            let target = call.map(|call| call.func.as_ref()).unwrap_or(&decorator.expression);
+            let (evals, _) = Evaluation::eval_from_ast(session, target, parent.clone(), &decorator.range.start());
+            let Some(symbol) = evals.first().and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()) else {
+                //@api.multi can't be resolved in the versions it is removed from
+                let path = PythonValidator::_unresolved_symbol_path(session, target, &parent, &decorator.range.start());
+                if let Some(deprecation) = path.and_then(|path| find_symbol_path_deprecation(&path, version_major)) {
+                    self._add_deprecation_diagnostic(session, deprecation, &decorator.range);
+                }
+                continue;
+            };
+            let tree = symbol.borrow().get_tree();
+            if let Some(deprecation) = find_symbol_deprecation(&tree, version_major) {
+                self._add_deprecation_diagnostic(session, deprecation, &decorator.range);
+            }
+            if let Some(info) = find_decorator_info(&tree, version_major) {
+                self._check_decorator_rules(info, call, func, in_model, &decorator.range);
+                if let Some(call) = call.filter(|_| in_model) {
+                    self._check_decorator_fields(session, info, call, &parent);
+                }
+            }
+        }
+    }
+
+    /* Return the full path of an attribute that can't be resolved, from the symbol its first parts are evaluated to */
+    fn _unresolved_symbol_path(session: &mut SessionInfo, expr: &Expr, scope: &Rc<RefCell<Symbol>>, position: &TextSize) -> Option<Vec<String>> {
+        let Expr::Attribute(attr) = expr else {
+            return None;
+        };
+        let (evals, _) = Evaluation::eval_from_ast(session, &attr.value, scope.clone(), position);
+        let mut path = match evals.first().and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()) {
+            Some(symbol) => flatten_tree(&symbol.borrow().get_tree()),
+            None => PythonValidator::_unresolved_symbol_path(session, &attr.value, scope, position)?,
+        };
+        path.push(attr.attr.to_string());
+        Some(path)
+    }
+
+    /* The fields given to @api.depends, @api.constrains or @api.onchange must be fields of the model. Only the first part of the
+    paths of @api.depends is checked, like for _rec_names_search. The other decorators ignore the paths */
+    fn _check_decorator_fields(&mut self, session: &mut SessionInfo, info: &DecoratorInfo, call: &ExprCall, class: &Rc<RefCell<Symbol>>) {
+        let Some(paths) = info.rules.iter().find_map(|rule| match rule {
+            DecoratorRule::FieldNames { paths } => Some(*paths),
+            _ => None,
+        }) else {
+            return;
+        };
+        let model_name = class.borrow().as_class_sym()._model.as_ref().unwrap().name.clone();
+        for arg in call.arguments.args.iter() {
+            let Expr::StringLiteral(field) = arg else {
+                continue;
+            };
+            let value = field.value.to_str();
+            let (severity, code, message) = if !paths && value.contains('.') {
+                (DiagnosticSeverity::WARNING, "OLS20902", format!("@{} only supports fields of the model, the path {} is ignored", info.name(), value))
+            } else {
+                let field_name = S!(value.split('.').next().unwrap());
+                if DomainValidator::is_field(session, class, &field_name, self.current_module.clone()) {
+                    continue;
+                }
+                (DiagnosticSeverity::ERROR, "OLS30902", format!("{} is not a field of model {}", field_name, model_name))
+            };
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(field.range.start().to_u32(), 0), Position::new(field.range.end().to_u32(), 0)),
+                Some(severity),
+                Some(NumberOrString::String(S!(code))),
+                Some(EXTENSION_NAME.to_string()),
+                message,
+                None,
+                None,
+            ));
+        }
+    }
+
+    fn _check_decorator_rules(&mut self, info: &DecoratorInfo, call: Option<&ExprCall>, func: &StmtFunctionDef, in_model: bool, range: &TextRange) {
+        for rule in info.rules.iter() {
+            let issue = match rule {
+                DecoratorRule::Called if call.is_none() => Some((DiagnosticSeverity::ERROR, "OLS30901", format!("@{} must be called with its arguments", info.name()))),
+                DecoratorRule::RequiredKeyword(keyword) if call.is_some_and(|call| call.arguments.find_keyword(keyword).is_none()) =>
+                    Some((DiagnosticSeverity::ERROR, "OLS30901", format!("@{} requires the {} argument", info.name(), keyword))),
+                DecoratorRule::PrivateMethod if !func.name.as_str().starts_with('_') =>
+                    Some((DiagnosticSeverity::ERROR, "OLS30901", format!("@{} can only decorate a private method, whose name starts with an underscore", info.name()))),
+                DecoratorRule::ModelMethod if !in_model =>
+                    Some((DiagnosticSeverity::WARNING, "OLS20901", format!("@{} only applies to methods of models", info.name()))),
+                DecoratorRule::MethodName(name) if in_model && func.name.as_str() != *name =>
+                    Some((DiagnosticSeverity::WARNING, "OLS20901", format!("@{} is meant to decorate the {} method", info.name(), name))),
+                _ => None,
+            };
+            if let Some((severity, code, message)) = issue {
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(severity),
+                    Some(NumberOrString::String(S!(code))),
+                    Some(EXTENSION_NAME.to_string()),
+                    message,
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+
+    /* Flag the calls to deprecated methods of models, like records.name_get(). Methods of the same name on other objects are
+    not reported */
+    fn _check_deprecated_calls(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        let version_major = session.sync_odoo.version_major;
+        let file_symbol = self.sym_stack[0].clone();
+        for call in AstUtils::collect_calls(ast) {
+            let Expr::Attribute(attr) = call.func.as_ref() else {
+                continue;
+            };
+            let Some(deprecation) = find_model_method_deprecation(attr.attr.as_str(), version_major) else {
+                continue;
+            };
+            if PythonValidator::_get_records_model(session, &file_symbol, call, &attr.value).is_none() {
+                continue;
+            }
+            self._add_deprecation_diagnostic(session, deprecation, &attr.attr.range);
+        }
+    }
+
+    fn _add_deprecation_diagnostic(&mut self, session: &mut SessionInfo, deprecation: &Deprecation, range: &TextRange) {
+        let version_major = session.sync_odoo.version_major;
+        let (severity, code) = if deprecation.is_removed(version_major) {
+            (DiagnosticSeverity::ERROR, "OLS30003")
+        } else {
+            (DiagnosticSeverity::WARNING, "OLS20007")
+        };
+        self.diagnostics.push(Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(severity),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            deprecation.message(version_major),
+            None,
+            None,
+        ));
     }
 
     fn _check_model(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>) {
@@ -264,7 +1442,9 @@ impl PythonValidator {
         if self.current_module.is_none() {
             return;
         }
+        let model_name = model.name.clone();
         //Check inherit field
+        let mut entries: Vec<(String, TextRange)> = vec![];
         let inherit = cl.get_symbol(&(vec![], vec![S!("_inherit")]), u32::MAX);
         if let Some(inherit) = inherit.last() {
             let inherit = inherit.borrow();
@@ -274,19 +1454,12 @@ impl PythonValidator {
                 if let Some(inherit_value) = inherit_value {
                     match inherit_value {
                         EvaluationValue::CONSTANT(Expr::StringLiteral(s)) => {
-                            self._check_module_dependency(session, &s.value.to_string(), &s.range());
+                            entries.push((s.value.to_string(), s.range()));
                         },
-                        EvaluationValue::LIST(l) => {
+                        EvaluationValue::LIST(l) | EvaluationValue::TUPLE(l) => {
                             for e in l {
                                 if let Expr::StringLiteral(s) = e {
-                                    self._check_module_dependency(session, &s.value.to_string(), &s.range());
-                                }
-                            }
-                        },
-                        EvaluationValue::TUPLE(l) => {
-                            for e in l {
-                                if let Expr::StringLiteral(s) = e {
-                                    self._check_module_dependency(session, &s.value.to_string(), &s.range());
+                                    entries.push((s.value.to_string(), s.range()));
                                 }
                             }
                         },
@@ -297,6 +1470,150 @@ impl PythonValidator {
                 }
             }
         }
+        drop(cl);
+        for (name, range) in entries.iter() {
+            self._check_module_dependency(session, name, range);
+        }
+        for (index, (name, range)) in entries.iter().enumerate() {
+            if entries[..index].iter().any(|(previous, _)| previous == name) {
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::WARNING),
+                    Some(NumberOrString::String(S!("OLS21002"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("{} is already inherited by this class", name),
+                    None,
+                    None,
+                ));
+            }
+        }
+        if entries.len() > 1 {
+            self._check_inherit_order(session, class, &model_name, &entries);
+        }
+    }
+
+    /* Odoo builds the class of a model with the models of _inherit as bases, in the order of the list: a mixin listed after a concrete
+    model is called by the super() of the concrete model, instead of wrapping it. Warn when both define the same methods, like create or
+    write, and the concrete model does not already inherit the mixin */
+    fn _check_inherit_order(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, model_name: &String, entries: &Vec<(String, TextRange)>) {
+        let from_module = self.current_module.clone().unwrap();
+        let mro = class.borrow().get_model_mro(session, from_module.clone());
+        let position = |name: &String| mro.iter().position(|model| model == name);
+        for (index, (mixin, range)) in entries.iter().enumerate() {
+            if mixin == model_name || entries[..index].iter().any(|(previous, _)| previous == mixin) || PythonValidator::is_abstract_model(session, &from_module, mixin) != Some(true) {
+                continue;
+            }
+            for (concrete, _) in entries[..index].iter() {
+                if concrete == model_name || concrete == mixin || PythonValidator::is_abstract_model(session, &from_module, concrete) != Some(false) {
+                    continue;
+                }
+                //the order of the effective mro decides, not the one of the list
+                if !position(concrete).is_some_and(|concrete_position| position(mixin).is_some_and(|mixin_position| concrete_position < mixin_position)) {
+                    continue;
+                }
+                if Symbol::get_model_linearization(session, &from_module, concrete).contains(mixin) {
+                    continue;
+                }
+                let mixin_methods = PythonValidator::get_model_methods(session, &from_module, mixin);
+                let mut overridden: Vec<String> = PythonValidator::get_model_methods(session, &from_module, concrete).intersection(&mixin_methods).cloned().collect();
+                if overridden.is_empty() {
+                    continue;
+                }
+                overridden.sort();
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::WARNING),
+                    Some(NumberOrString::String(S!("OLS21003"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("Mixin {} is inherited after {}, which overrides {}. The methods of {} will run before the ones of the mixin. List the mixins first in _inherit",
+                        mixin, concrete, overridden.join(", "), concrete),
+                    None,
+                    None,
+                ));
+                break;
+            }
+        }
+    }
+
+    /* None if the model is unknown from the module */
+    fn is_abstract_model(session: &mut SessionInfo, from_module: &Rc<RefCell<Symbol>>, name: &String) -> Option<bool> {
+        let model = session.sync_odoo.models.get(name).cloned()?;
+        let main_symbols = model.borrow().get_main_symbols(session, Some(from_module.clone()), &mut None);
+        if main_symbols.is_empty() {
+            return None;
+        }
+        Some(main_symbols.iter().all(|symbol| symbol.borrow().as_class_sym()._model.as_ref().is_some_and(|data| data.is_abstract)))
+    }
+
+    /* Names of the methods defined by the classes of a model, without the special methods of python */
+    fn get_model_methods(session: &mut SessionInfo, from_module: &Rc<RefCell<Symbol>>, name: &String) -> HashSet<String> {
+        let mut methods = HashSet::new();
+        let Some(model) = session.sync_odoo.models.get(name).cloned() else {
+            return methods;
+        };
+        for class in model.borrow().get_symbols(session, from_module.clone()) {
+            for (member_name, sections) in class.borrow().iter_symbols() {
+                if member_name.starts_with("__") {
+                    continue;
+                }
+                if sections.values().flatten().any(|member| member.borrow().typ() == SymType::FUNCTION && !member.borrow().is_comprehension()) {
+                    methods.insert(member_name.clone());
+                }
+            }
+        }
+        methods
+    }
+
+    /* Flag the fields and methods defined twice in the body of a class, like after a bad merge. Only the last definition is used
+    by python, so the first one is pointed as related information. Definitions in conditional blocks are not checked */
+    fn _check_duplicate_members(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, class_def: &StmtClassDef) {
+        let mut definitions: HashMap<String, (bool, TextRange)> = HashMap::new(); //name => (is a method, range of the first definition)
+        let mut uri = None;
+        for stmt in class_def.body.iter() {
+            let (name, is_method, range) = match stmt {
+                Stmt::FunctionDef(func) => {
+                    let is_property_accessor = func.decorator_list.iter().any(|decorator| match &decorator.expression {
+                        Expr::Name(name) => name.id.as_str() == "property" || name.id.as_str() == "overload",
+                        Expr::Attribute(attr) => ["setter", "getter", "deleter", "overload"].contains(&attr.attr.as_str()),
+                        _ => false
+                    });
+                    if is_property_accessor {
+                        continue;
+                    }
+                    (func.name.to_string(), true, func.name.range())
+                },
+                Stmt::Assign(assign) if assign.targets.len() == 1 && assign.targets[0].is_name_expr() && assign.value.is_call_expr() => {
+                    let target = assign.targets[0].as_name_expr().unwrap();
+                    let symbol = class.borrow().get_positioned_symbol(&target.id.to_string(), &target.range);
+                    if !symbol.is_some_and(|symbol| PythonValidator::get_field_info(session, &symbol).is_some()) {
+                        continue;
+                    }
+                    (target.id.to_string(), false, target.range)
+                },
+                _ => continue
+            };
+            let Some((first_is_method, first_range)) = definitions.get(&name).cloned() else {
+                definitions.insert(name, (is_method, range));
+                continue;
+            };
+            let uri = uri.get_or_insert_with(|| FileMgr::pathname2uri(&self.get_file_info(session.sync_odoo).borrow().uri)).clone();
+            let kind = |is_method: bool| if is_method { "method" } else { "field" };
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS21001"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("{} is already defined as a {} in this class. The first definition is overridden by this {}", name, kind(first_is_method), kind(is_method)),
+                Some(vec![DiagnosticRelatedInformation {
+                    location: Location {
+                        uri,
+                        range: Range::new(Position::new(first_range.start().to_u32(), 0), Position::new(first_range.end().to_u32(), 0)),
+                    },
+                    message: format!("First definition of {}", name),
+                }]),
+                None,
+            ));
+        }
     }
 
     fn _check_module_dependency(&mut self, session: &mut SessionInfo, model: &String, range: &TextRange) {
@@ -309,7 +1626,7 @@ impl PythonValidator {
                 let mut found_one = false;
                 for main_sym in borrowed_model.get_main_symbols(session, None, &mut None).iter() {
                     let main_sym = main_sym.borrow();
-                    let main_sym_module = main_sym.find_module();
+                    let main_sym_module = main_sym.owning_module();
                     if let Some(main_sym_module) = main_sym_module {
                         let module_name = main_sym_module.borrow().as_module_package().dir_name.clone();
                         main_modules.push(module_name.clone());
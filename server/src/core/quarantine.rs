@@ -0,0 +1,86 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use lsp_types::notification::Notification;
+use serde::{Deserialize, Serialize};
+
+/* Number of failures of the build steps of a file before it is quarantined */
+pub const MAX_FAILURES: u32 = 2;
+
+/* Time above which a build step of a single file counts as a failure. The step is not stopped, but a file that is that slow
+each time it is rebuilt would keep the session busy */
+pub const STEP_TIME_BUDGET: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileQuarantinedParams {
+    pub path: String,
+    pub step: String, //build step that failed last: ARCH, ARCH_EVAL, ODOO or VALIDATION
+    pub reason: String,
+}
+
+#[derive(Debug)]
+pub enum FileQuarantinedNotification {}
+
+impl Notification for FileQuarantinedNotification {
+    type Params = FileQuarantinedParams;
+    const METHOD: &'static str = "Odoo/fileQuarantined";
+}
+
+/* Files whose build keeps panicking or exceeding the time budget. A quarantined file keeps the symbols of its arch step,
+that only reads its syntax, but is not evaluated nor validated anymore, so the rest of the workspace is still built. The file
+gets a new chance when it is edited, and when the database is reset */
+#[derive(Debug, Default)]
+pub struct Quarantine {
+    failures: HashMap<String, u32>,
+    files: HashSet<String>,
+}
+
+impl Quarantine {
+
+    /* Record a failure of a build step of the file. Return true if the file has just been quarantined */
+    pub fn record_failure(&mut self, path: &str) -> bool {
+        if self.files.contains(path) {
+            return false;
+        }
+        let failures = self.failures.entry(path.to_string()).or_insert(0);
+        *failures += 1;
+        if *failures < MAX_FAILURES {
+            return false;
+        }
+        self.failures.remove(path);
+        self.files.insert(path.to_string());
+        true
+    }
+
+    /* A failure counts only if the next ones happen before a successful build of the file */
+    pub fn record_success(&mut self, path: &str) {
+        if !self.failures.is_empty() {
+            self.failures.remove(path);
+        }
+    }
+
+    pub fn is_quarantined(&self, path: &str) -> bool {
+        !self.files.is_empty() && self.files.contains(path)
+    }
+
+    /* Give a new chance to the file, when its content changes. A single one: it is quarantined again at its next failure */
+    pub fn release(&mut self, path: &str) -> bool {
+        if !self.files.remove(path) {
+            return false;
+        }
+        self.failures.insert(path.to_string(), MAX_FAILURES - 1);
+        true
+    }
+
+    pub fn files(&self) -> Vec<String> {
+        let mut files: Vec<String> = self.files.iter().cloned().collect();
+        files.sort();
+        files
+    }
+
+    pub fn clear(&mut self) {
+        self.failures.clear();
+        self.files.clear();
+    }
+}
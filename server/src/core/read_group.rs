@@ -0,0 +1,215 @@
+use std::collections::{HashMap, HashSet};
+
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{Expr, ExprCall, Stmt};
+use ruff_text_size::TextRange;
+
+/* Aggregate functions accepted in the fields of read_group and the aggregates of _read_group */
+pub const AGGREGATE_FUNCTIONS: [&str; 12] = ["sum", "avg", "min", "max", "count", "count_distinct", "array_agg", "array_agg_distinct",
+    "bool_and", "bool_or", "recordset", "sum_currency"];
+
+/* Granularities of the date and datetime fields in groupby, like "date:month" */
+pub const GRANULARITIES: [&str; 16] = ["hour", "day", "week", "month", "quarter", "year", "year_number", "quarter_number", "month_number",
+    "iso_week_number", "day_of_year", "day_of_month", "day_of_week", "hour_number", "minute_number", "second_number"];
+
+/* A groupby spec, like "partner_id" or "date:month" */
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupbySpec {
+    pub spec: String,
+    pub field: String, //first field of the path
+    pub granularity: Option<String>,
+    pub range: TextRange,
+}
+
+/* An aggregate spec: "amount_total", "amount_total:sum", "total:sum(amount_total)" or "__count" */
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateSpec {
+    pub spec: String,
+    pub key: String, //key of the result dictionaries
+    pub field: Option<String>, //None for __count
+    pub function: Option<String>,
+    pub range: TextRange,
+}
+
+/* The groups and aggregates requested by a call to read_group or _read_group with literal specs */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadGroupCall {
+    pub groupby: Vec<GroupbySpec>,
+    pub aggregates: Vec<AggregateSpec>,
+    pub lazy: bool,
+    pub returns_dicts: bool, //false for _read_group since Odoo 17, that returns tuples
+}
+
+impl ReadGroupCall {
+
+    /* read_group(domain, fields, groupby, offset, limit, orderby, lazy) returns a list of dictionaries. Since Odoo 17,
+    _read_group(domain, groupby, aggregates, ...) returns a list of tuples. Before, it had the signature of read_group */
+    pub fn parse(call: &ExprCall, version_major: u32) -> Option<ReadGroupCall> {
+        let Expr::Attribute(method) = call.func.as_ref() else {
+            return None;
+        };
+        let new_api = match method.attr.as_str() {
+            "read_group" => false,
+            "_read_group" => version_major >= 17,
+            _ => return None,
+        };
+        let (groupby, aggregates) = if new_api {
+            (call.arguments.find_argument("groupby", 1), call.arguments.find_argument("aggregates", 2))
+        } else {
+            (call.arguments.find_argument("groupby", 2), call.arguments.find_argument("fields", 1))
+        };
+        let lazy = new_api || !call.arguments.find_argument("lazy", 6).is_some_and(|lazy| lazy.as_boolean_literal_expr().is_some_and(|lazy| !lazy.value));
+        Some(ReadGroupCall {
+            groupby: groupby.map(string_literals).unwrap_or_default().into_iter().map(|(spec, range)| {
+                let (path, granularity) = match spec.split_once(':') {
+                    Some((path, granularity)) => (path, Some(granularity.to_string())),
+                    None => (spec.as_str(), None),
+                };
+                GroupbySpec {
+                    field: path.split('.').next().unwrap_or_default().to_string(),
+                    granularity,
+                    spec: spec.clone(),
+                    range,
+                }
+            }).collect(),
+            aggregates: aggregates.map(string_literals).unwrap_or_default().into_iter().map(|(spec, range)| parse_aggregate(spec, range)).collect(),
+            lazy,
+            returns_dicts: !new_api,
+        })
+    }
+
+    /* Keys of the dictionaries returned by read_group. A lazy read_group only groups by the first groupby, and counts the
+    records in <groupby>_count */
+    pub fn result_keys(&self) -> Vec<String> {
+        if !self.returns_dicts {
+            return vec![];
+        }
+        let groupby: Vec<&GroupbySpec> = if self.lazy { self.groupby.iter().take(1).collect() } else { self.groupby.iter().collect() };
+        let mut keys: Vec<String> = groupby.iter().map(|groupby| groupby.spec.clone()).collect();
+        keys.extend(self.aggregates.iter().map(|aggregate| aggregate.key.clone()));
+        match groupby.first() {
+            Some(first) if self.lazy => keys.push(format!("{}_count", first.field)),
+            _ => keys.push(String::from("__count")),
+        }
+        keys.push(String::from("__domain"));
+        if self.lazy {
+            keys.push(String::from("__context"));
+        }
+        //a groupby can also be aggregated, like groupby=['partner_id'] with fields=['partner_id:count']
+        let mut seen = HashSet::new();
+        keys.retain(|key| seen.insert(key.clone()));
+        keys
+    }
+
+    /* Granularities and aggregate functions that Odoo doesn't know, with the range of their spec */
+    pub fn spec_errors(&self) -> Vec<(TextRange, String)> {
+        let mut errors = vec![];
+        for groupby in self.groupby.iter() {
+            if let Some(granularity) = groupby.granularity.as_ref().filter(|granularity| !GRANULARITIES.contains(&granularity.as_str())) {
+                errors.push((groupby.range, format!("Invalid date granularity {} in groupby {}", granularity, groupby.spec)));
+            }
+        }
+        for aggregate in self.aggregates.iter() {
+            if let Some(function) = aggregate.function.as_ref().filter(|function| !AGGREGATE_FUNCTIONS.contains(&function.as_str())) {
+                errors.push((aggregate.range, format!("Invalid aggregate function {} in {}", function, aggregate.spec)));
+            }
+        }
+        errors
+    }
+}
+
+fn parse_aggregate(spec: String, range: TextRange) -> AggregateSpec {
+    if spec == "__count" {
+        return AggregateSpec { key: spec.clone(), field: None, function: None, spec, range };
+    }
+    let (name, function) = match spec.split_once(':') {
+        Some((name, function)) => (name.to_string(), Some(function.to_string())),
+        None => (spec.clone(), None),
+    };
+    //"alias:function(field)"
+    let (function, field) = match function.as_ref().and_then(|function| function.strip_suffix(')')).and_then(|function| function.split_once('(')) {
+        Some((function, field)) => (Some(function.to_string()), field.to_string()),
+        None => (function, name.clone()),
+    };
+    AggregateSpec { key: name, field: Some(field), function, spec, range }
+}
+
+/* The literal strings of a list or a tuple, or a single string */
+fn string_literals(expr: &Expr) -> Vec<(String, TextRange)> {
+    let elements = match expr {
+        Expr::List(list) => list.elts.iter().collect(),
+        Expr::Tuple(tuple) => tuple.elts.iter().collect(),
+        expr => vec![expr],
+    };
+    elements.into_iter().filter_map(|element| element.as_string_literal_expr().map(|literal| (literal.value.to_string(), literal.range))).collect()
+}
+
+/* The call to read_group giving the dictionary of a row: `self.read_group(...)[0]`, the loop variable of
+`for group in self.read_group(...)`, or of a loop on a variable assigned with the result of read_group */
+pub fn find_rows_call<'a>(body: &'a [Stmt], row: &'a Expr) -> Option<&'a ExprCall> {
+    let mut collector = RowsCollector { results: HashMap::new(), loops: vec![] };
+    for stmt in body.iter() {
+        collector.visit_stmt(stmt);
+    }
+    let rows_call = |rows: &'a Expr| -> Option<&'a ExprCall> {
+        match rows {
+            Expr::Call(call) if is_read_group(call) => Some(call),
+            Expr::Name(name) => collector.results.get(name.id.as_str()).copied(),
+            _ => None
+        }
+    };
+    match row {
+        Expr::Subscript(subscript) => rows_call(&subscript.value),
+        Expr::Name(name) => collector.loops.iter().rev()
+            .filter(|(target, _)| *target == name.id.as_str())
+            .find_map(|(_, iter)| rows_call(iter)),
+        _ => None
+    }
+}
+
+fn is_read_group(call: &ExprCall) -> bool {
+    call.func.as_attribute_expr().is_some_and(|method| matches!(method.attr.as_str(), "read_group" | "_read_group"))
+}
+
+struct RowsCollector<'a> {
+    results: HashMap<&'a str, &'a ExprCall>, //variables assigned with the result of read_group
+    loops: Vec<(&'a str, &'a Expr)>, //loop variables, with the iterated expression
+}
+
+impl<'a> Visitor<'a> for RowsCollector<'a> {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Assign(assign) => {
+                if let ([Expr::Name(target)], Expr::Call(call)) = (&assign.targets[..], assign.value.as_ref()) {
+                    if is_read_group(call) {
+                        self.results.insert(target.id.as_str(), call);
+                    }
+                }
+            },
+            Stmt::For(for_stmt) => {
+                if let Expr::Name(target) = for_stmt.target.as_ref() {
+                    self.loops.push((target.id.as_str(), &for_stmt.iter));
+                }
+            },
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        let generators = match expr {
+            Expr::ListComp(comp) => comp.generators.as_slice(),
+            Expr::SetComp(comp) => comp.generators.as_slice(),
+            Expr::DictComp(comp) => comp.generators.as_slice(),
+            Expr::Generator(comp) => comp.generators.as_slice(),
+            _ => &[],
+        };
+        for generator in generators.iter() {
+            if let Expr::Name(target) = &generator.target {
+                self.loops.push((target.id.as_str(), &generator.iter));
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
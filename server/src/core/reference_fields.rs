@@ -0,0 +1,57 @@
+use ruff_python_ast::{Expr, ExprCall};
+use ruff_text_size::TextRange;
+
+/* Fields holding a reference to a record of any model. The value of a Reference field is a record of one of the models of
+its selection, written as a "model,id" string. A Many2oneReference only holds the id of the record: the name of its model
+is read from the Char field given by model_field */
+pub const REFERENCE_FIELD: &str = "Reference";
+pub const MANY2ONE_REFERENCE_FIELD: &str = "Many2oneReference";
+
+/* Type of the field holding the name of the model of a Many2oneReference */
+pub const MODEL_FIELD_TYPE: &str = "Char";
+
+/* The selection of a Reference field: its first positional argument or the selection keyword */
+pub fn selection_expr(call: &ExprCall) -> Option<&Expr> {
+    call.arguments.find_argument("selection", 0)
+}
+
+/* The models of the selection of a Reference field, with the range of their names. A selection given by a method, like
+selection='_selection_target_model', can't be known */
+pub fn selection_models(call: &ExprCall) -> Vec<(String, TextRange)> {
+    let items = match selection_expr(call) {
+        Some(Expr::List(list)) => &list.elts,
+        Some(Expr::Tuple(tuple)) => &tuple.elts,
+        _ => return vec![],
+    };
+    items.iter().filter_map(|item| match item.as_tuple_expr().and_then(|t| t.elts.first()) {
+        Some(Expr::StringLiteral(model)) => Some((model.value.to_string(), model.range)),
+        _ => None,
+    }).collect()
+}
+
+/* The model_field of a Many2oneReference, with the range of the string. The first positional argument of the field is its string */
+pub fn model_field(call: &ExprCall) -> Option<(String, TextRange)> {
+    match call.arguments.find_keyword("model_field").map(|keyword| &keyword.value) {
+        Some(Expr::StringLiteral(model_field)) => Some((model_field.value.to_string(), model_field.range)),
+        _ => None,
+    }
+}
+
+/* Description of the value of a reference field, displayed on hover */
+pub fn describe_value(field_type: &str, call: &ExprCall) -> Option<String> {
+    match field_type {
+        REFERENCE_FIELD => {
+            let models: Vec<String> = selection_models(call).into_iter().map(|(model, _)| format!("`{}`", model)).collect();
+            let records = if models.is_empty() { "a record".to_string() } else { format!("a record of {}", models.join(" | ")) };
+            Some(format!("Value: {} or False, written as a `\"model,id\"` string", records))
+        },
+        MANY2ONE_REFERENCE_FIELD => {
+            let model = match model_field(call) {
+                Some((model_field, _)) => format!("the model named by `{}`", model_field),
+                None => "the model named by its model_field".to_string(),
+            };
+            Some(format!("Value: the id of a record of {}", model))
+        },
+        _ => None,
+    }
+}
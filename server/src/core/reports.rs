@@ -0,0 +1,221 @@
+use std::path::PathBuf;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, Location, NumberOrString, Position, Range};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::constants::EXTENSION_NAME;
+use crate::core::file_mgr::FileMgr;
+use crate::core::xml_parser::{attribute, field_text, strip_comments, LineIndex, XmlValue, FIELD_RE, RECORD_RE};
+use crate::threads::SessionInfo;
+use crate::S;
+
+pub const REPORT_MODEL: &str = "ir.actions.report";
+pub const PAPERFORMAT_MODEL: &str = "report.paperformat";
+
+static TEMPLATE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<template\b([^>]*?)/?>"#).unwrap());
+static REPORT_SHORTCUT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<report\b([^>]*?)/?>"#).unwrap());
+static QWEB_TYPE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<field\b[^>]*\bname=["']type["'][^>]*>\s*qweb\s*</field>"#).unwrap());
+
+/* A report action declared by a data file, with a record of ir.actions.report or the <report> shortcut */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportDeclaration {
+    pub xml_id: String, //qualified with the module
+    pub path: String,
+    pub line: u32, //0-based, like lsp positions
+    pub report_name: Option<XmlValue>, //xml id of the qweb template
+    pub report_type: Option<String>,
+    pub model: Option<XmlValue>,
+    pub print_report_name: Option<XmlValue>, //python expression giving the name of the printed file
+    pub paperformat: Option<XmlValue>, //xml id of the report.paperformat record
+}
+
+/* The records of the data files of a module that are used by its report actions. The xml files are scanned without being
+parsed, like the other xml features */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmlReportIndex {
+    pub reports: Vec<ReportDeclaration>,
+    pub templates: Vec<String>, //qualified xml ids of the qweb templates
+    pub paperformats: Vec<String>, //qualified xml ids of the paper formats
+}
+
+impl XmlReportIndex {
+
+    pub fn parse(module_name: &str, path: &str, content: &str) -> XmlReportIndex {
+        let content = strip_comments(content);
+        let lines = LineIndex::new(&content);
+        let mut index = XmlReportIndex::default();
+        for record in RECORD_RE.captures_iter(&content) {
+            let attrs = record.get(1).unwrap().as_str();
+            let (Some(id), Some(model)) = (attribute(attrs, "id"), attribute(attrs, "model")) else {
+                continue;
+            };
+            let xml_id = qualify(module_name, &id.value);
+            let body = record.get(2);
+            match model.value.as_str() {
+                REPORT_MODEL => {
+                    let mut report = ReportDeclaration {
+                        xml_id,
+                        path: path.to_string(),
+                        line: lines.line_of(record.get(0).unwrap().start()),
+                        report_name: None,
+                        report_type: None,
+                        model: None,
+                        print_report_name: None,
+                        paperformat: None,
+                    };
+                    let body_start = body.map(|body| body.start()).unwrap_or_default();
+                    for field in body.map(|body| FIELD_RE.captures_iter(body.as_str())).into_iter().flatten() {
+                        let field_attrs = field.get(1).unwrap();
+                        let Some(name) = attribute(field_attrs.as_str(), "name") else {
+                            continue;
+                        };
+                        let text = field_text(&field).map(|text| text.shifted(body_start));
+                        match name.value.as_str() {
+                            "report_name" => report.report_name = text,
+                            "report_type" => report.report_type = text.map(|text| text.value),
+                            "model" => report.model = text,
+                            "print_report_name" => report.print_report_name = text,
+                            "paperformat_id" => report.paperformat = attribute(field_attrs.as_str(), "ref")
+                                .map(|value| value.shifted(body_start + field_attrs.start())),
+                            _ => {}
+                        }
+                    }
+                    index.reports.push(report);
+                },
+                PAPERFORMAT_MODEL => index.paperformats.push(xml_id),
+                "ir.ui.view" if body.is_some_and(|body| QWEB_TYPE_RE.is_match(body.as_str())) => index.templates.push(xml_id),
+                _ => {}
+            }
+        }
+        for template in TEMPLATE_RE.captures_iter(&content) {
+            if let Some(id) = attribute(&template[1], "id") {
+                index.templates.push(qualify(module_name, &id.value));
+            }
+        }
+        //shortcut of the older versions: <report id="..." model="..." name="..." .../>
+        for shortcut in REPORT_SHORTCUT_RE.captures_iter(&content) {
+            let attrs = shortcut.get(1).unwrap();
+            let Some(id) = attribute(attrs.as_str(), "id") else {
+                continue;
+            };
+            let in_file = |value: XmlValue| value.shifted(attrs.start());
+            index.reports.push(ReportDeclaration {
+                xml_id: qualify(module_name, &id.value),
+                path: path.to_string(),
+                line: lines.line_of(shortcut.get(0).unwrap().start()),
+                report_name: attribute(attrs.as_str(), "name").map(in_file),
+                report_type: attribute(attrs.as_str(), "report_type").map(|value| value.value),
+                model: attribute(attrs.as_str(), "model").map(in_file),
+                print_report_name: attribute(attrs.as_str(), "print_report_name").map(in_file),
+                paperformat: attribute(attrs.as_str(), "paperformat").map(in_file),
+            });
+        }
+        index
+    }
+
+    pub fn extend(&mut self, other: XmlReportIndex) {
+        self.reports.extend(other.reports);
+        self.templates.extend(other.templates);
+        self.paperformats.extend(other.paperformats);
+    }
+}
+
+pub struct ReportValidator {}
+
+impl ReportValidator {
+
+    /* Validate the report actions of a xml file: their template, model, paper format and the expression of the name of the
+    printed file. References to the modules that are not loaded are not checked */
+    pub fn validate(session: &mut SessionInfo, path: &str, content: &str) -> Vec<Diagnostic> {
+        let module_name = ReportValidator::module_of_path(session, path).unwrap_or_default();
+        let index = XmlReportIndex::parse(&module_name, path, content);
+        let mut diagnostics = vec![];
+        for report in index.reports.iter() {
+            if let Some(report_name) = report.report_name.as_ref() {
+                let is_qweb = report.report_type.as_ref().map_or(true, |report_type| report_type.starts_with("qweb"));
+                if is_qweb && ReportValidator::is_unknown(session, &index, &report_name.value, |index| &index.templates) {
+                    diagnostics.push(ReportValidator::diagnostic(report_name.range(), "OLS31501",
+                        format!("Unknown template {} for report {}", report_name.value, report.xml_id)));
+                }
+            }
+            if let Some(model) = report.model.as_ref() {
+                if !session.sync_odoo.models.contains_key(&model.value) {
+                    diagnostics.push(ReportValidator::diagnostic(model.range(), "OLS31502", format!("Unknown model {}", model.value)));
+                }
+            }
+            if let Some(print_report_name) = report.print_report_name.as_ref() {
+                if let Err(error) = ruff_python_parser::parse_expression(&unescape(&print_report_name.value)) {
+                    diagnostics.push(ReportValidator::diagnostic(print_report_name.range(), "OLS31503",
+                        format!("Invalid print_report_name expression: {}", error.error)));
+                }
+            }
+            if let Some(paperformat) = report.paperformat.as_ref() {
+                let xml_id = qualify(&module_name, &paperformat.value);
+                if ReportValidator::is_unknown(session, &index, &xml_id, |index| &index.paperformats) {
+                    diagnostics.push(ReportValidator::diagnostic(paperformat.range(), "OLS31504", format!("Unknown paper format {}", xml_id)));
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /* A xml id is unknown if it is not declared by the file, nor by the loaded module it is qualified with */
+    fn is_unknown(session: &mut SessionInfo, file_index: &XmlReportIndex, xml_id: &String, ids: fn(&XmlReportIndex) -> &Vec<String>) -> bool {
+        if ids(file_index).contains(xml_id) {
+            return false;
+        }
+        let Some((module_name, _)) = xml_id.split_once('.') else {
+            return true;
+        };
+        let Some(module) = session.sync_odoo.modules.get(module_name).and_then(|module| module.upgrade()) else {
+            return false;
+        };
+        let module = module.borrow();
+        !ids(&module.as_module_package().reports).contains(xml_id)
+    }
+
+    /* Location of the report action with the given xml id, for the navigation from env.ref('module.report_x') */
+    pub fn find_report_location(session: &mut SessionInfo, xml_id: &str) -> Option<Location> {
+        let (module_name, _) = xml_id.split_once('.')?;
+        let module = session.sync_odoo.modules.get(module_name)?.upgrade()?;
+        let module = module.borrow();
+        let report = module.as_module_package().reports.reports.iter().find(|report| report.xml_id == xml_id)?;
+        Some(Location {
+            uri: FileMgr::pathname2uri(&report.path),
+            range: Range::new(Position::new(report.line, 0), Position::new(report.line, 0)),
+        })
+    }
+
+    /* Name of the loaded module containing the file */
+    pub fn module_of_path(session: &mut SessionInfo, path: &str) -> Option<String> {
+        let path = PathBuf::from(path);
+        session.sync_odoo.modules.iter()
+            .filter_map(|(name, module)| module.upgrade().map(|module| (name, module.borrow().as_module_package().path.clone())))
+            .find(|(_, module_path)| path.starts_with(module_path))
+            .map(|(name, _)| name.clone())
+    }
+
+    fn diagnostic(range: Range, code: &str, message: String) -> Diagnostic {
+        Diagnostic::new(range,
+            Some(DiagnosticSeverity::ERROR),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None)
+    }
+}
+
+/* Xml ids without a module are the ones of the current module */
+fn qualify(module_name: &str, id: &str) -> String {
+    if id.contains('.') {
+        id.to_string()
+    } else {
+        format!("{}.{}", module_name, id)
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("&quot;", "\"").replace("&apos;", "'").replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
@@ -0,0 +1,83 @@
+use lsp_types::notification::{Notification, Progress};
+use lsp_types::{ProgressParams, ProgressParamsValue, ProgressToken, WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressEnd, WorkDoneProgressReport};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::threads::SessionInfo;
+
+/* Progress of a long request, like a rename on the whole workspace. The steps are reported with the work done token
+given by the client, and the results are streamed with its partial result token. Without token, nothing is sent.
+The computation checks is_cancelled between its steps, so a cancelled request stops before producing a stale result */
+pub struct RequestProgress {
+    work_done_token: Option<ProgressToken>,
+    partial_result_token: Option<ProgressToken>,
+    total: usize,
+    done: usize,
+    percentage: u32, //last reported percentage, to send a report only when it changes
+}
+
+impl RequestProgress {
+
+    pub fn new(work_done_token: Option<ProgressToken>, partial_result_token: Option<ProgressToken>) -> Self {
+        Self { work_done_token, partial_result_token, total: 0, done: 0, percentage: 0 }
+    }
+
+    /* Progress of a request without tokens, used when a request is computed by another one */
+    pub fn none() -> Self {
+        RequestProgress::new(None, None)
+    }
+
+    pub fn begin(&mut self, session: &SessionInfo, title: &str) {
+        self.send_work_done(session, WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: title.to_string(),
+            cancellable: Some(true),
+            message: None,
+            percentage: Some(0),
+        }));
+    }
+
+    /* Mark a step as done. Return false if the request has been cancelled, and the computation must stop */
+    pub fn step(&mut self, session: &SessionInfo, message: &str) -> bool {
+        self.done += 1;
+        let percentage = if self.total == 0 { 100 } else { (self.done * 100 / self.total).min(100) as u32 };
+        if percentage != self.percentage {
+            self.percentage = percentage;
+            self.send_work_done(session, WorkDoneProgress::Report(WorkDoneProgressReport {
+                cancellable: Some(true),
+                message: Some(message.to_string()),
+                percentage: Some(percentage),
+            }));
+        }
+        !session.is_cancelled()
+    }
+
+    pub fn end(&self, session: &SessionInfo, message: Option<String>) {
+        self.send_work_done(session, WorkDoneProgress::End(WorkDoneProgressEnd { message }));
+    }
+
+    /* Add a part of the results. It is sent at once if the client gave a partial result token, else it is kept in results
+    for the final response */
+    pub fn add_results<T: Serialize>(&self, session: &SessionInfo, results: &mut Vec<T>, part: Vec<T>) {
+        match self.partial_result_token.as_ref() {
+            //the partial results are not covered by ProgressParamsValue, that only knows the work done progress
+            Some(token) if !part.is_empty() => session.send_notification(Progress::METHOD, json!({"token": token, "value": part})),
+            Some(_) => {},
+            None => results.extend(part),
+        }
+    }
+
+    /* Steps are added by each part of the computation, before it starts */
+    pub fn add_total(&mut self, steps: usize) {
+        self.total += steps;
+    }
+
+    fn send_work_done(&self, session: &SessionInfo, progress: WorkDoneProgress) {
+        let Some(token) = self.work_done_token.as_ref() else {
+            return;
+        };
+        session.send_notification(Progress::METHOD, ProgressParams {
+            token: token.clone(),
+            value: ProgressParamsValue::WorkDone(progress),
+        });
+    }
+}
@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use ruff_python_ast::{Decorator, Expr, StmtFunctionDef};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::S;
+use super::symbols::symbol::Symbol;
+
+pub static ROUTE_AUTHS: &[&str] = &["user", "public", "none", "bearer"];
+pub static ROUTE_TYPES: &[&str] = &["http", "json", "jsonrpc"];
+
+/* An url declared by a @http.route decorator, as written in the code */
+#[derive(Debug, Clone)]
+pub struct RouteDecl {
+    pub path: String,
+    pub range: TextRange, //range of the path literal
+    pub auth: Option<(String, TextRange)>,
+    pub typ: Option<(String, TextRange)>,
+    pub csrf: Option<bool>,
+    pub methods: Vec<String>,
+}
+
+impl RouteDecl {
+
+    /* Parse the @http.route (or @route) decorators of a function. A decorator can declare multiple urls, returning one RouteDecl per url */
+    pub fn from_function(func: &StmtFunctionDef) -> Vec<RouteDecl> {
+        let mut res = vec![];
+        for decorator in func.decorator_list.iter() {
+            res.extend(RouteDecl::from_decorator(decorator));
+        }
+        res
+    }
+
+    fn from_decorator(decorator: &Decorator) -> Vec<RouteDecl> {
+        let Expr::Call(call) = &decorator.expression else {
+            return vec![];
+        };
+        let is_route = match call.func.as_ref() {
+            Expr::Attribute(attr) => attr.attr.as_str() == "route",
+            Expr::Name(name) => name.id.as_str() == "route",
+            _ => false
+        };
+        if !is_route {
+            return vec![];
+        }
+        let mut paths = vec![];
+        let mut auth = None;
+        let mut typ = None;
+        let mut csrf = None;
+        let mut methods = vec![];
+        if let Some(first) = call.arguments.args.first() {
+            RouteDecl::collect_strings(first, &mut paths);
+        }
+        for keyword in call.arguments.keywords.iter() {
+            let Some(arg) = keyword.arg.as_ref() else {
+                continue;
+            };
+            match (arg.as_str(), &keyword.value) {
+                ("route", value) => RouteDecl::collect_strings(value, &mut paths),
+                ("auth", Expr::StringLiteral(s)) => auth = Some((S!(s.value.to_str()), s.range)),
+                ("type", Expr::StringLiteral(s)) => typ = Some((S!(s.value.to_str()), s.range)),
+                ("csrf", Expr::BooleanLiteral(b)) => csrf = Some(b.value),
+                ("methods", value) => {
+                    let mut values = vec![];
+                    RouteDecl::collect_strings(value, &mut values);
+                    methods = values.into_iter().map(|(method, _)| method).collect();
+                },
+                _ => {}
+            }
+        }
+        paths.into_iter().map(|(path, range)| RouteDecl {
+            path,
+            range,
+            auth: auth.clone(),
+            typ: typ.clone(),
+            csrf,
+            methods: methods.clone(),
+        }).collect()
+    }
+
+    fn collect_strings(expr: &Expr, values: &mut Vec<(String, TextRange)>) {
+        match expr {
+            Expr::StringLiteral(s) => values.push((S!(s.value.to_str()), s.range)),
+            Expr::List(list) => list.elts.iter().for_each(|e| RouteDecl::collect_strings(e, values)),
+            Expr::Tuple(tuple) => tuple.elts.iter().for_each(|e| RouteDecl::collect_strings(e, values)),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Route {
+    pub decl: RouteDecl,
+    pub function: Weak<RefCell<Symbol>>,
+    pub file_path: String,
+}
+
+impl Route {
+
+    pub fn auth(&self) -> &str {
+        self.decl.auth.as_ref().map(|(auth, _)| auth.as_str()).unwrap_or("user")
+    }
+
+    pub fn typ(&self) -> &str {
+        self.decl.typ.as_ref().map(|(typ, _)| typ.as_str()).unwrap_or("http")
+    }
+
+    /* csrf is only checked on http routes */
+    pub fn csrf(&self) -> bool {
+        self.decl.csrf.unwrap_or(self.typ() == "http")
+    }
+
+    pub fn function_name(&self) -> String {
+        self.function.upgrade().map(|f| f.borrow().name().clone()).unwrap_or_default()
+    }
+
+    /* Name of the function, prefixed by the name of its controller */
+    pub fn handler_name(&self) -> String {
+        let Some(function) = self.function.upgrade() else {
+            return S!("");
+        };
+        let function = function.borrow();
+        match function.parent().and_then(|p| p.upgrade()) {
+            Some(class) => format!("{}.{}", class.borrow().name(), function.name()),
+            None => function.name().clone()
+        }
+    }
+}
+
+/* Index of the urls declared by the controllers of all modules, filled during the odoo step */
+#[derive(Debug, Default)]
+pub struct RouteIndex {
+    routes: HashMap<String, Vec<Route>>,
+}
+
+impl RouteIndex {
+
+    pub fn add(&mut self, route: Route) {
+        self.routes.entry(route.decl.path.clone()).or_default().push(route);
+    }
+
+    /* Remove the routes declared in a file, before it is rebuilt */
+    pub fn remove_file(&mut self, file_path: &String) {
+        for routes in self.routes.values_mut() {
+            routes.retain(|route| &route.file_path != file_path);
+        }
+        self.routes.retain(|_, routes| !routes.is_empty());
+    }
+
+    pub fn get(&self, path: &String) -> Vec<&Route> {
+        self.routes.get(path).map(|routes| routes.iter().filter(|route| route.function.upgrade().is_some()).collect()).unwrap_or_default()
+    }
+
+    pub fn get_by_function(&self, function: &Rc<RefCell<Symbol>>) -> Vec<&Route> {
+        self.routes.values().flatten().filter(|route| route.function.upgrade().is_some_and(|f| Rc::ptr_eq(&f, function))).collect()
+    }
+
+    /* Routes whose url contains the query, case insensitive */
+    pub fn search(&self, query: &str) -> Vec<&Route> {
+        let query = query.to_lowercase();
+        let mut res: Vec<&Route> = self.routes.iter()
+            .filter(|(path, _)| path.to_lowercase().contains(&query))
+            .flat_map(|(_, routes)| routes.iter())
+            .filter(|route| route.function.upgrade().is_some())
+            .collect();
+        res.sort_by(|a, b| a.decl.path.cmp(&b.decl.path));
+        res
+    }
+
+    pub fn clear(&mut self) {
+        self.routes.clear();
+    }
+}
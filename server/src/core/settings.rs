@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use ruff_python_ast::{Expr, Keyword};
+use ruff_text_size::TextRange;
+
+use crate::constants::SymType;
+use crate::core::python_validator::PythonValidator;
+use crate::core::symbols::symbol::Symbol;
+use crate::core::xml_parser::{attribute, field_text, strip_comments, FIELD_RE, RECORD_RE};
+use crate::features::ast_utils::AstUtils;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* Model of the settings screen. Its records are never stored: the values of its fields are saved elsewhere, depending on
+the keyword arguments of the fields */
+pub const SETTINGS_MODEL: &str = "res.config.settings";
+pub const CONFIG_PARAMETER_MODEL: &str = "ir.config_parameter";
+
+/* Methods of ir.config_parameter taking the key of a system parameter as first argument */
+pub const CONFIG_PARAMETER_METHODS: [&str; 2] = ["get_param", "set_param"];
+
+static SET_PARAM_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<function\b[^>]*\bmodel=["']ir\.config_parameter["'][^>]*\bname=["']set_param["'][^>]*\beval=["']\(\s*['"]([^'"]+)['"]"#).unwrap());
+
+/* Where the value of a field of res.config.settings is saved */
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsStorage {
+    ConfigParameter(String, TextRange), //config_parameter="key": a system parameter
+    DefaultValue(String, TextRange), //default_model="model": the default value of the field without the "default_" prefix
+    ImpliedGroup(String, Option<String>), //implied_group="group": the group is given to the users of the other group
+}
+
+impl SettingsStorage {
+
+    /* Read the storage of a field from the keywords of its declaration */
+    pub fn from_keywords(keywords: &[Keyword]) -> Vec<SettingsStorage> {
+        let string_kwarg = |name: &str| keywords.iter()
+            .find(|keyword| keyword.arg.as_ref().is_some_and(|arg| arg.as_str() == name))
+            .and_then(|keyword| match &keyword.value {
+                Expr::StringLiteral(value) => Some((value.value.to_string(), value.range)),
+                _ => None
+            });
+        let mut res = vec![];
+        if let Some((key, range)) = string_kwarg("config_parameter") {
+            res.push(SettingsStorage::ConfigParameter(key, range));
+        }
+        if let Some((model, range)) = string_kwarg("default_model") {
+            res.push(SettingsStorage::DefaultValue(model, range));
+        }
+        if let Some((group, _)) = string_kwarg("implied_group") {
+            res.push(SettingsStorage::ImpliedGroup(group, string_kwarg("group").map(|(group, _)| group)));
+        }
+        res
+    }
+
+    /* Name of the field holding the default value on the default_model */
+    pub fn default_field_name(settings_field: &str) -> &str {
+        settings_field.strip_prefix("default_").unwrap_or(settings_field)
+    }
+
+    /* Find the field of the default_model holding the default value of a settings field. None if the model or the field
+    is unknown */
+    pub fn find_default_field(session: &mut SessionInfo, model_name: &str, settings_field: &str, from_module: Option<Rc<RefCell<Symbol>>>) -> Option<Rc<RefCell<Symbol>>> {
+        let model = session.sync_odoo.models.get(model_name).cloned()?;
+        let class = model.borrow().get_main_symbols(session, from_module.clone(), &mut None).first().cloned()?;
+        let field_name = S!(SettingsStorage::default_field_name(settings_field));
+        let (symbols, _) = class.borrow().get_member_symbol(session, &field_name, from_module, false, false);
+        let field = symbols.first()?.clone();
+        PythonValidator::get_field_info(session, &field)?;
+        Some(field)
+    }
+}
+
+/* Return the keys of the system parameters created by a data file, with a record of ir.config_parameter or a call to set_param */
+pub fn find_xml_config_parameters(content: &str) -> Vec<String> {
+    let content = strip_comments(content);
+    let mut res = vec![];
+    for record in RECORD_RE.captures_iter(&content) {
+        if !attribute(&record[1], "model").is_some_and(|model| model.value == CONFIG_PARAMETER_MODEL) {
+            continue;
+        }
+        let key = record.get(2).into_iter()
+            .flat_map(|body| FIELD_RE.captures_iter(body.as_str()))
+            .find(|field| attribute(&field[1], "name").is_some_and(|name| name.value == "key"))
+            .and_then(|field| field_text(&field));
+        if let Some(key) = key {
+            res.push(key.value);
+        }
+    }
+    for call in SET_PARAM_RE.captures_iter(&content) {
+        res.push(call[1].to_string());
+    }
+    res
+}
+
+/* Keys of the known system parameters: created by the data files of the modules, or saved by the fields of the settings */
+pub fn collect_config_parameter_keys(session: &mut SessionInfo) -> Vec<String> {
+    let mut keys = vec![];
+    for module in session.sync_odoo.modules.values() {
+        if let Some(module) = module.upgrade() {
+            keys.extend(module.borrow().as_module_package().config_parameters.iter().cloned());
+        }
+    }
+    if let Some(model) = session.sync_odoo.models.get(SETTINGS_MODEL).cloned() {
+        let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+        for class in classes.iter() {
+            let fields: Vec<Rc<RefCell<Symbol>>> = class.borrow().all_symbols().filter(|symbol| symbol.borrow().typ() == SymType::VARIABLE).collect();
+            for field in fields.iter() {
+                let Some(keywords) = AstUtils::get_field_call_keywords(session, field) else {
+                    continue;
+                };
+                for storage in SettingsStorage::from_keywords(&keywords) {
+                    if let SettingsStorage::ConfigParameter(key, _) = storage {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+    }
+    keys.sort();
+    keys.dedup();
+    keys
+}
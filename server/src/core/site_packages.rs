@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::SystemTime;
+
+/* Interval between two polls of the directories of sys.path */
+pub const SITE_PACKAGES_POLL_INTERVAL: u64 = 5000;
+
+/* Packages installed in the directories of sys.path, like the site-packages of a virtual environment. These directories
+are outside of the workspace, so the client doesn't watch them: they are polled to detect the packages installed, upgraded
+or removed with pip without restarting the server */
+#[derive(Debug, Default)]
+pub struct SitePackagesWatcher {
+    snapshots: HashMap<String, HashMap<String, Option<SystemTime>>>, //directory -> importable name -> modification time
+}
+
+impl SitePackagesWatcher {
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /* Read the given directories, and return the importable names (dir, name) that appeared, disappeared or were modified
+    since the last poll. The first poll of a directory only takes a snapshot */
+    pub fn poll(&mut self, dirs: &[String]) -> Vec<(String, String)> {
+        let mut changes = vec![];
+        self.snapshots.retain(|dir, _| dirs.contains(dir));
+        for dir in dirs.iter() {
+            let snapshot = SitePackagesWatcher::read_dir(dir);
+            if let Some(previous) = self.snapshots.get(dir) {
+                for (name, time) in snapshot.iter() {
+                    if previous.get(name) != Some(time) {
+                        changes.push((dir.clone(), name.clone()));
+                    }
+                }
+                for name in previous.keys() {
+                    if !snapshot.contains_key(name) {
+                        changes.push((dir.clone(), name.clone()));
+                    }
+                }
+            }
+            self.snapshots.insert(dir.clone(), snapshot);
+        }
+        changes.sort();
+        changes.dedup();
+        changes
+    }
+
+    fn read_dir(dir: &String) -> HashMap<String, Option<SystemTime>> {
+        let mut res = HashMap::new();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return res;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().is_ok_and(|typ| typ.is_dir());
+            if let Some(name) = SitePackagesWatcher::importable_name(&file_name, is_dir) {
+                let time = entry.metadata().ok().and_then(|metadata| metadata.modified().ok());
+                //a module can be given by multiple files (foo.py and foo.pyi): keep the last modification
+                let current = res.entry(name).or_insert(time);
+                if time > *current {
+                    *current = time;
+                }
+            }
+        }
+        res
+    }
+
+    /* Name under which an entry of the directory can be imported: packages, python files and compiled extensions
+    (foo.cpython-311-x86_64-linux-gnu.so). The metadata of the distributions (.dist-info, .pth) can't be imported */
+    pub fn importable_name(file_name: &str, is_dir: bool) -> Option<String> {
+        let name = if is_dir {
+            file_name
+        } else {
+            let (name, _) = file_name.split_once('.')?;
+            let extension = file_name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+            if !["py", "pyi", "so", "pyd"].contains(&extension) {
+                return None;
+            }
+            name
+        };
+        if name.is_empty() || name == "__pycache__" || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        Some(name.to_string())
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+
+/* Common english words and the vocabulary of the labels and helps of Odoo fields. The list is not a full dictionary: a word is
+only reported if it is not known AND is one edit away from a known word, so a missing word is silent instead of reported.
+The list is maintained in this repository and published under its license (see the header of spell_check_words.txt) */
+static BUNDLED_WORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    include_str!("spell_check_words.txt").lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+/* Words shorter than that are too often abbreviations or codes to be checked */
+const MIN_WORD_LEN: usize = 4;
+
+/* Spell check of the user-facing strings of the modules (string=, help=, selection labels), against the bundled
+wordlist and the dictionary of the project (dictionary key of odools.toml) */
+pub struct SpellChecker<'a> {
+    dictionary: &'a HashSet<String>, //lowercase words, see Config::dictionary
+}
+
+impl<'a> SpellChecker<'a> {
+
+    pub fn new(dictionary: &'a HashSet<String>) -> Self {
+        Self {
+            dictionary,
+        }
+    }
+
+    /* Return the misspelled words of the text, with their byte offset in the text and the known word they are probably a typo of */
+    pub fn check(&self, text: &str) -> Vec<(usize, String, String)> {
+        let mut res = vec![];
+        for token in text.split(|c: char| !(c.is_alphabetic() || c == '\'')) {
+            let token = token.trim_matches('\'');
+            let offset = token.as_ptr() as usize - text.as_ptr() as usize;
+            let token = token.strip_suffix("'s").unwrap_or(token);
+            if token.len() < MIN_WORD_LEN || !token.chars().all(|c| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            //acronyms and CamelCase names are not words
+            if token.chars().skip(1).any(|c| c.is_ascii_uppercase()) {
+                continue;
+            }
+            let word = token.to_lowercase();
+            if self.is_known(&word) {
+                continue;
+            }
+            if let Some(suggestion) = self.suggest(&word) {
+                res.push((offset, token.to_string(), suggestion));
+            }
+        }
+        res
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        BUNDLED_WORDS.contains(word) || self.dictionary.contains(word)
+    }
+
+    /* The word, or its stem for the usual inflections (plurals, past tense, gerund, adverbs) */
+    fn is_known(&self, word: &str) -> bool {
+        if self.contains(word) {
+            return true;
+        }
+        let mut stems = vec![];
+        if let Some(stem) = word.strip_suffix("ies") {
+            stems.push(format!("{}y", stem));
+        }
+        if let Some(stem) = word.strip_suffix("ied") {
+            stems.push(format!("{}y", stem));
+        }
+        for suffix in ["s", "es", "d", "ed", "ing", "ly", "er", "ers", "able"] {
+            if let Some(stem) = word.strip_suffix(suffix) {
+                stems.push(stem.to_string());
+                if suffix == "ing" || suffix == "able" || suffix == "er" || suffix == "ers" {
+                    stems.push(format!("{}e", stem)); //invoicing, closable, user
+                }
+                //doubled consonant: shipped, planning
+                let bytes = stem.as_bytes();
+                if bytes.len() > 2 && bytes[bytes.len() - 1] == bytes[bytes.len() - 2] {
+                    stems.push(stem[..stem.len() - 1].to_string());
+                }
+            }
+        }
+        stems.iter().any(|stem| stem.len() >= 2 && self.contains(stem))
+    }
+
+    /* Return the first known word at one edit of the word. Missing letters are the most usual typos, so insertions are
+    tried before deletions and substitutions */
+    fn suggest(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut candidates = vec![];
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut c = chars.clone();
+            c.swap(i, i + 1);
+            candidates.push(c);
+        }
+        for i in 0..=chars.len() {
+            for letter in 'a'..='z' {
+                let mut c = chars.clone();
+                c.insert(i, letter);
+                candidates.push(c);
+            }
+        }
+        for i in 0..chars.len() {
+            let mut c = chars.clone();
+            c.remove(i);
+            candidates.push(c);
+        }
+        for (i, current) in chars.iter().enumerate() {
+            for letter in 'a'..='z' {
+                if letter != *current {
+                    let mut c = chars.clone();
+                    c[i] = letter;
+                    candidates.push(c);
+                }
+            }
+        }
+        candidates.into_iter().map(|c| c.into_iter().collect::<String>()).find(|candidate| candidate.len() >= MIN_WORD_LEN && self.is_known(candidate))
+    }
+}
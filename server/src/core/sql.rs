@@ -0,0 +1,201 @@
+use ruff_python_ast::{Expr, ExprCall, FStringElement, FStringPart, Operator, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::features::ast_utils::AstUtils;
+
+/* Words that can follow a table name in a FROM clause, and are not its alias */
+const CLAUSE_KEYWORDS: [&str; 24] = ["WHERE", "JOIN", "LEFT", "RIGHT", "INNER", "OUTER", "FULL", "CROSS", "NATURAL", "LATERAL", "ON", "USING",
+    "GROUP", "ORDER", "HAVING", "LIMIT", "OFFSET", "UNION", "EXCEPT", "INTERSECT", "WINDOW", "SET", "RETURNING", "FOR"];
+
+/* True if the call runs a query on a cursor, like self.env.cr.execute(query) or self._cr.execute(query) */
+pub fn is_cursor_execute(call: &ExprCall) -> bool {
+    let Expr::Attribute(method) = call.func.as_ref() else {
+        return false;
+    };
+    if !["execute", "executemany"].contains(&method.attr.as_str()) {
+        return false;
+    }
+    match method.value.as_ref() {
+        Expr::Name(name) => ["cr", "_cr", "cursor"].contains(&name.id.as_str()),
+        Expr::Attribute(attr) => ["cr", "_cr"].contains(&attr.attr.as_str()),
+        _ => false
+    }
+}
+
+/* How values are interpolated in the text of a query, if some of them may come from the user: "an f-string", "% formatting",
+"str.format()" or "string concatenation". Literals, constants (UPPER_CASE names) and private attributes like self._table
+can't be given as parameters of a query, so they are accepted */
+pub fn unsafe_interpolation(query: &Expr) -> Option<&'static str> {
+    match query {
+        Expr::FString(fstring) => {
+            let unsafe_element = fstring.value.iter().any(|part| match part {
+                FStringPart::FString(fstring) => fstring.elements.iter().any(|element| match element {
+                    FStringElement::Expression(element) => !is_safe_value(&element.expression),
+                    FStringElement::Literal(_) => false,
+                }),
+                FStringPart::Literal(_) => false,
+            });
+            unsafe_element.then_some("an f-string")
+        },
+        Expr::BinOp(bin_op) if bin_op.op == Operator::Mod && bin_op.left.is_string_literal_expr() => {
+            let unsafe_value = match bin_op.right.as_ref() {
+                Expr::Tuple(tuple) => tuple.elts.iter().any(|elt| !is_safe_value(elt)),
+                Expr::Dict(dict) => dict.items.iter().any(|item| !is_safe_value(&item.value)),
+                value => !is_safe_value(value),
+            };
+            unsafe_value.then_some("% formatting")
+        },
+        Expr::BinOp(bin_op) if bin_op.op == Operator::Add && (is_text(&bin_op.left) || is_text(&bin_op.right)) => {
+            let unsafe_value = [bin_op.left.as_ref(), bin_op.right.as_ref()].into_iter().any(|side| {
+                !is_safe_value(side) && unsafe_interpolation(side).map_or(!is_text(side), |_| true)
+            });
+            unsafe_value.then_some("string concatenation")
+        },
+        Expr::Call(call) => {
+            let Expr::Attribute(method) = call.func.as_ref() else {
+                return None;
+            };
+            if method.attr.as_str() != "format" || !method.value.is_string_literal_expr() {
+                return None;
+            }
+            let unsafe_value = call.arguments.args.iter().any(|arg| !is_safe_value(arg))
+                || call.arguments.keywords.iter().any(|keyword| !is_safe_value(&keyword.value));
+            unsafe_value.then_some("str.format()")
+        },
+        _ => None
+    }
+}
+
+fn is_text(expr: &Expr) -> bool {
+    matches!(expr, Expr::StringLiteral(_) | Expr::FString(_)) || expr.as_bin_op_expr().is_some_and(|bin_op| is_text(&bin_op.left) || is_text(&bin_op.right))
+}
+
+fn is_safe_value(expr: &Expr) -> bool {
+    match expr {
+        Expr::StringLiteral(_) | Expr::NumberLiteral(_) | Expr::BooleanLiteral(_) | Expr::NoneLiteral(_) => true,
+        Expr::Name(name) => name.id.chars().all(|c| !c.is_lowercase()),
+        Expr::Attribute(attr) => attr.attr.starts_with('_'),
+        _ => false
+    }
+}
+
+/* Text of a query, with "?" in place of the interpolated values. None if it is not built from literals */
+pub fn query_text(query: &Expr) -> Option<String> {
+    match query {
+        Expr::StringLiteral(string) => Some(string.value.to_string()),
+        Expr::FString(fstring) => {
+            let mut text = String::new();
+            for part in fstring.value.iter() {
+                match part {
+                    FStringPart::Literal(literal) => text += &literal.value,
+                    FStringPart::FString(fstring) => {
+                        for element in fstring.elements.iter() {
+                            match element {
+                                FStringElement::Literal(literal) => text += &literal.value,
+                                FStringElement::Expression(_) => text += "?",
+                            }
+                        }
+                    }
+                }
+            }
+            Some(text)
+        },
+        Expr::BinOp(bin_op) if bin_op.op == Operator::Mod => query_text(&bin_op.left),
+        Expr::BinOp(bin_op) if bin_op.op == Operator::Add => {
+            let left = query_text(&bin_op.left).unwrap_or(String::from("?"));
+            let right = query_text(&bin_op.right).unwrap_or(String::from("?"));
+            Some(left + &right)
+        },
+        Expr::Call(call) => {
+            let method = call.func.as_attribute_expr()?;
+            if method.attr.as_str() != "format" {
+                return None;
+            }
+            query_text(&method.value)
+        },
+        _ => None
+    }
+}
+
+/* Tables read or written by a query: the names following FROM (with its list of tables), JOIN, UPDATE, INTO and TABLE */
+pub fn query_tables(query: &str) -> Vec<String> {
+    let tokens = tokenize(query);
+    let mut tables: Vec<String> = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        let keyword = tokens[i].to_uppercase();
+        i += 1;
+        if !["FROM", "JOIN", "UPDATE", "INTO", "TABLE"].contains(&keyword.as_str()) {
+            continue;
+        }
+        //EXTRACT(field FROM date) is not a table
+        if keyword == "FROM" && i >= 3 && tokens[i - 3] == "(" {
+            continue;
+        }
+        if tokens.get(i).is_some_and(|token| token.eq_ignore_ascii_case("ONLY")) {
+            i += 1;
+        }
+        while let Some(table) = tokens.get(i).filter(|token| is_identifier(token)) {
+            let table = table.trim_matches('"').rsplit('.').next().unwrap_or_default().trim_matches('"').to_string();
+            if !tables.contains(&table) {
+                tables.push(table);
+            }
+            i += 1;
+            if keyword != "FROM" {
+                break;
+            }
+            //FROM a, b AS c, d
+            if tokens.get(i).is_some_and(|token| token.eq_ignore_ascii_case("AS")) {
+                i += 1;
+            }
+            if tokens.get(i).is_some_and(|token| is_identifier(token) && !CLAUSE_KEYWORDS.contains(&token.to_uppercase().as_str())) {
+                i += 1;
+            }
+            if tokens.get(i).is_some_and(|token| token == ",") {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+    }
+    tables
+}
+
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        if quoted {
+            current.push(c);
+            quoted = c != '"';
+        } else if c.is_alphanumeric() || ['_', '.', '%', '"'].contains(&c) {
+            quoted = c == '"';
+            current.push(c);
+        } else {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn is_identifier(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '"')
+}
+
+/* The query given to cr.execute at this offset, with the range of the argument */
+pub fn find_query_at(ast: &Vec<Stmt>, offset: u32) -> Option<(String, TextRange)> {
+    AstUtils::collect_calls(ast).into_iter()
+        .filter(|call| is_cursor_execute(call))
+        .filter_map(|call| call.arguments.find_argument("query", 0))
+        .find(|query| query.range().start().to_u32() <= offset && offset <= query.range().end().to_u32())
+        .and_then(|query| Some((query_text(query)?, query.range())))
+}
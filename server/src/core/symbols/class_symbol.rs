@@ -16,9 +16,11 @@ pub struct ClassSymbol {
     pub is_external: bool,
     pub doc_string: Option<String>,
     pub bases: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
+    pub ordered_bases: Vec<Weak<RefCell<Symbol>>>, //the bases, in the order of the class definition, for the mro of the models
     pub ast_indexes: Vec<u16>, //list of index to reach the corresponding ast node from file ast
     pub weak_self: Option<Weak<RefCell<Symbol>>>,
     pub parent: Option<Weak<RefCell<Symbol>>>,
+    pub module: Option<Weak<RefCell<Symbol>>>, //module declaring the class. None outside of odoo modules
     pub range: TextRange,
     pub body_range: TextRange,
     pub _model: Option<ModelData>,
@@ -39,6 +41,7 @@ impl ClassSymbol {
             is_external,
             weak_self: None,
             parent: None,
+            module: None,
             range,
             body_range: TextRange::new(body_start, range.end()),
             ast_indexes: vec![],
@@ -47,6 +50,7 @@ impl ClassSymbol {
             symbols: HashMap::new(),
             ext_symbols: HashMap::new(),
             bases: PtrWeakHashSet::new(),
+            ordered_bases: vec![],
             _model: None,
         };
         res._init_symbol_mgr();
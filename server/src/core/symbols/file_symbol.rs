@@ -1,3 +1,4 @@
+use ruff_text_size::TextRange;
 use weak_table::PtrWeakHashSet;
 
 use crate::{constants::{BuildStatus, BuildSteps}, core::model::Model};
@@ -18,6 +19,7 @@ pub struct FileSymbol {
     pub validation_status: BuildStatus,
     pub not_found_paths: Vec<(BuildSteps, Vec<String>)>,
     pub in_workspace: bool,
+    pub dead_branches: Vec<TextRange>, //bodies of branches that are never executed with the current version of Odoo
     pub model_dependencies: PtrWeakHashSet<Weak<RefCell<Model>>>, //always on validation level, as odoo step is always required
     pub dependencies: [Vec<PtrWeakHashSet<Weak<RefCell<Symbol>>>>; 4],
     pub dependents: [Vec<PtrWeakHashSet<Weak<RefCell<Symbol>>>>; 3],
@@ -44,6 +46,7 @@ impl FileSymbol {
             validation_status: BuildStatus::PENDING,
             not_found_paths: vec![],
             in_workspace: false,
+            dead_branches: vec![],
             sections: vec![],
             symbols: HashMap::new(),
             ext_symbols: HashMap::new(),
@@ -3,7 +3,7 @@ use std::{cell::RefCell, collections::HashMap, rc::{Rc, Weak}};
 use lsp_types::Diagnostic;
 use ruff_text_size::{TextRange, TextSize};
 
-use crate::{constants::{BuildStatus, BuildSteps}, core::evaluation::{Context, Evaluation}, threads::SessionInfo};
+use crate::{constants::{BuildStatus, BuildSteps, SymType}, core::{docstring::Docstring, evaluation::{Context, Evaluation}, python_validator::PythonValidator}, threads::SessionInfo};
 
 use super::{symbol::Symbol, symbol_mgr::{SectionRange, SymbolMgr}};
 
@@ -22,7 +22,11 @@ pub struct FunctionSymbol {
     pub is_external: bool,
     pub is_static: bool,
     pub is_property: bool,
+    pub is_forwarding: bool, //only returns the call of the same method of super() with its parameters. See python_utils::is_forwarding_override
+    pub is_create_multi: bool, //decorated with @api.model_create_multi: the first parameter after self is a list of values
+    pub is_comprehension: bool, //scope of the variables of a comprehension, like <listcomp>. It has no ast node and is never called
     pub doc_string: Option<String>,
+    pub doc: Option<Docstring>, //parsed doc_string, if it has params, returns or raises sections
     pub ast_indexes: Vec<u16>, //list of index to reach the corresponding ast node from file ast
     pub diagnostics: HashMap<BuildSteps, Vec<Diagnostic>>, //only temporary used for CLASS and FUNCTION to be collected like others are stored on FileInfo
     pub evaluations: Vec<Evaluation>, //Vec, because sometimes a single allocation can be ambiguous, like ''' a = "5" if X else 5 '''
@@ -57,9 +61,13 @@ impl FunctionSymbol {
             body_range: TextRange::new(body_start, range.end()),
             is_static: false,
             is_property: false,
+            is_forwarding: false,
+            is_create_multi: false,
+            is_comprehension: false,
             diagnostics: HashMap::new(),
             ast_indexes: vec![],
             doc_string: None,
+            doc: None,
             evaluations: vec![],
             arch_status: BuildStatus::PENDING,
             arch_eval_status: BuildStatus::PENDING,
@@ -105,6 +113,49 @@ impl FunctionSymbol {
         }
     }
 
+    /* Method overridden by a method of a class: the first method with the same name in the other classes of the model, the inherited
+    models and the python bases. None outside of classes */
+    pub fn get_overridden_method(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) -> Option<Rc<RefCell<Symbol>>> {
+        let class = function.borrow().parent()?.upgrade()?;
+        if class.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        let name = function.borrow().name().clone();
+        let (members, _) = class.borrow().get_member_symbol(session, &name, None, false, true);
+        members.into_iter().find(|member| !Rc::ptr_eq(member, function) && member.borrow().typ() == SymType::FUNCTION)
+    }
+
+    /* Method giving its signature and its return type to a forwarding override: the first overridden method that is not forwarding too */
+    pub fn get_forwarded_method(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) -> Option<Rc<RefCell<Symbol>>> {
+        let mut method = function.clone();
+        let mut visited = vec![function.clone()];
+        while method.borrow().as_func().is_forwarding {
+            let overridden = FunctionSymbol::get_overridden_method(session, &method)?;
+            if visited.iter().any(|visited| Rc::ptr_eq(visited, &overridden)) {
+                return None;
+            }
+            visited.push(overridden.clone());
+            method = overridden;
+        }
+        (!Rc::ptr_eq(&method, function)).then_some(method)
+    }
+
+    /* Return evaluations of the method forwarded by a forwarding override, computed if they are not yet */
+    pub fn get_forwarded_evaluations(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) -> Vec<Evaluation> {
+        let Some(forwarded) = FunctionSymbol::get_forwarded_method(session, function) else {
+            return vec![];
+        };
+        let is_pending = forwarded.borrow().as_func().evaluations.is_empty()
+            && forwarded.borrow().build_status(BuildSteps::VALIDATION) == BuildStatus::PENDING
+            && forwarded.borrow().parent_file_or_function().and_then(|parent| parent.upgrade()).is_some_and(|parent| parent.borrow().build_status(BuildSteps::ODOO) == BuildStatus::DONE);
+        if is_pending {
+            let mut validator = PythonValidator::new(forwarded.clone());
+            validator.validate(session);
+        }
+        let evaluations = forwarded.borrow().as_func().evaluations.clone();
+        evaluations
+    }
+
     pub fn can_be_in_class(&self) -> bool {
         for arg in self.args.iter() {
             if !arg.is_kwargs && !arg.is_args { //is_args is technically false, as func(*self) is possible, but reaaaaally weird, so let's assume nobody do that
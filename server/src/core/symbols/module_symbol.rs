@@ -6,16 +6,22 @@ use weak_table::PtrWeakHashSet;
 use std::collections::{HashMap, HashSet};
 
 use crate::constants::*;
+use crate::core::access_rights::{parse_access_csv, parse_access_xml, ACCESS_MODEL};
+use crate::core::data_order::{find_forward_references, parse_data_file, DataFileIds};
 use crate::core::file_mgr::FileInfo;
 use crate::core::import_resolver::find_module;
+use crate::core::manifest_assets::{list_static_files, AssetPath};
 use crate::core::model::Model;
 use crate::core::odoo::SyncOdoo;
+use crate::core::reports::XmlReportIndex;
+use crate::core::settings::find_xml_config_parameters;
 use crate::core::symbols::symbol::Symbol;
 use crate::constants::EXTENSION_NAME;
 use crate::core::symbols::symbol_mgr::SymbolMgr;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
 use crate::S;
+use std::fs;
 use std::path::PathBuf;
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
@@ -31,10 +37,16 @@ pub struct ModuleSymbol {
     pub is_external: bool,
     root_path: String,
     loaded: bool,
-    module_name: String,
+    pub module_name: String,
+    pub summary: String,
+    pub version: String,
     pub dir_name: String,
     depends: Vec<String>,
-    data: Vec<String>, // TODO
+    data: Vec<(String, TextRange)>, //files of the 'data' key, with the range of their path in the manifest
+    pub config_parameters: Vec<String>, //keys of the system parameters created by the data files
+    pub access_models: HashSet<String>, //models given access to by the ir.model.access records, like model_sale_order
+    pub reports: XmlReportIndex, //report actions, templates and paper formats of the data files
+    pub assets: HashMap<String, Vec<AssetPath>>, //bundle name => paths of the 'assets' key of the manifest
     pub module_symbols: HashMap<String, Rc<RefCell<Symbol>>>,
     pub arch_status: BuildStatus,
     pub arch_eval_status: BuildStatus,
@@ -44,6 +56,7 @@ pub struct ModuleSymbol {
     pub parent: Option<Weak<RefCell<Symbol>>>,
     pub not_found_paths: Vec<(BuildSteps, Vec<String>)>,
     pub in_workspace: bool,
+    pub dead_branches: Vec<TextRange>, //bodies of branches that are never executed with the current version of Odoo
     pub model_dependencies: PtrWeakHashSet<Weak<RefCell<Model>>>, //always on validation level, as odoo step is always required
     pub dependencies: [Vec<PtrWeakHashSet<Weak<RefCell<Symbol>>>>; 4],
     pub dependents: [Vec<PtrWeakHashSet<Weak<RefCell<Symbol>>>>; 3],
@@ -65,12 +78,19 @@ impl ModuleSymbol {
             is_external,
             not_found_paths: vec![],
             in_workspace: false,
+            dead_branches: vec![],
             root_path: dir_path.sanitize(),
             loaded: false,
             module_name: String::new(),
+            summary: String::new(),
+            version: String::new(),
             dir_name: String::new(),
             depends: vec!("base".to_string()),
             data: Vec::new(),
+            config_parameters: Vec::new(),
+            access_models: HashSet::new(),
+            reports: XmlReportIndex::default(),
+            assets: HashMap::new(),
             weak_self: None,
             parent: None,
             module_symbols: HashMap::new(),
@@ -117,10 +137,11 @@ impl ModuleSymbol {
         };
         module._init_symbol_mgr();
         info!("building new module: {:?}", dir_path.sanitize());
-        if dir_path.components().last().unwrap().as_os_str().to_str().unwrap() == "base" {
+        //the name comes from the path the module is reached by, that can be a symlink to a directory with another name
+        if module.name == "base" {
             module.depends.clear();
         }
-        module.dir_name = dir_path.with_extension("").components().last().unwrap().as_os_str().to_str().unwrap().to_string();
+        module.dir_name = module.name.clone();
         let manifest_path = dir_path.join("__manifest__.py");
         if !manifest_path.exists() {
             return None
@@ -214,6 +235,14 @@ impl ModuleSymbol {
                                 } else {
                                     self.module_name = value.as_string_literal_expr().unwrap().value.to_string();
                                 }
+                            } else if key_str == "summary" {
+                                if let Some(summary) = value.as_string_literal_expr() {
+                                    self.summary = summary.value.to_string();
+                                }
+                            } else if key_str == "version" {
+                                if let Some(version) = value.as_string_literal_expr() {
+                                    self.version = version.value.to_string();
+                                }
                             } else if key_str == "depends" {
                                 if !value.is_list_expr() {
                                     res.push(self._create_diagnostic_for_manifest_key("The depends value should be a list", S!("OLS30204"), &key_literal.range));
@@ -239,10 +268,21 @@ impl ModuleSymbol {
                                         if !data.is_literal_expr() {
                                             res.push(self._create_diagnostic_for_manifest_key("The data key should be a list of strings", S!("OLS30208"), &data.range()));
                                         } else {
-                                            self.data.push(data.as_string_literal_expr().unwrap().value.to_string());
+                                            let data_path = data.as_string_literal_expr().unwrap().value.to_string();
+                                            self._check_manifest_file("Data", &data_path, &data.range(), &mut res);
+                                            self.data.push((data_path, data.range()));
                                         }
                                     }
                                 }
+                            } else if key_str == "demo" {
+                                //the demo files are only loaded with the demo data, but must exist as well
+                                if let Some(demo) = value.as_list_expr() {
+                                    for demo_file in demo.elts.iter().filter_map(|demo_file| demo_file.as_string_literal_expr()) {
+                                        self._check_manifest_file("Demo", &demo_file.value.to_string(), &demo_file.range, &mut res);
+                                    }
+                                }
+                            } else if key_str == "assets" {
+                                self._load_assets(value, &key_literal.range, &mut res);
                             } else if key_str == "active" {
                                 res.push(Diagnostic::new(
                                     Range::new(Position::new(key_literal.range.start().to_u32(), 0), Position::new(key_literal.range.end().to_u32(), 0)),
@@ -279,6 +319,82 @@ impl ModuleSymbol {
         res
     }
 
+    /* Index the bundles of the 'assets' key, and check that the files of the module they reference exist.
+    Paths of other modules are not checked, as these modules may not be loaded yet */
+    fn _load_assets(&mut self, value: &Expr, key_range: &TextRange, res: &mut Vec<Diagnostic>) {
+        let Some(bundles) = value.as_dict_expr() else {
+            res.push(self._create_diagnostic_for_manifest_key("The assets value should be a dict", S!("OLS30211"), key_range));
+            return;
+        };
+        for item in bundles.items.iter() {
+            let (Some(Expr::StringLiteral(bundle)), Some(paths)) = (item.key.as_ref(), item.value.as_list_expr()) else {
+                res.push(self._create_diagnostic_for_manifest_key("The assets should be a dict of lists, with bundle names as keys", S!("OLS30211"), &item.value.range()));
+                continue;
+            };
+            let mut bundle_paths = vec![];
+            for entry in paths.elts.iter() {
+                for asset in AssetPath::from_entry(entry) {
+                    if !asset.removed && !asset.is_url() && asset.module_name() == Some(self.dir_name.as_str()) && asset.resolve(&self.path).is_empty() {
+                        res.push(self._create_warning_for_asset(&asset));
+                    }
+                    bundle_paths.push(asset);
+                }
+            }
+            self.assets.entry(S!(bundle.value.to_str())).or_default().extend(bundle_paths);
+        }
+        let included: Vec<&AssetPath> = self.assets.values().flatten().filter(|asset| !asset.removed).collect();
+        let unused: Vec<String> = list_static_files(&self.path, &self.dir_name).into_iter()
+            .filter(|file| !included.iter().any(|asset| asset.matches(file))).collect();
+        if !unused.is_empty() {
+            let mut message = format!("{} file(s) of static/src are not referenced by any bundle: {}", unused.len(), unused.iter().take(5).cloned().collect::<Vec<String>>().join(", "));
+            if unused.len() > 5 {
+                message += ", ...";
+            }
+            res.push(Diagnostic::new(
+                Range::new(Position::new(key_range.start().to_u32(), 0), Position::new(key_range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20203"))),
+                Some(EXTENSION_NAME.to_string()),
+                message,
+                None,
+                None,
+            ));
+        }
+    }
+
+    fn _create_warning_for_asset(&self, asset: &AssetPath) -> Diagnostic {
+        let message = if asset.is_glob() {
+            format!("No file matches the asset {}", asset.path)
+        } else {
+            format!("Asset {} not found", asset.path)
+        };
+        Diagnostic::new(
+            Range::new(Position::new(asset.range.start().to_u32(), 0), Position::new(asset.range.end().to_u32(), 0)),
+            Some(DiagnosticSeverity::WARNING),
+            Some(NumberOrString::String(S!("OLS20202"))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None,
+        )
+    }
+
+    /* Report a file of the data or demo key of the manifest that doesn't exist in the module */
+    fn _check_manifest_file(&self, kind: &str, path: &String, range: &TextRange, res: &mut Vec<Diagnostic>) {
+        if PathBuf::from(&self.root_path).join(path).is_file() {
+            return;
+        }
+        res.push(Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(DiagnosticSeverity::WARNING),
+            Some(NumberOrString::String(S!("OLS20204"))),
+            Some(EXTENSION_NAME.to_string()),
+            format!("{} file {} not found", kind, path),
+            None,
+            None,
+        ));
+    }
+
     fn _create_diagnostic_for_manifest_key(&self, text: &str, code: String, range: &TextRange) -> Diagnostic {
         return Diagnostic::new(
             Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
@@ -328,8 +444,48 @@ impl ModuleSymbol {
         (diagnostics, loaded)
     }
 
-    fn _load_data(_symbol: Rc<RefCell<Symbol>>, _odoo: &mut SyncOdoo) -> Vec<Diagnostic> {
-        vec![]
+    fn _load_data(symbol: Rc<RefCell<Symbol>>, odoo: &mut SyncOdoo) -> Vec<Diagnostic> {
+        let mut symbol = symbol.borrow_mut();
+        let module = symbol.as_module_package_mut();
+        module.config_parameters.clear();
+        module.access_models.clear();
+        module.reports = XmlReportIndex::default();
+        let mut data_ids = vec![];
+        for (data, _) in module.data.iter() {
+            let path = PathBuf::from(&module.root_path).join(data);
+            let Ok(content) = fs::read_to_string(&path) else {
+                data_ids.push(DataFileIds::default());
+                continue;
+            };
+            data_ids.push(parse_data_file(&module.dir_name, &path, &content));
+            if data.ends_with(".csv") {
+                if path.file_stem().is_some_and(|stem| stem == ACCESS_MODEL) {
+                    module.access_models.extend(parse_access_csv(&content));
+                }
+                continue;
+            }
+            if !data.ends_with(".xml") {
+                continue;
+            }
+            module.config_parameters.extend(find_xml_config_parameters(&content));
+            module.access_models.extend(parse_access_xml(&content));
+            module.reports.extend(XmlReportIndex::parse(&module.dir_name, &path.sanitize(), &content));
+            odoo.xml_fields.index_file(&path.sanitize(), &content);
+        }
+        let mut diagnostics = vec![];
+        for reference in find_forward_references(&data_ids).iter() {
+            let (data, range) = &module.data[reference.file];
+            diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20205"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("{} uses {}.{}, which is only created by {}, loaded after it", data, module.dir_name, reference.xml_id, module.data[reference.declared_in].0),
+                None,
+                None,
+            ));
+        }
+        diagnostics
     }
 
     fn _load_arch(symbol: Rc<RefCell<Symbol>>, session: &mut SessionInfo) -> Vec<Diagnostic> {
@@ -345,6 +501,10 @@ impl ModuleSymbol {
         vec![]
     }
 
+    pub fn depends(&self) -> &Vec<String> {
+        &self.depends
+    }
+
     pub fn is_in_deps(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>, dir_name: &String, acc: &mut Option<HashSet<String>>) -> bool {
         if symbol.borrow().as_module_package().dir_name == *dir_name || symbol.borrow().as_module_package().depends.contains(dir_name) {
             return true;
@@ -1,3 +1,4 @@
+use ruff_text_size::TextRange;
 use weak_table::PtrWeakHashSet;
 
 use crate::{constants::{BuildStatus, BuildSteps}, core::model::Model, threads::SessionInfo, S};
@@ -110,6 +111,7 @@ pub struct PythonPackageSymbol {
     pub validation_status: BuildStatus,
     pub not_found_paths: Vec<(BuildSteps, Vec<String>)>,
     pub in_workspace: bool,
+    pub dead_branches: Vec<TextRange>, //bodies of branches that are never executed with the current version of Odoo
     pub module_symbols: HashMap<String, Rc<RefCell<Symbol>>>,
     pub model_dependencies: PtrWeakHashSet<Weak<RefCell<Model>>>, //always on validation level, as odoo step is always required
     pub dependencies: [Vec<PtrWeakHashSet<Weak<RefCell<Symbol>>>>; 4],
@@ -138,6 +140,7 @@ impl PythonPackageSymbol {
             validation_status: BuildStatus::PENDING,
             not_found_paths: vec![],
             in_workspace: false,
+            dead_branches: vec![],
             module_symbols: HashMap::new(),
             sections: vec![],
             symbols: HashMap::new(),
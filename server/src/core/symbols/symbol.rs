@@ -11,7 +11,8 @@ use crate::threads::SessionInfo;
 use crate::utils::{PathSanitizer as _};
 use crate::S;
 use core::panic;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use smallvec::SmallVec;
 use weak_table::PtrWeakHashSet;
 use std::path::PathBuf;
 use std::rc::{Rc, Weak};
@@ -31,6 +32,9 @@ use super::package_symbol::PackageSymbol;
 use super::symbol_mgr::SymbolMgr;
 use super::variable_symbol::VariableSymbol;
 
+//references to the next symbols of an evaluation chain. Most variables have a single evaluation: they are kept inline
+pub type SymbolRefs = SmallVec<[(Weak<RefCell<Symbol>>, bool); 2]>;
+
 #[derive(Debug)]
 pub enum Symbol {
     Root(RootSymbol),
@@ -412,6 +416,11 @@ impl Symbol {
         }
     }
 
+    /* True for the scopes of comprehensions, that are not members of their parent and must not be listed with them */
+    pub fn is_comprehension(&self) -> bool {
+        matches!(self, Symbol::Function(f) if f.is_comprehension)
+    }
+
     pub fn is_external(&self) -> bool {
         match self {
             Symbol::Root(_) => false,
@@ -665,6 +674,46 @@ impl Symbol {
             Symbol::Variable(_) => panic!(),
         }
     }
+    fn dead_branches_mut(&mut self) -> Option<&mut Vec<TextRange>> {
+        match self {
+            Symbol::Package(PackageSymbol::Module(m)) => Some(&mut m.dead_branches),
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => Some(&mut p.dead_branches),
+            Symbol::File(f) => Some(&mut f.dead_branches),
+            _ => None
+        }
+    }
+
+    pub fn add_dead_branch(&mut self, range: TextRange) {
+        if let Some(dead_branches) = self.dead_branches_mut() {
+            dead_branches.push(range);
+        }
+    }
+
+    pub fn clear_dead_branches(&mut self) {
+        if let Some(dead_branches) = self.dead_branches_mut() {
+            dead_branches.clear();
+        }
+    }
+
+    /* Return true if the symbol is declared in a branch that is never executed with the current version of Odoo */
+    pub fn is_in_dead_branch(&self) -> bool {
+        if !matches!(self.typ(), SymType::CLASS | SymType::FUNCTION | SymType::VARIABLE) {
+            return false;
+        }
+        let Some(file) = self.get_file().and_then(|file| file.upgrade()) else {
+            return false;
+        };
+        let start = self.range().start();
+        let file = file.borrow();
+        let dead_branches = match &*file {
+            Symbol::Package(PackageSymbol::Module(m)) => &m.dead_branches,
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => &p.dead_branches,
+            Symbol::File(f) => &f.dead_branches,
+            _ => return false
+        };
+        dead_branches.iter().any(|range| range.contains(start))
+    }
+
     pub fn set_in_workspace(&mut self, in_workspace: bool) {
         match self {
             Symbol::Root(_) => panic!(),
@@ -860,17 +909,27 @@ impl Symbol {
 
     ///Given a path, create the appropriated symbol and attach it to the given parent
     pub fn create_from_path(session: &mut SessionInfo, path: &PathBuf, parent: Rc<RefCell<Symbol>>, require_module: bool) -> Option<Rc<RefCell<Symbol>>> {
+        let symbol = Symbol::create_symbol_from_path(session, path, parent, require_module)?;
+        //the external flag of the configuration wins over the one inherited from the parent, and is inherited by the content
+        if let Some(external) = session.sync_odoo.config.external_override(&path.sanitize_canonical()) {
+            symbol.borrow_mut().set_is_external(external);
+        }
+        Some(symbol)
+    }
+
+    fn create_symbol_from_path(session: &mut SessionInfo, path: &PathBuf, parent: Rc<RefCell<Symbol>>, require_module: bool) -> Option<Rc<RefCell<Symbol>>> {
         let name: String = path.with_extension("").components().last().unwrap().as_os_str().to_str().unwrap().to_string();
-        let path_str = path.sanitize();
+        let path_str = path.sanitize_canonical();
         if path_str.ends_with(".py") || path_str.ends_with(".pyi") {
             return Some(parent.borrow_mut().add_new_file(session, &name, &path_str));
         }
 
         if parent.borrow().get_tree().clone() == tree(vec!["odoo", "addons"], vec![]) && path.join("__manifest__.py").exists() {
-            let module = parent.borrow_mut().add_new_module_package(session, &name, path);
+            let module = parent.borrow_mut().add_new_module_package(session, &name, &PathBuf::from(&path_str));
             if let Some(module) = module {
                 ModuleSymbol::load_module_info(module.clone(), session, parent.clone());
                 session.sync_odoo.modules.insert(module.borrow().as_module_package().dir_name.clone(), Rc::downgrade(&module));
+                session.sync_odoo.invalidate_module_ranks();
                 return Some(module);
             } else if require_module {
                 return None;
@@ -1274,6 +1333,8 @@ impl Symbol {
             if DEBUG_MEMORY && (mut_symbol.typ() == SymType::FILE || mut_symbol.typ() == SymType::PACKAGE) {
                 info!("Unloading symbol {:?} at {:?}", mut_symbol.name(), mut_symbol.paths());
             }
+            //the tree identifies the contribution of a class to its model, and can't be computed once the class is removed
+            let class_tree = (mut_symbol.typ() == SymType::CLASS).then(|| mut_symbol.get_tree());
             //unload symbol
             let parent = mut_symbol.parent().as_ref().unwrap().upgrade().unwrap().clone();
             let mut parent = parent.borrow_mut();
@@ -1286,12 +1347,14 @@ impl Symbol {
             match *ref_to_unload.borrow_mut() {
                 Symbol::Package(PackageSymbol::Module(ref mut m)) => {
                     session.sync_odoo.modules.remove(m.dir_name.as_str());
+                    session.sync_odoo.xml_fields.remove_dir(&m.path);
+                    session.sync_odoo.invalidate_module_ranks();
                 },
                 Symbol::Class(ref mut c) => {
                     if let Some(model_data) = c._model.as_ref() {
                         let model = session.sync_odoo.models.get(&model_data.name).cloned();
-                        if let Some(model) = model {
-                            model.borrow_mut().remove_symbol(session, &ref_to_unload);
+                        if let (Some(model), Some(class_tree)) = (model, class_tree.as_ref()) {
+                            model.borrow_mut().remove_symbol(session, &ref_to_unload, class_tree);
                         }
                     }
                 },
@@ -1352,7 +1415,7 @@ impl Symbol {
             return None;
         }
         if self.parent().is_some() {
-            return self.parent().as_ref().unwrap().upgrade().unwrap().borrow_mut().get_in_parents(sym_types, stop_same_file);
+            return self.parent().as_ref().unwrap().upgrade().unwrap().borrow().get_in_parents(sym_types, stop_same_file);
         }
         return None;
     }
@@ -1429,7 +1492,7 @@ impl Symbol {
             return self.weak_self().clone();
         }
         if self.parent().is_some() {
-            return self.parent().as_ref().unwrap().upgrade().unwrap().borrow_mut().get_file();
+            return self.parent().as_ref().unwrap().upgrade().unwrap().borrow().get_file();
         }
         return None;
     }
@@ -1439,11 +1502,49 @@ impl Symbol {
             return self.weak_self().clone();
         }
         if self.parent().is_some() {
-            return self.parent().as_ref().unwrap().upgrade().unwrap().borrow_mut().parent_file_or_function();
+            return self.parent().as_ref().unwrap().upgrade().unwrap().borrow().parent_file_or_function();
         }
         return None;
     }
 
+    /* Module declaring a class or a variable, kept on the symbol. It is None if the module has been unloaded, so a class
+    still referenced by a model after an incremental rebuild is not visible from any module. Other symbols search their module
+    in their parents */
+    pub fn owning_module(&self) -> Option<Rc<RefCell<Symbol>>> {
+        match self {
+            Symbol::Class(c) => c.module.as_ref().and_then(|module| module.upgrade()),
+            Symbol::Variable(v) => v.module.as_ref().and_then(|module| module.upgrade()),
+            _ => self.find_module()
+        }
+    }
+
+    fn set_owning_module(&mut self, module: Option<Weak<RefCell<Symbol>>>) {
+        match self {
+            Symbol::Class(c) => c.module = module,
+            Symbol::Variable(v) => v.module = module,
+            _ => {}
+        }
+    }
+
+    /* Set the owning module of a class and of its members from its parents, as a rebuild can give the file to another module */
+    pub fn update_owning_module(&mut self) {
+        let module = self.find_module().as_ref().map(Rc::downgrade);
+        self.set_owning_module_of_members(&module);
+        self.set_owning_module(module);
+    }
+
+    /* Set the owning module of the classes and variables declared in the symbol, and in its classes and functions. The
+    module is looked up by the caller, once for a whole file */
+    pub fn set_owning_module_of_members(&self, module: &Option<Weak<RefCell<Symbol>>>) {
+        for member in self.all_symbols() {
+            let mut member = member.borrow_mut();
+            member.set_owning_module(module.clone());
+            if matches!(member.typ(), SymType::CLASS | SymType::FUNCTION) {
+                member.set_owning_module_of_members(module);
+            }
+        }
+    }
+
     pub fn find_module(&self) -> Option<Rc<RefCell<Symbol>>> {
         match self {
             Symbol::Package(PackageSymbol::Module(m)) => {return self.get_rc();}
@@ -1467,91 +1568,74 @@ impl Symbol {
     ====
     next_refs on the 'a' in the print will return a SymbolRef to Test and one to Object
     */
-    pub fn next_refs(session: &mut SessionInfo, symbol: &Symbol, diagnostics: &mut Vec<Diagnostic>) -> VecDeque<(Weak<RefCell<Symbol>>, bool)> {
+    pub fn next_refs(session: &mut SessionInfo, symbol: &Symbol, diagnostics: &mut Vec<Diagnostic>) -> SymbolRefs {
         match symbol {
             Symbol::Variable(v) => {
-                let mut res = VecDeque::new();
+                let mut res = SymbolRefs::new();
                 for eval in v.evaluations.iter() {
                     //TODO context is modified in each for loop, which is wrong if a key in context is specific to one result!
                     let sym = eval.symbol.get_symbol(session, &mut None, diagnostics, None);
                     if !sym.0.is_expired() {
-                        res.push_back(sym);
+                        res.push(sym);
                     }
                 }
-                return res
+                res
             },
-            _ => {
-                let vec = VecDeque::new();
-                return vec
-            }
+            _ => SymbolRefs::new()
         }
     }
 
     pub fn follow_ref(symbol: &Rc<RefCell<Symbol>>, session: &mut SessionInfo, context: &mut Option<Context>, stop_on_type: bool, stop_on_value: bool, max_scope: Option<Rc<RefCell<Symbol>>>, diagnostics: &mut Vec<Diagnostic>) -> Vec<(Weak<RefCell<Symbol>>, bool)> {
         //return a list of all possible evaluation: a weak ptr to the final symbol, and a bool indicating if this is an instance or not
-        let mut results = Symbol::next_refs(session, &symbol.borrow(), &mut vec![]);
-        if results.is_empty() {
+        let first_refs = Symbol::next_refs(session, &symbol.borrow(), &mut vec![]);
+        if first_refs.is_empty() {
             return vec![(Rc::downgrade(symbol), symbol.borrow().typ() == SymType::VARIABLE)];
         }
         //there is a 'next_ref'. Remove "parent" from context if any
-        if context.is_some() {
-            context.as_mut().unwrap().remove(&S!("parent"));
+        if let Some(context) = context.as_mut() {
+            context.remove(&S!("parent"));
         }
         let can_eval_external = !symbol.borrow().is_external();
-        let mut index = 0;
-        while index < results.len() {
-            let (sym, instance) = &results[index];
-            let sym = sym.upgrade();
-            if sym.is_none() {
-                index += 1;
+        //refs are followed in order: the ones that can't be followed further are moved to the results, the others are replaced by their next refs
+        let mut results = Vec::with_capacity(first_refs.len());
+        let mut to_follow: VecDeque<(Weak<RefCell<Symbol>>, bool)> = first_refs.into_iter().collect();
+        while let Some((weak_sym, instance)) = to_follow.pop_front() {
+            let Some(sym_rc) = weak_sym.upgrade() else {
+                results.push((weak_sym, instance));
+                continue;
+            };
+            let sym = sym_rc.borrow();
+            let Symbol::Variable(ref v) = *sym else {
+                drop(sym);
+                results.push((weak_sym, instance));
+                continue;
+            };
+            if (stop_on_type && !instance && !v.is_import_variable)
+                || (stop_on_value && v.evaluations.len() == 1 && v.evaluations[0].value.is_some())
+                || max_scope.as_ref().is_some_and(|max_scope| !sym.has_rc_in_parents(max_scope.clone(), true)) {
+                drop(sym);
+                results.push((weak_sym, instance));
                 continue;
             }
-            let sym = sym.unwrap();
-            let sym = sym.borrow();
-            match *sym {
-                Symbol::Variable(ref v) => {
-                    if stop_on_type && !instance && !v.is_import_variable {
-                        index += 1;
-                        continue;
-                    }
-                    if stop_on_value && v.evaluations.len() == 1 && v.evaluations[0].value.is_some() {
-                        index += 1;
-                        continue;
-                    }
-                    if max_scope.is_some() && !sym.has_rc_in_parents(max_scope.as_ref().unwrap().clone(), true) {
-                        index += 1;
-                        continue;
-                    }
-                    if v.evaluations.is_empty() && can_eval_external {
-                        //no evaluation? let's check that the file has been evaluated
-                        let file_symbol = sym.get_file();
-                        match file_symbol {
-                            Some(file_symbol) => {
-                                if file_symbol.upgrade().expect("invalid weak value").borrow().build_status(BuildSteps::ARCH) == BuildStatus::PENDING &&
-                                session.sync_odoo.is_in_rebuild(&file_symbol.upgrade().unwrap(), BuildSteps::ARCH_EVAL) { //TODO check ARCH ?
-                                    let mut builder = PythonArchEval::new(file_symbol.upgrade().unwrap());
-                                    builder.eval_arch(session);
-                                }
-                            },
-                            None => {}
-                        }
-                    }
-                    let next_sym_refs = Symbol::next_refs(session, &sym, &mut vec![]);
-                    index += 1;
-                    if next_sym_refs.len() >= 1 {
-                        results.pop_front();
-                        index -= 1;
-                        for next_results in next_sym_refs {
-                            results.push_back(next_results);
-                        }
+            if v.evaluations.is_empty() && can_eval_external {
+                //no evaluation? let's check that the file has been evaluated
+                if let Some(file_symbol) = sym.get_file().and_then(|file| file.upgrade()) {
+                    if file_symbol.borrow().build_status(BuildSteps::ARCH) == BuildStatus::PENDING &&
+                    session.sync_odoo.is_in_rebuild(&file_symbol, BuildSteps::ARCH_EVAL) { //TODO check ARCH ?
+                        let mut builder = PythonArchEval::new(file_symbol);
+                        builder.eval_arch(session);
                     }
-                },
-                _ => {
-                    index += 1;
                 }
             }
+            let next_sym_refs = Symbol::next_refs(session, &sym, &mut vec![]);
+            drop(sym);
+            if next_sym_refs.is_empty() {
+                results.push((weak_sym, instance));
+            } else {
+                to_follow.extend(next_sym_refs);
+            }
         }
-        return Vec::from(results) // :'( a whole copy?
+        results
     }
 
     pub fn all_symbols(&self) -> impl Iterator<Item= Rc<RefCell<Symbol>>> {
@@ -1650,7 +1734,7 @@ impl Symbol {
         let mut results = vec![];
         //get local symbols
         on_symbol.borrow().all_symbols().for_each(|sym| {
-            if sym.borrow().name().starts_with(name) {
+            if sym.borrow().name().starts_with(name) && !sym.borrow().is_comprehension() {
                 if position.is_none() || position.unwrap() > sym.borrow().range().end().to_u32() {
                     results.push(sym.clone());
                 }
@@ -1660,7 +1744,7 @@ impl Symbol {
         if let Some(file) = on_symbol.borrow().get_file().clone() {
             let file = file.upgrade().unwrap();
             file.borrow().all_symbols().for_each(|sym| {
-                if sym.borrow().name().starts_with(name) {
+                if sym.borrow().name().starts_with(name) && !sym.borrow().is_comprehension() {
                     if position.is_none() || position.unwrap() > sym.borrow().range().end().to_u32() {
                         results.push(sym.clone());
                     }
@@ -1759,25 +1843,33 @@ impl Symbol {
             }
         }
         if self.typ() == SymType::CLASS && self.as_class_sym()._model.is_some() && !prevent_comodel {
-            let model = session.sync_odoo.models.get(&self.as_class_sym()._model.as_ref().unwrap().name);
-            if let Some(model) = model {
-                let mut from_module = from_module.clone();
-                if from_module.is_none() {
-                    from_module = self.find_module();
+            //the module is searched once, for both the classes of the model and the inherited models
+            let visible_from = from_module.clone().or_else(|| self.owning_module());
+            let model = session.sync_odoo.models.get(&self.as_class_sym()._model.as_ref().unwrap().name).cloned();
+            if let (Some(model), Some(visible_from)) = (model, visible_from.as_ref()) {
+                let loc_symbols = model.borrow().get_symbols(session, visible_from.clone());
+                for loc_sym in loc_symbols {
+                    if self.is_equal(&loc_sym) {
+                        continue;
+                    }
+                    let (attribut, att_diagnostic) = loc_sym.borrow().get_member_symbol(session, name, None, true, all);
+                    diagnostics.extend(att_diagnostic);
+                    if all {
+                        result.extend(attribut);
+                    } else if !attribut.is_empty() {
+                        return (attribut, diagnostics);
+                    }
                 }
-                if let Some(from_module) = from_module {
-                    let loc_symbols = model.clone().borrow().get_symbols(session, from_module);
-                    for loc_sym in loc_symbols {
-                        if self.is_equal(&loc_sym) {
-                            continue;
-                        }
-                        let (attribut, att_diagnostic) = loc_sym.borrow().get_member_symbol(session, name, None, true, all);
-                        diagnostics.extend(att_diagnostic);
-                        if all {
-                            result.extend(attribut);
-                        } else {
-                            return (attribut, diagnostics);
-                        }
+            }
+            //then look in the models inherited with _inherit, like mail.thread
+            if let Some(visible_from) = visible_from {
+                for inherited_class in self.get_inherited_model_classes(session, visible_from) {
+                    let (attribut, att_diagnostic) = inherited_class.borrow().get_member_symbol(session, name, None, true, all);
+                    diagnostics.extend(att_diagnostic);
+                    if all {
+                        result.extend(attribut);
+                    } else if !attribut.is_empty() {
+                        return (attribut, diagnostics);
                     }
                 }
             }
@@ -1801,6 +1893,175 @@ impl Symbol {
         (result, diagnostics)
     }
 
+    /* Return the classes of the models inherited with _inherit by the model of this class (or by any other class of the same model),
+    recursively, and visible from from_module. Classes of the model itself are not returned. */
+    pub fn get_inherited_model_classes(&self, session: &mut SessionInfo, from_module: Rc<RefCell<Symbol>>) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut res = vec![];
+        let Some(model_data) = self.as_class_sym()._model.as_ref() else {
+            return res;
+        };
+        let mut visited = HashSet::from([model_data.name.clone()]);
+        let mut to_visit = model_data.inherit.clone();
+        if let Some(model) = session.sync_odoo.models.get(&model_data.name).cloned() {
+            for class in model.borrow().get_symbols(session, from_module.clone()) {
+                if self.is_equal(&class) {
+                    continue;
+                }
+                if let Some(class_model) = class.borrow().as_class_sym()._model.as_ref() {
+                    to_visit.extend(class_model.inherit.iter().cloned());
+                }
+            }
+        }
+        while let Some(name) = to_visit.pop() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+            let Some(model) = session.sync_odoo.models.get(&name).cloned() else {
+                continue;
+            };
+            let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_symbols(session, from_module.clone()).collect();
+            for class in classes {
+                if let Some(class_model) = class.borrow().as_class_sym()._model.as_ref() {
+                    to_visit.extend(class_model.inherit.iter().filter(|n| !visited.contains(*n)).cloned());
+                }
+                res.push(class);
+            }
+        }
+        res
+    }
+
+    /* Return the names of the models in the order their methods are resolved for the model of this class: the model itself,
+    then the python bases of its classes and the models of _inherit, linearized with the C3 algorithm like the python classes built
+    by Odoo. A model listed before another one in _inherit comes first, so its overrides run before the ones of the other model. The
+    python bases that are not models, like mixins of plain python classes, are listed by their full name. Only models visible from
+    from_module are used. */
+    pub fn get_model_mro(&self, session: &mut SessionInfo, from_module: Rc<RefCell<Symbol>>) -> Vec<String> {
+        let Some(model_data) = self.as_class_sym()._model.as_ref() else {
+            return vec![];
+        };
+        //the bases and the _inherit of this class come first, then the ones of the other classes of the model
+        let mut parents: Vec<String> = vec![];
+        let own_parents = Symbol::get_python_bases(self, &model_data.name).into_iter().chain(model_data.inherit.iter().cloned());
+        for parent in own_parents.chain(Symbol::get_model_parents(session, &from_module, &model_data.name)) {
+            if parent == model_data.name {
+                continue;
+            }
+            if !parents.contains(&parent) {
+                parents.push(parent);
+            }
+        }
+        let mut visiting = HashSet::from([model_data.name.clone()]);
+        let mut linearizations = HashMap::new();
+        let mut sequences: Vec<Vec<String>> = parents.iter().map(|parent| Symbol::linearize_model(session, &from_module, parent, &mut visiting, &mut linearizations)).collect();
+        sequences.push(parents);
+        let mut res = vec![model_data.name.clone()];
+        res.extend(Symbol::merge_mro(sequences));
+        res
+    }
+
+    /* Parents of a model: the python bases of its classes, then the models of their _inherit. The classes declaring the model
+    come first */
+    fn get_model_parents(session: &mut SessionInfo, from_module: &Rc<RefCell<Symbol>>, name: &String) -> Vec<String> {
+        let Some(model) = session.sync_odoo.models.get(name).cloned() else {
+            return vec![];
+        };
+        let mut classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_symbols(session, from_module.clone()).collect();
+        classes.sort_by_key(|class| class.borrow().as_class_sym()._model.as_ref().map_or(true, |data| data.inherit.contains(&data.name)));
+        let mut parents = vec![];
+        for class in classes.iter() {
+            let class = class.borrow();
+            let Some(data) = class.as_class_sym()._model.as_ref() else {
+                continue;
+            };
+            for parent in Symbol::get_python_bases(&class, name).iter().chain(data.inherit.iter()) {
+                if parent != name && !parents.contains(parent) {
+                    parents.push(parent.clone());
+                }
+            }
+        }
+        parents
+    }
+
+    /* Python bases of a class of a model, in the order of the class definition, without the classes of the framework. A base
+    that is the class of another model is given by the name of its model, the other ones by their full name */
+    fn get_python_bases(class: &Symbol, model_name: &String) -> Vec<String> {
+        let mut res = vec![];
+        for base in class.as_class_sym().ordered_bases.iter().filter_map(|base| base.upgrade()) {
+            let base = base.borrow();
+            let base_name = match base.as_class_sym()._model.as_ref() {
+                Some(base_model) => base_model.name.clone(),
+                None => {
+                    let tree = base.get_tree();
+                    //BaseModel, Model, AbstractModel... of the framework, out of the addons
+                    if tree.0.first().is_some_and(|root| root == "odoo") && tree.0.get(1).is_some_and(|package| package != "addons") {
+                        continue;
+                    }
+                    tree.0.iter().chain(tree.1.iter()).cloned().collect::<Vec<String>>().join(".")
+                }
+            };
+            if &base_name != model_name && !res.contains(&base_name) {
+                res.push(base_name);
+            }
+        }
+        res
+    }
+
+    /* Same as get_model_mro, for a model name */
+    pub fn get_model_linearization(session: &mut SessionInfo, from_module: &Rc<RefCell<Symbol>>, name: &String) -> Vec<String> {
+        Symbol::linearize_model(session, from_module, name, &mut HashSet::new(), &mut HashMap::new())
+    }
+
+    /* Linearization of a model, memoized in linearizations: a model inherited through many paths is only linearized once */
+    fn linearize_model(session: &mut SessionInfo, from_module: &Rc<RefCell<Symbol>>, name: &String, visiting: &mut HashSet<String>, linearizations: &mut HashMap<String, Vec<String>>) -> Vec<String> {
+        if let Some(linearization) = linearizations.get(name) {
+            return linearization.clone();
+        }
+        if !visiting.insert(name.clone()) {
+            //inheritance cycle, reported by the validation of _inherit
+            return vec![name.clone()];
+        }
+        let parents = Symbol::get_model_parents(session, from_module, name);
+        let mut sequences: Vec<Vec<String>> = parents.iter().map(|parent| Symbol::linearize_model(session, from_module, parent, visiting, linearizations)).collect();
+        sequences.push(parents);
+        visiting.remove(name);
+        let mut res = vec![name.clone()];
+        res.extend(Symbol::merge_mro(sequences));
+        linearizations.insert(name.clone(), res.clone());
+        res
+    }
+
+    /* C3 merge of the linearizations of the parents. If the hierarchy is inconsistent, python would refuse to build the class:
+    the models are then listed depth first */
+    fn merge_mro(sequences: Vec<Vec<String>>) -> Vec<String> {
+        let mut remaining = sequences.clone();
+        let mut res: Vec<String> = vec![];
+        loop {
+            remaining.retain(|sequence| !sequence.is_empty());
+            if remaining.is_empty() {
+                return res;
+            }
+            let head = remaining.iter().map(|sequence| &sequence[0])
+                .find(|head| !remaining.iter().any(|sequence| sequence[1..].contains(head)))
+                .cloned();
+            let Some(head) = head else {
+                break;
+            };
+            for sequence in remaining.iter_mut() {
+                if sequence[0] == head {
+                    sequence.remove(0);
+                }
+            }
+            res.push(head);
+        }
+        res.clear();
+        for name in sequences.into_iter().flatten() {
+            if !res.contains(&name) {
+                res.push(name);
+            }
+        }
+        res
+    }
+
     pub fn is_equal(&self, other: &Rc<RefCell<Symbol>>) -> bool {
         return Weak::ptr_eq(&self.weak_self().unwrap_or(Weak::new()), &Rc::downgrade(other));
     }
@@ -1819,7 +2080,7 @@ impl Symbol {
                         for (_position, symbol_list) in section.iter() {
                             for symbol in symbol_list.iter() {
                                 match *symbol.borrow() {
-                                    Symbol::Function(_) => res.push(symbol.clone()),
+                                    Symbol::Function(ref f) if !f.is_comprehension => res.push(symbol.clone()),
                                     _ => {},
                                 }
                             }
@@ -13,6 +13,7 @@ pub struct VariableSymbol {
     pub ast_indexes: Vec<u16>, //list of index to reach the corresponding ast node from file ast
     pub weak_self: Option<Weak<RefCell<Symbol>>>,
     pub parent: Option<Weak<RefCell<Symbol>>>,
+    pub module: Option<Weak<RefCell<Symbol>>>, //module declaring the variable (the fields of models). None outside of odoo modules
     pub is_import_variable: bool,
     pub is_parameter: bool,
     pub evaluations: Vec<Evaluation>, //Vec, because sometimes a single allocation can be ambiguous, like ''' a = "5" if X else 5 '''
@@ -29,6 +30,7 @@ impl VariableSymbol {
             ast_indexes: vec![],
             weak_self: None,
             parent: None,
+            module: None,
             range,
             is_import_variable: false,
             is_parameter: false,
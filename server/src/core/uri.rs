@@ -0,0 +1,123 @@
+/* Conversions between the uris of the clients and the paths of the database. A file must have only one path in the
+database, whatever the spelling of the uri sent by the client: file:///C:/x, file:///c%3A/x and file:///c:/x are the
+same file on windows, and \\server\share\x is sent as file://server/share/x.
+The paths of the database use forward slashes and a lowercase drive letter. As the file system of windows is case
+insensitive, the existing files are canonicalized (see PathSanitizer::sanitize_canonical) to get their real casing. */
+
+/* Characters kept as is in the path of an uri (RFC 3986 unreserved and path characters) */
+const URI_PATH_CHARS: &str = "-._~!$&'()*+,;=:@/";
+
+/* Normalize the spelling of a path, without resolving it on the file system */
+pub fn normalize_path(path: &str) -> String {
+    normalize_path_for(path, cfg!(windows))
+}
+
+pub fn normalize_path_for(path: &str, windows: bool) -> String {
+    if !windows {
+        return path.to_string();
+    }
+    let mut path = path.replace('\\', "/");
+    //verbatim paths returned by canonicalize: //?/UNC/server/share/x and //?/C:/x
+    if let Some(unc) = path.strip_prefix("//?/UNC/") {
+        path = format!("//{}", unc);
+    } else if let Some(local) = path.strip_prefix("//?/") {
+        path = local.to_string();
+    }
+    let is_unc = path.starts_with("//");
+    let mut res = if is_unc { String::from("//") } else { String::new() };
+    let mut previous_slash = is_unc;
+    for c in path.chars().skip(if is_unc { 2 } else { 0 }) {
+        if c == '/' && previous_slash {
+            continue;
+        }
+        previous_slash = c == '/';
+        res.push(c);
+    }
+    if res.len() > 2 && res.as_bytes()[1] == b':' && res.as_bytes()[0].is_ascii_alphabetic() {
+        let drive = res[0..1].to_ascii_lowercase();
+        res.replace_range(0..1, &drive);
+    }
+    //the root of a drive keeps its slash: c:/
+    if res.ends_with('/') && res.len() > 3 {
+        res.pop();
+    }
+    res
+}
+
+/* Path of a file:// uri, percent-decoded and normalized. None if the uri is not a file uri */
+pub fn uri_to_path(uri: &str) -> Option<String> {
+    uri_to_path_for(uri, cfg!(windows))
+}
+
+pub fn uri_to_path_for(uri: &str, windows: bool) -> Option<String> {
+    if !uri.get(..7).is_some_and(|scheme| scheme.eq_ignore_ascii_case("file://")) {
+        return None;
+    }
+    let rest = &uri[7..];
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, ""),
+    };
+    let path = percent_decode(path)?;
+    let path = if !authority.is_empty() && !authority.eq_ignore_ascii_case("localhost") {
+        //unc path: file://server/share/x
+        format!("//{}{}", percent_decode(authority)?, path)
+    } else if windows && path.len() >= 3 && path.starts_with('/') && path.as_bytes()[2] == b':' && path.as_bytes()[1].is_ascii_alphabetic() {
+        //drive letter: file:///c:/x
+        path[1..].to_string()
+    } else {
+        path
+    };
+    Some(normalize_path_for(&path, windows))
+}
+
+/* file:// uri of a path, with the characters that are not allowed in an uri percent-encoded */
+pub fn path_to_uri(path: &str) -> String {
+    path_to_uri_for(path, cfg!(windows))
+}
+
+pub fn path_to_uri_for(path: &str, windows: bool) -> String {
+    let path = normalize_path_for(path, windows);
+    if let Some(unc) = path.strip_prefix("//") {
+        let (server, rest) = match unc.find('/') {
+            Some(index) => (&unc[..index], &unc[index..]),
+            None => (unc, ""),
+        };
+        return format!("file://{}{}", percent_encode(server), percent_encode(rest));
+    }
+    if path.starts_with('/') {
+        format!("file://{}", percent_encode(&path))
+    } else {
+        format!("file:///{}", percent_encode(&path))
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || URI_PATH_CHARS.as_bytes().contains(&byte) {
+            res.push(byte as char);
+        } else {
+            res += &format!("%{:02X}", byte);
+        }
+    }
+    res
+}
+
+/* None if an escape is invalid or if the decoded bytes are not utf-8 */
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = value.get(index + 1..index + 3)?;
+            res.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            res.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(res).ok()
+}
@@ -0,0 +1,299 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::EXTENSION_NAME;
+use crate::core::domain_validator::DomainValidator;
+use crate::core::symbols::symbol::Symbol;
+use crate::core::xml_parser::{attribute, attributes, field_text, strip_comments, LineIndex, XmlValue, FIELD_RE, RECORD_RE};
+use crate::threads::SessionInfo;
+use crate::S;
+
+pub const VIEW_MODEL: &str = "ir.ui.view";
+pub const MODEL_FIELDS_MODEL: &str = "ir.model.fields";
+
+static MODEL_FIELD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<field\b[^>]*\bname=["']model["'][^>]*>\s*([\w.]+)\s*</field>"#).unwrap());
+static ARCH_FIELD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<field\b[^>]*\bname=["']arch["'][^>]*>"#).unwrap());
+static FIELD_TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<(/?)field\b([^>]*?)(/?)>"#).unwrap());
+static AGGREGATE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(?:sum|avg)\s*="#).unwrap());
+
+/* A field used in the arch of a view, with the attributes giving a hint of its type */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewField {
+    pub name: XmlValue,
+    pub model: String,
+    pub widget: Option<String>,
+    pub options: Option<String>,
+    pub aggregated: bool, //a sum or avg is computed on the column of a list view
+    pub help: Option<String>,
+}
+
+/* Data of the diagnostics of unknown fields, used by the code action adding the field to the model */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownViewFieldData {
+    pub model: String,
+    pub field: String,
+    pub field_type: String,
+}
+
+/* The fields used by the views of a data file. Only the fields of the model of the view are given: the ones of the sub-views
+of x2many fields belong to the comodel, and the views without model, like the inherited ones, are skipped */
+pub fn parse_view_fields(content: &str) -> Vec<ViewField> {
+    let content = strip_comments(content);
+    let mut fields = vec![];
+    for record in RECORD_RE.captures_iter(&content) {
+        let (Some(model), Some(body)) = (attribute(&record[1], "model"), record.get(2)) else {
+            continue;
+        };
+        if model.value != VIEW_MODEL {
+            continue;
+        }
+        let Some(view_model) = MODEL_FIELD_RE.captures(body.as_str()).map(|captures| captures[1].to_string()) else {
+            continue;
+        };
+        //the arch contains other <field> tags: it ends with the last </field> of the record
+        let Some(arch_start) = ARCH_FIELD_RE.find(body.as_str()).map(|arch| arch.end()) else {
+            continue;
+        };
+        let arch_end = body.as_str().rfind("</field>").filter(|end| *end >= arch_start).unwrap_or(arch_start);
+        let arch_offset = body.start() + arch_start;
+        let mut depth = 0;
+        for tag in FIELD_TAG_RE.captures_iter(&body.as_str()[arch_start..arch_end]) {
+            if !tag[1].is_empty() {
+                depth -= 1;
+                continue;
+            }
+            let attrs = tag.get(2).unwrap();
+            if depth == 0 {
+                let mut values = attributes(attrs.as_str());
+                if let Some(name) = values.remove("name").filter(|name| !name.value.contains('.')) {
+                    fields.push(ViewField {
+                        name: name.shifted(arch_offset + attrs.start()),
+                        model: view_model.clone(),
+                        widget: values.remove("widget").map(|widget| widget.value),
+                        options: values.remove("options").map(|options| options.value),
+                        aggregated: AGGREGATE_RE.is_match(attrs.as_str()),
+                        help: values.remove("help").map(|help| help.value),
+                    });
+                }
+            }
+            if tag[3].is_empty() {
+                depth += 1;
+            }
+        }
+    }
+    fields
+}
+
+/* Help texts given to fields by a data file, as (model, field, help): the help attribute of the fields of the views, and the
+help of the ir.model.fields records. When a record gives its model by the xml id of the ir.model record, like
+base.model_res_partner, the model is named like the xml id: res_partner */
+pub fn parse_field_helps(content: &str) -> Vec<(String, String, String)> {
+    let mut helps: Vec<(String, String, String)> = parse_view_fields(content).into_iter()
+        .filter_map(|field| Some((field.model, field.name.value, field.help?)))
+        .collect();
+    let content = strip_comments(content);
+    for record in RECORD_RE.captures_iter(&content) {
+        let (Some(model), Some(body)) = (attribute(&record[1], "model"), record.get(2)) else {
+            continue;
+        };
+        if model.value != MODEL_FIELDS_MODEL {
+            continue;
+        }
+        let (mut field_model, mut name, mut help) = (None, None, None);
+        for field in FIELD_RE.captures_iter(body.as_str()) {
+            match attribute(&field[1], "name").map(|name| name.value).as_deref() {
+                Some("model") => field_model = field_text(&field).map(|text| text.value),
+                Some("model_id") => field_model = attribute(&field[1], "ref")
+                    .and_then(|xml_id| xml_id.value.rsplit('.').next().and_then(|name| name.strip_prefix("model_")).map(str::to_string)),
+                Some("name") => name = field_text(&field).map(|text| text.value),
+                Some("help") => help = field_text(&field).map(|text| text.value),
+                _ => {}
+            }
+        }
+        if let (Some(field_model), Some(name), Some(help)) = (field_model, name, help) {
+            helps.push((field_model, name, help));
+        }
+    }
+    helps
+}
+
+/* Index of the fields used by the views of the xml files, to go from a field to the views using it, and of the help texts
+given to fields by the xml files. The files are indexed when the data of their module is loaded, and again when they are edited */
+#[derive(Debug, Default)]
+pub struct XmlFieldIndex {
+    usages: HashMap<String, HashMap<String, Vec<(String, Range)>>>, //model => field => (path of the xml file, range of the name)
+    helps: HashMap<String, HashMap<String, Vec<(String, String)>>>, //model, or its name in xml ids => field => (path of the xml file, help)
+}
+
+impl XmlFieldIndex {
+
+    /* Index the fields used by the views of a xml file, replacing the ones indexed before for this file */
+    pub fn index_file(&mut self, path: &str, content: &str) {
+        self.remove_file(path);
+        let lines = LineIndex::new(content);
+        for field in parse_view_fields(content) {
+            let range = Range::new(lines.position_of(content, field.name.offset), lines.position_of(content, field.name.offset + field.name.value.len()));
+            self.usages.entry(field.model).or_default().entry(field.name.value).or_default().push((path.to_string(), range));
+        }
+        for (model, field, help) in parse_field_helps(content) {
+            self.helps.entry(model).or_default().entry(field).or_default().push((path.to_string(), help));
+        }
+    }
+
+    /* Forget the usages of fields in a xml file, before it is indexed again or deleted */
+    pub fn remove_file(&mut self, path: &str) {
+        self.remove_files(|p| p == path);
+    }
+
+    /* Forget the xml files of a directory, like the ones of an unloaded module */
+    pub fn remove_dir(&mut self, dir: &str) {
+        self.remove_files(|p| Path::new(p).starts_with(dir));
+    }
+
+    fn remove_files(&mut self, filter: impl Fn(&str) -> bool) {
+        for fields in self.usages.values_mut() {
+            for usages in fields.values_mut() {
+                usages.retain(|(p, _)| !filter(p));
+            }
+            fields.retain(|_, usages| !usages.is_empty());
+        }
+        self.usages.retain(|_, fields| !fields.is_empty());
+        for fields in self.helps.values_mut() {
+            for helps in fields.values_mut() {
+                helps.retain(|(p, _)| !filter(p));
+            }
+            fields.retain(|_, helps| !helps.is_empty());
+        }
+        self.helps.retain(|_, fields| !fields.is_empty());
+    }
+
+    pub fn get_usages(&self, model: &str, field: &str) -> &[(String, Range)] {
+        self.usages.get(model).and_then(|fields| fields.get(field)).map(|usages| usages.as_slice()).unwrap_or_default()
+    }
+
+    /* The model and the field used in a xml file at the given position */
+    pub fn get_usage_at(&self, path: &str, position: &Position) -> Option<(String, String)> {
+        self.usages.iter().find_map(|(model, fields)| fields.iter()
+            .find(|(_, usages)| usages.iter().any(|(p, range)| p == path && range.start <= *position && *position <= range.end))
+            .map(|(field, _)| (model.clone(), field.clone())))
+    }
+
+    /* The help texts given to a field of the model by the xml files, with the path of the file giving them */
+    pub fn get_helps(&self, model: &str, field: &str) -> Vec<&(String, String)> {
+        let xml_name = model.replace('.', "_");
+        [model, xml_name.as_str()].iter()
+            .filter_map(|model| self.helps.get(*model).and_then(|fields| fields.get(field)))
+            .flatten()
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.usages.clear();
+        self.helps.clear();
+    }
+}
+
+/* The type of field that a view most likely expects, from the widget displaying it, its options and its name */
+pub fn infer_field_type(field: &ViewField) -> &'static str {
+    let by_widget = match field.widget.as_deref() {
+        Some("monetary") => Some("Monetary"),
+        Some("many2one" | "many2one_avatar" | "many2one_avatar_user" | "res_partner_many2one") => Some("Many2one"),
+        Some("many2many" | "many2many_tags" | "many2many_checkboxes" | "many2many_binary" | "many2many_avatar_user") => Some("Many2many"),
+        Some("one2many" | "one2many_list" | "section_and_note_one2many") => Some("One2many"),
+        Some("boolean" | "boolean_toggle" | "boolean_favorite" | "boolean_icon") => Some("Boolean"),
+        Some("date" | "remaining_days") => Some("Date"),
+        Some("datetime" | "daterange") => Some("Datetime"),
+        Some("html") => Some("Html"),
+        Some("text") => Some("Text"),
+        Some("float" | "float_time" | "percentage" | "progressbar") => Some("Float"),
+        Some("integer" | "handle" | "priority") => Some("Integer"),
+        Some("selection" | "radio" | "badge" | "statusbar" | "state_selection") => Some("Selection"),
+        Some("image") => Some("Image"),
+        Some("binary" | "pdf_viewer") => Some("Binary"),
+        Some("color" | "color_picker") => Some("Integer"),
+        _ => None,
+    };
+    if let Some(typ) = by_widget {
+        return typ;
+    }
+    if field.options.as_deref().is_some_and(|options| options.contains("currency_field")) {
+        return "Monetary";
+    }
+    let name = field.name.value.as_str();
+    if name.ends_with("_ids") {
+        "Many2many"
+    } else if name.ends_with("_id") {
+        "Many2one"
+    } else if name.starts_with("is_") || name.starts_with("has_") || name == "active" {
+        "Boolean"
+    } else if name.ends_with("_datetime") || name.ends_with("_at") {
+        "Datetime"
+    } else if name.starts_with("date") || name.ends_with("_date") {
+        "Date"
+    } else if name.ends_with("_count") || name == "sequence" {
+        "Integer"
+    } else if field.aggregated {
+        "Float"
+    } else {
+        "Char"
+    }
+}
+
+/* The comodel of a relational field used by a view, from its name: partner_id is a field on res.partner. The model named like
+the field is preferred, then the shortest model ending like it, so that the same comodel is given on each request */
+pub fn guess_comodel<'a>(models: impl Iterator<Item = &'a String>, field: &str) -> Option<String> {
+    let base = field.strip_suffix("_ids").or(field.strip_suffix("_id")).unwrap_or(field);
+    let suffix = format!(".{}", base);
+    let mut candidates: Vec<&String> = models.filter(|name| name.replace('.', "_") == base || name.ends_with(&suffix)).collect();
+    candidates.sort_by_key(|name| (name.replace('.', "_") != base, name.len(), name.as_str()));
+    candidates.first().map(|name| name.to_string())
+}
+
+pub struct ViewValidator {}
+
+impl ViewValidator {
+
+    /* Report the fields used by the views of a xml file that are not members of their model. The models that are not
+    loaded are not checked */
+    pub fn validate(session: &mut SessionInfo, content: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for field in parse_view_fields(content).iter() {
+            if !session.sync_odoo.models.contains_key(&field.model) || ViewValidator::is_model_field(session, &field.model, &field.name.value) {
+                continue;
+            }
+            let data = UnknownViewFieldData {
+                model: field.model.clone(),
+                field: field.name.value.clone(),
+                field_type: S!(infer_field_type(field)),
+            };
+            diagnostics.push(Diagnostic {
+                data: serde_json::to_value(data).ok(),
+                ..Diagnostic::new(field.name.range(),
+                    Some(DiagnosticSeverity::ERROR),
+                    Some(NumberOrString::String(S!("OLS31201"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("{} is not a field of model {}", field.name.value, field.model),
+                    None,
+                    None)
+            });
+        }
+        diagnostics
+    }
+
+    /* True if one of the classes of the model has a field with this name. Methods and other members are not fields */
+    fn is_model_field(session: &mut SessionInfo, model: &String, name: &String) -> bool {
+        let Some(model) = session.sync_odoo.models.get(model).cloned() else {
+            return true;
+        };
+        let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+        classes.iter().any(|class| DomainValidator::is_field(session, class, name, None))
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use ruff_python_ast::visitor::{Visitor, walk_except_handler, walk_expr, walk_pattern, walk_stmt};
+use ruff_python_ast::{Comprehension, ExceptHandler, Expr, ExprContext, Mod, Parameters, Pattern, Stmt};
+use ruff_python_parser::Mode;
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::constants::EXTENSION_NAME;
+use crate::core::symbols::symbol::Symbol;
+use crate::core::xml_parser::{attribute, FIELD_RE, RECORD_RE};
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* Models whose records run the python code of their 'code' field */
+static CODE_MODELS: &[&str] = &["ir.actions.server", "ir.cron"];
+
+/* Variables given by Odoo to the code of server actions and crons (see ir.actions.server._get_eval_context) */
+pub static EVAL_CONTEXT: &[&str] = &["env", "model", "record", "records", "time", "datetime", "dateutil", "timezone",
+    "float_compare", "b64encode", "b64decode", "log", "_logger", "Warning", "UserError", "Command", "uid", "user"];
+
+/* Variables of the evaluation context that are recordsets of the model of the action */
+pub static RECORDSET_VARIABLES: &[&str] = &["model", "record", "records"];
+
+/* Builtins available in safe_eval */
+static SAFE_BUILTINS: &[&str] = &["True", "False", "None", "bool", "bytes", "int", "float", "str", "list", "dict", "set", "tuple",
+    "frozenset", "len", "min", "max", "sum", "abs", "all", "any", "enumerate", "filter", "map", "range", "round", "sorted",
+    "reversed", "zip", "isinstance", "hasattr", "getattr", "setattr", "divmod", "repr", "chr", "ord", "Exception", "ValueError",
+    "TypeError", "KeyError", "IndexError", "NameError", "ZeroDivisionError", "AssertionError", "RuntimeError", "_"];
+
+/* Python code written in the 'code' field of a server action or a cron of a xml data file. The code is unescaped, so
+each byte of the source is mapped to its offset in the xml file */
+#[derive(Debug)]
+pub struct EmbeddedCode {
+    pub model: Option<String>, //name of the model given by model_id, if it is a known model
+    pub source: String,
+    offsets: Vec<usize>, //offset in the xml file of each byte of the source, and of the end of the source
+}
+
+impl EmbeddedCode {
+
+    pub fn to_file_offset(&self, offset: usize) -> usize {
+        self.offsets[offset.min(self.offsets.len() - 1)]
+    }
+
+    /* Offset in the source of an offset of the xml file, if it is in the code (its end included, for completion) */
+    pub fn to_source_offset(&self, file_offset: usize) -> Option<usize> {
+        if file_offset < self.offsets[0] || file_offset > *self.offsets.last().unwrap() {
+            return None;
+        }
+        Some(self.offsets.partition_point(|offset| *offset < file_offset))
+    }
+
+    fn to_file_range(&self, range: TextRange) -> Range {
+        Range::new(Position::new(self.to_file_offset(range.start().to_usize()) as u32, 0),
+            Position::new(self.to_file_offset(range.end().to_usize()) as u32, 0))
+    }
+
+    /* The identifier at the offset of the source, and the identifier it is an attribute of: ("record", "partner_id") for
+    "record.partner_id". With only_prefix, the identifier stops at the offset, to complete it */
+    pub fn attribute_at(&self, offset: usize, only_prefix: bool) -> (Option<String>, String) {
+        let bytes = self.source.as_bytes();
+        let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+        let mut start = offset.min(bytes.len());
+        while start > 0 && is_ident(bytes[start - 1]) {
+            start -= 1;
+        }
+        let mut end = offset.min(bytes.len());
+        while !only_prefix && end < bytes.len() && is_ident(bytes[end]) {
+            end += 1;
+        }
+        let word = self.source[start..end].to_string();
+        if start == 0 || bytes[start - 1] != b'.' {
+            return (None, word);
+        }
+        let base_end = start - 1;
+        let mut base_start = base_end;
+        while base_start > 0 && is_ident(bytes[base_start - 1]) {
+            base_start -= 1;
+        }
+        (Some(self.source[base_start..base_end].to_string()), word)
+    }
+}
+
+pub struct ServerActionCode {}
+
+impl ServerActionCode {
+
+    /* Find the code of the server actions and crons of a xml file */
+    pub fn extract(session: &mut SessionInfo, text: &str) -> Vec<EmbeddedCode> {
+        let mut res = vec![];
+        for record in RECORD_RE.captures_iter(text) {
+            let attrs = record.get(1).unwrap().as_str();
+            if !attribute(attrs, "model").is_some_and(|model| CODE_MODELS.contains(&model.value.as_str())) {
+                continue;
+            }
+            let Some(body) = record.get(2) else {
+                continue;
+            };
+            let mut model = None;
+            let mut code = None;
+            for field in FIELD_RE.captures_iter(body.as_str()) {
+                let field_attrs = field.get(1).unwrap().as_str();
+                match attribute(field_attrs, "name").as_ref().map(|name| name.value.as_str()) {
+                    Some("model_id") => model = attribute(field_attrs, "ref").and_then(|xml_id| ServerActionCode::model_from_xml_id(session, &xml_id.value)),
+                    Some("code") => code = field.get(2).map(|content| (content.start() + body.start(), content.as_str())),
+                    _ => {}
+                }
+            }
+            if let Some((start, content)) = code {
+                let (source, offsets) = ServerActionCode::unescape(content, start);
+                res.push(EmbeddedCode { model, source, offsets });
+            }
+        }
+        res
+    }
+
+    /* Validate the code of the server actions and crons of an opened xml file of the workspace */
+    pub fn validate(session: &mut SessionInfo, text: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for code in ServerActionCode::extract(session, text).iter() {
+            diagnostics.extend(ServerActionCode::validate_code(session, code));
+        }
+        diagnostics
+    }
+
+    fn validate_code(session: &mut SessionInfo, code: &EmbeddedCode) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let parsed = ruff_python_parser::parse_unchecked(&code.source, Mode::Module);
+        for error in parsed.errors().iter() {
+            diagnostics.push(ServerActionCode::diagnostic(code.to_file_range(error.location), DiagnosticSeverity::ERROR, "OLS30001", error.error.to_string()));
+        }
+        let Mod::Module(module) = parsed.syntax() else {
+            return diagnostics;
+        };
+        //the code is the body of a function whose parameters are the variables of the evaluation context
+        let mut visitor = ScopeVisitor::new(&module.body);
+        for stmt in module.body.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        for range in visitor.imports.iter() {
+            diagnostics.push(ServerActionCode::diagnostic(code.to_file_range(*range), DiagnosticSeverity::ERROR, "OLS30801",
+                S!("Imports are not allowed in the code of server actions. Use the variables of the evaluation context")));
+        }
+        for (name, range) in visitor.unbound.iter() {
+            diagnostics.push(ServerActionCode::diagnostic(code.to_file_range(*range), DiagnosticSeverity::WARNING, "OLS20801",
+                format!("{} is not defined in the evaluation context of server actions", name)));
+        }
+        for (attr, range) in visitor.private_attributes.iter() {
+            diagnostics.push(ServerActionCode::diagnostic(code.to_file_range(*range), DiagnosticSeverity::ERROR, "OLS30802",
+                format!("safe_eval forbids the access to the private attribute {}", attr)));
+        }
+        if let Some(model) = code.model.as_ref() {
+            for (attr, range) in visitor.model_attributes.iter() {
+                if !ServerActionCode::has_member(session, model, attr) {
+                    diagnostics.push(ServerActionCode::diagnostic(code.to_file_range(*range), DiagnosticSeverity::ERROR, "OLS30803",
+                        format!("{} is not a field or a method of model {}", attr, model)));
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /* Return true if the classes of the model, or the classes they inherit from, have a member with this name */
+    pub fn has_member(session: &mut SessionInfo, model: &String, name: &String) -> bool {
+        let Some(model) = session.sync_odoo.models.get(model).cloned() else {
+            return true;
+        };
+        let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+        classes.iter().any(|class| !class.borrow().get_member_symbol(session, name, None, false, false).0.is_empty())
+    }
+
+    /* The members named 'name' declared in the classes of the model */
+    pub fn get_model_members(session: &mut SessionInfo, model: &String, name: &String) -> Vec<Rc<RefCell<Symbol>>> {
+        let Some(model) = session.sync_odoo.models.get(model).cloned() else {
+            return vec![];
+        };
+        let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+        classes.iter().flat_map(|class| class.borrow().get_symbol(&(vec![], vec![name.clone()]), u32::MAX)).collect()
+    }
+
+    /* model_id refers to the xml id of the ir.model record of the model: 'base.model_res_partner' for res.partner */
+    fn model_from_xml_id(session: &mut SessionInfo, xml_id: &str) -> Option<String> {
+        let name = xml_id.split_once('.').map(|(_, name)| name).unwrap_or(xml_id);
+        let name = name.strip_prefix("model_")?;
+        session.sync_odoo.models.keys().find(|model| model.replace('.', "_") == name).cloned()
+    }
+
+    /* Unescape the content of the field (CDATA sections and xml entities) and strip it as Odoo does before compiling it */
+    pub fn unescape(content: &str, start: usize) -> (String, Vec<usize>) {
+        let mut source = String::new();
+        let mut offsets = vec![];
+        let mut index = 0;
+        while index < content.len() {
+            let rest = &content[index..];
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let cdata_len = cdata.find("]]>").unwrap_or(cdata.len());
+                let cdata_start = index + "<![CDATA[".len();
+                source.push_str(&cdata[..cdata_len]);
+                offsets.extend((cdata_start..cdata_start + cdata_len).map(|offset| offset + start));
+                index = (cdata_start + cdata_len + "]]>".len()).min(content.len());
+                continue;
+            }
+            let entity = [("&lt;", "<"), ("&gt;", ">"), ("&amp;", "&"), ("&quot;", "\""), ("&apos;", "'")].iter()
+                .find(|(entity, _)| rest.starts_with(entity));
+            if let Some((entity, value)) = entity {
+                source.push_str(value);
+                offsets.push(index + start);
+                index += entity.len();
+                continue;
+            }
+            let c = rest.chars().next().unwrap();
+            source.push(c);
+            offsets.extend((index..index + c.len_utf8()).map(|offset| offset + start));
+            index += c.len_utf8();
+        }
+        offsets.push(content.len() + start);
+        //strip the code, as Odoo does
+        if source.trim().is_empty() {
+            return (String::new(), vec![offsets[0]]);
+        }
+        let leading = source.len() - source.trim_start().len();
+        let trailing = source.trim_end().len();
+        (source[leading..trailing].to_string(), offsets[leading..=trailing].to_vec())
+    }
+
+    fn diagnostic(range: Range, severity: DiagnosticSeverity, code: &str, message: String) -> Diagnostic {
+        Diagnostic::new(range,
+            Some(severity),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None)
+    }
+}
+
+/* Names bound by a scope of the code. Class bodies are not enclosing scopes: their names are not visible from their methods */
+#[derive(Default)]
+struct Scope {
+    is_class: bool,
+    bindings: HashSet<String>,
+}
+
+impl Scope {
+
+    /* The scope of a function or class body, with the names bound anywhere in it */
+    fn of_body(is_class: bool, body: &[Stmt]) -> Scope {
+        let mut collector = BindingCollector::default();
+        for stmt in body.iter() {
+            collector.visit_stmt(stmt);
+        }
+        Scope { is_class, bindings: collector.bindings }
+    }
+
+    fn add_parameters(&mut self, parameters: &Parameters) {
+        for param in parameters.iter() {
+            self.bindings.insert(param.name().to_string());
+        }
+    }
+}
+
+/* What a name used by the code refers to */
+#[derive(PartialEq)]
+enum Binding {
+    Local, //bound by the code, in the scope of the name or an enclosing one
+    Context, //a variable of the evaluation context, not rebound by the code
+    Builtin,
+    Unbound,
+}
+
+/* Collect the names bound by a body, without the ones of the nested functions, classes, lambdas and comprehensions */
+#[derive(Default)]
+struct BindingCollector {
+    bindings: HashSet<String>,
+}
+
+impl<'a> Visitor<'a> for BindingCollector {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::FunctionDef(func) => { self.bindings.insert(func.name.to_string()); },
+            Stmt::ClassDef(class) => { self.bindings.insert(class.name.to_string()); },
+            Stmt::Import(import) => {
+                for alias in import.names.iter() {
+                    let name = alias.asname.as_ref().map(|asname| asname.as_str()).unwrap_or(alias.name.as_str().split('.').next().unwrap());
+                    self.bindings.insert(name.to_string());
+                }
+            },
+            Stmt::ImportFrom(import) => {
+                for alias in import.names.iter() {
+                    self.bindings.insert(alias.asname.as_ref().unwrap_or(&alias.name).to_string());
+                }
+            },
+            Stmt::Global(global) => self.bindings.extend(global.names.iter().map(|name| name.to_string())),
+            Stmt::Nonlocal(nonlocal) => self.bindings.extend(nonlocal.names.iter().map(|name| name.to_string())),
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(name) if name.ctx != ExprContext::Load => { self.bindings.insert(name.id.to_string()); },
+            Expr::Lambda(_) | Expr::ListComp(_) | Expr::SetComp(_) | Expr::DictComp(_) | Expr::Generator(_) => {},
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_except_handler(&mut self, except_handler: &'a ExceptHandler) {
+        let ExceptHandler::ExceptHandler(handler) = except_handler;
+        if let Some(name) = handler.name.as_ref() {
+            self.bindings.insert(name.to_string());
+        }
+        walk_except_handler(self, except_handler);
+    }
+
+    fn visit_pattern(&mut self, pattern: &'a Pattern) {
+        let name = match pattern {
+            Pattern::MatchAs(pattern) => pattern.name.as_ref(),
+            Pattern::MatchStar(pattern) => pattern.name.as_ref(),
+            Pattern::MatchMapping(pattern) => pattern.rest.as_ref(),
+            _ => None
+        };
+        if let Some(name) = name {
+            self.bindings.insert(name.to_string());
+        }
+        walk_pattern(self, pattern);
+    }
+}
+
+/* Resolve the names used by the code in the scopes they are used in. The code is the body of a function whose parameters
+are the variables of the evaluation context: a variable rebound by the code is a local variable in the whole body */
+struct ScopeVisitor {
+    scopes: Vec<Scope>,
+    imports: Vec<TextRange>,
+    unbound: Vec<(String, TextRange)>,
+    private_attributes: Vec<(String, TextRange)>,
+    model_attributes: Vec<(String, TextRange)>, //attributes of model, record and records, when they are the ones of the context
+}
+
+impl ScopeVisitor {
+
+    fn new(body: &[Stmt]) -> ScopeVisitor {
+        ScopeVisitor {
+            scopes: vec![Scope::of_body(false, body)],
+            imports: vec![],
+            unbound: vec![],
+            private_attributes: vec![],
+            model_attributes: vec![],
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Binding {
+        let innermost = self.scopes.len() - 1;
+        for (index, scope) in self.scopes.iter().enumerate().rev() {
+            if scope.is_class && index != innermost {
+                continue;
+            }
+            if scope.bindings.contains(name) {
+                return Binding::Local;
+            }
+        }
+        if EVAL_CONTEXT.contains(&name) {
+            Binding::Context
+        } else if SAFE_BUILTINS.contains(&name) {
+            Binding::Builtin
+        } else {
+            Binding::Unbound
+        }
+    }
+
+    /* The default values of the parameters are evaluated in the enclosing scope */
+    fn visit_defaults(&mut self, parameters: &Parameters) {
+        for param in parameters.posonlyargs.iter().chain(&parameters.args).chain(&parameters.kwonlyargs) {
+            if let Some(default) = param.default.as_ref() {
+                self.visit_expr(default);
+            }
+        }
+    }
+
+    /* The iterable of the first generator is evaluated in the enclosing scope, the rest of the comprehension in its own scope */
+    fn visit_comprehension_scope(&mut self, elements: &[&Expr], generators: &[Comprehension]) {
+        let Some(first) = generators.first() else {
+            return;
+        };
+        self.visit_expr(&first.iter);
+        let mut collector = BindingCollector::default();
+        for generator in generators.iter() {
+            collector.visit_expr(&generator.target);
+        }
+        self.scopes.push(Scope { is_class: false, bindings: collector.bindings });
+        for (index, generator) in generators.iter().enumerate() {
+            if index > 0 {
+                self.visit_expr(&generator.iter);
+            }
+            for condition in generator.ifs.iter() {
+                self.visit_expr(condition);
+            }
+        }
+        for element in elements.iter() {
+            self.visit_expr(element);
+        }
+        self.scopes.pop();
+    }
+}
+
+impl<'a> Visitor<'a> for ScopeVisitor {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Import(_) | Stmt::ImportFrom(_) => self.imports.push(stmt.range()),
+            Stmt::FunctionDef(func) => {
+                for decorator in func.decorator_list.iter() {
+                    self.visit_decorator(decorator);
+                }
+                self.visit_defaults(&func.parameters);
+                let mut scope = Scope::of_body(false, &func.body);
+                scope.add_parameters(&func.parameters);
+                self.scopes.push(scope);
+                for stmt in func.body.iter() {
+                    self.visit_stmt(stmt);
+                }
+                self.scopes.pop();
+            },
+            Stmt::ClassDef(class) => {
+                for decorator in class.decorator_list.iter() {
+                    self.visit_decorator(decorator);
+                }
+                if let Some(arguments) = class.arguments.as_ref() {
+                    self.visit_arguments(arguments);
+                }
+                self.scopes.push(Scope::of_body(true, &class.body));
+                for stmt in class.body.iter() {
+                    self.visit_stmt(stmt);
+                }
+                self.scopes.pop();
+            },
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(name) if name.ctx == ExprContext::Load => {
+                if self.resolve(name.id.as_str()) == Binding::Unbound {
+                    self.unbound.push((name.id.to_string(), name.range));
+                }
+            },
+            Expr::Attribute(attribute) => {
+                if attribute.attr.starts_with("__") {
+                    self.private_attributes.push((attribute.attr.to_string(), attribute.attr.range));
+                } else if let Expr::Name(base) = attribute.value.as_ref() {
+                    if RECORDSET_VARIABLES.contains(&base.id.as_str()) && self.resolve(base.id.as_str()) == Binding::Context {
+                        self.model_attributes.push((attribute.attr.to_string(), attribute.attr.range));
+                    }
+                }
+                walk_expr(self, expr);
+            },
+            Expr::Lambda(lambda) => {
+                let mut scope = Scope::default();
+                if let Some(parameters) = lambda.parameters.as_ref() {
+                    self.visit_defaults(parameters);
+                    scope.add_parameters(parameters);
+                }
+                self.scopes.push(scope);
+                self.visit_expr(&lambda.body);
+                self.scopes.pop();
+            },
+            Expr::ListComp(comp) => self.visit_comprehension_scope(&[&*comp.elt], &comp.generators),
+            Expr::SetComp(comp) => self.visit_comprehension_scope(&[&*comp.elt], &comp.generators),
+            Expr::Generator(comp) => self.visit_comprehension_scope(&[&*comp.elt], &comp.generators),
+            Expr::DictComp(comp) => self.visit_comprehension_scope(&[&*comp.key, &*comp.value], &comp.generators),
+            _ => walk_expr(self, expr),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use lsp_types::{Position, Range};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+/* The scanner shared by the features reading the data files. The xml files are scanned with regexes instead of being parsed:
+the files being edited are often invalid, and the features only need the records, their fields and their attributes */
+
+pub(crate) static COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<!--.*?-->"#).unwrap());
+pub(crate) static RECORD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<record\b([^>]*?)(?:/>|>(.*?)</record>)"#).unwrap());
+pub(crate) static FIELD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<field\b([^>]*?)(?:/>|>(.*?)</field>)"#).unwrap());
+static ATTRIBUTE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+static REF_ATTRIBUTE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(?:ref|inherit_id|parent|action)\s*=\s*["']([\w.]+)["']"#).unwrap());
+pub(crate) static GROUPS_ATTRIBUTE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\bgroups\s*=\s*["']([^"']+)["']"#).unwrap());
+//env.ref() in the code of server actions is only called when the action runs
+static REF_CALL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?:^|[^.\w])ref\(\s*["']([\w.]+)["']\s*\)"#).unwrap());
+static ACTION_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"%\(([\w.]+)\)d"#).unwrap());
+
+/* A value of a data file, with its offset in the file */
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlValue {
+    pub value: String,
+    pub offset: usize,
+}
+
+impl XmlValue {
+
+    /* Range of the value, with offsets in place of the positions, like the other diagnostics before being published */
+    pub(crate) fn range(&self) -> Range {
+        Range::new(Position::new(self.offset as u32, 0), Position::new((self.offset + self.value.len()) as u32, 0))
+    }
+
+    /* The value found in a part of the file starting at the given offset, with its offset in the file */
+    pub(crate) fn shifted(self, start: usize) -> XmlValue {
+        XmlValue { offset: self.offset + start, ..self }
+    }
+}
+
+/* Lines of a file, to give the line of an offset without scanning the content again */
+pub struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+
+    pub fn new(content: &str) -> LineIndex {
+        LineIndex { starts: std::iter::once(0).chain(content.match_indices('\n').map(|(index, _)| index + 1)).collect() }
+    }
+
+    /* 0-based line of the offset, like lsp positions */
+    pub fn line_of(&self, offset: usize) -> u32 {
+        (self.starts.partition_point(|start| *start <= offset) - 1) as u32
+    }
+
+    /* Lsp position of the offset in the content the index is built on, with the character counted in utf-16 code units */
+    pub fn position_of(&self, content: &str, offset: usize) -> Position {
+        let line = self.line_of(offset);
+        let start = self.starts[line as usize];
+        Position::new(line, content[start..offset].encode_utf16().count() as u32)
+    }
+}
+
+/* Blank the comments of a xml file, keeping the offsets and the lines of the rest of the content */
+pub fn strip_comments(content: &str) -> Cow<str> {
+    COMMENT_RE.replace_all(content, |c: &Captures| c[0].bytes().map(|b| if b == b'\n' { '\n' } else { ' ' }).collect::<String>())
+}
+
+/* Value of an attribute of a tag, from the text between the tag name and its end. The offset is relative to this text */
+pub fn attribute(attrs: &str, name: &str) -> Option<XmlValue> {
+    ATTRIBUTE_RE.captures_iter(attrs)
+        .find(|c| &c[1] == name)
+        .and_then(|c| c.iter().skip(2).flatten().next())
+        .map(|value| XmlValue { value: value.as_str().to_string(), offset: value.start() })
+}
+
+/* All the attributes of a tag by name, to read several of them without scanning the tag again */
+pub fn attributes(attrs: &str) -> HashMap<&str, XmlValue> {
+    ATTRIBUTE_RE.captures_iter(attrs)
+        .filter_map(|c| {
+            let value = c.iter().skip(2).flatten().next()?;
+            Some((c.get(1).unwrap().as_str(), XmlValue { value: value.as_str().to_string(), offset: value.start() }))
+        })
+        .collect()
+}
+
+/* Trimmed text of a <field> matched by FIELD_RE, with its offset in the text given to the regex */
+pub fn field_text(field: &Captures) -> Option<XmlValue> {
+    let text = field.get(2)?;
+    let value = text.as_str().trim();
+    let leading = text.as_str().len() - text.as_str().trim_start().len();
+    (!value.is_empty()).then(|| XmlValue { value: value.to_string(), offset: text.start() + leading })
+}
+
+/* The xml ids referenced by the tags of a data file while it is loaded, as written in the file: the attributes referencing a
+record, the ref() of the evaluated attributes, the %(xml_id)d of the actions and the groups of the elements */
+pub fn xml_id_references(content: &str) -> Vec<&str> {
+    let mut references: Vec<&str> = REF_ATTRIBUTE_RE.captures_iter(content)
+        .chain(REF_CALL_RE.captures_iter(content))
+        .chain(ACTION_ID_RE.captures_iter(content))
+        .map(|reference| reference.get(1).unwrap().as_str())
+        .collect();
+    for groups in GROUPS_ATTRIBUTE_RE.captures_iter(content) {
+        references.extend(group_references(groups.get(1).unwrap().as_str()));
+    }
+    references
+}
+
+/* The groups of a list like groups="base.group_user,-base.group_portal" */
+pub fn group_references(groups: &str) -> impl Iterator<Item = &str> {
+    groups.split(',').map(|group| group.trim().trim_start_matches(['-', '!'])).filter(|group| !group.is_empty())
+}
@@ -0,0 +1,167 @@
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::hash::{Hash, Hasher};
+use std::panic::AssertUnwindSafe;
+use std::sync::{Mutex, MutexGuard};
+
+use once_cell::sync::Lazy;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+use crate::core::config::Config;
+
+#[cfg(feature = "server")]
+use {
+    std::path::PathBuf,
+    std::sync::TryLockError,
+    std::time::{SystemTime, UNIX_EPOCH},
+    std::{env, fs},
+    crossbeam_channel::Sender,
+    lsp_server::{Message, Notification},
+    serde_json::json,
+    tracing_panic::panic_hook,
+    crate::constants::EXTENSION_VERSION,
+};
+
+/* Number of log lines kept in memory to be attached to crash reports */
+const CRASH_LOG_LINES: usize = 200;
+
+#[derive(Debug, Default)]
+#[cfg_attr(not(feature = "server"), allow(dead_code))] //only read by the panic hook
+struct CrashContext {
+    config_hash: Option<u64>,
+    current_file: Option<String>,
+    logs: VecDeque<String>,
+}
+
+static CRASH_CONTEXT: Lazy<Mutex<CrashContext>> = Lazy::new(|| Mutex::new(CrashContext::default()));
+
+thread_local! {
+    //true while running code whose panics are caught and handled, like the build steps of a file (see Quarantine)
+    static RECOVERABLE: Cell<bool> = Cell::new(false);
+}
+
+/* Collect what is needed to make a crash actionable (last log lines, configuration, file being processed), and when the
+server panics, write it in a crash report file and send it to the client with the Odoo/displayCrashNotification notification.
+As a panic can occur while the SyncOdoo is locked, this context is kept outside of it. */
+pub struct CrashReport {}
+
+impl CrashReport {
+
+    fn context() -> MutexGuard<'static, CrashContext> {
+        CRASH_CONTEXT.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn set_config(config: &Config) {
+        let mut hasher = DefaultHasher::new();
+        format!("{:?}", config).hash(&mut hasher);
+        CrashReport::context().config_hash = Some(hasher.finish());
+    }
+
+    pub fn set_current_file(step: &str, path: Option<&String>) {
+        CrashReport::context().current_file = path.map(|path| format!("{} - {}", step, path));
+    }
+
+    /* Run the function, and return the message of its panic if it panics. These panics are handled by the caller: they are
+    logged, but no crash report is written nor sent to the client */
+    pub fn catch_recoverable<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+        let previous = RECOVERABLE.with(|recoverable| recoverable.replace(true));
+        let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+        RECOVERABLE.with(|recoverable| recoverable.set(previous));
+        result.map_err(|payload| {
+            payload.downcast_ref::<&str>().map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or(String::from("unknown panic"))
+        })
+    }
+
+    #[cfg(feature = "server")]
+    pub fn install_panic_hook(sender: Sender<Message>, log_dir: String) {
+        std::panic::set_hook(Box::new(move |panic_info| {
+            panic_hook(panic_info);
+            if RECOVERABLE.with(|recoverable| recoverable.get()) {
+                return;
+            }
+            //do not wait on the lock: the panic could have been raised while it was held
+            let context = match CRASH_CONTEXT.try_lock() {
+                Ok(context) => Some(context),
+                Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            };
+            let config_hash = context.as_ref().and_then(|c| c.config_hash).map(|hash| format!("{:016x}", hash));
+            let current_file = context.as_ref().and_then(|c| c.current_file.clone());
+            let logs: Vec<String> = context.as_ref().map(|c| c.logs.iter().cloned().collect()).unwrap_or_default();
+            drop(context);
+            let crash_info = format!("{panic_info}");
+            let crash_report = CrashReport::write_report(&log_dir, &crash_info, &config_hash, &current_file, &logs);
+            let _ = sender.send(Message::Notification(Notification{
+                method: "Odoo/displayCrashNotification".to_string(),
+                params: json!({
+                    "crashInfo": crash_info,
+                    "pid": std::process::id(),
+                    "version": EXTENSION_VERSION,
+                    "configHash": config_hash,
+                    "currentFile": current_file,
+                    "logs": logs,
+                    "crashReport": crash_report,
+                })
+            }));
+        }));
+    }
+
+    #[cfg(feature = "server")]
+    fn write_report(log_dir: &String, crash_info: &String, config_hash: &Option<String>, current_file: &Option<String>, logs: &Vec<String>) -> Option<String> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let path = PathBuf::from(log_dir).join(format!("crash_report_{}_{}.txt", std::process::id(), timestamp));
+        let mut content = String::new();
+        let _ = writeln!(content, "Server version: {}", EXTENSION_VERSION);
+        let _ = writeln!(content, "Operating system: {}", env::consts::OS);
+        let _ = writeln!(content, "Pid: {}", std::process::id());
+        let _ = writeln!(content, "Config hash: {}", config_hash.as_deref().unwrap_or("not loaded"));
+        let _ = writeln!(content, "Current file: {}", current_file.as_deref().unwrap_or("none"));
+        let _ = writeln!(content, "\n{}", crash_info);
+        let _ = writeln!(content, "\n{}", std::backtrace::Backtrace::force_capture());
+        let _ = writeln!(content, "\nLast {} log lines:", logs.len());
+        for line in logs.iter() {
+            let _ = writeln!(content, "{}", line);
+        }
+        match fs::write(&path, content) {
+            Ok(_) => Some(path.to_string_lossy().to_string()),
+            Err(_) => None
+        }
+    }
+}
+
+/* Tracing layer keeping the last log lines in memory for the crash reports */
+pub struct LogBufferLayer {}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = LogLineVisitor { line: String::new() };
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let line = format!("{} {}: {}", metadata.level(), metadata.target(), visitor.line);
+        let mut context = CrashReport::context();
+        if context.logs.len() >= CRASH_LOG_LINES {
+            context.logs.pop_front();
+        }
+        context.logs.push_back(line);
+    }
+}
+
+struct LogLineVisitor {
+    line: String,
+}
+
+impl Visit for LogLineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.line, "{:?}", value);
+        } else {
+            let _ = write!(self.line, " {}={:?}", field.name(), value);
+        }
+    }
+}
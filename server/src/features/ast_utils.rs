@@ -1,13 +1,17 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::cell::RefCell;
 use crate::core::evaluation::{AnalyzeAstResult, Context, ContextValue, Evaluation, ExprOrIdent};
 use crate::core::symbols::symbol::Symbol;
 use crate::core::file_mgr::FileInfo;
+use crate::core::manifest_assets::AssetPath;
+use crate::constants::SymType;
+use crate::utils::PathSanitizer as _;
 use crate::threads::SessionInfo;
 use crate::S;
 use ruff_python_ast::visitor::{Visitor, walk_expr, walk_stmt, walk_alias, walk_except_handler, walk_parameter, walk_keyword, walk_pattern_keyword, walk_type_param, walk_pattern};
-use ruff_python_ast::{Expr, Stmt, Alias, ExceptHandler, Parameter, Keyword, PatternKeyword, TypeParam, Pattern};
+use ruff_python_ast::{Comprehension, Expr, ExprCall, Stmt, Alias, ExceptHandler, Parameter, Keyword, PatternKeyword, TypeParam, Pattern};
 use ruff_text_size::{Ranged, TextRange, TextSize};
 use tracing::warn;
 
@@ -60,6 +64,125 @@ impl AstUtils {
         }
     }
 
+    /* Given the ast of a manifest, return the name and the range of the 'depends' entry under the offset, if any */
+    pub fn find_manifest_depend_at(ast: &Vec<Stmt>, offset: u32) -> Option<(String, TextRange)> {
+        let offset = TextSize::new(offset);
+        let dict = ast.get(0)?.as_expr_stmt()?.value.as_dict_expr()?;
+        for item in dict.items.iter() {
+            let Some(Expr::StringLiteral(key)) = item.key.as_ref() else {
+                continue;
+            };
+            if key.value.to_str() != "depends" {
+                continue;
+            }
+            let list = item.value.as_list_expr()?;
+            for depend in list.elts.iter() {
+                if let Expr::StringLiteral(depend) = depend {
+                    if depend.range.contains_inclusive(offset) {
+                        return Some((depend.value.to_string(), depend.range));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /* Find the path of the 'assets' key of a manifest at the given offset */
+    pub fn find_manifest_asset_at(ast: &Vec<Stmt>, offset: u32) -> Option<AssetPath> {
+        let offset = TextSize::new(offset);
+        let dict = ast.get(0)?.as_expr_stmt()?.value.as_dict_expr()?;
+        for item in dict.items.iter() {
+            let Some(Expr::StringLiteral(key)) = item.key.as_ref() else {
+                continue;
+            };
+            if key.value.to_str() != "assets" {
+                continue;
+            }
+            for bundle in item.value.as_dict_expr()?.items.iter() {
+                let Some(paths) = bundle.value.as_list_expr() else {
+                    continue;
+                };
+                for entry in paths.elts.iter() {
+                    if let Some(asset) = AssetPath::from_entry(entry).into_iter().find(|asset| asset.range.contains_inclusive(offset)) {
+                        return Some(asset);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /* Return all the calls of the given statements, including the nested ones */
+    pub fn collect_calls<'a>(stmts: &'a Vec<Stmt>) -> Vec<&'a ExprCall> {
+        let mut visitor = CallCollectorVisitor {
+            calls: vec![],
+        };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.calls
+    }
+
+    /* Return the innermost call with its arguments at the offset. Only the statements and expressions containing the offset
+    are visited */
+    pub fn find_call_at<'a>(stmts: &'a Vec<Stmt>, offset: TextSize) -> Option<&'a ExprCall> {
+        let stmt = stmts.iter().find(|stmt| stmt.range().contains_inclusive(offset))?;
+        let mut visitor = CallFinderVisitor {
+            offset,
+            call: None,
+        };
+        visitor.visit_stmt(stmt);
+        visitor.call
+    }
+
+    /* Return the comprehensions of a statement, outer ones first, with the name of their scope, their range and their generators */
+    pub fn collect_comprehensions<'a>(stmt: &'a Stmt) -> Vec<(&'static str, TextRange, &'a [Comprehension])> {
+        let mut visitor = ComprehensionCollectorVisitor {
+            comprehensions: vec![],
+        };
+        visitor.visit_stmt(stmt);
+        visitor.comprehensions
+    }
+
+    /* Find the declaration of a field (or any class attribute assigned with a call) in its class body and return the keywords of the call */
+    pub fn get_field_call_keywords(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<Vec<Keyword>> {
+        AstUtils::get_field_call(session, symbol).map(|call| call.arguments.keywords.to_vec())
+    }
+
+    /* Find the declaration of a field (or any class attribute assigned with a call) in its class body and return the call */
+    pub fn get_field_call(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<ExprCall> {
+        let var_range = symbol.borrow().range().clone();
+        let class = symbol.borrow().parent()?.upgrade()?;
+        let class_indexes = class.borrow().ast_indexes()?.clone();
+        if class_indexes.is_empty() {
+            return None;
+        }
+        let file_symbol = class.borrow().get_file()?.upgrade()?;
+        let mut path = file_symbol.borrow().paths().first()?.clone();
+        if file_symbol.borrow().typ() == SymType::PACKAGE {
+            path = PathBuf::from(path).join("__init__.py").sanitize() + file_symbol.borrow().as_package().i_ext().as_str();
+        }
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path)?;
+        let file_info = file_info.borrow();
+        let ast = file_info.ast.as_ref()?;
+        let class_stmt = AstUtils::find_stmt_from_ast(ast, &class_indexes).as_class_def_stmt()?;
+        for stmt in class_stmt.body.iter() {
+            let (targets, value): (Vec<&Expr>, &Expr) = match stmt {
+                Stmt::Assign(assign) => (assign.targets.iter().collect(), &assign.value),
+                Stmt::AnnAssign(assign) if assign.value.is_some() => (vec![&assign.target], assign.value.as_ref().unwrap()),
+                _ => continue
+            };
+            if !targets.iter().any(|target| target.range() == var_range) {
+                continue;
+            }
+            let Expr::Call(call) = value else {
+                return None;
+            };
+            return Some(call.clone());
+        }
+        None
+    }
+
     pub fn find_stmt_from_ast<'a>(ast: &'a Vec<Stmt>, indexes: &Vec<u16>) -> &'a Stmt {
         let mut stmt = ast.get(indexes[0] as usize).expect("index not found in ast");
         let mut i_index = 1;
@@ -124,6 +247,68 @@ impl AstUtils {
 
 }
 
+struct CallCollectorVisitor<'a> {
+    calls: Vec<&'a ExprCall>,
+}
+
+impl<'a> Visitor<'a> for CallCollectorVisitor<'a> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Call(call) = expr {
+            self.calls.push(call);
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct CallFinderVisitor<'a> {
+    offset: TextSize,
+    call: Option<&'a ExprCall>,
+}
+
+impl<'a> Visitor<'a> for CallFinderVisitor<'a> {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if stmt.range().contains_inclusive(self.offset) {
+            walk_stmt(self, stmt);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if !expr.range().contains_inclusive(self.offset) {
+            return;
+        }
+        if let Expr::Call(call) = expr {
+            if call.arguments.range.contains_inclusive(self.offset) {
+                self.call = Some(call);
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct ComprehensionCollectorVisitor<'a> {
+    comprehensions: Vec<(&'static str, TextRange, &'a [Comprehension])>,
+}
+
+impl<'a> Visitor<'a> for ComprehensionCollectorVisitor<'a> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        //scopes are named like in CPython
+        let comprehension = match expr {
+            Expr::ListComp(comp) => Some(("<listcomp>", comp.generators.as_slice())),
+            Expr::SetComp(comp) => Some(("<setcomp>", comp.generators.as_slice())),
+            Expr::DictComp(comp) => Some(("<dictcomp>", comp.generators.as_slice())),
+            Expr::Generator(comp) => Some(("<genexpr>", comp.generators.as_slice())),
+            _ => None,
+        };
+        if let Some((name, generators)) = comprehension {
+            self.comprehensions.push((name, expr.range(), generators));
+        }
+        walk_expr(self, expr);
+    }
+}
+
 pub struct ExprFinderVisitor<'a> {
     offset: TextSize,
     expr: Option<ExprOrIdent<'a>>,
@@ -0,0 +1,264 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::rc::Rc;
+
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse, Diagnostic, NumberOrString, Position, Range, TextEdit, Uri, WorkspaceEdit};
+use ruff_python_ast::{Alias, Expr, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::constants::*;
+use crate::core::file_mgr::{DiagnosticSuppressions, FileInfo, FileMgr};
+use crate::core::python_validator::PythonValidator;
+use crate::core::reports::ReportValidator;
+use crate::core::symbols::symbol::Symbol;
+use crate::core::views::{guess_comodel, UnknownViewFieldData};
+use crate::features::ast_utils::AstUtils;
+use crate::features::workspace_edit::WorkspaceEditBuilder;
+use crate::threads::SessionInfo;
+use crate::S;
+
+use super::model_source::ModelSourceFeature;
+
+pub struct CodeActionFeature {}
+
+impl CodeActionFeature {
+
+    pub fn get_code_actions(session: &mut SessionInfo, file_info: &Rc<RefCell<FileInfo>>, params: &CodeActionParams) -> Option<CodeActionResponse> {
+        let mut actions = vec![];
+        for diagnostic in params.context.diagnostics.iter() {
+            if diagnostic.source.as_deref() != Some(EXTENSION_NAME) {
+                continue;
+            }
+            let Some(NumberOrString::String(code)) = &diagnostic.code else {
+                continue;
+            };
+            if code == "OLS31201" {
+                if let Some(action) = CodeActionFeature::add_view_field(session, &params.text_document.uri, diagnostic) {
+                    actions.push(CodeActionOrCommand::CodeAction(action));
+                }
+            }
+            if let Some(action) = CodeActionFeature::suppress_on_line(file_info, &params.text_document.uri, diagnostic, code) {
+                actions.push(CodeActionOrCommand::CodeAction(action));
+            }
+            actions.push(CodeActionOrCommand::CodeAction(CodeActionFeature::suppress_in_file(&params.text_document.uri, diagnostic, code)));
+        }
+        if CodeActionFeature::is_requested(params, &CodeActionKind::SOURCE_ORGANIZE_IMPORTS) {
+            if let Some(action) = CodeActionFeature::organize_imports(session, file_info, &params.text_document.uri) {
+                actions.push(CodeActionOrCommand::CodeAction(action));
+            }
+        }
+        if actions.is_empty() {
+            return None;
+        }
+        Some(actions)
+    }
+
+    /* The client can restrict the actions to some kinds, like "source" for the source actions of the editor */
+    fn is_requested(params: &CodeActionParams, kind: &CodeActionKind) -> bool {
+        match &params.context.only {
+            Some(only) => only.iter().any(|requested| kind.as_str() == requested.as_str() || kind.as_str().starts_with(&format!("{}.", requested.as_str()))),
+            None => true,
+        }
+    }
+
+    /* Sort the imports at the top of a python file in the groups of the Odoo guidelines: python and external libraries, odoo,
+    the other Odoo modules, then the relative imports. The from imports of the same module are merged, with their names sorted.
+    Nothing is proposed if the imports are already organized, or if comments between them would be lost */
+    fn organize_imports(session: &mut SessionInfo, file_info: &Rc<RefCell<FileInfo>>, uri: &Uri) -> Option<CodeAction> {
+        let (range, new_text) = {
+            let file_info = file_info.borrow();
+            let ast = file_info.ast.as_ref()?;
+            let imports: Vec<&Stmt> = ast.iter()
+                .skip_while(|stmt| matches!(stmt, Stmt::Expr(expr) if matches!(*expr.value, Expr::StringLiteral(_)))) //docstring
+                .take_while(|stmt| matches!(stmt, Stmt::Import(_) | Stmt::ImportFrom(_)))
+                .collect();
+            let range = TextRange::new(imports.first()?.range().start(), imports.last()?.range().end());
+            let text = file_info.get_text(&range)?;
+            if text.contains('#') {
+                return None;
+            }
+            //lines by group, plain imports before from imports, then by module
+            let mut lines: BTreeMap<(u8, bool, String), BTreeSet<String>> = BTreeMap::new();
+            for stmt in imports.iter() {
+                match stmt {
+                    Stmt::Import(import) => {
+                        for alias in import.names.iter() {
+                            let group = CodeActionFeature::import_group(alias.name.as_str(), 0);
+                            lines.entry((group, false, CodeActionFeature::alias_text(alias))).or_default();
+                        }
+                    },
+                    Stmt::ImportFrom(import) => {
+                        let module = format!("{}{}", ".".repeat(import.level as usize), import.module.as_ref().map(|module| module.as_str()).unwrap_or(""));
+                        let group = CodeActionFeature::import_group(&module, import.level);
+                        lines.entry((group, true, module)).or_default().extend(import.names.iter().map(CodeActionFeature::alias_text));
+                    },
+                    _ => {},
+                }
+            }
+            let mut groups: BTreeMap<u8, Vec<String>> = BTreeMap::new();
+            for ((group, from, module), names) in lines.into_iter() {
+                let line = match from {
+                    true => format!("from {} import {}", module, names.into_iter().collect::<Vec<String>>().join(", ")),
+                    false => format!("import {}", module),
+                };
+                groups.entry(group).or_default().push(line);
+            }
+            let new_text = groups.into_values().map(|lines| lines.join("\n")).collect::<Vec<String>>().join("\n\n");
+            if new_text == text {
+                return None;
+            }
+            (Range::new(file_info.offset_to_position(range.start().to_usize()), file_info.offset_to_position(range.end().to_usize())), new_text)
+        };
+        let mut builder = WorkspaceEditBuilder::new("Organize imports");
+        builder.add_edit(&FileMgr::uri2pathname(uri.as_str()), TextEdit { range, new_text });
+        Some(CodeAction {
+            title: S!("Organize imports"),
+            kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+            edit: Some(builder.build(session)),
+            ..Default::default()
+        })
+    }
+
+    /* 0 for python and external libraries, 1 for odoo, 2 for the other Odoo modules and 3 for the relative imports */
+    fn import_group(module: &str, level: u32) -> u8 {
+        if level > 0 {
+            3
+        } else if module == "odoo.addons" || module.starts_with("odoo.addons.") {
+            2
+        } else if module == "odoo" || module.starts_with("odoo.") {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn alias_text(alias: &Alias) -> String {
+        match &alias.asname {
+            Some(asname) => format!("{} as {}", alias.name, asname),
+            None => alias.name.to_string(),
+        }
+    }
+
+    /* Add the code to the suppression comment of the line, or add a new one at the end of the line */
+    fn suppress_on_line(file_info: &Rc<RefCell<FileInfo>>, uri: &Uri, diagnostic: &Diagnostic, code: &String) -> Option<CodeAction> {
+        let line_number = diagnostic.range.start.line;
+        let line = file_info.borrow().get_line(line_number)?;
+        let existing_directive = line.find(SUPPRESSION_COMMENT)
+            .and_then(|index| DiagnosticSuppressions::parse_directive(&line[index + SUPPRESSION_COMMENT.len()..]))
+            .filter(|(file_scope, _)| !file_scope);
+        let new_text = match existing_directive {
+            Some((_, codes)) if codes.contains(code) => return None,
+            Some(_) => format!(",{}", code),
+            None => format!("  {} disable={}", SUPPRESSION_COMMENT, code),
+        };
+        let end_of_line = Position::new(line_number, line.chars().count() as u32);
+        Some(CodeActionFeature::build_action(format!("Suppress {} on this line", code), uri, diagnostic, TextEdit {
+            range: Range::new(end_of_line, end_of_line),
+            new_text,
+        }))
+    }
+
+    fn suppress_in_file(uri: &Uri, diagnostic: &Diagnostic, code: &String) -> CodeAction {
+        let start_of_file = Position::new(0, 0);
+        CodeActionFeature::build_action(format!("Suppress {} in this file", code), uri, diagnostic, TextEdit {
+            range: Range::new(start_of_file, start_of_file),
+            new_text: format!("{} disable-file={}\n", SUPPRESSION_COMMENT, code),
+        })
+    }
+
+    /* Declare the field used by a view at the end of the class of its model in the module of the view, with the type that the
+    view expects. Relational fields are only declared if their comodel is found from their name, and the One2many fields if
+    the comodel has a Many2one field on the model to use as inverse */
+    fn add_view_field(session: &mut SessionInfo, uri: &Uri, diagnostic: &Diagnostic) -> Option<CodeAction> {
+        let data: UnknownViewFieldData = serde_json::from_value(diagnostic.data.clone()?).ok()?;
+        let module_name = ReportValidator::module_of_path(session, &FileMgr::uri2pathname(uri.as_str()))?;
+        let model = session.sync_odoo.models.get(&data.model).cloned()?;
+        let class = model.borrow().get_all_symbols().filter(|class| {
+            class.borrow().find_module().is_some_and(|module| module.borrow().as_module_package().dir_name == module_name)
+        }).last()?;
+        let path = ModelSourceFeature::file_path(&class)?;
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path)?;
+        let file_info = file_info.borrow();
+        let class_end = file_info.offset_to_position(class.borrow().range().end().to_usize());
+        //the members of the class give the indentation of its body
+        let indent = class.borrow().all_symbols()
+            .filter(|member| !member.borrow().is_comprehension())
+            .map(|member| file_info.offset_to_position(member.borrow().range().start().to_usize()))
+            .find(|position| position.line > file_info.offset_to_position(class.borrow().range().start().to_usize()).line)
+            .map(|position| position.character)
+            .unwrap_or(4);
+        let mut arguments = vec![];
+        if matches!(data.field_type.as_str(), "Many2one" | "Many2many" | "One2many") {
+            let comodel = guess_comodel(session.sync_odoo.models.keys(), &data.field)?;
+            arguments.push(format!("'{}'", comodel));
+            if data.field_type == "One2many" {
+                arguments.push(format!("'{}'", CodeActionFeature::find_inverse_name(session, &comodel, &data.model)?));
+            }
+        }
+        arguments.push(format!("string=\"{}\"", CodeActionFeature::field_label(&data.field)));
+        let edit = TextEdit {
+            range: Range::new(class_end, class_end),
+            new_text: format!("\n{}{} = fields.{}({})", " ".repeat(indent as usize), data.field, data.field_type, arguments.join(", ")),
+        };
+        Some(CodeAction {
+            title: format!("Add field {} to model {}", data.field, data.model),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(FileMgr::pathname2uri(&path), vec![edit])])),
+                ..Default::default()
+            }),
+            is_preferred: Some(true),
+            ..Default::default()
+        })
+    }
+
+    /* The Many2one field of the comodel on the model, used as inverse of a One2many field. The first one in the alphabetical
+    order is taken if there are several */
+    fn find_inverse_name(session: &mut SessionInfo, comodel: &String, model: &str) -> Option<String> {
+        let comodel = session.sync_odoo.models.get(comodel).cloned()?;
+        let classes: Vec<Rc<RefCell<Symbol>>> = comodel.borrow().get_all_symbols().collect();
+        let mut candidates = vec![];
+        for class in classes.iter() {
+            let members: Vec<Rc<RefCell<Symbol>>> = class.borrow().all_symbols().collect();
+            for member in members.iter() {
+                if !PythonValidator::get_field_info(session, member).is_some_and(|(field_type, _)| field_type == "Many2one") {
+                    continue;
+                }
+                let Some(call) = AstUtils::get_field_call(session, member) else {
+                    continue;
+                };
+                let target = call.arguments.args.first()
+                    .or_else(|| call.arguments.keywords.iter().find(|keyword| keyword.arg.as_ref().is_some_and(|arg| arg.as_str() == "comodel_name")).map(|keyword| &keyword.value))
+                    .and_then(|target| target.as_string_literal_expr())
+                    .map(|target| target.value.to_string());
+                if target.as_deref() == Some(model) {
+                    candidates.push(member.borrow().name().clone());
+                }
+            }
+        }
+        candidates.sort();
+        candidates.into_iter().next()
+    }
+
+    /* The label Odoo would give to the field: partner_id is displayed as Partner */
+    fn field_label(name: &str) -> String {
+        let name = name.strip_suffix("_ids").or(name.strip_suffix("_id")).unwrap_or(name);
+        let label = name.replace('_', " ");
+        let mut chars = label.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    }
+
+    fn build_action(title: String, uri: &Uri, diagnostic: &Diagnostic, edit: TextEdit) -> CodeAction {
+        CodeAction {
+            title,
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
@@ -1,21 +1,38 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, rc::Rc};
 use lsp_types::{CompletionItem, CompletionItemKind, CompletionItemLabelDetails, CompletionList, CompletionResponse, MarkupContent};
 use ruff_python_ast::{ExceptHandler, Expr, ExprAttribute, ExprIf, ExprName, ExprSubscript, ExprYield, Stmt, StmtGlobal, StmtImport, StmtImportFrom, StmtNonlocal};
 use ruff_text_size::Ranged;
+use serde::{Deserialize, Serialize};
 
 use crate::constants::SymType;
+use crate::core::domain_validator::MAGIC_FIELDS;
 use crate::core::evaluation::Evaluation;
 use crate::core::import_resolver;
 use crate::core::python_arch_eval_hooks::PythonArchEvalHooks;
+use crate::core::python_utils;
+use crate::core::read_group::{self, ReadGroupCall};
+use crate::core::reference_fields;
+use crate::core::settings::{collect_config_parameter_keys, CONFIG_PARAMETER_METHODS};
 use crate::core::symbols::module_symbol::ModuleSymbol;
+use crate::core::xml_code::{ServerActionCode, EVAL_CONTEXT, RECORDSET_VARIABLES};
 use crate::threads::SessionInfo;
 use crate::S;
 use crate::core::symbols::symbol::Symbol;
 use crate::core::file_mgr::FileInfo;
 
+use super::ast_utils::AstUtils;
 use super::hover::HoverFeature;
+use super::model_source::ModelSourceFeature;
+use super::snippets::SnippetFeature;
 
 
+/* Data of the completion items of fields, to build their documentation on completionItem/resolve */
+#[derive(Serialize, Deserialize)]
+struct FieldResolveData {
+    model: String,
+    field: String,
+}
+
 #[allow(non_camel_case_types)]
 pub enum ExpectedType {
     MODEL_NAME,
@@ -35,7 +52,149 @@ impl CompletionFeature {
         let offset = file_info.borrow().position_to_offset(line, character);
         let file_info =  file_info.borrow();
         let ast = file_info.ast.as_ref().unwrap();
-        return complete_vec_stmt(ast, session, file_symbol, offset)
+        let response = complete_vec_stmt(ast, session, file_symbol, offset);
+        let snippets = SnippetFeature::get_snippets(session, &file_info, line, character);
+        if snippets.is_empty() {
+            return response;
+        }
+        match response {
+            Some(CompletionResponse::List(mut list)) => {
+                list.items.extend(snippets);
+                Some(CompletionResponse::List(list))
+            },
+            Some(CompletionResponse::Array(mut items)) => {
+                items.extend(snippets);
+                Some(CompletionResponse::Array(items))
+            },
+            None => Some(CompletionResponse::List(CompletionList {
+                is_incomplete: false,
+                items: snippets,
+            })),
+        }
+    }
+}
+
+impl CompletionFeature {
+
+    /* Add the documentation of the items that were sent without it */
+    pub fn resolve(session: &mut SessionInfo, mut item: CompletionItem) -> CompletionItem {
+        let Some(data) = item.data.clone().and_then(|data| serde_json::from_value::<FieldResolveData>(data).ok()) else {
+            return item;
+        };
+        if let Some(documentation) = CompletionFeature::build_field_documentation(session, &data.model, &data.field) {
+            item.documentation = Some(lsp_types::Documentation::MarkupContent(MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: documentation,
+            }));
+        }
+        item
+    }
+
+    /* Description of the field, followed by the modules declaring it in the order they are loaded, its compute or related
+    as given by the last declaration, and an example of usage */
+    fn build_field_documentation(session: &mut SessionInfo, model_name: &String, field_name: &String) -> Option<String> {
+        let model = session.sync_odoo.models.get(model_name).cloned()?;
+        let mut classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+        session.sync_odoo.sort_by_module_rank(&mut classes);
+        let declarations: Vec<(String, Rc<RefCell<Symbol>>)> = classes.iter().filter_map(|class| {
+            let field = class.borrow().get_sub_symbol(field_name, u32::MAX).last().cloned()?;
+            let module = class.borrow().find_module().map(|module| module.borrow().as_module_package().dir_name.clone()).unwrap_or(S!("odoo"));
+            Some((module, field))
+        }).collect();
+        let first_declaration = declarations.first()?.1.clone();
+        let mut value = HoverFeature::build_markdown_description(session, &vec![Evaluation::eval_from_symbol(&Rc::downgrade(&first_declaration))]);
+        value += format!("  \n***  \nDefined in `{}`", declarations[0].0).as_str();
+        if declarations.len() > 1 {
+            let overrides: Vec<String> = declarations[1..].iter().map(|(module, _)| format!("`{}`", module)).collect();
+            value += format!(", overridden in {}", overrides.join(", ")).as_str();
+        }
+        let mut compute = None;
+        let mut related = None;
+        let mut store = None;
+        for (_, field) in declarations.iter() {
+            for keyword in AstUtils::get_field_call_keywords(session, field).unwrap_or_default() {
+                match (keyword.arg.as_ref().map(|arg| arg.as_str()), &keyword.value) {
+                    (Some("compute"), Expr::StringLiteral(method)) => compute = Some(method.value.to_string()),
+                    (Some("related"), Expr::StringLiteral(path)) => related = Some(path.value.to_string()),
+                    (Some("store"), Expr::BooleanLiteral(stored)) => store = Some(stored.value),
+                    _ => {}
+                }
+            }
+        }
+        let stored = if store == Some(true) {" (stored)"} else {""};
+        if let Some(compute) = compute {
+            value += format!("  \nComputed by `{}`{}", compute, stored).as_str();
+        } else if let Some(related) = related {
+            value += format!("  \nRelated to `{}`{}", related, stored).as_str();
+        }
+        let evaluation = first_declaration.borrow().evaluations().and_then(|evals| evals.first().cloned());
+        let field_type = evaluation.and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade())
+            .map(|typ| typ.borrow().name().clone())
+            .unwrap_or_default();
+        let usage = match field_type.as_str() {
+            "Many2one" => format!("record.{0}.display_name\nrecords.mapped('{0}')", field_name),
+            "One2many" | "Many2many" => format!("record.{0}.ids\nrecords.mapped('{0}')", field_name),
+            "Boolean" => format!("record.{0}\nrecords.filtered('{0}')", field_name),
+            _ => format!("record.{0}\nrecords.mapped('{0}')", field_name),
+        };
+        value += format!("  \n***  \n```python\n{}\nself.env['{}'].search([('{}', '=', value)])\n```", usage, model_name, field_name).as_str();
+        Some(value)
+    }
+
+    /* Completion in the python code of the server actions and crons of a xml file: the variables of their evaluation context,
+    and the members of the model of the action after "record.", "records." or "model." */
+    pub fn autocomplete_server_action(session: &mut SessionInfo,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32
+    ) -> Option<CompletionResponse> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let text = file_info.borrow().get_content()?;
+        let code = ServerActionCode::extract(session, &text).into_iter().find(|code| code.to_source_offset(offset).is_some())?;
+        let (base, typed) = code.attribute_at(code.to_source_offset(offset).unwrap(), true);
+        let mut items = vec![];
+        match base {
+            None => {
+                for name in EVAL_CONTEXT.iter() {
+                    if let Some(quality) = CompletionScore::match_quality(name, &typed) {
+                        items.push(CompletionItem {
+                            label: name.to_string(),
+                            kind: Some(CompletionItemKind::VARIABLE),
+                            sort_text: Some(format!("{}{}{}", CompletionScore::MODEL_MEMBER, quality, name)),
+                            ..Default::default()
+                        });
+                    }
+                }
+            },
+            Some(base) if RECORDSET_VARIABLES.contains(&base.as_str()) => {
+                let model = session.sync_odoo.models.get(code.model.as_ref()?).cloned()?;
+                let mut classes: Vec<(Rc<RefCell<Symbol>>, bool)> = model.borrow().get_all_symbols().map(|class| (class, false)).collect();
+                let base_model = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("models")], vec![S!("BaseModel")]), u32::MAX);
+                classes.extend(base_model.into_iter().map(|class| (class, true)));
+                let mut seen = HashSet::new();
+                for (class, inherited) in classes.iter() {
+                    let all_symbols: Vec<Rc<RefCell<Symbol>>> = class.borrow().all_symbols().filter(|sym| !sym.borrow().is_comprehension()).collect();
+                    for sym in all_symbols {
+                        let name = sym.borrow().name().clone();
+                        let Some(quality) = CompletionScore::match_quality(&name, &typed) else {
+                            continue;
+                        };
+                        if !seen.insert(name) {
+                            continue;
+                        }
+                        let category = CompletionScore::member_category(session, class, &sym, *inherited);
+                        let mut item = build_completion_item_from_symbol(session, &sym);
+                        item.sort_text = Some(CompletionScore::sort_text(category, quality, &sym));
+                        items.push(item);
+                    }
+                }
+            },
+            Some(_) => return None,
+        }
+        Some(CompletionResponse::List(CompletionList {
+            is_incomplete: false,
+            items
+        }))
     }
 }
 
@@ -136,7 +295,10 @@ fn complete_delete_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>
 
 fn complete_assign_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, stmt_assign: &ruff_python_ast::StmtAssign, offset: usize) -> Option<CompletionResponse> {
     if offset > stmt_assign.value.range().start().to_usize() && offset <= stmt_assign.value.range().end().to_usize() {
-        return complete_expr( &stmt_assign.value, session, file, offset, false, &vec![]);
+        //_inherit = 'x' or _inherit = ['x', 'y']: the strings are model names
+        let is_inherit = stmt_assign.targets.len() == 1 && stmt_assign.targets[0].as_name_expr().is_some_and(|name| name.id.as_str() == "_inherit");
+        let expected_type = if is_inherit { vec![ExpectedType::MODEL_NAME] } else { vec![] };
+        return complete_expr( &stmt_assign.value, session, file, offset, false, &expected_type);
     }
     None
 }
@@ -357,7 +519,7 @@ fn complete_expr(expr: &Expr, session: &mut SessionInfo, file: &Rc<RefCell<Symbo
         Expr::YieldFrom(_) => None,
         Expr::Compare(expr_compare) => complete_compare(session, file, expr_compare, offset, is_param, expected_type),
         Expr::Call(expr_call) => complete_call(session, file, expr_call, offset, is_param, expected_type),
-        Expr::FString(_) => None,
+        Expr::FString(_) => None, //f-strings are completed by the call they are given to, see complete_call
         Expr::StringLiteral(expr_string_literal) => complete_string_literal(session, file, expr_string_literal, offset, is_param, expected_type),
         Expr::BytesLiteral(_) => None,
         Expr::NumberLiteral(_) => None,
@@ -369,7 +531,7 @@ fn complete_expr(expr: &Expr, session: &mut SessionInfo, file: &Rc<RefCell<Symbo
         Expr::Starred(_) => None,
         Expr::Name(expr_name) => complete_name(session, file, expr_name, offset, is_param, expected_type),
         Expr::List(expr_list) => complete_list(session, file, expr_list, offset, is_param, expected_type),
-        Expr::Tuple(_) => None,
+        Expr::Tuple(expr_tuple) => complete_tuple(session, file, expr_tuple, offset, is_param, expected_type),
         Expr::Slice(_) => None,
         Expr::IpyEscapeCommand(_) => None,
     }
@@ -463,6 +625,45 @@ fn complete_call(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_cal
     if offset > expr_call.func.range().start().to_usize() && offset <= expr_call.func.range().end().to_usize() {
         return complete_expr( &expr_call.func, session, file, offset, is_param, expected_type);
     }
+    if let (Expr::Attribute(method), Some(arg)) = (expr_call.func.as_ref(), expr_call.arguments.args.first()) {
+        if FIELD_PATH_METHODS.contains(&method.attr.as_str()) && offset > arg.range().start().to_usize() && offset <= arg.range().end().to_usize() {
+            if let Some(prefix) = get_source(session, file).and_then(|source| python_utils::literal_prefix(arg, &source, offset)) {
+                return complete_field_names(session, file, &method.value, &prefix, offset, is_param);
+            }
+        }
+    }
+    //keys of system parameters, in get_param("key") and in the config_parameter="key" of settings fields
+    let key = match (expr_call.func.as_ref(), expr_call.arguments.args.first()) {
+        (Expr::Attribute(method), Some(key @ Expr::StringLiteral(_))) if CONFIG_PARAMETER_METHODS.contains(&method.attr.as_str()) => Some(key),
+        _ => expr_call.arguments.find_keyword("config_parameter").map(|keyword| &keyword.value).filter(|value| value.is_string_literal_expr()),
+    };
+    if let Some(key) = key {
+        if offset > key.range().start().to_usize() && offset < key.range().end().to_usize() {
+            if let Some(prefix) = get_source(session, file).and_then(|source| python_utils::literal_prefix(key, &source, offset)) {
+                return complete_config_parameter_keys(session, &prefix);
+            }
+        }
+    }
+    //models of the selection of a Reference field: fields.Reference([('res.partner', 'Partner')])
+    let func_name = match expr_call.func.as_ref() {
+        Expr::Attribute(attr) => Some(attr.attr.as_str()),
+        Expr::Name(name) => Some(name.id.as_str()),
+        _ => None,
+    };
+    if func_name == Some(reference_fields::REFERENCE_FIELD) {
+        let items: &[Expr] = match reference_fields::selection_expr(expr_call) {
+            Some(Expr::List(list)) => &list.elts[..],
+            Some(Expr::Tuple(tuple)) => &tuple.elts[..],
+            _ => &[],
+        };
+        for item in items.iter() {
+            if let Some(Expr::StringLiteral(model)) = item.as_tuple_expr().and_then(|t| t.elts.first()) {
+                if offset > model.range.start().to_usize() && offset < model.range.end().to_usize() {
+                    return complete_string_literal(session, file, model, offset, is_param, &vec![ExpectedType::MODEL_NAME]);
+                }
+            }
+        }
+    }
     for arg in expr_call.arguments.args.iter() {
         if offset > arg.range().start().to_usize() && offset <= arg.range().end().to_usize() {
             return complete_expr( &arg, session, file, offset, is_param, expected_type);
@@ -471,6 +672,73 @@ fn complete_call(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_cal
     None
 }
 
+/* Methods of recordsets taking a field name as first argument, like records.mapped("partner_id") */
+const FIELD_PATH_METHODS: [&str; 4] = ["mapped", "filtered", "sorted", "grouped"];
+
+/* Source of the file, to read the strings as they are written */
+fn get_source(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>) -> Option<String> {
+    let path = ModelSourceFeature::file_path(file)?;
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path)?;
+    let source = file_info.borrow().get_content();
+    source
+}
+
+/* Complete the field names of the model of records, for a field name given as a string starting with prefix. Only the
+first field of a path ("partner_id.name") is completed */
+fn complete_field_names(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, records: &Expr, prefix: &str, offset: usize, is_param: bool) -> Option<CompletionResponse> {
+    let mut items = vec![];
+    if !prefix.contains('.') {
+        let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, is_param);
+        let evaluations = Evaluation::eval_from_ast(session, records, scope, &records.range().start()).0;
+        let mut seen = HashSet::new();
+        for evaluation in evaluations.iter() {
+            let Some(symbol) = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], Some(file.clone())).0.upgrade() else {
+                continue;
+            };
+            let symbol_types = Symbol::follow_ref(&symbol, session, &mut None, true, false, None, &mut vec![]);
+            for symbol_type in symbol_types.iter() {
+                let Some(symbol_type) = symbol_type.0.upgrade() else {
+                    continue;
+                };
+                let (model_classes, inherited_classes) = get_model_classes(session, file, &symbol_type);
+                let mut classes = vec![symbol_type.clone()];
+                classes.extend(model_classes);
+                classes.extend(inherited_classes);
+                let mut all_symbols: Vec<Rc<RefCell<Symbol>>> = classes.iter().flat_map(|class| class.borrow().all_symbols().collect::<Vec<_>>()).collect();
+                all_symbols.extend(get_magic_fields(session, &symbol_type));
+                for sym in all_symbols {
+                    let name = sym.borrow().name().clone();
+                    let Some(quality) = CompletionScore::match_quality(&name, prefix) else {
+                        continue;
+                    };
+                    if !CompletionScore::is_field(session, &sym) || !seen.insert(name) {
+                        continue;
+                    }
+                    let mut item = build_field_completion_item(session, &sym);
+                    item.sort_text = Some(CompletionScore::sort_text(CompletionScore::MODEL_FIELD, quality, &sym));
+                    items.push(item);
+                }
+            }
+        }
+    }
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items
+    }))
+}
+
+fn complete_config_parameter_keys(session: &mut SessionInfo, prefix: &str) -> Option<CompletionResponse> {
+    let items = collect_config_parameter_keys(session).into_iter().filter(|key| key.starts_with(prefix)).map(|key| CompletionItem {
+        label: key,
+        kind: Some(CompletionItemKind::CONSTANT),
+        ..Default::default()
+    }).collect();
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items
+    }))
+}
+
 fn complete_string_literal(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_string_literal: &ruff_python_ast::ExprStringLiteral, offset: usize, is_param: bool, expected_type: &Vec<ExpectedType>) -> Option<CompletionResponse> {
     let mut items = vec![];
     let current_module: Rc<RefCell<Symbol>> = file.borrow().find_module().expect("Completion requested outside an odoo module");
@@ -487,7 +755,7 @@ fn complete_string_literal(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>
                     
                         let model_class_syms = model.borrow().get_main_symbols(session, None,&mut None);
                         let modules = model_class_syms.iter().flat_map(|model_rc| 
-                            model_rc.borrow().find_module());
+                            model_rc.borrow().owning_module());
                         let required_modules = modules.filter(|module| 
                             !ModuleSymbol::is_in_deps(session, &current_module, &module.borrow().as_module_package().dir_name, &mut None));
                         let dep_names: Vec<String> = required_modules.map(|module| module.borrow().as_module_package().dir_name.clone()).collect();
@@ -537,13 +805,42 @@ fn complete_attribut(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, attr
                 let parent_sym_types = Symbol::follow_ref(&parent_sym, session, &mut None, true, false, None, &mut vec![]);
                 for parent_sym_type in parent_sym_types.iter() {
                     if let Some(parent_sym_type) = parent_sym_type.0.upgrade() {
-                        let parent_borrowed = parent_sym_type.borrow();
-                        let all_symbols = parent_borrowed.all_symbols();
-                        drop(parent_borrowed);
-                        for sym in all_symbols {
-                            if sym.borrow().name().starts_with(attr.attr.id.as_str()) {
-                                items.push(build_completion_item_from_symbol(session, &sym));
+                        let (model_classes, inherited_classes) = get_model_classes(session, file, &parent_sym_type);
+                        let mut classes = vec![(parent_sym_type.clone(), false)];
+                        classes.extend(model_classes.into_iter().map(|class| (class, false)));
+                        classes.extend(inherited_classes.into_iter().map(|class| (class, true)));
+                        let mut seen = HashSet::new();
+                        for (class, inherited) in classes.iter() {
+                            let all_symbols: Vec<Rc<RefCell<Symbol>>> = class.borrow().all_symbols().filter(|sym| !sym.borrow().is_comprehension()).collect();
+                            for sym in all_symbols {
+                                let name = sym.borrow().name().clone();
+                                let Some(quality) = CompletionScore::match_quality(&name, attr.attr.id.as_str()) else {
+                                    continue;
+                                };
+                                if !seen.insert(name) {
+                                    continue;
+                                }
+                                let category = CompletionScore::member_category(session, class, &sym, *inherited);
+                                let mut item = if category == CompletionScore::MODEL_FIELD {
+                                    build_field_completion_item(session, &sym)
+                                } else {
+                                    build_completion_item_from_symbol(session, &sym)
+                                };
+                                item.sort_text = Some(CompletionScore::sort_text(category, quality, &sym));
+                                items.push(item);
+                            }
+                        }
+                        for sym in get_magic_fields(session, &parent_sym_type) {
+                            let name = sym.borrow().name().clone();
+                            let Some(quality) = CompletionScore::match_quality(&name, attr.attr.id.as_str()) else {
+                                continue;
+                            };
+                            if !seen.insert(name) {
+                                continue;
                             }
+                            let mut item = build_field_completion_item(session, &sym);
+                            item.sort_text = Some(CompletionScore::sort_text(CompletionScore::MODEL_FIELD, quality, &sym));
+                            items.push(item);
                         }
                     }
                 }
@@ -556,7 +853,55 @@ fn complete_attribut(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, attr
     }))
 }
 
+/* If symbol is a model class, return the other classes of the model, and the classes of the models it inherits (mail.thread...),
+as visible from the module of the file */
+fn get_model_classes(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, symbol: &Rc<RefCell<Symbol>>) -> (Vec<Rc<RefCell<Symbol>>>, Vec<Rc<RefCell<Symbol>>>) {
+    if symbol.borrow().typ() != SymType::CLASS {
+        return (vec![], vec![]);
+    }
+    let Some(model_name) = symbol.borrow().as_class_sym()._model.as_ref().map(|model| model.name.clone()) else {
+        return (vec![], vec![]);
+    };
+    let Some(from_module) = file.borrow().find_module() else {
+        return (vec![], vec![]);
+    };
+    let mut model_classes = vec![];
+    if let Some(model) = session.sync_odoo.models.get(&model_name).cloned() {
+        model_classes.extend(model.borrow().get_symbols(session, from_module.clone()).filter(|class| !Rc::ptr_eq(class, symbol)));
+    }
+    let inherited_classes = symbol.borrow().get_inherited_model_classes(session, from_module);
+    (model_classes, inherited_classes)
+}
+
+/* Fields that every model has (id, display_name, create_uid...) are declared on BaseModel. It is a python base of the
+model classes, so they are not among the members of the classes of the model */
+fn get_magic_fields(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Vec<Rc<RefCell<Symbol>>> {
+    if symbol.borrow().typ() != SymType::CLASS || symbol.borrow().as_class_sym()._model.is_none() {
+        return vec![];
+    }
+    MAGIC_FIELDS.iter().filter_map(|name| {
+        symbol.borrow().get_member_symbol(session, &S!(*name), None, true, false).0.first().cloned()
+    }).collect()
+}
+
 fn complete_subscript(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_subscript: &ExprSubscript, offset: usize, is_param: bool, expected_type: &Vec<ExpectedType>) -> Option<CompletionResponse> {
+    //keys of the rows of read_group, like group['partner_id_count']
+    if let Expr::StringLiteral(key) = expr_subscript.slice.as_ref() {
+        if offset > key.range.start().to_usize() && offset < key.range.end().to_usize() {
+            let prefix = get_source(session, file).and_then(|source| python_utils::literal_prefix(&expr_subscript.slice, &source, offset));
+            if let (Some(prefix), Some(keys)) = (prefix, get_read_group_keys(session, file, &expr_subscript.value, offset)) {
+                let items = keys.into_iter().filter(|name| name.starts_with(&prefix)).map(|name| CompletionItem {
+                    label: name,
+                    kind: Some(CompletionItemKind::FIELD),
+                    ..Default::default()
+                }).collect();
+                return Some(CompletionResponse::List(CompletionList {
+                    is_incomplete: false,
+                    items
+                }));
+            }
+        }
+    }
     let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, is_param);
     let subscripted = Evaluation::eval_from_ast(session, &expr_subscript.value, scope, &expr_subscript.value.range().start()).0;
     for eval in subscripted.iter() {
@@ -582,6 +927,17 @@ fn complete_subscript(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, exp
     complete_expr(&expr_subscript.slice, session, file, offset, false, &vec![])
 }
 
+/* Keys of the dictionary of a row of read_group, when the row comes from a call in the same function with literal specs */
+fn get_read_group_keys(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, row: &Expr, offset: usize) -> Option<Vec<String>> {
+    let path = ModelSourceFeature::file_path(file)?;
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path)?;
+    let file_info = file_info.borrow();
+    let function = python_utils::find_enclosing_function(file_info.ast.as_ref()?, offset as u32)?;
+    let call = read_group::find_rows_call(&function.body, row)?;
+    let keys = ReadGroupCall::parse(call, session.sync_odoo.version_major)?.result_keys();
+    (!keys.is_empty()).then_some(keys)
+}
+
 fn complete_name(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_name: &ExprName, offset: usize, is_param: bool, expected_type: &Vec<ExpectedType>) -> Option<CompletionResponse> {
     let mut items = vec![];
     let name = expr_name.id.to_string();
@@ -589,9 +945,18 @@ fn complete_name(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_nam
         let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, is_param);
         let symbols = Symbol::get_all_infered_names(session.sync_odoo,& scope, &name, Some(offset as u32));
         for symbol in symbols {
+            let quality = CompletionScore::match_quality(symbol.borrow().name(), &name).unwrap_or(CompletionScore::SUBSEQUENCE);
+            let category = if symbol.borrow().is_in_dead_branch() {
+                CompletionScore::DEAD_BRANCH
+            } else if CompletionScore::is_builtin(&symbol) {
+                CompletionScore::BUILTIN
+            } else {
+                CompletionScore::MODEL_MEMBER
+            };
             items.push(CompletionItem {
                 label: symbol.borrow().name().clone(),
                 kind: Some(lsp_types::CompletionItemKind::VARIABLE),
+                sort_text: Some(CompletionScore::sort_text(category, quality, &symbol)),
                 ..Default::default()
             });
         }
@@ -612,7 +977,39 @@ fn complete_list(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_lis
     None
 }
 
+fn complete_tuple(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_tuple: &ruff_python_ast::ExprTuple, offset: usize, is_param: bool, expected_type: &Vec<ExpectedType>) -> Option<CompletionResponse> {
+    for expr in expr_tuple.elts.iter() {
+        if offset > expr.range().start().to_usize() && offset < expr.range().end().to_usize() {
+            return complete_expr( expr, session, file, offset, is_param, expected_type);
+        }
+    }
+    None
+}
+
 fn build_completion_item_from_symbol(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> CompletionItem {
+    let mut item = build_completion_item_without_documentation(session, symbol);
+    item.documentation = Some(
+        lsp_types::Documentation::MarkupContent(MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value: HoverFeature::build_markdown_description(session, &vec![Evaluation::eval_from_symbol(&Rc::downgrade(symbol))])
+        }));
+    item
+}
+
+/* Fields are sent without documentation, as the chain of their declarations in all modules is expensive to build.
+It is given by completionItem/resolve, from the model and the field in the data of the item */
+fn build_field_completion_item(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> CompletionItem {
+    let class = symbol.borrow().parent().and_then(|parent| parent.upgrade());
+    let model = class.and_then(|class| class.borrow().as_class_sym()._model.as_ref().map(|model| model.name.clone()));
+    let Some(model) = model else {
+        return build_completion_item_from_symbol(session, symbol);
+    };
+    let mut item = build_completion_item_without_documentation(session, symbol);
+    item.data = serde_json::to_value(FieldResolveData { model, field: symbol.borrow().name().clone() }).ok();
+    item
+}
+
+fn build_completion_item_without_documentation(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> CompletionItem {
     let typ = Symbol::follow_ref(symbol, session, &mut None, true, true, None, &mut vec![]);
     let mut label_details = Some(CompletionItemLabelDetails {
         detail: None,
@@ -750,14 +1147,9 @@ fn build_completion_item_from_symbol(session: &mut SessionInfo, symbol: &Rc<RefC
     CompletionItem {
         label: symbol.borrow().name().clone(),
         label_details: label_details,
-        detail: None,
+        detail: symbol.borrow().find_module().map(|module| module.borrow().as_module_package().dir_name.clone()),
         kind: Some(get_completion_item_kind(symbol)),
         sort_text: Some(get_sort_text_for_symbol(symbol)),
-        documentation: Some(
-            lsp_types::Documentation::MarkupContent(MarkupContent {
-                kind: lsp_types::MarkupKind::Markdown,
-                value: HoverFeature::build_markdown_description(session, &vec![Evaluation::eval_from_symbol(&Rc::downgrade(symbol))])
-            })),
         ..Default::default()
     }
 }
@@ -788,6 +1180,111 @@ fn get_sort_text_for_symbol(sym: &Rc<RefCell<Symbol>>/*, cl: Option<Rc<RefCell<S
     text
 }
 
+/* Relevance of completion items. Items are sorted by category (fields of the model, its other members, members of inherited
+models, builtins, symbols declared in branches that are dead for the current version of Odoo), then by how well they match
+the typed text, then by name (private names last) */
+struct CompletionScore {}
+
+impl CompletionScore {
+    const MODEL_FIELD: u8 = 0;
+    const MODEL_MEMBER: u8 = 1;
+    const INHERITED_MEMBER: u8 = 2;
+    const BUILTIN: u8 = 3;
+    const DEAD_BRANCH: u8 = 4;
+
+    const PREFIX: u8 = 0;
+    const CASE_INSENSITIVE_PREFIX: u8 = 1;
+    const SUBSEQUENCE: u8 = 2;
+
+    fn sort_text(category: u8, quality: u8, symbol: &Rc<RefCell<Symbol>>) -> String {
+        format!("{}{}{}", category, quality, get_sort_text_for_symbol(symbol))
+    }
+
+    /* Return how well name matches the typed text, or None if it doesn't match. The typed text can be a prefix of the name, or a
+    subsequence of the parts of a snake_case name ("pid" or "par_id" for "partner_id") */
+    fn match_quality(name: &str, typed: &str) -> Option<u8> {
+        if name.starts_with(typed) {
+            return Some(CompletionScore::PREFIX);
+        }
+        let name = name.to_lowercase();
+        let typed = typed.to_lowercase();
+        if name.starts_with(&typed) {
+            return Some(CompletionScore::CASE_INSENSITIVE_PREFIX);
+        }
+        let parts: Vec<&str> = name.split('_').filter(|part| !part.is_empty()).collect();
+        if CompletionScore::matches_parts(&parts, typed.trim_start_matches('_').replace('_', "").as_str()) {
+            return Some(CompletionScore::SUBSEQUENCE);
+        }
+        None
+    }
+
+    /* The typed text must be made of prefixes of the parts, in order. Parts can be skipped, but not the first one. The
+    results are memoized by part and by offset in the typed text, as the ways to split the typed text between the parts grow
+    exponentially with the number of parts */
+    fn matches_parts(parts: &[&str], typed: &str) -> bool {
+        CompletionScore::matches_parts_from(parts, typed, 0, 0, &mut HashMap::new())
+    }
+
+    /* The typed text from the offset starts with a prefix of the part at the index, and its rest matches the next parts */
+    fn matches_parts_from(parts: &[&str], typed: &str, index: usize, offset: usize, memo: &mut HashMap<(usize, usize), bool>) -> bool {
+        if offset == typed.len() {
+            return true;
+        }
+        if index == parts.len() {
+            return false;
+        }
+        if let Some(result) = memo.get(&(index, offset)) {
+            return *result;
+        }
+        let part = parts[index];
+        let remaining = &typed[offset..];
+        let mut result = false;
+        let mut length = part.len().min(remaining.len());
+        while length > 0 && !result {
+            if part.is_char_boundary(length) && remaining.is_char_boundary(length) && remaining.starts_with(&part[..length]) {
+                result = (index + 1..=parts.len()).any(|next| CompletionScore::matches_parts_from(parts, typed, next, offset + length, memo));
+            }
+            length -= 1;
+        }
+        memo.insert((index, offset), result);
+        result
+    }
+
+    fn member_category(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, symbol: &Rc<RefCell<Symbol>>, inherited: bool) -> u8 {
+        if symbol.borrow().is_in_dead_branch() {
+            return CompletionScore::DEAD_BRANCH;
+        }
+        if CompletionScore::is_builtin(class) {
+            return CompletionScore::BUILTIN;
+        }
+        if inherited {
+            return CompletionScore::INHERITED_MEMBER;
+        }
+        if CompletionScore::is_field(session, symbol) {
+            return CompletionScore::MODEL_FIELD;
+        }
+        CompletionScore::MODEL_MEMBER
+    }
+
+    fn is_builtin(symbol: &Rc<RefCell<Symbol>>) -> bool {
+        symbol.borrow().get_tree().0.first().is_some_and(|name| name == "builtins")
+    }
+
+    fn is_field(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> bool {
+        if symbol.borrow().typ() != SymType::VARIABLE {
+            return false;
+        }
+        let Some(evaluation) = symbol.borrow().evaluations().and_then(|evals| evals.first().cloned()) else {
+            return false;
+        };
+        let Some(eval_symbol) = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade() else {
+            return false;
+        };
+        let tree = eval_symbol.borrow().get_tree();
+        tree.0 == vec![S!("odoo"), S!("fields")]
+    }
+}
+
 fn get_completion_item_kind(symbol: &Rc<RefCell<Symbol>>) -> CompletionItemKind {
     match symbol.borrow().typ() {
         SymType::ROOT => CompletionItemKind::TEXT,
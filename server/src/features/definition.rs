@@ -1,17 +1,24 @@
 use std::path::PathBuf;
 use std::{cell::RefCell, rc::Rc};
-use ruff_text_size::TextRange;
+use ruff_python_ast::Stmt;
+use ruff_text_size::{Ranged, TextRange, TextSize};
 use lsp_types::{GotoDefinitionResponse, Location, Range};
 
 use crate::constants::SymType;
+use crate::core::config::DefinitionTarget;
 use crate::core::evaluation::AnalyzeAstResult;
 use crate::core::file_mgr::{FileMgr, FileInfo};
+use crate::core::manifest_assets::AssetPath;
+use crate::core::reports::ReportValidator;
 use crate::core::symbols::symbol::Symbol;
+use crate::core::xml_code::{ServerActionCode, RECORDSET_VARIABLES};
 use crate::threads::SessionInfo;
 use crate::features::ast_utils::AstUtils;
 use crate::utils::PathSanitizer as _;
 
 
+/* A glob of an asset can match a whole directory. Don't flood the client with locations */
+const MAX_ASSET_LOCATIONS: usize = 50;
 
 pub struct DefinitionFeature {}
 
@@ -24,6 +31,9 @@ impl DefinitionFeature {
         character: u32
     ) -> Option<GotoDefinitionResponse> {
         let offset = file_info.borrow().position_to_offset(line, character);
+        if let Some(location) = DefinitionFeature::get_ref_location(session, file_symbol, file_info, offset) {
+            return Some(GotoDefinitionResponse::Scalar(location));
+        }
         let (analyse_ast_result, _range): (AnalyzeAstResult, Option<TextRange>) = AstUtils::get_symbols(session, file_symbol, file_info, offset as u32);
         if analyse_ast_result.evaluations.len() == 0 {
             return None;
@@ -36,36 +46,187 @@ impl DefinitionFeature {
                 continue;
             }
             let symbol =loc_sym.unwrap();
-            let file = symbol.borrow().get_file();
-            if let Some(file) = file {
-                for path in file.upgrade().unwrap().borrow().paths().iter() {
-                    match symbol.borrow().typ() {
-                        SymType::PACKAGE => {
-                            links.push(Location{
-                                uri: FileMgr::pathname2uri(&PathBuf::from(path).join(format!("__init__.py{}", symbol.borrow().as_package().i_ext())).sanitize()),
-                                range: Range::default()
-                            });
-                        },
-                        _ => {
-                            let range = if eval.range.is_some() {
-                                eval.range.unwrap().clone()
-                            } else {
-                                let get_sym = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
-                                if let Some(eval_sym) = get_sym.0.upgrade() {
-                                    eval_sym.borrow().range().clone()
-                                } else {
-                                    continue;
-                                }
-                            };
-                            links.push(Location{
-                                uri: FileMgr::pathname2uri(path),
-                                range: session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, path, &range)
-                            });
-                        }
-                    }
+            let Some(file) = symbol.borrow().get_file().and_then(|file| file.upgrade()) else {
+                continue;
+            };
+            let file_paths: Vec<String> = match file.borrow().typ() {
+                SymType::PACKAGE => file.borrow().paths().iter().map(|path|
+                    PathBuf::from(path).join(format!("__init__.py{}", file.borrow().as_package().i_ext())).sanitize()
+                ).collect(),
+                _ => file.borrow().paths().clone()
+            };
+            for path in file_paths.iter() {
+                if symbol.borrow().typ() == SymType::PACKAGE {
+                    links.push(DefinitionFeature::external_location(session, &symbol, path, None));
+                    continue;
                 }
+                //the range of the evaluation is the evaluated expression, in the analyzed file. The symbol can be in another file,
+                //like a stub of typeshed for a literal or a call
+                let range = symbol.borrow().range().clone();
+                links.push(DefinitionFeature::external_location(session, &symbol, path, Some(&range)));
             }
         }
         Some(GotoDefinitionResponse::Array(links))
     }
+
+    /* Record of the xml id given to a ref call: env.ref('module.report_x'). Only the report actions are indexed. An xml id
+    without module is the one of the module of the file */
+    fn get_ref_location(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: usize) -> Option<Location> {
+        let offset = TextSize::new(offset as u32);
+        let xml_id = {
+            let file_info = file_info.borrow();
+            let call = AstUtils::find_call_at(file_info.ast.as_ref()?, offset)
+                .filter(|call| call.func.as_attribute_expr().is_some_and(|func| func.attr.as_str() == "ref")
+                    && call.arguments.args.first().is_some_and(|arg| arg.range().contains_inclusive(offset)))?;
+            call.arguments.args.first()?.as_string_literal_expr()?.value.to_string()
+        };
+        let xml_id = if xml_id.contains('.') {
+            xml_id
+        } else {
+            let module = file_symbol.borrow().find_module()?;
+            let module_name = module.borrow().as_module_package().dir_name.clone();
+            format!("{}.{}", module_name, xml_id)
+        };
+        ReportValidator::find_report_location(session, &xml_id)
+    }
+
+    /* Location of a symbol in the given file. If the file is a stub of an external package and the user prefers the
+    implementation, the python file is searched in sys.path and the symbol is located in it by its name */
+    fn external_location(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>, path: &String, range: Option<&TextRange>) -> Location {
+        if path.ends_with(".pyi") && session.sync_odoo.config.definition_target == DefinitionTarget::Implementation {
+            if let Some(implementation) = DefinitionFeature::find_implementation(session, path) {
+                let range = if symbol.borrow().typ() == SymType::PACKAGE || symbol.borrow().typ() == SymType::FILE {
+                    None
+                } else {
+                    DefinitionFeature::find_in_implementation(&implementation, &symbol.borrow().get_tree().1)
+                };
+                let range = range.map(|range| session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, &implementation, &range));
+                return Location {
+                    uri: FileMgr::pathname2uri(&implementation),
+                    range: range.unwrap_or_default()
+                };
+            }
+        }
+        Location {
+            uri: FileMgr::pathname2uri(path),
+            range: range.map(|range| session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, path, range)).unwrap_or_default()
+        }
+    }
+
+    /* Return the python file implementing a stub: next to the stub, or in sys.path at the same relative path. The stubs of
+    typeshed are in a directory named by their distribution, that is not a part of the import path */
+    fn find_implementation(session: &mut SessionInfo, stub_path: &String) -> Option<String> {
+        let stub_path = PathBuf::from(stub_path);
+        let next_to_stub = stub_path.with_extension("py");
+        if next_to_stub.exists() {
+            return Some(next_to_stub.sanitize());
+        }
+        let stdlib_dir = PathBuf::from(&session.sync_odoo.stdlib_dir);
+        let relative = if let Ok(relative) = stub_path.strip_prefix(&stdlib_dir) {
+            relative.to_path_buf()
+        } else {
+            let stubs_dir = session.sync_odoo.stubs_dirs.iter().find(|stubs_dir| stub_path.starts_with(stubs_dir))?;
+            let in_distribution = stub_path.strip_prefix(stubs_dir).ok()?;
+            in_distribution.components().skip(1).collect()
+        };
+        let relative = relative.with_extension("py");
+        let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+        let sys_path = root.borrow().as_root().sys_path.clone();
+        sys_path.iter().map(|sys_path| PathBuf::from(sys_path).join(&relative)).find(|path| path.exists()).map(|path| path.sanitize())
+    }
+
+    /* Find the declaration of a symbol in a python file, by the names of its classes */
+    fn find_in_implementation(path: &String, names: &Vec<String>) -> Option<TextRange> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let parsed = ruff_python_parser::parse_module(&content).ok()?;
+        let mut body = &parsed.syntax().body;
+        let mut range = None;
+        for name in names.iter() {
+            let stmt = body.iter().rev().find(|stmt| match stmt {
+                Stmt::ClassDef(class_def) => class_def.name.as_str() == name,
+                Stmt::FunctionDef(func_def) => func_def.name.as_str() == name,
+                Stmt::Assign(assign) => assign.targets.iter().any(|target| target.as_name_expr().is_some_and(|target| target.id.as_str() == name)),
+                Stmt::AnnAssign(assign) => assign.target.as_name_expr().is_some_and(|target| target.id.as_str() == name),
+                _ => false
+            })?;
+            range = Some(match stmt {
+                Stmt::ClassDef(class_def) => {
+                    body = &class_def.body;
+                    class_def.name.range
+                },
+                Stmt::FunctionDef(func_def) => {
+                    body = &func_def.body;
+                    func_def.name.range
+                },
+                _ => stmt.range()
+            });
+        }
+        range
+    }
+
+    /* Go to the manifest of the module under the cursor, if the cursor is on an entry of the 'depends' key of a manifest,
+    or to the files targeted by a path of the 'assets' key */
+    pub fn get_manifest_location(session: &mut SessionInfo,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32
+    ) -> Option<GotoDefinitionResponse> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        if let Some(asset) = AstUtils::find_manifest_asset_at(file_info.borrow().ast.as_ref().unwrap(), offset as u32) {
+            return DefinitionFeature::get_asset_locations(session, &asset);
+        }
+        let (depend, _range) = AstUtils::find_manifest_depend_at(file_info.borrow().ast.as_ref().unwrap(), offset as u32)?;
+        let module = session.sync_odoo.modules.get(&depend).and_then(|m| m.upgrade())?;
+        let path = PathBuf::from(module.borrow().as_module_package().path.clone()).join("__manifest__.py").sanitize();
+        Some(GotoDefinitionResponse::Scalar(Location{
+            uri: FileMgr::pathname2uri(&path),
+            range: Range::default()
+        }))
+    }
+
+    /* Go to the declarations of the member of the model under the cursor, in the python code of a server action or a cron */
+    pub fn get_server_action_location(session: &mut SessionInfo,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32
+    ) -> Option<GotoDefinitionResponse> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let text = file_info.borrow().get_content()?;
+        let code = ServerActionCode::extract(session, &text).into_iter().find(|code| code.to_source_offset(offset).is_some())?;
+        let (base, name) = code.attribute_at(code.to_source_offset(offset).unwrap(), false);
+        if !base.is_some_and(|base| RECORDSET_VARIABLES.contains(&base.as_str())) {
+            return None;
+        }
+        let mut links = vec![];
+        for member in ServerActionCode::get_model_members(session, code.model.as_ref()?, &name).iter() {
+            let Some(file) = member.borrow().get_file().and_then(|file| file.upgrade()) else {
+                continue;
+            };
+            let path = file.borrow().paths()[0].clone();
+            let path = if file.borrow().typ() == SymType::PACKAGE {
+                PathBuf::from(path).join(format!("__init__.py{}", file.borrow().as_package().i_ext())).sanitize()
+            } else {
+                path
+            };
+            let range = member.borrow().range().clone();
+            links.push(Location{
+                uri: FileMgr::pathname2uri(&path),
+                range: session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, &path, &range)
+            });
+        }
+        Some(GotoDefinitionResponse::Array(links))
+    }
+
+    fn get_asset_locations(session: &mut SessionInfo, asset: &AssetPath) -> Option<GotoDefinitionResponse> {
+        let module = session.sync_odoo.modules.get(asset.module_name()?).and_then(|m| m.upgrade())?;
+        let module_dir = module.borrow().as_module_package().path.clone();
+        let links: Vec<Location> = asset.resolve(&module_dir).iter().take(MAX_ASSET_LOCATIONS).map(|path| Location{
+            uri: FileMgr::pathname2uri(&path.sanitize()),
+            range: Range::default()
+        }).collect();
+        if links.is_empty() {
+            return None;
+        }
+        Some(GotoDefinitionResponse::Array(links))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,131 @@
+use lsp_types::{Color, ColorInformation, ColorPresentation, ColorPresentationParams, Range};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::{Ranged, TextSize};
+
+use crate::core::file_mgr::FileInfo;
+use crate::core::xml_parser::{attribute, attributes, field_text, strip_comments, XmlValue, FIELD_RE};
+use crate::threads::SessionInfo;
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<[\w:.-]+\b([^>]*?)/?>"#).unwrap());
+
+/* Swatches of the hex colors of the views and data files, and of the defaults of the color fields of the models. The
+xml files are scanned without being parsed, like the other xml features */
+pub struct DocumentColorFeature {}
+
+impl DocumentColorFeature {
+
+    pub fn get_document_colors(session: &mut SessionInfo, path: &String) -> Option<Vec<ColorInformation>> {
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(path)?;
+        let file_info = file_info.borrow();
+        if path.ends_with(".xml") {
+            let content = file_info.get_content()?;
+            return Some(DocumentColorFeature::find_xml_colors(&content).into_iter().map(|(value, color)| ColorInformation {
+                range: Range::new(file_info.offset_to_position(value.offset), file_info.offset_to_position(value.offset + value.value.len())),
+                color,
+            }).collect());
+        }
+        let mut res = vec![];
+        for stmt in file_info.ast.as_ref()?.iter() {
+            if let Stmt::ClassDef(class_def) = stmt {
+                DocumentColorFeature::field_default_colors(&file_info, &class_def.body, &mut res);
+            }
+        }
+        Some(res)
+    }
+
+    /* Hex colors of a xml file: the values of the attributes whose name contains "color" (color, data-color, color_field...),
+    and of the fields whose name contains "color" in the records of the data files */
+    pub fn find_xml_colors(content: &str) -> Vec<(XmlValue, Color)> {
+        let content = strip_comments(content);
+        let mut values = vec![];
+        for tag in TAG_RE.captures_iter(&content) {
+            let attrs = tag.get(1).unwrap();
+            values.extend(attributes(attrs.as_str()).into_iter()
+                .filter(|(name, _)| name.contains("color"))
+                .map(|(_, value)| value.shifted(attrs.start())));
+        }
+        for field in FIELD_RE.captures_iter(&content) {
+            if attribute(&field[1], "name").is_some_and(|name| name.value.contains("color")) {
+                values.extend(field_text(&field));
+            }
+        }
+        let mut res: Vec<(XmlValue, Color)> = values.into_iter()
+            .filter_map(|value| DocumentColorFeature::parse_hex_color(&value.value).map(|color| (value, color)))
+            .collect();
+        res.sort_by_key(|(value, _)| value.offset);
+        res
+    }
+
+    /* Colors given as default of the Char fields named *_color: name_color = fields.Char(default="#FF0000") */
+    fn field_default_colors(file_info: &FileInfo, body: &[Stmt], res: &mut Vec<ColorInformation>) {
+        for stmt in body.iter() {
+            let Stmt::Assign(assign) = stmt else {
+                continue;
+            };
+            let is_color_field = assign.targets.iter().any(|target| target.as_name_expr().is_some_and(|name| {
+                name.id.as_str() == "color" || name.id.as_str().ends_with("_color")
+            }));
+            let Expr::Call(call) = assign.value.as_ref() else {
+                continue;
+            };
+            let is_char = call.func.as_attribute_expr().is_some_and(|attr| attr.attr.as_str() == "Char"
+                && attr.value.as_name_expr().is_some_and(|name| name.id.as_str() == "fields"));
+            if !is_color_field || !is_char {
+                continue;
+            }
+            let default = call.arguments.keywords.iter().find(|keyword| keyword.arg.as_ref().is_some_and(|arg| arg.as_str() == "default"));
+            let Some(Expr::StringLiteral(value)) = default.map(|keyword| &keyword.value) else {
+                continue;
+            };
+            let Some(color) = DocumentColorFeature::parse_hex_color(value.value.to_str()) else {
+                continue;
+            };
+            //the swatch is on the content of the string, without its quotes
+            let range = value.range();
+            res.push(ColorInformation {
+                range: Range::new(file_info.offset_to_position((range.start() + TextSize::new(1)).to_usize()),
+                    file_info.offset_to_position((range.end() - TextSize::new(1)).to_usize())),
+                color,
+            });
+        }
+    }
+
+    /* The color picker of the client edits the color in place: keep the hex notation, with the alpha only if it is set */
+    pub fn get_color_presentations(params: &ColorPresentationParams) -> Vec<ColorPresentation> {
+        vec![ColorPresentation {
+            label: DocumentColorFeature::to_hex_color(&params.color),
+            text_edit: None,
+            additional_text_edits: None,
+        }]
+    }
+
+    /* Parse #RGB, #RGBA, #RRGGBB and #RRGGBBAA colors */
+    pub fn parse_hex_color(value: &str) -> Option<Color> {
+        let hex = value.strip_prefix('#')?;
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channels: Vec<u8> = match hex.len() {
+            3 | 4 => hex.chars().map(|c| u8::from_str_radix(&c.to_string().repeat(2), 16).unwrap()).collect(),
+            6 | 8 => (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect(),
+            _ => return None,
+        };
+        Some(Color {
+            red: channels[0] as f32 / 255.0,
+            green: channels[1] as f32 / 255.0,
+            blue: channels[2] as f32 / 255.0,
+            alpha: channels.get(3).map(|alpha| *alpha as f32 / 255.0).unwrap_or(1.0),
+        })
+    }
+
+    pub fn to_hex_color(color: &Color) -> String {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let mut res = format!("#{:02X}{:02X}{:02X}", channel(color.red), channel(color.green), channel(color.blue));
+        if channel(color.alpha) != 255 {
+            res += &format!("{:02X}", channel(color.alpha));
+        }
+        res
+    }
+}
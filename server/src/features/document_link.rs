@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use lsp_types::{DocumentLink, Position, Range, Uri};
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::core::csv_parser::{is_reference_column, read_csv};
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::manifest_assets::AssetPath;
+use crate::core::reports::ReportValidator;
+use crate::features::xml_ids::XmlIdFeature;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+
+/* Keys of a manifest listing files of the module, relatively to its root */
+static FILE_LIST_KEYS: &[&str] = &["data", "demo"];
+
+/* Clickable paths of the manifests: the data files and the assets. Missing files are not linked, they are reported by
+the diagnostics of the manifest. The records referenced by the csv data files are linked to the file creating them */
+pub struct DocumentLinkFeature {}
+
+impl DocumentLinkFeature {
+
+    pub fn get_document_links(session: &mut SessionInfo, path: &String) -> Option<Vec<DocumentLink>> {
+        if path.ends_with(".csv") {
+            return DocumentLinkFeature::get_csv_links(session, path);
+        }
+        if !path.ends_with("__manifest__.py") {
+            return None;
+        }
+        let module_dir = PathBuf::from(path).parent()?.sanitize();
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(path)?;
+        let file_info = file_info.borrow();
+        let Some(Stmt::Expr(expr)) = file_info.ast.as_ref()?.first() else {
+            return None;
+        };
+        let dict = expr.value.as_dict_expr()?;
+        let mut links = vec![];
+        for item in dict.items.iter() {
+            let Some(Expr::StringLiteral(key)) = item.key.as_ref() else {
+                continue;
+            };
+            let key = key.value.to_str();
+            if FILE_LIST_KEYS.contains(&key) {
+                let Some(files) = item.value.as_list_expr() else {
+                    continue;
+                };
+                for file in files.elts.iter() {
+                    let Expr::StringLiteral(file) = file else {
+                        continue;
+                    };
+                    let target = PathBuf::from(&module_dir).join(file.value.to_str());
+                    if target.is_file() {
+                        links.push(DocumentLinkFeature::link(&file_info, file.range(), &target));
+                    }
+                }
+            } else if key == "assets" {
+                let Some(bundles) = item.value.as_dict_expr() else {
+                    continue;
+                };
+                for paths in bundles.items.iter().filter_map(|bundle| bundle.value.as_list_expr()) {
+                    for asset in paths.elts.iter().flat_map(AssetPath::from_entry) {
+                        if asset.is_glob() || asset.is_url() {
+                            continue;
+                        }
+                        let Some(asset_module_dir) = DocumentLinkFeature::module_dir(session, asset.module_name(), &module_dir) else {
+                            continue;
+                        };
+                        if let Some(target) = asset.resolve(&asset_module_dir).first() {
+                            links.push(DocumentLinkFeature::link(&file_info, asset.range, target));
+                        }
+                    }
+                }
+            }
+        }
+        Some(links)
+    }
+
+    /* Links of the xml ids of the columns referencing records, like "model_id:id" or "groups_id/id", to the line of the
+data file creating the record. The records created by Odoo itself, like the ones of the models, are not linked */
+    fn get_csv_links(session: &mut SessionInfo, path: &String) -> Option<Vec<DocumentLink>> {
+        let module_name = ReportValidator::module_of_path(session, path)?;
+        let content = fs::read_to_string(path).ok()?;
+        let lines: Vec<&str> = content.lines().collect();
+        let mut declarations: HashMap<String, HashMap<String, (String, u32)>> = HashMap::new();
+        let mut links = vec![];
+        for row in read_csv(&content).iter() {
+            let Some(line) = lines.get(row.line as usize) else {
+                continue;
+            };
+            //the values are searched in the order of the columns, a multiline row is only linked on its first line
+            let mut cursor = 0;
+            for (column, value) in row.values.iter() {
+                let Some(start) = DocumentLinkFeature::find_value(line, value, cursor) else {
+                    continue;
+                };
+                cursor = start + value.len();
+                if !is_reference_column(column) {
+                    continue;
+                }
+                let mut offset = start;
+                for xml_id in value.split(',') {
+                    let id_start = offset + xml_id.len() - xml_id.trim_start().len();
+                    offset += xml_id.len() + 1;
+                    let xml_id = xml_id.trim();
+                    if xml_id.is_empty() {
+                        continue;
+                    }
+                    let qualified = if xml_id.contains('.') { xml_id.to_string() } else { format!("{}.{}", module_name, xml_id) };
+                    let (target_module, _) = qualified.split_once('.').unwrap();
+                    if !declarations.contains_key(target_module) {
+                        let module = session.sync_odoo.modules.get(target_module).and_then(|module| module.upgrade());
+                        let module_declarations = module.map(|module| XmlIdFeature::find_declarations(target_module, &module.borrow().as_module_package().path))
+                            .unwrap_or_default();
+                        declarations.insert(target_module.to_string(), module_declarations);
+                    }
+                    let Some((target, target_line)) = declarations[target_module].get(&qualified) else {
+                        continue;
+                    };
+                    let target = format!("{}#L{}", FileMgr::pathname2uri(&PathBuf::from(target).sanitize()).as_str(), target_line + 1);
+                    let character = |index: usize| line[..index].encode_utf16().count() as u32;
+                    links.push(DocumentLink {
+                        range: Range::new(Position::new(row.line, character(id_start)), Position::new(row.line, character(id_start + xml_id.len()))),
+                        target: Uri::from_str(&target).ok(),
+                        tooltip: Some(qualified.clone()),
+                        data: None,
+                    });
+                }
+            }
+        }
+        Some(links)
+    }
+
+    /* Byte index of a value of a csv row in its line, from the given index. The value must be a whole cell, quoted or not */
+    fn find_value(line: &str, value: &str, from: usize) -> Option<usize> {
+        if value.is_empty() {
+            return None;
+        }
+        let is_separator = |c: Option<char>| c.map_or(true, |c| c == ',' || c == '"' || c.is_whitespace());
+        line[from..].match_indices(value).map(|(index, _)| from + index).find(|index| {
+            is_separator(line[..*index].chars().last()) && is_separator(line[*index + value.len()..].chars().next())
+        })
+    }
+
+    /* Directory of the module an asset path starts with. The current module can be not loaded yet */
+    fn module_dir(session: &mut SessionInfo, module_name: Option<&str>, current_module_dir: &String) -> Option<String> {
+        let module_name = module_name?;
+        if PathBuf::from(current_module_dir).file_name().is_some_and(|name| name == module_name) {
+            return Some(current_module_dir.clone());
+        }
+        let module = session.sync_odoo.modules.get(module_name)?.upgrade()?;
+        let path = module.borrow().as_module_package().path.clone();
+        Some(path)
+    }
+
+    /* Link on the content of a string literal, without its quotes */
+    fn link(file_info: &FileInfo, range: TextRange, target: &PathBuf) -> DocumentLink {
+        let (start, end) = if range.len() >= TextSize::new(2) {
+            (range.start() + TextSize::new(1), range.end() - TextSize::new(1))
+        } else {
+            (range.start(), range.end())
+        };
+        DocumentLink {
+            range: Range::new(file_info.offset_to_position(start.to_usize()), file_info.offset_to_position(end.to_usize())),
+            target: Some(FileMgr::pathname2uri(&target.sanitize())),
+            tooltip: None,
+            data: None,
+        }
+    }
+}
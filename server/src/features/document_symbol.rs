@@ -0,0 +1,108 @@
+use lsp_types::{Diagnostic, DiagnosticSeverity, DocumentSymbol, DocumentSymbolResponse, NumberOrString, Position, Range, SymbolKind};
+use ropey::Rope;
+use ruff_python_ast::{Expr, Mod, Stmt};
+use ruff_python_parser::Mode;
+use ruff_text_size::TextRange;
+
+use crate::constants::EXTENSION_NAME;
+use crate::core::file_mgr::FileInfo;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* Outline of a file: its classes, functions and variables. It is built from the ast only, so it doesn't wait for the
+file to be built */
+pub struct DocumentSymbolFeature {}
+
+impl DocumentSymbolFeature {
+
+    pub fn get_document_symbols(session: &mut SessionInfo, path: &String) -> Option<DocumentSymbolResponse> {
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(path)?;
+        let file_info = file_info.borrow();
+        let ast = file_info.ast.as_ref()?;
+        Some(DocumentSymbolResponse::Nested(DocumentSymbolFeature::outline(ast, false, &|offset: usize| file_info.offset_to_position(offset))))
+    }
+
+    /* Parse a document without the database, and return its syntax errors and its outline. It is used to answer at once
+    when a file is opened while the server is busy building, the full diagnostics follow when the build reaches the file */
+    pub fn quick_parse(text: &str) -> (Vec<Diagnostic>, Vec<DocumentSymbol>) {
+        let rope = Rope::from(text);
+        let to_position = |offset: usize| FileInfo::offset_to_position_with_rope(&rope, offset);
+        let parsed = ruff_python_parser::parse_unchecked(text, Mode::Module);
+        let diagnostics = parsed.errors().iter().map(|error| Diagnostic::new(
+            Range::new(to_position(error.location.start().to_usize()), to_position(error.location.end().to_usize())),
+            Some(DiagnosticSeverity::ERROR),
+            Some(NumberOrString::String(S!("OLS30001"))),
+            Some(EXTENSION_NAME.to_string()),
+            error.error.to_string(),
+            None,
+            None)).collect();
+        let symbols = match parsed.into_syntax() {
+            Mod::Module(module) => DocumentSymbolFeature::outline(&module.body, false, &to_position),
+            Mod::Expression(_) => vec![],
+        };
+        (diagnostics, symbols)
+    }
+
+    fn outline(stmts: &[Stmt], in_class: bool, to_position: &dyn Fn(usize) -> Position) -> Vec<DocumentSymbol> {
+        let mut res = vec![];
+        for stmt in stmts.iter() {
+            match stmt {
+                Stmt::ClassDef(class_def) => {
+                    let children = DocumentSymbolFeature::outline(&class_def.body, true, to_position);
+                    res.push(DocumentSymbolFeature::symbol(class_def.name.as_str(), SymbolKind::CLASS, class_def.range, class_def.name.range, Some(children), to_position));
+                },
+                Stmt::FunctionDef(func_def) => {
+                    let kind = if in_class { SymbolKind::METHOD } else { SymbolKind::FUNCTION };
+                    res.push(DocumentSymbolFeature::symbol(func_def.name.as_str(), kind, func_def.range, func_def.name.range, None, to_position));
+                },
+                Stmt::Assign(assign) => {
+                    for target in assign.targets.iter() {
+                        if let Expr::Name(name) = target {
+                            let kind = DocumentSymbolFeature::variable_kind(&assign.value, in_class);
+                            res.push(DocumentSymbolFeature::symbol(name.id.as_str(), kind, assign.range, name.range, None, to_position));
+                        }
+                    }
+                },
+                Stmt::AnnAssign(ann_assign) => {
+                    if let Expr::Name(name) = ann_assign.target.as_ref() {
+                        let kind = match ann_assign.value.as_ref() {
+                            Some(value) => DocumentSymbolFeature::variable_kind(value, in_class),
+                            None => SymbolKind::VARIABLE,
+                        };
+                        res.push(DocumentSymbolFeature::symbol(name.id.as_str(), kind, ann_assign.range, name.range, None, to_position));
+                    }
+                },
+                _ => {}
+            }
+        }
+        res
+    }
+
+    /* Odoo fields are recognized by their syntax (fields.Char(...)), as their evaluation is not available */
+    fn variable_kind(value: &Expr, in_class: bool) -> SymbolKind {
+        if in_class {
+            if let Expr::Call(call) = value {
+                if let Expr::Attribute(attr) = call.func.as_ref() {
+                    if attr.value.as_name_expr().is_some_and(|name| name.id.as_str() == "fields") {
+                        return SymbolKind::FIELD;
+                    }
+                }
+            }
+        }
+        SymbolKind::VARIABLE
+    }
+
+    fn symbol(name: &str, kind: SymbolKind, range: TextRange, selection_range: TextRange, children: Option<Vec<DocumentSymbol>>, to_position: &dyn Fn(usize) -> Position) -> DocumentSymbol {
+        #[allow(deprecated)]
+        DocumentSymbol {
+            name: name.to_string(),
+            detail: None,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: Range::new(to_position(range.start().to_usize()), to_position(range.end().to_usize())),
+            selection_range: Range::new(to_position(selection_range.start().to_usize()), to_position(selection_range.end().to_usize())),
+            children,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{DocumentOnTypeFormattingParams, DocumentRangeFormattingParams, FormattingOptions, Position, Range, TextEdit};
+
+use crate::core::file_mgr::FileInfo;
+
+/* Maximum number of lines scanned backward to find the opening parenthesis of a closing one */
+const MAX_PAREN_SCAN_LINES: u32 = 200;
+
+/* Minimal formatting, that only fixes indentation and aligns declaration blocks. Anything else is left to the formatter of the user (black, ruff...) */
+pub struct FormattingFeature {}
+
+impl FormattingFeature {
+
+    pub fn on_type_formatting(file_info: &Rc<RefCell<FileInfo>>, params: &DocumentOnTypeFormattingParams) -> Option<Vec<TextEdit>> {
+        let file_info = file_info.borrow();
+        let line = params.text_document_position.position.line;
+        let edit = match params.ch.as_str() {
+            ":" => FormattingFeature::indent_after_header(&file_info, line, line + 1, &params.options),
+            "\n" if line > 0 => FormattingFeature::indent_after_header(&file_info, line - 1, line, &params.options),
+            ")" => FormattingFeature::align_closing_paren(&file_info, line),
+            _ => None
+        };
+        edit.map(|edit| vec![edit])
+    }
+
+    /* Align the blocks of field declarations (name = fields.Char(...)) of the range on their '=', or the entries of the
+    manifest dict on their values. Consecutive declarations at the same indentation form a block, that is ended by an
+    empty line or any other statement. Comment lines are kept as they are and don't end a block */
+    pub fn range_formatting(file_info: &Rc<RefCell<FileInfo>>, params: &DocumentRangeFormattingParams) -> Option<Vec<TextEdit>> {
+        let file_info = file_info.borrow();
+        let is_manifest = file_info.uri.ends_with("__manifest__.py");
+        let mut edits = vec![];
+        let mut block: Vec<(u32, AlignedLine)> = vec![];
+        let mut depth = 0; //number of brackets left open by the previous declaration, that continues on the next lines
+        for line in params.range.start.line..=params.range.end.line {
+            let Some(text) = file_info.get_line(line) else {
+                break;
+            };
+            if depth > 0 {
+                depth -= FormattingFeature::bracket_balance(&text, &['(', '[', '{'], &[')', ']', '}']);
+                continue;
+            }
+            let code = text.trim();
+            if code.starts_with('#') {
+                continue;
+            }
+            let aligned_line = if is_manifest { AlignedLine::parse_dict_entry(&text) } else { AlignedLine::parse_field_declaration(&text) };
+            match aligned_line {
+                Some(aligned_line) => {
+                    if block.first().is_some_and(|(_, first)| first.indent != aligned_line.indent) {
+                        FormattingFeature::align_block(&mut block, &mut edits);
+                    }
+                    depth = -FormattingFeature::bracket_balance(&aligned_line.value, &['(', '[', '{'], &[')', ']', '}']);
+                    block.push((line, aligned_line));
+                },
+                None => FormattingFeature::align_block(&mut block, &mut edits)
+            }
+        }
+        FormattingFeature::align_block(&mut block, &mut edits);
+        Some(edits)
+    }
+
+    /* Replace the lines of the block by their aligned version and empty the block. A single line is left as it is.
+    The keys are padded to the same number of characters, but the ends of the replaced ranges are in UTF-16 units, like
+    the columns of the client */
+    fn align_block(block: &mut Vec<(u32, AlignedLine)>, edits: &mut Vec<TextEdit>) {
+        if block.len() > 1 {
+            let width = block.iter().map(|(_, aligned_line)| aligned_line.key.chars().count()).max().unwrap_or(0);
+            for (line, aligned_line) in block.iter() {
+                let new_text = aligned_line.aligned(width);
+                if new_text != aligned_line.text {
+                    edits.push(TextEdit {
+                        range: Range::new(Position::new(*line, 0), Position::new(*line, aligned_line.text.encode_utf16().count() as u32)),
+                        new_text,
+                    });
+                }
+            }
+        }
+        block.clear();
+    }
+
+    /* If header_line is a class or function declaration, indent the empty line following it */
+    fn indent_after_header(file_info: &FileInfo, header_line: u32, line: u32, options: &FormattingOptions) -> Option<TextEdit> {
+        let header = file_info.get_line(header_line)?;
+        let code = header.trim();
+        let is_header = code.starts_with("class ") || code.starts_with("def ") || code.starts_with("async def ");
+        if !is_header || !code.ends_with(':') {
+            return None;
+        }
+        let current = file_info.get_line(line)?;
+        if !current.trim().is_empty() {
+            return None;
+        }
+        let unit = if options.insert_spaces { " ".repeat(options.tab_size as usize) } else { "\t".to_string() };
+        let indent = FormattingFeature::indentation(&header).to_string() + &unit;
+        FormattingFeature::replace_indentation(line, &current, indent)
+    }
+
+    /* If the line only contains closing parenthesis, align it with the line of the matching opening parenthesis */
+    fn align_closing_paren(file_info: &FileInfo, line: u32) -> Option<TextEdit> {
+        let current = file_info.get_line(line)?;
+        let code = current.trim();
+        if code.is_empty() || !code.starts_with(')') || !code.chars().all(|c| c == ')' || c == ',') {
+            return None;
+        }
+        let mut depth = code.chars().filter(|c| *c == ')').count() as i32;
+        let mut scanned_line = line;
+        while scanned_line > 0 && line - scanned_line < MAX_PAREN_SCAN_LINES {
+            scanned_line -= 1;
+            let text = file_info.get_line(scanned_line)?;
+            depth += FormattingFeature::paren_balance(&text);
+            if depth <= 0 {
+                let indent = FormattingFeature::indentation(&text).to_string();
+                return FormattingFeature::replace_indentation(line, &current, indent);
+            }
+        }
+        None
+    }
+
+    /* Number of closing parenthesis minus the number of opening ones, ignoring strings and comments */
+    fn paren_balance(line: &str) -> i32 {
+        FormattingFeature::bracket_balance(line, &['('], &[')'])
+    }
+
+    fn bracket_balance(line: &str, opening: &[char], closing: &[char]) -> i32 {
+        let mut balance = 0;
+        let mut quote: Option<char> = None;
+        let mut escaped = false;
+        for c in line.chars() {
+            if let Some(q) = quote {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+                continue;
+            }
+            match c {
+                '#' => break,
+                '"' | '\'' => quote = Some(c),
+                c if opening.contains(&c) => balance -= 1,
+                c if closing.contains(&c) => balance += 1,
+                _ => {}
+            }
+        }
+        balance
+    }
+
+    fn indentation(line: &str) -> &str {
+        &line[..line.len() - line.trim_start().len()]
+    }
+
+    fn replace_indentation(line: u32, text: &str, indent: String) -> Option<TextEdit> {
+        let current_indent = FormattingFeature::indentation(text);
+        if current_indent == indent {
+            return None;
+        }
+        Some(TextEdit {
+            range: Range::new(Position::new(line, 0), Position::new(line, current_indent.encode_utf16().count() as u32)),
+            new_text: indent,
+        })
+    }
+}
+
+/* A line of a block to align: a field declaration, aligned on its '=', or an entry of a dict, aligned on its value */
+struct AlignedLine {
+    text: String,
+    indent: String,
+    key: String, //the name of the field, or the quoted key of the dict entry followed by its ':'
+    separator: &'static str,
+    value: String, //the rest of the line, with its comment
+}
+
+impl AlignedLine {
+
+    fn parse_field_declaration(text: &str) -> Option<AlignedLine> {
+        let code = text.trim_start();
+        let name_len = code.find(|c: char| !(c.is_alphanumeric() || c == '_'))?;
+        if name_len == 0 || code.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let after_name = code[name_len..].trim_start();
+        let value = after_name.strip_prefix('=')?;
+        if value.starts_with('=') {
+            return None;
+        }
+        let value = value.trim_start();
+        if !value.starts_with("fields.") {
+            return None;
+        }
+        Some(AlignedLine {
+            text: text.to_string(),
+            indent: FormattingFeature::indentation(text).to_string(),
+            key: code[..name_len].to_string(),
+            separator: " = ",
+            value: value.to_string(),
+        })
+    }
+
+    fn parse_dict_entry(text: &str) -> Option<AlignedLine> {
+        let code = text.trim_start();
+        let quote = code.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+        let key_len = code[1..].find(quote)? + 2;
+        let value = code[key_len..].trim_start().strip_prefix(':')?.trim_start();
+        if value.is_empty() {
+            return None;
+        }
+        Some(AlignedLine {
+            text: text.to_string(),
+            indent: FormattingFeature::indentation(text).to_string(),
+            key: code[..key_len].to_string() + ":",
+            separator: " ",
+            value: value.to_string(),
+        })
+    }
+
+    fn aligned(&self, width: usize) -> String {
+        let padding = " ".repeat(width - self.key.chars().count());
+        format!("{}{}{}{}{}", self.indent, self.key, padding, self.separator, self.value)
+    }
+}
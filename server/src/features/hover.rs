@@ -1,12 +1,24 @@
+use ruff_python_ast::Expr;
 use ruff_text_size::TextRange;
 use lsp_types::{Hover, HoverContents, MarkupContent, Range};
 use weak_table::traits::WeakElement;
+use crate::core::cardinality::{Cardinality, CardinalityAnalyzer};
+use crate::core::decorators::find_decorator_info;
+use crate::core::docstring::DocParam;
 use crate::core::evaluation::{AnalyzeAstResult, Context, Evaluation};
 use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::model::Model;
+use crate::core::populate;
+use crate::core::python_utils;
+use crate::core::python_validator::PythonValidator;
+use crate::core::reference_fields;
+use crate::core::settings::{SettingsStorage, SETTINGS_MODEL};
+use crate::core::sql;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
 use std::path::PathBuf;
 use std::rc::{Rc, Weak};
+use crate::core::symbols::function_symbol::FunctionSymbol;
 use crate::core::symbols::symbol::Symbol;
 use crate::constants::*;
 use crate::features::ast_utils::AstUtils;
@@ -19,11 +31,23 @@ impl HoverFeature {
 
     pub fn get_hover(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<Hover> {
         let offset = file_info.borrow().position_to_offset(line, character);
+        if session.sync_odoo.config.populate_checks {
+            if let Some(hover) = HoverFeature::get_populate_factory_hover(session, file_symbol, file_info, offset as u32) {
+                return Some(hover);
+            }
+        }
+        if let Some(hover) = HoverFeature::get_sql_hover(session, file_symbol, file_info, offset as u32) {
+            return Some(hover);
+        }
         let (analyse_ast_result, range): (AnalyzeAstResult, Option<TextRange>) = AstUtils::get_symbols(session, file_symbol, file_info, offset as u32);
         let evals = analyse_ast_result.evaluations;
         if evals.is_empty() {
             return None;
         };
+        let mut value = HoverFeature::build_markdown_description(session, &evals);
+        if let Some(records) = HoverFeature::build_cardinality_info(session, file_info, &evals, range.unwrap()) {
+            value = value + "  \n***  \n" + records.as_str();
+        }
         let range = Some(Range {
             start: file_info.borrow().offset_to_position(range.unwrap().start().to_usize()),
             end: file_info.borrow().offset_to_position(range.unwrap().end().to_usize())
@@ -31,10 +55,123 @@ impl HoverFeature {
         return Some(Hover { contents:
             HoverContents::Markup(MarkupContent {
                 kind: lsp_types::MarkupKind::Markdown,
-                value: HoverFeature::build_markdown_description(session, &evals)
+                value: value
+            }),
+            range: range
+        });
+    }
+
+    /*
+    Hover on the field name of a factory returned by _populate_factories. Display the field of the model
+     */
+    fn get_populate_factory_hover(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> Option<Hover> {
+        let (field_name, range) = populate::find_factory_field_name_at(file_info.borrow().ast.as_ref()?, offset)?;
+        let function = Symbol::get_scope_symbol(file_symbol.clone(), offset, false);
+        let class = function.borrow().parent()?.upgrade()?;
+        if class.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        let from_module = file_symbol.borrow().find_module();
+        let (symbols, _) = class.borrow().get_member_symbol(session, &field_name, from_module, false, false);
+        let field = symbols.first()?.clone();
+        PythonValidator::get_field_info(session, &field)?;
+        let range = Some(Range {
+            start: file_info.borrow().offset_to_position(range.start().to_usize()),
+            end: file_info.borrow().offset_to_position(range.end().to_usize())
+        });
+        Some(Hover { contents:
+            HoverContents::Markup(MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: HoverFeature::build_markdown_description(session, &vec![Evaluation::eval_from_symbol(&Rc::downgrade(&field))])
+            }),
+            range: range
+        })
+    }
+
+    /*
+    Hover on the query given to cr.execute. Display the tables it uses, with the models stored in them
+     */
+    fn get_sql_hover(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> Option<Hover> {
+        let (query, range) = sql::find_query_at(file_info.borrow().ast.as_ref()?, offset)?;
+        let tables = sql::query_tables(&query);
+        if tables.is_empty() {
+            return None;
+        }
+        let from_module = file_symbol.borrow().find_module();
+        let models: Vec<(String, Rc<RefCell<Model>>)> = session.sync_odoo.models.iter().map(|(name, model)| (name.clone(), model.clone())).collect();
+        let mut table_models: Vec<Vec<String>> = vec![vec![]; tables.len()];
+        for (name, model) in models {
+            let symbols = model.borrow().get_main_symbols(session, from_module.clone(), &mut None);
+            for symbol in symbols {
+                let Some(table) = symbol.borrow().as_class_sym()._model.as_ref().map(|model_data| model_data.table.clone()) else {
+                    continue;
+                };
+                if let Some(index) = tables.iter().position(|t| *t == table) {
+                    if !table_models[index].contains(&name) {
+                        table_models[index].push(name.clone());
+                    }
+                }
+            }
+        }
+        let mut value = S!("**SQL query**  \nTables:  \n");
+        for (table, models) in tables.iter().zip(table_models.iter_mut()) {
+            models.sort();
+            if models.is_empty() {
+                value += format!("- `{}`: not the table of a model  \n", table).as_str();
+            } else {
+                value += format!("- `{}`: {}  \n", table, models.iter().map(|model| format!("`{}`", model)).collect::<Vec<_>>().join(", ")).as_str();
+            }
+        }
+        let range = Some(Range {
+            start: file_info.borrow().offset_to_position(range.start().to_usize()),
+            end: file_info.borrow().offset_to_position(range.end().to_usize())
+        });
+        Some(Hover { contents:
+            HoverContents::Markup(MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: value
             }),
             range: range
+        })
+    }
+
+    /*
+    Hover on an entry of the 'depends' key of a manifest. Display the module information if it has been found in the addons paths
+     */
+    pub fn get_manifest_hover(session: &mut SessionInfo, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<Hover> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let (depend, range) = AstUtils::find_manifest_depend_at(file_info.borrow().ast.as_ref().unwrap(), offset as u32)?;
+        let mut value = format!("```python  \n(module) {}  \n```", depend);
+        let module = session.sync_odoo.modules.get(&depend).and_then(|m| m.upgrade());
+        if let Some(module) = module {
+            let module = module.borrow();
+            let module = module.as_module_package();
+            value += "  \n***  \n";
+            if !module.module_name.is_empty() {
+                value += format!("**{}**  \n", module.module_name).as_str();
+            }
+            if !module.summary.is_empty() {
+                value += format!("{}  \n", module.summary).as_str();
+            }
+            if !module.version.is_empty() {
+                value += format!("Version: {}  \n", module.version).as_str();
+            }
+            let manifest_path = PathBuf::from(module.path.clone()).join("__manifest__.py").sanitize();
+            value += format!("Path: [{}]({})", module.path, FileMgr::pathname2uri(&manifest_path).as_str()).as_str();
+        } else {
+            value += "  \n***  \nModule not found in the configured addons paths";
+        }
+        let range = Some(Range {
+            start: file_info.borrow().offset_to_position(range.start().to_usize()),
+            end: file_info.borrow().offset_to_position(range.end().to_usize())
         });
+        Some(Hover { contents:
+            HoverContents::Markup(MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: value
+            }),
+            range: range
+        })
     }
 
     /*
@@ -70,8 +207,13 @@ impl HoverFeature {
             single_func_eval = true;
             value += "def ";
             value += &symbol.name();
-            //display args
-            let function = infered_types[0].0.upgrade().unwrap();
+            //display args. A forwarding override (def write(self, vals): return super().write(vals)) has the signature of its parent
+            let mut function = infered_types[0].0.upgrade().unwrap();
+            if function.borrow().as_func().is_forwarding {
+                if let Some(forwarded) = FunctionSymbol::get_forwarded_method(session, &function) {
+                    function = forwarded;
+                }
+            }
             let function = function.borrow();
             let function = function.as_func();
             value += "(";
@@ -152,6 +294,200 @@ impl HoverFeature {
         value
     }
 
+    /*
+    Build the help of a field: the help given in its declaration, and the ones added to it in xml files (views, data)
+     */
+    fn build_field_help(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        if symbol.borrow().typ() != SymType::VARIABLE {
+            return None;
+        }
+        let class = symbol.borrow().parent()?.upgrade()?;
+        if class.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        let model_name = class.borrow().as_class_sym()._model.as_ref()?.name.clone();
+        let field_name = symbol.borrow().name().clone();
+        let mut helps = vec![];
+        if let Some(keywords) = AstUtils::get_field_call_keywords(session, symbol) {
+            let help = keywords.iter().find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == "help"));
+            if let Some(Expr::StringLiteral(help)) = help.map(|k| &k.value) {
+                helps.push(help.value.to_string());
+            }
+        }
+        for (path, help) in session.sync_odoo.xml_fields.get_helps(&model_name, &field_name) {
+            helps.push(format!("{}  \n*(from [{}]({}))*", help, PathBuf::from(path).file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or(path.clone()), FileMgr::pathname2uri(path).as_str()));
+        }
+        if helps.is_empty() {
+            return None;
+        }
+        Some(helps.join("  \n  \n"))
+    }
+
+    /*
+    Build the description of a field of res.config.settings. The settings records are never stored: display where the value
+    of the field is saved, with a link to the field holding it for a default value
+     */
+    fn build_settings_info(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        if symbol.borrow().typ() != SymType::VARIABLE {
+            return None;
+        }
+        let class = symbol.borrow().parent()?.upgrade()?;
+        if class.borrow().typ() != SymType::CLASS || !class.borrow().as_class_sym()._model.as_ref().is_some_and(|model| model.name == SETTINGS_MODEL) {
+            return None;
+        }
+        let keywords = AstUtils::get_field_call_keywords(session, symbol)?;
+        let field_name = symbol.borrow().name().clone();
+        let from_module = class.borrow().find_module();
+        let mut lines = vec![];
+        for storage in SettingsStorage::from_keywords(&keywords) {
+            match storage {
+                SettingsStorage::ConfigParameter(key, _) => {
+                    lines.push(format!("Saved in the system parameter `{}`", key));
+                },
+                SettingsStorage::DefaultValue(model, _) => {
+                    let default_field = SettingsStorage::default_field_name(&field_name).to_string();
+                    let mut line = format!("Saved as the default value of `{}` on `{}`", default_field, model);
+                    let field = SettingsStorage::find_default_field(session, &model, &field_name, from_module.clone());
+                    let file = field.as_ref().and_then(|field| field.borrow().get_file()).and_then(|file| file.upgrade());
+                    if let (Some(field), Some(file)) = (field, file) {
+                        if file.borrow().typ() == SymType::FILE {
+                            let path = FileMgr::pathname2uri(&file.borrow().paths()[0]);
+                            line += format!(" ([{}]({}#{}))", default_field, path.as_str(), field.borrow().range().start().to_u32()).as_str();
+                        }
+                    }
+                    lines.push(line);
+                },
+                SettingsStorage::ImpliedGroup(group, users) => {
+                    lines.push(format!("Gives the group `{}` to the users of `{}`", group, users.unwrap_or(S!("base.group_user"))));
+                },
+            }
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        Some(lines.join("  \n"))
+    }
+
+    /*
+    Build the description of the value of a Reference or Many2oneReference field: the models of the records it can hold
+     */
+    fn build_reference_info(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        let (field_type, _) = PythonValidator::get_field_info(session, symbol)?;
+        if field_type != reference_fields::REFERENCE_FIELD && field_type != reference_fields::MANY2ONE_REFERENCE_FIELD {
+            return None;
+        }
+        let call = AstUtils::get_field_call(session, symbol)?;
+        reference_fields::describe_value(&field_type, &call)
+    }
+
+    /*
+    Build the number of records of a local recordset of a method at the hovered place, narrowed by ensure_one() and the
+    len() guards before it
+     */
+    fn build_cardinality_info(session: &mut SessionInfo, file_info: &Rc<RefCell<FileInfo>>, evals: &Vec<Evaluation>, range: TextRange) -> Option<String> {
+        let symbol = evals.first()?.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()?;
+        let type_refs = Symbol::follow_ref(&symbol, session, &mut None, true, false, None, &mut vec![]);
+        let is_recordset = type_refs.iter().filter_map(|typ| typ.0.upgrade())
+            .any(|typ| typ.borrow().typ() == SymType::CLASS && typ.borrow().as_class_sym()._model.is_some());
+        if !is_recordset {
+            return None;
+        }
+        let file_info = file_info.borrow();
+        let name = file_info.get_text(&range)?;
+        let function = python_utils::find_enclosing_function(file_info.ast.as_ref()?, range.start().to_u32())?;
+        match CardinalityAnalyzer::cardinality_at(function, range.start().to_u32(), &name) {
+            Cardinality::Singleton => Some(S!("Records: a single record at this point")),
+            Cardinality::Multi => Some(format!("Records: may contain several records at this point. Loop on them or call {}.ensure_one() before reading their fields", name)),
+            Cardinality::Unknown => None,
+        }
+    }
+
+    /*
+    Build the documentation of a function from its parsed docstring, with the parameters in the order of the arguments of the function.
+    Return None if the docstring has no section, to display it as it is written
+     */
+    fn build_function_doc(symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        let symbol = symbol.borrow();
+        if symbol.typ() != SymType::FUNCTION {
+            return None;
+        }
+        let function = symbol.as_func();
+        let doc = function.doc.as_ref()?;
+        let mut value = doc.summary.clone();
+        let arg_names: Vec<String> = function.args.iter().filter_map(|arg| arg.symbol.upgrade()).map(|arg| arg.borrow().name().clone()).collect();
+        let mut params: Vec<&DocParam> = arg_names.iter().filter_map(|name| doc.get_param(name)).collect();
+        params.extend(doc.params.iter().filter(|param| !arg_names.contains(&param.name)));
+        if !params.is_empty() {
+            value += "  \n  \n**Parameters**  \n";
+            for param in params.iter() {
+                value += format!("- `{}`", param.name).as_str();
+                if let Some(typ) = param.typ.as_ref() {
+                    value += format!(" (`{}`)", typ).as_str();
+                }
+                if !param.description.is_empty() {
+                    value += format!(": {}", param.description).as_str();
+                }
+                value += "  \n";
+            }
+        }
+        if let Some(returns) = doc.returns.as_ref() {
+            value += "  \n**Returns**";
+            if let Some(typ) = returns.typ.as_ref() {
+                value += format!(" `{}`", typ).as_str();
+            }
+            if !returns.description.is_empty() {
+                value += format!(": {}", returns.description).as_str();
+            }
+            value += "  \n";
+        }
+        if !doc.raises.is_empty() {
+            value += "  \n**Raises**  \n";
+            for raise in doc.raises.iter() {
+                value += format!("- `{}`", raise.exception).as_str();
+                if !raise.description.is_empty() {
+                    value += format!(": {}", raise.description).as_str();
+                }
+                value += "  \n";
+            }
+        }
+        Some(value)
+    }
+
+    /*
+    Build the description of the urls handled by a controller method: its path and the auth, type and csrf parameters of the route
+     */
+    fn build_route_info(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        if symbol.borrow().typ() != SymType::FUNCTION {
+            return None;
+        }
+        let routes = session.sync_odoo.routes.get_by_function(symbol);
+        if routes.is_empty() {
+            return None;
+        }
+        let mut lines = vec![];
+        for route in routes.iter() {
+            let mut line = format!("Route `{}`  \nauth: `{}`, type: `{}`, csrf: `{}`", route.decl.path, route.auth(), route.typ(), if route.csrf() {"True"} else {"False"});
+            if !route.decl.methods.is_empty() {
+                line += format!(", methods: `{}`", route.decl.methods.join(", ")).as_str();
+            }
+            lines.push(line);
+        }
+        Some(lines.join("  \n"))
+    }
+
+    /*
+    Build the explanation of an Odoo decorator, like @api.depends or @api.ondelete, and the rules on the functions it decorates
+     */
+    fn build_decorator_info(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        let tree = symbol.borrow().get_tree();
+        let info = find_decorator_info(&tree, session.sync_odoo.version_major)?;
+        let mut value = format!("Odoo decorator `@{}`  \n{}", info.name(), info.description);
+        for usage in info.usage().iter() {
+            value += format!("  \n- {}", usage).as_str();
+        }
+        Some(value)
+    }
+
     pub fn build_markdown_description(session: &mut SessionInfo, evals: &Vec<Evaluation>) -> String {
         //let eval = &evals[0]; //TODO handle more evaluations
         let mut value = S!("");
@@ -192,11 +528,33 @@ impl HoverFeature {
             for typ in type_refs.iter() {
                 let typ = typ.0.upgrade();
                 if let Some(typ) = typ {
-                    if typ.borrow().doc_string().is_some() {
+                    if let Some(doc) = HoverFeature::build_function_doc(&typ) {
+                        value = value + "  \n***  \n" + doc.as_str();
+                    } else if typ.borrow().doc_string().is_some() {
                         value = value + "  \n***  \n" + typ.borrow().doc_string().as_ref().unwrap();
                     }
                 }
             }
+            // BLOCK 4: help of the field
+            if let Some(help) = HoverFeature::build_field_help(session, &symbol) {
+                value = value + "  \n***  \n" + help.as_str();
+            }
+            // BLOCK 5: routes of a controller method
+            if let Some(routes) = HoverFeature::build_route_info(session, &symbol) {
+                value = value + "  \n***  \n" + routes.as_str();
+            }
+            // BLOCK 6: Odoo semantics of a decorator
+            if let Some(decorator) = HoverFeature::build_decorator_info(session, &symbol) {
+                value = value + "  \n***  \n" + decorator.as_str();
+            }
+            // BLOCK 7: storage of a settings field
+            if let Some(settings) = HoverFeature::build_settings_info(session, &symbol) {
+                value = value + "  \n***  \n" + settings.as_str();
+            }
+            // BLOCK 8: value of a reference field
+            if let Some(reference) = HoverFeature::build_reference_info(session, &symbol) {
+                value = value + "  \n***  \n" + reference.as_str();
+            }
         }
         value
     }
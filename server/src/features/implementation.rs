@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::{cell::RefCell, rc::Rc};
+
+use lsp_types::{GotoDefinitionResponse, Location};
+use ruff_text_size::TextRange;
+
+use crate::constants::SymType;
+use crate::core::evaluation::AnalyzeAstResult;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::symbols::symbol::Symbol;
+use crate::features::ast_utils::AstUtils;
+use crate::features::definition::DefinitionFeature;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+
+/* Go to implementation on a method of a model lists the overrides of the method in the models of the workspace: in the
+classes extending the same model, and in the models inheriting it, like the models using a mixin. Definition goes to the
+method of the mixin or of odoo itself, implementation to the code of the workspace. Other symbols go to their definition */
+pub struct ImplementationFeature {}
+
+impl ImplementationFeature {
+
+    pub fn get_implementations(session: &mut SessionInfo,
+        file_symbol: &Rc<RefCell<Symbol>>,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32
+    ) -> Option<GotoDefinitionResponse> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let (analyse_ast_result, _range): (AnalyzeAstResult, Option<TextRange>) = AstUtils::get_symbols(session, file_symbol, file_info, offset as u32);
+        let mut methods = vec![];
+        for eval in analyse_ast_result.evaluations.iter() {
+            let Some(symbol) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade() else {
+                continue;
+            };
+            if let Some(method) = ImplementationFeature::model_method(&symbol) {
+                methods.push((symbol, method));
+            }
+        }
+        if methods.is_empty() {
+            return DefinitionFeature::get_location(session, file_symbol, file_info, line, character);
+        }
+        let mut links = vec![];
+        let mut seen = HashSet::new();
+        for (symbol, (model_name, method_name)) in methods.iter() {
+            for method in ImplementationFeature::find_overrides(session, model_name, method_name) {
+                if Rc::ptr_eq(&method, symbol) || !seen.insert(method.as_ptr()) {
+                    continue;
+                }
+                if let Some(location) = ImplementationFeature::location(session, &method) {
+                    links.push(location);
+                }
+            }
+        }
+        Some(GotoDefinitionResponse::Array(links))
+    }
+
+    /* The model and the name of a method declared in a model */
+    fn model_method(symbol: &Rc<RefCell<Symbol>>) -> Option<(String, String)> {
+        if symbol.borrow().typ() != SymType::FUNCTION {
+            return None;
+        }
+        let class = symbol.borrow().parent()?.upgrade()?;
+        if class.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        let model_name = class.borrow().as_class_sym()._model.as_ref()?.name.clone();
+        Some((model_name, symbol.borrow().name().clone()))
+    }
+
+    /* The methods named like method_name in the classes of the workspace extending the model or a model inheriting it */
+    pub fn find_overrides(session: &mut SessionInfo, model_name: &String, method_name: &String) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut res = vec![];
+        for model in ImplementationFeature::inheriting_models(session, model_name).iter() {
+            let Some(model) = session.sync_odoo.models.get(model).cloned() else {
+                continue;
+            };
+            let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+            for class in classes.iter() {
+                let in_workspace = class.borrow().get_file().and_then(|file| file.upgrade())
+                    .is_some_and(|file| file.borrow().in_workspace() && !file.borrow().is_external());
+                if !in_workspace {
+                    continue;
+                }
+                let members = class.borrow().get_symbol(&(vec![], vec![method_name.clone()]), u32::MAX);
+                res.extend(members.into_iter().filter(|member| member.borrow().typ() == SymType::FUNCTION));
+            }
+        }
+        res
+    }
+
+    /* The model and all the models inheriting it, directly or through other models */
+    fn inheriting_models(session: &mut SessionInfo, model_name: &String) -> Vec<String> {
+        let mut res = vec![model_name.clone()];
+        let mut index = 0;
+        while index < res.len() {
+            let parent = res[index].clone();
+            let mut children: Vec<String> = session.sync_odoo.models.iter().filter(|(name, model)| {
+                !res.contains(*name) && model.borrow().get_all_symbols().any(|class| class.borrow().as_class_sym()._model.as_ref()
+                    .is_some_and(|data| data.inherit.contains(&parent)))
+            }).map(|(name, _)| name.clone()).collect();
+            children.sort();
+            res.extend(children);
+            index += 1;
+        }
+        res
+    }
+
+    fn location(session: &mut SessionInfo, method: &Rc<RefCell<Symbol>>) -> Option<Location> {
+        let file = method.borrow().get_file()?.upgrade()?;
+        let mut path = file.borrow().paths().first()?.clone();
+        if file.borrow().typ() == SymType::PACKAGE {
+            path = PathBuf::from(path).join("__init__.py").sanitize() + file.borrow().as_package().i_ext().as_str();
+        }
+        let range = method.borrow().range().clone();
+        Some(Location {
+            uri: FileMgr::pathname2uri(&path),
+            range: session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, &path, &range),
+        })
+    }
+}
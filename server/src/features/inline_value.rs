@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use lsp_types::{InlineValue, InlineValueEvaluatableExpression, InlineValueParams, InlineValueVariableLookup, Range};
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::{Ranged, TextSize};
+
+use crate::constants::SymType;
+use crate::core::evaluation::Evaluation;
+use crate::core::python_utils;
+use crate::core::python_validator::PythonValidator;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* Values shown by the editor while debugging, on the lines of the function where the debugger stopped, up to the stopped line.
+The variables are looked up by name, and the chains of fields like order.partner_id.name are given to the debugger as expressions
+to evaluate. Only fields are given: reading them has no side effect, unlike calling methods or properties */
+pub struct InlineValueFeature {}
+
+impl InlineValueFeature {
+
+    pub fn get_inline_values(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, path: &String, params: &InlineValueParams) -> Option<Vec<InlineValue>> {
+        let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(path)?;
+        let stopped = params.context.stopped_location.start;
+        //the evaluation can validate other functions of the file, so the ast is not kept borrowed
+        let (function_start, candidates) = {
+            let file_info = file_info.borrow();
+            let stopped_offset = file_info.position_to_offset(stopped.line, stopped.character) as u32;
+            let function = python_utils::find_enclosing_function(file_info.ast.as_ref()?, stopped_offset)?;
+            let mut collector = CandidateCollector { candidates: vec![] };
+            for stmt in function.body.iter() {
+                collector.visit_stmt(stmt);
+            }
+            (file_info.offset_to_position(function.range.start().to_usize()).line, collector.candidates)
+        };
+        let first_line = function_start.max(params.range.start.line);
+        let last_line = stopped.line.min(params.range.end.line);
+        let mut shown: HashSet<(u32, String)> = HashSet::new();
+        let mut res = vec![];
+        for candidate in candidates.iter() {
+            let range = {
+                let file_info = file_info.borrow();
+                Range::new(file_info.offset_to_position(candidate.range().start().to_usize()), file_info.offset_to_position(candidate.range().end().to_usize()))
+            };
+            if range.start.line < first_line || range.start.line > last_line {
+                continue;
+            }
+            let text = CandidateCollector::chain_text(candidate);
+            if shown.contains(&(range.start.line, text.clone())) {
+                continue;
+            }
+            let scope = Symbol::get_scope_symbol(file_symbol.clone(), candidate.range().start().to_u32(), false);
+            let value = match candidate {
+                Expr::Name(name) if InlineValueFeature::is_value_variable(session, &scope, &name.id.to_string(), candidate.range().start()) => {
+                    InlineValue::VariableLookup(InlineValueVariableLookup { range, variable_name: Some(text.clone()), case_sensitive_lookup: true })
+                },
+                Expr::Attribute(_) if InlineValueFeature::is_field(session, &scope, candidate) => {
+                    InlineValue::EvaluatableExpression(InlineValueEvaluatableExpression { range, expression: Some(text.clone()) })
+                },
+                _ => continue,
+            };
+            shown.insert((range.start.line, text));
+            res.push(value);
+        }
+        Some(res)
+    }
+
+    /* True if the name is a variable holding a value, and not an imported module, a class or a function */
+    fn is_value_variable(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, name: &String, position: TextSize) -> bool {
+        let variables = Symbol::infer_name(session.sync_odoo, scope, name, Some(position.to_u32()));
+        let Some(variable) = variables.last() else {
+            return false;
+        };
+        if variable.borrow().typ() != SymType::VARIABLE {
+            return false;
+        }
+        let values = Symbol::follow_ref(variable, session, &mut None, false, false, None, &mut vec![]);
+        !values.iter().any(|(value, instance)| value.upgrade().is_some_and(|value| {
+            let typ = value.borrow().typ();
+            !*instance && matches!(typ, SymType::CLASS | SymType::FUNCTION | SymType::FILE | SymType::PACKAGE | SymType::NAMESPACE)
+        }))
+    }
+
+    /* True if the attribute chain is a field of a model, like order.partner_id.name: its last attribute is a field of the
+    records it is read on */
+    fn is_field(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, attribute: &Expr) -> bool {
+        let Expr::Attribute(attr) = attribute else {
+            return false;
+        };
+        let (base_evals, _) = Evaluation::eval_from_ast(session, &attr.value, scope.clone(), &attribute.range().start());
+        let Some(base) = base_evals.first().and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade()) else {
+            return false;
+        };
+        if base.borrow().typ() != SymType::CLASS || base.borrow().as_class_sym()._model.is_none() {
+            return false;
+        }
+        let (members, _) = base.borrow().get_member_symbol(session, &attr.attr.to_string(), None, false, false);
+        members.first().is_some_and(|member| PythonValidator::get_field_info(session, member).is_some())
+    }
+}
+
+/* Names and chains of attributes without calls nor subscripts, the only expressions given to the debugger */
+struct CandidateCollector {
+    candidates: Vec<Expr>,
+}
+
+impl CandidateCollector {
+
+    fn is_chain(expr: &Expr) -> bool {
+        match expr {
+            Expr::Name(_) => true,
+            Expr::Attribute(attr) => CandidateCollector::is_chain(&attr.value),
+            _ => false
+        }
+    }
+
+    fn chain_text(expr: &Expr) -> String {
+        match expr {
+            Expr::Attribute(attr) => format!("{}.{}", CandidateCollector::chain_text(&attr.value), attr.attr),
+            Expr::Name(name) => name.id.to_string(),
+            _ => String::new()
+        }
+    }
+
+    fn chain_root(expr: &Expr) -> &Expr {
+        match expr {
+            Expr::Attribute(attr) => CandidateCollector::chain_root(&attr.value),
+            _ => expr
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for CandidateCollector {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        //nested functions and classes are not executed where they are declared
+        if matches!(stmt, Stmt::FunctionDef(_) | Stmt::ClassDef(_)) {
+            return;
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(_) => self.candidates.push(expr.clone()),
+            Expr::Attribute(_) if CandidateCollector::is_chain(expr) => {
+                //the longest chain, and the variable it starts from
+                self.candidates.push(CandidateCollector::chain_root(expr).clone());
+                self.candidates.push(expr.clone());
+            },
+            _ => walk_expr(self, expr),
+        }
+    }
+}
@@ -1,4 +1,21 @@
 pub mod completion;
+pub mod code_action;
 pub mod definition;
+pub mod document_color;
+pub mod document_link;
+pub mod document_symbol;
+pub mod formatting;
 pub mod hover;
-pub mod ast_utils;
\ No newline at end of file
+pub mod implementation;
+pub mod inline_value;
+pub mod model_source;
+pub mod references;
+pub mod rename;
+pub mod scaffold;
+pub mod signature_help;
+pub mod snippets;
+pub mod translation;
+pub mod workspace_symbol;
+pub mod xml_ids;
+pub mod ast_utils;
+pub mod workspace_edit;
\ No newline at end of file
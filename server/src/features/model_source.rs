@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::request::Request;
+use lsp_types::{Location, Range};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::*;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetModelSourceParams {
+    pub model: String,
+    pub module: Option<String>, //if set, only classes visible from this module are rendered
+}
+
+/* Link a line of the rendered content to the definition it has been rendered from */
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSourceLink {
+    pub line: u32,
+    pub location: Location,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetModelSourceResult {
+    pub content: String,
+    pub links: Vec<ModelSourceLink>,
+}
+
+#[derive(Debug)]
+pub enum GetModelSourceRequest {}
+
+impl Request for GetModelSourceRequest {
+    type Params = GetModelSourceParams;
+    type Result = GetModelSourceResult;
+    const METHOD: &'static str = "Odoo/getModelSource";
+}
+
+/* Render a read-only python view of a model, made of the declarations of all the classes of the model, module by module.
+Each rendered line of declaration is linked to its source, so the client can navigate from the virtual document. */
+pub struct ModelSourceFeature {}
+
+impl ModelSourceFeature {
+
+    pub fn get_model_source(session: &mut SessionInfo, params: &GetModelSourceParams) -> Option<GetModelSourceResult> {
+        let model = session.sync_odoo.models.get(&params.model).cloned()?;
+        let classes: Vec<Rc<RefCell<Symbol>>> = match params.module.as_ref().and_then(|name| session.sync_odoo.modules.get(name)).and_then(|m| m.upgrade()) {
+            Some(module) => model.borrow().get_symbols(session, module).collect(),
+            None => model.borrow().get_all_symbols().collect(),
+        };
+        let mut classes: Vec<(bool, String, Rc<RefCell<Symbol>>)> = classes.into_iter().map(|class| {
+            let is_extension = class.borrow().as_class_sym()._model.as_ref().is_some_and(|model| model.inherit.contains(&model.name));
+            let module_name = class.borrow().find_module().map(|module| module.borrow().as_module_package().dir_name.clone()).unwrap_or_default();
+            (is_extension, module_name, class)
+        }).collect();
+        //the declaration of the model first, then its extensions
+        classes.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        let mut renderer = ModelSourceRenderer { content: String::new(), line: 0, links: vec![] };
+        renderer.push_line(&format!("# Model {} - read-only view generated by OdooLS", params.model), None);
+        for (_, module_name, class) in classes.iter() {
+            renderer.push_line("", None);
+            let Some(path) = ModelSourceFeature::file_path(class) else {
+                continue;
+            };
+            let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path);
+            let Some(file_info) = file_info else {
+                continue;
+            };
+            let file_info = file_info.borrow();
+            let class_range = class.borrow().range().clone();
+            let class_start = file_info.offset_to_position(class_range.start().to_usize());
+            renderer.push_line(&format!("# --- {} ({})", if module_name.is_empty() { "no module" } else { module_name.as_str() }, path), None);
+            let class_line = file_info.get_line(class_start.line).unwrap_or_else(|| format!("class {}:", class.borrow().name()));
+            renderer.push_line(class_line.trim(), Some(ModelSourceFeature::location(&path, &file_info, class_range.start().to_usize(), class_range.end().to_usize())));
+            let mut members: Vec<Rc<RefCell<Symbol>>> = class.borrow().all_symbols().collect();
+            members.sort_by_key(|member| member.borrow().range().start());
+            for member in members.iter() {
+                let member = member.borrow();
+                if !matches!(member.typ(), SymType::VARIABLE | SymType::FUNCTION) || member.is_comprehension() {
+                    continue;
+                }
+                let range = member.range().clone();
+                let start = file_info.offset_to_position(range.start().to_usize());
+                let Some(source_line) = file_info.get_line(start.line) else {
+                    continue;
+                };
+                let mut rendered = format!("    {}", source_line.trim());
+                if member.typ() == SymType::FUNCTION {
+                    rendered += " ...";
+                }
+                renderer.push_line(&rendered, Some(ModelSourceFeature::location(&path, &file_info, range.start().to_usize(), range.end().to_usize())));
+            }
+        }
+        Some(GetModelSourceResult {
+            content: renderer.content,
+            links: renderer.links,
+        })
+    }
+
+    pub fn file_path(class: &Rc<RefCell<Symbol>>) -> Option<String> {
+        let file_symbol = class.borrow().get_file()?.upgrade()?;
+        let file_symbol = file_symbol.borrow();
+        let mut path = file_symbol.paths().first()?.clone();
+        if file_symbol.typ() == SymType::PACKAGE {
+            path = PathBuf::from(path).join("__init__.py").sanitize() + file_symbol.as_package().i_ext().as_str();
+        }
+        Some(path)
+    }
+
+    fn location(path: &String, file_info: &FileInfo, start: usize, end: usize) -> Location {
+        Location {
+            uri: FileMgr::pathname2uri(path),
+            range: Range {
+                start: file_info.offset_to_position(start),
+                end: file_info.offset_to_position(end),
+            },
+        }
+    }
+}
+
+struct ModelSourceRenderer {
+    content: String,
+    line: u32,
+    links: Vec<ModelSourceLink>,
+}
+
+impl ModelSourceRenderer {
+
+    fn push_line(&mut self, text: &str, location: Option<Location>) {
+        if let Some(location) = location {
+            self.links.push(ModelSourceLink { line: self.line, location });
+        }
+        self.content += text;
+        self.content += "\n";
+        self.line += 1;
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+use std::{cell::RefCell, rc::Rc};
+use lsp_types::{Location, Position};
+use ruff_text_size::TextRange;
+
+use crate::constants::SymType;
+use crate::core::file_mgr::{FileMgr, FileInfo};
+use crate::core::python_validator::PythonValidator;
+use crate::core::request_progress::RequestProgress;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::features::ast_utils::AstUtils;
+use crate::utils::PathSanitizer as _;
+use crate::S;
+
+/* References of fields: their declarations in all the classes of the model, and their usages in xml files.
+It gives the impact of removing a field from a model */
+pub struct ReferenceFeature {}
+
+impl ReferenceFeature {
+
+    pub fn get_references(session: &mut SessionInfo,
+        file_symbol: &Rc<RefCell<Symbol>>,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32,
+        progress: &mut RequestProgress
+    ) -> Option<Vec<Location>> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let (model_name, field, _range) = ReferenceFeature::get_field_at(session, file_symbol, file_info, offset as u32)?;
+        let field_name = field.borrow().name().clone();
+        Some(ReferenceFeature::get_field_references(session, &model_name, &field_name, progress))
+    }
+
+    /* The field under the offset, with the name of its model and the range of the expression giving it */
+    pub fn get_field_at(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> Option<(String, Rc<RefCell<Symbol>>, TextRange)> {
+        let (analyse_ast_result, range) = AstUtils::get_symbols(session, file_symbol, file_info, offset);
+        for eval in analyse_ast_result.evaluations.iter() {
+            let Some(symbol) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade() else {
+                continue;
+            };
+            if PythonValidator::get_field_info(session, &symbol).is_none() {
+                continue;
+            }
+            let Some(class) = symbol.borrow().parent().and_then(|p| p.upgrade()) else {
+                continue;
+            };
+            if class.borrow().typ() != SymType::CLASS {
+                continue;
+            }
+            let Some(model_name) = class.borrow().as_class_sym()._model.as_ref().map(|model| model.name.clone()) else {
+                continue;
+            };
+            return Some((model_name, symbol, range?));
+        }
+        None
+    }
+
+    /* References from a field used in a xml file, if this usage has been indexed */
+    pub fn get_xml_references(session: &mut SessionInfo, path: &String, position: &Position, progress: &mut RequestProgress) -> Option<Vec<Location>> {
+        let (model_name, field_name) = session.sync_odoo.xml_fields.get_usage_at(path, position)?;
+        Some(ReferenceFeature::get_field_references(session, &model_name, &field_name, progress))
+    }
+
+    /* The declarations of the field in the classes of the model, then its usages in xml files. Each class and each xml file is
+    a step of the progress, and its locations are streamed if the client asked for partial results: they are not in the
+    returned ones. The search stops when the request is cancelled */
+    pub fn get_field_references(session: &mut SessionInfo, model_name: &String, field_name: &String, progress: &mut RequestProgress) -> Vec<Location> {
+        let mut locations = vec![];
+        let Some(model) = session.sync_odoo.models.get(model_name).cloned() else {
+            return locations;
+        };
+        let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+        let mut usages: Vec<(String, Vec<Location>)> = vec![];
+        for (path, range) in session.sync_odoo.xml_fields.get_usages(model_name, field_name).iter() {
+            let location = Location { uri: FileMgr::pathname2uri(path), range: range.clone() };
+            match usages.iter_mut().find(|(usage_path, _)| usage_path == path) {
+                Some((_, file_usages)) => file_usages.push(location),
+                None => usages.push((path.clone(), vec![location])),
+            }
+        }
+        progress.add_total(classes.len() + usages.len());
+        for class in classes.iter() {
+            let mut class_locations = vec![];
+            let fields = class.borrow().get_symbol(&(vec![], vec![field_name.clone()]), u32::MAX);
+            for field in fields.iter() {
+                if PythonValidator::get_field_info(session, field).is_none() {
+                    continue;
+                }
+                let Some(file) = field.borrow().get_file().and_then(|f| f.upgrade()) else {
+                    continue;
+                };
+                let mut path = file.borrow().paths().first().cloned().unwrap_or(S!(""));
+                if file.borrow().typ() == SymType::PACKAGE {
+                    path = PathBuf::from(path).join("__init__.py").sanitize() + file.borrow().as_package().i_ext().as_str();
+                }
+                let range = field.borrow().range().clone();
+                class_locations.push(Location {
+                    uri: FileMgr::pathname2uri(&path),
+                    range: session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, &path, &range),
+                });
+            }
+            progress.add_results(session, &mut locations, class_locations);
+            if !progress.step(session, class.borrow().name()) {
+                return locations;
+            }
+        }
+        for (path, file_usages) in usages.into_iter() {
+            progress.add_results(session, &mut locations, file_usages);
+            if !progress.step(session, &path) {
+                break;
+            }
+        }
+        locations
+    }
+}
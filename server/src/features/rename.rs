@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+use std::{cell::RefCell, rc::Rc};
+
+use lsp_types::{PrepareRenameResponse, TextEdit, WorkspaceEdit};
+use ruff_python_ast::visitor::{Visitor, walk_expr};
+use ruff_python_ast::{Expr, ExprAttribute, Stmt};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::constants::SymType;
+use crate::core::domain_validator::MAGIC_FIELDS;
+use crate::core::evaluation::Evaluation;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::request_progress::RequestProgress;
+use crate::core::symbols::symbol::Symbol;
+use crate::features::references::ReferenceFeature;
+use crate::features::workspace_edit::WorkspaceEditBuilder;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+use crate::S;
+
+static PYTHON_KEYWORDS: &[&str] = &["False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield"];
+
+/* Rename of the fields of models: their declarations in all the classes of the model, their usages as attributes of records
+in the python files of the workspace and their usages in xml files. Other symbols are refused by prepareRename, so the client
+never starts a rename that the server can't complete. Field names written in strings (domains, api.depends...) are not renamed. */
+pub struct RenameFeature {}
+
+impl RenameFeature {
+
+    /* Return the range of the name to rename, or the reason why the symbol can't be renamed */
+    pub fn prepare_rename(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Result<PrepareRenameResponse, String> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let (_model_name, _field, range) = RenameFeature::get_renameable_field(session, file_symbol, file_info, offset as u32)?;
+        let path = file_symbol.borrow().paths()[0].clone();
+        let path = RenameFeature::file_path(file_symbol, &path);
+        Ok(PrepareRenameResponse::Range(session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, &path, &range)))
+    }
+
+    /* The python files of the workspace are evaluated to find the usages of the field: it is the long part of the rename, reported
+    file by file. A cancelled rename returns an error instead of an incomplete edit */
+    pub fn rename(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32, new_name: &String, progress: &mut RequestProgress) -> Result<WorkspaceEdit, String> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let (model_name, field, _range) = RenameFeature::get_renameable_field(session, file_symbol, file_info, offset as u32)?;
+        let field_name = field.borrow().name().clone();
+        if !RenameFeature::is_identifier(new_name) {
+            return Err(format!("'{}' is not a valid field name", new_name));
+        }
+        if let Some(model) = session.sync_odoo.models.get(&model_name).cloned() {
+            let classes: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_all_symbols().collect();
+            if classes.iter().any(|class| !class.borrow().get_symbol(&(vec![], vec![new_name.clone()]), u32::MAX).is_empty()) {
+                return Err(format!("{} already has a member named {}", model_name, new_name));
+            }
+        }
+        let mut builder = WorkspaceEditBuilder::new(&format!("Rename {}.{} to {}", model_name, field_name, new_name));
+        progress.begin(session, &format!("Renaming {}.{}", model_name, field_name));
+        let files = RenameFeature::workspace_files(session);
+        progress.add_total(files.len());
+        let references = ReferenceFeature::get_field_references(session, &model_name, &field_name, progress);
+        let usages = RenameFeature::get_python_usages(session, &files, &model_name, &field_name, progress);
+        progress.end(session, None);
+        let Some(usages) = usages else {
+            return Err(S!("The rename has been cancelled"));
+        };
+        for location in references.into_iter() {
+            builder.add_edit(&FileMgr::uri2pathname(location.uri.as_str()), TextEdit { range: location.range, new_text: new_name.clone() });
+        }
+        for (path, range) in usages.into_iter() {
+            let range = session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, &path, &range);
+            builder.add_edit(&path, TextEdit { range, new_text: new_name.clone() });
+        }
+        Ok(builder.build(session))
+    }
+
+    /* The field under the offset, if it can be renamed: a field of a model, that is not a magic field, and whose declarations
+    are all in the workspace. Return the name of the model, the field and the range of its name */
+    fn get_renameable_field(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> Result<(String, Rc<RefCell<Symbol>>, TextRange), String> {
+        let Some((model_name, field, expr_range)) = ReferenceFeature::get_field_at(session, file_symbol, file_info, offset) else {
+            return Err(S!("Only the fields of models can be renamed"));
+        };
+        let field_name = field.borrow().name().clone();
+        if MAGIC_FIELDS.contains(&field_name.as_str()) {
+            return Err(format!("{} is a magic field of Odoo and can't be renamed", field_name));
+        }
+        //the name is at the end of the expression: 'field' or 'records.field'
+        let name_len = TextSize::new(field_name.len() as u32);
+        if expr_range.len() < name_len {
+            return Err(format!("Unable to find the name of {}", field_name));
+        }
+        let range = TextRange::new(expr_range.end() - name_len, expr_range.end());
+        if file_info.borrow().get_text(&range) != Some(field_name.clone()) {
+            return Err(format!("Unable to find the name of {}", field_name));
+        }
+        let odoo_path = PathBuf::from(&session.sync_odoo.config.odoo_path);
+        let classes: Vec<Rc<RefCell<Symbol>>> = session.sync_odoo.models.get(&model_name).map(|model| model.borrow().get_all_symbols().collect()).unwrap_or_default();
+        for class in classes.iter() {
+            if class.borrow().get_symbol(&(vec![], vec![field_name.clone()]), u32::MAX).is_empty() {
+                continue;
+            }
+            let Some(file) = class.borrow().get_file().and_then(|f| f.upgrade()) else {
+                continue;
+            };
+            let file_path = file.borrow().paths().first().cloned().unwrap_or_default();
+            if file.borrow().is_external() || !file.borrow().in_workspace() || PathBuf::from(&file_path).starts_with(&odoo_path) {
+                let module_name = file.borrow().find_module().map(|module| module.borrow().as_module_package().dir_name.clone()).unwrap_or(file_path);
+                return Err(format!("{} is declared in {}, outside of the workspace, and can't be renamed", field_name, module_name));
+            }
+        }
+        Ok((model_name, field, range))
+    }
+
+    /* Attributes named like the field in the python files of the workspace, whose base is a record of the model. None if the
+    request has been cancelled */
+    fn get_python_usages(session: &mut SessionInfo, files: &Vec<Rc<RefCell<Symbol>>>, model_name: &String, field_name: &String, progress: &mut RequestProgress) -> Option<Vec<(String, TextRange)>> {
+        let mut res = vec![];
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        for file_symbol in files.iter() {
+            let path = file_symbol.borrow().paths()[0].clone();
+            let path = RenameFeature::file_path(file_symbol, &path);
+            if !progress.step(session, &path) {
+                return None;
+            }
+            let Some(file_info) = file_mgr.borrow().get_file_info(&path) else {
+                continue;
+            };
+            let attributes: Vec<ExprAttribute> = match file_info.borrow().ast.as_ref() {
+                Some(ast) => RenameFeature::collect_attributes(ast, field_name),
+                None => continue,
+            };
+            for attribute in attributes.iter() {
+                let scope = Symbol::get_scope_symbol(file_symbol.clone(), attribute.range.start().to_u32(), false);
+                let (evaluations, _) = Evaluation::eval_from_ast(session, &attribute.value, scope, &attribute.range.start());
+                let is_model = evaluations.iter().any(|eval| {
+                    let Some(base) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade() else {
+                        return false;
+                    };
+                    Symbol::follow_ref(&base, session, &mut None, false, false, None, &mut vec![]).iter().any(|(class, _)| {
+                        class.upgrade().is_some_and(|class| class.borrow().typ() == SymType::CLASS &&
+                            class.borrow().as_class_sym()._model.as_ref().is_some_and(|model| model.name == *model_name))
+                    })
+                });
+                if is_model {
+                    res.push((path.clone(), attribute.attr.range));
+                }
+            }
+        }
+        Some(res)
+    }
+
+    fn workspace_files(session: &mut SessionInfo) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut res = vec![];
+        let mut to_visit: Vec<Rc<RefCell<Symbol>>> = session.sync_odoo.modules.values().filter_map(|module| module.upgrade())
+            .filter(|module| module.borrow().in_workspace()).collect();
+        while let Some(symbol) = to_visit.pop() {
+            if [SymType::FILE, SymType::PACKAGE].contains(&symbol.borrow().typ()) {
+                res.push(symbol.clone());
+            }
+            to_visit.extend(symbol.borrow().all_module_symbol().cloned());
+        }
+        res
+    }
+
+    fn file_path(file_symbol: &Rc<RefCell<Symbol>>, path: &String) -> String {
+        if file_symbol.borrow().typ() == SymType::PACKAGE {
+            return PathBuf::from(path).join("__init__.py").sanitize() + file_symbol.borrow().as_package().i_ext().as_str();
+        }
+        path.clone()
+    }
+
+    fn collect_attributes(stmts: &Vec<Stmt>, name: &String) -> Vec<ExprAttribute> {
+        let mut visitor = AttributeCollectorVisitor { name, attributes: vec![] };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.attributes
+    }
+
+    fn is_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_') && !PYTHON_KEYWORDS.contains(&name)
+    }
+}
+
+struct AttributeCollectorVisitor<'a> {
+    name: &'a String,
+    attributes: Vec<ExprAttribute>,
+}
+
+impl<'a, 'b> Visitor<'b> for AttributeCollectorVisitor<'a> {
+
+    fn visit_expr(&mut self, expr: &'b Expr) {
+        if let Expr::Attribute(attribute) = expr {
+            if attribute.attr.as_str() == self.name.as_str() {
+                self.attributes.push(attribute.clone());
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
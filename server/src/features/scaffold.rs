@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use lsp_types::request::Request;
+use lsp_types::{CreateFile, DocumentChangeOperation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier, Range, ResourceOp, ResourceOperationKind, TextDocumentEdit, TextEdit, WorkspaceEdit};
+use serde::{Deserialize, Serialize};
+
+use crate::core::file_mgr::FileMgr;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaffoldParams {
+    pub name: String, //technical name of the module, used as its directory name
+    pub addons_path: Option<String>, //default to the first addons path of the configuration
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaffoldResult {
+    pub path: String, //directory of the new module
+    pub applied: bool, //false if the client refused the edit
+}
+
+/* Create the skeleton of a new module, like `odoo-bin scaffold`. The files are created by a workspace/applyEdit request, so
+the client shows them like any other edit and the server indexes them through the usual notifications */
+#[derive(Debug)]
+pub enum ScaffoldRequest {}
+
+impl Request for ScaffoldRequest {
+    type Params = ScaffoldParams;
+    type Result = ScaffoldResult;
+    const METHOD: &'static str = "Odoo/scaffold";
+}
+
+pub struct ScaffoldFeature {}
+
+impl ScaffoldFeature {
+
+    /* A module name must be importable as odoo.addons.<name> */
+    pub fn is_valid_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|first| first.is_ascii_lowercase() || first == '_')
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+
+    /* Files of the new module, relative to its directory, with their content. List views are <tree> before Odoo 18 */
+    pub fn module_files(name: &str, version_major: u32) -> Vec<(&'static str, String)> {
+        let title: Vec<String> = name.split('_').filter(|word| !word.is_empty()).map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        }).collect();
+        let title = title.join(" ");
+        let model = format!("{0}.{0}", name);
+        let model_id = model.replace('.', "_");
+        let list_tag = if version_major != 0 && version_major < 18 { "tree" } else { "list" };
+        vec![
+            ("__init__.py", String::from("from . import models\n")),
+            ("__manifest__.py", format!("{{\n    'name': '{}',\n    'version': '1.0',\n    'summary': '',\n    'depends': ['base'],\n    'data': [\n        'security/ir.model.access.csv',\n        'views/views.xml',\n    ],\n    'license': 'LGPL-3',\n}}\n", title)),
+            ("models/__init__.py", String::from("from . import models\n")),
+            ("models/models.py", format!("from odoo import fields, models\n\n\nclass {}(models.Model):\n    _name = '{}'\n    _description = '{}'\n\n    name = fields.Char(required=True)\n", name, model, title)),
+            ("security/ir.model.access.csv", format!("id,name,model_id:id,group_id:id,perm_read,perm_write,perm_create,perm_unlink\naccess_{0},access_{0},model_{0},base.group_user,1,1,1,1\n", model_id)),
+            ("views/views.xml", format!("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<odoo>\n    <record id=\"{0}_view_list\" model=\"ir.ui.view\">\n        <field name=\"name\">{1}.list</field>\n        <field name=\"model\">{1}</field>\n        <field name=\"arch\" type=\"xml\">\n            <{2}>\n                <field name=\"name\"/>\n            </{2}>\n        </field>\n    </record>\n</odoo>\n", model_id, model, list_tag)),
+        ]
+    }
+
+    /* Edit creating the files of the module in its directory, and filling them */
+    pub fn build_edit(module_dir: &Path, name: &str, version_major: u32) -> WorkspaceEdit {
+        let mut operations = vec![];
+        for (relative_path, content) in ScaffoldFeature::module_files(name, version_major) {
+            let uri = FileMgr::pathname2uri(&module_dir.join(relative_path).sanitize());
+            operations.push(DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+                uri: uri.clone(),
+                options: None,
+                annotation_id: None,
+            })));
+            operations.push(DocumentChangeOperation::Edit(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: vec![OneOf::Left(TextEdit { range: Range::default(), new_text: content })],
+            }));
+        }
+        WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Operations(operations)),
+            ..Default::default()
+        }
+    }
+
+    /* Files can only be created by the clients supporting the create resource operation */
+    pub fn supports_create(session: &mut SessionInfo) -> bool {
+        session.sync_odoo.capabilities.workspace.as_ref()
+            .and_then(|workspace| workspace.workspace_edit.as_ref())
+            .and_then(|workspace_edit| workspace_edit.resource_operations.as_ref())
+            .is_some_and(|operations| operations.contains(&ResourceOperationKind::Create))
+    }
+}
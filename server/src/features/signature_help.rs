@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp, SignatureInformation};
+use ruff_python_ast::ExprCall;
+use ruff_text_size::{Ranged, TextSize};
+
+use crate::constants::SymType;
+use crate::core::evaluation::Evaluation;
+use crate::core::file_mgr::FileInfo;
+use crate::core::symbols::function_symbol::FunctionSymbol;
+use crate::core::symbols::symbol::Symbol;
+use crate::features::ast_utils::AstUtils;
+use crate::threads::SessionInfo;
+
+pub struct SignatureHelpFeature {}
+
+impl SignatureHelpFeature {
+
+    /* Signature of the function called around the position, with the documentation of its parameters taken from its parsed
+    docstring, and the parameter of the argument under the cursor */
+    pub fn get_signature_help(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<SignatureHelp> {
+        let offset = file_info.borrow().position_to_offset(line, character);
+        let call = {
+            let file_info = file_info.borrow();
+            AstUtils::find_call_at(file_info.ast.as_ref()?, TextSize::new(offset as u32))?.clone()
+        };
+        let function = SignatureHelpFeature::called_function(session, file_symbol, &call, offset)?;
+        let function = function.borrow();
+        let function = function.as_func();
+        let mut label = format!("{}(", function.name);
+        let mut names = vec![];
+        let mut parameters = vec![];
+        for (index, arg) in function.args.iter().enumerate() {
+            let Some(arg_symbol) = arg.symbol.upgrade() else {
+                continue;
+            };
+            let name = arg_symbol.borrow().name().clone();
+            if index > 0 {
+                label += ", ";
+            }
+            let prefix = if arg.is_kwargs { "**" } else if arg.is_args { "*" } else { "" };
+            let start = label.encode_utf16().count() as u32;
+            label += &format!("{}{}", prefix, name);
+            let end = label.encode_utf16().count() as u32;
+            parameters.push(ParameterInformation {
+                label: ParameterLabel::LabelOffsets([start, end]),
+                documentation: SignatureHelpFeature::param_documentation(function, &name),
+            });
+            names.push(name);
+        }
+        label += ")";
+        let documentation = match function.doc.as_ref() {
+            Some(doc) => Some(doc.summary.clone()).filter(|summary| !summary.is_empty()),
+            None => function.doc_string.clone(),
+        };
+        let active_parameter = SignatureHelpFeature::active_parameter(function, &names, &call, offset);
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label,
+                documentation: documentation.map(|value| Documentation::MarkupContent(MarkupContent { kind: MarkupKind::Markdown, value })),
+                parameters: Some(parameters),
+                active_parameter,
+            }],
+            active_signature: Some(0),
+            active_parameter,
+        })
+    }
+
+    /* Function evaluated from the callee of the call. A forwarding override has the signature of the method it forwards to */
+    fn called_function(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, call: &ExprCall, offset: usize) -> Option<Rc<RefCell<Symbol>>> {
+        let scope = Symbol::get_scope_symbol(file_symbol.clone(), offset as u32, false);
+        let evaluations = Evaluation::eval_from_ast(session, &call.func, scope, &call.func.range().start()).0;
+        for evaluation in evaluations.iter() {
+            let Some(symbol) = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], Some(file_symbol.clone())).0.upgrade() else {
+                continue;
+            };
+            for (symbol, _) in Symbol::follow_ref(&symbol, session, &mut None, true, false, None, &mut vec![]).iter() {
+                let Some(symbol) = symbol.upgrade() else {
+                    continue;
+                };
+                if symbol.borrow().typ() != SymType::FUNCTION || symbol.borrow().as_func().is_property {
+                    continue;
+                }
+                if symbol.borrow().as_func().is_forwarding {
+                    if let Some(forwarded) = FunctionSymbol::get_forwarded_method(session, &symbol) {
+                        return Some(forwarded);
+                    }
+                }
+                return Some(symbol);
+            }
+        }
+        None
+    }
+
+    /* Description of the parameter in the docstring, with its documented type */
+    fn param_documentation(function: &FunctionSymbol, name: &String) -> Option<Documentation> {
+        let param = function.doc.as_ref()?.get_param(name)?;
+        let mut value = String::new();
+        if let Some(typ) = param.typ.as_ref() {
+            value += &format!("`{}`", typ);
+            if !param.description.is_empty() {
+                value += ": ";
+            }
+        }
+        value += &param.description;
+        if value.is_empty() {
+            return None;
+        }
+        Some(Documentation::MarkupContent(MarkupContent { kind: MarkupKind::Markdown, value }))
+    }
+
+    /* Index of the parameter receiving the argument at the offset: the one named by a keyword argument, else the position of
+    the argument, after self or cls for a method. The positions past the last parameter go to *args */
+    fn active_parameter(function: &FunctionSymbol, names: &Vec<String>, call: &ExprCall, offset: usize) -> Option<u32> {
+        let offset = TextSize::new(offset as u32);
+        if let Some(keyword) = call.arguments.keywords.iter().find(|keyword| keyword.range().contains_inclusive(offset)) {
+            let name = keyword.arg.as_ref()?;
+            return names.iter().position(|arg_name| arg_name == name.as_str()).map(|index| index as u32);
+        }
+        let is_method = !function.is_static && function.parent.as_ref().and_then(|parent| parent.upgrade())
+            .is_some_and(|parent| parent.borrow().typ() == SymType::CLASS);
+        let mut index = call.arguments.args.iter().filter(|arg| arg.range().end() < offset).count();
+        if is_method {
+            index += 1;
+        }
+        let positional = function.args.iter().position(|arg| arg.is_args || arg.is_kwargs).unwrap_or(function.args.len());
+        if index >= positional {
+            return function.args.iter().position(|arg| arg.is_args).map(|index| index as u32);
+        }
+        Some(index as u32)
+    }
+}
@@ -0,0 +1,151 @@
+use lsp_types::{CompletionItem, CompletionItemKind, InsertTextFormat, InsertTextMode};
+use ruff_python_ast::{Expr, Stmt, StmtClassDef};
+use ruff_text_size::Ranged;
+
+use crate::core::file_mgr::FileInfo;
+use crate::threads::SessionInfo;
+
+/* Where a snippet can be inserted */
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum SnippetContext {
+    Module,
+    ModelClass,
+    ControllerClass,
+}
+
+struct Snippet {
+    label: &'static str,
+    detail: &'static str,
+    context: SnippetContext,
+    body: &'static str,
+}
+
+static SNIPPETS: &[Snippet] = &[
+    Snippet {
+        label: "model",
+        detail: "New Odoo model",
+        context: SnippetContext::Module,
+        body: "class ${1:ModelName}(models.Model):\n    _name = '${2:model.name}'\n    _description = '${3:Description}'\n\n    ${0}",
+    },
+    Snippet {
+        label: "inherit",
+        detail: "Extension of an existing model",
+        context: SnippetContext::Module,
+        body: "class ${1:ModelName}(models.Model):\n    _inherit = '${2:model.name}'\n\n    ${0}",
+    },
+    Snippet {
+        label: "controller",
+        detail: "New http controller",
+        context: SnippetContext::Module,
+        body: "class ${1:Controller}(http.Controller):\n\n    @http.route('/${2:path}', type='${3|http,json|}', auth='${4|user,public,none|}')\n    def ${5:handler}(self, **kwargs):\n        ${0:pass}",
+    },
+    Snippet {
+        label: "compute",
+        detail: "Computed field with its compute method",
+        context: SnippetContext::ModelClass,
+        body: "${1:field_name} = fields.${2:Char}(compute='_compute_$1')\n\n@api.depends('${3}')\ndef _compute_$1(self):\n    for record in self:\n        record.$1 = ${0:False}",
+    },
+    Snippet {
+        label: "onchange",
+        detail: "Onchange method",
+        context: SnippetContext::ModelClass,
+        body: "@api.onchange('${1:field_name}')\ndef _onchange_$1(self):\n    ${0:pass}",
+    },
+    Snippet {
+        label: "constrains",
+        detail: "Python constraint",
+        context: SnippetContext::ModelClass,
+        body: "@api.constrains('${1:field_name}')\ndef _check_$1(self):\n    for record in self:\n        if ${2:not record.$1}:\n            raise ValidationError(${3:_(\"${4:Invalid value}\")})",
+    },
+    Snippet {
+        label: "sql_constraint",
+        detail: "SQL constraint",
+        context: SnippetContext::ModelClass,
+        body: "_sql_constraints = [\n    ('${1:name}_uniq', '${2:unique(name)}', '${3:The name must be unique.}'),\n]",
+    },
+    Snippet {
+        label: "route",
+        detail: "Http route",
+        context: SnippetContext::ControllerClass,
+        body: "@http.route('/${1:path}', type='${2|http,json|}', auth='${3|user,public,none|}')\ndef ${4:handler}(self, **kwargs):\n    ${0:pass}",
+    },
+];
+
+/* Snippets for the boilerplate of Odoo code, proposed at the start of a statement, depending on where the cursor is */
+pub struct SnippetFeature {}
+
+impl SnippetFeature {
+
+    pub fn get_snippets(session: &mut SessionInfo, file_info: &FileInfo, line: u32, character: u32) -> Vec<CompletionItem> {
+        let snippet_support = session.sync_odoo.capabilities.text_document.as_ref()
+            .and_then(|text_document| text_document.completion.as_ref())
+            .and_then(|completion| completion.completion_item.as_ref())
+            .and_then(|completion_item| completion_item.snippet_support)
+            .unwrap_or(false);
+        if !snippet_support {
+            return vec![];
+        }
+        let Some(text) = file_info.get_line(line) else {
+            return vec![];
+        };
+        let typed: String = text.chars().take(character as usize).collect();
+        let typed = typed.trim_start();
+        if typed.is_empty() || !typed.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return vec![];
+        }
+        let Some(ast) = file_info.ast.as_ref() else {
+            return vec![];
+        };
+        let offset = file_info.position_to_offset(line, character);
+        let Some(context) = SnippetFeature::get_context(ast, offset) else {
+            return vec![];
+        };
+        SNIPPETS.iter().filter(|snippet| snippet.context == context && snippet.label.starts_with(typed)).map(|snippet| CompletionItem {
+            label: snippet.label.to_string(),
+            kind: Some(CompletionItemKind::SNIPPET),
+            detail: Some(snippet.detail.to_string()),
+            insert_text: Some(snippet.body.to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
+            sort_text: Some(format!("~{}", snippet.label)), //after the symbols
+            ..Default::default()
+        }).collect()
+    }
+
+    /* Find the innermost class or function containing the offset. Snippets are not proposed in functions */
+    fn get_context(stmts: &[Stmt], offset: usize) -> Option<SnippetContext> {
+        for stmt in stmts.iter() {
+            if stmt.range().start().to_usize() > offset || stmt.range().end().to_usize() < offset {
+                continue;
+            }
+            match stmt {
+                Stmt::ClassDef(class) if class.body.first().is_some_and(|first| first.range().start().to_usize() <= offset) => {
+                    return match SnippetFeature::get_context(&class.body, offset) {
+                        Some(SnippetContext::Module) => SnippetFeature::get_class_context(class),
+                        other => other,
+                    };
+                },
+                Stmt::FunctionDef(func) if func.body.first().is_some_and(|first| first.range().start().to_usize() <= offset) => {
+                    return None;
+                },
+                _ => {}
+            }
+        }
+        Some(SnippetContext::Module)
+    }
+
+    fn get_class_context(class: &StmtClassDef) -> Option<SnippetContext> {
+        let base_names: Vec<&str> = class.bases().iter().filter_map(|base| match base {
+            Expr::Attribute(attr) => Some(attr.attr.as_str()),
+            Expr::Name(name) => Some(name.id.as_str()),
+            _ => None
+        }).collect();
+        if base_names.iter().any(|name| *name == "Controller") {
+            return Some(SnippetContext::ControllerClass);
+        }
+        if base_names.iter().any(|name| ["Model", "TransientModel", "AbstractModel"].contains(name)) {
+            return Some(SnippetContext::ModelClass);
+        }
+        None
+    }
+}
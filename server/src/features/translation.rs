@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use lsp_types::request::Request;
+use ruff_python_ast::visitor::{Visitor, walk_expr};
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+use serde::{Deserialize, Serialize};
+
+use crate::threads::SessionInfo;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPotPreviewParams {
+    pub module: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslatableString {
+    pub msgid: String,
+    pub occurrences: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleTranslations {
+    pub module: String,
+    pub strings: Vec<TranslatableString>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPotPreviewResult {
+    pub modules: Vec<ModuleTranslations>,
+}
+
+#[derive(Debug)]
+pub enum ExportPotPreviewRequest {}
+
+impl Request for ExportPotPreviewRequest {
+    type Params = ExportPotPreviewParams;
+    type Result = ExportPotPreviewResult;
+    const METHOD: &'static str = "Odoo/exportPotPreview";
+}
+
+/* A call to a translation function (_(), _lt() or env._()).
+msgid is None if the first argument is not a string literal, as the string can't be extracted for translation */
+#[derive(Debug)]
+pub struct TranslationCall {
+    pub msgid: Option<String>,
+    pub range: TextRange,
+}
+
+pub struct TranslationCallVisitor {
+    calls: Vec<TranslationCall>,
+}
+
+impl TranslationCallVisitor {
+
+    pub fn collect(ast: &Vec<Stmt>) -> Vec<TranslationCall> {
+        let mut visitor = Self {
+            calls: vec![],
+        };
+        for stmt in ast.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.calls
+    }
+
+    fn is_translation_func(func: &Expr) -> bool {
+        match func {
+            Expr::Name(name) => name.id.as_str() == "_" || name.id.as_str() == "_lt",
+            Expr::Attribute(attr) => attr.attr.as_str() == "_",
+            _ => false
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for TranslationCallVisitor {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Call(call) = expr {
+            if TranslationCallVisitor::is_translation_func(&call.func) {
+                if let Some(arg) = call.arguments.args.first() {
+                    self.calls.push(TranslationCall {
+                        msgid: match arg {
+                            Expr::StringLiteral(s) => Some(s.value.to_string()),
+                            _ => None
+                        },
+                        range: arg.range(),
+                    });
+                }
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+pub struct TranslationFeature {}
+
+impl TranslationFeature {
+
+    /* List the translatable strings of the modules in the workspace, grouped by msgid like in a .pot file */
+    pub fn export_pot_preview(session: &mut SessionInfo, params: &ExportPotPreviewParams) -> ExportPotPreviewResult {
+        let mut modules: Vec<(String, String)> = vec![];
+        for (name, module) in session.sync_odoo.modules.iter() {
+            if params.module.as_ref().is_some_and(|m| m != name) {
+                continue;
+            }
+            let Some(module) = module.upgrade() else {
+                continue;
+            };
+            let module = module.borrow();
+            if !module.in_workspace() {
+                continue;
+            }
+            modules.push((name.clone(), module.as_module_package().path.clone()));
+        }
+        modules.sort();
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let file_mgr = file_mgr.borrow();
+        let mut result = vec![];
+        for (module_name, module_path) in modules.iter() {
+            let mut strings: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            let mut paths: Vec<&String> = file_mgr.files.keys().filter(|p| PathBuf::from(p).starts_with(module_path)).collect();
+            paths.sort();
+            for path in paths {
+                let file_info = file_mgr.files.get(path).unwrap().borrow();
+                let Some(ast) = file_info.ast.as_ref() else {
+                    continue;
+                };
+                let relative_path = PathBuf::from(path).strip_prefix(module_path).map(|p| p.to_string_lossy().to_string()).unwrap_or(path.clone());
+                for call in TranslationCallVisitor::collect(ast) {
+                    let Some(msgid) = call.msgid else {
+                        continue;
+                    };
+                    let position = file_info.offset_to_position(call.range.start().to_usize());
+                    strings.entry(msgid).or_default().push(format!("{}:{}", relative_path, position.line + 1));
+                }
+            }
+            result.push(ModuleTranslations {
+                module: module_name.clone(),
+                strings: strings.into_iter().map(|(msgid, occurrences)| TranslatableString {msgid, occurrences}).collect(),
+            });
+        }
+        ExportPotPreviewResult {
+            modules: result,
+        }
+    }
+}
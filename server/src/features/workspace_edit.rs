@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use lsp_types::{AnnotatedTextEdit, ChangeAnnotation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier, TextDocumentEdit, TextEdit, WorkspaceEdit};
+
+use crate::core::file_mgr::FileMgr;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* Collect the text edits of a refactoring spanning many files (rename, organize imports...) and build the WorkspaceEdit to send.
+If the client supports change annotations, edits are annotated per Odoo module and flagged as needing confirmation, so the user
+can preview the changes and deselect whole modules before applying them. */
+pub struct WorkspaceEditBuilder {
+    label: String,
+    files: Vec<(String, Vec<TextEdit>)>,
+}
+
+impl WorkspaceEditBuilder {
+
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: S!(label),
+            files: vec![],
+        }
+    }
+
+    pub fn add_edit(&mut self, path: &String, edit: TextEdit) {
+        match self.files.iter_mut().find(|(p, _)| p == path) {
+            Some((_, edits)) => edits.push(edit),
+            None => self.files.push((path.clone(), vec![edit])),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn build(self, session: &mut SessionInfo) -> WorkspaceEdit {
+        if !WorkspaceEditBuilder::supports_change_annotations(session) {
+            let mut changes = HashMap::new();
+            for (path, edits) in self.files.into_iter() {
+                changes.insert(FileMgr::pathname2uri(&path), edits);
+            }
+            return WorkspaceEdit {
+                changes: Some(changes),
+                ..Default::default()
+            };
+        }
+        let mut document_changes = vec![];
+        let mut annotations: HashMap<String, ChangeAnnotation> = HashMap::new();
+        for (path, edits) in self.files.into_iter() {
+            let module_name = WorkspaceEditBuilder::owning_module(session, &path);
+            let annotation_id = match &module_name {
+                Some(name) => format!("module:{}", name),
+                None => S!("other"),
+            };
+            if !annotations.contains_key(&annotation_id) {
+                annotations.insert(annotation_id.clone(), ChangeAnnotation {
+                    label: match &module_name {
+                        Some(name) => name.clone(),
+                        None => S!("Files outside of Odoo modules"),
+                    },
+                    needs_confirmation: Some(true),
+                    description: Some(self.label.clone()),
+                });
+            }
+            let version = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path)
+                .filter(|file_info| file_info.borrow().opened)
+                .map(|file_info| file_info.borrow().version);
+            document_changes.push(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier {
+                    uri: FileMgr::pathname2uri(&path),
+                    version,
+                },
+                edits: edits.into_iter().map(|text_edit| OneOf::Right(AnnotatedTextEdit {
+                    text_edit,
+                    annotation_id: annotation_id.clone(),
+                })).collect(),
+            });
+        }
+        WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Edits(document_changes)),
+            change_annotations: Some(annotations),
+        }
+    }
+
+    /* Annotated edits are only valid in document_changes, so the client has to support both */
+    fn supports_change_annotations(session: &mut SessionInfo) -> bool {
+        let Some(workspace_edit) = session.sync_odoo.capabilities.workspace.as_ref().and_then(|w| w.workspace_edit.as_ref()) else {
+            return false;
+        };
+        workspace_edit.document_changes.unwrap_or(false) && workspace_edit.change_annotation_support.is_some()
+    }
+
+    fn owning_module(session: &mut SessionInfo, path: &String) -> Option<String> {
+        let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(path))?;
+        let module = file_symbol.borrow().find_module()?;
+        let dir_name = module.borrow().as_module_package().dir_name.clone();
+        Some(dir_name)
+    }
+}
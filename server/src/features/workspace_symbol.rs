@@ -0,0 +1,37 @@
+use lsp_types::{Location, Range, SymbolInformation, SymbolKind, WorkspaceSymbolResponse};
+
+use crate::core::file_mgr::FileMgr;
+use crate::threads::SessionInfo;
+
+/* Workspace symbols are the urls of the http controllers, to find the handler of an url */
+pub struct WorkspaceSymbolFeature {}
+
+impl WorkspaceSymbolFeature {
+
+    pub fn get_workspace_symbols(session: &mut SessionInfo, query: &str) -> Option<WorkspaceSymbolResponse> {
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let mut symbols = vec![];
+        for route in session.sync_odoo.routes.search(query) {
+            let Some(file_info) = file_mgr.borrow().get_file_info(&route.file_path) else {
+                continue;
+            };
+            let file_info = file_info.borrow();
+            #[allow(deprecated)]
+            symbols.push(SymbolInformation {
+                name: route.decl.path.clone(),
+                kind: SymbolKind::FUNCTION,
+                tags: None,
+                deprecated: None,
+                location: Location {
+                    uri: FileMgr::pathname2uri(&route.file_path),
+                    range: Range::new(
+                        file_info.offset_to_position(route.decl.range.start().to_usize()),
+                        file_info.offset_to_position(route.decl.range.end().to_usize())
+                    ),
+                },
+                container_name: Some(route.handler_name()),
+            });
+        }
+        Some(WorkspaceSymbolResponse::Flat(symbols))
+    }
+}
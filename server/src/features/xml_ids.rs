@@ -0,0 +1,262 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lsp_types::request::Request;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::core::csv_parser::{is_reference_column, read_csv};
+use crate::core::xml_parser::{attribute, group_references, xml_id_references, strip_comments, LineIndex, GROUPS_ATTRIBUTE_RE, RECORD_RE};
+use crate::threads::SessionInfo;
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindUnusedXmlIdsParams {
+    pub module: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedXmlId {
+    pub xml_id: String,
+    pub model: String,
+    pub path: String,
+    pub line: u32, //0-based, like lsp positions
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindUnusedXmlIdsResult {
+    pub xml_ids: Vec<UnusedXmlId>,
+}
+
+#[derive(Debug)]
+pub enum FindUnusedXmlIdsRequest {}
+
+impl Request for FindUnusedXmlIdsRequest {
+    type Params = FindUnusedXmlIdsParams;
+    type Result = FindUnusedXmlIdsResult;
+    const METHOD: &'static str = "Odoo/findUnusedXmlIds";
+}
+
+/* Models whose records are used by Odoo without being referenced: views are attached to their model (and templates are
+rendered by inheritance or by the website), menus are displayed, access rights, rules, crons and assets are applied */
+static IMPLICIT_MODELS: &[&str] = &["ir.ui.view", "ir.ui.menu", "ir.model.access", "ir.rule", "ir.cron", "ir.asset"];
+
+/* Files where an xml id can be referenced */
+static SCANNED_EXTENSIONS: &[&str] = &["xml", "csv", "py", "js"];
+
+static SHORTCUT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<(template|menuitem|report|act_window)\b([^>]*)>"#).unwrap());
+/* Python calls taking xml ids: env.ref(), user.has_group()... The same calls are found in the code of the server actions */
+static REF_CALL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(?:ref|has_group|user_has_groups|_xmlid_to_res_id|_xmlid_to_res_model_res_id|_xmlid_lookup)\(\s*["']([^"']+)["']"#).unwrap());
+/* Javascript code gives qualified xml ids as strings, to the action service for example */
+static JS_XML_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"["']([A-Za-z_]\w*\.[A-Za-z_]\w*)["']"#).unwrap());
+
+/* A record created by a data file of a module */
+struct XmlIdDeclaration {
+    xml_id: String, //qualified with the module
+    model: String,
+    path: String,
+    line: u32,
+    implicit: bool,
+}
+
+pub struct XmlIdFeature {}
+
+impl XmlIdFeature {
+
+    /* List the records of the modules in the workspace that are never referenced by the python, xml or csv files of these
+    modules, as candidates for a cleanup */
+    pub fn find_unused_xml_ids(session: &mut SessionInfo, params: &FindUnusedXmlIdsParams) -> FindUnusedXmlIdsResult {
+        let mut modules: Vec<(String, String)> = vec![];
+        for (name, module) in session.sync_odoo.modules.iter() {
+            let Some(module) = module.upgrade() else {
+                continue;
+            };
+            let module = module.borrow();
+            if !module.in_workspace() {
+                continue;
+            }
+            modules.push((name.clone(), module.as_module_package().path.clone()));
+        }
+        modules.sort();
+        let mut xml_ids = XmlIdFeature::find_unused(&modules);
+        if let Some(module) = params.module.as_ref() {
+            xml_ids.retain(|xml_id| xml_id.xml_id.split_once('.').is_some_and(|(m, _)| m == module));
+        }
+        FindUnusedXmlIdsResult { xml_ids }
+    }
+
+    /* Find the unused records of the given modules (name, path). A record is used if its xml id is referenced: by the tags of
+    the data files (ref, parent, action, groups, ref() of the evaluated attributes), the relational columns of the csv files, the
+    calls of the python code (env.ref, has_group...), the groups of the fields, or a string of the javascript code. Short names
+    are the ones of the module of the file, except in javascript */
+    pub fn find_unused(modules: &[(String, String)]) -> Vec<UnusedXmlId> {
+        let mut declarations = vec![];
+        let mut references: HashSet<String> = HashSet::new();
+        for (module_name, module_path) in modules.iter() {
+            let mut paths = vec![];
+            XmlIdFeature::collect_files(Path::new(module_path), &mut paths);
+            paths.sort();
+            for path in paths.iter() {
+                let Ok(content) = fs::read_to_string(path) else {
+                    continue;
+                };
+                let path = path.to_string_lossy().to_string();
+                match path.rsplit_once('.').map(|(_, ext)| ext) {
+                    Some("xml") => {
+                        let content = strip_comments(&content);
+                        XmlIdFeature::collect_xml_declarations(module_name, &path, &content, &mut declarations);
+                        for reference in xml_id_references(&content) {
+                            XmlIdFeature::add_reference(module_name, reference, &mut references);
+                        }
+                        XmlIdFeature::collect_python_references(module_name, &content, &mut references);
+                    },
+                    Some("csv") => XmlIdFeature::collect_csv(module_name, &path, &content, &mut declarations, &mut references),
+                    Some("py") => XmlIdFeature::collect_python_references(module_name, &content, &mut references),
+                    _ => {
+                        for xml_id in JS_XML_ID_RE.captures_iter(&content) {
+                            references.insert(xml_id[1].to_string());
+                        }
+                    }
+                }
+            }
+        }
+        let mut res = vec![];
+        for declaration in declarations.into_iter() {
+            if declaration.implicit || references.contains(&declaration.xml_id) {
+                continue;
+            }
+            res.push(UnusedXmlId {
+                xml_id: declaration.xml_id,
+                model: declaration.model,
+                path: declaration.path,
+                line: declaration.line,
+            });
+        }
+        res
+    }
+
+    /* Records created by the xml and csv files of a module, by their qualified xml id, with the file and the line creating them */
+    pub fn find_declarations(module_name: &str, module_path: &str) -> HashMap<String, (String, u32)> {
+        let mut declarations = vec![];
+        let mut paths = vec![];
+        XmlIdFeature::collect_files(Path::new(module_path), &mut paths);
+        paths.sort();
+        for path in paths.iter() {
+            let Ok(content) = fs::read_to_string(path) else {
+                continue;
+            };
+            let path = path.to_string_lossy().to_string();
+            match path.rsplit_once('.').map(|(_, ext)| ext) {
+                Some("xml") => XmlIdFeature::collect_xml_declarations(module_name, &path, &strip_comments(&content), &mut declarations),
+                Some("csv") => XmlIdFeature::collect_csv(module_name, &path, &content, &mut declarations, &mut HashSet::new()),
+                _ => {}
+            }
+        }
+        let mut res = HashMap::new();
+        for declaration in declarations.into_iter() {
+            //the first file creates the record, the next ones update it
+            res.entry(declaration.xml_id).or_insert((declaration.path, declaration.line));
+        }
+        res
+    }
+
+    fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                XmlIdFeature::collect_files(&path, paths);
+            } else if path.extension().is_some_and(|ext| SCANNED_EXTENSIONS.iter().any(|e| ext == *e)) {
+                paths.push(path);
+            }
+        }
+    }
+
+    fn collect_xml_declarations(module_name: &str, path: &str, content: &str, declarations: &mut Vec<XmlIdDeclaration>) {
+        let lines = LineIndex::new(content);
+        for record in RECORD_RE.captures_iter(content) {
+            let attrs = record.get(1).unwrap().as_str();
+            let (Some(id), Some(model)) = (attribute(attrs, "id"), attribute(attrs, "model")) else {
+                continue;
+            };
+            //actions bound to a model are displayed in its action menu
+            let bound = record.get(2).is_some_and(|body| body.as_str().contains("\"binding_model_id\""));
+            let line = lines.line_of(record.get(0).unwrap().start());
+            XmlIdFeature::add_declaration(module_name, path, &id.value, model.value, line, bound, declarations);
+        }
+        for shortcut in SHORTCUT_RE.captures_iter(content) {
+            let attrs = shortcut.get(2).unwrap().as_str();
+            let Some(id) = attribute(attrs, "id") else {
+                continue;
+            };
+            let model = match &shortcut[1] {
+                "template" => "ir.ui.view",
+                "menuitem" => "ir.ui.menu",
+                "report" => "ir.actions.report",
+                _ => "ir.actions.act_window",
+            };
+            let line = lines.line_of(shortcut.get(0).unwrap().start());
+            XmlIdFeature::add_declaration(module_name, path, &id.value, model.to_string(), line, false, declarations);
+        }
+    }
+
+    /* Records of csv files, named after their model, with the xml ids in the "id" column, and the records referenced by the
+    columns named like "model_id:id" or "groups_id/id" */
+    fn collect_csv(module_name: &str, path: &str, content: &str, declarations: &mut Vec<XmlIdDeclaration>, references: &mut HashSet<String>) {
+        let Some(model) = Path::new(path).file_stem().map(|stem| stem.to_string_lossy().to_string()) else {
+            return;
+        };
+        for row in read_csv(content).iter() {
+            for (column, value) in row.values.iter().filter(|(_, value)| !value.is_empty()) {
+                if column == "id" {
+                    XmlIdFeature::add_declaration(module_name, path, value, model.clone(), row.line, false, declarations);
+                } else if is_reference_column(column) {
+                    XmlIdFeature::add_reference(module_name, value, references);
+                }
+            }
+        }
+    }
+
+    /* xml ids given to the calls of python code, and the groups of fields or elements */
+    fn collect_python_references(module_name: &str, content: &str, references: &mut HashSet<String>) {
+        for call in REF_CALL_RE.captures_iter(content) {
+            for reference in group_references(call.get(1).unwrap().as_str()) {
+                XmlIdFeature::add_reference(module_name, reference, references);
+            }
+        }
+        for groups in GROUPS_ATTRIBUTE_RE.captures_iter(content) {
+            for reference in group_references(groups.get(1).unwrap().as_str()) {
+                XmlIdFeature::add_reference(module_name, reference, references);
+            }
+        }
+    }
+
+    fn add_reference(module_name: &str, xml_id: &str, references: &mut HashSet<String>) {
+        if xml_id.contains('.') {
+            references.insert(xml_id.to_string());
+        } else {
+            references.insert(format!("{}.{}", module_name, xml_id));
+        }
+    }
+
+    fn add_declaration(module_name: &str, path: &str, id: &str, model: String, line: u32, implicit: bool, declarations: &mut Vec<XmlIdDeclaration>) {
+        //a qualified id of another module overrides its record, which is not a new one
+        if id.split_once('.').is_some_and(|(module, _)| module != module_name) {
+            return;
+        }
+        let name = id.split_once('.').map(|(_, name)| name).unwrap_or(id);
+        declarations.push(XmlIdDeclaration {
+            xml_id: format!("{}.{}", module_name, name),
+            implicit: implicit || IMPLICIT_MODELS.contains(&model.as_str()),
+            model,
+            path: path.to_string(),
+            line,
+        });
+    }
+}
@@ -1,9 +1,20 @@
+//! Odoo language server.
+//!
+//! The analysis of Odoo code (import resolution, build steps, model registry, diagnostics) is usable without the language
+//! server: see the [`api`] module. The LSP connection, the command line and the crash handler are only available with
+//! the `server` feature, enabled by default.
+
+pub mod api;
+#[cfg(feature = "server")]
 pub mod args;
+#[cfg(feature = "server")]
 pub mod cli_backend;
 pub mod constants;
+pub mod crash_report;
 pub mod core;
 pub mod threads;
 pub mod features;
+#[cfg(feature = "server")]
 pub mod server;
 pub mod tasks;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
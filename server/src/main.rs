@@ -1,10 +1,7 @@
-use lsp_server::Notification;
-use serde_json::json;
-use odoo_ls_server::{args::{Cli, LogLevel}, cli_backend::CliBackend, constants::*, server::Server, utils::PathSanitizer};
+use odoo_ls_server::{args::{Cli, LogLevel}, cli_backend::CliBackend, constants::*, crash_report::{CrashReport, LogBufferLayer}, server::Server, utils::PathSanitizer};
 use clap::Parser;
 use tracing::{info, Level};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
-use tracing_panic::panic_hook;
 use tracing_subscriber::{fmt, FmtSubscriber, layer::SubscriberExt};
 
 use std::{env, path::PathBuf};
@@ -42,7 +39,7 @@ fn main() {
         .rotation(Rotation::HOURLY)
         .filename_prefix("odoo_logs")
         .filename_suffix(format!("{}.log", std::process::id()))
-        .build(log_dir)
+        .build(log_dir.clone())
         .expect("failed to initialize rolling file appender");
     let (file_writer, _guard) = tracing_appender::non_blocking(file_appender);
     let subscriber = FmtSubscriber::builder()
@@ -51,8 +48,9 @@ fn main() {
         .with_max_level(log_level)
         .with_ansi(false)
         .with_writer(file_writer)
-        .finish();
-    if cli.parse || use_debug {
+        .finish()
+        .with(LogBufferLayer {});
+    if cli.parse || cli.doctor || use_debug {
         let stdout_subscriber = fmt::layer().with_writer(std::io::stdout).with_ansi(true);
         tracing::subscriber::set_global_default(subscriber.with(stdout_subscriber)).expect("Unable to set default tracing subscriber");
     } else {
@@ -71,41 +69,35 @@ fn main() {
     info!("Operating system: {}", std::env::consts::OS);
     info!("");
 
-    if cli.parse {
+    if cli.doctor {
+        info!("starting server (doctor mode)");
+        let backend = CliBackend::new(cli);
+        backend.doctor();
+    } else if cli.migrate_scan {
+        info!("starting server (migration scan mode)");
+        let backend = CliBackend::new(cli);
+        backend.migrate_scan();
+    } else if cli.bench {
+        info!("starting server (bench mode)");
+        let backend = CliBackend::new(cli);
+        backend.bench();
+    } else if cli.parse {
         info!("starting server (single parse mode)");
         let backend = CliBackend::new(cli);
         backend.run();
     } else if use_debug {
         info!(tag = "test", "starting server (debug mode)");
         let mut serv = Server::new_tcp().expect("Unable to start tcp connection");
+        serv.set_no_delay(cli.no_delay);
         serv.initialize().expect("Error while initializing server");
-        let sender_panic = serv.connection.as_ref().unwrap().sender.clone();
-        std::panic::set_hook(Box::new(move |panic_info| {
-            panic_hook(panic_info);
-            let _ = sender_panic.send(lsp_server::Message::Notification(Notification{
-                method: "Odoo/displayCrashNotification".to_string(),
-                params: json!({
-                    "crashInfo": format!("{panic_info}"),
-                    "pid": std::process::id()
-                })
-            }));
-        }));
+        CrashReport::install_panic_hook(serv.connection.as_ref().unwrap().sender.clone(), log_dir.clone());
         serv.run(cli.clientProcessId);
     } else {
         info!("starting server");
         let mut serv = Server::new_stdio();
+        serv.set_no_delay(cli.no_delay);
         serv.initialize().expect("Error while initializing server");
-        let sender_panic = serv.connection.as_ref().unwrap().sender.clone();
-        std::panic::set_hook(Box::new(move |panic_info| {
-            panic_hook(panic_info);
-            let _ = sender_panic.send(lsp_server::Message::Notification(Notification{
-                method: "Odoo/displayCrashNotification".to_string(),
-                params: json!({
-                    "crashInfo": format!("{panic_info}"),
-                    "pid": std::process::id()
-                })
-            }));
-        }));
+        CrashReport::install_panic_hook(serv.connection.as_ref().unwrap().sender.clone(), log_dir.clone());
         serv.run(cli.clientProcessId);
     }
     info!(">>>>>>>>>>>>>>>>>> End Session <<<<<<<<<<<<<<<<<<");
@@ -1,22 +1,25 @@
-use std::{collections::HashMap, io::Error, panic, sync::{atomic::AtomicBool, Arc, Mutex}, thread::JoinHandle};
-
-use crossbeam_channel::{Receiver, Select, Sender};
-use lsp_server::{Connection, IoThreads, Message, ProtocolError, RequestId, ResponseError};
-use lsp_types::{notification::{DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidChangeWorkspaceFolders, DidCloseTextDocument,
-    DidCreateFiles, DidDeleteFiles, DidOpenTextDocument, DidRenameFiles, DidSaveTextDocument, Notification},
-    request::{Completion, GotoDefinition, HoverRequest, Request, ResolveCompletionItem, Shutdown}, CompletionOptions, DefinitionOptions,
-    FileOperationFilter, FileOperationPattern, FileOperationRegistrationOptions, HoverProviderCapability, InitializeParams, InitializeResult,
-    OneOf, SaveOptions, ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+use std::{collections::{HashMap, HashSet}, io::Error, panic, sync::{atomic::AtomicBool, Arc, Mutex}, thread::JoinHandle};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Select, Sender};
+use lsp_server::{Connection, IoThreads, Message, RequestId, Response};
+use lsp_types::{notification::{Cancel, DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidChangeWorkspaceFolders, DidCloseTextDocument,
+    DidCreateFiles, DidDeleteFiles, DidOpenTextDocument, DidRenameFiles, DidSaveTextDocument, Exit, Notification},
+    CodeActionKind, CodeActionOptions, CodeActionProviderCapability, ColorProviderCapability,
+    request::{CodeActionRequest, ColorPresentationRequest, Completion, DocumentColor, DocumentLinkRequest, DocumentSymbolRequest, ExecuteCommand, GotoDefinition, GotoImplementation, HoverRequest, InlineValueRequest, OnTypeFormatting, PrepareRenameRequest, RangeFormatting, References, Rename, Request, ResolveCompletionItem, Shutdown, SignatureHelpRequest, WorkspaceSymbolRequest}, CompletionOptions, DefinitionOptions,
+    DocumentLinkOptions, DocumentOnTypeFormattingOptions, ExecuteCommandOptions, DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse,
+    FileOperationFilter, FileOperationPattern, FileOperationRegistrationOptions, HoverProviderCapability, ImplementationProviderCapability, InitializeParams, InitializeResult,
+    CancelParams, NumberOrString, OneOf, ReferencesOptions, RenameOptions, SaveOptions, ServerCapabilities, ServerInfo, SignatureHelpOptions, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
     WorkDoneProgressOptions, WorkspaceFileOperationsServerCapabilities, WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities};
 use serde_json::json;
 #[cfg(target_os = "linux")]
 use nix;
 use tracing::{error, info, warn};
 
-use crate::{constants::EXTENSION_VERSION, core::{file_mgr::FileMgr, odoo::SyncOdoo}, threads::{delayed_changes_process_thread, message_processor_thread_main, message_processor_thread_read, DelayedProcessingMessage}, S};
+use crate::{constants::EXTENSION_VERSION, core::{build_stats::BuildStatsRequest, cache::ClearCacheRequest, commands::{ReloadModuleRequest, ServerCommand}, config::{Config, ConfigurationSchemaRequest}, diagnostic_codes::{ListDiagnosticCodesRequest, ListDiagnosticCodesResult, DIAGNOSTIC_CODES}, focus::ToggleFocusRequest, doctor::DoctorRequest, file_mgr::FileMgr, integrity_checker::CheckIntegrityRequest, module_graph::ExportModuleGraphRequest, odoo::SyncOdoo}, features::{model_source::GetModelSourceRequest, scaffold::ScaffoldRequest, translation::ExportPotPreviewRequest, xml_ids::FindUnusedXmlIdsRequest}, threads::{ServerError, delayed_changes_process_thread, message_processor_thread_main, message_processor_thread_read, quick_parse_thread, DelayedProcessingMessage, QuickParseMessage}, S};
 
 const THREAD_MAIN_COUNT: u16 = 1;
 const THREAD_READ_COUNT: u16 = 1;
+pub const SHUTDOWN_TIMEOUT: u64 = 5000; //ms
 
 /**
  * Server handle connection between the client and the extension.
@@ -26,7 +29,7 @@ const THREAD_READ_COUNT: u16 = 1;
 pub struct Server {
     pub connection: Option<Connection>,
     client_process_id: u32,
-    io_threads: IoThreads,
+    io_threads: Option<IoThreads>, //None for in-memory connections
     receivers_w_to_s: Vec<Receiver<Message>>,
     msg_id: i32,
     id_list: HashMap<RequestId, u16>, //map each request to its thread. firsts ids for main thread, nexts for read ones, last for delayed_process thread
@@ -39,27 +42,11 @@ pub struct Server {
     sender_to_delayed_process: Sender<DelayedProcessingMessage>, //unique channel to delayed process thread
     sync_odoo: Arc<Mutex<SyncOdoo>>,
     interrupt_rebuild_boolean: Arc<AtomicBool>,
-}
-
-#[derive(Debug)]
-
-pub enum ServerError {
-    ProtocolError(ProtocolError),
-    Serialization(serde_json::Error),
-    ServerError(String),
-    ResponseError(ResponseError),
-}
-
-impl From<ProtocolError> for ServerError {
-    fn from(error: ProtocolError) -> Self {
-        ServerError::ProtocolError(error)
-    }
-}
-
-impl From<serde_json::Error> for ServerError {
-    fn from(error: serde_json::Error) -> Self {
-        ServerError::Serialization(error)
-    }
+    shutdown_boolean: Arc<AtomicBool>,
+    cancelled_requests: Arc<Mutex<HashSet<RequestId>>>,
+    pending_requests: HashSet<RequestId>, //requests of the client sent to the threads, that have not been answered yet
+    sender_to_quick_parse: Sender<QuickParseMessage>, //unique channel to the quick parse thread
+    quick_outlines: Arc<Mutex<HashMap<String, Vec<DocumentSymbol>>>>, //outlines of the documents opened while the database was busy, by uri. Dropped when the document changes
 }
 
 impl Server {
@@ -67,7 +54,7 @@ impl Server {
     pub fn new_tcp() -> Result<Self, Error> {
         match Connection::listen("127.0.0.1:2087") {
             Ok((conn, io_threads)) => {
-                Ok(Server::init(conn, io_threads))
+                Ok(Server::init(conn, Some(io_threads)))
             },
             Err(e) => Err(e)
         }
@@ -75,13 +62,29 @@ impl Server {
 
     pub fn new_stdio() -> Self {
         let (conn, io_threads) = Connection::stdio();
-        Server::init(conn, io_threads)
+        Server::init(conn, Some(io_threads))
+    }
+
+    /* Server connected to the returned client connection, without io. Used by the integration tests to talk to the server */
+    pub fn new_memory() -> (Self, Connection) {
+        let (conn, client) = Connection::memory();
+        (Server::init(conn, None), client)
     }
 
-    fn init(conn: Connection, io_threads: IoThreads) -> Self {
+    /* Process the changes at once instead of waiting for the user to stop typing (--no-delay), so the runs of the
+    integration tests are deterministic */
+    pub fn set_no_delay(&mut self, no_delay: bool) {
+        let mut sync_odoo = self.sync_odoo.lock().unwrap();
+        sync_odoo.config.no_delay = no_delay;
+        let _ = self.sender_to_delayed_process.send(DelayedProcessingMessage::UPDATE_DELAY(sync_odoo.config.event_delays()));
+    }
+
+    fn init(conn: Connection, io_threads: Option<IoThreads>) -> Self {
         let mut threads = vec![];
         let sync_odoo = Arc::new(Mutex::new(SyncOdoo::new()));
         let interrupt_rebuild_boolean = sync_odoo.lock().unwrap().interrupt_rebuild.clone();
+        let shutdown_boolean = sync_odoo.lock().unwrap().shutdown.clone();
+        let cancelled_requests = sync_odoo.lock().unwrap().cancelled_requests.clone();
         let mut receivers_w_to_s = vec![];
         let mut senders_s_to_main = vec![];
         let (sender_to_delayed_process, receiver_delayed_process) = crossbeam_channel::unbounded();
@@ -126,6 +129,18 @@ impl Server {
             delayed_changes_process_thread(sender_delayed_to_s, receiver_s_to_delayed, receiver_delayed_process, so)
         });
 
+        let quick_outlines = Arc::new(Mutex::new(HashMap::new()));
+        let (sender_to_quick_parse, receiver_quick_parse) = crossbeam_channel::unbounded();
+        let (sender_quick_parse_to_s, receiver_quick_parse_to_s) = crossbeam_channel::unbounded();
+        receivers_w_to_s.push(receiver_quick_parse_to_s);
+        threads.push({
+            let sync_odoo = sync_odoo.clone();
+            let quick_outlines = quick_outlines.clone();
+            std::thread::spawn(move || {
+                quick_parse_thread(sender_quick_parse_to_s, receiver_quick_parse, sync_odoo, quick_outlines);
+            })
+        });
+
         // let (sender_to_server, receiver_to_server) = crossbeam_channel::unbounded();
         // let (sender_from_server_reactive, receiver_from_server) = crossbeam_channel::unbounded();
         // server.add_receiver(receiver_to_server.clone());
@@ -150,6 +165,11 @@ impl Server {
             delayed_process_thread,
             sync_odoo: sync_odoo,
             interrupt_rebuild_boolean: interrupt_rebuild_boolean,
+            shutdown_boolean: shutdown_boolean,
+            cancelled_requests,
+            pending_requests: HashSet::new(),
+            sender_to_quick_parse,
+            quick_outlines,
         }
     }
 
@@ -163,6 +183,8 @@ impl Server {
         if let Some(initialize_params) = initialize_params.process_id {
             self.client_process_id = initialize_params;
         }
+        self.sync_odoo.lock().unwrap().capabilities = initialize_params.capabilities.clone();
+        self.sync_odoo.lock().unwrap().initialization_options = initialize_params.initialization_options.clone();
         if let Some(workspace_folders) = initialize_params.workspace_folders {
             let mut sync_odoo = self.sync_odoo.lock().unwrap();
             let file_mgr = sync_odoo.get_file_mgr();
@@ -192,13 +214,51 @@ impl Server {
                     save: Some(lsp_types::TextDocumentSyncSaveOptions::SaveOptions(SaveOptions{include_text: Some(false)})) //TODO could deactivate if set on 'afterDelay?
                 })),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec![S!("("), S!(",")]),
+                    retrigger_characters: None,
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
                 definition_provider: Some(OneOf::Right(DefinitionOptions{
                     work_done_progress_options: WorkDoneProgressOptions{
                         work_done_progress: Some(false)
                     }
                 })),
-                completion_provider: Some(CompletionOptions {
+                implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                    code_action_kinds: Some(vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE_ORGANIZE_IMPORTS]),
+                    ..CodeActionOptions::default()
+                })),
+                references_provider: Some(OneOf::Right(ReferencesOptions {
+                    work_done_progress_options: WorkDoneProgressOptions {
+                        work_done_progress: Some(true)
+                    }
+                })),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions {
+                        work_done_progress: Some(true)
+                    },
+                })),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: ServerCommand::names(),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                document_link_provider: Some(DocumentLinkOptions {
                     resolve_provider: Some(false),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
+                color_provider: Some(ColorProviderCapability::Simple(true)),
+                inline_value_provider: Some(OneOf::Left(true)),
+                document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                    first_trigger_character: S!(":"),
+                    more_trigger_character: Some(vec![S!(")"), S!("\n")]),
+                }),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
+                completion_provider: Some(CompletionOptions {
+                    resolve_provider: Some(true),
                     trigger_characters: Some(vec![S!("."), S!(","), S!("'"), S!("\"")]),
                     ..CompletionOptions::default()
                 }),
@@ -300,23 +360,24 @@ impl Server {
 
             if index == 0 { //comes from client
                 if let Message::Request(r) = &msg {
+                    if r.method == Shutdown::METHOD {
+                        //stop the builds before waiting for the exit notification, as the main thread could hold the database for a long time
+                        self.shutdown_boolean.store(true, std::sync::atomic::Ordering::SeqCst);
+                    }
                     if self.connection.as_ref().unwrap().handle_shutdown(r).unwrap_or(false) {
-                        for _ in 0..self.senders_s_to_main.len() {
-                            self.sender_s_to_main.send(Message::Notification(lsp_server::Notification{
-                                method: Shutdown::METHOD.to_string(),
-                                params: serde_json::Value::Null,
-                            })).unwrap(); //sent as notification as we already handled the request for the client
-                        }
-                        for _ in 0..self.senders_s_to_read.len() {
-                            self.sender_s_to_read.send(Message::Notification(lsp_server::Notification{
-                                method: Shutdown::METHOD.to_string(),
-                                params: serde_json::Value::Null,
-                            })).unwrap(); //sent as notification as we already handled the request for the client
-                        }
+                        self.stop_threads();
                         info!("Got shutdown request. Exiting.");
                         break;
                     }
                 }
+                if let Message::Notification(n) = &msg {
+                    if n.method == Exit::METHOD {
+                        self.shutdown_boolean.store(true, std::sync::atomic::Ordering::SeqCst);
+                        self.stop_threads();
+                        warn!("Got exit notification without shutdown request. Exiting.");
+                        break;
+                    }
+                }
                 self.dispatch(msg);
             } else { // comes from threads
                 match msg {
@@ -330,6 +391,8 @@ impl Server {
                         self.connection.as_ref().unwrap().sender.send(Message::Notification(n)).unwrap();
                     },
                     Message::Response(r) => {
+                        self.pending_requests.remove(&r.id);
+                        self.cancelled_requests.lock().unwrap().remove(&r.id);
                         self.connection.as_ref().unwrap().sender.send(Message::Response(r)).unwrap();
                     }
                 }
@@ -340,16 +403,51 @@ impl Server {
         let hook = panic::take_hook(); //drop sender stored in panic
         drop(hook);
         let _ = self.sender_to_delayed_process.send(DelayedProcessingMessage::EXIT);
+        let _ = self.sender_to_quick_parse.send(QuickParseMessage::EXIT);
         let _ = stop_sender.send(());
         self.connection = None; //drop connection before joining threads
+        let watchdog = Server::spawn_exit_watchdog();
         if let Some(pid_join_handle) = pid_thread {
             pid_join_handle.join().unwrap();
         }
         for thread in self.threads {
             thread.join().unwrap();
         }
-        self.io_threads.join().unwrap();
+        if let Some(io_threads) = self.io_threads {
+            io_threads.join().unwrap();
+        }
         self.delayed_process_thread.join().unwrap();
+        let _ = watchdog.send(());
+    }
+
+    /* Ask the message processor threads to stop. Sent as notifications, as the shutdown request has already been answered */
+    fn stop_threads(&self) {
+        for _ in 0..self.senders_s_to_main.len() {
+            self.sender_s_to_main.send(Message::Notification(lsp_server::Notification{
+                method: Shutdown::METHOD.to_string(),
+                params: serde_json::Value::Null,
+            })).unwrap();
+        }
+        for _ in 0..self.senders_s_to_read.len() {
+            self.sender_s_to_read.send(Message::Notification(lsp_server::Notification{
+                method: Shutdown::METHOD.to_string(),
+                params: serde_json::Value::Null,
+            })).unwrap();
+        }
+    }
+
+    /* Threads stop their build before the next file, but a single file can still take some time to be processed.
+    Kill the process if the threads did not stop after SHUTDOWN_TIMEOUT, so the editor is never blocked on exit. The returned
+    sender is signaled once the threads are joined, to stop the watchdog: the process embedding the server goes on */
+    fn spawn_exit_watchdog() -> Sender<()> {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        std::thread::spawn(move || {
+            if receiver.recv_timeout(std::time::Duration::from_millis(SHUTDOWN_TIMEOUT)) == Err(RecvTimeoutError::Timeout) {
+                error!("Threads did not stop {} ms after shutdown - killing the server", SHUTDOWN_TIMEOUT);
+                std::process::exit(1);
+            }
+        });
+        sender
     }
 
     /* address a message to the right thread. */
@@ -357,17 +455,40 @@ impl Server {
         match msg {
             Message::Request(r) => {
                 match r.method.as_str() {
-                    HoverRequest::METHOD | GotoDefinition::METHOD => {
+                    DocumentSymbolRequest::METHOD => {
+                        if let Some(outline) = self.get_quick_outline(&r.params) {
+                            self.connection.as_ref().unwrap().sender.send(Message::Response(Response::new_ok(r.id, DocumentSymbolResponse::Nested(outline)))).unwrap();
+                            return;
+                        }
+                        self.interrupt_rebuild_boolean.store(true, std::sync::atomic::Ordering::SeqCst);
+                        self.pending_requests.insert(r.id.clone());
+                        self.sender_s_to_read.send(Message::Request(r)).unwrap();
+                    },
+                    HoverRequest::METHOD | SignatureHelpRequest::METHOD | ResolveCompletionItem::METHOD | GotoDefinition::METHOD | GotoImplementation::METHOD | References::METHOD | PrepareRenameRequest::METHOD | Rename::METHOD | CodeActionRequest::METHOD | DocumentLinkRequest::METHOD | DocumentColor::METHOD | ColorPresentationRequest::METHOD | InlineValueRequest::METHOD | CheckIntegrityRequest::METHOD | ExportPotPreviewRequest::METHOD | FindUnusedXmlIdsRequest::METHOD | ExportModuleGraphRequest::METHOD | GetModelSourceRequest::METHOD | WorkspaceSymbolRequest::METHOD | DoctorRequest::METHOD | BuildStatsRequest::METHOD => {
                         self.interrupt_rebuild_boolean.store(true, std::sync::atomic::Ordering::SeqCst);
+                        self.pending_requests.insert(r.id.clone());
                         self.sender_s_to_read.send(Message::Request(r)).unwrap();
                     },
-                    Completion::METHOD => {
+                    Completion::METHOD | OnTypeFormatting::METHOD | RangeFormatting::METHOD | ClearCacheRequest::METHOD | ReloadModuleRequest::METHOD | ToggleFocusRequest::METHOD | ScaffoldRequest::METHOD => {
                         self.interrupt_rebuild_boolean.store(true, std::sync::atomic::Ordering::SeqCst);
+                        self.pending_requests.insert(r.id.clone());
                         self.sender_s_to_main.send(Message::Request(r)).unwrap();
                     },
-                    ResolveCompletionItem::METHOD => {
-                        info!("Got ignored CompletionItem/resolve")
-                    }
+                    ConfigurationSchemaRequest::METHOD => {
+                        //static, no need to wait for the database
+                        self.connection.as_ref().unwrap().sender.send(Message::Response(Response::new_ok(r.id, Config::schema()))).unwrap();
+                    },
+                    ListDiagnosticCodesRequest::METHOD => {
+                        let result = ListDiagnosticCodesResult { codes: DIAGNOSTIC_CODES.clone() };
+                        self.connection.as_ref().unwrap().sender.send(Message::Response(Response::new_ok(r.id, result))).unwrap();
+                    },
+                    ExecuteCommand::METHOD => {
+                        let id = r.id.clone();
+                        match ServerCommand::to_request(r) {
+                            Ok(request) => self.dispatch(Message::Request(request)),
+                            Err(error) => self.connection.as_ref().unwrap().sender.send(Message::Response(Response::new_err(id, error.code, error.message))).unwrap(),
+                        }
+                    },
                     _ => {panic!("Not handled Request Id: {}", r.method)}
                 }
             },
@@ -400,11 +521,33 @@ impl Server {
             },
             Message::Notification(n) => {
                 match n.method.as_str() {
-                    DidOpenTextDocument::METHOD | DidChangeConfiguration::METHOD | DidChangeWorkspaceFolders::METHOD |
-                    DidChangeTextDocument::METHOD | DidCloseTextDocument::METHOD | DidSaveTextDocument::METHOD |
+                    DidOpenTextDocument::METHOD => {
+                        self.sender_to_quick_parse.send(QuickParseMessage::OPEN(n.params.clone())).unwrap();
+                        self.sender_s_to_main.send(Message::Notification(n)).unwrap();
+                    },
+                    DidChangeTextDocument::METHOD | DidCloseTextDocument::METHOD => {
+                        if let Some(uri) = n.params.get("textDocument").and_then(|doc| doc.get("uri")).and_then(|uri| uri.as_str()) {
+                            self.sender_to_quick_parse.send(QuickParseMessage::FORGET(uri.to_string())).unwrap();
+                        }
+                        self.sender_s_to_main.send(Message::Notification(n)).unwrap();
+                    },
+                    DidChangeWorkspaceFolders::METHOD | DidChangeConfiguration::METHOD | DidSaveTextDocument::METHOD |
                     DidRenameFiles::METHOD | DidCreateFiles::METHOD | DidChangeWatchedFiles::METHOD | DidDeleteFiles::METHOD => {
                         self.sender_s_to_main.send(Message::Notification(n)).unwrap();
                     }
+                    Cancel::METHOD => {
+                        //the threads check the cancellation between the steps of the long requests. The ids of the requests
+                        //already answered are ignored
+                        if let Ok(params) = serde_json::from_value::<CancelParams>(n.params) {
+                            let id = match params.id {
+                                NumberOrString::Number(id) => RequestId::from(id),
+                                NumberOrString::String(id) => RequestId::from(id),
+                            };
+                            if self.pending_requests.contains(&id) {
+                                self.cancelled_requests.lock().unwrap().insert(id);
+                            }
+                        }
+                    },
                     _ => {
                         if n.method.starts_with("$/") {
                             warn!("Not handled message id: {}", n.method);
@@ -417,6 +560,16 @@ impl Server {
         }
     }
 
+    /* The outline of the document from its quick parse, if the database is still busy */
+    fn get_quick_outline(&self, params: &serde_json::Value) -> Option<Vec<DocumentSymbol>> {
+        let params = serde_json::from_value::<DocumentSymbolParams>(params.clone()).ok()?;
+        let outline = self.quick_outlines.lock().unwrap().get(params.text_document.uri.as_str())?.clone();
+        if self.sync_odoo.try_lock().is_ok() {
+            return None;
+        }
+        Some(outline)
+    }
+
     #[cfg(any(target_os = "linux", target_os = "macos"))]
     fn spawn_pid_thread(&self, pid: u32, stop_channel: Receiver<()>) -> JoinHandle<()> {
         use std::process::exit;
@@ -1,22 +1,55 @@
-use std::{path::PathBuf, sync::{atomic::Ordering, Arc, Mutex}, time::Instant};
+use std::{path::PathBuf, sync::atomic::Ordering, time::Instant};
 
-use crossbeam_channel::{Receiver, Sender, TryRecvError};
-use lsp_server::{Message, RequestId, Response, ResponseError};
-use lsp_types::{notification::{DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidChangeWorkspaceFolders,
-    DidCloseTextDocument, DidCreateFiles, DidDeleteFiles, DidOpenTextDocument, DidRenameFiles, DidSaveTextDocument, LogMessage,
-    Notification}, request::{Completion, GotoDefinition, GotoTypeDefinitionResponse, HoverRequest, Request, Shutdown},
-    CompletionResponse, Hover, LogMessageParams, MessageType};
+use crossbeam_channel::{Receiver, Sender};
+use lsp_server::{Message, ProtocolError, RequestId, ResponseError};
+use lsp_types::{notification::{LogMessage, Notification}, LogMessageParams, MessageType};
 use serde::{de::DeserializeOwned, Serialize};
-use serde_json::Value;
-use tracing::{error, warn};
+use tracing::error;
 
-use crate::{core::{config::RefreshMode, odoo::{Odoo, SyncOdoo}}, server::ServerError, S};
+use crate::{core::{config::{EventDelays, RefreshMode}, odoo::SyncOdoo}, S};
+
+/* Imports only needed by the threads processing the messages of the LSP connection */
+#[cfg(feature = "server")]
+use {
+    std::{collections::HashMap, sync::{Arc, Mutex}},
+    crossbeam_channel::{RecvTimeoutError, TryRecvError},
+    lsp_server::Response,
+    lsp_types::{notification::{DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidChangeWorkspaceFolders,
+        DidCloseTextDocument, DidCreateFiles, DidDeleteFiles, DidOpenTextDocument, DidRenameFiles, DidSaveTextDocument, PublishDiagnostics},
+        request::{CodeActionRequest, ColorPresentationRequest, Completion, DocumentColor, DocumentLinkRequest, DocumentSymbolRequest, GotoDefinition, GotoImplementation, GotoImplementationResponse, GotoTypeDefinitionResponse, HoverRequest, InlineValueRequest, OnTypeFormatting, PrepareRenameRequest, RangeFormatting, References, Rename, Request, ResolveCompletionItem, Shutdown, SignatureHelpRequest, WorkspaceSymbolRequest},
+        CodeActionResponse, ColorInformation, ColorPresentation, CompletionItem, CompletionResponse, DidOpenTextDocumentParams, DocumentLink, DocumentSymbol, DocumentSymbolResponse, Hover, InlineValue, Location, PrepareRenameResponse, PublishDiagnosticsParams, SignatureHelp, TextEdit, WorkspaceEdit, WorkspaceSymbolResponse},
+    serde_json::Value,
+    tracing::warn,
+    crate::{core::{build_stats::{BuildStatsRequest, BuildStatsResult, RequestStats}, cache::{ClearCacheRequest, ClearCacheResult}, commands::{ReloadModuleRequest, ReloadModuleResult}, diagnostic_codes::add_code_description, focus::{ToggleFocusRequest, ToggleFocusResult}, doctor::{DoctorReport, DoctorRequest}, integrity_checker::{CheckIntegrityRequest, CheckIntegrityResult}, module_graph::{ExportModuleGraphRequest, ExportModuleGraphResult}, odoo::{InitState, Odoo}, site_packages::SITE_PACKAGES_POLL_INTERVAL}, features::{document_symbol::DocumentSymbolFeature, model_source::{GetModelSourceRequest, GetModelSourceResult}, scaffold::{ScaffoldRequest, ScaffoldResult}, translation::{ExportPotPreviewRequest, ExportPotPreviewResult}, xml_ids::{FindUnusedXmlIdsRequest, FindUnusedXmlIdsResult}}},
+};
+
+#[derive(Debug)]
+
+pub enum ServerError {
+    ProtocolError(ProtocolError),
+    Serialization(serde_json::Error),
+    ServerError(String),
+    ResponseError(ResponseError),
+}
+
+impl From<ProtocolError> for ServerError {
+    fn from(error: ProtocolError) -> Self {
+        ServerError::ProtocolError(error)
+    }
+}
+
+impl From<serde_json::Error> for ServerError {
+    fn from(error: serde_json::Error) -> Self {
+        ServerError::Serialization(error)
+    }
+}
 
 pub struct SessionInfo<'a> {
     sender: Sender<Message>,
     receiver: Receiver<Message>,
     pub sync_odoo: &'a mut SyncOdoo,
-    delayed_process_sender: Option<Sender<DelayedProcessingMessage>> //option, because not available in read thread (by convention, no real need to not provide it)
+    delayed_process_sender: Option<Sender<DelayedProcessingMessage>>, //option, because not available in read thread (by convention, no real need to not provide it)
+    current_request: Option<RequestId>, //request of the client being processed
 }
 
 impl <'a> SessionInfo<'a> {
@@ -75,7 +108,7 @@ impl <'a> SessionInfo<'a> {
     }
 
     pub fn request_update_file_index(session: &mut SessionInfo, path: &PathBuf) {
-        if session.delayed_process_sender.is_none() || !session.sync_odoo.need_rebuild && session.sync_odoo.config.refresh_mode == RefreshMode::Adaptive && session.sync_odoo.get_rebuild_queue_size() < 10 {
+        if session.delayed_process_sender.is_none() || session.sync_odoo.config.no_delay || !session.sync_odoo.need_rebuild && session.sync_odoo.config.refresh_mode == RefreshMode::Adaptive && session.sync_odoo.get_rebuild_queue_size() < 10 {
             let tree = session.sync_odoo.tree_from_path(&path);
             if !tree.is_err() { //is part of odoo (and in addons path)
                 let tree = tree.unwrap().clone();
@@ -89,16 +122,19 @@ impl <'a> SessionInfo<'a> {
     }
 
     pub fn request_reload(session: &mut SessionInfo) {
-        if let Some(sender) = &session.delayed_process_sender {
-            let _ = sender.send(DelayedProcessingMessage::REBUILD(std::time::Instant::now()));
-        } else {
-            SyncOdoo::reset(session, session.sync_odoo.config.clone());
+        match &session.delayed_process_sender {
+            Some(sender) if !session.sync_odoo.config.no_delay => {
+                let _ = sender.send(DelayedProcessingMessage::REBUILD(std::time::Instant::now()));
+            },
+            _ => {
+                SyncOdoo::reset(session, session.sync_odoo.config.clone());
+            }
         }
     }
 
-    pub fn update_auto_refresh_delay(&self, delay: u64) {
+    pub fn update_event_delays(&self, delays: EventDelays) {
         if let Some(sender) = &self.delayed_process_sender {
-            let _ = sender.send(DelayedProcessingMessage::UPDATE_DELAY(delay));
+            let _ = sender.send(DelayedProcessingMessage::UPDATE_DELAY(delays));
         }
     }
 
@@ -108,17 +144,24 @@ impl <'a> SessionInfo<'a> {
         }
     }
 
+    /* True if the client cancelled the request being processed. Long requests check it between their steps */
+    pub fn is_cancelled(&self) -> bool {
+        self.current_request.as_ref().is_some_and(|id| self.sync_odoo.cancelled_requests.lock().unwrap().contains(id))
+    }
+
     /* use it for test or tools, that do not need to connect to the server, and only want a fake session to use SyncOdoo */
     pub fn new_from_custom_channel(sender: Sender<Message>, receiver: Receiver<Message>, sync_odoo: &'a mut SyncOdoo, delayed_process_sender: Option<Sender<DelayedProcessingMessage>>) -> Self {
         Self {
             sender,
             receiver,
             sync_odoo,
-            delayed_process_sender: delayed_process_sender
+            delayed_process_sender: delayed_process_sender,
+            current_request: None,
         }
     }
 }
 
+#[cfg(feature = "server")]
 fn to_value<T: Serialize + std::fmt::Debug>(result: Result<Option<T>, ResponseError>) -> (Option<Value>, Option<ResponseError>) {
     let value = match &result {
         Ok(Some(r)) => Some(serde_json::json!(r)),
@@ -139,31 +182,34 @@ pub struct UpdateFileIndexData {
 
 #[allow(non_camel_case_types)]
 pub enum DelayedProcessingMessage {
-    UPDATE_DELAY(u64), //update the delay before starting any update
+    UPDATE_DELAY(EventDelays), //update the delays before starting any update
     PROCESS(Instant), //Process rebuilds after delay
     UPDATE_FILE_INDEX(UpdateFileIndexData), //update the file after delay
     REBUILD(Instant), //reset the database after the delay
     EXIT, //exit the thread
 }
 
+#[cfg(feature = "server")]
 pub fn delayed_changes_process_thread(sender_session: Sender<Message>, receiver_session: Receiver<Message>, receiver: Receiver<DelayedProcessingMessage>, sync_odoo: Arc<Mutex<SyncOdoo>>) {
-    const MAX_DELAY: u64 = 15000;
-    let mut normal_delay = std::time::Duration::from_millis(std::cmp::min(sync_odoo.lock().unwrap().config.auto_save_delay, MAX_DELAY));
+    let mut delays = sync_odoo.lock().unwrap().config.event_delays();
+    let shutdown = sync_odoo.lock().unwrap().shutdown.clone();
     loop {
         let mut rebuild = false;
         let mut update_file_index = None;
-        let mut delay = normal_delay;
-        let msg = receiver.recv();
+        let mut delay = std::time::Duration::from_millis(delays.refresh);
+        //without message, the thread polls the installed python packages
+        let msg = receiver.recv_timeout(std::time::Duration::from_millis(SITE_PACKAGES_POLL_INTERVAL));
         match msg {
             Ok(DelayedProcessingMessage::EXIT) => {
                 return;
             },
-            Ok(DelayedProcessingMessage::UPDATE_DELAY(duration)) => {
-                normal_delay = std::time::Duration::from_millis(std::cmp::min(duration, MAX_DELAY));
+            Ok(DelayedProcessingMessage::UPDATE_DELAY(new_delays)) => {
+                delays = new_delays;
             }
             Ok(DelayedProcessingMessage::REBUILD(time) | DelayedProcessingMessage::PROCESS(time) | DelayedProcessingMessage::UPDATE_FILE_INDEX(UpdateFileIndexData{path: _, time})) => {
                 if matches!(msg, Ok(DelayedProcessingMessage::REBUILD(_))) {
                     rebuild = true;
+                    delay = std::time::Duration::from_millis(delays.reload);
                 }
                 let mut last_time = time;
                 let mut to_wait = (time + delay) - std::time::Instant::now();
@@ -174,8 +220,9 @@ pub fn delayed_changes_process_thread(sender_session: Sender<Message>, receiver_
                         let new_msg = receiver.try_recv();
                         match new_msg {
                             Ok(DelayedProcessingMessage::EXIT) => {return;},
-                            Ok(DelayedProcessingMessage::UPDATE_DELAY(duration)) => {
-                                delay = std::time::Duration::from_millis(std::cmp::min(duration, MAX_DELAY));
+                            Ok(DelayedProcessingMessage::UPDATE_DELAY(new_delays)) => {
+                                delays = new_delays;
+                                delay = std::time::Duration::from_millis(if rebuild { delays.reload } else { delays.refresh });
                             }
                             Ok(DelayedProcessingMessage::PROCESS(t)) => {
                                 if t > last_time {
@@ -185,7 +232,7 @@ pub fn delayed_changes_process_thread(sender_session: Sender<Message>, receiver_
                             },
                             Ok(DelayedProcessingMessage::REBUILD(t)) => {
                                 rebuild = true;
-                                delay = std::time::Duration::from_millis(std::cmp::max(normal_delay.as_millis() as u64, 4000));
+                                delay = std::time::Duration::from_millis(delays.reload);
                                 if t > last_time {
                                     to_wait = (t + delay) - std::time::Instant::now();
                                     last_time = t;
@@ -205,36 +252,127 @@ pub fn delayed_changes_process_thread(sender_session: Sender<Message>, receiver_
                         }
                     }
                 }
+                if shutdown.load(Ordering::SeqCst) {
+                    return;
+                }
                 {
                     let mut session = SessionInfo{
                         sender: sender_session.clone(),
                         receiver: receiver_session.clone(),
                         sync_odoo: &mut sync_odoo.lock().unwrap(),
-                        delayed_process_sender: None
+                        delayed_process_sender: None,
+                        current_request: None,
                     };
                     if rebuild {
                         let config = session.sync_odoo.config.clone();
                         SyncOdoo::reset(&mut session, config);
                     } else {
                         if let Some(path) = update_file_index {
-                            let tree = session.sync_odoo.tree_from_path(&path);
-                            if !tree.is_err() { //is part of odoo (and in addons path)
-                                let tree = tree.unwrap().clone();
-                                let _ = SyncOdoo::_unload_path(&mut session, &path, false);
-                                SyncOdoo::search_symbols_to_rebuild(&mut session, &tree);
-                            }
+                            SyncOdoo::reload_path(&mut session, &path);
                         }
                         SyncOdoo::process_rebuilds(&mut session);
                     }
                 }
             }
-            Err(_) => {
+            Err(RecvTimeoutError::Timeout) => {
+                //don't wait for a build in progress, the next poll will see the changes
+                if let Ok(mut sync_odoo) = sync_odoo.try_lock() {
+                    if sync_odoo.state_init == InitState::ODOO_READY && sync_odoo.config.refresh_mode != RefreshMode::Off {
+                        let mut session = SessionInfo{
+                            sender: sender_session.clone(),
+                            receiver: receiver_session.clone(),
+                            sync_odoo: &mut sync_odoo,
+                            delayed_process_sender: None,
+                            current_request: None,
+                        };
+                        SyncOdoo::refresh_site_packages(&mut session);
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[allow(non_camel_case_types)]
+pub enum QuickParseMessage {
+    OPEN(Value), //params of a didOpen notification
+    FORGET(String), //drop the outline of the document, by uri, as its text changed
+    EXIT, //exit the thread
+}
+
+/* First phase of the opening of a python file. If the database is held by a build, the file would wait for the end of
+the build to get any feedback: publish its syntax errors and keep its outline from a quick parse of the text. The parse
+runs here rather than on the dispatch thread, so a large file doesn't delay the other messages of the client. The
+outlines are shared with the server, that answers the documentSymbol requests with them while the database is busy.
+The main thread publishes the full diagnostics once it processes the notification */
+#[cfg(feature = "server")]
+pub fn quick_parse_thread(sender: Sender<Message>, receiver: Receiver<QuickParseMessage>, sync_odoo: Arc<Mutex<SyncOdoo>>, outlines: Arc<Mutex<HashMap<String, Vec<DocumentSymbol>>>>) {
+    loop {
+        match receiver.recv() {
+            Ok(QuickParseMessage::OPEN(params)) => {
+                let Ok(params) = serde_json::from_value::<DidOpenTextDocumentParams>(params) else {
+                    continue;
+                };
+                if !params.text_document.uri.as_str().ends_with(".py") || sync_odoo.try_lock().is_ok() {
+                    continue;
+                }
+                let (mut diagnostics, outline) = DocumentSymbolFeature::quick_parse(&params.text_document.text);
+                diagnostics.iter_mut().for_each(add_code_description);
+                let _ = sender.send(Message::Notification(lsp_server::Notification::new(
+                    PublishDiagnostics::METHOD.to_string(),
+                    PublishDiagnosticsParams {
+                        uri: params.text_document.uri.clone(),
+                        diagnostics,
+                        version: Some(params.text_document.version),
+                    })));
+                outlines.lock().unwrap().insert(params.text_document.uri.to_string(), outline);
+            },
+            Ok(QuickParseMessage::FORGET(uri)) => {
+                outlines.lock().unwrap().remove(&uri);
+            },
+            Ok(QuickParseMessage::EXIT) | Err(_) => {
                 return;
             }
         }
     }
 }
 
+#[cfg(feature = "server")]
+fn request_id(msg: &Message) -> Option<RequestId> {
+    match msg {
+        Message::Request(r) => Some(r.id.clone()),
+        _ => None
+    }
+}
+
+/* The answer to a request cancelled by the client. A result computed before the cancellation is dropped, as the client
+does not expect it anymore */
+#[cfg(feature = "server")]
+fn cancelled_result() -> (Option<Value>, Option<ResponseError>) {
+    (None, Some(ResponseError {
+        code: lsp_server::ErrorCode::RequestCanceled as i32,
+        message: S!("Request cancelled by the client"),
+        data: None
+    }))
+}
+
+/* Add the duration of a request to the stats, and warn the client if it is slow, with enough context for the user to report it */
+#[cfg(feature = "server")]
+fn record_request_latency(session: &mut SessionInfo, method: &str, target: Option<String>, start: Instant) {
+    let duration = start.elapsed();
+    let threshold = session.sync_odoo.config.slow_request_threshold;
+    if session.sync_odoo.request_stats.record(method, duration, threshold) {
+        let target = target.map(|target| format!(" on {}", target)).unwrap_or_default();
+        warn!("Slow request {}{}: {} ms", method, target, duration.as_millis());
+        session.log_message(MessageType::WARNING, format!("Slow request {}{}: {} ms (threshold: {} ms)", method, target, duration.as_millis(), threshold));
+    }
+}
+
+#[cfg(feature = "server")]
 pub fn message_processor_thread_main(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_receiver: Receiver<Message>, sender: Sender<Message>, receiver: Receiver<Message>, delayed_process_sender: Sender<DelayedProcessingMessage>) {
     loop {
         let msg = generic_receiver.recv();
@@ -247,22 +385,52 @@ pub fn message_processor_thread_main(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_re
             sender: sender.clone(),
             receiver: receiver.clone(),
             sync_odoo: &mut sync_odoo.lock().unwrap(),
-            delayed_process_sender: Some(delayed_process_sender.clone())
+            delayed_process_sender: Some(delayed_process_sender.clone()),
+            current_request: request_id(&msg),
         };
         match msg {
             Message::Request(r) => {
+                let method = r.method.clone();
+                let target = RequestStats::describe_target(&r.params);
+                let start = Instant::now();
+                if session.is_cancelled() {
+                    let (value, error) = cancelled_result();
+                    sender.send(Message::Response(Response { id: r.id, result: value, error: error })).unwrap();
+                    continue;
+                }
                 let (value, error) = match r.method.as_str() {
                     Completion::METHOD => {
                         //Handle completion in main because updates has to be done before the autocompletion
                         //Moreover, autocompletion will trigger a process_rebuild if needed.
                         to_value::<CompletionResponse>(Odoo::handle_autocomplete(&mut session, serde_json::from_value(r.params).unwrap()))
                     },
+                    OnTypeFormatting::METHOD => {
+                        //Like completion, the formatting must be computed on the last version of the document
+                        to_value::<Vec<TextEdit>>(Odoo::handle_on_type_formatting(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    RangeFormatting::METHOD => {
+                        to_value::<Vec<TextEdit>>(Odoo::handle_range_formatting(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    ClearCacheRequest::METHOD => {
+                        to_value::<ClearCacheResult>(Odoo::handle_clear_cache(&mut session))
+                    },
+                    ReloadModuleRequest::METHOD => {
+                        to_value::<ReloadModuleResult>(Odoo::handle_reload_module(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    ToggleFocusRequest::METHOD => {
+                        to_value::<ToggleFocusResult>(Odoo::handle_toggle_focus(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    ScaffoldRequest::METHOD => {
+                        to_value::<ScaffoldResult>(Odoo::handle_scaffold(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
                     _ => {error!("Request not handled by main thread: {}", r.method); (None, Some(ResponseError{
                         code: 1,
                         message: S!("Request not handled by the server"),
                         data: None
                     }))}
                 };
+                record_request_latency(&mut session, &method, target, start);
+                let (value, error) = if session.is_cancelled() { cancelled_result() } else { (value, error) };
                 sender.send(Message::Response(Response { id: r.id, result: value, error: error })).unwrap();
             },
             Message::Notification(n) => {
@@ -291,6 +459,7 @@ pub fn message_processor_thread_main(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_re
     }
 }
 
+#[cfg(feature = "server")]
 pub fn message_processor_thread_read(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_receiver: Receiver<Message>, sender: Sender<Message>, receiver: Receiver<Message>) {
     loop {
         let msg = generic_receiver.recv();
@@ -303,23 +472,94 @@ pub fn message_processor_thread_read(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_re
             sender: sender.clone(),
             receiver: receiver.clone(),
             sync_odoo: &mut sync_odoo.lock().unwrap(), //TODO work on read access
-            delayed_process_sender: None
+            delayed_process_sender: None,
+            current_request: request_id(&msg),
         };
         match msg {
             Message::Request(r) => {
+                let method = r.method.clone();
+                let target = RequestStats::describe_target(&r.params);
+                let start = Instant::now();
+                if session.is_cancelled() {
+                    let (value, error) = cancelled_result();
+                    sender.send(Message::Response(Response { id: r.id, result: value, error: error })).unwrap();
+                    continue;
+                }
                 let (value, error) = match r.method.as_str() {
                     HoverRequest::METHOD => {
                         to_value::<Hover>(Odoo::handle_hover(&mut session, serde_json::from_value(r.params).unwrap()))
                     },
+                    SignatureHelpRequest::METHOD => {
+                        to_value::<SignatureHelp>(Odoo::handle_signature_help(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    ResolveCompletionItem::METHOD => {
+                        to_value::<CompletionItem>(Odoo::handle_completion_resolve(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
                     GotoDefinition::METHOD => {
                         to_value::<GotoTypeDefinitionResponse>(Odoo::handle_goto_definition(&mut session, serde_json::from_value(r.params).unwrap()))
                     },
+                    GotoImplementation::METHOD => {
+                        to_value::<GotoImplementationResponse>(Odoo::handle_goto_implementation(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    References::METHOD => {
+                        to_value::<Vec<Location>>(Odoo::handle_references(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    PrepareRenameRequest::METHOD => {
+                        to_value::<PrepareRenameResponse>(Odoo::handle_prepare_rename(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    Rename::METHOD => {
+                        to_value::<WorkspaceEdit>(Odoo::handle_rename(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    CodeActionRequest::METHOD => {
+                        to_value::<CodeActionResponse>(Odoo::handle_code_action(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    CheckIntegrityRequest::METHOD => {
+                        to_value::<CheckIntegrityResult>(Odoo::handle_check_integrity(&mut session))
+                    },
+                    ExportPotPreviewRequest::METHOD => {
+                        to_value::<ExportPotPreviewResult>(Odoo::handle_export_pot_preview(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    FindUnusedXmlIdsRequest::METHOD => {
+                        to_value::<FindUnusedXmlIdsResult>(Odoo::handle_find_unused_xml_ids(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    ExportModuleGraphRequest::METHOD => {
+                        to_value::<ExportModuleGraphResult>(Odoo::handle_export_module_graph(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    GetModelSourceRequest::METHOD => {
+                        to_value::<GetModelSourceResult>(Odoo::handle_get_model_source(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    DoctorRequest::METHOD => {
+                        to_value::<DoctorReport>(Odoo::handle_doctor(&mut session))
+                    },
+                    BuildStatsRequest::METHOD => {
+                        to_value::<BuildStatsResult>(Odoo::handle_build_stats(&mut session))
+                    },
+                    DocumentSymbolRequest::METHOD => {
+                        to_value::<DocumentSymbolResponse>(Odoo::handle_document_symbol(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    DocumentLinkRequest::METHOD => {
+                        to_value::<Vec<DocumentLink>>(Odoo::handle_document_link(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    DocumentColor::METHOD => {
+                        to_value::<Vec<ColorInformation>>(Odoo::handle_document_color(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    InlineValueRequest::METHOD => {
+                        to_value::<Vec<InlineValue>>(Odoo::handle_inline_value(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
+                    ColorPresentationRequest::METHOD => {
+                        to_value::<Vec<ColorPresentation>>(Odoo::handle_color_presentation(serde_json::from_value(r.params).unwrap()))
+                    },
+                    WorkspaceSymbolRequest::METHOD => {
+                        to_value::<WorkspaceSymbolResponse>(Odoo::handle_workspace_symbol(&mut session, serde_json::from_value(r.params).unwrap()))
+                    },
                     _ => {error!("Request not handled by read thread: {}", r.method); (None, Some(ResponseError{
                         code: 1,
                         message: S!("Request not handled by the server"),
                         data: None
                     }))}
                 };
+                record_request_latency(&mut session, &method, target, start);
+                let (value, error) = if session.is_cancelled() { cancelled_result() } else { (value, error) };
                 sender.send(Message::Response(Response { id: r.id, result: value, error: error })).unwrap();
             },
             Message::Notification(r) => {
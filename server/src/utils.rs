@@ -1,7 +1,9 @@
-use std::{fs, path::{Path, PathBuf}, str::FromStr};
+use std::{fs, path::{Path, PathBuf}};
 use path_slash::{PathBufExt, PathExt};
 use ruff_text_size::TextSize;
 
+use crate::core::uri;
+
 #[macro_export]
 macro_rules! S {
     ($x: expr) => {
@@ -99,50 +101,45 @@ pub trait ToFilePath {
 
 impl ToFilePath for lsp_types::Uri {
 
+    /* Same path as FileMgr::uri2pathname, whatever the spelling of the uri */
     fn to_file_path(&self) -> Result<PathBuf, ()> {
-        let url = url::Url::from_str(self.as_str()).map_err(|_| ())?;
-        url.to_file_path()
+        let path = uri::uri_to_path(self.as_str()).ok_or(())?;
+        Ok(PathBuf::from(PathBuf::from(path).sanitize_canonical()))
     }
 
 }
 
 pub trait PathSanitizer {
     fn sanitize(&self) -> String;
+    /* Sanitized path with all the symlinks resolved, so a file reached through many paths has only one path in the
+    database. A path that doesn't exist (yet) is only sanitized */
+    fn sanitize_canonical(&self) -> String;
 }
 
 impl PathSanitizer for PathBuf {
 
-    fn sanitize(&self) -> String {
-        let mut path = self.to_slash_lossy().to_string();
-
-        #[cfg(windows)]
-        {
-            // Check if path begin with a letter + ':'
-            if path.len() > 2 && path.chars().nth(1) == Some(':') {
-                let disk_letter = path.chars().next().unwrap().to_ascii_lowercase();
-                path.replace_range(0..1, &disk_letter.to_string());
-            }
-        }
+    fn sanitize_canonical(&self) -> String {
+        self.as_path().sanitize_canonical()
+    }
 
-        path
+    fn sanitize(&self) -> String {
+        uri::normalize_path(&self.to_slash_lossy())
     }
 }
 
 impl PathSanitizer for Path {
 
-    fn sanitize(&self) -> String {
-        let mut path = self.to_slash_lossy().to_string();
-
-        #[cfg(windows)]
-        {
-            // Check if path begin with a letter + ':'
-            if path.len() > 2 && path.chars().nth(1) == Some(':') {
-                let disk_letter = path.chars().next().unwrap().to_ascii_lowercase();
-                path.replace_range(0..1, &disk_letter.to_string());
-            }
+    fn sanitize_canonical(&self) -> String {
+        match fs::canonicalize(self) {
+            //on windows, canonicalize returns a verbatim path (\\?\C:\... or \\?\UNC\...) that the clients never use,
+            //normalized by sanitize
+            Ok(canonical) => canonical.sanitize(),
+            Err(_) => self.sanitize()
         }
+    }
 
-        path
+    fn sanitize(&self) -> String {
+        uri::normalize_path(&self.to_slash_lossy())
     }
 }
 
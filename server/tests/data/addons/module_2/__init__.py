@@ -1 +1,2 @@
-from . import base_test_models
\ No newline at end of file
+from . import base_test_models
+from . import models
@@ -0,0 +1,11 @@
+from odoo import models
+
+
+class model_name_override(models.Model):
+    _inherit = "pygls.tests.m_name"
+
+    def func_1(self):
+        return super().func_1()
+
+    def func_2(self):
+        pass
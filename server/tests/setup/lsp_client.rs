@@ -0,0 +1,202 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{ClientCapabilities, InitializeParams, TextDocumentItem, DidOpenTextDocumentParams, WorkspaceFolder};
+use lsp_types::notification::{DidOpenTextDocument, Notification as _};
+use serde_json::{json, Value};
+
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::server::Server;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+/* Time to wait for a message of the server, including the build of the database */
+const TIMEOUT: Duration = Duration::from_secs(900);
+
+/* A client talking to a Server over an in-memory connection, configured with the initializationOptions */
+pub struct LspTestClient {
+    connection: Connection,
+    server_thread: JoinHandle<()>,
+    next_id: i32,
+    pub addons_path: String,
+    pub notifications: Vec<Notification>, //notifications sent by the server, apart from the logs
+}
+
+impl LspTestClient {
+
+    /* Start a server on the test addons, and wait for the build of the database */
+    pub fn start() -> Self {
+        let community_path = env::var("COMMUNITY_PATH").expect("Please provide COMMUNITY_PATH environment variable with a valid path to your Odoo Community folder");
+        let addons_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").sanitize();
+        let (mut server, connection) = Server::new_memory();
+        server.set_no_delay(true);
+        let server_thread = thread::spawn(move || {
+            server.initialize().expect("Error while initializing server");
+            server.run(None);
+        });
+        let mut client = LspTestClient {
+            connection,
+            server_thread,
+            next_id: 0,
+            addons_path: addons_path.clone(),
+            notifications: vec![],
+        };
+        let workspace_uri = FileMgr::pathname2uri(&addons_path);
+        #[allow(deprecated)]
+        let params = InitializeParams {
+            capabilities: ClientCapabilities::default(),
+            workspace_folders: Some(vec![WorkspaceFolder { uri: workspace_uri, name: S!("addons") }]),
+            initialization_options: Some(json!({
+                "odooPath": community_path,
+                "addons": [addons_path],
+                "pythonPath": "python3",
+                "autoRefresh": "off",
+            })),
+            ..Default::default()
+        };
+        client.request_value("initialize", serde_json::to_value(params).unwrap());
+        client.notify("initialized", json!({}));
+        client.wait_for_notification("$Odoo/loadingStatusUpdate", |params| params.as_str() == Some("stop"));
+        client
+    }
+
+    /* Send a request and wait for its result. Panics if the server answers with an error */
+    pub fn request_value(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        self.connection.sender.send(Message::Request(Request { id: id.clone(), method: S!(method), params })).unwrap();
+        loop {
+            if let Message::Response(response) = self.receive() {
+                if response.id != id {
+                    continue;
+                }
+                if let Some(error) = response.error {
+                    panic!("{} failed: {}", method, error.message);
+                }
+                return response.result.unwrap_or(Value::Null);
+            }
+        }
+    }
+
+    /* Stop the server like an editor, with the shutdown request and the exit notification, and wait for the end of its threads */
+    pub fn stop(mut self) {
+        self.request_value("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        self.server_thread.join().expect("The server panicked while stopping");
+    }
+
+    pub fn notify(&mut self, method: &str, params: Value) {
+        self.connection.sender.send(Message::Notification(Notification { method: S!(method), params })).unwrap();
+    }
+
+    /* Open a file of the test addons, given by its path relative to the addons directory */
+    pub fn open(&mut self, relative_path: &str) {
+        let path = Path::new(&self.addons_path).join(relative_path).sanitize();
+        let text = fs::read_to_string(&path).unwrap_or_else(|_| panic!("Unable to read {}", path));
+        let params = DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: FileMgr::pathname2uri(&path),
+                language_id: S!("python"),
+                version: 1,
+                text,
+            },
+        };
+        self.notify(DidOpenTextDocument::METHOD, serde_json::to_value(params).unwrap());
+    }
+
+    /* Wait for a notification of the server with the given method and matching params. The previous ones are kept */
+    pub fn wait_for_notification(&mut self, method: &str, matches: impl Fn(&Value) -> bool) -> Value {
+        if let Some(notification) = self.notifications.iter().find(|n| n.method == method && matches(&n.params)) {
+            return notification.params.clone();
+        }
+        loop {
+            if let Message::Notification(notification) = self.receive() {
+                if notification.method == method && matches(&notification.params) {
+                    return notification.params;
+                }
+            }
+        }
+    }
+
+    /* Next message of the server. The requests of the server are answered here, like an editor would */
+    fn receive(&mut self) -> Message {
+        let message = self.connection.receiver.recv_timeout(TIMEOUT).expect("No message from the server");
+        match &message {
+            Message::Request(request) => {
+                let result = match request.method.as_str() {
+                    "workspace/configuration" => json!([null]),
+                    "Odoo/getPythonPath" => json!({"pythonPath": "python3"}),
+                    _ => Value::Null, //registerCapability, workDoneProgress/create...
+                };
+                self.connection.sender.send(Message::Response(Response::new_ok(request.id.clone(), result))).unwrap();
+            },
+            Message::Notification(notification) if notification.method != "window/logMessage" => {
+                self.notifications.push(notification.clone());
+            },
+            _ => {}
+        }
+        message
+    }
+
+    /* The uri of a file of the test addons, as written in the golden files: ${addons}/module_1/models/models.py */
+    pub fn replace_placeholders(&self, value: &Value) -> Value {
+        let addons_uri = FileMgr::pathname2uri(&self.addons_path).as_str().to_string();
+        serde_json::from_str(&value.to_string().replace("${addons}", &addons_uri)).unwrap()
+    }
+}
+
+/* Replay the steps of a golden file, and check the results of the requests against the expected ones. With the
+ODOOLS_UPDATE_GOLDEN environment variable, the expected results are replaced by the actual ones instead.
+A golden file is a json object with a list of steps:
+    {"open": "module_1/models/models.py"}: open a file of the test addons
+    {"method": "textDocument/hover", "params": {...}, "expected": {...}}: send a request
+The expected result only has to be a part of the actual one: see matches */
+pub fn replay(client: &mut LspTestClient, golden_path: &Path) {
+    let content = fs::read_to_string(golden_path).unwrap_or_else(|_| panic!("Unable to read {:?}", golden_path));
+    let mut golden: Value = serde_json::from_str(&content).unwrap_or_else(|e| panic!("Invalid golden file {:?}: {}", golden_path, e));
+    let update = env::var("ODOOLS_UPDATE_GOLDEN").is_ok();
+    let steps = golden.get_mut("steps").and_then(|steps| steps.as_array_mut()).expect("A golden file must contain a list of steps");
+    for (index, step) in steps.iter_mut().enumerate() {
+        if let Some(path) = step.get("open").and_then(|path| path.as_str()) {
+            client.open(path);
+            continue;
+        }
+        let method = step.get("method").and_then(|method| method.as_str()).unwrap_or_else(|| panic!("Step {} of {:?} has no method", index, golden_path)).to_string();
+        let params = client.replace_placeholders(step.get("params").unwrap_or(&Value::Null));
+        let result = client.request_value(&method, params);
+        if update {
+            step["expected"] = result;
+            continue;
+        }
+        let expected = client.replace_placeholders(step.get("expected").unwrap_or(&Value::Null));
+        assert!(matches(&expected, &result), "Step {} ({}) of {:?}: expected\n{}\nto be part of\n{}",
+            index, method, golden_path, serde_json::to_string_pretty(&expected).unwrap(), serde_json::to_string_pretty(&result).unwrap());
+    }
+    if update {
+        fs::write(golden_path, serde_json::to_string_pretty(&golden).unwrap() + "\n").unwrap();
+    }
+}
+
+/* True if the expected value is a part of the actual one: the keys of the expected objects are compared, and each
+element of an expected array must match an element of the actual one. An empty expected array only matches an empty
+array. {"$contains": "text"} matches the strings containing the text, for the results that depend on the version of
+Odoo, like the documentation of its methods */
+pub fn matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::String(actual)) if expected.len() == 1 && expected.contains_key("$contains") => {
+            expected["$contains"].as_str().is_some_and(|text| actual.contains(text))
+        },
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.iter().all(|(key, value)| actual.get(key).is_some_and(|actual| matches(value, actual)))
+        },
+        (Value::Array(expected), Value::Array(actual)) if expected.is_empty() => actual.is_empty(),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.iter().all(|value| actual.iter().any(|actual| matches(value, actual)))
+        },
+        (expected, actual) => expected == actual,
+    }
+}
@@ -1 +1,5 @@
-pub mod setup;
\ No newline at end of file
+#[allow(dead_code)] //each test only uses a part of the helpers
+pub mod setup;
+#[cfg(feature = "server")]
+#[allow(dead_code)] //each test only uses a part of the client
+pub mod lsp_client;
@@ -1,6 +1,6 @@
 use std::env;
-
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 
 use odoo_ls_server::{core::{config::{Config, DiagMissingImportsMode}, odoo::SyncOdoo}, threads::SessionInfo, utils::PathSanitizer as _};
@@ -11,7 +11,51 @@ use tracing_appender::rolling::RollingFileAppender;
 use tracing_subscriber::{fmt, layer::SubscriberExt, FmtSubscriber};
 
 pub fn setup_server() -> SyncOdoo {
+    setup_server_with_addons(vec![])
+}
+
+/* Write an Odoo module in the addons directory root. The manifest and the __init__.py importing the python files of
+the module are generated, unless they are part of files. Files can be in subdirectories of the module */
+pub fn write_module(root: &Path, name: &str, depends: &[&str], files: &[(&str, &str)]) -> PathBuf {
+    let module_dir = root.join(name);
+    fs::create_dir_all(&module_dir).unwrap();
+    if !files.iter().any(|(path, _)| *path == "__manifest__.py") {
+        let depends: Vec<String> = depends.iter().map(|depend| format!("'{}'", depend)).collect();
+        fs::write(module_dir.join("__manifest__.py"), format!("{{'name': '{}', 'depends': [{}]}}\n", name, depends.join(", "))).unwrap();
+    }
+    if !files.iter().any(|(path, _)| *path == "__init__.py") {
+        let imports: Vec<String> = files.iter()
+            .filter_map(|(path, _)| path.strip_suffix(".py").filter(|module| !module.contains('/') && !module.starts_with("__")))
+            .map(|module| format!("from . import {}\n", module))
+            .collect();
+        fs::write(module_dir.join("__init__.py"), imports.concat()).unwrap();
+    }
+    for (path, content) in files.iter() {
+        let path = module_dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, content).unwrap();
+    }
+    module_dir
+}
+
+/* Same as setup_server, with additional addons paths, like generated ones */
+pub fn setup_server_with_addons(extra_addons: Vec<String>) -> SyncOdoo {
+    let config = setup_config(extra_addons);
+    let mut server = SyncOdoo::new();
+    server.load_odoo_addons = false;
 
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut server, None);
+    SyncOdoo::init(&mut session, config);
+
+    server
+}
+
+/* Set the logs of the tests up, and give the configuration of a server on the test addons and the extra addons paths.
+For the tests that have to run SyncOdoo::init themselves. Must be called once per test binary */
+pub fn setup_config(extra_addons: Vec<String>) -> Config {
     let file_appender = RollingFileAppender::builder()
         .max_log_files(20) // only the most recent 5 log files will be kept
         .filename_prefix(format!("odoo_tests_logs_{}", std::process::id()))
@@ -32,24 +76,18 @@ pub fn setup_server() -> SyncOdoo {
 
     let community_path = env::var("COMMUNITY_PATH").expect("Please provide COMMUNITY_PATH environment variable with a valid path to your Odoo Community folder");
     info!("Community path: {:?}", community_path);
-    let mut server = SyncOdoo::new();
-    server.load_odoo_addons = false;
-
     let mut test_addons_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     test_addons_path = test_addons_path.join("tests").join("data").join("addons");
     info!("Test addons path: {:?}", test_addons_path);
 
     let mut config = Config::new();
     config.addons = vec![test_addons_path.sanitize()];
+    config.addons.extend(extra_addons);
     config.odoo_path = community_path;
     config.python_path = S!("python3");
     config.refresh_mode = odoo_ls_server::core::config::RefreshMode::Off;
     config.diag_missing_imports = DiagMissingImportsMode::All;
     config.no_typeshed = false;
 
-    let (s, r) = crossbeam_channel::unbounded();
-    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut server, None);
-    SyncOdoo::init(&mut session, config);
-
-    server
-}
\ No newline at end of file
+    config
+}
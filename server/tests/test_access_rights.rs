@@ -0,0 +1,31 @@
+use odoo_ls_server::core::access_rights::{model_xml_name, parse_access_csv, parse_access_xml};
+
+#[test]
+fn test_parse_access_csv() {
+    let csv = "id,name,model_id:id,group_id:id,perm_read,perm_write,perm_create,perm_unlink\n\
+        access_library_book_user,library.book.user,model_library_book,base.group_user,1,0,0,0\n\
+        \"access_partner_manager\",res.partner.manager,\"base.model_res_partner\",base.group_system,1,1,1,1\n";
+    assert_eq!(parse_access_csv(csv), vec!["model_library_book", "model_res_partner"]);
+    assert!(parse_access_csv("id,name\naccess,name\n").is_empty());
+    //the quoted values can contain commas, quotes and line breaks
+    let csv = "id,name,model_id:id,group_id:id\n\
+        access_book,\"Books, \"\"all\"\"\nof them\",model_library_book,base.group_user\n";
+    assert_eq!(parse_access_csv(csv), vec!["model_library_book"]);
+}
+
+#[test]
+fn test_parse_access_xml() {
+    let xml = r#"<odoo>
+        <record id="access_library_loan" model="ir.model.access">
+            <field name="name">library.loan</field>
+            <field name="model_id" ref="model_library_loan"/>
+            <field name="perm_read" eval="True"/>
+        </record>
+        <!-- <record id="access_old" model="ir.model.access"><field name="model_id" ref="model_old"/></record> -->
+        <record id="rule_library_loan" model="ir.rule">
+            <field name="model_id" ref="model_library_rule"/>
+        </record>
+    </odoo>"#;
+    assert_eq!(parse_access_xml(xml), vec!["model_library_loan"]);
+    assert_eq!(model_xml_name("library.loan"), "model_library_loan");
+}
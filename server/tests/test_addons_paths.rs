@@ -0,0 +1,58 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::constants::tree;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* Addons paths are merged in odoo.addons: the first path providing a module wins, and a module always wins over a plain
+python package of the same name */
+#[test]
+fn test_addons_paths() {
+    let root = env::temp_dir().join(format!("odoo_ls_addons_paths_{}", std::process::id()));
+    let first = root.join("first");
+    let second = root.join("second");
+    setup::setup::write_module(&first, "shared_module", &[], &[("models.py", "from odoo import models\n\n\nclass FirstModel(models.Model):\n    _name = \"shared.first\"\n")]);
+    setup::setup::write_module(&second, "shared_module", &[], &[("models.py", "from odoo import models\n\n\nclass SecondModel(models.Model):\n    _name = \"shared.second\"\n")]);
+    //a plain python package, without manifest
+    let helpers = setup::setup::write_module(&first, "helpers", &[], &[("models.py", "def helper():\n    return True\n")]);
+    fs::remove_file(helpers.join("__manifest__.py")).unwrap();
+    setup::setup::write_module(&second, "helpers", &[], &[("models.py", "from odoo import models\n\n\nclass Helper(models.Model):\n    _name = \"shared.helper\"\n")]);
+    //a module symlinked in the addons path, from a directory with another name
+    let linked = setup::setup::write_module(&root.join("outside"), "real_linked", &[], &[("models.py", "")]);
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&linked, first.join("linked_module")).unwrap();
+
+    //the first path is given twice: its priority is not changed
+    let odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![first.sanitize(), second.sanitize(), first.sanitize()]);
+    let module_path = |name: &str| odoo.modules.get(name).and_then(|module| module.upgrade())
+        .map(|module| PathBuf::from(module.borrow().as_module_package().path.clone()))
+        .unwrap_or_else(|| panic!("module {} not loaded", name));
+    assert_eq!(module_path("shared_module"), first.join("shared_module"));
+    assert_eq!(module_path("helpers"), second.join("helpers"));
+    assert!(odoo.models.contains_key("shared.first"));
+    assert!(!odoo.models.contains_key("shared.second"));
+    assert!(odoo.models.contains_key("shared.helper"));
+
+    let addons = odoo.get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX)[0].clone();
+    let first_paths = addons.borrow().paths().iter().filter(|path| PathBuf::from(path) == first).count();
+    assert_eq!(first_paths, 1);
+
+    //files of the hidden module are not part of odoo.addons
+    assert_eq!(odoo.tree_from_path(&first.join("shared_module").join("models.py")).ok(), Some((vec![S!("odoo"), S!("addons"), S!("shared_module"), S!("models")], vec![])));
+    assert!(odoo.tree_from_path(&second.join("shared_module").join("models.py")).is_err());
+
+    //only the extension of python files is removed from the names
+    assert_eq!(odoo.tree_from_path(&first.join("shared_module").join("models.pyi")).ok(), Some((vec![S!("odoo"), S!("addons"), S!("shared_module"), S!("models")], vec![])));
+    assert_eq!(odoo.tree_from_path(&first.join("shared_module").join("data.pyramid")).ok(), Some((vec![S!("odoo"), S!("addons"), S!("shared_module"), S!("data.pyramid")], vec![])));
+    #[cfg(unix)]
+    {
+        assert_eq!(odoo.tree_from_path(&linked.join("models.pyi")).ok(), Some((vec![S!("odoo"), S!("addons"), S!("linked_module"), S!("models")], vec![])));
+        assert_eq!(odoo.tree_from_path(&linked.join("data.pyramid")).ok(), Some((vec![S!("odoo"), S!("addons"), S!("linked_module"), S!("data.pyramid")], vec![])));
+    }
+    let _ = fs::remove_dir_all(&root);
+}
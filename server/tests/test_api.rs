@@ -0,0 +1,65 @@
+use std::env;
+use std::fs;
+
+use odoo_ls_server::api::Analysis;
+use odoo_ls_server::core::config::Config;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+const BASE_MODELS: &str = r#"from odoo import fields, models
+
+
+class Partner(models.Model):
+    _name = "api.partner"
+
+    name = fields.Char()
+"#;
+
+const USER_MODELS: &str = r#"from odoo import fields, models
+from odoo.addons.api_unknown_module import helpers
+
+
+class Partner(models.Model):
+    _inherit = "api.partner"
+
+    nickname = fields.Char()
+"#;
+
+/* The analysis can be run without the language server, and only the workspace folders are validated */
+#[test]
+fn test_analysis() {
+    let root = env::temp_dir().join(format!("odoo_ls_api_{}", std::process::id()));
+    for (module, depends, content) in [("api_base", "", BASE_MODELS), ("api_user", "'api_base'", USER_MODELS)] {
+        let module_dir = root.join("addons").join(module);
+        fs::create_dir_all(&module_dir).unwrap();
+        fs::write(module_dir.join("__manifest__.py"), format!("{{'name': '{}', 'depends': [{}]}}\n", module, depends)).unwrap();
+        fs::write(module_dir.join("__init__.py"), "from . import models\n").unwrap();
+        fs::write(module_dir.join("models.py"), content).unwrap();
+    }
+    let mut config = Config::new();
+    config.odoo_path = env::var("COMMUNITY_PATH").expect("Please provide COMMUNITY_PATH environment variable with a valid path to your Odoo Community folder");
+    config.addons = vec![root.join("addons").sanitize()];
+    config.python_path = S!("python3");
+    let mut analysis = Analysis::new(config, vec![root.join("addons").join("api_user").sanitize()]);
+
+    assert!(analysis.model_names().contains(&S!("api.partner")));
+    assert_eq!(analysis.model_classes("api.partner", Some("api_base")).len(), 1);
+    assert_eq!(analysis.model_classes("api.partner", Some("api_user")).len(), 2);
+    assert_eq!(analysis.model_classes("api.partner", None).len(), 2);
+    assert!(analysis.model_classes("api.unknown", None).is_empty());
+
+    let diagnostics = analysis.take_diagnostics();
+    let user_models = root.join("addons").join("api_user").join("models.py").sanitize();
+    let base_models = root.join("addons").join("api_base").join("models.py").sanitize();
+    assert!(diagnostics.get(&user_models).is_some_and(|diagnostics| diagnostics.iter().any(|d| d.message.contains("api_unknown_module"))),
+        "the unresolved import is not reported: {:?}", diagnostics.get(&user_models));
+    assert!(diagnostics.get(&base_models).map_or(true, |diagnostics| diagnostics.is_empty()));
+    //diagnostics are only returned once
+    assert!(analysis.take_diagnostics().is_empty());
+
+    //the lower level API is reachable from the analysis
+    let session = analysis.session();
+    assert!(session.sync_odoo.modules.contains_key("api_user"));
+    drop(session);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use odoo_ls_server::core::bench::{BenchReport, ProcessMemory, WorkloadTimings};
+
+#[test]
+fn test_workload_timings() {
+    let durations: Vec<Duration> = (1..=10).rev().map(Duration::from_millis).collect();
+    let timings = WorkloadTimings::from_durations("hover", &durations, Some(2048));
+    assert_eq!(timings.runs, 10);
+    assert_eq!(timings.total_us, 55000);
+    assert_eq!(timings.mean_us, 5500);
+    assert_eq!(timings.p50_us, 5000);
+    assert_eq!(timings.p95_us, 9000);
+    assert_eq!(timings.max_us, 10000);
+    //a workload without run, like the edits of addons without python file
+    let empty = WorkloadTimings::from_durations("incremental edit", &[], None);
+    assert_eq!((empty.runs, empty.mean_us, empty.max_us), (0, 0, 0));
+}
+
+#[test]
+fn test_process_memory() {
+    let status = "Name:\todoo_ls_server\nVmPeak:\t 2500000 kB\nVmHWM:\t 1200000 kB\nVmRSS:\t  900000 kB\n";
+    assert_eq!(ProcessMemory::parse(status), Some(ProcessMemory { rss_kb: 900000, peak_rss_kb: 1200000 }));
+    assert_eq!(ProcessMemory::parse("Name:\todoo_ls_server\n"), None);
+}
+
+#[test]
+fn test_bench_report_markdown() {
+    let report = BenchReport {
+        server_version: String::from("1.0.0"),
+        odoo_path: String::from("/opt/odoo"),
+        modules: 2,
+        models: 30,
+        files: 400,
+        workloads: vec![
+            WorkloadTimings::from_durations("full build", &[Duration::from_millis(12500)], Some(512000)),
+            WorkloadTimings::from_durations("hover", &[Duration::from_micros(250), Duration::from_micros(750)], None),
+        ],
+        peak_rss_kb: Some(614400),
+    };
+    let markdown = report.to_markdown();
+    assert!(markdown.contains("| full build | 1 | 12500.00 | 12500.00 | 12500.00 | 12500.00 | 12500.00 | 500 |"));
+    assert!(markdown.contains("| hover | 2 | 1.00 | 0.50 | 0.25 | 0.25 | 0.75 | - |"));
+    assert!(markdown.contains("Peak memory: 600 MB"));
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use lsp_server::Message;
+use lsp_types::notification::Notification;
+use lsp_types::{Diagnostic, Position, Range};
+use odoo_ls_server::core::build_journal::{BuildJournal, JournalFile, BATCH_SIZE};
+use odoo_ls_server::core::module_status::ModuleStatusNotification;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+const SHUTDOWN_B_FILES: usize = 30;
+
+#[test]
+fn test_batches_follow_dependencies() {
+    let mut depends: HashMap<String, Vec<String>> = HashMap::new();
+    depends.insert("base".to_string(), vec![]);
+    //modules named before base in the alphabetical order, but depending on it
+    for i in 0..BATCH_SIZE {
+        depends.insert(format!("a_module_{:02}", i), vec!["base".to_string()]);
+    }
+    depends.insert("sale".to_string(), vec!["a_module_00".to_string(), "mail".to_string()]);
+    depends.insert("mail".to_string(), vec!["base".to_string()]);
+    let batches = BuildJournal::batches(&depends);
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].len(), BATCH_SIZE);
+    assert_eq!(batches[0][0], "base");
+    //sale comes after its dependencies
+    assert_eq!(batches[1], vec![format!("a_module_{:02}", BATCH_SIZE - 1), "mail".to_string(), "sale".to_string()]);
+    //the same modules give the same batches on the next start
+    assert_eq!(BuildJournal::batches(&depends), batches);
+}
+
+#[test]
+fn test_journal_resume() {
+    let dir = env::temp_dir().join(format!("odoo_ls_build_journal_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let key = BuildJournal::key("fingerprint", "/odoo", &vec!["/addons".to_string()]);
+    assert_ne!(key, BuildJournal::key("fingerprint", "/odoo", &vec!["/other_addons".to_string()]));
+    assert!(BuildJournal::load(&dir, &key).is_none());
+
+
+    let mut journal = BuildJournal::new(key.clone());
+    let content = "from odoo import models\n";
+    let diagnostic = Diagnostic::new_simple(Range::new(Position::new(5, 0), Position::new(11, 0)), String::from("Unknown model"));
+    let mut files = HashMap::new();
+    files.insert("/addons/mail/models.py".to_string(), JournalFile::new(content, vec![diagnostic]));
+    journal.complete_batch(&["base".to_string(), "mail".to_string()], files);
+    //the directory is created with the first journal
+    journal.save(&dir.join("cache")).unwrap();
+    let loaded = BuildJournal::load(&dir.join("cache"), &key).unwrap();
+    assert_eq!(loaded, journal);
+    assert!(loaded.completed_modules().contains("mail"));
+    //the recorded diagnostics are only reused for the same content
+    let file = &loaded.files["/addons/mail/models.py"];
+    assert!(file.matches(content));
+    assert!(!file.matches("from odoo import fields\n"));
+    journal.save(&dir).unwrap();
+
+    //a truncated journal is ignored
+    fs::write(BuildJournal::path(&dir, &key), "{\"key\": \"").unwrap();
+    assert!(BuildJournal::load(&dir, &key).is_none());
+    journal.remove(&dir);
+    assert!(!BuildJournal::path(&dir, &key).exists());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+/* The shutdown of the server stops the initial build between two files. The journal keeps the completed batches and the files
+already validated by the stopped batch, but not its modules, that are built again on the next start */
+#[test]
+fn test_journal_on_shutdown() {
+    let root = env::temp_dir().join(format!("odoo_ls_journal_shutdown_{}", std::process::id()));
+    let addons = root.join("addons");
+    //modules without dependencies fill the first batch: the modules of the test come after them
+    let mut fillers = vec![];
+    for i in 0..BATCH_SIZE {
+        let name = format!("shutdown_filler_{:02}", i);
+        setup::setup::write_module(&addons, &name, &[], &[("models.py", "from odoo import models\n")]);
+        fillers.push(name);
+    }
+    let filler_names: Vec<&str> = fillers.iter().map(|name| name.as_str()).collect();
+    setup::setup::write_module(&addons, "shutdown_a", &filler_names, &[("models.py", "from odoo import models\n")]);
+    let b_files: Vec<(String, String)> = (0..SHUTDOWN_B_FILES)
+        .map(|i| (format!("models_{:02}.py", i), format!("from odoo import fields, models\n\n\nclass Model{i}(models.Model):\n    _name = \"shutdown.b{i}\"\n\n    name = fields.Char()\n")))
+        .collect();
+    let b_files: Vec<(&str, &str)> = b_files.iter().map(|(name, content)| (name.as_str(), content.as_str())).collect();
+    setup::setup::write_module(&addons, "shutdown_b", &["shutdown_a"], &b_files);
+
+    env::set_var("XDG_CACHE_HOME", root.join("cache"));
+    let config = setup::setup::setup_config(vec![addons.sanitize()]);
+    //each message of the server waits for the test, so the shutdown is requested before the validation of the next file
+    let (s, r) = crossbeam_channel::bounded(0);
+    let session_receiver = r.clone();
+    let (shutdown_sender, shutdown_receiver) = mpsc::channel();
+    let build = thread::spawn(move || {
+        let mut odoo = SyncOdoo::new();
+        odoo.load_odoo_addons = false;
+        shutdown_sender.send(odoo.shutdown.clone()).unwrap();
+        let mut session = SessionInfo::new_from_custom_channel(s, session_receiver, &mut odoo, None);
+        SyncOdoo::init(&mut session, config);
+    });
+    let shutdown = shutdown_receiver.recv().unwrap();
+    while !build.is_finished() {
+        let Ok(Message::Notification(notification)) = r.recv_timeout(Duration::from_millis(100)) else {
+            continue;
+        };
+        if notification.method == ModuleStatusNotification::METHOD && notification.params["module"] == "shutdown_a" {
+            shutdown.store(true, Ordering::SeqCst);
+        }
+    }
+    build.join().unwrap();
+
+    let journals: Vec<BuildJournal> = fs::read_dir(root.join("cache").join("odoo_ls")).expect("no journal has been written")
+        .filter_map(|entry| fs::read_to_string(entry.unwrap().path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    assert_eq!(journals.len(), 1);
+    let journal = &journals[0];
+    let completed = journal.completed_modules();
+    assert!(completed.contains("base") && completed.contains("shutdown_filler_00"));
+    assert!(!completed.contains("shutdown_b"));
+    assert!(journal.files.contains_key(&addons.join("shutdown_a").join("models.py").sanitize()));
+    let b_dir = addons.join("shutdown_b").sanitize();
+    assert!(journal.files.keys().filter(|path| path.starts_with(&b_dir)).count() < SHUTDOWN_B_FILES);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,52 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::PublishDiagnosticsParams;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+/* An opened file waiting for its validation is validated before the rest of the queue, even if its module would come last */
+#[test]
+fn test_build_priority() {
+    let root = env::temp_dir().join(format!("odoo_ls_build_priority_{}", std::process::id()));
+    for (module, depends) in [("priority_base", vec![]), ("priority_user", vec!["priority_base"])] {
+        setup::setup::write_module(&root.join("addons"), module, &depends, &[("models.py", "from odoo import models\n")]);
+    }
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let client = r.clone();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let base_path = root.join("addons").join("priority_base").join("models.py").sanitize();
+    let user_path = root.join("addons").join("priority_user").join("models.py").sanitize();
+
+    let validation_order = |session: &mut SessionInfo, prioritized: Option<&String>| {
+        for path in [&base_path, &user_path] {
+            let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(path)).expect("models.py is not loaded");
+            file_symbol.borrow_mut().set_in_workspace(true);
+            session.sync_odoo.add_to_validations(file_symbol);
+        }
+        if let Some(path) = prioritized {
+            session.sync_odoo.prioritize_file(&PathBuf::from(path));
+        }
+        SyncOdoo::process_rebuilds(session);
+        client.try_iter().filter_map(|message| match message {
+            Message::Notification(notification) if notification.method == PublishDiagnostics::METHOD => {
+                serde_json::from_value::<PublishDiagnosticsParams>(notification.params).ok().map(|params| FileMgr::uri2pathname(params.uri.as_str()))
+            },
+            _ => None,
+        }).collect::<Vec<String>>()
+    };
+    //modules are validated in the order of their dependencies
+    assert_eq!(validation_order(&mut session, None), vec![base_path.clone(), user_path.clone()]);
+    assert_eq!(validation_order(&mut session, Some(&user_path)), vec![user_path.clone(), base_path.clone()]);
+    //the priority is dropped once the file is validated
+    assert_eq!(validation_order(&mut session, None), vec![base_path.clone(), user_path.clone()]);
+    let _ = fs::remove_dir_all(&root);
+}
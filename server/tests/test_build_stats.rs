@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use odoo_ls_server::core::build_stats::RequestStats;
+use serde_json::json;
+
+#[test]
+fn test_request_stats() {
+    let mut stats = RequestStats::default();
+    assert!(!stats.record("textDocument/hover", Duration::from_millis(30), 2000));
+    assert!(stats.record("textDocument/hover", Duration::from_millis(2500), 2000));
+    assert!(!stats.record("textDocument/completion", Duration::from_millis(100), 2000));
+    //a threshold of 0 disables the reports, not the stats
+    assert!(!stats.record("textDocument/completion", Duration::from_millis(5000), 0));
+
+    let latencies = stats.latencies();
+    assert_eq!(latencies.len(), 2);
+    assert_eq!(latencies[0].method, "textDocument/completion");
+    assert_eq!(latencies[0].count, 2);
+    assert_eq!(latencies[0].total_ms, 5100);
+    assert_eq!(latencies[0].slow, 0);
+    assert_eq!(latencies[1].method, "textDocument/hover");
+    assert_eq!(latencies[1].max_ms, 2500);
+    assert_eq!(latencies[1].slow, 1);
+}
+
+#[test]
+fn test_describe_target() {
+    let hover = json!({"textDocument": {"uri": "file:///addons/sale/models/sale.py"}, "position": {"line": 9, "character": 4}});
+    assert_eq!(RequestStats::describe_target(&hover).unwrap(), "file:///addons/sale/models/sale.py:10");
+    let code_action = json!({"textDocument": {"uri": "file:///a.py"}, "range": {"start": {"line": 0, "character": 0}, "end": {"line": 1, "character": 0}}});
+    assert_eq!(RequestStats::describe_target(&code_action).unwrap(), "file:///a.py:1");
+    assert_eq!(RequestStats::describe_target(&json!({"textDocument": {"uri": "file:///a.py"}})).unwrap(), "file:///a.py");
+    assert!(RequestStats::describe_target(&json!(null)).is_none());
+}
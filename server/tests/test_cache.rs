@@ -0,0 +1,26 @@
+use std::hash::Hasher;
+
+use odoo_ls_server::core::cache::{CacheFingerprint, StableHasher};
+
+#[test]
+fn test_stable_hasher() {
+    //FNV-1a reference values
+    assert_eq!(StableHasher::new().finish(), 0xcbf29ce484222325);
+    let mut hasher = StableHasher::new();
+    hasher.write(b"a");
+    assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+}
+
+#[test]
+fn test_cache_fingerprint_key() {
+    let fingerprint = CacheFingerprint {
+        python_version: String::from("3.12.1"),
+        odoo_version: String::from("17.0"),
+        server_version: String::from("0.2.7"),
+        hook_sets: vec![(String::from("oca_queue_job"), 1)],
+    };
+    //the key names files kept between runs: it must not change with the build of the server
+    assert_eq!(fingerprint.key(), "e00fa47d8327f8bc");
+    let other = CacheFingerprint { python_version: String::from("3.11.0"), ..fingerprint.clone() };
+    assert_ne!(fingerprint.key(), other.key());
+}
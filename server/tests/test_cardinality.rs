@@ -0,0 +1,105 @@
+use odoo_ls_server::core::cardinality::{method_self_cardinality, Cardinality, CardinalityAnalyzer};
+use ruff_python_ast::{Stmt, StmtFunctionDef};
+
+fn parse_function(code: &str) -> StmtFunctionDef {
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let Some(Stmt::FunctionDef(func_def)) = module.body.into_iter().next() else {
+        panic!("not a function: {}", code);
+    };
+    func_def
+}
+
+/* The operands reported by the analyzer, as "records.field" */
+fn flagged(code: &str) -> Vec<String> {
+    let func_def = parse_function(code);
+    CardinalityAnalyzer::analyze(&func_def).iter().map(|attr| {
+        format!("{}.{}", attr.value.as_name_expr().unwrap().id, attr.attr)
+    }).collect()
+}
+
+#[test]
+fn test_method_self_cardinality() {
+    assert_eq!(method_self_cardinality(&parse_function("@api.depends('amount')\ndef _total(self):\n    pass\n")), Cardinality::Multi);
+    assert_eq!(method_self_cardinality(&parse_function("@api.constrains('amount')\ndef _check(self):\n    pass\n")), Cardinality::Multi);
+    assert_eq!(method_self_cardinality(&parse_function("def _compute_total(self):\n    pass\n")), Cardinality::Multi);
+    assert_eq!(method_self_cardinality(&parse_function("@api.onchange('amount')\ndef _onchange_amount(self):\n    pass\n")), Cardinality::Singleton);
+    assert_eq!(method_self_cardinality(&parse_function("def action_confirm(self):\n    pass\n")), Cardinality::Unknown);
+}
+
+#[test]
+fn test_cardinality_operands() {
+    //computes are called on several records
+    assert_eq!(flagged("def _compute_total(self):\n    self.total = self.amount * 2\n"), vec!["self.amount"]);
+    assert_eq!(flagged("def _compute_total(self):\n    for rec in self:\n        rec.total = rec.amount * 2\n"), Vec::<String>::new());
+    assert_eq!(flagged("def _compute_total(self):\n    self.ensure_one()\n    self.total = self.amount * 2\n"), Vec::<String>::new());
+    assert_eq!(flagged("@api.constrains('amount')\ndef _check(self):\n    if self.amount < 0:\n        pass\n"), vec!["self.amount"]);
+    //search gives several records, unless limited to one
+    assert_eq!(flagged("def action(self):\n    orders = self.env['sale.order'].search([])\n    return orders.amount + 1\n"), vec!["orders.amount"]);
+    assert_eq!(flagged("def action(self):\n    order = self.env['sale.order'].search([], limit=1)\n    return order.amount + 1\n"), Vec::<String>::new());
+    assert_eq!(flagged("def action(self):\n    orders = self.env['sale.order'].browse(order_ids).sudo()\n    return -orders.amount\n"), vec!["orders.amount"]);
+    assert_eq!(flagged("def action(self):\n    order = self.env['sale.order'].browse(order_id)\n    return order.amount + 1\n"), Vec::<String>::new());
+    //the cardinality is lost when the branches disagree
+    assert_eq!(flagged("def action(self, x):\n    orders = self.env['sale.order'].search([])\n    if x:\n        orders.ensure_one()\n    return orders.amount + 1\n"), Vec::<String>::new());
+    //unknown methods are not reported, nor the fields not used in arithmetic
+    assert_eq!(flagged("def action(self):\n    return self.amount * 2\n"), Vec::<String>::new());
+    assert_eq!(flagged("def _compute_total(self):\n    self.name = self.partner_id.name\n"), Vec::<String>::new());
+}
+
+#[test]
+fn test_cardinality_len_guards() {
+    //the block of `len(records) == 1` and the code after a raising `len(records) > 1` see a single record
+    assert_eq!(flagged("def _compute_total(self):\n    if len(self) == 1:\n        self.total = self.amount * 2\n"), Vec::<String>::new());
+    assert_eq!(flagged("def _compute_total(self):\n    if len(self) > 1:\n        raise UserError('One at a time')\n    self.total = self.amount * 2\n"), Vec::<String>::new());
+    assert_eq!(flagged("def _compute_total(self):\n    if len(self) != 1:\n        return\n    self.total = self.amount * 2\n"), Vec::<String>::new());
+    assert_eq!(flagged("def _compute_total(self):\n    if len(self) > 1:\n        pass\n    else:\n        self.total = self.amount * 2\n"), Vec::<String>::new());
+    assert_eq!(flagged("def _compute_total(self):\n    assert len(self) <= 1\n    self.total = self.amount * 2\n"), Vec::<String>::new());
+    //only the lengths compared to 1 are guards
+    assert_eq!(flagged("def _compute_total(self):\n    if len(self) == 2:\n        return\n    self.total = self.amount * 2\n"), vec!["self.amount"]);
+    //without raise nor return, the number of records is not known after the guard
+    let code = "def _compute_total(self):\n    if len(self) > 1:\n        _logger.info('several')\n    self.total = self.amount * 2\n";
+    let func_def = parse_function(code);
+    assert_eq!(CardinalityAnalyzer::cardinality_at(&func_def, code.find("self.total").unwrap() as u32, "self"), Cardinality::Unknown);
+}
+
+#[test]
+fn test_cardinality_at() {
+    let code = "def _compute_total(self):\n    orders = self.env['sale.order'].search([])\n    total = orders.amount\n    orders.ensure_one()\n    total = orders.amount\n    order = orders.ensure_one()\n";
+    let func_def = parse_function(code);
+    let at = |line: usize, name: &str| {
+        let offset = code.lines().take(line).map(|line| line.len() + 1).sum::<usize>() + 4;
+        CardinalityAnalyzer::cardinality_at(&func_def, offset as u32, name)
+    };
+    assert_eq!(at(1, "self"), Cardinality::Multi);
+    assert_eq!(at(2, "orders"), Cardinality::Multi);
+    assert_eq!(at(4, "orders"), Cardinality::Singleton);
+    assert_eq!(at(1, "order"), Cardinality::Unknown);
+    //ensure_one() returns the record
+    assert_eq!(at(6, "order"), Cardinality::Singleton);
+    //in the block of a guard
+    let code = "def action(self):\n    if len(self) == 1:\n        return self.name\n    return self.display_name\n";
+    let func_def = parse_function(code);
+    let offset = |text: &str| code.find(text).unwrap() as u32;
+    assert_eq!(CardinalityAnalyzer::cardinality_at(&func_def, offset("self.name"), "self"), Cardinality::Singleton);
+    assert_eq!(CardinalityAnalyzer::cardinality_at(&func_def, offset("self.display_name"), "self"), Cardinality::Unknown);
+}
+
+#[test]
+fn test_cardinality_at_guard_test() {
+    //the guard only applies in its block, not in its own test
+    let code = "def action(self):\n    if len(self) == 1:\n        return self.name\n";
+    let func_def = parse_function(code);
+    assert_eq!(CardinalityAnalyzer::cardinality_at(&func_def, code.find("self) ==").unwrap() as u32, "self"), Cardinality::Unknown);
+    assert_eq!(CardinalityAnalyzer::cardinality_at(&func_def, code.find("self.name").unwrap() as u32, "self"), Cardinality::Singleton);
+}
+
+#[test]
+fn test_cardinality_loop_break() {
+    //the body of the loop ends by a break, but the code after the loop runs after it, with orders being a singleton or not
+    let code = "def action(self):\n    orders = self.env['sale.order'].search([])\n    for line in self:\n        orders.ensure_one()\n        break\n    return orders.name\n";
+    let func_def = parse_function(code);
+    assert_eq!(CardinalityAnalyzer::cardinality_at(&func_def, code.find("orders.name").unwrap() as u32, "orders"), Cardinality::Unknown);
+    //a break in an if block of the loop still skips the rest of the loop body
+    let code = "def action(self):\n    for line in self:\n        orders = self.env['sale.order'].search([])\n        if len(orders) > 1:\n            break\n        return orders.name\n";
+    let func_def = parse_function(code);
+    assert_eq!(CardinalityAnalyzer::cardinality_at(&func_def, code.find("orders.name").unwrap() as u32, "orders"), Cardinality::Singleton);
+}
@@ -0,0 +1,31 @@
+use std::env;
+use std::path::Path;
+
+use serde_json::json;
+
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+use setup::lsp_client::LspTestClient;
+
+/* The diagnostics of a document opened through a symlinked directory are published on the uri of the client, and not on
+the canonical path the file is stored with */
+#[cfg(unix)]
+#[test]
+fn test_diagnostics_on_client_uri() {
+    let mut client = LspTestClient::start();
+    let link = env::temp_dir().join(format!("odoo_ls_client_uri_{}", std::process::id()));
+    let _ = std::fs::remove_file(&link);
+    std::os::unix::fs::symlink(Path::new(&client.addons_path).join("module_1"), &link).unwrap();
+    let uri = FileMgr::pathname2uri(&link.join("models").join("models.py").sanitize());
+    client.notify("textDocument/didOpen", json!({
+        "textDocument": {"uri": uri, "languageId": "python", "version": 2, "text": "def broken(:\n    pass\n"},
+    }));
+    let params = client.wait_for_notification("textDocument/publishDiagnostics", |params| params["uri"] == json!(uri.as_str()));
+    assert!(params["diagnostics"].as_array().is_some_and(|diagnostics| diagnostics.iter().any(|d| d["code"] == "OLS30001")));
+    let canonical_uri = FileMgr::pathname2uri(&Path::new(&client.addons_path).join("module_1").join("models").join("models.py").sanitize());
+    assert!(!client.notifications.iter().any(|n| n.params["uri"] == json!(canonical_uri.as_str()) && n.params["version"] == 2));
+    let _ = std::fs::remove_file(&link);
+    client.stop();
+}
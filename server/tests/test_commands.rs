@@ -0,0 +1,33 @@
+use lsp_server::{Request, RequestId};
+use odoo_ls_server::core::commands::ServerCommand;
+use serde_json::json;
+
+fn execute(command: &str, arguments: serde_json::Value) -> Result<Request, String> {
+    let request = Request {
+        id: RequestId::from(7),
+        method: String::from("workspace/executeCommand"),
+        params: json!({"command": command, "arguments": arguments}),
+    };
+    ServerCommand::to_request(request).map_err(|error| error.message)
+}
+
+#[test]
+fn test_execute_command() {
+    assert!(ServerCommand::names().contains(&String::from("odools.reloadModule")));
+
+    let request = execute("odools.reloadModule", json!([{"module": "sale"}])).unwrap();
+    assert_eq!(request.id, RequestId::from(7));
+    assert_eq!(request.method, "Odoo/reloadModule");
+    assert_eq!(request.params, json!({"module": "sale"}));
+
+    //without argument, the params are null or an empty object, depending on the request
+    assert_eq!(execute("odools.doctor", json!([])).unwrap().params, serde_json::Value::Null);
+    assert_eq!(execute("odools.exportModuleGraph", json!([])).unwrap().params, json!({}));
+
+    let request = execute("odools.scaffold", json!([{"name": "library_book"}])).unwrap();
+    assert_eq!(request.method, "Odoo/scaffold");
+    assert_eq!(request.params, json!({"name": "library_book"}));
+
+    assert_eq!(execute("odools.reloadModule", json!([])).unwrap_err(), "Invalid argument for command odools.reloadModule");
+    assert_eq!(execute("odools.unknown", json!([])).unwrap_err(), "Unknown command odools.unknown");
+}
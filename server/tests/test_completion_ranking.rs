@@ -0,0 +1,80 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{CompletionItem, CompletionResponse};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::completion::CompletionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+const MODELS: &str = r#"from odoo import fields, models
+
+
+class RankingMixin(models.AbstractModel):
+    _name = "ranking.mixin"
+
+    partner_note = fields.Char()
+
+    def part_of_mixin(self):
+        return True
+
+
+class RankingPartner(models.Model):
+    _name = "ranking.partner"
+    _inherit = ["ranking.mixin"]
+
+    partner_id = fields.Many2one("ranking.partner")
+    parent_name = fields.Char()
+
+    def parse(self):
+        return True
+
+    def action(self):
+        self.pa
+        self.pid
+"#;
+
+fn complete(session: &mut SessionInfo, path: &String, line: u32, character: u32) -> Vec<CompletionItem> {
+    let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(path)).expect("models.py is not loaded");
+    let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(path).expect("models.py has no file info");
+    let Some(CompletionResponse::List(list)) = CompletionFeature::autocomplete(session, &file_symbol, &file_info, line, character) else {
+        panic!("no completion at {}:{}", line, character);
+    };
+    //snippets are not ranked
+    let mut items: Vec<CompletionItem> = list.items.into_iter().filter(|item| item.sort_text.is_some()).collect();
+    items.sort_by_key(|item| item.sort_text.clone().unwrap_or_default());
+    items
+}
+
+fn labels(items: &Vec<CompletionItem>) -> Vec<&str> {
+    items.iter().map(|item| item.label.as_str()).collect()
+}
+
+/* Completion items are ranked by category (fields, other members, inherited members), then by match quality */
+#[test]
+fn test_completion_ranking() {
+    let root = env::temp_dir().join(format!("odoo_ls_completion_ranking_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "ranking_module", &[], &[("models.py", MODELS)]);
+
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let path = module_dir.join("models.py").sanitize();
+    let line = MODELS.lines().position(|line| line.trim() == "self.pa").unwrap() as u32;
+
+    let items = complete(&mut session, &path, line, 15);
+    let ours: Vec<&str> = labels(&items).into_iter().filter(|label| ["parent_name", "partner_id", "parse", "partner_note", "part_of_mixin"].contains(label)).collect();
+    assert_eq!(ours, vec!["parent_name", "partner_id", "parse", "part_of_mixin", "partner_note"]);
+    let partner_id = items.iter().find(|item| item.label == "partner_id").unwrap();
+    assert_eq!(partner_id.detail.as_deref(), Some("ranking_module"));
+
+    //"pid" is not a prefix, but matches the parts of partner_id and parent_... names
+    let items = complete(&mut session, &path, line + 1, 16);
+    let labels = labels(&items);
+    assert_eq!(labels.first(), Some(&"partner_id"));
+    assert!(!labels.contains(&"parse"));
+    let _ = fs::remove_dir_all(&root);
+}
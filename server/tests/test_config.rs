@@ -0,0 +1,106 @@
+use lsp_server::Message;
+use lsp_types::notification::{LogMessage, Notification};
+use lsp_types::{LogMessageParams, MessageType};
+use odoo_ls_server::core::config::{AddonsPolicy, Config, EventDelays};
+use odoo_ls_server::core::odoo::{Odoo, SyncOdoo};
+use odoo_ls_server::threads::SessionInfo;
+use serde_json::{json, Map, Value};
+
+#[test]
+fn test_event_delays() {
+    let mut config = Config::new();
+    config.auto_save_delay = 2000;
+    assert_eq!(config.event_delays(), EventDelays { refresh: 2000, reload: 4000 });
+    //the refresh delay is bounded, and the reload never happens before the refresh
+    config.auto_save_delay = 20000;
+    config.max_refresh_delay = 10000;
+    assert_eq!(config.event_delays(), EventDelays { refresh: 10000, reload: 10000 });
+    config.no_delay = true;
+    assert_eq!(config.event_delays(), EventDelays { refresh: 0, reload: 0 });
+}
+
+#[test]
+fn test_merge_client_settings() {
+    assert_eq!(Config::merge_client_settings(None, None), None);
+    let options = json!({"odooPath": "/opt/odoo", "addons": ["/opt/addons"], "spellCheck": true});
+    let settings = Config::merge_client_settings(Some(&options), None).unwrap();
+    assert_eq!(settings.get("odooPath"), Some(&json!("/opt/odoo")));
+    //the settings of workspace/configuration override the initialization options
+    let workspace = json!({"spellCheck": false, "selectedConfiguration": "1"});
+    let settings = Config::merge_client_settings(Some(&options), Some(&workspace)).unwrap();
+    assert_eq!(settings.get("spellCheck"), Some(&json!(false)));
+    assert_eq!(settings.get("addons"), Some(&json!(["/opt/addons"])));
+    assert_eq!(settings.get("selectedConfiguration"), Some(&json!("1")));
+    //a client without settings answers null to workspace/configuration
+    assert_eq!(Config::merge_client_settings(None, Some(&serde_json::Value::Null)), None);
+}
+
+#[test]
+fn test_configuration_schema() {
+    let schema = Config::schema();
+    let properties = schema.get("properties").and_then(|properties| properties.as_object()).unwrap();
+    for key in ["odooPath", "addons", "pythonPath", "diagMissingImportLevel", "autoRefresh", "largeFileThreshold"] {
+        assert!(properties.contains_key(key), "{} is not in the schema", key);
+    }
+    assert_eq!(properties["diagMissingImportLevel"]["enum"], json!(["all", "only_odoo", "none"]));
+}
+
+/* Default values of the properties of a schema, nested in the objects like autocompletion */
+fn schema_defaults(schema: &Value) -> Value {
+    let mut defaults = Map::new();
+    for (key, property) in schema["properties"].as_object().unwrap() {
+        if let Some(default) = property.get("default") {
+            defaults.insert(key.clone(), default.clone());
+        } else if property.get("properties").is_some() {
+            defaults.insert(key.clone(), schema_defaults(property));
+        }
+    }
+    Value::Object(defaults)
+}
+
+/* Giving the defaults of the schema gives the same configuration as giving nothing, and every setting of the schema is
+known by the parser */
+#[test]
+fn test_schema_defaults() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let client = r.clone();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let python_path = Some(String::from("python3"));
+    let defaults = schema_defaults(&Config::schema());
+    assert_eq!(defaults["autoRefresh"], json!("adaptive"));
+    let parsed_defaults = Odoo::parse_configuration(&mut session, &defaults, python_path.clone()).unwrap();
+    let errors: Vec<String> = client.try_iter().filter_map(|message| match message {
+        Message::Notification(notification) if notification.method == LogMessage::METHOD => serde_json::from_value::<LogMessageParams>(notification.params).ok(),
+        _ => None,
+    }).filter(|log| log.typ == MessageType::ERROR && !log.message.contains("selected configuration")).map(|log| log.message).collect();
+    assert!(errors.is_empty(), "the defaults of the schema are rejected: {:?}", errors);
+    let parsed_nothing = Odoo::parse_configuration(&mut session, &json!({}), python_path).unwrap();
+    assert_eq!(format!("{:?}", parsed_defaults), format!("{:?}", parsed_nothing));
+}
+
+#[test]
+fn test_addons_policy() {
+    let mut config = Config::new();
+    assert_eq!(config.addons_policy("/opt/oca/partner_firstname/models/res_partner.py"), AddonsPolicy::Full);
+    config.addons_policies.insert(String::from("/opt/oca"), AddonsPolicy::SuggestOnly);
+    config.addons_policies.insert(String::from("/opt/oca/vendored"), AddonsPolicy::Ignore);
+    assert_eq!(config.addons_policy("/opt/oca/partner_firstname/models/res_partner.py"), AddonsPolicy::SuggestOnly);
+    assert_eq!(config.addons_policy("/opt/oca/vendored/web_widget/__init__.py"), AddonsPolicy::Ignore);
+    //only whole directory names are matched
+    assert_eq!(config.addons_policy("/opt/oca_custom/my_module/__init__.py"), AddonsPolicy::Full);
+    assert_eq!("suggestOnly".parse::<AddonsPolicy>(), Ok(AddonsPolicy::SuggestOnly));
+    assert!("suggest-only".parse::<AddonsPolicy>().is_err());
+}
+
+#[test]
+fn test_external_override() {
+    let mut config = Config::new();
+    assert_eq!(config.external_override("/work/odoo/odoo/models.py"), None);
+    config.external_paths.insert(String::from("/work/odoo"), true);
+    config.external_paths.insert(String::from("/work/odoo/addons/my_patched_module"), false);
+    assert_eq!(config.external_override("/work/odoo/odoo/models.py"), Some(true));
+    assert_eq!(config.external_override("/work/odoo/addons/my_patched_module/models/sale.py"), Some(false));
+    //only whole directory names are matched
+    assert_eq!(config.external_override("/work/odoo_custom/my_module/__init__.py"), None);
+}
@@ -0,0 +1,47 @@
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+use lsp_server::Message;
+use odoo_ls_server::core::config::Config;
+use odoo_ls_server::crash_report::{CrashReport, LogBufferLayer};
+use odoo_ls_server::S;
+use tracing_subscriber::layer::SubscriberExt;
+
+/* The panic hook is global to the process: everything is checked in a single test */
+#[test]
+fn test_crash_report() {
+    let log_dir = env::temp_dir().join(format!("odoo_ls_crash_report_{}", std::process::id()));
+    fs::create_dir_all(&log_dir).unwrap();
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    CrashReport::install_panic_hook(sender, log_dir.to_string_lossy().to_string());
+    CrashReport::set_config(&Config::new());
+    let path = S!("/tmp/crash_module/models.py");
+    CrashReport::set_current_file("ARCH", Some(&path));
+    let subscriber = tracing_subscriber::registry().with(LogBufferLayer {});
+    tracing::subscriber::with_default(subscriber, || tracing::info!("building crash_module"));
+
+    //recoverable panics are returned to the caller, and nothing is sent to the client
+    assert_eq!(CrashReport::catch_recoverable(|| 42), Ok(42));
+    assert_eq!(CrashReport::catch_recoverable(|| -> i32 { panic!("recoverable failure") }), Err(S!("recoverable failure")));
+    assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+
+    //any other panic is reported with the context of the crash
+    let thread = std::thread::spawn(|| panic!("unexpected failure"));
+    assert!(thread.join().is_err());
+    let Ok(Message::Notification(notification)) = receiver.recv_timeout(Duration::from_secs(5)) else {
+        panic!("no crash notification sent");
+    };
+    assert_eq!(notification.method, "Odoo/displayCrashNotification");
+    let params = notification.params;
+    assert!(params["crashInfo"].as_str().unwrap().contains("unexpected failure"));
+    assert_eq!(params["configHash"].as_str().map(|hash| hash.len()), Some(16));
+    assert_eq!(params["currentFile"].as_str(), Some("ARCH - /tmp/crash_module/models.py"));
+    let logs: Vec<&str> = params["logs"].as_array().unwrap().iter().filter_map(|line| line.as_str()).collect();
+    assert!(logs.iter().any(|line| line.contains("building crash_module")));
+    let report = fs::read_to_string(params["crashReport"].as_str().unwrap()).unwrap();
+    assert!(report.contains("Current file: ARCH - /tmp/crash_module/models.py"));
+    assert!(report.contains("unexpected failure"));
+    let _ = std::panic::take_hook();
+    let _ = fs::remove_dir_all(&log_dir);
+}
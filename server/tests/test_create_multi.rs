@@ -0,0 +1,48 @@
+use odoo_ls_server::core::create_multi::{analyze, is_create_multi, vals_list_parameter};
+use ruff_python_ast::{Stmt, StmtFunctionDef};
+
+fn parse_function(code: &str) -> StmtFunctionDef {
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let Some(Stmt::FunctionDef(func_def)) = module.body.into_iter().next() else {
+        panic!("not a function: {}", code);
+    };
+    func_def
+}
+
+fn keys(code: &str) -> Vec<String> {
+    let func_def = parse_function(code);
+    analyze(&func_def).keys.iter().map(|key| key.value.to_string()).collect()
+}
+
+fn dict_uses(code: &str) -> Vec<String> {
+    let func_def = parse_function(code);
+    analyze(&func_def).dict_uses.iter().map(|range| code[range.start().to_usize()..range.end().to_usize()].to_string()).collect()
+}
+
+#[test]
+fn test_is_create_multi() {
+    let func_def = parse_function("@api.model_create_multi\ndef create(self, vals_list):\n    pass\n");
+    assert!(is_create_multi(&func_def));
+    assert_eq!(vals_list_parameter(&func_def), Some("vals_list"));
+    assert!(is_create_multi(&parse_function("@model_create_multi\ndef create(self, values):\n    pass\n")));
+    assert!(!is_create_multi(&parse_function("@api.model\ndef create(self, vals):\n    pass\n")));
+    assert_eq!(vals_list_parameter(&parse_function("def create(self):\n    pass\n")), None);
+}
+
+#[test]
+fn test_create_multi_keys() {
+    assert_eq!(keys("def create(self, vals_list):\n    for vals in vals_list:\n        vals['name'] = vals.get('code')\n"), vec!["name", "code"]);
+    assert_eq!(keys("def create(self, vals_list):\n    if 'partner_id' in vals_list[0]:\n        vals_list[0].setdefault('state', 'draft')\n"), vec!["partner_id", "state"]);
+    assert_eq!(keys("def create(self, vals_list):\n    names = [vals['name'] for vals in vals_list]\n"), vec!["name"]);
+    //popped keys may be extra values removed before the creation
+    assert_eq!(keys("def create(self, vals_list):\n    for vals in vals_list:\n        vals.pop('custom', None)\n"), Vec::<String>::new());
+    //other dictionaries are not checked
+    assert_eq!(keys("def create(self, vals_list):\n    defaults = {}\n    defaults['x'] = 1\n"), Vec::<String>::new());
+}
+
+#[test]
+fn test_create_multi_dict_uses() {
+    assert_eq!(dict_uses("def create(self, vals_list):\n    name = vals_list['name']\n"), vec!["vals_list['name']"]);
+    assert_eq!(dict_uses("def create(self, vals_list):\n    vals_list.update({'a': 1})\n"), vec!["vals_list.update({'a': 1})"]);
+    assert_eq!(dict_uses("def create(self, vals_list):\n    vals_list.append({})\n    first = vals_list[0]\n"), Vec::<String>::new());
+}
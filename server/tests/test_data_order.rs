@@ -0,0 +1,85 @@
+use odoo_ls_server::core::data_order::{find_forward_references, DataFileIds, ForwardReference};
+
+const SECURITY: &str = r#"<odoo>
+    <record id="group_manager" model="res.groups">
+        <field name="name">Manager</field>
+        <field name="implied_ids" eval="[(4, ref('base.group_user'))]"/>
+    </record>
+    <record id="rule_own_orders" model="ir.rule">
+        <field name="model_id" ref="model_my_order"/>
+        <field name="groups" eval="[(4, ref('group_manager'))]"/>
+    </record>
+</odoo>
+"#;
+
+const VIEWS: &str = r#"<odoo>
+    <record id="view_order_form" model="ir.ui.view">
+        <field name="model">my.order</field>
+        <field name="arch" type="xml">
+            <form>
+                <button name="%(my_module.action_confirm_wizard)d" type="action" groups="my_module.group_manager,-base.group_portal"/>
+                <!-- <field name="old" groups="group_removed"/> -->
+            </form>
+        </field>
+    </record>
+    <record id="action_orders" model="ir.actions.server">
+        <field name="code">action = env.ref('my_module.action_later')</field>
+    </record>
+</odoo>
+"#;
+
+const ACTIONS: &str = r#"<odoo>
+    <record id="action_confirm_wizard" model="ir.actions.act_window">
+        <field name="res_model">my.order.confirm</field>
+    </record>
+    <menuitem id="menu_orders" parent="sale.sale_menu_root" action="action_orders"/>
+</odoo>
+"#;
+
+#[test]
+fn test_parse_xml_ids() {
+    let security = DataFileIds::parse_xml("my_module", SECURITY);
+    assert_eq!(security.declared, vec!["group_manager", "rule_own_orders"]);
+    //the records of other modules are created by the dependencies
+    assert_eq!(security.referenced, vec!["model_my_order", "group_manager"]);
+    let views = DataFileIds::parse_xml("my_module", VIEWS);
+    assert_eq!(views.declared, vec!["view_order_form", "action_orders"]);
+    //env.ref() is only called when the server action runs, and the comments are ignored
+    assert_eq!(views.referenced, vec!["action_confirm_wizard", "group_manager"]);
+    let actions = DataFileIds::parse_xml("my_module", ACTIONS);
+    assert_eq!(actions.declared, vec!["action_confirm_wizard", "menu_orders"]);
+    assert_eq!(actions.referenced, vec!["action_orders"]);
+}
+
+#[test]
+fn test_parse_csv_ids() {
+    let access = DataFileIds::parse_csv("my_module", "id,name,model_id:id,group_id:id,perm_read\n\
+        access_order_manager,order manager,model_my_order,my_module.group_manager,1\n\
+        access_order_user,order user,model_my_order,base.group_user,1\n");
+    assert_eq!(access.declared, vec!["access_order_manager", "access_order_user"]);
+    assert_eq!(access.referenced, vec!["model_my_order", "group_manager"]);
+    //a quoted value with a comma doesn't shift the next columns
+    let access = DataFileIds::parse_csv("my_module", "id,name,model_id:id\n\"access_quoted\",\"order, manager\",model_my_quoted\n");
+    assert_eq!(access.declared, vec!["access_quoted"]);
+    assert_eq!(access.referenced, vec!["model_my_quoted"]);
+}
+
+#[test]
+fn test_forward_references() {
+    let security = DataFileIds::parse_xml("my_module", SECURITY);
+    let views = DataFileIds::parse_xml("my_module", VIEWS);
+    let actions = DataFileIds::parse_xml("my_module", ACTIONS);
+    //the views use the wizard action of actions.xml, that uses the server action of views.xml
+    assert_eq!(find_forward_references(&[security.clone(), views.clone(), actions.clone()]), vec![
+        ForwardReference { file: 1, xml_id: "action_confirm_wizard".to_string(), declared_in: 2 },
+    ]);
+    assert_eq!(find_forward_references(&[views.clone(), security.clone(), actions.clone()]), vec![
+        ForwardReference { file: 0, xml_id: "action_confirm_wizard".to_string(), declared_in: 2 },
+        ForwardReference { file: 0, xml_id: "group_manager".to_string(), declared_in: 1 },
+    ]);
+    assert_eq!(find_forward_references(&[security, actions.clone(), views]), vec![
+        ForwardReference { file: 1, xml_id: "action_orders".to_string(), declared_in: 2 },
+    ]);
+    //model_my_order is created by the module when its models are registered, not by a data file
+    assert!(find_forward_references(&[actions]).is_empty());
+}
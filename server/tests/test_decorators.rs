@@ -0,0 +1,95 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{Diagnostic, HoverContents, NumberOrString};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+const MODELS: &str = r#"from odoo import api, fields, models
+
+
+class DecoratedModel(models.Model):
+    _name = "decorated.model"
+
+    name = fields.Char()
+    partner_id = fields.Many2one("res.partner")
+    total = fields.Integer(compute="_compute_total")
+
+    @api.depends("name", "partner_id.name", "display_name", "unknown_depend")
+    def _compute_total(self):
+        pass
+
+    @api.constrains("name", "unknown_constraint", "partner_id.name")
+    def _check_name(self):
+        pass
+
+    @api.onchange("partner_id", "unknown_onchange")
+    def _onchange_partner_id(self):
+        pass
+"#;
+
+/* The fields given to @api.depends, @api.constrains and @api.onchange are checked on the model: the first part of the paths of
+@api.depends, and only plain fields for the others. The hover of the decorators explains them with these rules */
+#[test]
+fn test_field_decorators() {
+    let root = env::temp_dir().join(format!("odoo_ls_decorators_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "decorators_module", &["base"], &[("models.py", MODELS)]);
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let path = module_dir.join("models.py").sanitize();
+    let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&path)).expect("models.py is not loaded");
+    let file_mgr = session.sync_odoo.get_file_mgr();
+    let (_, file_info) = file_mgr.borrow_mut().update_file_info(&mut session, &path, None, None, false);
+
+    file_symbol.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file_symbol.clone());
+    validator.validate(&mut session);
+    let with_code = |code: &str| -> Vec<Diagnostic> {
+        validator.diagnostics.iter().filter(|d| d.code == Some(NumberOrString::String(S!(code)))).cloned().collect()
+    };
+    //ranges are still offsets in the file before being published
+    let literal = |diagnostic: &Diagnostic| &MODELS[diagnostic.range.start.line as usize..diagnostic.range.end.line as usize];
+    let unknown_fields = with_code("OLS30902");
+    let messages: Vec<&str> = unknown_fields.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(messages, vec![
+        "unknown_depend is not a field of model decorated.model",
+        "unknown_constraint is not a field of model decorated.model",
+        "unknown_onchange is not a field of model decorated.model",
+    ]);
+    assert_eq!(literal(&unknown_fields[0]), "\"unknown_depend\"");
+    let paths = with_code("OLS20902");
+    assert_eq!(paths.len(), 1, "unexpected paths: {:?}", paths);
+    assert_eq!(paths[0].message, "@odoo.api.constrains only supports fields of the model, the path partner_id.name is ignored");
+    assert_eq!(literal(&paths[0]), "\"partner_id.name\"");
+    assert!(with_code("OLS30901").is_empty() && with_code("OLS20901").is_empty());
+
+    let hover_text = |session: &mut SessionInfo, decorator: &str| {
+        let line = MODELS.lines().position(|line| line.contains(&format!("@api.{}(", decorator))).unwrap();
+        let character = MODELS.lines().nth(line).unwrap().find(decorator).unwrap() + 1;
+        let hover = HoverFeature::get_hover(session, &file_symbol, &file_info, line as u32, character as u32)
+            .unwrap_or_else(|| panic!("no hover on @api.{}", decorator));
+        match hover.contents {
+            HoverContents::Markup(markup) => markup.value,
+            _ => panic!("the hover is not in markdown"),
+        }
+    };
+    let depends = hover_text(&mut session, "depends");
+    assert!(depends.contains("Odoo decorator `@odoo.api.depends`"), "{}", depends);
+    assert!(depends.contains("its arguments are fields of the model, or paths starting by a field"));
+    for decorator in ["constrains", "onchange"] {
+        let text = hover_text(&mut session, decorator);
+        assert!(text.contains(&format!("Odoo decorator `@odoo.api.{}`", decorator)), "{}", text);
+        assert!(text.contains("its arguments are fields of the model, paths are not supported"));
+        assert!(text.contains("must be called with its arguments"));
+    }
+    let _ = fs::remove_dir_all(&root);
+}
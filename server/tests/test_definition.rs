@@ -0,0 +1,39 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::GotoDefinitionResponse;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::definition::DefinitionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+/* The definition of a literal is the class of the literal in the builtins stub. The position in the stub is the one of the
+class, not the one of the literal in the analyzed file, that can even be past the end of the stub */
+#[test]
+fn test_definition_in_stub() {
+    let root = env::temp_dir().join(format!("odoo_ls_definition_{}", std::process::id()));
+    let builtins_size = 300_000;
+    let content = format!("{}\nTIMEOUT = 5\n", "#".repeat(builtins_size));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "stub_definition", &["base"], &[("constants.py", content.as_str())]);
+
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+
+    let path = module_dir.join("constants.py").sanitize();
+    let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&path)).expect("constants.py is not loaded");
+    let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path).expect("constants.py has no file info");
+    let Some(GotoDefinitionResponse::Array(locations)) = DefinitionFeature::get_location(&mut session, &file_symbol, &file_info, 1, 10) else {
+        panic!("no definition for the literal");
+    };
+    let location = locations.iter().find(|location| location.uri.as_str().ends_with("builtins.pyi")).expect("the literal is not located in builtins.pyi");
+    let stub = fs::read_to_string(FileMgr::uri2pathname(location.uri.as_str())).unwrap();
+    assert!(stub.len() < builtins_size, "the analyzed file must be longer than the stub");
+    let line = stub.lines().nth(location.range.start.line as usize).unwrap();
+    assert!(line.starts_with("class int"), "{:?} is not the declaration of int", line);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,56 @@
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{NumberOrString, PublishDiagnosticsParams};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+/* Diagnostics published by the server, by uri */
+fn published_diagnostics(receiver: &crossbeam_channel::Receiver<Message>) -> Vec<PublishDiagnosticsParams> {
+    receiver.try_iter().filter_map(|message| match message {
+        Message::Notification(notification) if notification.method == PublishDiagnostics::METHOD => {
+            serde_json::from_value(notification.params).ok()
+        },
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn test_degraded_mode_diagnostic() {
+    let mut odoo = SyncOdoo::new();
+    odoo.get_file_mgr().borrow_mut().add_workspace_folder(S!(env!("CARGO_MANIFEST_DIR")));
+    let (s, r) = crossbeam_channel::unbounded();
+    let client = r.clone();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+
+    session.sync_odoo.degraded = Some(S!("The python interpreter 'python3' can't be run"));
+    SyncOdoo::publish_degraded_mode(&mut session);
+    let published = published_diagnostics(&client);
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].diagnostics.len(), 1);
+    assert_eq!(published[0].diagnostics[0].code, Some(NumberOrString::String(S!("OLS30004"))));
+    assert!(published[0].diagnostics[0].code_description.as_ref().is_some_and(|description| description.href.as_str().ends_with("#ols30004")));
+    let folder_uri = published[0].uri.clone();
+
+    /* Once the configuration is fixed, the diagnostic is cleared on the same folder */
+    session.sync_odoo.degraded = None;
+    SyncOdoo::publish_degraded_mode(&mut session);
+    let published = published_diagnostics(&client);
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].uri, folder_uri);
+    assert!(published[0].diagnostics.is_empty());
+
+    /* Nothing is left to clear afterward */
+    SyncOdoo::publish_degraded_mode(&mut session);
+    assert!(published_diagnostics(&client).is_empty());
+}
+
+#[test]
+fn test_degraded_mode_unresolvable() {
+    let mut odoo = SyncOdoo::new();
+    assert!(!odoo.is_unresolvable(&[S!("requests")]));
+    odoo.degraded = Some(S!("The stdlib stubs can't be found"));
+    assert!(odoo.is_unresolvable(&[S!("requests"), S!("Session")]));
+    assert!(!odoo.is_unresolvable(&[S!("odoo"), S!("fields")]));
+    assert!(!odoo.is_unresolvable(&[]));
+}
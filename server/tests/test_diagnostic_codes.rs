@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{Diagnostic, NumberOrString, Position, Range};
+use odoo_ls_server::core::diagnostic_codes::{add_code_description, parse_error_codes, DIAGNOSTIC_CODES};
+use regex::Regex;
+
+#[test]
+fn test_parse_error_codes() {
+    let doc = "# Errors\n\n## Warnings\n\n### OLS20001\n\"XXXX not found\"\n\nThe symbol can't be resolved.\n\n### OLS30102\n\"Asset X not found\" or \"No file matches the asset X\"\n\nThe asset is missing.\n";
+    let codes = parse_error_codes(doc);
+    assert_eq!(codes.len(), 2);
+    assert_eq!(codes[0].code, "OLS20001");
+    assert_eq!(codes[0].severity, "warning");
+    assert_eq!(codes[0].message, "XXXX not found");
+    assert_eq!(codes[0].description, "The symbol can't be resolved.");
+    assert!(codes[0].href.ends_with("error_code.md#ols20001"));
+    assert_eq!(codes[1].severity, "error");
+    //alternative messages keep their quotes
+    assert_eq!(codes[1].message, "\"Asset X not found\" or \"No file matches the asset X\"");
+}
+
+#[test]
+fn test_code_description() {
+    let mut diagnostic = Diagnostic::new(Range::new(Position::new(0, 0), Position::new(0, 0)), None,
+        Some(NumberOrString::String("OLS20204".to_string())), None, "message".to_string(), None, None);
+    add_code_description(&mut diagnostic);
+    assert!(diagnostic.code_description.is_some_and(|description| description.href.as_str().ends_with("#ols20204")));
+    let mut unknown = Diagnostic { code: Some(NumberOrString::String("OLS99999".to_string())), ..Default::default() };
+    add_code_description(&mut unknown);
+    assert!(unknown.code_description.is_none());
+}
+
+/* Every code emitted by the server is documented in error_code.md, so it has a link and is listed by Odoo/listDiagnosticCodes */
+#[test]
+fn test_all_codes_documented() {
+    let code_re = Regex::new(r#""(OLS\d{5})""#).unwrap();
+    let mut to_visit = vec![PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src")];
+    let mut missing = vec![];
+    while let Some(path) = to_visit.pop() {
+        if path.is_dir() {
+            to_visit.extend(fs::read_dir(&path).unwrap().filter_map(|entry| entry.ok()).map(|entry| entry.path()));
+            continue;
+        }
+        if path.extension().map_or(true, |ext| ext != "rs") {
+            continue;
+        }
+        let content = fs::read_to_string(&path).unwrap();
+        for code in code_re.captures_iter(&content) {
+            if !DIAGNOSTIC_CODES.iter().any(|info| info.code == code[1]) {
+                missing.push(format!("{} in {}", &code[1], path.display()));
+            }
+        }
+    }
+    assert!(missing.is_empty(), "Undocumented codes: {:?}", missing);
+}
@@ -0,0 +1,162 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{Documentation, ParameterLabel};
+use odoo_ls_server::core::docstring::{DocParam, DocRaise, DocReturn, Docstring};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::signature_help::SignatureHelpFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn param(name: &str, typ: Option<&str>, description: &str) -> DocParam {
+    DocParam { name: S!(name), typ: typ.map(|typ| S!(typ)), description: S!(description) }
+}
+
+#[test]
+fn test_sphinx_docstring() {
+    let doc = Docstring::parse("Compute the total of the lines.
+
+        :param lines: lines to sum,
+            without the cancelled ones
+        :type lines: recordset
+        :param int precision: number of digits
+        :return: the total
+        :rtype: float
+        :raises UserError: if a line has no price
+        ");
+    assert_eq!(doc.summary, "Compute the total of the lines.");
+    assert_eq!(doc.params, vec![
+        param("lines", Some("recordset"), "lines to sum, without the cancelled ones"),
+        param("precision", Some("int"), "number of digits"),
+    ]);
+    assert_eq!(doc.returns, Some(DocReturn { typ: Some(S!("float")), description: S!("the total") }));
+    assert_eq!(doc.raises, vec![DocRaise { exception: S!("UserError"), description: S!("if a line has no price") }]);
+    assert!(doc.has_sections());
+}
+
+#[test]
+fn test_google_docstring() {
+    let doc = Docstring::parse("Send the invoice by email.
+
+        Args:
+            template (str): xml id of the template
+            *partners: recipients, the customer
+                if none is given
+            force_send: send now
+
+        Returns:
+            bool: True if the mail was sent
+
+        Raises:
+            UserError: if the invoice is a draft
+            AccessError
+
+        The attachments are regenerated.
+        ");
+    assert_eq!(doc.params, vec![
+        param("template", Some("str"), "xml id of the template"),
+        param("partners", None, "recipients, the customer if none is given"),
+        param("force_send", None, "send now"),
+    ]);
+    assert_eq!(doc.returns, Some(DocReturn { typ: Some(S!("bool")), description: S!("True if the mail was sent") }));
+    assert_eq!(doc.raises, vec![
+        DocRaise { exception: S!("UserError"), description: S!("if the invoice is a draft") },
+        DocRaise { exception: S!("AccessError"), description: S!("") },
+    ]);
+    //the text after the sections is a part of the summary
+    assert_eq!(doc.summary, "Send the invoice by email.\n\nThe attachments are regenerated.");
+    assert_eq!(doc.get_param("template").and_then(|param| param.typ.clone()), Some(S!("str")));
+}
+
+#[test]
+fn test_free_text_docstring() {
+    let doc = Docstring::parse("Return the name.\n\n    Note: the name is translated.\n    ");
+    assert!(!doc.has_sections());
+    assert_eq!(doc.summary, "Return the name.\n\nNote: the name is translated.");
+}
+
+const SIGNATURES: &str = r#"from odoo import models
+
+
+def total(lines, precision=2, *others, **options):
+    """Compute the total.
+
+    :param lines: lines to sum
+    :param int precision: number of digits
+    """
+    return 0
+
+
+class Invoice(models.Model):
+    _name = "docstring.invoice"
+
+    def send(self, template, force_send=False):
+        """Send the invoice.
+
+        Args:
+            template (str): xml id of the template
+        """
+        return True
+
+    def action(self):
+        total(1, 3, 4)
+        total(1, options=2)
+        self.send("mail", force_send=True)
+"#;
+
+/* The signature help gives the parameters of the called function, documented by its docstring, and the one of the argument
+under the cursor, after self for a method */
+#[test]
+fn test_signature_help() {
+    let root = env::temp_dir().join(format!("odoo_ls_signature_help_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "docstring", &["base"], &[("models.py", SIGNATURES)]);
+
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let path = module_dir.join("models.py").sanitize();
+    let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&path)).expect("models.py is not loaded");
+    let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path).expect("models.py has no file info");
+    let position = |code: &str, after: &str| -> (u32, u32) {
+        let line = SIGNATURES.lines().position(|line| line.trim() == code).unwrap();
+        let character = SIGNATURES.lines().nth(line).unwrap().find(after).unwrap() + after.len();
+        (line as u32, character as u32)
+    };
+
+    let (line, character) = position("total(1, 3, 4)", "total(1, ");
+    let help = SignatureHelpFeature::get_signature_help(&mut session, &file_symbol, &file_info, line, character).expect("no signature help");
+    let signature = &help.signatures[0];
+    assert_eq!(signature.label, "total(lines, precision, *others, **options)");
+    assert_eq!(help.active_parameter, Some(1));
+    let parameters = signature.parameters.as_ref().unwrap();
+    assert_eq!(parameters[1].label, ParameterLabel::LabelOffsets([13, 22]));
+    match parameters[1].documentation.as_ref() {
+        Some(Documentation::MarkupContent(content)) => assert_eq!(content.value, "`int`: number of digits"),
+        other => panic!("precision is not documented: {:?}", other),
+    }
+    match signature.documentation.as_ref() {
+        Some(Documentation::MarkupContent(content)) => assert_eq!(content.value, "Compute the total."),
+        other => panic!("total is not documented: {:?}", other),
+    }
+    //the positional arguments past the parameters go to *others
+    let (line, character) = position("total(1, 3, 4)", "total(1, 3, ");
+    let help = SignatureHelpFeature::get_signature_help(&mut session, &file_symbol, &file_info, line, character).unwrap();
+    assert_eq!(help.active_parameter, Some(2));
+    let (line, character) = position("total(1, options=2)", "options=");
+    let help = SignatureHelpFeature::get_signature_help(&mut session, &file_symbol, &file_info, line, character).unwrap();
+    assert_eq!(help.active_parameter, Some(3));
+
+    //the first argument of a method is given to the parameter after self
+    let (line, character) = position("self.send(\"mail\", force_send=True)", "self.send(");
+    let help = SignatureHelpFeature::get_signature_help(&mut session, &file_symbol, &file_info, line, character).expect("no signature help on the method");
+    assert_eq!(help.signatures[0].label, "send(self, template, force_send)");
+    assert_eq!(help.active_parameter, Some(1));
+    let (line, character) = position("self.send(\"mail\", force_send=True)", "force_send=");
+    let help = SignatureHelpFeature::get_signature_help(&mut session, &file_symbol, &file_info, line, character).unwrap();
+    assert_eq!(help.active_parameter, Some(2));
+    let _ = fs::remove_dir_all(&root);
+}
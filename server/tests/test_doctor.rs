@@ -0,0 +1,33 @@
+use std::env;
+use std::fs;
+
+use odoo_ls_server::core::config::Config;
+use odoo_ls_server::core::doctor::{Doctor, DoctorStatus};
+
+#[test]
+fn test_doctor() {
+    let dir = env::temp_dir().join(format!("odoo_ls_doctor_{}", std::process::id()));
+    let addons = dir.join("addons");
+    fs::create_dir_all(addons.join("my_module")).unwrap();
+    fs::write(addons.join("my_module").join("__manifest__.py"), "{'name': 'My module'}").unwrap();
+    //the cache directory doesn't exist yet: it is not reported as existing, but as creatable
+    env::set_var("XDG_CACHE_HOME", dir.join("cache"));
+    let mut config = Config::new();
+    config.odoo_path = dir.join("odoo").to_string_lossy().to_string();
+    config.addons = vec![addons.to_string_lossy().to_string()];
+    let stdlib_dir = dir.join("stdlib").to_string_lossy().to_string();
+    let report = Doctor::run(&config, &stdlib_dir, &vec![]);
+    let check = |name: &str| report.checks.iter().find(|check| check.name == name).unwrap().clone();
+    assert!(report.has_errors());
+    assert_eq!(check("odoo path").status, DoctorStatus::Error);
+    assert_eq!(check("typeshed").status, DoctorStatus::Error);
+    assert_eq!(check("addons").status, DoctorStatus::Ok);
+    assert!(check("addons").message.ends_with("1 modules"));
+    let cache = check("cache");
+    assert_eq!(cache.status, DoctorStatus::Ok);
+    assert!(cache.message.contains("doesn't exist yet"), "{}", cache.message);
+    fs::create_dir_all(dir.join("cache").join("odoo_ls")).unwrap();
+    let report = Doctor::run(&config, &stdlib_dir, &vec![]);
+    assert!(report.checks.iter().any(|check| check.name == "cache" && check.message.ends_with("is writable")));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,33 @@
+use lsp_types::Color;
+use odoo_ls_server::features::document_color::DocumentColorFeature;
+
+#[test]
+fn test_hex_colors() {
+    let red = DocumentColorFeature::parse_hex_color("#FF0000").unwrap();
+    assert_eq!(red, Color { red: 1.0, green: 0.0, blue: 0.0, alpha: 1.0 });
+    assert_eq!(DocumentColorFeature::parse_hex_color("#f00"), Some(red));
+    assert_eq!(DocumentColorFeature::parse_hex_color("#00ff0080").map(|color| DocumentColorFeature::to_hex_color(&color)), Some(String::from("#00FF0080")));
+    assert_eq!(DocumentColorFeature::parse_hex_color("#12345"), None);
+    assert_eq!(DocumentColorFeature::parse_hex_color("#GGGGGG"), None);
+    assert_eq!(DocumentColorFeature::parse_hex_color("red"), None);
+    //the alpha is only written if the color is transparent
+    assert_eq!(DocumentColorFeature::to_hex_color(&red), "#FF0000");
+}
+
+#[test]
+fn test_xml_colors() {
+    let content = r##"<odoo>
+    <!-- <div data-color="#000000"/> -->
+    <record id="stage_new" model="project.task.type">
+        <field name="name">#FF0000 is not a color field</field>
+        <field name="color_hex"> #00FF00 </field>
+    </record>
+    <kanban highlight_color="#00f" color="not a color">
+        <div t-att-title="'background_color=&quot;#123456&quot;'"/>
+    </kanban>
+</odoo>"##;
+    let colors: Vec<&str> = DocumentColorFeature::find_xml_colors(content).iter()
+        .map(|(value, _)| &content[value.offset..value.offset + value.value.len()])
+        .collect();
+    assert_eq!(colors, vec!["#00FF00", "#00f"]);
+}
@@ -0,0 +1,75 @@
+use std::env;
+use std::fs;
+
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::document_link::DocumentLinkFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+const MANIFEST: &str = r#"{
+    'name': 'Document links',
+    'depends': ['base'],
+    'data': [
+        'security/security.xml',
+        'security/ir.model.access.csv',
+        'views/missing_views.xml',
+    ],
+    'demo': [
+        'demo/missing_demo.xml',
+    ],
+}
+"#;
+
+const SECURITY: &str = r#"<odoo>
+    <record id="group_link_manager" model="res.groups">
+        <field name="name">Link manager</field>
+    </record>
+</odoo>
+"#;
+
+const ACCESS: &str = "id,name,model_id:id,group_id:id,perm_read\n\
+access_link_manager,access.link.manager,model_res_partner,group_link_manager,1\n\
+access_link_user,\"access, with comma\",model_res_partner,base.group_user,1\n";
+
+/* The existing data files of the manifest are linked, the missing data and demo files are reported. The records referenced
+by the csv files are linked to the line creating them */
+#[test]
+fn test_document_links() {
+    let root = env::temp_dir().join(format!("odoo_ls_document_link_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "document_links", &[], &[
+        ("__manifest__.py", MANIFEST),
+        ("security/security.xml", SECURITY),
+        ("security/ir.model.access.csv", ACCESS),
+    ]);
+
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+
+    let manifest_path = module_dir.join("__manifest__.py").sanitize();
+    let links = DocumentLinkFeature::get_document_links(&mut session, &manifest_path).expect("no links for the manifest");
+    let targets: Vec<String> = links.iter().map(|link| link.target.as_ref().unwrap().as_str().to_string()).collect();
+    assert_eq!(targets.len(), 2, "{:?}", targets);
+    assert!(targets[0].ends_with("security/security.xml"));
+    assert!(targets[1].ends_with("security/ir.model.access.csv"));
+    //the link is on the path, without its quotes
+    assert_eq!((links[0].range.start.line, links[0].range.start.character), (4, 9));
+
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&manifest_path).expect("the manifest is not loaded");
+    let (_, warnings) = file_info.borrow().count_diagnostics(&session.sync_odoo.config);
+    assert_eq!(warnings, 2, "the missing data and demo files are not reported");
+
+    let csv_path = module_dir.join("security").join("ir.model.access.csv").sanitize();
+    let links = DocumentLinkFeature::get_document_links(&mut session, &csv_path).expect("no links for the csv file");
+    //model_res_partner is created by Odoo, and base.group_user by the base module
+    let own_links: Vec<_> = links.iter().filter(|link| link.tooltip.as_deref() == Some("document_links.group_link_manager")).collect();
+    assert_eq!(own_links.len(), 1, "{:?}", links);
+    let link = own_links[0];
+    let start = ACCESS.lines().nth(1).unwrap().find("group_link_manager").unwrap() as u32;
+    assert_eq!((link.range.start.line, link.range.start.character, link.range.end.character), (1, start, start + 18));
+    assert!(link.target.as_ref().unwrap().as_str().ends_with("security/security.xml#L2"));
+    assert!(links.iter().all(|link| link.tooltip.as_deref() != Some("document_links.model_res_partner")));
+    let _ = fs::remove_dir_all(&root);
+}
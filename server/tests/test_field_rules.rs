@@ -0,0 +1,24 @@
+use odoo_ls_server::core::field_rules::{FieldKwargs, FIELD_KWARG_RULES};
+
+/* Codes of the rules matching the keywords of a field declaration */
+fn matching_rules(declaration: &str) -> Vec<&'static str> {
+    let expr = ruff_python_parser::parse_expression(declaration).unwrap().into_syntax();
+    let call = expr.body.as_call_expr().expect("a field declaration must be a call");
+    let kwargs = FieldKwargs::from_keywords(&call.arguments.keywords);
+    FIELD_KWARG_RULES.iter().filter(|rule| (rule.matches)(&kwargs)).map(|rule| rule.code).collect()
+}
+
+#[test]
+fn test_field_kwarg_rules() {
+    assert_eq!(matching_rules("fields.Char(store=True)"), vec!["OLS20404"]);
+    assert_eq!(matching_rules("fields.Char(compute='_compute_name', store=True)"), Vec::<&str>::new());
+    assert_eq!(matching_rules("fields.Char(compute='_compute_name', store=True, search='_search_name')"), vec!["OLS20405"]);
+    assert_eq!(matching_rules("fields.Char(inverse='_inverse_name')"), vec!["OLS20406"]);
+    assert_eq!(matching_rules("fields.Char(related='partner_id.name', readonly=False)"), vec!["OLS20407"]);
+    assert_eq!(matching_rules("fields.Char(related='partner_id.name', readonly=False, store=True)"), Vec::<&str>::new());
+    assert_eq!(matching_rules("fields.Char(compute_sudo=True)"), vec!["OLS20408"]);
+    assert_eq!(matching_rules("fields.Char(compute='_compute_name', compute_sudo=True)"), Vec::<&str>::new());
+    assert_eq!(matching_rules("fields.Char(compute='_compute_name', copy=True)"), vec!["OLS20409"]);
+    //the keywords that are not boolean literals are only checked for their presence
+    assert_eq!(matching_rules("fields.Char(store=is_stored)"), Vec::<&str>::new());
+}
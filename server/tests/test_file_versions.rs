@@ -0,0 +1,81 @@
+use std::env;
+
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{Diagnostic, DiagnosticSeverity, Position, PublishDiagnosticsParams, Range, TextDocumentContentChangeEvent};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+fn full_change(text: &str) -> Vec<TextDocumentContentChangeEvent> {
+    vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: S!(text) }]
+}
+
+/* Diagnostics computed on a version of the document that has been edited since are never published */
+#[test]
+fn test_stale_diagnostics() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let client = r.clone();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_file_versions.py").sanitize();
+    let warning = Diagnostic {
+        range: Range::new(Position::new(0, 0), Position::new(1, 0)),
+        severity: Some(DiagnosticSeverity::WARNING),
+        message: S!("unused variable"),
+        ..Default::default()
+    };
+
+    let (_, file_info) = file_mgr.update_file_info(&mut session, &path, Some(&full_change("a = 1\n")), Some(1), false);
+    file_info.borrow_mut().replace_diagnostics(BuildSteps::ARCH_EVAL, vec![warning.clone()]);
+    assert_eq!(file_info.borrow().count_diagnostics(&session.sync_odoo.config), (0, 1));
+
+    //the document is edited before the rebuild: the diagnostics of the previous version are stale
+    file_mgr.update_file_info(&mut session, &path, Some(&full_change("a = 2\n")), Some(2), false);
+    assert!(file_info.borrow().step_diagnostics(BuildSteps::ARCH_EVAL).is_none());
+    assert_eq!(file_info.borrow().count_diagnostics(&session.sync_odoo.config), (0, 0));
+    //a slow validation of the first version finishing now is discarded
+    assert!(!file_info.borrow_mut().replace_diagnostics_for_version(BuildSteps::VALIDATION, vec![warning.clone()], 1));
+
+    file_info.borrow_mut().publish_diagnostics(&mut session);
+    let published: Vec<PublishDiagnosticsParams> = client.try_iter().filter_map(|message| match message {
+        Message::Notification(notification) if notification.method == PublishDiagnostics::METHOD => serde_json::from_value(notification.params).ok(),
+        _ => None,
+    }).collect();
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].version, Some(2));
+    assert!(published[0].diagnostics.is_empty(), "stale diagnostics are published: {:?}", published[0].diagnostics);
+
+    //the diagnostics of the current version are kept
+    assert!(file_info.borrow_mut().replace_diagnostics_for_version(BuildSteps::VALIDATION, vec![warning], 2));
+    assert_eq!(file_info.borrow().count_diagnostics(&session.sync_odoo.config), (0, 1));
+}
+
+/* The large flag follows the largeFileThreshold setting, even if the text of the file doesn't change */
+#[test]
+fn test_large_file_threshold() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    session.sync_odoo.config.large_file_threshold = 1;
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_large_file.py").sanitize();
+    let text = "VALUE = 1\n".repeat(200);
+
+    let (_, file_info) = file_mgr.update_file_info(&mut session, &path, Some(&full_change(&text)), Some(1), false);
+    assert!(file_info.borrow().large);
+    //the same text is not parsed again, the flag is only recomputed when the threshold changes
+    file_mgr.update_file_info(&mut session, &path, Some(&full_change(&text)), Some(2), false);
+    assert!(file_info.borrow().large);
+    assert_eq!(file_mgr.update_large_files(1), Vec::<String>::new());
+    assert_eq!(file_mgr.update_large_files(4), vec![path.clone()]);
+    assert!(!file_info.borrow().large);
+    //0 disables the threshold
+    assert_eq!(file_mgr.update_large_files(0), Vec::<String>::new());
+    assert_eq!(file_mgr.update_large_files(1), vec![path]);
+    assert!(file_info.borrow().large);
+}
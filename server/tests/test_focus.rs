@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use odoo_ls_server::core::focus::Focus;
+
+fn depends() -> HashMap<String, Vec<String>> {
+    HashMap::from([
+        (String::from("base"), vec![]),
+        (String::from("mail"), vec![String::from("base")]),
+        (String::from("sale"), vec![String::from("mail")]),
+        (String::from("stock"), vec![String::from("base")]),
+    ])
+}
+
+#[test]
+fn test_focus() {
+    let mut focus = Focus::default();
+    //without focus, everything is validated
+    assert!(!focus.is_active());
+    assert!(focus.contains("stock", depends));
+
+    //the focused modules and their dependencies are validated
+    focus.toggle(&[String::from("sale")]);
+    assert!(focus.contains("sale", depends));
+    assert!(focus.contains("base", depends));
+    assert!(!focus.contains("stock", depends));
+
+    focus.toggle(&[String::from("stock"), String::from("sale")]);
+    assert_eq!(focus.modules(), &vec![String::from("stock")]);
+    assert!(!focus.contains("mail", depends));
+
+    //no module clears the focus
+    focus.toggle(&[]);
+    assert!(!focus.is_active());
+    assert!(focus.contains("mail", depends));
+}
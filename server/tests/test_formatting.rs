@@ -0,0 +1,85 @@
+use std::env;
+
+use lsp_types::{DocumentOnTypeFormattingParams, DocumentRangeFormattingParams, FormattingOptions, Position, Range, TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentPositionParams, TextEdit};
+use odoo_ls_server::core::config::Config;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::formatting::FormattingFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+const CODE: &str = r#"from odoo import fields, models
+
+
+class Partner(models.Model):
+    _name = "formatting.partner"
+
+    name = fields.Char(string="Nom 😀")
+    partner_id = fields.Many2one("res.partner")
+"#;
+
+/* The field declarations are aligned on their '=', and the replaced ranges end at the UTF-16 length of the lines */
+#[test]
+fn test_align_field_declarations() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_formatting.py").sanitize();
+    let change = vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: S!(CODE) }];
+    let (_, file_info) = file_mgr.update_file_info(&mut session, &path, Some(&change), Some(1), false);
+    let params = DocumentRangeFormattingParams {
+        text_document: TextDocumentIdentifier { uri: FileMgr::pathname2uri(&path) },
+        range: Range::new(Position::new(4, 0), Position::new(7, 0)),
+        options: FormattingOptions { tab_size: 4, insert_spaces: true, ..Default::default() },
+        work_done_progress_params: Default::default(),
+    };
+    let edits = FormattingFeature::range_formatting(&file_info, &params).unwrap();
+    //_name is separated from the fields by an empty line, and partner_id is already aligned
+    assert_eq!(edits.len(), 1);
+    let line = "    name = fields.Char(string=\"Nom 😀\")";
+    assert_eq!(edits[0].range, Range::new(Position::new(6, 0), Position::new(6, line.encode_utf16().count() as u32)));
+    assert_eq!(edits[0].range.end.character, line.chars().count() as u32 + 1);
+    assert_eq!(edits[0].new_text, "    name       = fields.Char(string=\"Nom 😀\")");
+}
+
+const ON_TYPE_CODE: &str = "class Partner(models.Model):
+
+    def action(self):
+  
+    name = fields.Char(
+        string=\"(Name\",
+            )
+";
+
+/* Typing ':' or a new line after a header indents the next line, and a closing parenthesis is aligned with its opening line */
+#[test]
+fn test_on_type_formatting() {
+    assert!(!Config::new().on_type_formatting);
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_on_type_formatting.py").sanitize();
+    let change = vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: S!(ON_TYPE_CODE) }];
+    let (_, file_info) = file_mgr.update_file_info(&mut session, &path, Some(&change), Some(1), false);
+    let on_type = |line: u32, ch: &str| {
+        let params = DocumentOnTypeFormattingParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: FileMgr::pathname2uri(&path) },
+                position: Position::new(line, 0),
+            },
+            ch: S!(ch),
+            options: FormattingOptions { tab_size: 4, insert_spaces: true, ..Default::default() },
+        };
+        FormattingFeature::on_type_formatting(&file_info, &params)
+    };
+    let edit = |line: u32, end: u32, new_text: &str| Some(vec![TextEdit { range: Range::new(Position::new(line, 0), Position::new(line, end)), new_text: S!(new_text) }]);
+    assert_eq!(on_type(0, ":"), edit(1, 0, "    "));
+    assert_eq!(on_type(3, "\n"), edit(3, 2, "        "));
+    //the parenthesis in the string is ignored
+    assert_eq!(on_type(6, ")"), edit(6, 12, "    "));
+    assert_eq!(on_type(5, ":"), None);
+    assert_eq!(on_type(4, ")"), None);
+}
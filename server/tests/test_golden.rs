@@ -0,0 +1,39 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::json;
+
+mod setup;
+use setup::lsp_client::{matches, replay, LspTestClient};
+
+/* Replay the recorded sessions of tests/data/golden against a server started on the test addons.
+Run with ODOOLS_UPDATE_GOLDEN=1 to record the results of a new session */
+#[test]
+fn test_golden_sessions() {
+    let golden_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("golden");
+    let mut golden_files: Vec<PathBuf> = fs::read_dir(&golden_dir).unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    golden_files.sort();
+    assert!(!golden_files.is_empty());
+    let mut client = LspTestClient::start();
+    for golden_file in golden_files.iter() {
+        replay(&mut client, golden_file);
+    }
+    client.stop();
+}
+
+#[test]
+fn test_golden_matches() {
+    let actual = json!({"contents": {"kind": "markdown", "value": "```python\ntest_int: int\n```"}, "range": null});
+    assert!(matches(&json!({"contents": {"kind": "markdown"}}), &actual));
+    assert!(matches(&json!({"contents": {"value": {"$contains": "test_int"}}}), &actual));
+    assert!(!matches(&json!({"contents": {"value": {"$contains": "test_char"}}}), &actual));
+    assert!(!matches(&json!({"contents": {"kind": "plaintext"}}), &actual));
+    let items = json!([{"label": "test_int", "kind": 5}, {"label": "get_test_int", "kind": 2}]);
+    assert!(matches(&json!([{"label": "get_test_int"}]), &items));
+    assert!(!matches(&json!([{"label": "test_char"}]), &items));
+    assert!(!matches(&json!([]), &items));
+    assert!(matches(&json!([]), &json!([])));
+}
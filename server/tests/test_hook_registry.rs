@@ -0,0 +1,14 @@
+use odoo_ls_server::core::hook_registry::HookRegistry;
+use odoo_ls_server::S;
+
+/* The "odoo" set is always loaded, and contains the hooks of the arch step, matched on the full tree of the symbol */
+#[test]
+fn test_arch_builder_hooks() {
+    let registry = HookRegistry::new();
+    assert_eq!(registry.get_class_hooks(&(vec![S!("odoo"), S!("models")], vec![S!("BaseModel")])).len(), 1);
+    assert_eq!(registry.get_class_hooks(&(vec![S!("odoo"), S!("fields")], vec![S!("Many2one")])).len(), 1);
+    assert!(registry.get_class_hooks(&(vec![S!("my_module"), S!("models")], vec![S!("BaseModel")])).is_empty());
+    assert!(registry.get_class_hooks(&(vec![S!("odoo"), S!("fields")], vec![S!("Field")])).is_empty());
+    assert_eq!(registry.get_arch_file_hooks(&vec![S!("odoo"), S!("release")]).len(), 1);
+    assert!(registry.get_arch_file_hooks(&vec![S!("my_module"), S!("release")]).is_empty());
+}
@@ -0,0 +1,264 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use odoo_ls_server::constants::SymType;
+use odoo_ls_server::core::import_resolver::resolve_import_stmt;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+use ruff_python_ast::{Alias, Identifier, Stmt};
+use ruff_text_size::TextRange;
+
+mod setup;
+
+/* Property tests of the import resolver: randomized package layouts are generated in a temporary addons path, then
+every generated python file, package and re-exported name is imported and the results are checked against invariants.
+A failing seed can be replayed by setting RESOLVER_SEEDS to a comma separated list of seeds. */
+
+const DEFAULT_SEEDS: &[u64] = &[1, 7, 42, 1337, 2024, 65537, 99991, 123456789];
+const MAX_DEPTH: u32 = 3;
+const MAX_CHILDREN: u64 = 4;
+
+/* Small xorshift generator, so a seed always gives the same layout on every platform */
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % max
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EntryKind {
+    Package,    //directory with an __init__.py
+    Namespace,  //directory without __init__.py
+    Module,     //name.py
+    Stub,       //name.pyi only
+    StubbedModule, //name.py and name.pyi: the .py file is used
+    Symlink,    //symlink to a package generated outside of the addons path
+}
+
+/* An entry generated in the layout, that must be importable */
+#[derive(Debug)]
+struct Expected {
+    tree: Vec<String>, //from the root of the module
+    kind: EntryKind,
+    class_name: Option<String>,
+    path: PathBuf,
+}
+
+struct Layout {
+    module_name: String,
+    entries: Vec<Expected>,
+    reexports: Vec<(Vec<String>, String)>, //package and the name its __init__ imports from a child
+    counter: u32,
+}
+
+impl Layout {
+
+    fn generate(seed: u64, addons_dir: &Path, targets_dir: &Path) -> Layout {
+        let mut rng = Rng(seed);
+        let mut layout = Layout { module_name: format!("resolver_fuzz_{}", seed), entries: vec![], reexports: vec![], counter: 0 };
+        let module_dir = setup::setup::write_module(addons_dir, &layout.module_name, &["base"], &[]);
+        let init = layout.fill_dir(&mut rng, &module_dir, targets_dir, &vec![], 0, true);
+        fs::write(module_dir.join("__init__.py"), init).unwrap();
+        layout
+    }
+
+    /* Fill a directory with random children and return the content of its __init__.py */
+    fn fill_dir(&mut self, rng: &mut Rng, dir: &Path, targets_dir: &Path, tree: &Vec<String>, depth: u32, can_reexport: bool) -> String {
+        let mut init = String::new();
+        for _ in 0..(1 + rng.below(MAX_CHILDREN)) {
+            self.counter += 1;
+            let name = format!("e{}", self.counter);
+            let class_name = format!("Class{}", self.counter);
+            let mut child_tree = tree.clone();
+            child_tree.push(name.clone());
+            let kind = match rng.below(if depth < MAX_DEPTH { 6 } else { 3 }) {
+                0 => EntryKind::Module,
+                1 => EntryKind::Stub,
+                2 => EntryKind::StubbedModule,
+                3 => EntryKind::Package,
+                4 => EntryKind::Namespace,
+                _ => if cfg!(unix) { EntryKind::Symlink } else { EntryKind::Package },
+            };
+            let path = match kind {
+                EntryKind::Module => {
+                    let path = dir.join(format!("{}.py", name));
+                    fs::write(&path, format!("class {}:\n    pass\n", class_name)).unwrap();
+                    path
+                },
+                EntryKind::Stub => {
+                    let path = dir.join(format!("{}.pyi", name));
+                    fs::write(&path, format!("class {}: ...\n", class_name)).unwrap();
+                    path
+                },
+                EntryKind::StubbedModule => {
+                    fs::write(dir.join(format!("{}.pyi", name)), format!("class {}: ...\n", class_name)).unwrap();
+                    let path = dir.join(format!("{}.py", name));
+                    fs::write(&path, format!("class {}:\n    pass\n", class_name)).unwrap();
+                    path
+                },
+                EntryKind::Package | EntryKind::Symlink => {
+                    let path = dir.join(&name);
+                    let real_dir = if kind == EntryKind::Symlink { targets_dir.join(format!("{}_target", name)) } else { path.clone() };
+                    fs::create_dir_all(&real_dir).unwrap();
+                    let mut child_init = format!("class {}:\n    pass\n", class_name);
+                    child_init += &self.fill_dir(rng, &real_dir, targets_dir, &child_tree, depth + 1, true);
+                    fs::write(real_dir.join("__init__.py"), child_init).unwrap();
+                    #[cfg(unix)]
+                    if kind == EntryKind::Symlink {
+                        std::os::unix::fs::symlink(&real_dir, &path).unwrap();
+                    }
+                    path
+                },
+                EntryKind::Namespace => {
+                    let path = dir.join(&name);
+                    fs::create_dir_all(&path).unwrap();
+                    //a namespace package has no __init__.py to re-export its children
+                    self.fill_dir(rng, &path, targets_dir, &child_tree, depth + 1, false);
+                    path
+                },
+            };
+            let has_class = kind != EntryKind::Namespace;
+            if can_reexport && has_class && kind != EntryKind::Package && kind != EntryKind::Symlink && rng.below(3) == 0 {
+                init += &format!("from .{} import {}\n", name, class_name);
+                self.reexports.push((tree.clone(), class_name.clone()));
+            }
+            self.entries.push(Expected {
+                tree: child_tree,
+                kind,
+                class_name: if has_class { Some(class_name) } else { None },
+                path,
+            });
+        }
+        init
+    }
+}
+
+fn alias(name: &str) -> Alias {
+    Alias {
+        range: TextRange::default(),
+        name: Identifier::new(name.to_string(), TextRange::default()),
+        asname: None,
+    }
+}
+
+/* Resolve "from <from_stmt> import <name>" from the given file, with the given level */
+fn import_from(session: &mut SessionInfo, source: &Rc<RefCell<Symbol>>, from_stmt: &str, name: &str, level: u32) -> (bool, Rc<RefCell<Symbol>>) {
+    let from_stmt = if from_stmt.is_empty() { None } else { Some(Identifier::new(from_stmt.to_string(), TextRange::default())) };
+    let mut results = resolve_import_stmt(session, source, from_stmt.as_ref(), &[alias(name)], Some(level), &mut None);
+    assert_eq!(results.len(), 1, "one result is expected by alias");
+    let result = results.pop().unwrap();
+    (result.found, result.symbol)
+}
+
+fn get_module(session: &mut SessionInfo, name: &str) -> Rc<RefCell<Symbol>> {
+    let odoo_addons = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons")], vec![]), u32::MAX);
+    let odoo_addons = odoo_addons.first().expect("odoo.addons is not loaded").clone();
+    let (found, module) = import_from(session, &odoo_addons, "odoo.addons", name, 0);
+    assert!(found, "module {} not found", name);
+    module
+}
+
+fn check_layout(session: &mut SessionInfo, layout: &Layout) {
+    let module = get_module(session, &layout.module_name);
+    let base = format!("odoo.addons.{}", layout.module_name);
+    for entry in layout.entries.iter() {
+        let parent = entry.tree[..entry.tree.len() - 1].join(".");
+        let name = entry.tree.last().unwrap();
+        let from_stmt = if parent.is_empty() { base.clone() } else { format!("{}.{}", base, parent) };
+        let context = format!("{:?} {} (module {})", entry.kind, entry.tree.join("."), layout.module_name);
+
+        //the entry is found, and resolving it again gives the same symbol
+        let (found, symbol) = import_from(session, &module, &from_stmt, name, 0);
+        assert!(found, "{} not found", context);
+        let (found_again, symbol_again) = import_from(session, &module, &from_stmt, name, 0);
+        assert!(found_again && Rc::ptr_eq(&symbol, &symbol_again), "{} is not resolved to the same symbol twice", context);
+
+        //an absolute and a relative import give the same symbol
+        let (found_relative, symbol_relative) = import_from(session, &module, &parent, name, 1);
+        assert!(found_relative && Rc::ptr_eq(&symbol, &symbol_relative), "{} is resolved differently with a relative import", context);
+
+        //the symbol is of the right kind, and points to the right file
+        let expected_type = match entry.kind {
+            EntryKind::Namespace => SymType::NAMESPACE,
+            EntryKind::Package | EntryKind::Symlink => SymType::PACKAGE,
+            _ => SymType::FILE,
+        };
+        assert_eq!(symbol.borrow().typ(), expected_type, "{} has a wrong type", context);
+        if expected_type != SymType::NAMESPACE {
+            assert!(symbol.borrow().paths().contains(&entry.path.sanitize_canonical()), "{} points to {:?} instead of {:?}", context, symbol.borrow().paths(), entry.path);
+        }
+
+        //the content of the entry is importable
+        if let Some(class_name) = entry.class_name.as_ref() {
+            let (found, class) = import_from(session, &module, &format!("{}.{}", from_stmt, name), class_name, 0);
+            assert!(found, "{}.{} not found", context, class_name);
+            assert_eq!(class.borrow().typ(), SymType::CLASS, "{}.{} is not a class", context, class_name);
+        }
+
+        //a missing name is never found
+        let (found_missing, _) = import_from(session, &module, &from_stmt, &format!("{}_missing", name), 0);
+        assert!(!found_missing, "{}_missing should not be found", context);
+    }
+    for (package, class_name) in layout.reexports.iter() {
+        let from_stmt = if package.is_empty() { base.clone() } else { format!("{}.{}", base, package.join(".")) };
+        let (found, class) = import_from(session, &module, &from_stmt, class_name, 0);
+        assert!(found, "{} re-exported by {} not found", class_name, from_stmt);
+        assert_eq!(class.borrow().typ(), SymType::CLASS, "{} re-exported by {} is not a class", class_name, from_stmt);
+    }
+}
+
+fn check_corpus(session: &mut SessionInfo) {
+    let module_1 = get_module(session, "module_1");
+    let corpus = fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("import_corpus.txt")).unwrap();
+    for line in corpus.lines().filter(|line| !line.trim().is_empty() && !line.starts_with('#')) {
+        let expect_found = !line.ends_with("# not found");
+        let parsed = ruff_python_parser::parse_module(line).unwrap_or_else(|_| panic!("invalid statement in corpus: {}", line));
+        for stmt in parsed.into_syntax().body.iter() {
+            let results = match stmt {
+                Stmt::Import(import) => resolve_import_stmt(session, &module_1, None, &import.names, None, &mut None),
+                Stmt::ImportFrom(import_from) => resolve_import_stmt(session, &module_1, import_from.module.as_ref(), &import_from.names, Some(import_from.level), &mut None),
+                _ => panic!("only imports are expected in corpus: {}", line),
+            };
+            for result in results.iter() {
+                assert_eq!(result.found, expect_found, "{}: {} resolution is wrong", line, result.name);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_import_resolver() {
+    let seeds: Vec<u64> = match env::var("RESOLVER_SEEDS") {
+        Ok(seeds) => seeds.split(',').map(|seed| seed.trim().parse().expect("invalid seed")).collect(),
+        Err(_) => DEFAULT_SEEDS.to_vec(),
+    };
+    let root = env::temp_dir().join(format!("odoo_ls_resolver_{}", std::process::id()));
+    let addons_dir = root.join("addons");
+    let targets_dir = root.join("targets");
+    let layouts: Vec<Layout> = seeds.iter().map(|seed| Layout::generate(*seed, &addons_dir, &targets_dir)).collect();
+
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![addons_dir.sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+
+    for layout in layouts.iter() {
+        check_layout(&mut session, layout);
+    }
+    check_corpus(&mut session);
+    let _ = fs::remove_dir_all(&root);
+}
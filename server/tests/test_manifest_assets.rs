@@ -0,0 +1,93 @@
+use std::env;
+use std::fs;
+
+use lsp_types::GotoDefinitionResponse;
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::manifest_assets::AssetPath;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::definition::DefinitionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+const MANIFEST: &str = r#"{
+    'name': 'Assets',
+    'depends': [],
+    'assets': {
+        'web.assets_backend': [
+            'assets_module/static/src/js/*.js',
+            'assets_module/static/src/scss/style.scss',
+            'assets_module/static/src/js/missing.js',
+            ('remove', 'assets_module/static/src/js/old.js'),
+            'web/static/src/legacy.js',
+        ],
+        'web.assets_frontend': [
+            ('include', 'web._assets_helpers'),
+            'assets_module/static/src/css/*.css',
+        ],
+    },
+}
+"#;
+
+fn parse_entry(code: &str) -> Vec<(String, bool)> {
+    let expr = ruff_python_parser::parse_expression(code).unwrap().into_syntax().body;
+    AssetPath::from_entry(&expr).into_iter().map(|asset| (asset.path, asset.removed)).collect()
+}
+
+#[test]
+fn test_asset_paths() {
+    assert_eq!(parse_entry("'sale/static/src/app.js'"), vec![(S!("sale/static/src/app.js"), false)]);
+    assert_eq!(parse_entry("('remove', 'sale/static/src/app.js')"), vec![(S!("sale/static/src/app.js"), true)]);
+    assert_eq!(parse_entry("('after', 'web/static/src/a.js', 'sale/static/src/b.js')"), vec![(S!("sale/static/src/b.js"), false)]);
+    assert_eq!(parse_entry("('replace', 'web/static/src/a.js', 'sale/static/src/b.js')"), vec![(S!("web/static/src/a.js"), true), (S!("sale/static/src/b.js"), false)]);
+    assert!(parse_entry("('include', 'web._assets_helpers')").is_empty());
+
+    let asset = AssetPath::from_entry(&ruff_python_parser::parse_expression("'/sale/static/src/**/*.js'").unwrap().into_syntax().body).remove(0);
+    assert!(asset.is_glob() && !asset.is_url());
+    assert_eq!(asset.module_name(), Some("sale"));
+    assert!(asset.matches("sale/static/src/views/list.js"));
+    assert!(!asset.matches("sale/static/src/views/list.xml"));
+}
+
+/* Files of the module referenced by the bundles must exist, and the files of static/src should be in a bundle */
+#[test]
+fn test_manifest_assets() {
+    let root = env::temp_dir().join(format!("odoo_ls_manifest_assets_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "assets_module", &[], &[
+        ("__manifest__.py", MANIFEST),
+        ("static/src/js/app.js", ""),
+        ("static/src/js/widget.js", ""),
+        ("static/src/scss/style.scss", ""),
+        ("static/src/xml/unused.xml", ""),
+    ]);
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+
+    let module = session.sync_odoo.modules.get("assets_module").and_then(|module| module.upgrade()).expect("assets_module is not loaded");
+    let bundle_sizes = |bundle: &str| module.borrow().as_module_package().assets.get(bundle).map(|paths| paths.len());
+    assert_eq!(bundle_sizes("web.assets_backend"), Some(5));
+    assert_eq!(bundle_sizes("web.assets_frontend"), Some(1));
+
+    let manifest_path = module_dir.join("__manifest__.py").sanitize();
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&manifest_path).expect("the manifest is not loaded");
+    let messages: Vec<String> = file_info.borrow().step_diagnostics(BuildSteps::SYNTAX).cloned().unwrap_or_default().iter()
+        .map(|diagnostic| diagnostic.message.clone()).collect();
+    assert_eq!(messages, vec![
+        S!("Asset assets_module/static/src/js/missing.js not found"),
+        S!("No file matches the asset assets_module/static/src/css/*.css"),
+        S!("1 file(s) of static/src are not referenced by any bundle: assets_module/static/src/xml/unused.xml"),
+    ]);
+
+    //a glob leads to all the files it matches
+    let Some(GotoDefinitionResponse::Array(locations)) = DefinitionFeature::get_manifest_location(&mut session, &file_info, 5, 20) else {
+        panic!("no definition for the glob");
+    };
+    let mut files: Vec<String> = locations.iter().map(|location| location.uri.as_str().rsplit('/').next().unwrap().to_string()).collect();
+    files.sort();
+    assert_eq!(files, vec![S!("app.js"), S!("widget.js")]);
+    assert!(DefinitionFeature::get_manifest_location(&mut session, &file_info, 7, 20).is_none());
+    let _ = fs::remove_dir_all(&root);
+}
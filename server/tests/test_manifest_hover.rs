@@ -0,0 +1,62 @@
+use std::env;
+use std::fs;
+
+use lsp_types::{GotoDefinitionResponse, HoverContents};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::definition::DefinitionFeature;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+const BASE_MANIFEST: &str = r#"{
+    'name': 'Hover base',
+    'summary': 'Base module of the hover tests',
+    'version': '1.2.0',
+    'depends': ['base'],
+}
+"#;
+
+const USER_MANIFEST: &str = r#"{
+    'name': 'Hover user',
+    'depends': ['hover_base', 'missing_module'],
+}
+"#;
+
+/* The entries of the depends key of a manifest show the summary of the module on hover, and lead to its manifest */
+#[test]
+fn test_manifest_depends_hover() {
+    let root = env::temp_dir().join(format!("odoo_ls_manifest_hover_{}", std::process::id()));
+    for (module, manifest) in [("hover_base", BASE_MANIFEST), ("hover_user", USER_MANIFEST)] {
+        setup::setup::write_module(&root.join("addons"), module, &[], &[("__manifest__.py", manifest)]);
+    }
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let manifest_path = root.join("addons").join("hover_user").join("__manifest__.py").sanitize();
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&manifest_path).expect("the manifest is not loaded");
+    let hover_text = |session: &mut SessionInfo, line: u32, character: u32| {
+        HoverFeature::get_manifest_hover(session, &file_info, line, character).map(|hover| match hover.contents {
+            HoverContents::Markup(markup) => markup.value,
+            _ => panic!("the hover is not in markdown"),
+        })
+    };
+
+    let text = hover_text(&mut session, 2, 18).expect("no hover on hover_base");
+    assert!(text.contains("(module) hover_base"));
+    assert!(text.contains("**Hover base**"));
+    assert!(text.contains("Base module of the hover tests"));
+    assert!(text.contains("Version: 1.2.0"));
+    let text = hover_text(&mut session, 2, 33).expect("no hover on missing_module");
+    assert!(text.contains("Module not found in the configured addons paths"));
+    //only the depends entries have a hover
+    assert!(hover_text(&mut session, 1, 6).is_none());
+
+    let Some(GotoDefinitionResponse::Scalar(location)) = DefinitionFeature::get_manifest_location(&mut session, &file_info, 2, 18) else {
+        panic!("no definition for hover_base");
+    };
+    assert!(location.uri.as_str().ends_with("hover_base/__manifest__.py"));
+    assert!(DefinitionFeature::get_manifest_location(&mut session, &file_info, 2, 33).is_none());
+    let _ = fs::remove_dir_all(&root);
+}
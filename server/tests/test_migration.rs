@@ -0,0 +1,86 @@
+use std::env;
+use std::fs;
+
+use clap::Parser;
+use odoo_ls_server::args::Cli;
+use odoo_ls_server::core::migration::MigrationScan;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+/* The migration scan only reads the files of the modules, so these tests don't need an Odoo Community folder */
+
+const MODELS: &str = r#"from odoo import api, models
+from odoo.tests.common import SavepointCase
+
+
+class Partner(models.Model):
+    _inherit = "res.partner"
+
+    @api.multi
+    def name_get(self):
+        return super().name_get()
+
+    def action_check(self):
+        return self.user_has_groups("base.group_user")
+
+    def action_names(self):
+        return self.env["res.partner"].browse(1).name_get()
+
+
+class Report:
+    def name_get(self):
+        return self.name_get()
+
+
+def report_names(report):
+    return report.name_get()
+
+
+class TestPartner(SavepointCase):
+    pass
+"#;
+
+#[test]
+fn test_migrate_scan() {
+    let root = env::temp_dir().join(format!("odoo_ls_migration_{}", std::process::id()));
+    let module_dir = root.join("migrated_module");
+    fs::create_dir_all(&module_dir).unwrap();
+    fs::write(module_dir.join("__manifest__.py"), "{'name': 'Migrated Module', 'depends': ['base']}\n").unwrap();
+    fs::write(module_dir.join("__init__.py"), "from . import models\n").unwrap();
+    fs::write(module_dir.join("models.py"), MODELS).unwrap();
+    let addons_paths = vec![root.sanitize()];
+
+    //from 12 to 13: only the api decorators removed in 13.0
+    let report = MigrationScan::new(12, 13).run(&addons_paths);
+    assert_eq!(report.modules.len(), 1);
+    let rules: Vec<&String> = report.modules[0].issues.iter().map(|issue| &issue.rule).collect();
+    assert_eq!(rules, vec!["odoo.api.multi"]);
+    assert!(report.modules[0].issues[0].removed);
+    assert_eq!(report.modules[0].issues[0].occurrences[0].line, 8);
+
+    //from 15 to 18: the other APIs, but not the ones already removed before 15
+    let report = MigrationScan::new(15, 18).run(&addons_paths);
+    let module = &report.modules[0];
+    assert_eq!(module.module, "migrated_module");
+    let rules: Vec<&String> = module.issues.iter().map(|issue| &issue.rule).collect();
+    assert_eq!(rules, vec!["name_get", "odoo.tests.common.SavepointCase", "user_has_groups"]);
+    //the override of name_get, and the calls on super() and on the records of env. Not the methods of other objects
+    assert_eq!(module.issues[0].occurrences.len(), 3);
+    //the import and the base class
+    assert_eq!(module.issues[1].occurrences.len(), 2);
+    assert!(report.to_markdown().contains("## migrated_module"));
+
+    //no deprecation between 13 and 14
+    assert!(MigrationScan::new(13, 14).run(&addons_paths).modules.is_empty());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_migrate_scan_arguments() {
+    //a missing version is an error of the command line, not a panic of the scan
+    assert!(Cli::try_parse_from(["odoo_ls_server", "--migrate-scan", "--from", "16"]).is_err());
+    assert!(Cli::try_parse_from(["odoo_ls_server", "--migrate-scan", "--to", "17"]).is_err());
+    let cli = Cli::try_parse_from(["odoo_ls_server", "--migrate-scan", "--from", "16", "--to", "17"]).unwrap();
+    assert_eq!((cli.migrate_from, cli.migrate_to), (Some(16), Some(17)));
+    //the versions are only required by the scan
+    assert!(Cli::try_parse_from(["odoo_ls_server"]).is_ok());
+}
@@ -0,0 +1,25 @@
+use odoo_ls_server::core::model::{ModelContribution, ModelData};
+use ruff_python_ast::Stmt;
+use ruff_text_size::TextRange;
+
+fn contribution(code: &str) -> ModelContribution {
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let Some(Stmt::ClassDef(class_def)) = module.body.first() else {
+        panic!("not a class: {}", code);
+    };
+    let mut data = ModelData::new();
+    data.name = String::from("res.partner");
+    ModelContribution::from_class(&data, class_def, |range: &TextRange| code.get(range.start().to_usize()..range.end().to_usize()).map(|text| text.to_string()))
+}
+
+#[test]
+fn test_model_contribution() {
+    let base = contribution("class Partner(models.Model):\n    _inherit = 'res.partner'\n    code = fields.Char()\n\n    @api.depends('code')\n    def _compute_name(self):\n        return self.code\n");
+    //editing the body of a method, or moving the class, doesn't change what it brings to the model
+    assert_eq!(base, contribution("class Partner(models.Model):\n    _inherit = 'res.partner'\n    code = fields.Char()\n\n    @api.depends('code')\n    def _compute_name(self):\n        x = 1\n        return self.code + x\n"));
+    assert_eq!(base, contribution("\n\nclass Partner(models.Model):\n    _inherit = 'res.partner'\n    code = fields.Char()\n\n    @api.depends('code')\n    def _compute_name(self):\n        return self.code\n"));
+    //the fields, the signatures and the decorators of the methods are part of it
+    assert_ne!(base, contribution("class Partner(models.Model):\n    _inherit = 'res.partner'\n    code = fields.Integer()\n\n    @api.depends('code')\n    def _compute_name(self):\n        return self.code\n"));
+    assert_ne!(base, contribution("class Partner(models.Model):\n    _inherit = 'res.partner'\n    code = fields.Char()\n\n    @api.depends('code')\n    def _compute_name(self, extra):\n        return self.code\n"));
+    assert_ne!(base, contribution("class Partner(models.Model):\n    _inherit = 'res.partner'\n    code = fields.Char()\n\n    def _compute_name(self):\n        return self.code\n"));
+}
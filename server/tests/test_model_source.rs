@@ -0,0 +1,76 @@
+use std::env;
+use std::fs;
+
+use lsp_types::Position;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::model_source::{GetModelSourceParams, ModelSourceFeature};
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+const BASE_MODELS: &str = r#"from odoo import fields, models
+
+
+class SourcePartner(models.Model):
+    _name = "source.partner"
+
+    name = fields.Char()
+
+    def action_confirm(self):
+        return True
+"#;
+
+const EXTENSION_MODELS: &str = r#"from odoo import fields, models
+
+
+class SourcePartner(models.Model):
+    _inherit = "source.partner"
+
+    nickname = fields.Char()
+"#;
+
+/* The model is rendered class by class, its declaration first, and each line of declaration links to its source */
+#[test]
+fn test_model_source() {
+    let root = env::temp_dir().join(format!("odoo_ls_model_source_{}", std::process::id()));
+    //the extension is named to be found before the declaration in the addons path
+    for (module, depends, content) in [("source_base", vec![], BASE_MODELS), ("source_a_extension", vec!["source_base"], EXTENSION_MODELS)] {
+        setup::setup::write_module(&root.join("addons"), module, &depends, &[("models.py", content)]);
+    }
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let base_path = root.join("addons").join("source_base").join("models.py").sanitize();
+    let extension_path = root.join("addons").join("source_a_extension").join("models.py").sanitize();
+
+    let result = ModelSourceFeature::get_model_source(&mut session, &GetModelSourceParams { model: S!("source.partner"), module: None }).unwrap();
+    assert_eq!(result.content, format!("# Model source.partner - read-only view generated by OdooLS
+
+# --- source_base ({})
+class SourcePartner(models.Model):
+    _name = \"source.partner\"
+    name = fields.Char()
+    def action_confirm(self): ...
+
+# --- source_a_extension ({})
+class SourcePartner(models.Model):
+    _inherit = \"source.partner\"
+    nickname = fields.Char()
+", base_path, extension_path));
+    let links: Vec<(u32, String, u32)> = result.links.iter().map(|link| (link.line, FileMgr::uri2pathname(link.location.uri.as_str()), link.location.range.start.line)).collect();
+    assert_eq!(links, vec![
+        (3, base_path.clone(), 3), (4, base_path.clone(), 4), (5, base_path.clone(), 6), (6, base_path.clone(), 8),
+        (9, extension_path.clone(), 3), (10, extension_path.clone(), 4), (11, extension_path.clone(), 6),
+    ]);
+    assert_eq!(result.links[2].location.range.start, Position::new(6, 4));
+
+    //only the classes visible from the module are rendered
+    let result = ModelSourceFeature::get_model_source(&mut session, &GetModelSourceParams { model: S!("source.partner"), module: Some(S!("source_base")) }).unwrap();
+    assert!(!result.content.contains("nickname"));
+    assert_eq!(result.links.len(), 4);
+    assert!(ModelSourceFeature::get_model_source(&mut session, &GetModelSourceParams { model: S!("source.unknown"), module: None }).is_none());
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::PublishDiagnosticsParams;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::module_graph::ModuleGraph;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+/* Modules are ranked after their dependencies. Unknown dependencies are ignored, and the modules of a cycle come last */
+#[test]
+fn test_module_ranks() {
+    let mut depends: HashMap<String, Vec<String>> = HashMap::new();
+    depends.insert("base".to_string(), vec![]);
+    depends.insert("mail".to_string(), vec!["base".to_string(), "missing".to_string()]);
+    depends.insert("account".to_string(), vec!["mail".to_string()]);
+    depends.insert("cycle_a".to_string(), vec!["cycle_b".to_string()]);
+    depends.insert("cycle_b".to_string(), vec!["cycle_a".to_string()]);
+    let ranks = ModuleGraph::ranks(&depends);
+    assert_eq!(ranks["base"], 0);
+    assert_eq!(ranks["mail"], 1);
+    assert_eq!(ranks["account"], 2);
+    assert_eq!(ranks["cycle_a"], 3);
+    assert_eq!(ranks["cycle_b"], 3);
+}
+
+/* The files of a module are validated after the files of the module it depends on, whatever the order of the names of the
+modules and of the queue */
+#[test]
+fn test_validation_order() {
+    let root = env::temp_dir().join(format!("odoo_ls_module_graph_{}", std::process::id()));
+    let addons = root.join("addons");
+    //the dependent module comes first in the alphabetical order
+    setup::setup::write_module(&addons, "chain_z_base", &[], &[("models.py", "from odoo import models\n")]);
+    setup::setup::write_module(&addons, "chain_a_user", &["chain_z_base"], &[("models.py", "from odoo import models\n")]);
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![addons.sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let client = r.clone();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let base_path = addons.join("chain_z_base").join("models.py").sanitize();
+    let user_path = addons.join("chain_a_user").join("models.py").sanitize();
+
+    for path in [&user_path, &base_path] {
+        let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(path)).expect("models.py is not loaded");
+        file_symbol.borrow_mut().set_in_workspace(true);
+        session.sync_odoo.add_to_validations(file_symbol);
+    }
+    SyncOdoo::process_rebuilds(&mut session);
+    let published: Vec<String> = client.try_iter().filter_map(|message| match message {
+        Message::Notification(notification) if notification.method == PublishDiagnostics::METHOD => {
+            serde_json::from_value::<PublishDiagnosticsParams>(notification.params).ok().map(|params| FileMgr::uri2pathname(params.uri.as_str()))
+        },
+        _ => None,
+    }).collect();
+    assert_eq!(published, vec![base_path.clone(), user_path.clone()]);
+    let _ = fs::remove_dir_all(&root);
+}
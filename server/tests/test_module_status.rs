@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::env;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, TextDocumentContentChangeEvent};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::module_status::ModuleStatusTracker;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+fn diagnostic(code: &str, severity: DiagnosticSeverity) -> Diagnostic {
+    Diagnostic {
+        range: Range::new(Position::new(0, 0), Position::new(1, 0)),
+        severity: Some(severity),
+        code: Some(NumberOrString::String(S!(code))),
+        message: S!(code),
+        ..Default::default()
+    }
+}
+
+/* The status of a module is given once its last file waiting for validation is validated, with the counts of the files */
+#[test]
+fn test_module_status() {
+    let mut tracker = ModuleStatusTracker::default();
+    let module = S!("sale");
+    let mut computed = 0;
+    tracker.start_file(&module);
+    tracker.end_file(&module, 1, 2);
+    assert!(tracker.is_pending(&module, || {
+        computed += 1;
+        HashMap::from([(S!("sale"), 2), (S!("stock"), 1)])
+    }));
+    //the counts are computed once, then updated as the files leave the queue
+    tracker.pop_pending(&module);
+    tracker.start_file(&module);
+    tracker.end_file(&module, 0, 1);
+    assert!(tracker.is_pending(&module, || { computed += 1; HashMap::new() }));
+    tracker.pop_pending(&module);
+    tracker.start_file(&module);
+    tracker.end_file(&module, 1, 0);
+    assert!(!tracker.is_pending(&module, || { computed += 1; HashMap::new() }));
+    assert_eq!(computed, 1);
+    let status = tracker.finish(&module).unwrap();
+    assert_eq!((status.files, status.errors, status.warnings), (3, 2, 3));
+    assert!(tracker.finish(&module).is_none());
+
+    //files added to the queue invalidate the counts
+    tracker.invalidate_pending();
+    assert!(tracker.is_pending(&module, || HashMap::from([(S!("sale"), 1)])));
+}
+
+/* The diagnostics counted in the status of the modules are the ones reported to the client, with the settings of the project */
+#[test]
+fn test_count_configured_diagnostics() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_module_status.py").sanitize();
+    let change = vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: S!("a = 1\n") }];
+    let (_, file_info) = file_mgr.update_file_info(&mut session, &path, Some(&change), Some(1), false);
+    file_info.borrow_mut().replace_diagnostics(BuildSteps::VALIDATION, vec![
+        diagnostic("OLS20001", DiagnosticSeverity::ERROR),
+        diagnostic("OLS20002", DiagnosticSeverity::WARNING),
+        diagnostic("OLS20003", DiagnosticSeverity::WARNING),
+    ]);
+    assert_eq!(file_info.borrow().count_diagnostics(&session.sync_odoo.config), (1, 2));
+
+    session.sync_odoo.config.diag_severities.insert(S!("OLS20001"), None);
+    session.sync_odoo.config.diag_severities.insert(S!("OLS20002"), Some(DiagnosticSeverity::ERROR));
+    assert_eq!(file_info.borrow().count_diagnostics(&session.sync_odoo.config), (1, 1));
+
+    session.sync_odoo.config.exclude = vec![S!("**/odoo_ls_module_status.py")];
+    assert_eq!(file_info.borrow().count_diagnostics(&session.sync_odoo.config), (0, 0));
+}
@@ -0,0 +1,100 @@
+use std::cell::RefCell;
+use std::env;
+use std::rc::Rc;
+
+use lsp_types::{ChangeAnnotationWorkspaceEditClientCapabilities, CodeActionContext, CodeActionKind, CodeActionOrCommand, CodeActionParams, DocumentChanges, OneOf,
+    PartialResultParams, Position, Range, TextDocumentContentChangeEvent, TextDocumentIdentifier, Uri, WorkDoneProgressParams, WorkspaceClientCapabilities,
+    WorkspaceEdit, WorkspaceEditClientCapabilities};
+use odoo_ls_server::core::file_mgr::{FileInfo, FileMgr};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::code_action::CodeActionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+const CODE: &str = r#""""Wizard of the module"""
+from . import wizard
+from odoo import models, api
+import logging
+from odoo.exceptions import UserError
+from odoo import (
+    api,
+    fields,
+)
+import os
+from odoo.addons.base.models.res_partner import Partner as BasePartner
+
+
+_logger = logging.getLogger(__name__)
+"#;
+
+const ORGANIZED: &str = "import logging
+import os
+
+from odoo import api, fields, models
+from odoo.exceptions import UserError
+
+from odoo.addons.base.models.res_partner import Partner as BasePartner
+
+from . import wizard";
+
+fn organize_imports(session: &mut SessionInfo, file_info: &Rc<RefCell<FileInfo>>, uri: &Uri, only: Option<Vec<CodeActionKind>>) -> Option<WorkspaceEdit> {
+    let params = CodeActionParams {
+        text_document: TextDocumentIdentifier { uri: uri.clone() },
+        range: Range::new(Position::new(0, 0), Position::new(0, 0)),
+        context: CodeActionContext { diagnostics: vec![], only, trigger_kind: None },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let actions = CodeActionFeature::get_code_actions(session, file_info, &params)?;
+    actions.into_iter().find_map(|action| match action {
+        CodeActionOrCommand::CodeAction(action) if action.kind == Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS) => action.edit,
+        _ => None,
+    })
+}
+
+/* The imports after the docstring are grouped and sorted, the from imports of a module are merged. The edit is annotated for
+a preview if the client supports it, and no action is given if the imports are organized or if comments would be lost */
+#[test]
+fn test_organize_imports() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_organize_imports.py").sanitize();
+    let uri = FileMgr::pathname2uri(&path);
+    let update = |session: &mut SessionInfo, file_mgr: &mut FileMgr, code: &str, version: i32| {
+        let change = vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: S!(code) }];
+        file_mgr.update_file_info(session, &path, Some(&change), Some(version), false).1
+    };
+    let file_info = update(&mut session, &mut file_mgr, CODE, 1);
+
+    let edit = organize_imports(&mut session, &file_info, &uri, Some(vec![CodeActionKind::SOURCE])).expect("no organize imports action");
+    let edits = &edit.changes.unwrap()[&uri];
+    assert_eq!(edits.len(), 1);
+    let end_line = CODE.lines().position(|line| line.starts_with("from odoo.addons")).unwrap() as u32;
+    assert_eq!(edits[0].range, Range::new(Position::new(1, 0), Position::new(end_line, CODE.lines().nth(end_line as usize).unwrap().len() as u32)));
+    assert_eq!(edits[0].new_text, ORGANIZED);
+    //only the quick fixes are requested
+    assert!(organize_imports(&mut session, &file_info, &uri, Some(vec![CodeActionKind::QUICKFIX])).is_none());
+
+    session.sync_odoo.capabilities.workspace = Some(WorkspaceClientCapabilities {
+        workspace_edit: Some(WorkspaceEditClientCapabilities {
+            document_changes: Some(true),
+            change_annotation_support: Some(ChangeAnnotationWorkspaceEditClientCapabilities { groups_on_label: Some(true) }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    let edit = organize_imports(&mut session, &file_info, &uri, None).expect("no organize imports action");
+    assert_eq!(edit.change_annotations.unwrap()["other"].description.as_deref(), Some("Organize imports"));
+    let Some(DocumentChanges::Edits(document_edits)) = edit.document_changes else {
+        panic!("the edits are not document changes");
+    };
+    assert!(matches!(&document_edits[0].edits[0], OneOf::Right(annotated) if annotated.text_edit.new_text == ORGANIZED));
+
+    let file_info = update(&mut session, &mut file_mgr, &format!("{}\n", ORGANIZED), 2);
+    assert!(organize_imports(&mut session, &file_info, &uri, None).is_none());
+    let file_info = update(&mut session, &mut file_mgr, "import os\n# needed by the tests\nimport logging\n", 3);
+    assert!(organize_imports(&mut session, &file_info, &uri, None).is_none());
+}
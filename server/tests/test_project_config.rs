@@ -0,0 +1,77 @@
+use std::env;
+use std::fs;
+
+use lsp_types::{DiagnosticSeverity, NumberOrString, Position};
+use odoo_ls_server::core::config::{AddonsPolicy, Config};
+use odoo_ls_server::core::project_config::{ProjectConfig, PROJECT_CONFIG_FILE};
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+const PROJECT_SETTINGS: &str = r#"odoo_path = "../odoo"
+addons = ["./addons"]
+python_path = "python3"
+exclude = ["addons/*/migrations/**", "[invalid"]
+strict = "yes"
+color = "blue"
+
+[diagnostics]
+OLS20201 = "error"
+OLS20001 = "none"
+OLS20002 = "loud"
+
+[addons_policy]
+"../oca" = "suggestOnly"
+"#;
+
+/* The valid settings are loaded, relative paths being resolved from the folder of the file, and the invalid ones are reported
+at their key */
+#[test]
+fn test_load_project_config() {
+    let root = env::temp_dir().join(format!("odoo_ls_project_config_{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let path = root.join(PROJECT_CONFIG_FILE);
+    fs::write(&path, PROJECT_SETTINGS).unwrap();
+
+    let (config, diagnostics) = ProjectConfig::load(&path);
+    assert_eq!(config.odoo_path, Some(root.join("../odoo").sanitize()));
+    assert_eq!(config.addons, Some(vec![root.join("./addons").sanitize()]));
+    assert_eq!(config.python_path, Some(S!("python3")));
+    assert_eq!(config.exclude, vec![S!("addons/*/migrations/**")]);
+    assert_eq!(config.strict, None);
+    assert_eq!(config.diag_severities.get("OLS20201"), Some(&Some(DiagnosticSeverity::ERROR)));
+    assert_eq!(config.diag_severities.get("OLS20001"), Some(&None));
+    assert!(!config.diag_severities.contains_key("OLS20002"));
+    assert_eq!(config.addons_policies.get(&root.join("../oca").sanitize_canonical()), Some(&AddonsPolicy::SuggestOnly));
+
+    //the keys are not read in the order of the file
+    let mut messages: Vec<(u32, String)> = diagnostics.iter().map(|diagnostic| (diagnostic.range.start.line, diagnostic.message.clone())).collect();
+    messages.sort();
+    assert_eq!(messages.len(), 4, "{:?}", messages);
+    assert_eq!(messages[0].0, 3);
+    assert!(messages[0].1.starts_with("Invalid pattern '[invalid'"));
+    assert_eq!(messages[1], (4, S!("strict must be a boolean")));
+    assert_eq!(messages[2], (5, S!("Unknown key: color")));
+    assert_eq!(messages[3], (10, S!("Invalid severity for OLS20002. Use error, warning, info, hint or none")));
+    assert!(diagnostics.iter().all(|diagnostic| diagnostic.code == Some(NumberOrString::String(S!("OLS31601")))));
+
+    //a file that is not toml is reported at the position of the error
+    fs::write(&path, "addons = [\"./addons\"\nstrict = true\n").unwrap();
+    let (_, diagnostics) = ProjectConfig::load(&path);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.starts_with("Invalid odools.toml"));
+    assert_ne!(diagnostics[0].range.start, Position::new(0, 0));
+    let _ = fs::remove_dir_all(&root);
+}
+
+/* The exclude patterns are relative to the folder of odools.toml */
+#[test]
+fn test_exclude_from_project_root() {
+    let mut config = Config::new();
+    config.project_config = Some(S!("/work/project/odools.toml"));
+    config.exclude = vec![S!("addons/*/migrations/**"), S!("**/tests/*.py")];
+    assert!(config.is_excluded("/work/project/addons/sale_custom/migrations/17.0.1.1/post-migrate.py"));
+    assert!(config.is_excluded("/work/project/addons/sale_custom/tests/test_sale.py"));
+    assert!(!config.is_excluded("/work/project/addons/sale_custom/models/sale.py"));
+    //the patterns don't match the same folders outside of the project
+    assert!(!config.is_excluded("/work/other/addons/sale_custom/migrations/17.0.1.1/post-migrate.py"));
+}
@@ -0,0 +1,158 @@
+use odoo_ls_server::core::python_utils::{catches_import_error, dead_if_branches, eval_version_condition, getattr_as_attribute, hasattr_guarded_bodies, hasattr_guards, is_forwarding_override, is_sudo_chain, literal_prefix};
+use ruff_python_ast::{Expr, Stmt};
+use odoo_ls_server::S;
+
+fn is_forwarding(code: &str) -> bool {
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let Some(Stmt::FunctionDef(func_def)) = module.body.first() else {
+        panic!("not a function: {}", code);
+    };
+    is_forwarding_override(func_def)
+}
+
+#[test]
+fn test_forwarding_override() {
+    assert!(is_forwarding("def write(self, vals):\n    return super().write(vals)\n"));
+    assert!(is_forwarding("def create(self, *args, **kwargs):\n    \"\"\"doc\"\"\"\n    return super().create(*args, **kwargs)\n"));
+    assert!(is_forwarding("def copy(self, default=None, *, check=True):\n    return super(Partner, self).copy(default=default, check=check)\n"));
+    //the parameters are changed, or not all given
+    assert!(!is_forwarding("def write(self, vals):\n    return super().write(dict(vals))\n"));
+    assert!(!is_forwarding("def write(self, vals, check):\n    return super().write(vals)\n"));
+    assert!(!is_forwarding("def write(self, a, b):\n    return super().write(b, a)\n"));
+    //another method, or more statements
+    assert!(!is_forwarding("def write(self, vals):\n    return super().create(vals)\n"));
+    assert!(!is_forwarding("def write(self, vals):\n    self.check()\n    return super().write(vals)\n"));
+    assert!(!is_forwarding("def write(self, vals):\n    super().write(vals)\n"));
+}
+
+fn parse_expr(code: &str) -> Expr {
+    *ruff_python_parser::parse_expression(code).unwrap().into_syntax().body
+}
+
+#[test]
+fn test_getattr_as_attribute() {
+    let Expr::Call(call) = parse_expr("getattr(rec, 'partner_id')") else {
+        panic!("not a call");
+    };
+    let attribute = getattr_as_attribute(&call).unwrap();
+    assert_eq!(attribute.attr.as_str(), "partner_id");
+    assert_eq!(attribute.value.as_name_expr().unwrap().id.as_str(), "rec");
+    assert!(getattr_as_attribute(parse_expr("getattr(rec, 'partner_id', False)").as_call_expr().unwrap()).is_some());
+    //the name must be a literal identifier
+    assert!(getattr_as_attribute(parse_expr("getattr(rec, name)").as_call_expr().unwrap()).is_none());
+    assert!(getattr_as_attribute(parse_expr("getattr(rec, 'partner_id.name')").as_call_expr().unwrap()).is_none());
+    assert!(getattr_as_attribute(parse_expr("hasattr(rec, 'partner_id')").as_call_expr().unwrap()).is_none());
+}
+
+#[test]
+fn test_hasattr_guards() {
+    let guards = |code: &str| hasattr_guards(&parse_expr(code)).into_iter().map(|(_, name)| name.to_string()).collect::<Vec<String>>();
+    assert_eq!(guards("hasattr(rec, 'partner_id')"), vec!["partner_id"]);
+    assert_eq!(guards("hasattr(rec, 'partner_id') and hasattr(rec, 'user_id')"), vec!["partner_id", "user_id"]);
+    //nothing is guaranteed by a or, nor by a negation
+    assert!(guards("hasattr(rec, 'partner_id') or hasattr(rec, 'user_id')").is_empty());
+    assert!(guards("not hasattr(rec, 'partner_id')").is_empty());
+}
+
+#[test]
+fn test_hasattr_guarded_bodies() {
+    let code = "def check(rec):\n    for line in rec:\n        if hasattr(line, 'partner_id'):\n            getattr(line, 'partner_id')\n        else:\n            pass\n    return rec\n";
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let bodies = hasattr_guarded_bodies(&module.body);
+    assert_eq!(bodies.len(), 1);
+    let (range, object, name) = bodies[0];
+    assert_eq!(name, "partner_id");
+    assert_eq!(object.as_name_expr().unwrap().id.as_str(), "line");
+    //only the body of the if is guarded, not its else clause
+    assert_eq!(&code[range.start().to_usize()..range.end().to_usize()], "getattr(line, 'partner_id')");
+}
+
+#[test]
+fn test_sudo_chain() {
+    assert!(is_sudo_chain(&parse_expr("self.env['res.partner'].sudo()")));
+    assert!(is_sudo_chain(&parse_expr("self.sudo().partner_id")));
+    assert!(is_sudo_chain(&parse_expr("self.env['res.partner'].sudo().with_context(active_test=False)")));
+    assert!(!is_sudo_chain(&parse_expr("self.env['res.partner'].with_context(active_test=False)")));
+    assert!(!is_sudo_chain(&parse_expr("partners")));
+}
+
+/* The text of the string before the cursor, given by the | of the code */
+fn prefix_at(code: &str) -> Option<String> {
+    let offset = code.find('|').unwrap();
+    let source = code.replacen('|', "", 1);
+    literal_prefix(&parse_expr(&source), &source, offset)
+}
+
+#[test]
+fn test_literal_prefix() {
+    assert_eq!(prefix_at(r#""part|ner_id""#), Some(S!("part")));
+    assert_eq!(prefix_at(r#"'''tri|ple'''"#), Some(S!("tri")));
+    assert_eq!(prefix_at(r#""partner_" "i|d""#), Some(S!("partner_i")));
+    //as written in the source
+    assert_eq!(prefix_at(r#""a\tb|""#), Some(S!(r"a\tb")));
+    assert_eq!(prefix_at(r#"|"partner""#), None);
+    //the multi-byte characters before the cursor are kept
+    assert_eq!(prefix_at(r#"f"café_|{suffix}""#), Some(S!("café_")));
+    assert_eq!(prefix_at(r#"f"partner_{suffix}|_id""#), None);
+    assert_eq!(prefix_at(r#""part|ner_%s" % suffix"#), Some(S!("part")));
+    assert_eq!(prefix_at(r#""partner_%s|" % suffix"#), None);
+    assert_eq!(prefix_at(r#""par|tner_{}".format(suffix)"#), Some(S!("par")));
+    assert_eq!(prefix_at(r#""partner_{}|".format(suffix)"#), None);
+}
+
+fn catches(handlers: &str) -> bool {
+    let code = format!("try:\n    import lxml\n{}\n    pass\n", handlers);
+    let module = ruff_python_parser::parse_module(&code).unwrap().into_syntax();
+    let Some(Stmt::Try(try_stmt)) = module.body.first() else {
+        panic!("not a try: {}", code);
+    };
+    catches_import_error(&try_stmt.handlers)
+}
+
+#[test]
+fn test_catches_import_error() {
+    assert!(catches("except ImportError:"));
+    assert!(catches("except ModuleNotFoundError as e:"));
+    assert!(catches("except (ValueError, ImportError):"));
+    assert!(catches("except ValueError:\n    pass\nexcept ImportError:"));
+    //any error of the body is caught, not only the failed imports
+    assert!(!catches("except:"));
+    assert!(!catches("except Exception:"));
+    assert!(!catches("except BaseException:"));
+    assert!(!catches("except ValueError:"));
+}
+
+fn version_condition(code: &str) -> Option<bool> {
+    eval_version_condition(&parse_expr(code), (17, 0, 1))
+}
+
+#[test]
+fn test_eval_version_condition() {
+    assert_eq!(version_condition("odoo.release.version_info >= (17, 0)"), Some(true));
+    assert_eq!(version_condition("release.version_info < (16,)"), Some(false));
+    assert_eq!(version_condition("(17, 0, 2) > release.version_info"), Some(true));
+    assert_eq!(version_condition("release.version_info[0] == 17"), Some(true));
+    assert_eq!(version_condition("release.version_info[1] != 0"), Some(false));
+    assert_eq!(version_condition("not release.version_info >= (18, 0)"), Some(true));
+    assert_eq!(version_condition("release.version_info >= (17, 0) and release.version_info < (18, 0)"), Some(true));
+    //a part that can't be evaluated decides only if the others don't
+    assert_eq!(version_condition("release.version_info < (16, 0) and self.env.context.get('x')"), Some(false));
+    assert_eq!(version_condition("release.version_info >= (16, 0) and self.env.context.get('x')"), None);
+    assert_eq!(version_condition("sys.version_info >= (3, 10)"), None);
+    assert_eq!(version_condition("release.version_info >= version"), None);
+}
+
+#[test]
+fn test_dead_if_branches() {
+    let dead_branches = |code: &str| {
+        let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+        let Some(Stmt::If(if_stmt)) = module.body.first() else {
+            panic!("not an if statement: {}", code);
+        };
+        dead_if_branches(if_stmt, (17, 0, 1))
+    };
+    assert_eq!(dead_branches("if release.version_info >= (17, 0):\n    a = 1\nelse:\n    a = 2\n"), vec![false, true]);
+    assert_eq!(dead_branches("if release.version_info < (16, 0):\n    a = 1\nelif release.version_info < (18, 0):\n    a = 2\nelse:\n    a = 3\n"), vec![true, false, true]);
+    //once a branch can't be evaluated, the next ones can't be known as dead unless their own test is false
+    assert_eq!(dead_branches("if x:\n    a = 1\nelif release.version_info >= (17, 0):\n    a = 2\nelif release.version_info >= (18, 0):\n    a = 3\nelse:\n    a = 4\n"), vec![false, false, true, false]);
+}
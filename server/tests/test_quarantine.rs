@@ -0,0 +1,43 @@
+use odoo_ls_server::core::quarantine::{Quarantine, MAX_FAILURES};
+use odoo_ls_server::crash_report::CrashReport;
+
+#[test]
+fn test_quarantine() {
+    let mut quarantine = Quarantine::default();
+    let path = "/addons/my_module/models/broken.py";
+    for _ in 1..MAX_FAILURES {
+        assert!(!quarantine.record_failure(path));
+    }
+    assert!(!quarantine.is_quarantined(path));
+    assert!(quarantine.record_failure(path));
+    assert!(quarantine.is_quarantined(path));
+    //notified only once
+    assert!(!quarantine.record_failure(path));
+    assert_eq!(quarantine.files(), vec![path.to_string()]);
+    //an edit gives a new chance, but a single one
+    assert!(quarantine.release(path));
+    assert!(!quarantine.is_quarantined(path));
+    assert!(quarantine.record_failure(path));
+    quarantine.clear();
+    assert!(quarantine.files().is_empty());
+}
+
+#[test]
+fn test_quarantine_success_resets_failures() {
+    let mut quarantine = Quarantine::default();
+    let path = "/addons/my_module/models/flaky.py";
+    for _ in 0..MAX_FAILURES * 2 {
+        assert!(!quarantine.record_failure(path));
+        quarantine.record_success(path);
+    }
+    assert!(!quarantine.is_quarantined(path));
+    assert!(!quarantine.release(path));
+}
+
+#[test]
+fn test_catch_recoverable() {
+    assert_eq!(CrashReport::catch_recoverable(|| 42), Ok(42));
+    assert_eq!(CrashReport::catch_recoverable(|| -> u32 { panic!("broken hook") }), Err(String::from("broken hook")));
+    let name = "sale.order";
+    assert_eq!(CrashReport::catch_recoverable(|| -> u32 { panic!("no model {}", name) }), Err(String::from("no model sale.order")));
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lsp_server::Message;
+use lsp_types::{DidOpenTextDocumentParams, NumberOrString, PublishDiagnosticsParams, SymbolKind, TextDocumentItem, Uri};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::document_symbol::DocumentSymbolFeature;
+use odoo_ls_server::threads::{quick_parse_thread, QuickParseMessage};
+use odoo_ls_server::S;
+
+const CODE: &str = r#"from odoo import models
+
+
+class Partner(models.Model):
+    _name = "quick.partner"
+
+    def action_done(self):
+        return True
+
+    def broken(self:
+        pass
+"#;
+
+#[test]
+fn test_quick_parse() {
+    let (diagnostics, outline) = DocumentSymbolFeature::quick_parse(CODE);
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics.iter().all(|diagnostic| diagnostic.code == Some(NumberOrString::String(S!("OLS30001")))));
+    //the syntax error is in the signature of broken, after the valid methods
+    assert!(diagnostics[0].range.start.line >= 9);
+    assert_eq!(outline.len(), 1);
+    assert_eq!(outline[0].name, "Partner");
+    assert_eq!(outline[0].kind, SymbolKind::CLASS);
+    let children: Vec<&str> = outline[0].children.as_ref().unwrap().iter().map(|child| child.name.as_str()).collect();
+    assert!(children.contains(&"_name") && children.contains(&"action_done"));
+}
+
+fn open_params(uri: &str) -> serde_json::Value {
+    serde_json::to_value(DidOpenTextDocumentParams {
+        text_document: TextDocumentItem {
+            uri: uri.parse::<Uri>().unwrap(),
+            language_id: S!("python"),
+            version: 3,
+            text: S!(CODE),
+        },
+    }).unwrap()
+}
+
+/* The quick parse runs on its own thread, and only while the database is held by a build */
+#[test]
+fn test_quick_parse_thread() {
+    let sync_odoo = Arc::new(Mutex::new(SyncOdoo::new()));
+    let outlines = Arc::new(Mutex::new(HashMap::new()));
+    let (sender, receiver_from_thread) = crossbeam_channel::unbounded();
+    let (sender_to_thread, receiver) = crossbeam_channel::unbounded();
+    let thread = {
+        let sync_odoo = sync_odoo.clone();
+        let outlines = outlines.clone();
+        std::thread::spawn(move || quick_parse_thread(sender, receiver, sync_odoo, outlines))
+    };
+    let uri = "file:///tmp/quick_module/models.py";
+
+    //the database is free: the main thread answers
+    sender_to_thread.send(QuickParseMessage::OPEN(open_params(uri))).unwrap();
+    sender_to_thread.send(QuickParseMessage::FORGET(S!("file:///tmp/other.py"))).unwrap();
+    assert!(receiver_from_thread.recv_timeout(Duration::from_millis(500)).is_err());
+    assert!(outlines.lock().unwrap().is_empty());
+
+    //a build holds the database
+    let guard = sync_odoo.lock().unwrap();
+    sender_to_thread.send(QuickParseMessage::OPEN(open_params("file:///tmp/quick_module/data.xml"))).unwrap();
+    sender_to_thread.send(QuickParseMessage::OPEN(open_params(uri))).unwrap();
+    let Ok(Message::Notification(notification)) = receiver_from_thread.recv_timeout(Duration::from_secs(10)) else {
+        panic!("no diagnostics published by the quick parse");
+    };
+    assert_eq!(notification.method, "textDocument/publishDiagnostics");
+    let params: PublishDiagnosticsParams = serde_json::from_value(notification.params).unwrap();
+    assert_eq!(params.uri.as_str(), uri);
+    assert_eq!(params.version, Some(3));
+    assert!(!params.diagnostics.is_empty());
+    assert!(params.diagnostics[0].code_description.is_some());
+    assert_eq!(outlines.lock().unwrap().get(uri).map(|outline| outline.len()), Some(1));
+
+    //the outline is dropped when the document changes
+    sender_to_thread.send(QuickParseMessage::FORGET(S!(uri))).unwrap();
+    sender_to_thread.send(QuickParseMessage::EXIT).unwrap();
+    thread.join().unwrap();
+    drop(guard);
+    assert!(outlines.lock().unwrap().is_empty());
+}
@@ -0,0 +1,60 @@
+use odoo_ls_server::core::read_group::{find_rows_call, ReadGroupCall};
+use ruff_python_ast::{Expr, Stmt};
+
+fn parse_call(code: &str, version_major: u32) -> Option<ReadGroupCall> {
+    let expr = *ruff_python_parser::parse_expression(code).unwrap().into_syntax().body;
+    let Expr::Call(call) = expr else {
+        panic!("not a call: {}", code);
+    };
+    ReadGroupCall::parse(&call, version_major)
+}
+
+#[test]
+fn test_read_group_keys() {
+    let lazy = parse_call("self.read_group([], ['amount_total:sum', 'total:max(amount_untaxed)'], ['partner_id', 'date_order:month'])", 16).unwrap();
+    assert_eq!(lazy.groupby[1].field, "date_order");
+    assert_eq!(lazy.groupby[1].granularity.as_deref(), Some("month"));
+    assert_eq!(lazy.aggregates[1].key, "total");
+    assert_eq!(lazy.aggregates[1].field.as_deref(), Some("amount_untaxed"));
+    assert_eq!(lazy.result_keys(), vec!["partner_id", "amount_total", "total", "partner_id_count", "__domain", "__context"]);
+
+    let full = parse_call("self.read_group([], ['amount_total'], groupby=['partner_id', 'date_order:month'], lazy=False)", 16).unwrap();
+    assert_eq!(full.result_keys(), vec!["partner_id", "date_order:month", "amount_total", "__count", "__domain"]);
+
+    //an aggregated groupby gives a single key, even when other keys come between them
+    let aggregated = parse_call("self.read_group([], ['partner_id:count_distinct'], ['partner_id', 'user_id'], lazy=False)", 16).unwrap();
+    assert_eq!(aggregated.result_keys(), vec!["partner_id", "user_id", "__count", "__domain"]);
+
+    //_read_group returns tuples since Odoo 17, with groupby before the aggregates
+    let tuples = parse_call("self._read_group([], ['partner_id'], ['amount_total:sum', '__count'])", 17).unwrap();
+    assert!(tuples.result_keys().is_empty());
+    assert_eq!(tuples.aggregates[1].field, None);
+    assert_eq!(tuples.groupby[0].field, "partner_id");
+    assert!(parse_call("self.search([])", 17).is_none());
+}
+
+#[test]
+fn test_read_group_spec_errors() {
+    let call = parse_call("self.read_group([], ['amount_total:total', 'amount_tax:sum'], ['date_order:fortnight', 'date_order:week'])", 16).unwrap();
+    let errors: Vec<String> = call.spec_errors().into_iter().map(|(_, message)| message).collect();
+    assert_eq!(errors, vec!["Invalid date granularity fortnight in groupby date_order:fortnight", "Invalid aggregate function total in amount_total:total"]);
+}
+
+#[test]
+fn test_find_rows_call() {
+    let code = "def f(self):\n    groups = self.read_group([], ['amount_total'], ['partner_id'])\n    for group in groups:\n        group\n    [row for row in self.read_group([], [], ['user_id'])]\n    other = self.search([])\n    for rec in other:\n        rec\n";
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let Some(Stmt::FunctionDef(func_def)) = module.body.first() else {
+        panic!("not a function");
+    };
+    let name = |id: &str| *ruff_python_parser::parse_expression(id).unwrap().into_syntax().body;
+    let group = name("group");
+    let call = find_rows_call(&func_def.body, &group).unwrap();
+    assert_eq!(ReadGroupCall::parse(call, 16).unwrap().groupby[0].field, "partner_id");
+    let row = name("row");
+    assert_eq!(ReadGroupCall::parse(find_rows_call(&func_def.body, &row).unwrap(), 16).unwrap().groupby[0].field, "user_id");
+    let rec = name("rec");
+    assert!(find_rows_call(&func_def.body, &rec).is_none());
+    let first = name("groups[0]");
+    assert!(find_rows_call(&func_def.body, &first).is_some());
+}
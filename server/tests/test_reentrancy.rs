@@ -0,0 +1,145 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::deferred_mutations::DeferredMutation;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::completion::CompletionFeature;
+use odoo_ls_server::features::definition::DefinitionFeature;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::features::signature_help::SignatureHelpFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* Stress test of the borrows of the symbols: a module full of recursive definitions, whose evaluation re-enters the
+evaluation of the symbols being evaluated, is built, then every feature is requested at every position of its files.
+Any symbol borrowed twice makes the test panic. */
+
+const RECURSIVE_MODELS: &str = r#"from odoo import api, fields, models
+
+
+def ping(value):
+    return pong(value)
+
+
+def pong(value):
+    return ping(value)
+
+
+def itself():
+    return itself()
+
+
+loop_a = loop_b = None
+loop_a = loop_b
+loop_b = loop_a
+
+
+class Recursive(models.Model):
+    _name = "reentrancy.recursive"
+    _inherit = ["reentrancy.recursive"]
+
+    parent_id = fields.Many2one("reentrancy.recursive")
+    child_ids = fields.One2many("reentrancy.recursive", "parent_id")
+    name = fields.Char(compute="_compute_name")
+
+    @api.depends("parent_id.name", "parent_id.parent_id.name")
+    def _compute_name(self):
+        for record in self:
+            record.name = record.parent_id.name
+
+    def root(self):
+        if self.parent_id:
+            return self.parent_id.root()
+        return self
+
+    def env_chain(self):
+        return self.env["reentrancy.recursive"].browse().env["reentrancy.recursive"].env_chain()
+
+    def self_loop(self):
+        record = self
+        record = record.parent_id
+        record = record.child_ids.parent_id
+        return record.self_loop().root().parent_id
+
+    def with_super(self):
+        return super().with_super().with_super()
+
+
+class Other(models.Model):
+    _name = "reentrancy.other"
+    _inherit = "reentrancy.recursive"
+
+    def root(self):
+        return super().root().env["reentrancy.other"].root()
+
+    @property
+    def itself(self):
+        return self.itself
+"#;
+
+#[test]
+fn test_reentrant_features() {
+    let root = env::temp_dir().join(format!("odoo_ls_reentrancy_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "reentrancy", &["base"], &[("models.py", RECURSIVE_MODELS)]);
+
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+
+    let path = module_dir.join("models.py").sanitize();
+    let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&path)).expect("models.py is not loaded");
+    let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path).expect("models.py has no file info");
+    for (line, content) in RECURSIVE_MODELS.lines().enumerate() {
+        for character in 0..=content.len() as u32 {
+            HoverFeature::get_hover(&mut session, &file_symbol, &file_info, line as u32, character);
+            DefinitionFeature::get_location(&mut session, &file_symbol, &file_info, line as u32, character);
+            CompletionFeature::autocomplete(&mut session, &file_symbol, &file_info, line as u32, character);
+            SignatureHelpFeature::get_signature_help(&mut session, &file_symbol, &file_info, line as u32, character);
+        }
+    }
+    //the changes queued by the hooks of the features are applied by the next rebuild
+    SyncOdoo::process_rebuilds(&mut session);
+    assert!(session.sync_odoo.deferred_mutations.is_empty());
+    let _ = fs::remove_dir_all(&root);
+}
+
+/* The changes of the files queued by the hooks while the files are borrowed are applied once they are released. The
+changes of the files removed in between are dropped */
+#[test]
+fn test_deferred_mutations() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let root = Symbol::new_root();
+    let file = root.borrow_mut().add_new_file(&mut session, &S!("models"), &S!("/tmp/deferred/models.py"));
+    let api = root.borrow_mut().add_new_file(&mut session, &S!("api"), &S!("/tmp/deferred/api.py"));
+    let other_root = Symbol::new_root();
+    let removed = other_root.borrow_mut().add_new_file(&mut session, &S!("removed"), &S!("/tmp/deferred/removed.py"));
+    {
+        let _evaluated = file.borrow();
+        session.sync_odoo.deferred_mutations.push(DeferredMutation::AddDependency {
+            file: Rc::downgrade(&file),
+            dependency: Rc::downgrade(&api),
+            step: BuildSteps::ARCH_EVAL,
+            dep_level: BuildSteps::ARCH,
+        });
+        session.sync_odoo.deferred_mutations.push(DeferredMutation::AddNotFoundPath {
+            file: Rc::downgrade(&removed),
+            step: BuildSteps::ARCH_EVAL,
+            path: vec![S!("odoo"), S!("unknown")],
+        });
+    }
+    drop(removed);
+    drop(other_root);
+    session.sync_odoo.apply_deferred_mutations();
+    assert!(session.sync_odoo.deferred_mutations.is_empty());
+    assert!(file.borrow().dependencies()[BuildSteps::ARCH_EVAL as usize][BuildSteps::ARCH as usize].contains(&api));
+    assert!(api.borrow().dependents()[BuildSteps::ARCH as usize][BuildSteps::ARCH_EVAL as usize].contains(&file));
+}
@@ -0,0 +1,90 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{Position, PrepareRenameResponse, Range};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::request_progress::RequestProgress;
+use odoo_ls_server::features::rename::RenameFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn set_in_workspace(session: &mut SessionInfo, module: &str) {
+    let module = session.sync_odoo.modules.get(module).and_then(|module| module.upgrade()).expect("the module is not loaded");
+    module.borrow_mut().set_in_workspace(true);
+    let files: Vec<_> = module.borrow().all_module_symbol().cloned().collect();
+    for file in files.iter() {
+        file.borrow_mut().set_in_workspace(true);
+    }
+}
+
+const BASE_MODELS: &str = r#"from odoo import fields, models
+
+
+class RenamePartner(models.Model):
+    _name = "rename.partner"
+
+    nickname = fields.Char()
+"#;
+
+const USER_MODELS: &str = r#"from odoo import models
+
+
+class RenamePartner(models.Model):
+    _inherit = "rename.partner"
+
+    def action(self):
+        return self.nickname, self.id
+"#;
+
+/* prepareRename refuses what can't be renamed: symbols that are not fields, magic fields and fields declared outside of
+the workspace. Otherwise it gives the range of the name, and the rename edits the declaration and the usages */
+#[test]
+fn test_rename() {
+    let root = env::temp_dir().join(format!("odoo_ls_rename_{}", std::process::id()));
+    for (module, depends, content) in [("rename_base", vec![], BASE_MODELS), ("rename_user", vec!["rename_base"], USER_MODELS)] {
+        setup::setup::write_module(&root.join("addons"), module, &depends, &[("models.py", content)]);
+    }
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let base_path = root.join("addons").join("rename_base").join("models.py").sanitize();
+    let user_path = root.join("addons").join("rename_user").join("models.py").sanitize();
+    let base_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&base_path)).expect("rename_base is not loaded");
+    let user_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&user_path)).expect("rename_user is not loaded");
+    let base_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&base_path).unwrap();
+    let user_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&user_path).unwrap();
+    set_in_workspace(&mut session, "rename_user");
+
+    assert_eq!(RenameFeature::prepare_rename(&mut session, &user_symbol, &user_info, 7, 16), Err(S!("Only the fields of models can be renamed")));
+    assert_eq!(RenameFeature::prepare_rename(&mut session, &user_symbol, &user_info, 7, 35), Err(S!("id is a magic field of Odoo and can't be renamed")));
+    assert_eq!(RenameFeature::prepare_rename(&mut session, &user_symbol, &user_info, 7, 22),
+        Err(S!("nickname is declared in rename_base, outside of the workspace, and can't be renamed")));
+
+    set_in_workspace(&mut session, "rename_base");
+    assert_eq!(RenameFeature::prepare_rename(&mut session, &user_symbol, &user_info, 7, 22),
+        Ok(PrepareRenameResponse::Range(Range::new(Position::new(7, 20), Position::new(7, 28)))));
+    assert_eq!(RenameFeature::prepare_rename(&mut session, &base_symbol, &base_info, 6, 6),
+        Ok(PrepareRenameResponse::Range(Range::new(Position::new(6, 4), Position::new(6, 12)))));
+
+    //the new name is checked before any edit
+    assert_eq!(RenameFeature::rename(&mut session, &user_symbol, &user_info, 7, 22, &S!("class"), &mut RequestProgress::none()),
+        Err(S!("'class' is not a valid field name")));
+    assert_eq!(RenameFeature::rename(&mut session, &user_symbol, &user_info, 7, 22, &S!("action"), &mut RequestProgress::none()),
+        Err(S!("rename.partner already has a member named action")));
+    let edit = RenameFeature::rename(&mut session, &user_symbol, &user_info, 7, 22, &S!("alias"), &mut RequestProgress::none()).unwrap();
+    let mut edits: Vec<(String, Range, String)> = edit.changes.unwrap().into_iter().flat_map(|(uri, edits)| {
+        let path = FileMgr::uri2pathname(uri.as_str());
+        edits.into_iter().map(move |edit| (path.clone(), edit.range, edit.new_text))
+    }).collect();
+    edits.sort_by_key(|(path, range, _)| (path.clone(), range.start));
+    assert_eq!(edits, vec![
+        (base_path.clone(), Range::new(Position::new(6, 4), Position::new(6, 12)), S!("alias")),
+        (user_path.clone(), Range::new(Position::new(7, 20), Position::new(7, 28)), S!("alias")),
+    ]);
+    let _ = fs::remove_dir_all(&root);
+}
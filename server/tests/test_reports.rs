@@ -0,0 +1,48 @@
+use odoo_ls_server::core::reports::XmlReportIndex;
+use odoo_ls_server::S;
+
+const REPORTS: &str = r#"<odoo>
+    <record id="paperformat_label" model="report.paperformat">
+        <field name="name">Label</field>
+    </record>
+    <template id="report_label">
+        <t t-call="web.html_container"/>
+    </template>
+    <!-- <template id="commented_template"/> -->
+    <record id="action_report_label" model="ir.actions.report">
+        <field name="name">Labels</field>
+        <field name="model">res.partner</field>
+        <field name="report_type">qweb-pdf</field>
+        <field name="report_name">label_module.report_label</field>
+        <field name="print_report_name">'Label - %s' % (object.name)</field>
+        <field name="paperformat_id" ref="paperformat_label"/>
+    </record>
+    <report id="action_report_badge" model="hr.employee" name="label_module.report_badge" string="Badge"/>
+</odoo>
+"#;
+
+#[test]
+fn test_report_index() {
+    let index = XmlReportIndex::parse("label_module", "/addons/label_module/reports.xml", REPORTS);
+    assert_eq!(index.templates, vec![S!("label_module.report_label")]);
+    assert_eq!(index.paperformats, vec![S!("label_module.paperformat_label")]);
+    assert_eq!(index.reports.len(), 2);
+
+    let report = &index.reports[0];
+    assert_eq!(report.xml_id, "label_module.action_report_label");
+    assert_eq!(report.line, 8);
+    assert_eq!(report.report_type.as_deref(), Some("qweb-pdf"));
+    //the values keep their offset in the file
+    for value in [&report.report_name, &report.model, &report.print_report_name, &report.paperformat] {
+        let value = value.as_ref().unwrap();
+        assert_eq!(&REPORTS[value.offset..value.offset + value.value.len()], value.value);
+    }
+    assert_eq!(report.paperformat.as_ref().unwrap().value, "paperformat_label");
+
+    let shortcut = &index.reports[1];
+    assert_eq!(shortcut.xml_id, "label_module.action_report_badge");
+    assert_eq!(shortcut.line, 16);
+    let model = shortcut.model.as_ref().unwrap();
+    assert_eq!(model.value, "hr.employee");
+    assert_eq!(&REPORTS[model.offset..model.offset + model.value.len()], "hr.employee");
+}
@@ -0,0 +1,36 @@
+use serde_json::json;
+
+mod setup;
+use setup::lsp_client::LspTestClient;
+
+/* Progress of the long requests: the references are streamed with the partial result token of the client, and the rename
+reports its steps with the work done token */
+#[test]
+fn test_request_progress() {
+    let mut client = LspTestClient::start();
+    client.open("module_1/models/models.py");
+    let uri = format!("{}/module_1/models/models.py", client.replace_placeholders(&json!("${addons}")).as_str().unwrap());
+
+    let references = client.request_value("textDocument/references", json!({
+        "textDocument": {"uri": uri},
+        "position": {"line": 15, "character": 4},
+        "context": {"includeDeclaration": true},
+        "partialResultToken": "references-token",
+    }));
+    //all the locations have been streamed: the final response only contains the ones that were not sent
+    assert_eq!(references, json!([]));
+    let partial = client.wait_for_notification("$/progress", |params| params["token"] == "references-token");
+    assert!(partial["value"].as_array().is_some_and(|locations| locations.iter().any(|location| location["uri"] == json!(uri))));
+
+    let edit = client.request_value("textDocument/rename", json!({
+        "textDocument": {"uri": uri},
+        "position": {"line": 15, "character": 4},
+        "newName": "f1_renamed",
+        "workDoneToken": "rename-token",
+    }));
+    assert!(edit["documentChanges"].is_array() || edit["changes"].is_object());
+    let begin = client.wait_for_notification("$/progress", |params| params["token"] == "rename-token" && params["value"]["kind"] == "begin");
+    assert_eq!(begin["value"]["cancellable"], json!(true));
+    client.wait_for_notification("$/progress", |params| params["token"] == "rename-token" && params["value"]["kind"] == "end");
+    client.stop();
+}
@@ -0,0 +1,87 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{HoverContents, WorkspaceSymbolResponse};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::features::workspace_symbol::WorkspaceSymbolFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+const CONTROLLERS: &str = r#"from odoo import http
+
+
+class Shop(http.Controller):
+
+    @http.route('/routes/shop', type='http', auth='public', methods=['GET'])
+    def shop(self, **kwargs):
+        return ""
+
+    @http.route(['/routes/cart', '/routes/basket'], type='jsn', auth='nobody')
+    def cart(self, **kwargs):
+        return ""
+
+
+class ShopExtension(Shop):
+
+    @http.route('/routes/shop', type='http', auth='public')
+    def shop(self, **kwargs):
+        return super().shop(**kwargs)
+
+
+class Other(http.Controller):
+
+    @http.route('/routes/shop', auth='user')
+    def other_shop(self):
+        return ""
+"#;
+
+/* Routes are indexed during the odoo step: unknown parameters and urls handled by two different methods are reported,
+and the urls can be searched and hovered */
+#[test]
+fn test_routes() {
+    let root = env::temp_dir().join(format!("odoo_ls_routes_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "routes_module", &[], &[("controllers.py", CONTROLLERS)]);
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let path = module_dir.join("controllers.py").sanitize();
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path).expect("controllers.py has no file info");
+
+    let messages: Vec<String> = file_info.borrow().step_diagnostics(BuildSteps::ODOO).cloned().unwrap_or_default().iter()
+        .map(|diagnostic| diagnostic.message.clone()).collect();
+    assert_eq!(messages, vec![
+        S!("Unknown auth \"nobody\" for route /routes/cart. Expected one of: user, public, none, bearer"),
+        S!("Unknown type \"jsn\" for route /routes/cart. Expected one of: http, json, jsonrpc"),
+        S!("Unknown auth \"nobody\" for route /routes/basket. Expected one of: user, public, none, bearer"),
+        S!("Unknown type \"jsn\" for route /routes/basket. Expected one of: http, json, jsonrpc"),
+        //the override of shop by ShopExtension is not a duplicate
+        S!("Route /routes/shop is already declared by Shop.shop"),
+    ]);
+
+    let Some(WorkspaceSymbolResponse::Flat(symbols)) = WorkspaceSymbolFeature::get_workspace_symbols(&mut session, "ROUTES/") else {
+        panic!("no workspace symbols");
+    };
+    let urls: Vec<(&str, &str)> = symbols.iter().map(|symbol| (symbol.name.as_str(), symbol.container_name.as_deref().unwrap_or_default())).collect();
+    assert_eq!(&urls[..2], &[("/routes/basket", "Shop.cart"), ("/routes/cart", "Shop.cart")]);
+    let mut shop_handlers: Vec<&str> = urls[2..].iter().map(|(url, handler)| {
+        assert_eq!(*url, "/routes/shop");
+        *handler
+    }).collect();
+    shop_handlers.sort();
+    assert_eq!(shop_handlers, vec!["Other.other_shop", "Shop.shop", "ShopExtension.shop"]);
+    assert_eq!(symbols[0].location.range.start.line, 9);
+
+    let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&path)).expect("controllers.py is not loaded");
+    let hover = HoverFeature::get_hover(&mut session, &file_symbol, &file_info, 6, 9).expect("no hover on shop");
+    let HoverContents::Markup(markup) = hover.contents else {
+        panic!("the hover is not in markdown");
+    };
+    assert!(markup.value.contains("Route `/routes/shop`  \nauth: `public`, type: `http`, csrf: `True`, methods: `GET`"), "{}", markup.value);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use lsp_types::{DocumentChangeOperation, DocumentChanges, ResourceOp};
+use odoo_ls_server::features::scaffold::ScaffoldFeature;
+
+#[test]
+fn test_scaffold_names() {
+    assert!(ScaffoldFeature::is_valid_name("my_module"));
+    assert!(ScaffoldFeature::is_valid_name("l10n_be_2"));
+    assert!(!ScaffoldFeature::is_valid_name("My_Module"));
+    assert!(!ScaffoldFeature::is_valid_name("2fa"));
+    assert!(!ScaffoldFeature::is_valid_name("my-module"));
+    assert!(!ScaffoldFeature::is_valid_name(""));
+}
+
+#[test]
+fn test_scaffold_files() {
+    let files = ScaffoldFeature::module_files("library_book", 17);
+    let content = |path: &str| files.iter().find(|(file, _)| *file == path).unwrap().1.clone();
+    assert!(content("__manifest__.py").contains("'name': 'Library Book'"));
+    assert!(content("models/models.py").contains("_name = 'library_book.library_book'"));
+    assert!(content("security/ir.model.access.csv").contains("model_library_book_library_book,base.group_user"));
+    //list views are <tree> before Odoo 18
+    assert!(content("views/views.xml").contains("<tree>"));
+    assert!(ScaffoldFeature::module_files("library_book", 18).iter().any(|(_, content)| content.contains("<list>")));
+}
+
+#[test]
+fn test_scaffold_edit() {
+    let module_dir = PathBuf::from(if cfg!(windows) { "C:\\addons\\library_book" } else { "/addons/library_book" });
+    let edit = ScaffoldFeature::build_edit(&module_dir, "library_book", 18);
+    let Some(DocumentChanges::Operations(operations)) = edit.document_changes else {
+        panic!("The files must be created with resource operations");
+    };
+    //each file is created, then filled
+    assert_eq!(operations.len(), ScaffoldFeature::module_files("library_book", 18).len() * 2);
+    let DocumentChangeOperation::Op(ResourceOp::Create(create)) = &operations[0] else {
+        panic!("The first operation must create the __init__.py file");
+    };
+    assert!(create.uri.as_str().ends_with("/addons/library_book/__init__.py"));
+    let DocumentChangeOperation::Edit(fill) = &operations[1] else {
+        panic!("The second operation must fill the __init__.py file");
+    };
+    assert_eq!(fill.text_document.uri, create.uri);
+}
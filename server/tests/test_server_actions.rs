@@ -0,0 +1,170 @@
+use std::env;
+use std::fs;
+
+use lsp_types::{Diagnostic, NumberOrString};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::xml_code::ServerActionCode;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+const ACTIONS: &str = r#"<odoo>
+    <record id="action_scopes" model="ir.actions.server">
+        <field name="name">Scopes</field>
+        <field name="model_id" ref="base.model_res_partner"/>
+        <field name="state">code</field>
+        <field name="code">
+import json
+total = sum(line.amount for line in records)
+def helper(value):
+    local_name = value
+    return local_name
+helper(total)
+log(local_name)
+squares = [item * 2 for item in records.ids]
+log(item)
+class Helper:
+    factor = 2
+    def double(self):
+        return factor
+if total &gt; 10 and model.name != &quot;done&quot;:
+    raise UserError(env.user.__class__)
+        </field>
+    </record>
+    <record id="view_with_code" model="ir.ui.view">
+        <field name="code">not_python</field>
+    </record>
+    <record id="cron_cleanup" model="ir.cron">
+        <field name="name">Cleanup</field>
+        <field name="code"><![CDATA[
+if records and 0 < len(records):
+    missing_name.unlink()
+]]></field>
+    </record>
+</odoo>
+"#;
+
+/* The text of the xml file covered by the range of a diagnostic, whose positions hold offsets until it is published */
+fn covered_text<'a>(text: &'a str, diagnostic: &Diagnostic) -> &'a str {
+    &text[diagnostic.range.start.line as usize..diagnostic.range.end.line as usize]
+}
+
+fn code_of(diagnostic: &Diagnostic) -> &str {
+    match diagnostic.code.as_ref() {
+        Some(NumberOrString::String(code)) => code.as_str(),
+        _ => "",
+    }
+}
+
+#[test]
+fn test_unescape() {
+    //the entities and the CDATA markers are removed, the code is stripped, and each byte keeps its offset in the file
+    let (source, offsets) = ServerActionCode::unescape("  a &lt; b<![CDATA[ & c]]>  ", 100);
+    assert_eq!(source, "a < b & c");
+    assert_eq!(offsets, vec![102, 103, 104, 108, 109, 119, 120, 121, 122, 126]);
+    let (source, offsets) = ServerActionCode::unescape(" \n  ", 5);
+    assert_eq!(source, "");
+    assert_eq!(offsets, vec![5]);
+}
+
+#[test]
+fn test_extract() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let codes = ServerActionCode::extract(&mut session, ACTIONS);
+    //the code of views is not python
+    assert_eq!(codes.len(), 2);
+    //res.partner is not loaded without the addons
+    assert!(codes[0].model.is_none());
+    assert!(codes[0].source.starts_with("import json\n"));
+    assert!(codes[0].source.ends_with("raise UserError(env.user.__class__)"));
+    assert!(codes[0].source.contains("if total > 10 and model.name != \"done\":"));
+    assert_eq!(codes[1].source, "if records and 0 < len(records):\n    missing_name.unlink()");
+
+    //the offset map goes from the source to the file and back, entities included
+    let code = &codes[0];
+    let greater = code.source.find('>').unwrap();
+    assert!(ACTIONS[code.to_file_offset(greater)..].starts_with("&gt; 10"));
+    assert_eq!(code.to_source_offset(code.to_file_offset(greater)), Some(greater));
+    let class = code.source.find("__class__").unwrap();
+    assert!(ACTIONS[code.to_file_offset(class)..].starts_with("__class__)"));
+    assert_eq!(code.to_source_offset(code.to_file_offset(class)), Some(class));
+    let cdata = &codes[1];
+    assert!(ACTIONS[cdata.to_file_offset(0)..].starts_with("if records and 0 < len"));
+    //the offsets outside of the code are not mapped
+    assert_eq!(code.to_source_offset(ACTIONS.find("action_scopes").unwrap()), None);
+    assert_eq!(cdata.to_source_offset(ACTIONS.find("]]>").unwrap() + 3), None);
+}
+
+/* The code is validated as the body of a function, with the variables of the evaluation context as parameters: the names
+bound by nested functions, classes and comprehensions are not visible outside of them */
+#[test]
+fn test_scope_diagnostics() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let diagnostics = ServerActionCode::validate(&mut session, ACTIONS);
+    let found: Vec<(&str, &str)> = diagnostics.iter().map(|diagnostic| (code_of(diagnostic), covered_text(ACTIONS, diagnostic))).collect();
+    assert_eq!(found, vec![
+        ("OLS30801", "import json"),
+        ("OLS20801", "local_name"),
+        ("OLS20801", "item"),
+        ("OLS20801", "factor"),
+        ("OLS30802", "__class__"),
+        ("OLS20801", "missing_name"),
+    ]);
+    //the ranges are the ones of the names used outside of their scope, not of their definition
+    let local_name = diagnostics.iter().find(|diagnostic| covered_text(ACTIONS, diagnostic) == "local_name").unwrap();
+    assert_eq!(local_name.range.start.line as usize, ACTIONS.find("log(local_name)").unwrap() + "log(".len());
+    let item = diagnostics.iter().find(|diagnostic| covered_text(ACTIONS, diagnostic) == "item").unwrap();
+    assert_eq!(item.range.start.line as usize, ACTIONS.find("log(item)").unwrap() + "log(".len());
+}
+
+const MODELS: &str = r#"from odoo import fields, models
+
+
+class ActionPartner(models.Model):
+    _name = "action.partner"
+
+    note = fields.Char()
+
+    def action_done(self):
+        return True
+"#;
+
+const MEMBER_ACTIONS: &str = r#"<odoo>
+    <record id="action_members" model="ir.actions.server">
+        <field name="name">Members</field>
+        <field name="model_id" ref="model_action_partner"/>
+        <field name="code">
+record.note = &quot;done&quot;
+record.action_done()
+records.unknown_field
+for rec in records:
+    rec.unknown_loop
+def rebind(record):
+    return record.unknown_parameter
+        </field>
+    </record>
+</odoo>
+"#;
+
+/* The attributes of model, record and records are members of the model given by model_id, unless the names are rebound */
+#[test]
+fn test_model_members() {
+    let root = env::temp_dir().join(format!("odoo_ls_server_actions_{}", std::process::id()));
+    setup::setup::write_module(&root.join("addons"), "action_module", &[], &[("models.py", MODELS)]);
+
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let codes = ServerActionCode::extract(&mut session, MEMBER_ACTIONS);
+    assert_eq!(codes[0].model.as_deref(), Some("action.partner"));
+
+    let diagnostics = ServerActionCode::validate(&mut session, MEMBER_ACTIONS);
+    let found: Vec<(&str, &str)> = diagnostics.iter().map(|diagnostic| (code_of(diagnostic), covered_text(MEMBER_ACTIONS, diagnostic))).collect();
+    assert_eq!(found, vec![("OLS30803", "unknown_field")]);
+    let _ = fs::remove_dir_all(&root);
+}
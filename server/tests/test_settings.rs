@@ -0,0 +1,19 @@
+use odoo_ls_server::core::settings::find_xml_config_parameters;
+
+#[test]
+fn test_xml_config_parameters() {
+    let content = r#"<odoo>
+    <record id="default_delay" model="ir.config_parameter">
+        <field name="value">10</field>
+        <field name="key">
+            sale.default_delay
+        </field>
+    </record>
+    <!-- <record id="old_key" model="ir.config_parameter"><field name="key">sale.old_key</field></record> -->
+    <record id="partner" model="res.partner">
+        <field name="key">not_a_parameter</field>
+    </record>
+    <function model="ir.config_parameter" name="set_param" eval="('sale.max_lines', 100)"/>
+</odoo>"#;
+    assert_eq!(find_xml_config_parameters(content), vec!["sale.default_delay", "sale.max_lines"]);
+}
@@ -0,0 +1,19 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use odoo_ls_server::server::SHUTDOWN_TIMEOUT;
+
+mod setup;
+use setup::lsp_client::LspTestClient;
+
+/* The threads of the server are stopped by the shutdown request and the exit notification. Once they are joined, the watchdog
+that kills a stuck server is stopped, and the process embedding the server goes on */
+#[test]
+fn test_shutdown() {
+    let client = LspTestClient::start();
+    let start = Instant::now();
+    client.stop();
+    assert!(start.elapsed() < Duration::from_millis(SHUTDOWN_TIMEOUT));
+    //the watchdog would have ended the process of the tests by now
+    thread::sleep(Duration::from_millis(SHUTDOWN_TIMEOUT + 1000));
+}
@@ -0,0 +1,31 @@
+use std::env;
+use std::fs;
+
+use odoo_ls_server::core::site_packages::SitePackagesWatcher;
+use odoo_ls_server::S;
+
+#[test]
+fn test_site_packages_watcher() {
+    assert_eq!(SitePackagesWatcher::importable_name("requests", true), Some(S!("requests")));
+    assert_eq!(SitePackagesWatcher::importable_name("six.py", false), Some(S!("six")));
+    assert_eq!(SitePackagesWatcher::importable_name("_cffi_backend.cpython-311-x86_64-linux-gnu.so", false), Some(S!("_cffi_backend")));
+    assert_eq!(SitePackagesWatcher::importable_name("requests-2.31.0.dist-info", true), None);
+    assert_eq!(SitePackagesWatcher::importable_name("distutils-precedence.pth", false), None);
+    assert_eq!(SitePackagesWatcher::importable_name("__pycache__", true), None);
+
+    let dir = env::temp_dir().join(format!("ols_site_packages_{}", std::process::id()));
+    fs::create_dir_all(dir.join("requests")).unwrap();
+    fs::write(dir.join("six.py"), "").unwrap();
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    let mut watcher = SitePackagesWatcher::default();
+    //the first poll only takes a snapshot
+    assert!(watcher.poll(&dirs).is_empty());
+    assert!(watcher.poll(&dirs).is_empty());
+
+    fs::create_dir_all(dir.join("xlsxwriter")).unwrap();
+    fs::create_dir_all(dir.join("XlsxWriter-3.1.9.dist-info")).unwrap();
+    fs::remove_file(dir.join("six.py")).unwrap();
+    let changes: Vec<String> = watcher.poll(&dirs).into_iter().map(|(_, name)| name).collect();
+    assert_eq!(changes, vec![S!("six"), S!("xlsxwriter")]);
+    assert!(watcher.poll(&dirs).is_empty());
+}
@@ -0,0 +1,64 @@
+use std::env;
+
+use lsp_types::{ClientCapabilities, CompletionClientCapabilities, CompletionItemCapability, InsertTextFormat, TextDocumentClientCapabilities, TextDocumentContentChangeEvent};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::snippets::SnippetFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+const CODE: &str = r#"from odoo import http, models
+
+co
+
+class Partner(models.Model):
+    _name = "snippet.partner"
+
+    co
+
+    def action(self):
+        co
+
+
+class Main(http.Controller):
+    ro
+"#;
+
+/* Snippets depend on where the cursor is: module level, body of a model or of a controller. None are given in functions */
+#[test]
+fn test_snippets() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_snippets.py").sanitize();
+    let change = vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: S!(CODE) }];
+    let (_, file_info) = file_mgr.update_file_info(&mut session, &path, Some(&change), Some(1), false);
+    let file_info = file_info.borrow();
+    let labels = |session: &mut SessionInfo, line: u32| {
+        let character = file_info.get_line(line).unwrap().len() as u32;
+        SnippetFeature::get_snippets(session, &file_info, line, character).into_iter().map(|item| item.label).collect::<Vec<String>>()
+    };
+
+    //the client must support snippets
+    assert!(labels(&mut session, 2).is_empty());
+    session.sync_odoo.capabilities = ClientCapabilities {
+        text_document: Some(TextDocumentClientCapabilities {
+            completion: Some(CompletionClientCapabilities {
+                completion_item: Some(CompletionItemCapability { snippet_support: Some(true), ..Default::default() }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(labels(&mut session, 2), vec![S!("controller")]);
+    assert_eq!(labels(&mut session, 7), vec![S!("compute"), S!("constrains")]);
+    assert!(labels(&mut session, 10).is_empty());
+    assert_eq!(labels(&mut session, 14), vec![S!("route")]);
+
+    let items = SnippetFeature::get_snippets(&mut session, &file_info, 14, 6);
+    assert_eq!(items[0].insert_text_format, Some(InsertTextFormat::SNIPPET));
+    assert!(items[0].insert_text.as_ref().unwrap().starts_with("@http.route('/${1:path}'"));
+}
@@ -0,0 +1,21 @@
+use std::collections::HashSet;
+
+use odoo_ls_server::core::spell_check::SpellChecker;
+use odoo_ls_server::S;
+
+#[test]
+fn test_spell_check() {
+    let dictionary = HashSet::new();
+    let spell_checker = SpellChecker::new(&dictionary);
+    assert_eq!(spell_checker.check("Recieve the custmer's invoices"), vec![(0, S!("Recieve"), S!("receive")), (12, S!("custmer"), S!("customer"))]);
+    assert_eq!(spell_checker.check("Delivery Adress"), vec![(9, S!("Adress"), S!("address"))]);
+    //inflections, acronyms, short words and unknown words without close known word are not reported
+    assert!(spell_checker.check("Scheduled invoicing of the shipped quantities").is_empty());
+    assert!(spell_checker.check("VAT of the EU partners, in %(currency)s").is_empty());
+    assert!(spell_checker.check("Qwzrtplk").is_empty());
+
+    //words of the project, given in lowercase by the config
+    let dictionary = HashSet::from([S!("custmer")]);
+    let spell_checker = SpellChecker::new(&dictionary);
+    assert!(spell_checker.check("Custmer").is_empty());
+}
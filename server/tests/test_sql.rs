@@ -0,0 +1,49 @@
+use odoo_ls_server::core::sql::{find_query_at, query_tables, unsafe_interpolation};
+use ruff_python_ast::{Expr, Stmt};
+
+fn query_of(code: &str) -> Expr {
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let Some(Stmt::Expr(expr)) = module.body.into_iter().next() else {
+        panic!("not an expression: {}", code);
+    };
+    let Expr::Call(call) = *expr.value else {
+        panic!("not a call: {}", code);
+    };
+    call.arguments.args[0].clone()
+}
+
+#[test]
+fn test_unsafe_interpolation() {
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(f\"SELECT id FROM res_partner WHERE name = '{name}'\")")), Some("an f-string"));
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(\"SELECT id FROM res_partner WHERE name = '%s'\" % name)")), Some("% formatting"));
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(\"SELECT id FROM %s WHERE name = '%s'\" % (self._table, name))")), Some("% formatting"));
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(\"SELECT id FROM res_partner WHERE name = '{}'\".format(name))")), Some("str.format()"));
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(\"SELECT id FROM res_partner WHERE name = '\" + name + \"'\")")), Some("string concatenation"));
+    //parameters, table names and constants are not reported
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(\"SELECT id FROM res_partner WHERE name = %s\", (name,))")), None);
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(\"SELECT id FROM %s\" % self._table)")), None);
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(f\"SELECT id FROM {self._table} LIMIT {LIMIT}\")")), None);
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(\"SELECT id \" + \"FROM res_partner\")")), None);
+    assert_eq!(unsafe_interpolation(&query_of("cr.execute(SQL(\"SELECT id FROM res_partner WHERE name = %s\", name))")), None);
+}
+
+#[test]
+fn test_query_tables() {
+    assert_eq!(query_tables("SELECT id FROM res_partner WHERE active"), vec!["res_partner"]);
+    assert_eq!(query_tables("SELECT p.id FROM res_partner p, res_users AS u JOIN \"res_company\" c ON c.id = u.company_id"),
+        vec!["res_partner", "res_users", "res_company"]);
+    assert_eq!(query_tables("UPDATE sale_order SET state = 'done'; INSERT INTO sale_order_line (name) VALUES ('x')"), vec!["sale_order", "sale_order_line"]);
+    assert_eq!(query_tables("SELECT EXTRACT(year FROM create_date) FROM public.account_move"), vec!["account_move"]);
+    assert_eq!(query_tables("SELECT id FROM %s"), Vec::<String>::new());
+}
+
+#[test]
+fn test_find_query_at() {
+    let code = "def f(self):\n    self.env.cr.execute(\"SELECT id FROM res_partner\")\n    execute(\"SELECT 1\")\n";
+    let module = ruff_python_parser::parse_module(code).unwrap().into_syntax();
+    let offset = code.find("res_partner").unwrap() as u32;
+    assert_eq!(find_query_at(&module.body, offset).map(|(query, _)| query), Some(String::from("SELECT id FROM res_partner")));
+    //not a cursor
+    let offset = code.find("SELECT 1").unwrap() as u32;
+    assert_eq!(find_query_at(&module.body, offset), None);
+}
@@ -0,0 +1,88 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::TextDocumentContentChangeEvent;
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::file_mgr::{FileInfo, FileMgr};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+const CELLS: &str = "# %%
+%time x = 1
+if x:
+    !ls
+%%sql
+SELECT 1
+#%%
+y = 2
+";
+
+const SCRATCH: &str = r#"# %%
+from odoo import models
+%load_ext autoreload
+!pip list
+
+# %%
+class Scratch(models.Model):
+    _name = "standalone.scratch"
+
+# %%
+%%sql
+SELECT id FROM res_partner
+"#;
+
+/* The IPython magics of the cells are masked before the parsing, without moving the rest of the file */
+#[test]
+fn test_cell_magics() {
+    let masked = FileInfo::mask_cell_magics(CELLS).unwrap();
+    assert_eq!(masked, "# %%\n...        \nif x:\n    ...\n...  \n        \n#%%\ny = 2\n");
+    assert_eq!(masked.len(), CELLS.len());
+    assert!(FileInfo::mask_cell_magics("x = 1\n%time x\n").is_none());
+
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let syntax_errors = |session: &mut SessionInfo, file_mgr: &mut FileMgr, name: &str, code: &str| {
+        let path = env::temp_dir().join(name).sanitize();
+        let change = vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: S!(code) }];
+        let (_, file_info) = file_mgr.update_file_info(session, &path, Some(&change), Some(1), false);
+        let count = file_info.borrow().step_diagnostics(BuildSteps::SYNTAX).map(|diagnostics| diagnostics.len()).unwrap_or_default();
+        count
+    };
+    assert_eq!(syntax_errors(&mut session, &mut file_mgr, "odoo_ls_cells.py", CELLS), 0);
+    //without cell, the magics are syntax errors
+    assert!(syntax_errors(&mut session, &mut file_mgr, "odoo_ls_no_cells.py", &CELLS.replace("%%\n", "\n")) > 0);
+}
+
+/* A scratch file of the workspace, outside of the addons paths, is analyzed on its own: its classes are not registered as models */
+#[test]
+fn test_standalone_file() {
+    let root = env::temp_dir().join(format!("odoo_ls_standalone_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "standalone_module", &[], &[]);
+    fs::write(root.join("scratch.py"), SCRATCH).unwrap();
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let path = PathBuf::from(root.join("scratch.py").sanitize());
+
+    assert!(!session.sync_odoo.is_standalone_candidate(&module_dir.join("__init__.py")));
+    assert!(session.sync_odoo.is_standalone_candidate(&path));
+    SyncOdoo::reload_path(&mut session, &path);
+    SyncOdoo::process_rebuilds(&mut session);
+    assert!(session.sync_odoo.tree_from_path(&path).is_ok_and(|tree| SyncOdoo::is_standalone_tree(&tree)));
+    assert!(!session.sync_odoo.is_standalone_candidate(&path));
+
+    let file_symbol = session.sync_odoo.get_file_symbol(&path).expect("scratch.py is not loaded");
+    assert!(!file_symbol.borrow().get_symbol(&(vec![], vec![S!("Scratch")]), u32::MAX).is_empty());
+    assert!(!session.sync_odoo.models.contains_key("standalone.scratch"));
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path.sanitize()).expect("scratch.py has no file info");
+    assert!(file_info.borrow().step_diagnostics(BuildSteps::SYNTAX).cloned().unwrap_or_default().is_empty());
+    let _ = fs::remove_dir_all(&root);
+}
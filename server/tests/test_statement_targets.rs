@@ -0,0 +1,79 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{CompletionResponse, HoverContents};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::completion::CompletionFeature;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+const MODELS: &str = r#"from odoo import fields, models
+from odoo.tests import Form
+
+
+class LoopModel(models.Model):
+    _name = "loop.model"
+
+    loop_note = fields.Char()
+
+    def action_loop(self):
+        for rec in self:
+            rec.loop_n
+        else:
+            rec.loop_n
+
+    def action_form(self):
+        with Form(self) as form:
+            form.loop_n
+"#;
+
+/* Line and end of the first line of MODELS containing text, after its first line containing after */
+fn position(after: &str, text: &str) -> (u32, u32) {
+    let start = MODELS.lines().position(|line| line.contains(after)).unwrap();
+    let line = start + MODELS.lines().skip(start).position(|line| line.contains(text)).unwrap();
+    (line as u32, MODELS.lines().nth(line).unwrap().len() as u32)
+}
+
+/* The targets of a for on a recordset and of "with Form(record) as f" are evaluated as records of the model: hover gives their
+model, and completion their fields, in the loop and its else clause and in the block of the form */
+#[test]
+fn test_statement_targets() {
+    let root = env::temp_dir().join(format!("odoo_ls_statement_targets_{}", std::process::id()));
+    let module_dir = setup::setup::write_module(&root.join("addons"), "targets_module", &["base"], &[("models.py", MODELS)]);
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let path = module_dir.join("models.py").sanitize();
+    let file_symbol = session.sync_odoo.get_file_symbol(&PathBuf::from(&path)).expect("models.py is not loaded");
+    let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path).expect("models.py has no file info");
+
+    let hover_text = |session: &mut SessionInfo, (line, character): (u32, u32)| {
+        let hover = HoverFeature::get_hover(session, &file_symbol, &file_info, line, character).expect("no hover on the target");
+        match hover.contents {
+            HoverContents::Markup(markup) => markup.value,
+            _ => panic!("the hover is not in markdown"),
+        }
+    };
+    let completes_field = |session: &mut SessionInfo, (line, character): (u32, u32)| {
+        let Some(CompletionResponse::List(list)) = CompletionFeature::autocomplete(session, &file_symbol, &file_info, line, character) else {
+            panic!("no completion at {}:{}", line, character);
+        };
+        list.items.iter().any(|item| item.label == "loop_note")
+    };
+    //on the name of the target of the for
+    let (line, _) = position("def action_loop", "for rec in self");
+    let hover = hover_text(&mut session, (line, 13));
+    assert!(hover.contains("LoopModel"), "the model of rec is not inferred: {}", hover);
+    assert!(completes_field(&mut session, position("def action_loop", "rec.loop_n")));
+    assert!(completes_field(&mut session, position("else:", "rec.loop_n")));
+
+    let (line, _) = position("def action_form", "with Form(self) as form");
+    let hover = hover_text(&mut session, (line, 29));
+    assert!(hover.contains("LoopModel"), "the record of the form is not inferred: {}", hover);
+    assert!(completes_field(&mut session, position("def action_form", "form.loop_n")));
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,87 @@
+use std::env;
+
+use lsp_types::{CodeActionContext, CodeActionOrCommand, CodeActionParams, Diagnostic, NumberOrString, PartialResultParams, Position, Range, TextDocumentContentChangeEvent, TextDocumentIdentifier, WorkDoneProgressParams};
+use odoo_ls_server::constants::{BuildSteps, EXTENSION_NAME};
+use odoo_ls_server::core::file_mgr::{DiagnosticSuppressions, FileMgr};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::code_action::CodeActionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+const CODE: &str = "# odoo-ls: disable-file=OLS20001
+import os  # odoo-ls: disable=OLS30001,OLS20002
+import sys
+";
+
+fn diagnostic(code: &str, offset: usize) -> Diagnostic {
+    Diagnostic {
+        range: Range::new(Position::new(offset as u32, 0), Position::new(offset as u32 + 1, 0)),
+        code: Some(NumberOrString::String(S!(code))),
+        source: Some(S!(EXTENSION_NAME)),
+        message: S!(code),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_directive() {
+    assert_eq!(DiagnosticSuppressions::parse_directive(" disable=OLS30001, OLS20002"), Some((false, vec![S!("OLS30001"), S!("OLS20002")])));
+    assert_eq!(DiagnosticSuppressions::parse_directive(" disable-file=OLS20001"), Some((true, vec![S!("OLS20001")])));
+    assert_eq!(DiagnosticSuppressions::parse_directive(" enable=OLS20001"), None);
+}
+
+/* Diagnostics disabled by a comment on their line, or for the whole file, are dropped when they are attached to the file */
+#[test]
+fn test_suppressed_diagnostics() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let mut file_mgr = FileMgr::new();
+    let path = env::temp_dir().join("odoo_ls_suppressions.py").sanitize();
+    let change = TextDocumentContentChangeEvent { range: None, range_length: None, text: S!(CODE) };
+    let (_, file_info) = file_mgr.update_file_info(&mut session, &path, Some(&vec![change]), Some(1), false);
+    let line_1 = CODE.find("import os").unwrap();
+    let line_2 = CODE.find("import sys").unwrap();
+
+    file_info.borrow_mut().replace_diagnostics(BuildSteps::ARCH_EVAL, vec![
+        diagnostic("OLS20001", line_2),
+        diagnostic("OLS30001", line_1),
+        diagnostic("OLS30001", line_2),
+        diagnostic("OLS20003", line_1),
+    ]);
+    let kept: Vec<(String, u32)> = file_info.borrow().step_diagnostics(BuildSteps::ARCH_EVAL).unwrap().iter()
+        .map(|d| (d.message.clone(), d.range.start.line)).collect();
+    assert_eq!(kept, vec![(S!("OLS30001"), line_2 as u32), (S!("OLS20003"), line_1 as u32)]);
+
+    //the code actions insert the comments, on converted ranges
+    let uri = FileMgr::pathname2uri(&path);
+    let on_line = |line: u32, code: &str| {
+        let mut diagnostic = diagnostic(code, 0);
+        diagnostic.range = Range::new(Position::new(line, 0), Position::new(line, 3));
+        diagnostic
+    };
+    let params = CodeActionParams {
+        text_document: TextDocumentIdentifier { uri: uri.clone() },
+        range: Range::new(Position::new(1, 0), Position::new(2, 0)),
+        context: CodeActionContext { diagnostics: vec![on_line(2, "OLS30001"), on_line(1, "OLS20003"), on_line(1, "OLS30001")], only: None, trigger_kind: None },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let actions = CodeActionFeature::get_code_actions(&mut session, &file_info, &params).unwrap();
+    let edits: Vec<(String, Position, String)> = actions.iter().map(|action| {
+        let CodeActionOrCommand::CodeAction(action) = action else {
+            panic!("unexpected command");
+        };
+        let edit = action.edit.as_ref().unwrap().changes.as_ref().unwrap().get(&uri).unwrap()[0].clone();
+        (action.title.clone(), edit.range.start, edit.new_text)
+    }).collect();
+    assert_eq!(edits, vec![
+        (S!("Suppress OLS30001 on this line"), Position::new(2, 10), S!("  # odoo-ls: disable=OLS30001")),
+        (S!("Suppress OLS30001 in this file"), Position::new(0, 0), S!("# odoo-ls: disable-file=OLS30001\n")),
+        (S!("Suppress OLS20003 on this line"), Position::new(1, 47), S!(",OLS20003")),
+        (S!("Suppress OLS20003 in this file"), Position::new(0, 0), S!("# odoo-ls: disable-file=OLS20003\n")),
+        //already disabled on its line
+        (S!("Suppress OLS30001 in this file"), Position::new(0, 0), S!("# odoo-ls: disable-file=OLS30001\n")),
+    ]);
+}
@@ -0,0 +1,66 @@
+use std::env;
+use std::fs;
+
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::features::translation::{ExportPotPreviewParams, TranslatableString, TranslationFeature};
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+const MODELS: &str = r#"from odoo import _, models
+from odoo.tools.translate import _lt
+
+LABEL = _lt("Lazy label")
+
+
+class TranslatedModel(models.Model):
+    _name = "translated.model"
+
+    def action_warn(self):
+        message = "dynamic"
+        return _("Done"), self.env._("Done"), _(message), _("Count: %s") % 3
+"#;
+
+/* The literal strings given to the translation functions are exported by msgid, the other values are reported as they
+can't be extracted */
+#[test]
+fn test_translations() {
+    let root = env::temp_dir().join(format!("odoo_ls_translation_{}", std::process::id()));
+    setup::setup::write_module(&root.join("addons"), "translated_module", &[], &[("models.py", MODELS)]);
+    let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![root.join("addons").sanitize()]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+
+    let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("translated_module"), S!("models")], vec![]), u32::MAX);
+    let file = file.first().expect("the models of translated_module are not loaded").clone();
+    let path = file.borrow().paths()[0].clone();
+    let file_mgr = session.sync_odoo.get_file_mgr();
+    file_mgr.borrow_mut().update_file_info(&mut session, &path, None, None, false);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    let warnings: Vec<_> = validator.diagnostics.iter().filter(|d| d.code == Some(NumberOrString::String(S!("OLS20501")))).collect();
+    assert_eq!(warnings.len(), 1, "unexpected translation warnings: {:?}", warnings);
+    //ranges are still offsets in the file before being published
+    let start = warnings[0].range.start.line as usize;
+    assert_eq!(&MODELS[start..start + "message".len()], "message");
+
+    //only the modules of the workspace are exported
+    let params = ExportPotPreviewParams { module: Some(S!("translated_module")) };
+    assert!(TranslationFeature::export_pot_preview(&mut session, &params).modules.is_empty());
+    let module = session.sync_odoo.modules.get("translated_module").and_then(|module| module.upgrade()).unwrap();
+    module.borrow_mut().set_in_workspace(true);
+    let result = TranslationFeature::export_pot_preview(&mut session, &params);
+    assert_eq!(result.modules.len(), 1);
+    assert_eq!(result.modules[0].strings, vec![
+        TranslatableString { msgid: S!("Count: %s"), occurrences: vec![S!("models.py:12")] },
+        TranslatableString { msgid: S!("Done"), occurrences: vec![S!("models.py:12"), S!("models.py:12")] },
+        TranslatableString { msgid: S!("Lazy label"), occurrences: vec![S!("models.py:4")] },
+    ]);
+    let _ = fs::remove_dir_all(&root);
+}
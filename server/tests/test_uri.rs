@@ -0,0 +1,29 @@
+use odoo_ls_server::core::uri::{normalize_path_for, path_to_uri_for, uri_to_path_for};
+use odoo_ls_server::S;
+
+#[test]
+fn test_windows_uris() {
+    //spellings of the same file
+    for uri in ["file:///C:/odoo/addons/sale/models.py", "file:///c%3A/odoo/addons/sale/models.py", "FILE:///c:/odoo//addons/sale/models.py"] {
+        assert_eq!(uri_to_path_for(uri, true), Some(S!("c:/odoo/addons/sale/models.py")), "{}", uri);
+    }
+    assert_eq!(normalize_path_for(r"C:\odoo\addons\sale\", true), "c:/odoo/addons/sale");
+    assert_eq!(normalize_path_for(r"\\?\C:\odoo\addons", true), "c:/odoo/addons");
+    assert_eq!(normalize_path_for("c:/", true), "c:/");
+
+    //unc shares
+    assert_eq!(uri_to_path_for("file://server/share/my%20addons/x.py", true), Some(S!("//server/share/my addons/x.py")));
+    assert_eq!(normalize_path_for(r"\\?\UNC\server\share\x.py", true), "//server/share/x.py");
+    assert_eq!(path_to_uri_for(r"\\server\share\my addons\x.py", true), "file://server/share/my%20addons/x.py");
+
+    assert_eq!(path_to_uri_for(r"C:\odoo\my addons\é.py", true), "file:///c:/odoo/my%20addons/%C3%A9.py");
+    assert_eq!(uri_to_path_for("https://odoo.com", true), None);
+}
+
+#[test]
+fn test_posix_uris() {
+    assert_eq!(path_to_uri_for("/home/odoo/my addons/#1.py", false), "file:///home/odoo/my%20addons/%231.py");
+    assert_eq!(uri_to_path_for("file:///home/odoo/my%20addons/%231.py", false), Some(S!("/home/odoo/my addons/#1.py")));
+    assert_eq!(uri_to_path_for("file://localhost/home/odoo", false), Some(S!("/home/odoo")));
+    assert_eq!(uri_to_path_for("file:///home/%ZZ", false), None);
+}
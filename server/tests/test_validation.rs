@@ -0,0 +1,1154 @@
+use std::env;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+
+use lsp_types::{Diagnostic, NumberOrString, Position};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::integrity_checker::IntegrityChecker;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::reference_fields;
+use odoo_ls_server::core::reports::ReportValidator;
+use odoo_ls_server::core::request_progress::RequestProgress;
+use odoo_ls_server::core::settings::collect_config_parameter_keys;
+use odoo_ls_server::core::symbols::function_symbol::FunctionSymbol;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::ast_utils::AstUtils;
+use odoo_ls_server::features::references::ReferenceFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* Tests of the diagnostics of the validator, on modules generated in a temporary addons path */
+
+const DUPLICATE_MEMBERS: &str = r#"from odoo import fields, models
+
+
+class DuplicateMembers(models.Model):
+    _name = "duplicate.members"
+
+    name = fields.Char()
+    partner = fields.Char()
+    name = fields.Text()
+
+    def action_done(self):
+        pass
+
+    @property
+    def value(self):
+        return 1
+
+    @value.setter
+    def value(self, value):
+        pass
+
+    def action_done(self):
+        return True
+
+    if partner:
+        def _compute_name(self):
+            pass
+    else:
+        def _compute_name(self):
+            pass
+"#;
+
+const POPULATE: &str = r#"from odoo import fields, models
+
+
+class PopulatedModel(models.Model):
+    _name = "populated.model"
+    _populate_sizes = {"small": 10, "medium": 100, "huge": 10000}
+
+    name = fields.Char()
+    active = fields.Boolean()
+
+    def _populate_factories(self):
+        factories = [("name", None)]
+        factories += [("wrong_field", None)]
+        return factories + [("active", None), ("create_date", None)]
+"#;
+
+const REC_NAMES: &str = r#"from odoo import fields, models
+
+
+class RecNames(models.Model):
+    _name = "rec.names"
+    _rec_name = "code"
+    _rec_names_search = ["code", "partner_id.name", "display_name", "reference"]
+
+    code = fields.Char()
+    partner_id = fields.Many2one("res.partner")
+
+
+class WrongRecName(models.Model):
+    _name = "wrong.rec.name"
+    _rec_name = "title"
+"#;
+
+const SETTINGS: &str = r#"from odoo import fields, models
+
+
+class ResConfigSettings(models.TransientModel):
+    _inherit = "res.config.settings"
+
+    default_lang = fields.Char(default_model="res.partner")
+    default_unknown_field = fields.Char(default_model="res.partner")
+    default_name = fields.Char(default_model="unknown.model")
+    settings_delay = fields.Integer(config_parameter="settings_module.delay")
+"#;
+
+const SETTINGS_DATA: &str = r#"<odoo>
+    <record id="param_url" model="ir.config_parameter">
+        <field name="key">settings_module.url</field>
+        <field name="value">https://example.com</field>
+    </record>
+    <function model="ir.config_parameter" name="set_param" eval="('settings_module.token', 'secret')"/>
+</odoo>"#;
+
+const OWNER_BASE: &str = r#"from odoo import fields, models
+
+
+class OwnerModel(models.Model):
+    _name = "owner.model"
+
+    name = fields.Char()
+"#;
+
+const OWNER_EXTENSION: &str = r#"from odoo import fields, models
+
+
+class OwnerModel(models.Model):
+    _inherit = "owner.model"
+
+    extension_note = fields.Char()
+"#;
+
+const STRICT: &str = r#"from odoo import fields, models
+
+
+class StrictModel(models.Model):
+    _name = "strict.model"
+
+    name = fields.Char()
+
+    def action_check(self):
+        self.name.unknown_attribute
+        total = len(self) + 1
+        first = second = unknown_assigned
+        self.name = unknown_value
+        return unknown_name
+
+    def action_guarded(self):
+        if hasattr(self.name, 'guarded_attribute'):
+            self.name.guarded_attribute
+            self.guarded_attribute
+        if hasattr(self, 'optional_member'):
+            getattr(self, 'optional_member')
+        getattr(self, 'optional_member')
+"#;
+
+const SETUP_CLASS: &str = r#"from odoo import models
+
+DEFAULT_COUNT = 3
+
+
+class Helper:
+    pass
+
+
+class CachedRecordsCase:
+    name = "case"
+
+    @classmethod
+    def setUpClass(cls):
+        cls.name = 5
+        cls.label = "cached"
+        with open("file") as f:
+            cls.count = 3
+        cls.count = "three"
+        cls.helper = Helper()
+        cls.default_count = DEFAULT_COUNT
+        cls.labels = [cls.label]
+        local_label = "local"
+        cls.local_label = local_label
+
+    def test_cached(self):
+        return self.label, self.count
+"#;
+
+const REPORT_MODELS: &str = r#"from odoo import fields, models
+
+
+class ReportLabel(models.Model):
+    _name = "report.label"
+
+    name = fields.Char()
+"#;
+
+const REPORT_DATA: &str = r#"<odoo>
+    <record id="paperformat_label" model="report.paperformat">
+        <field name="name">Label</field>
+    </record>
+    <template id="report_label">
+        <t t-call="web.html_container"/>
+    </template>
+</odoo>
+"#;
+
+const REPORT_ACTIONS: &str = r#"<odoo>
+    <record id="action_report_label" model="ir.actions.report">
+        <field name="model">report.label</field>
+        <field name="report_name">report_module.report_label</field>
+        <field name="print_report_name">'Label - %s' % (object.name)</field>
+        <field name="paperformat_id" ref="paperformat_label"/>
+    </record>
+    <record id="action_report_wrong" model="ir.actions.report">
+        <field name="model">report.unknown</field>
+        <field name="report_name">report_module.report_unknown</field>
+        <field name="print_report_name">'Label - %s' % (object.name</field>
+        <field name="paperformat_id" ref="paperformat_unknown"/>
+    </record>
+    <record id="action_report_external" model="ir.actions.report">
+        <field name="model">report.label</field>
+        <field name="report_name">not_loaded_module.report_label</field>
+        <field name="paperformat_id" ref="not_loaded_module.paperformat_label"/>
+    </record>
+</odoo>
+"#;
+
+const INHERIT_ORDER: &str = r#"from odoo import api, models
+
+
+class TrackingMixin(models.AbstractModel):
+    _name = "tracking.mixin"
+
+    @api.model_create_multi
+    def create(self, vals_list):
+        return super().create(vals_list)
+
+
+class Order(models.Model):
+    _name = "inherit.order"
+
+    @api.model_create_multi
+    def create(self, vals_list):
+        return super().create(vals_list)
+
+
+class SafeOrder(models.Model):
+    _name = "inherit.safe.order"
+    _inherit = ["tracking.mixin", "inherit.order"]
+
+
+class WrongOrder(models.Model):
+    _name = "inherit.wrong.order"
+    _inherit = ("inherit.order", "tracking.mixin", "tracking.mixin")
+
+
+class PrintMixin:
+
+    def action_print(self):
+        return True
+
+
+class PrintedOrder(PrintMixin, models.Model):
+    _name = "inherit.printed.order"
+    _inherit = ["inherit.order"]
+"#;
+
+const FORWARDING: &str = r#"from odoo import models
+
+
+class Forwarded(models.Model):
+    _name = "forwarded.model"
+
+    def action_value(self, value, *args, **kwargs):
+        return "value"
+
+
+class ForwardingExtension(models.Model):
+    _inherit = "forwarded.model"
+
+    def action_value(self, value, *args, **kwargs):
+        """Keep the value"""
+        return super().action_value(value, *args, **kwargs)
+
+    def action_changed(self, value):
+        return super().action_value(value + 1)
+"#;
+
+const REFERENCES: &str = r#"from odoo import fields, models
+
+
+class ReferenceAttachment(models.Model):
+    _name = "reference.attachment"
+
+    res_model = fields.Char()
+    res_type = fields.Selection([("partner", "Partner")])
+    res_id = fields.Many2oneReference("Resource", model_field="res_model")
+    typed_id = fields.Many2oneReference(model_field="res_type")
+    unknown_id = fields.Many2oneReference(model_field="res_unknown")
+    target = fields.Reference([("res.partner", "Partner"), ("res.users", "User")], string="Target")
+"#;
+
+const DELEGATION: &str = r#"from odoo import fields, models
+
+
+class DelegatedPartner(models.Model):
+    _name = "delegated.partner"
+
+    email = fields.Char()
+
+
+class DelegatingUser(models.Model):
+    _name = "delegating.user"
+    _inherits = {"delegated.partner": "partner_id"}
+
+    login = fields.Char()
+
+    def action_search(self):
+        self.search([("email", "=", "a"), ("login", "!=", False), ("partner_id", "!=", False), ("phone", "=", "1")])
+        self.write({"email": "a", "partner_id": 1, "phone": "1"})
+"#;
+
+const CREATE_MULTI: &str = r#"from odoo import api, fields, models
+
+
+class MultiOrder(models.Model):
+    _name = "multi.order"
+
+    name = fields.Char()
+
+    @api.model_create_multi
+    def create(self, vals_list):
+        for vals in vals_list:
+            vals.setdefault("name", "/")
+        return super().create(vals_list)
+
+
+class PlainOrder(models.Model):
+    _name = "plain.order"
+
+    name = fields.Char()
+
+    def action_create(self):
+        self.env["multi.order"].create({"name": "a"})
+        self.env["multi.order"].create([{"name": "a"}])
+        self.env["plain.order"].create({"name": "a"})
+"#;
+
+const VIEW_FIELDS: &str = r#"from odoo import fields, models
+
+
+class ViewPartner(models.Model):
+    _name = "view.partner"
+
+    email = fields.Char()
+"#;
+
+const VIEW_FIELDS_DATA: &str = r#"<odoo>
+    <record id="view_partner_form" model="ir.ui.view">
+        <field name="model">view.partner</field>
+        <field name="arch" type="xml">
+            <form>
+                <field name="email"/>
+            </form>
+        </field>
+    </record>
+</odoo>"#;
+
+const DEPRECATED: &str = r#"from odoo import api, models
+
+
+class DeprecatedPartner(models.Model):
+    _name = "deprecated.partner"
+
+    @api.multi
+    def action_names(self):
+        return self.env["deprecated.partner"].name_get()
+
+
+class Report:
+    def name_get(self):
+        return []
+
+    def action_names(self):
+        return self.name_get()
+"#;
+
+const COMPREHENSIONS: &str = r#"from odoo import fields, models
+
+
+class ComprehensionPartner(models.Model):
+    _name = "comprehension.partner"
+
+    name = fields.Char()
+
+    def action_names(self):
+        partner = "no partner"
+        names = [partner.name for partner in self]
+        words = [word for name in names for word in name.split()]
+        return partner
+"#;
+
+const SPELLING: &str = r#"from odoo import fields, models
+
+
+class SpellingPartner(models.Model):
+    _name = "spelling.partner"
+
+    name = fields.Char(help="Shown on the\tcustmer invoices")
+"#;
+
+const OPTIONAL_IMPORTS: &str = r#"try:
+    import json
+except ImportError:
+    json = None
+
+try:
+    import odoo_ls_optional_package
+except (ImportError, ModuleNotFoundError):
+    odoo_ls_optional_package = None
+
+try:
+    import odoo_ls_broad_handler_package
+except Exception:
+    pass
+
+try:
+    import odoo_ls_bare_handler_package
+except:
+    pass
+
+from odoo import models
+
+
+class OptionalImports(models.Model):
+    _name = "optional.imports"
+"#;
+
+const FIELD_DEFAULTS: &str = r#"from odoo import fields, models
+
+
+def _default_name():
+    return "default"
+
+
+def _default_count():
+    return "many"
+
+
+class DefaultModel(models.Model):
+    _name = "default.model"
+
+    name = fields.Char(default=_default_name)
+    count = fields.Integer(default=_default_count)
+    active = fields.Boolean(default=True)
+    ratio = fields.Float(default="high")
+    label = fields.Char(default=lambda self: 5)
+    code = fields.Char(default=_unknown_default)
+"#;
+
+const ORDERS: &str = r#"from odoo import fields, models
+
+
+class OrderedModel(models.Model):
+    _name = "ordered.model"
+    _order = "date desc, name, sequence nulls last"
+
+    name = fields.Char()
+    date = fields.Date()
+    sequence = fields.Integer()
+    total = fields.Float(compute="_compute_total")
+    stored_total = fields.Float(compute="_compute_total", store=True)
+    tag_ids = fields.Many2many("ordered.model")
+
+    def _compute_total(self):
+        pass
+
+    def action_sort(self):
+        self.search([], order="stored_total, id desc")
+        self.search([], order="total")
+        self.search([], order="tag_ids asc")
+        return self.sorted("unknown_field")
+
+
+class BadlyOrderedModel(models.Model):
+    _name = "badly.ordered.model"
+    _order = "name upward"
+
+    name = fields.Char()
+"#;
+
+const ANNOTATIONS: &str = r#"from typing import Optional
+
+from odoo import models
+
+
+class AnnotatedPartner(models.Model):
+    _name = "annotated.partner"
+
+
+partner: AnnotatedPartner = None
+maybe_partner: Optional[AnnotatedPartner] = None
+quoted_partner: "AnnotatedPartner | None" = None
+partners: list[AnnotatedPartner] = []
+commented_partner = None  # type: AnnotatedPartner
+unresolved: UnknownType = 5
+
+
+def notify(record: AnnotatedPartner, count=3):
+    return record
+"#;
+
+const MIXINS: &str = r#"from odoo import fields, models
+
+
+class ThreadMixin(models.AbstractModel):
+    _name = "test.thread"
+
+    message_ids = fields.One2many("test.message", "res_id")
+
+    def message_post(self, body=""):
+        return True
+
+
+class ActivityMixin(models.AbstractModel):
+    _name = "test.activity.mixin"
+    _inherit = ["test.thread"]
+
+    def activity_schedule(self):
+        return True
+
+
+class Ticket(models.Model):
+    _name = "mixin.ticket"
+    _inherit = ["test.activity.mixin"]
+
+    name = fields.Char()
+"#;
+
+const VERSIONED: &str = r#"from odoo import fields, models, release
+
+
+class VersionedModel(models.Model):
+    _name = "versioned.model"
+
+    if release.version_info >= (1, 0):
+        alive_count = fields.Integer(default="many")
+    else:
+        dead_count = fields.Integer(default="none")
+    if release.version_info[0] < 1:
+        dead_ratio = fields.Float(default="none")
+"#;
+
+type Check = Box<dyn FnOnce(&mut SessionInfo) + Send>;
+
+static SERVER: OnceLock<Mutex<mpsc::Sender<(Check, mpsc::Sender<thread::Result<()>>)>>> = OnceLock::new();
+
+fn addons_dir() -> PathBuf {
+    env::temp_dir().join(format!("odoo_ls_validation_{}", std::process::id())).join("addons")
+}
+
+/* Run a check on the server shared by the tests of the file. The server is not Send: it is built on its own thread on the first
+call, and runs the checks of the tests one at a time. The panic of a check is given back to its test. The generated modules
+are left in the temporary directory, as no test knows if it is the last one */
+fn with_server(check: impl FnOnce(&mut SessionInfo) + Send + 'static) {
+    let sender = SERVER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<(Check, mpsc::Sender<thread::Result<()>>)>();
+        thread::spawn(move || {
+            let addons = addons_dir();
+            let _ = fs::remove_dir_all(&addons);
+            for (module, content) in [("duplicate_members", DUPLICATE_MEMBERS), ("populated_module", POPULATE), ("rec_names_module", REC_NAMES), ("owner_base", OWNER_BASE), ("strict_module", STRICT), ("setup_class_module", SETUP_CLASS), ("inherit_order_module", INHERIT_ORDER), ("forwarding_module", FORWARDING), ("reference_module", REFERENCES), ("delegation_module", DELEGATION), ("create_multi_module", CREATE_MULTI), ("deprecated_module", DEPRECATED), ("comprehension_module", COMPREHENSIONS), ("spelling_module", SPELLING), ("optional_import_module", OPTIONAL_IMPORTS), ("defaults_module", FIELD_DEFAULTS), ("order_module", ORDERS), ("annotation_module", ANNOTATIONS), ("mixin_module", MIXINS), ("versioned_module", VERSIONED)] {
+                setup::setup::write_module(&addons, module, &[], &[("models.py", content)]);
+            }
+            setup::setup::write_module(&addons, "owner_extension", &["owner_base"], &[("models.py", OWNER_EXTENSION)]);
+            //modules loading a data file
+            for (module, content, data_file, data) in [("settings_module", SETTINGS, "data.xml", SETTINGS_DATA), ("report_module", REPORT_MODELS, "data.xml", REPORT_DATA), ("view_fields_module", VIEW_FIELDS, "views.xml", VIEW_FIELDS_DATA)] {
+                let manifest = format!("{{'name': '{}', 'depends': [], 'data': ['{}']}}\n", module, data_file);
+                setup::setup::write_module(&addons, module, &[], &[("__manifest__.py", manifest.as_str()), ("models.py", content), (data_file, data)]);
+            }
+            let mut odoo: SyncOdoo = setup::setup::setup_server_with_addons(vec![addons.sanitize()]);
+            odoo.config.populate_checks = true;
+            let (s, r) = crossbeam_channel::unbounded();
+            let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+            for (check, result) in receiver {
+                let _ = result.send(panic::catch_unwind(AssertUnwindSafe(|| check(&mut session))));
+            }
+        });
+        Mutex::new(sender)
+    }).lock().unwrap().clone();
+    let (result_sender, result_receiver) = mpsc::channel();
+    sender.send((Box::new(check), result_sender)).expect("the validation server has stopped");
+    if let Err(panic) = result_receiver.recv().expect("the validation server has stopped") {
+        panic::resume_unwind(panic);
+    }
+}
+
+/* Validate the models.py file of a generated module, and return the diagnostics with the given code */
+fn validate_models(session: &mut SessionInfo, module: &str, code: &str) -> Vec<Diagnostic> {
+    let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!(module), S!("models")], vec![]), u32::MAX);
+    let file = file.first().unwrap_or_else(|| panic!("models of {} are not loaded", module)).clone();
+    let path = file.borrow().paths()[0].clone();
+    let file_mgr = session.sync_odoo.get_file_mgr();
+    file_mgr.borrow_mut().update_file_info(session, &path, None, None, false);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(session);
+    validator.diagnostics.into_iter()
+        .filter(|d| d.code == Some(NumberOrString::String(S!(code))))
+        .collect()
+}
+
+#[test]
+fn test_duplicate_members() {
+    with_server(|session| {
+        let duplicates = validate_models(session, "duplicate_members", "OLS21001");
+        //the field name and the method action_done. Property accessors and conditional definitions are not duplicates
+        assert_eq!(duplicates.len(), 2, "unexpected duplicates: {:?}", duplicates);
+        assert!(duplicates[0].message.starts_with("name is already defined as a field"));
+        assert!(duplicates[1].message.starts_with("action_done is already defined as a method"));
+        for duplicate in duplicates.iter() {
+            let related = duplicate.related_information.as_ref().expect("the first definition is not given");
+            assert_eq!(related.len(), 1);
+            //ranges are still offsets in the file before being published
+            assert!(related[0].location.range.start.line < duplicate.range.start.line);
+        }
+    });
+}
+
+#[test]
+fn test_populate() {
+    with_server(|session| {
+        let unknown_fields = validate_models(session, "populated_module", "OLS30405");
+        assert_eq!(unknown_fields.len(), 1, "unexpected factories: {:?}", unknown_fields);
+        assert_eq!(unknown_fields[0].message, "wrong_field is not a field of model populated.model");
+        let unknown_sizes = validate_models(session, "populated_module", "OLS20410");
+        assert_eq!(unknown_sizes.len(), 1);
+        assert!(unknown_sizes[0].message.starts_with("Unknown populate size huge"));
+    });
+}
+
+#[test]
+fn test_rec_names() {
+    with_server(|session| {
+        //paths are checked on their first part, magic fields are always fields
+        let unknown_fields = validate_models(session, "rec_names_module", "OLS30406");
+        assert_eq!(unknown_fields.len(), 2, "unexpected rec names: {:?}", unknown_fields);
+        assert_eq!(unknown_fields[0].message, "reference is not a field of model rec.names");
+        assert_eq!(unknown_fields[1].message, "title is not a field of model wrong.rec.name");
+        let model = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("rec_names_module"), S!("models")], vec![S!("RecNames")]), u32::MAX);
+        let model = model.first().unwrap().borrow();
+        let model_data = model.as_class_sym()._model.as_ref().unwrap();
+        assert_eq!(model_data.rec_name, Some(S!("code")));
+        assert_eq!(model_data.rec_names_search, vec![S!("code"), S!("partner_id.name"), S!("display_name"), S!("reference")]);
+    });
+}
+
+#[test]
+fn test_settings() {
+    with_server(|session| {
+        let wrong_defaults = validate_models(session, "settings_module", "OLS30407");
+        assert_eq!(wrong_defaults.len(), 2, "unexpected default models: {:?}", wrong_defaults);
+        assert_eq!(wrong_defaults[0].message, "unknown_field is not a field of model res.partner");
+        assert_eq!(wrong_defaults[1].message, "Unknown model unknown.model");
+        //keys of the data files and of the settings fields
+        let keys = collect_config_parameter_keys(session);
+        for key in ["settings_module.delay", "settings_module.token", "settings_module.url"] {
+            assert!(keys.contains(&S!(key)), "{} is not a known system parameter", key);
+        }
+    });
+}
+
+/* Fields of a module are only visible from the modules depending on it, before and after an incremental rebuild */
+#[test]
+fn test_owning_modules() {
+    with_server(|session| {
+        check_owner_visibility(session);
+        let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("owner_extension"), S!("models")], vec![]), u32::MAX);
+        let path = file.first().unwrap().borrow().paths()[0].clone();
+        SyncOdoo::reload_path(session, &PathBuf::from(path));
+        SyncOdoo::process_rebuilds(session);
+        check_owner_visibility(session);
+        let violations: Vec<String> = IntegrityChecker::check(session).violations.into_iter().filter(|violation| violation.contains("owned by")).collect();
+        assert!(violations.is_empty(), "wrong owning modules: {:?}", violations);
+        check_strong_cycles(session);
+    });
+}
+
+/* A class holding its own file as extension symbol can't be freed once the file is removed from the tree */
+fn check_strong_cycles(session: &mut SessionInfo) {
+    let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("owner_base"), S!("models")], vec![]), u32::MAX);
+    let file = file.first().unwrap().clone();
+    let class = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("owner_base"), S!("models")], vec![S!("OwnerModel")]), u32::MAX);
+    let class = class.first().unwrap().clone();
+    class.borrow_mut().as_class_sym_mut().ext_symbols.insert(S!("models"), vec![file.clone()]);
+    let violations = IntegrityChecker::check(session).violations;
+    class.borrow_mut().as_class_sym_mut().ext_symbols.clear();
+    let cycles: Vec<&String> = violations.iter().filter(|violation| violation.contains("cycle of strong references")).collect();
+    assert_eq!(cycles.len(), 1, "unexpected cycles: {:?}", violations);
+    assert_eq!(*cycles[0], "OwnerModel: cycle of strong references: OwnerModel -> models -> OwnerModel");
+    assert!(IntegrityChecker::check(session).violations.iter().all(|violation| !violation.contains("cycle of strong references")));
+}
+
+fn check_owner_visibility(session: &mut SessionInfo) {
+    let base = session.sync_odoo.modules.get("owner_base").and_then(|module| module.upgrade()).unwrap();
+    let extension = session.sync_odoo.modules.get("owner_extension").and_then(|module| module.upgrade()).unwrap();
+    let class = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("owner_base"), S!("models")], vec![S!("OwnerModel")]), u32::MAX);
+    let class = class.first().unwrap().clone();
+    let (from_base, _) = class.borrow().get_member_symbol(session, &S!("extension_note"), Some(base), false, false);
+    assert!(from_base.is_empty(), "extension_note is visible from owner_base");
+    let (from_extension, _) = class.borrow().get_member_symbol(session, &S!("extension_note"), Some(extension.clone()), false, false);
+    let field = from_extension.first().expect("extension_note is not visible from owner_extension").clone();
+    assert!(field.borrow().owning_module().is_some_and(|module| Rc::ptr_eq(&module, &extension)));
+}
+
+#[test]
+fn test_strict_mode() {
+    with_server(|session| {
+        //only reported in strict mode, for the files of the workspace
+        assert!(validate_models(session, "strict_module", "OLS20008").is_empty());
+        let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("strict_module"), S!("models")], vec![]), u32::MAX);
+        file.first().unwrap().borrow_mut().set_in_workspace(true);
+        session.sync_odoo.config.strict_mode = true;
+        //the give-ups of the assigned and returned values are recorded when the method is evaluated again
+        file.first().unwrap().borrow_mut().invalidate_sub_functions(session);
+        let give_ups = validate_models(session, "strict_module", "OLS20008");
+        session.sync_odoo.config.strict_mode = false;
+        let messages: Vec<&str> = give_ups.iter().map(|d| d.message.as_str()).collect();
+        //the hasattr guards only apply to the tested object: self.guarded_attribute is reported once, self.name.guarded_attribute is not
+        for expected in ["unknown_attribute: attribute not found", "unknown_assigned: name not found", "unknown_value: name not found", "unknown_name: name not found", "guarded_attribute: attribute not found"] {
+            let count = messages.iter().filter(|message| **message == format!("Unable to infer the value of {}", expected)).count();
+            assert_eq!(count, 1, "{} is reported {} times: {:?}", expected, count, messages);
+        }
+        //operations and subscripts that are not evaluated are not give-ups
+        let start = STRICT.find("total = ").unwrap() as u32;
+        let end = start + STRICT[start as usize..].find('\n').unwrap() as u32;
+        assert!(give_ups.iter().all(|d| d.range.start.line < start || d.range.start.line > end), "unexpected give-ups: {:?}", messages);
+        let guarded = STRICT.find("self.guarded_attribute").unwrap() as u32 + "self.".len() as u32;
+        assert!(give_ups.iter().any(|d| d.range.start.line == guarded));
+        //only the getattr outside of the hasattr guard is reported
+        let getattrs = validate_models(session, "strict_module", "OLS20009");
+        assert_eq!(getattrs.len(), 1, "unexpected getattr diagnostics: {:?}", getattrs);
+        assert_eq!(getattrs[0].range.start.line as usize, STRICT.rfind("'optional_member'").unwrap());
+    });
+}
+
+/* Attributes cached on cls by setUpClass are members of the class, evaluated from their first assignment */
+#[test]
+fn test_setup_class() {
+    with_server(|session| {
+        let class = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("setup_class_module"), S!("models")], vec![S!("CachedRecordsCase")]), u32::MAX);
+        let class = class.first().unwrap().clone();
+        let member_type = |session: &mut SessionInfo, name: &str| {
+            let members = class.borrow().get_content_symbol(name, u32::MAX);
+            assert_eq!(members.len(), 1, "{} is declared {} times", name, members.len());
+            let evaluations = members[0].borrow().evaluations().cloned().unwrap_or_default();
+            let evaluation = evaluations.first().unwrap_or_else(|| panic!("{} is not evaluated", name));
+            let typ = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().unwrap();
+            let typ = Symbol::follow_ref(&typ, session, &mut None, false, false, None, &mut vec![]);
+            let typ = typ.first().and_then(|(typ, _)| typ.upgrade()).unwrap_or_else(|| panic!("{} has no type", name));
+            let typ = typ.borrow().name().clone();
+            typ
+        };
+        //name is declared in the class body, count keeps its first value
+        assert_eq!(member_type(session, "name"), "str");
+        assert_eq!(member_type(session, "label"), "str");
+        assert_eq!(member_type(session, "count"), "int");
+        //non-literal values: an instance, a global variable and a container built from another cached attribute
+        assert_eq!(member_type(session, "helper"), "Helper");
+        assert_eq!(member_type(session, "default_count"), "int");
+        assert_eq!(member_type(session, "labels"), "list");
+        //the local variables of setUpClass are not built when the class is evaluated: the attribute is only declared
+        assert_eq!(class.borrow().get_content_symbol("local_label", u32::MAX).len(), 1);
+    });
+}
+
+/* read_group returns a list of dictionaries, and _read_group a list of tuples since Odoo 17 */
+#[test]
+fn test_read_group_type() {
+    with_server(|session| {
+        let base_model = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("models")], vec![S!("BaseModel")]), u32::MAX);
+        let base_model = base_model.first().unwrap().clone();
+        let tuples = if session.sync_odoo.version_major >= 17 { "tuple" } else { "dict" };
+        for (method, element) in [("read_group", "dict"), ("_read_group", tuples)] {
+            let function = base_model.borrow().get_content_symbol(method, u32::MAX);
+            let evaluations = function.first().unwrap_or_else(|| panic!("{} is not a method of BaseModel", method)).borrow().evaluations().cloned().unwrap_or_default();
+            assert_eq!(evaluations.len(), 1, "{} is not typed", method);
+            let list = evaluations[0].symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().unwrap();
+            assert_eq!(list.borrow().name(), "list");
+            let rows = evaluations[0].element_evaluation(session).unwrap_or_else(|| panic!("the rows of {} are not typed", method));
+            let row = rows.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().unwrap();
+            assert_eq!(row.borrow().name(), element);
+        }
+    });
+}
+
+/* The helpers of odoo.tools returning lists give the type of their elements */
+#[test]
+fn test_tools_list_types() {
+    with_server(|session| {
+        for (file, function, element) in [("misc", "groupby", "tuple"), ("mail", "email_split", "str")] {
+            let symbol = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("tools"), S!(file)], vec![S!(function)]), u32::MAX);
+            let evaluations = symbol.first().unwrap_or_else(|| panic!("{} is not in odoo.tools.{}", function, file)).borrow().evaluations().cloned().unwrap_or_default();
+            assert_eq!(evaluations.len(), 1, "{} is not typed", function);
+            let list = evaluations[0].symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().unwrap();
+            assert_eq!(list.borrow().name(), "list");
+            let elements = evaluations[0].element_evaluation(session).unwrap_or_else(|| panic!("the elements of {} are not typed", function));
+            let typ = elements.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().unwrap();
+            assert_eq!(typ.borrow().name(), element);
+        }
+    });
+}
+
+/* Templates and paper formats are searched in the data files of the modules, the references to other modules are skipped */
+#[test]
+fn test_reports() {
+    with_server(|session| {
+        let path = addons_dir().join("report_module").join("report.xml").sanitize();
+        let diagnostics = ReportValidator::validate(session, &path, REPORT_ACTIONS);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages.len(), 4, "unexpected report diagnostics: {:?}", messages);
+        assert_eq!(messages[0], "Unknown template report_module.report_unknown for report report_module.action_report_wrong");
+        assert_eq!(messages[1], "Unknown model report.unknown");
+        assert!(messages[2].starts_with("Invalid print_report_name expression"));
+        assert_eq!(messages[3], "Unknown paper format report_module.paperformat_unknown");
+        //ranges are still offsets in the file before being published
+        let start = diagnostics[1].range.start.line as usize;
+        assert_eq!(&REPORT_ACTIONS[start..start + "report.unknown".len()], "report.unknown");
+    });
+}
+
+#[test]
+fn test_inherit_order() {
+    with_server(|session| {
+        let duplicates = validate_models(session, "inherit_order_module", "OLS21002");
+        assert_eq!(duplicates.len(), 1, "unexpected duplicates: {:?}", duplicates);
+        assert_eq!(duplicates[0].message, "tracking.mixin is already inherited by this class");
+        //only the mixin listed after the model overriding create is reported, once
+        let orders = validate_models(session, "inherit_order_module", "OLS21003");
+        assert_eq!(orders.len(), 1, "unexpected orders: {:?}", orders);
+        assert!(orders[0].message.starts_with("Mixin tracking.mixin is inherited after inherit.order, which overrides create."));
+        let module = session.sync_odoo.modules.get("inherit_order_module").and_then(|module| module.upgrade()).unwrap();
+        for (class_name, expected) in [("SafeOrder", ["inherit.safe.order", "tracking.mixin", "inherit.order"]), ("WrongOrder", ["inherit.wrong.order", "inherit.order", "tracking.mixin"])] {
+            let class = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("inherit_order_module"), S!("models")], vec![S!(class_name)]), u32::MAX);
+            let class = class.first().unwrap_or_else(|| panic!("{} is not loaded", class_name)).clone();
+            let mro = class.borrow().get_model_mro(session, module.clone());
+            assert_eq!(mro, expected);
+        }
+        //the python bases come before the models of _inherit
+        let class = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("inherit_order_module"), S!("models")], vec![S!("PrintedOrder")]), u32::MAX);
+        let mro = class.first().expect("PrintedOrder is not loaded").borrow().get_model_mro(session, module.clone());
+        assert_eq!(mro, ["inherit.printed.order", "odoo.addons.inherit_order_module.models.PrintMixin", "inherit.order"]);
+    });
+}
+
+#[test]
+fn test_forwarding_overrides() {
+    with_server(|session| {
+        validate_models(session, "forwarding_module", "");
+        let method = |session: &mut SessionInfo, class_name: &str, name: &str| {
+            let classes = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("forwarding_module"), S!("models")], vec![S!(class_name)]), u32::MAX);
+            let class = classes.first().unwrap_or_else(|| panic!("{} is not loaded", class_name)).clone();
+            let method = class.borrow().get_content_symbol(name, u32::MAX);
+            method.first().unwrap_or_else(|| panic!("{} is not a method of {}", name, class_name)).clone()
+        };
+        let forwarding = method(session, "ForwardingExtension", "action_value");
+        assert!(forwarding.borrow().as_func().is_forwarding);
+        assert!(!method(session, "ForwardingExtension", "action_changed").borrow().as_func().is_forwarding);
+        let forwarded = FunctionSymbol::get_forwarded_method(session, &forwarding).expect("the forwarded method is not found");
+        assert!(Rc::ptr_eq(&forwarded, &method(session, "Forwarded", "action_value")));
+        //the override returns the str of its parent instead of None
+        let evaluations = FunctionSymbol::get_forwarded_evaluations(session, &forwarding);
+        assert_eq!(evaluations.len(), 1);
+        let returned = evaluations[0].symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().expect("unknown return type");
+        assert_eq!(returned.borrow().name(), "str");
+    });
+}
+
+/* The model_field of a Many2oneReference must be a Char field of the model, and the hover of a Reference field gives the
+models of its selection */
+#[test]
+fn test_reference_fields() {
+    with_server(|session| {
+        let unknown_fields = validate_models(session, "reference_module", "OLS30408");
+        assert_eq!(unknown_fields.len(), 1, "unexpected model fields: {:?}", unknown_fields);
+        assert_eq!(unknown_fields[0].message, "res_unknown is not a field of model reference.attachment");
+        let wrong_types = validate_models(session, "reference_module", "OLS20413");
+        assert_eq!(wrong_types.len(), 1, "unexpected model fields: {:?}", wrong_types);
+        assert!(wrong_types[0].message.starts_with("The model_field res_type is a Selection field"));
+        let class = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("reference_module"), S!("models")], vec![S!("ReferenceAttachment")]), u32::MAX);
+        let class = class.first().unwrap().clone();
+        let target = class.borrow().get_symbol(&(vec![], vec![S!("target")]), u32::MAX).first().unwrap().clone();
+        let call = AstUtils::get_field_call(session, &target).expect("declaration of target not found");
+        assert_eq!(reference_fields::selection_models(&call).into_iter().map(|(model, _)| model).collect::<Vec<_>>(), vec!["res.partner", "res.users"]);
+        assert_eq!(reference_fields::describe_value("Reference", &call).unwrap(), "Value: a record of `res.partner` | `res.users` or False, written as a `\"model,id\"` string");
+        let res_id = class.borrow().get_symbol(&(vec![], vec![S!("res_id")]), u32::MAX).first().unwrap().clone();
+        let call = AstUtils::get_field_call(session, &res_id).unwrap();
+        assert_eq!(reference_fields::describe_value("Many2oneReference", &call).unwrap(), "Value: the id of a record of the model named by `res_model`");
+    });
+}
+
+/* The fields of the parents given by _inherits, and the link fields, are fields of the model in domains and values */
+#[test]
+fn test_delegated_fields() {
+    with_server(|session| {
+        let domain_fields = validate_models(session, "delegation_module", "OLS30602");
+        assert_eq!(domain_fields.len(), 1, "unexpected domain fields: {:?}", domain_fields);
+        assert_eq!(domain_fields[0].message, "phone is not a field of model delegating.user");
+        let vals_fields = validate_models(session, "delegation_module", "OLS30404");
+        assert_eq!(vals_fields.len(), 1, "unexpected values: {:?}", vals_fields);
+        assert_eq!(vals_fields[0].message, "phone is not a field of model delegating.user");
+    });
+}
+
+/* A single dictionary given to create is only reported when the create of the model is overridden with @api.model_create_multi
+in the workspace, and the list of values of such a create is a list of dictionaries */
+#[test]
+fn test_create_multi() {
+    with_server(|session| {
+        let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("create_multi_module"), S!("models")], vec![]), u32::MAX);
+        let file = file.first().unwrap().clone();
+        file.borrow_mut().set_in_workspace(true);
+        let single_dicts = validate_models(session, "create_multi_module", "OLS20411");
+        assert_eq!(single_dicts.len(), 1, "unexpected create calls: {:?}", single_dicts);
+        let create = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("create_multi_module"), S!("models")], vec![S!("MultiOrder"), S!("create")]), u32::MAX);
+        let create = create.first().unwrap().clone();
+        let vals = create.borrow().get_symbol(&(vec![], vec![S!("vals")]), u32::MAX).first().unwrap().clone();
+        let evaluations = vals.borrow().evaluations().cloned().unwrap_or_default();
+        assert_eq!(evaluations.len(), 1, "vals is not evaluated");
+        let typ = evaluations[0].symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().expect("vals has no type");
+        assert_eq!(typ.borrow().name(), "dict");
+    });
+}
+
+/* The fields used by the views of the data files are references of the field, and give its references from the xml file */
+#[test]
+fn test_xml_field_references() {
+    with_server(|session| {
+        let views_path = addons_dir().join("view_fields_module").join("views.xml").sanitize();
+        let locations = ReferenceFeature::get_field_references(session, &S!("view.partner"), &S!("email"), &mut RequestProgress::none());
+        let view_usage = locations.iter().find(|location| location.uri.path().ends_with("views.xml")).expect("the view is not a reference of the field");
+        //<field name="email"/> is on the 6th line of the file
+        assert_eq!(view_usage.range.start, Position::new(5, 29));
+        assert_eq!(view_usage.range.end, Position::new(5, 34));
+        assert!(locations.iter().any(|location| location.uri.path().ends_with("models.py")));
+        let from_xml = ReferenceFeature::get_xml_references(session, &views_path, &Position::new(5, 31), &mut RequestProgress::none()).expect("no field at the position");
+        assert_eq!(from_xml.len(), locations.len());
+    });
+}
+
+#[test]
+fn test_deprecations() {
+    with_server(|session| {
+        let version_major = session.sync_odoo.version_major;
+        let mut diagnostics = validate_models(session, "deprecated_module", "OLS30003");
+        diagnostics.extend(validate_models(session, "deprecated_module", "OLS20007"));
+        //api.multi is removed in the version it is deprecated in, so it can only be found by its path
+        assert_eq!(diagnostics.iter().filter(|d| d.message.contains("odoo.api.multi")).count(), 1, "{:?}", diagnostics);
+        //name_get is reported on the records of the model, not on the method of the other class
+        let name_gets: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.message.contains("name_get")).collect();
+        assert_eq!(name_gets.len(), if version_major >= 17 { 1 } else { 0 }, "{:?}", name_gets);
+        if let Some(name_get) = name_gets.first() {
+            assert!(name_get.range.start.line > DEPRECATED.find("deprecated.partner\"].").unwrap() as u32);
+            assert!(name_get.range.start.line < DEPRECATED.find("class Report").unwrap() as u32);
+        }
+    });
+}
+
+/* The variables of comprehensions are declared in their own scope, and don't hide the variables of the function */
+#[test]
+fn test_comprehension_scopes() {
+    with_server(|session| {
+        validate_models(session, "comprehension_module", "OLS30001");
+        let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("comprehension_module"), S!("models")], vec![]), u32::MAX);
+        let file = file.first().unwrap().clone();
+        let method = file.borrow().get_symbol(&(vec![], vec![S!("ComprehensionPartner"), S!("action_names")]), u32::MAX).first().unwrap().clone();
+        let declarations = |name: &str| -> Vec<Rc<RefCell<Symbol>>> {
+            method.borrow().iter_symbols().filter(|(symbol_name, _)| symbol_name.as_str() == name)
+                .flat_map(|(_, sections)| sections.values().flatten().cloned().collect::<Vec<_>>()).collect()
+        };
+        assert_eq!(declarations("partner").len(), 1);
+        assert!(declarations("word").is_empty());
+        let scopes = declarations("<listcomp>");
+        assert_eq!(scopes.len(), 2);
+        assert!(scopes.iter().all(|scope| scope.borrow().is_comprehension()));
+        //the variable of the comprehension is evaluated as an element of self
+        let offset = COMPREHENSIONS.find("partner.name").unwrap() as u32;
+        let scope = Symbol::get_scope_symbol(file.clone(), offset, false);
+        assert!(scope.borrow().is_comprehension());
+        let partner = Symbol::infer_name(session.sync_odoo, &scope, &S!("partner"), Some(offset));
+        assert_eq!(partner.len(), 1);
+        assert!(Rc::ptr_eq(&partner[0].borrow().parent().unwrap().upgrade().unwrap(), &scope));
+        let evaluations = partner[0].borrow().evaluations().cloned().unwrap_or_default();
+        let typ = evaluations.first().and_then(|evaluation| evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade());
+        assert!(typ.is_some_and(|typ| typ.borrow().name() == "ComprehensionPartner"));
+        //the second iterable of a comprehension uses the variable of the first one
+        let offset = COMPREHENSIONS.find("name.split").unwrap() as u32;
+        let scope = Symbol::get_scope_symbol(file.clone(), offset, false);
+        assert_eq!(Symbol::infer_name(session.sync_odoo, &scope, &S!("word"), Some(offset)).len(), 1);
+        //after the comprehension, the variable of the function is used
+        let offset = COMPREHENSIONS.find("return partner").unwrap() as u32;
+        let scope = Symbol::get_scope_symbol(file.clone(), offset, false);
+        let partner = Symbol::infer_name(session.sync_odoo, &scope, &S!("partner"), Some(offset));
+        assert_eq!(partner.len(), 1);
+        assert!(Rc::ptr_eq(&partner[0].borrow().parent().unwrap().upgrade().unwrap(), &method));
+    });
+}
+
+/* The return types of the evaluated methods are part of what the class brings to its model, so the dependents of the model are
+validated again when they change */
+#[test]
+fn test_model_return_types() {
+    with_server(|session| {
+        let tree = (vec![S!("odoo"), S!("addons"), S!("comprehension_module"), S!("models")], vec![S!("ComprehensionPartner")]);
+        let model = session.sync_odoo.models.get("comprehension.partner").cloned().expect("model not found");
+        let model = model.borrow();
+        let contribution = model.get_contribution(&tree).expect("no contribution for the class");
+        let return_types = contribution.return_types.get("action_names").expect("the return type of action_names is not recorded");
+        assert_eq!(return_types.len(), 1);
+        assert!(return_types[0].starts_with("builtins.str"), "{:?}", return_types);
+    });
+}
+
+/* Only the misspelled word is reported, at its position in the source even after an escape sequence */
+#[test]
+fn test_spelling() {
+    with_server(|session| {
+        session.sync_odoo.config.spell_check = true;
+        let typos = validate_models(session, "spelling_module", "OLS10501");
+        session.sync_odoo.config.spell_check = false;
+        assert_eq!(typos.len(), 1, "unexpected typos: {:?}", typos);
+        let start = SPELLING.find("custmer").unwrap() as u32;
+        assert_eq!((typos[0].range.start, typos[0].range.end), (Position::new(start, 0), Position::new(start + 7, 0)));
+    });
+}
+
+/* Only the handlers of ImportError make the imports of a try optional. An optional import is typed as the module or None */
+#[test]
+fn test_optional_imports() {
+    with_server(|session| {
+        let file = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("optional_import_module"), S!("models")], vec![]), u32::MAX);
+        let file = file.first().unwrap().clone();
+        let not_found: Vec<String> = file.borrow().not_found_paths().iter().map(|(_, path)| path.join(".")).collect();
+        assert!(!not_found.contains(&S!("odoo_ls_optional_package")), "{:?}", not_found);
+        assert!(not_found.contains(&S!("odoo_ls_broad_handler_package")), "{:?}", not_found);
+        assert!(not_found.contains(&S!("odoo_ls_bare_handler_package")), "{:?}", not_found);
+        let imported = |name: &str| -> Rc<RefCell<Symbol>> {
+            file.borrow().iter_symbols().filter(|(symbol_name, _)| symbol_name.as_str() == name)
+                .flat_map(|(_, sections)| sections.values().flatten().cloned().collect::<Vec<_>>())
+                .find(|symbol| symbol.borrow().as_variable().is_import_variable).unwrap_or_else(|| panic!("{} is not imported", name))
+        };
+        let json = imported("json");
+        let evaluations = json.borrow().evaluations().cloned().unwrap_or_default();
+        assert_eq!(evaluations.len(), 2);
+        let module = evaluations[0].symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade();
+        assert!(module.is_some_and(|module| module.borrow().name() == "json"));
+        assert!(evaluations[1].symbol.is_instance().is_none() && evaluations[1].symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().is_none());
+        //a missing optional package is None
+        let missing = imported("odoo_ls_optional_package");
+        let evaluations = missing.borrow().evaluations().cloned().unwrap_or_default();
+        assert_eq!(evaluations.len(), 1);
+        assert!(evaluations[0].symbol.is_instance().is_none());
+    });
+}
+
+/* The default callables of the fields must exist, and the defaults must match the type of the field. Booleans are always
+accepted, as False is the empty value of any field */
+#[test]
+fn test_field_defaults() {
+    with_server(|session| {
+        let not_found = validate_models(session, "defaults_module", "OLS30401");
+        assert_eq!(not_found.len(), 1, "unexpected missing defaults: {:?}", not_found);
+        assert_eq!(not_found[0].message, "Default callable _unknown_default not found");
+        let mismatches = validate_models(session, "defaults_module", "OLS20401");
+        let messages: Vec<&str> = mismatches.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages, vec![
+            "Default value of type str is not compatible with a Integer field",
+            "Default value of type str is not compatible with a Float field",
+            "Default value of type int is not compatible with a Char field",
+        ]);
+    });
+}
+
+#[test]
+fn test_orders() {
+    with_server(|session| {
+        let messages = |diagnostics: Vec<Diagnostic>| diagnostics.iter().map(|d| d.message.clone()).collect::<Vec<String>>();
+        assert_eq!(messages(validate_models(session, "order_module", "OLS30403")), vec![S!("Invalid order specification: \"name upward\"")]);
+        assert_eq!(messages(validate_models(session, "order_module", "OLS20402")), vec![S!("Field total is not stored and can not be used to sort records")]);
+        assert_eq!(messages(validate_models(session, "order_module", "OLS20403")), vec![S!("Field tag_ids of type Many2many can not be used to sort records")]);
+        assert_eq!(messages(validate_models(session, "order_module", "OLS30402")), vec![S!("unknown_field is not a field of model ordered.model")]);
+    });
+}
+
+/* Annotations and type comments give the evaluation of variables and parameters, instead of their value */
+#[test]
+fn test_annotations() {
+    with_server(|session| {
+        let file_tree = vec![S!("odoo"), S!("addons"), S!("annotation_module"), S!("models")];
+        let notify = session.sync_odoo.get_symbol(&(file_tree.clone(), vec![S!("notify")]), u32::MAX).first().unwrap().clone();
+        let type_name = |session: &mut SessionInfo, symbol: Rc<RefCell<Symbol>>| {
+            let evaluations = symbol.borrow().evaluations().cloned().unwrap_or_default();
+            let evaluation = evaluations.first().unwrap_or_else(|| panic!("{} is not evaluated", symbol.borrow().name()));
+            let typ = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).0.upgrade().unwrap();
+            let typ = typ.borrow().name().clone();
+            typ
+        };
+        for (name, expected) in [("partner", "AnnotatedPartner"), ("maybe_partner", "AnnotatedPartner"), ("quoted_partner", "AnnotatedPartner"),
+                ("partners", "list"), ("commented_partner", "AnnotatedPartner"), ("unresolved", "int")] {
+            let variable = session.sync_odoo.get_symbol(&(file_tree.clone(), vec![S!(name)]), u32::MAX);
+            let variable = variable.first().unwrap_or_else(|| panic!("{} is not declared", name)).clone();
+            assert_eq!(type_name(session, variable), expected, "wrong evaluation of {}", name);
+        }
+        for (name, expected) in [("record", "AnnotatedPartner"), ("count", "int")] {
+            let parameter = notify.borrow().get_content_symbol(name, u32::MAX);
+            let parameter = parameter.first().unwrap_or_else(|| panic!("{} is not a parameter of notify", name)).clone();
+            assert_eq!(type_name(session, parameter), expected, "wrong evaluation of {}", name);
+        }
+    });
+}
+
+/* Members of the models inherited with _inherit, like mail.thread, are members of the inheriting model */
+#[test]
+fn test_inherited_mixins() {
+    with_server(|session| {
+        let ticket = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("mixin_module"), S!("models")], vec![S!("Ticket")]), u32::MAX);
+        let ticket = ticket.first().unwrap().clone();
+        let module = ticket.borrow().find_module().unwrap();
+        let inherited: Vec<String> = ticket.borrow().get_inherited_model_classes(session, module).iter().map(|class| class.borrow().name().clone()).collect();
+        assert_eq!(inherited, vec![S!("ActivityMixin"), S!("ThreadMixin")]);
+        for (member, owner) in [("name", "Ticket"), ("activity_schedule", "ActivityMixin"), ("message_post", "ThreadMixin"), ("message_ids", "ThreadMixin")] {
+            let (symbols, _) = ticket.borrow().get_member_symbol(session, &S!(member), None, false, false);
+            let symbol = symbols.first().unwrap_or_else(|| panic!("{} is not a member of mixin.ticket", member)).clone();
+            let parent = symbol.borrow().parent().unwrap().upgrade().unwrap();
+            assert_eq!(parent.borrow().name(), owner);
+        }
+        let (symbols, _) = ticket.borrow().get_member_symbol(session, &S!("unknown_member"), None, false, false);
+        assert!(symbols.is_empty());
+    });
+}
+
+/* Branches that are never executed with the analyzed version of Odoo are not validated, and their symbols are flagged */
+#[test]
+fn test_dead_branches() {
+    with_server(|session| {
+        let mismatches = validate_models(session, "versioned_module", "OLS20401");
+        assert_eq!(mismatches.len(), 1, "unexpected diagnostics: {:?}", mismatches);
+        assert_eq!(mismatches[0].message, "Default value of type str is not compatible with a Integer field");
+        let class = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("versioned_module"), S!("models")], vec![S!("VersionedModel")]), u32::MAX);
+        let class = class.first().unwrap().clone();
+        for (name, dead) in [("alive_count", false), ("dead_count", true), ("dead_ratio", true)] {
+            let member = class.borrow().get_content_symbol(name, u32::MAX);
+            assert_eq!(member.first().unwrap_or_else(|| panic!("{} is not declared", name)).borrow().is_in_dead_branch(), dead, "wrong branch for {}", name);
+        }
+    });
+}
@@ -0,0 +1,120 @@
+use lsp_types::Position;
+use odoo_ls_server::core::views::{guess_comodel, infer_field_type, parse_field_helps, parse_view_fields, XmlFieldIndex};
+
+const VIEWS: &str = r#"<odoo>
+    <record id="view_order_form" model="ir.ui.view">
+        <field name="name">sale.order.form</field>
+        <field name="model">sale.order</field>
+        <field name="arch" type="xml">
+            <form>
+                <field name="partner_id"/>
+                <field name="amount_discount" widget="monetary"/>
+                <!-- <field name="commented"/> -->
+                <field name="order_line">
+                    <list>
+                        <field name="product_id"/>
+                    </list>
+                </field>
+                <field name="tag_ids" widget="many2many_tags"/>
+                <field name="is_urgent"/>
+            </form>
+        </field>
+    </record>
+    <record id="view_order_form_inherit" model="ir.ui.view">
+        <field name="inherit_id" ref="view_order_form"/>
+        <field name="arch" type="xml">
+            <field name="partner_id" position="after"/>
+        </field>
+    </record>
+</odoo>
+"#;
+
+#[test]
+fn test_view_fields() {
+    let fields = parse_view_fields(VIEWS);
+    let names: Vec<&str> = fields.iter().map(|field| field.name.value.as_str()).collect();
+    //the fields of the sub-view belong to the comodel, and the inherited view has no model
+    assert_eq!(names, vec!["partner_id", "amount_discount", "order_line", "tag_ids", "is_urgent"]);
+    for field in fields.iter() {
+        assert_eq!(field.model, "sale.order");
+        assert_eq!(&VIEWS[field.name.offset..field.name.offset + field.name.value.len()], field.name.value);
+    }
+    assert_eq!(fields[1].widget.as_deref(), Some("monetary"));
+}
+
+#[test]
+fn test_infer_field_type() {
+    let fields = parse_view_fields(VIEWS);
+    let types: Vec<&str> = fields.iter().map(infer_field_type).collect();
+    assert_eq!(types, vec!["Many2one", "Monetary", "Char", "Many2many", "Boolean"]);
+}
+
+#[test]
+fn test_guess_comodel() {
+    let models: Vec<String> = ["sale.order.line", "res.partner", "account.move.line", "sale.line", "product.product"].iter().map(|model| model.to_string()).collect();
+    //the same comodel whatever the order of the models
+    for models in [models.clone(), models.iter().rev().cloned().collect()] {
+        assert_eq!(guess_comodel(models.iter(), "partner_id").as_deref(), Some("res.partner"));
+        assert_eq!(guess_comodel(models.iter(), "line_ids").as_deref(), Some("sale.line"));
+        assert_eq!(guess_comodel(models.iter(), "sale_order_line_ids").as_deref(), Some("sale.order.line"));
+        assert_eq!(guess_comodel(models.iter(), "tag_ids"), None);
+    }
+}
+
+const HELPS: &str = r#"<odoo>
+    <record id="view_partner_form" model="ir.ui.view">
+        <field name="model">res.partner</field>
+        <field name="arch" type="xml">
+            <form>
+                <field name="email" help="Used to send the invoices"/>
+                <field name="phone"/>
+            </form>
+        </field>
+    </record>
+    <record id="field_partner_ref" model="ir.model.fields">
+        <field name="model_id" ref="base.model_res_partner"/>
+        <field name="name">ref</field>
+        <field name="help">
+            Internal reference
+        </field>
+    </record>
+    <record id="field_order_note" model="ir.model.fields">
+        <field name="model">sale.order</field>
+        <field name="name">note</field>
+        <field name="help">Printed on the quotation</field>
+    </record>
+</odoo>
+"#;
+
+#[test]
+fn test_field_helps() {
+    let helps = parse_field_helps(HELPS);
+    assert_eq!(helps, vec![
+        ("res.partner".to_string(), "email".to_string(), "Used to send the invoices".to_string()),
+        ("res_partner".to_string(), "ref".to_string(), "Internal reference".to_string()),
+        ("sale.order".to_string(), "note".to_string(), "Printed on the quotation".to_string()),
+    ]);
+}
+
+#[test]
+fn test_xml_field_index() {
+    let mut index = XmlFieldIndex::default();
+    index.index_file("/addons/partner/views.xml", HELPS);
+    //the model given by its xml id is found by its name
+    let helps: Vec<&str> = index.get_helps("res.partner", "ref").iter().map(|(_, help)| help.as_str()).collect();
+    assert_eq!(helps, vec!["Internal reference"]);
+    assert_eq!(index.get_helps("res.partner", "email")[0].0, "/addons/partner/views.xml");
+    assert!(index.get_helps("res.partner", "phone").is_empty());
+    let usages = index.get_usages("res.partner", "phone");
+    assert_eq!(usages.len(), 1);
+    assert_eq!(usages[0].1.start, Position::new(6, 29));
+    assert_eq!(index.get_usage_at("/addons/partner/views.xml", &Position::new(6, 30)), Some(("res.partner".to_string(), "phone".to_string())));
+
+    //indexing a file again replaces its fields
+    index.index_file("/addons/partner/views.xml", "<odoo/>");
+    assert!(index.get_usages("res.partner", "phone").is_empty());
+    assert!(index.get_helps("res.partner", "ref").is_empty());
+    index.index_file("/addons/partner/views.xml", HELPS);
+    index.remove_dir("/addons/partner");
+    assert!(index.get_usages("res.partner", "email").is_empty());
+}
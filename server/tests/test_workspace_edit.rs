@@ -0,0 +1,57 @@
+use std::env;
+
+use lsp_types::{ChangeAnnotationWorkspaceEditClientCapabilities, DocumentChanges, OneOf, Position, Range, TextEdit, WorkspaceClientCapabilities, WorkspaceEditClientCapabilities};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::workspace_edit::WorkspaceEditBuilder;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+fn build_edit(session: &mut SessionInfo, paths: &[String]) -> lsp_types::WorkspaceEdit {
+    let mut builder = WorkspaceEditBuilder::new("Rename partner_id to customer_id");
+    assert!(builder.is_empty());
+    for (line, path) in paths.iter().enumerate() {
+        builder.add_edit(path, TextEdit { range: Range::new(Position::new(line as u32, 0), Position::new(line as u32, 10)), new_text: S!("customer_id") });
+    }
+    builder.add_edit(&paths[0], TextEdit { range: Range::new(Position::new(5, 0), Position::new(5, 10)), new_text: S!("customer_id") });
+    builder.build(session)
+}
+
+/* The edits are grouped by file, and annotated by module only if the client supports change annotations */
+#[test]
+fn test_workspace_edit_annotations() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo, None);
+    let paths = vec![env::temp_dir().join("odoo_ls_edit_a.py").sanitize(), env::temp_dir().join("odoo_ls_edit_b.py").sanitize()];
+
+    let edit = build_edit(&mut session, &paths);
+    assert!(edit.document_changes.is_none() && edit.change_annotations.is_none());
+    let changes = edit.changes.unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[&FileMgr::pathname2uri(&paths[0])].len(), 2);
+
+    session.sync_odoo.capabilities.workspace = Some(WorkspaceClientCapabilities {
+        workspace_edit: Some(WorkspaceEditClientCapabilities {
+            document_changes: Some(true),
+            change_annotation_support: Some(ChangeAnnotationWorkspaceEditClientCapabilities { groups_on_label: Some(true) }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    let edit = build_edit(&mut session, &paths);
+    assert!(edit.changes.is_none());
+    //the files are outside of any Odoo module: they share the same annotation, that must be confirmed by the user
+    let annotations = edit.change_annotations.unwrap();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations["other"].needs_confirmation, Some(true));
+    assert_eq!(annotations["other"].description.as_deref(), Some("Rename partner_id to customer_id"));
+    let Some(DocumentChanges::Edits(document_edits)) = edit.document_changes else {
+        panic!("the edits are not document changes");
+    };
+    assert_eq!(document_edits.len(), 2);
+    assert_eq!(document_edits[0].text_document.uri, FileMgr::pathname2uri(&paths[0]));
+    assert_eq!(document_edits[0].text_document.version, None);
+    assert!(document_edits[0].edits.iter().all(|edit| matches!(edit, OneOf::Right(annotated) if annotated.annotation_id == "other")));
+}
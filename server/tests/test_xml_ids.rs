@@ -0,0 +1,66 @@
+use std::env;
+use std::fs;
+
+use odoo_ls_server::features::xml_ids::XmlIdFeature;
+use odoo_ls_server::S;
+
+#[test]
+fn test_find_unused_xml_ids() {
+    let module_path = env::temp_dir().join(format!("ols_xml_ids_{}", std::process::id())).join("xml_ids_module");
+    fs::create_dir_all(module_path.join("views")).unwrap();
+    fs::create_dir_all(module_path.join("data")).unwrap();
+    fs::create_dir_all(module_path.join("security")).unwrap();
+    fs::write(module_path.join("views").join("views.xml"), r#"<odoo>
+    <record id="view_partner_form" model="ir.ui.view">
+        <field name="model">res.partner</field>
+    </record>
+    <record id="action_partner" model="ir.actions.act_window">
+        <field name="res_model">res.partner</field>
+    </record>
+    <menuitem id="menu_partner" action="action_partner"/>
+    <record id="action_unused" model="ir.actions.act_window">
+        <field name="res_model">res.partner</field>
+    </record>
+    <!-- <menuitem id="menu_old" action="action_old"/> -->
+    <record id="action_old" model="ir.actions.act_window">
+        <field name="res_model">res.partner</field>
+    </record>
+    <record id="action_bound" model="ir.actions.server">
+        <field name="binding_model_id" ref="base.model_res_partner"/>
+    </record>
+</odoo>"#).unwrap();
+    fs::write(module_path.join("data").join("res.partner.category.csv"), "id,name\ncategory_vip,VIP\ncategory_used,Used\n").unwrap();
+    fs::write(module_path.join("data").join("data.xml"), r#"<odoo>
+    <record id="group_manager" model="res.groups">
+        <field name="name">Manager</field>
+    </record>
+    <record id="group_admin" model="res.groups">
+        <field name="name">Admin</field>
+    </record>
+    <record id="action_js" model="ir.actions.act_window">
+        <field name="res_model">res.partner</field>
+    </record>
+</odoo>"#).unwrap();
+    fs::write(module_path.join("security").join("ir.model.access.csv"), "id,name,model_id:id,group_id:id\naccess_partner,access,base.model_res_partner,xml_ids_module.group_manager\n").unwrap();
+    //mentions of the names that are not references: a comment and the string of a field
+    fs::write(module_path.join("models.py"), r#"from odoo import fields
+
+# action_unused and xml_ids_module.action_old are not used anymore
+def _get_category(self):
+    return self.env.ref('xml_ids_module.category_used')
+
+secret = fields.Char(string='category_vip', groups='xml_ids_module.group_admin')
+"#).unwrap();
+    fs::write(module_path.join("actions.js"), "this.action.doAction(\"xml_ids_module.action_js\");\n").unwrap();
+
+    let unused = XmlIdFeature::find_unused(&[(S!("xml_ids_module"), module_path.to_string_lossy().to_string())]);
+    let mut names: Vec<&str> = unused.iter().map(|xml_id| xml_id.xml_id.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["xml_ids_module.action_old", "xml_ids_module.action_unused", "xml_ids_module.category_vip"]);
+    let action_unused = unused.iter().find(|xml_id| xml_id.xml_id == "xml_ids_module.action_unused").unwrap();
+    assert_eq!(action_unused.model, "ir.actions.act_window");
+    assert_eq!(action_unused.line, 8);
+    let category_vip = unused.iter().find(|xml_id| xml_id.xml_id == "xml_ids_module.category_vip").unwrap();
+    assert_eq!(category_vip.model, "res.partner.category");
+    assert_eq!(category_vip.line, 1);
+}
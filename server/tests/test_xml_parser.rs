@@ -0,0 +1,32 @@
+use odoo_ls_server::core::xml_parser::{attribute, attributes, strip_comments, LineIndex};
+
+#[test]
+fn test_attribute() {
+    let attrs = r#" id="view_form" string='Name = "x"' t-name="other" name="partner_id""#;
+    let name = attribute(attrs, "name").unwrap();
+    //name is not found in the values, nor in the attributes ending with it
+    assert_eq!(name.value, "partner_id");
+    assert_eq!(&attrs[name.offset..name.offset + name.value.len()], "partner_id");
+    assert_eq!(attribute(attrs, "string").unwrap().value, r#"Name = "x""#);
+    assert_eq!(attribute(attrs, "t-name").unwrap().value, "other");
+    assert!(attribute(attrs, "widget").is_none());
+
+    let values = attributes(attrs);
+    assert_eq!(values.len(), 4);
+    assert_eq!(values["id"].value, "view_form");
+    assert_eq!(values["name"], name);
+}
+
+#[test]
+fn test_comments_and_lines() {
+    let content = "<odoo>\n<!-- <record id=\"a\"\n model=\"b\"/> -->\n<record id=\"c\"/>\n</odoo>\n";
+    let stripped = strip_comments(content);
+    assert_eq!(stripped.len(), content.len());
+    assert!(!stripped.contains("id=\"a\""));
+    let lines = LineIndex::new(&stripped);
+    //the lines of the comment are kept
+    assert_eq!(lines.line_of(stripped.find("<record").unwrap()), 3);
+    assert_eq!(lines.line_of(0), 0);
+    assert_eq!(lines.line_of(content.find('\n').unwrap()), 0);
+    assert_eq!(lines.line_of(content.find('\n').unwrap() + 1), 1);
+}
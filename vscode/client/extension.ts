@@ -57,6 +57,7 @@ function getClientOptions(): LanguageClientOptions {
         documentSelector: [
             { scheme: "file", language: "python" },
             { scheme: "untitled", language: "python" },
+            { scheme: "file", pattern: "**/*.csv" }, //the references of the data files are linked
         ],
         synchronize: {
         },
@@ -347,6 +348,12 @@ async function initLanguageServerClient(context: ExtensionContext, outputChannel
             client.onNotification("Odoo/displayCrashNotification", async (params) => {
                 await displayCrashMessage(context, params["crashInfo"], params["pid"]);
             }),
+            client.onNotification("Odoo/fileQuarantined", async (params) => {
+                global.OUTPUT_CHANNEL.appendLine(`[WARNING] ${params["path"]} is quarantined: ${params["reason"]}`);
+                await window.showWarningMessage(
+                    `The Odoo extension stopped analyzing ${params["path"]}, as its build keeps failing. It will be analyzed again once edited.`
+                );
+            }),
             workspace.onDidChangeConfiguration(async (event)=>{
                 if(!event.affectsConfiguration("Odoo")|| global.CLIENT_IS_SAVING){
                     return;